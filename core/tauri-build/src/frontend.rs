@@ -0,0 +1,264 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Runs the frontend build hooks when the application is compiled with Cargo instead of the Tauri CLI.
+
+use std::{
+  collections::hash_map::DefaultHasher,
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+  process::Command,
+  time::UNIX_EPOCH,
+};
+
+use anyhow::{anyhow, Context};
+use tauri_utils::config::{AppUrl, BeforeDevCommand, Config, HookCommand, WindowUrl};
+
+use crate::Result;
+
+/// The environment variable set by the Tauri CLI, which runs the hooks itself.
+const CLI_ENV: &str = "TAURI_CLI";
+/// Directories that never contain frontend sources.
+const IGNORED_DIRS: &[&str] = &["node_modules", "target", ".git"];
+
+/// Whether the build script is running under `tauri dev` or `tauri build`.
+pub fn is_cli_build() -> bool {
+  std::env::var_os(CLI_ENV).is_some()
+}
+
+/// Runs `beforeDevCommand` (when `dev` is true) or `beforeBuildCommand`,
+/// unless the frontend directory did not change since the last run.
+pub fn build(config: &Config, dev: bool, out_dir: &Path) -> Result<()> {
+  let (name, script, cwd) = if dev {
+    match &config.build.before_dev_command {
+      Some(BeforeDevCommand::ScriptWithOptions {
+        script,
+        cwd,
+        wait: true,
+      }) => ("beforeDevCommand", script, cwd),
+      Some(BeforeDevCommand::Script(script))
+      | Some(BeforeDevCommand::ScriptWithOptions { script, .. })
+        if !script.is_empty() =>
+      {
+        println!(
+          "cargo:warning=`beforeDevCommand` is not run on `cargo build` since it does not set `wait: true`; start `{}` manually",
+          script
+        );
+        return Ok(());
+      }
+      _ => return Ok(()),
+    }
+  } else {
+    match &config.build.before_build_command {
+      Some(HookCommand::Script(script)) => ("beforeBuildCommand", script, &None),
+      Some(HookCommand::ScriptWithOptions { script, cwd }) => ("beforeBuildCommand", script, cwd),
+      None => return Ok(()),
+    }
+  };
+  if script.is_empty() {
+    return Ok(());
+  }
+
+  let tauri_dir = std::env::current_dir()?;
+  let cwd = match cwd {
+    Some(cwd) => tauri_dir.join(cwd),
+    None => tauri_dir
+      .parent()
+      .map(Path::to_path_buf)
+      .unwrap_or_else(|| tauri_dir.clone()),
+  };
+  let cwd = cwd.canonicalize().unwrap_or(cwd);
+
+  let dist_dir = match (dev, &config.build.dev_path, &config.build.dist_dir) {
+    (true, AppUrl::Url(WindowUrl::App(path)), _)
+    | (false, _, AppUrl::Url(WindowUrl::App(path))) => Some(tauri_dir.join(path)),
+    _ => None,
+  };
+  let ignored = std::iter::once(&tauri_dir)
+    .chain(&dist_dir)
+    .map(|d| d.canonicalize().unwrap_or_else(|_| d.clone()))
+    .collect::<Vec<_>>();
+
+  let sources = sources(&cwd, &ignored)?;
+  for source in &sources {
+    println!("cargo:rerun-if-changed={}", source.display());
+  }
+
+  let stamp_path = out_dir.join(format!("{}.stamp", name));
+  let stamp = fingerprint(script, &sources).to_string();
+  let dist_exists = dist_dir.as_ref().map_or(true, |d| d.exists());
+  if dist_exists && std::fs::read_to_string(&stamp_path).ok().as_deref() == Some(stamp.as_str()) {
+    return Ok(());
+  }
+
+  run(name, script, &cwd, dev)?;
+  std::fs::write(stamp_path, stamp)?;
+
+  Ok(())
+}
+
+/// Lists the paths of the frontend directory that are watched for changes.
+///
+/// Cargo watches the directories recursively, so the directory itself is returned unless it contains
+/// the Tauri or dist directory, which change on every build.
+fn sources(dir: &Path, ignored: &[PathBuf]) -> Result<Vec<PathBuf>> {
+  if !ignored.iter().any(|i| i.starts_with(dir)) {
+    return Ok(vec![dir.to_path_buf()]);
+  }
+  let mut entries = Vec::new();
+  for entry in std::fs::read_dir(dir)
+    .with_context(|| format!("failed to read the frontend directory `{}`", dir.display()))?
+  {
+    let path = entry?.path();
+    let ignored_name = path
+      .file_name()
+      .map_or(false, |name| IGNORED_DIRS.iter().any(|d| name == *d));
+    if ignored_name || ignored.contains(&path) {
+      continue;
+    }
+    if ignored.iter().any(|i| i.starts_with(&path)) {
+      // the directory contains the Tauri or dist directory, so watch its other entries instead
+      entries.extend(sources(&path, ignored)?);
+    } else {
+      entries.push(path);
+    }
+  }
+  entries.sort();
+  Ok(entries)
+}
+
+/// Hashes the script and the path, size and modification time of every file under `sources`.
+fn fingerprint(script: &str, sources: &[PathBuf]) -> u64 {
+  fn visit(path: &Path, hasher: &mut DefaultHasher) {
+    if path.is_dir() {
+      if let Ok(entries) = std::fs::read_dir(path) {
+        let mut entries = entries
+          .filter_map(|e| e.ok().map(|e| e.path()))
+          .filter(|p| {
+            !p.file_name()
+              .map_or(false, |name| IGNORED_DIRS.iter().any(|d| name == *d))
+          })
+          .collect::<Vec<_>>();
+        entries.sort();
+        for entry in entries {
+          visit(&entry, hasher);
+        }
+      }
+    } else if let Ok(metadata) = path.metadata() {
+      path.hash(hasher);
+      metadata.len().hash(hasher);
+      if let Ok(modified) = metadata.modified() {
+        modified
+          .duration_since(UNIX_EPOCH)
+          .unwrap_or_default()
+          .hash(hasher);
+      }
+    }
+  }
+
+  let mut hasher = DefaultHasher::new();
+  script.hash(&mut hasher);
+  for source in sources {
+    visit(source, &mut hasher);
+  }
+  hasher.finish()
+}
+
+/// The environment variables the CLI sets for its hooks, derived from the Cargo target.
+fn env(dev: bool) -> Vec<(&'static str, String)> {
+  let var = |name: &str| std::env::var(name).unwrap_or_default();
+  let target_os = var("CARGO_CFG_TARGET_OS");
+  let mut env = vec![
+    ("TAURI_TARGET_TRIPLE", var("TARGET")),
+    ("TAURI_ARCH", var("CARGO_CFG_TARGET_ARCH")),
+    ("TAURI_FAMILY", var("CARGO_CFG_TARGET_FAMILY")),
+  ];
+  match target_os.as_str() {
+    "linux" => env.push(("TAURI_PLATFORM_TYPE", "Linux".into())),
+    "windows" => env.push(("TAURI_PLATFORM_TYPE", "Windows_NT".into())),
+    "macos" => env.push(("TAURI_PLATFORM_TYPE", "Darwin".into())),
+    _ => (),
+  }
+  env.push(("TAURI_PLATFORM", target_os));
+  if dev || var("PROFILE") == "debug" {
+    env.push(("TAURI_DEBUG", "true".into()));
+  }
+  env
+}
+
+fn run(name: &str, script: &str, cwd: &Path, dev: bool) -> Result<()> {
+  #[cfg(windows)]
+  let mut command = {
+    let mut command = Command::new("cmd");
+    command.arg("/S").arg("/C").arg(script);
+    command
+  };
+  #[cfg(not(windows))]
+  let mut command = {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(script);
+    command
+  };
+
+  let status = command
+    .current_dir(cwd)
+    .envs(env(dev))
+    .status()
+    .with_context(|| format!("failed to run {} `{}`", name, script))?;
+  if !status.success() {
+    return Err(anyhow!(
+      "{} `{}` failed with exit code {}",
+      name,
+      script,
+      status.code().unwrap_or_default()
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  #[test]
+  fn sources() {
+    let dir = std::env::temp_dir().join(format!("tauri-build-frontend-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for path in [
+      "src/main.js",
+      "src-tauri/src/main.rs",
+      "node_modules/pkg/index.js",
+      "dist/index.html",
+      "nested/dist/index.html",
+      "nested/index.js",
+    ] {
+      let path = dir.join(path);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, "").unwrap();
+    }
+    std::fs::write(dir.join("package.json"), "{}").unwrap();
+
+    let ignored = vec![dir.join("src-tauri"), dir.join("nested/dist")];
+    let sources = super::sources(&dir, &ignored).unwrap();
+    assert_eq!(
+      sources,
+      ["dist", "nested/index.js", "package.json", "src"]
+        .iter()
+        .map(|p| dir.join(p))
+        .collect::<Vec<PathBuf>>()
+    );
+
+    let fingerprint = super::fingerprint("npm run build", &sources);
+    assert_eq!(fingerprint, super::fingerprint("npm run build", &sources));
+    assert_ne!(fingerprint, super::fingerprint("yarn build", &sources));
+
+    // a frontend directory outside of the Tauri directory is watched as a whole
+    assert_eq!(
+      super::sources(&dir.join("src"), &ignored).unwrap(),
+      vec![dir.join("src")]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
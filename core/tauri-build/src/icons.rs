@@ -0,0 +1,58 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Generates the application icons from a single source image, like the `tauri icon` CLI command.
+
+use std::{
+  fs::create_dir_all,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use anyhow::Context;
+use tauri_utils::icons::{resize_and_save_png, write_icns, write_ico, ICO_SIZES, PNG_ICONS};
+
+use crate::Result;
+
+fn modified(path: &Path) -> Option<SystemTime> {
+  path.metadata().and_then(|m| m.modified()).ok()
+}
+
+/// Generates the icons from the `source` image into `out_dir`,
+/// unless all of them are already newer than the source.
+///
+/// Returns the paths of the icons, in the order of the default `tauri > bundle > icon` configuration.
+pub fn generate(source: &Path, out_dir: &Path) -> Result<Vec<PathBuf>> {
+  let icons = PNG_ICONS
+    .iter()
+    .map(|(_, name)| *name)
+    .chain(["icon.icns", "icon.ico"])
+    .map(|name| out_dir.join(name))
+    .collect::<Vec<_>>();
+
+  let source_modified =
+    modified(source).with_context(|| format!("icon source `{}` not found", source.display()))?;
+  let up_to_date = icons.iter().all(|icon| {
+    modified(icon)
+      .map(|m| m >= source_modified)
+      .unwrap_or(false)
+  });
+  if up_to_date {
+    return Ok(icons);
+  }
+
+  let source = tauri_utils::icons::open(source)
+    .with_context(|| format!("failed to decode icon source `{}`", source.display()))?;
+
+  create_dir_all(out_dir)?;
+  write_icns(&source, &out_dir.join("icon.icns")).context("failed to generate the .icns file")?;
+  write_ico(&source, ICO_SIZES, &out_dir.join("icon.ico"))
+    .context("failed to generate the .ico file")?;
+  for (size, name) in PNG_ICONS {
+    resize_and_save_png(&source, *size, &out_dir.join(name))
+      .with_context(|| format!("failed to generate {}", name))?;
+  }
+
+  Ok(icons)
+}
@@ -7,18 +7,33 @@
 pub use anyhow::Result;
 use heck::AsShoutySnakeCase;
 
-use tauri_utils::resources::{external_binaries, resource_relpath, ResourcePaths};
+use tauri_utils::{
+  config::{
+    Allowlist, AllowlistConfig, AppAllowlistConfig, ClipboardAllowlistConfig,
+    DialogAllowlistConfig, FsAllowlistConfig, GlobalShortcutAllowlistConfig, HttpAllowlistConfig,
+    LogAllowlistConfig, MdnsAllowlistConfig, NotificationAllowlistConfig, OAuthAllowlistConfig,
+    OsAllowlistConfig, PathAllowlistConfig, ProcessAllowlistConfig, ProtocolAllowlistConfig,
+    SerialAllowlistConfig, ShellAllowlistConfig, StoreAllowlistConfig, WebsocketAllowlistConfig,
+    WindowAllowlistConfig,
+  },
+  resources::{external_binaries, resource_relpath, ResourcePaths},
+};
 
 use std::path::{Path, PathBuf};
 
 #[cfg(feature = "codegen")]
 mod codegen;
+mod frontend;
+#[cfg(feature = "icons")]
+mod icons;
 #[cfg(windows)]
 mod static_vcruntime;
+mod windows_manifest;
 
 #[cfg(feature = "codegen")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "codegen")))]
 pub use codegen::context::CodegenContext;
+pub use windows_manifest::{DpiAwareness, ExecutionLevel, SupportedOs, WindowsManifest};
 
 fn copy_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
   let from = from.as_ref();
@@ -110,6 +125,9 @@ pub struct WindowsAttributes {
   ///
   /// If it is left unset, it will look up a path in the registry, i.e. HKLM\SOFTWARE\Microsoft\Windows Kits\Installed Roots
   sdk_dir: Option<PathBuf>,
+  /// The application manifest to embed, replacing the one generated from [`Self::manifest`].
+  app_manifest: Option<String>,
+  manifest: WindowsManifest,
 }
 
 impl WindowsAttributes {
@@ -135,6 +153,25 @@ impl WindowsAttributes {
     self.sdk_dir = Some(sdk_dir.as_ref().into());
     self
   }
+
+  /// Sets the options merged into the embedded application manifest,
+  /// such as the DPI awareness or the UAC execution level. Currently only used on Windows.
+  #[must_use]
+  pub fn manifest(mut self, manifest: WindowsManifest) -> Self {
+    self.manifest = manifest;
+    self
+  }
+
+  /// Sets the application manifest XML to embed, instead of the one generated from [`Self::manifest`].
+  /// Currently only used on Windows.
+  ///
+  /// The manifest should declare the dependency on `Microsoft.Windows.Common-Controls` version 6,
+  /// which is required by the webview.
+  #[must_use]
+  pub fn app_manifest<S: Into<String>>(mut self, app_manifest: S) -> Self {
+    self.app_manifest.replace(app_manifest.into());
+    self
+  }
 }
 
 /// The attributes used on the build.
@@ -142,6 +179,11 @@ impl WindowsAttributes {
 pub struct Attributes {
   #[allow(dead_code)]
   windows_attributes: WindowsAttributes,
+  allowlist_report: bool,
+  strict_allowlist: bool,
+  frontend_build: bool,
+  #[cfg(feature = "icons")]
+  icon_source: Option<PathBuf>,
 }
 
 impl Attributes {
@@ -156,6 +198,57 @@ impl Attributes {
     self.windows_attributes = windows_attributes;
     self
   }
+
+  /// Writes a report of the API features compiled in and the ones enabled by the allowlist, per API module,
+  /// to the `allowlist-report.txt` file of `OUT_DIR`.
+  ///
+  /// Build scripts can't change the features of the `tauri` dependency,
+  /// which are configured from the allowlist by the `tauri dev` and `tauri build` commands.
+  #[must_use]
+  pub fn allowlist_report(mut self, allowlist_report: bool) -> Self {
+    self.allowlist_report = allowlist_report;
+    self
+  }
+
+  /// Fails the build when the features compiled in the `tauri` crate do not match the allowlist,
+  /// either because a feature is not allowlisted or because an allowlisted API is not compiled in.
+  ///
+  /// Unlike the check of the `Cargo.toml` `[dependencies]` table, which always fails the build,
+  /// this also covers the features enabled by the app features, the workspace dependencies and other crates.
+  /// Build scripts can't change the features of the `tauri` dependency, so they are still configured
+  /// from the allowlist by the `tauri dev` and `tauri build` commands.
+  #[must_use]
+  pub fn strict_allowlist(mut self, strict_allowlist: bool) -> Self {
+    self.strict_allowlist = strict_allowlist;
+    self
+  }
+
+  /// Runs the `build > beforeBuildCommand` on release builds and the `build > beforeDevCommand` on development builds
+  /// when the app is compiled with Cargo directly, so `cargo build` and `cargo run` don't require the Tauri CLI.
+  ///
+  /// The command only runs again when a file in the frontend directory changes,
+  /// which is the hook `cwd` or the parent of the Tauri directory, excluding `node_modules` and the assets directory.
+  /// The `beforeDevCommand` is only executed if it sets `wait: true`, since a dev server would block the build.
+  #[must_use]
+  pub fn frontend_build(mut self, frontend_build: bool) -> Self {
+    self.frontend_build = frontend_build;
+    self
+  }
+
+  /// Sets the square PNG, JPEG, BMP or ICO image (1024x1024 recommended) used to generate the app icons.
+  ///
+  /// The `icon.ico`, `icon.icns` and PNG icons of the `tauri icon` command are written to the `icons` directory of `OUT_DIR`,
+  /// and replace the `tauri > bundle > icon` configuration on the embedded Windows resource and the window icons of
+  /// [`tauri::generate_context!`](https://docs.rs/tauri/*/tauri/macro.generate_context.html).
+  /// The bundler still reads the configured icons, so use the `tauri icon` command for the bundle icons.
+  /// They are only regenerated when the source image changes.
+  #[cfg(feature = "icons")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "icons")))]
+  #[must_use]
+  pub fn icon_source<P: AsRef<Path>>(mut self, icon_source: P) -> Self {
+    self.icon_source.replace(icon_source.as_ref().into());
+    self
+  }
 }
 
 /// Run all build time helpers for your Tauri Application.
@@ -179,26 +272,19 @@ impl Attributes {
 /// This is typically desirable when running inside a build script; see [`try_build`] for no panics.
 pub fn build() {
   if let Err(error) = try_build(Attributes::default()) {
-    let error = format!("{:#}", error);
-    println!("{}", error);
-    if error.starts_with("unknown field") {
-      print!("found an unknown configuration field. This usually means that you are using a CLI version that is newer than `tauri-build` and is incompatible. ");
-      println!(
-        "Please try updating the Rust crates by running `cargo update` in the Tauri app folder."
-      );
-    }
-    std::process::exit(1);
+    panic!("{:#}", error);
   }
 }
 
 /// Non-panicking [`build()`].
 #[allow(unused_variables)]
 pub fn try_build(attributes: Attributes) -> Result<()> {
-  use anyhow::anyhow;
+  use anyhow::{anyhow, Context};
   use cargo_toml::{Dependency, Manifest};
   use tauri_utils::config::{Config, TauriConfig};
 
   println!("cargo:rerun-if-env-changed=TAURI_CONFIG");
+  println!("cargo:rerun-if-env-changed=TAURI_PROFILE");
   println!("cargo:rerun-if-changed=tauri.conf.json");
   #[cfg(feature = "config-json5")]
   println!("cargo:rerun-if-changed=tauri.conf.json5");
@@ -210,17 +296,61 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
   cfg_alias("desktop", !mobile);
   cfg_alias("mobile", mobile);
 
-  let mut config = serde_json::from_value(tauri_utils::config::parse::read_from(
-    std::env::current_dir().unwrap(),
-  )?)?;
+  let (mut config, extended) =
+    tauri_utils::config::parse::read_from_with_extends(std::env::current_dir().unwrap())?;
+  for path in extended {
+    println!("cargo:rerun-if-changed={}", path.display());
+  }
+  tauri_utils::config::parse::apply_profile(
+    &mut config,
+    std::env::var("TAURI_PROFILE").ok().as_deref(),
+  )?;
   if let Ok(env) = std::env::var("TAURI_CONFIG") {
     let merge_config: serde_json::Value = serde_json::from_str(&env)?;
     json_patch::merge(&mut config, &merge_config);
   }
-  let config: Config = serde_json::from_value(config)?;
+  for name in tauri_utils::config::parse::interpolate_env(&mut config)? {
+    println!("cargo:rerun-if-env-changed={}", name);
+  }
+
+  #[cfg(feature = "icons")]
+  if let Some(icon_source) = &attributes.icon_source {
+    println!("cargo:rerun-if-changed={}", icon_source.display());
+    let icons = icons::generate(
+      icon_source,
+      &Path::new(&std::env::var("OUT_DIR").unwrap()).join("icons"),
+    )?;
+    // the generated icons replace the configured ones on the Windows resource,
+    // and on `tauri::generate_context!` and the `CodegenContext` through `TAURI_CONFIG`
+    let icon_config = serde_json::json!({ "tauri": { "bundle": { "icon": icons } } });
+    json_patch::merge(&mut config, &icon_config);
+    let env_config = merge_env_config(std::env::var("TAURI_CONFIG").ok().as_deref(), &icon_config);
+    println!("cargo:rustc-env=TAURI_CONFIG={}", env_config);
+    std::env::set_var("TAURI_CONFIG", env_config.to_string());
+  }
+
+  let config: Config = serde_json::from_value(config).map_err(|error| {
+    let error = anyhow::Error::from(error);
+    if error.to_string().starts_with("unknown field") {
+      error.context("found an unknown configuration field. This usually means that you are using a CLI version that is newer than `tauri-build` and is incompatible. Please try updating the Rust crates by running `cargo update` in the Tauri app folder")
+    } else {
+      error
+    }
+  })?;
 
   cfg_alias("dev", !has_feature("custom-protocol"));
 
+  if attributes.frontend_build {
+    println!("cargo:rerun-if-env-changed=TAURI_CLI");
+    if !frontend::is_cli_build() {
+      frontend::build(
+        &config,
+        !has_feature("custom-protocol"),
+        Path::new(&std::env::var("OUT_DIR").unwrap()),
+      )?;
+    }
+  }
+
   let mut manifest = Manifest::from_path("Cargo.toml")?;
   if let Some(tauri) = manifest.dependencies.remove("tauri") {
     let features = match tauri {
@@ -229,6 +359,13 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
       Dependency::Inherited(dep) => dep.features,
     };
 
+    if attributes.allowlist_report {
+      let report = allowlist_report(&features, &config.tauri.allowlist);
+      let report_path = Path::new(&std::env::var("OUT_DIR").unwrap()).join("allowlist-report.txt");
+      std::fs::write(&report_path, report.join("\n"))
+        .with_context(|| format!("failed to write {}", report_path.display()))?;
+    }
+
     let all_cli_managed_features = TauriConfig::all_features();
     let diff = features_diff(
       &features
@@ -274,6 +411,31 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     }
   }
 
+  if attributes.strict_allowlist {
+    let compiled = std::env::var("DEP_TAURI_FEATURES").map_err(|_| {
+      anyhow!("the `tauri` crate did not report its features, so they can't be checked against the allowlist")
+    })?;
+    let compiled = compiled
+      .split(',')
+      .filter(|f| !f.is_empty())
+      .map(String::from)
+      .collect::<Vec<_>>();
+    let allowlisted = config
+      .tauri
+      .features()
+      .into_iter()
+      .map(String::from)
+      .collect::<Vec<_>>();
+    let diff = strict_features_diff(&compiled, &allowlisted);
+    if !diff.remove.is_empty() || !diff.add.is_empty() {
+      return Err(anyhow!(
+        "the features compiled in the `tauri` crate do not match the allowlist defined under `tauri.conf.json`: not allowlisted [{}], not compiled in [{}]",
+        diff.remove.join(", "),
+        diff.add.join(", ")
+      ));
+    }
+  }
+
   let target_triple = std::env::var("TARGET").unwrap();
   let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
   // TODO: far from ideal, but there's no other way to get the target dir, see <https://github.com/rust-lang/cargo/issues/5457>
@@ -294,13 +456,15 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     )?;
   }
 
-  #[allow(unused_mut, clippy::redundant_clone)]
+  #[allow(clippy::redundant_clone)]
   let mut resources = config.tauri.bundle.resources.clone().unwrap_or_default();
   #[cfg(windows)]
-  if let Some(fixed_webview2_runtime_path) = &config.tauri.bundle.windows.webview_fixed_runtime_path
-  {
+  if let Some(fixed_webview2_runtime_path) = config.tauri.bundle.windows.fixed_runtime_path() {
     resources.push(fixed_webview2_runtime_path.display().to_string());
   }
+  if let Some(i18n) = &config.tauri.i18n {
+    resources.push(i18n.resource_pattern());
+  }
   copy_resources(ResourcePaths::new(resources.as_slice(), true), target_dir)?;
 
   #[cfg(target_os = "macos")]
@@ -312,7 +476,6 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
 
   #[cfg(windows)]
   {
-    use anyhow::Context;
     use semver::Version;
     use winres::{VersionInfo, WindowsResource};
 
@@ -336,24 +499,11 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     if window_icon_path.exists() {
       let mut res = WindowsResource::new();
 
-      res.set_manifest(
-        r#"
-        <assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
-          <dependency>
-              <dependentAssembly>
-                  <assemblyIdentity
-                      type="win32"
-                      name="Microsoft.Windows.Common-Controls"
-                      version="6.0.0.0"
-                      processorArchitecture="*"
-                      publicKeyToken="6595b64144ccf1df"
-                      language="*"
-                  />
-              </dependentAssembly>
-          </dependency>
-        </assembly>
-        "#,
-      );
+      let manifest = attributes
+        .windows_attributes
+        .app_manifest
+        .unwrap_or_else(|| attributes.windows_attributes.manifest.render());
+      res.set_manifest(&manifest);
 
       if let Some(sdk_dir) = &attributes.windows_attributes.sdk_dir {
         if let Some(sdk_dir_str) = sdk_dir.to_str() {
@@ -427,6 +577,140 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
   Ok(())
 }
 
+/// Lists the API features compiled in and the ones enabled by the allowlist, per API module.
+fn allowlist_report(features: &[String], allowlist: &AllowlistConfig) -> Vec<String> {
+  let modules = [
+    (
+      "fs",
+      FsAllowlistConfig::all_features(),
+      allowlist.fs.to_features(),
+    ),
+    (
+      "window",
+      WindowAllowlistConfig::all_features(),
+      allowlist.window.to_features(),
+    ),
+    (
+      "shell",
+      ShellAllowlistConfig::all_features(),
+      allowlist.shell.to_features(),
+    ),
+    (
+      "dialog",
+      DialogAllowlistConfig::all_features(),
+      allowlist.dialog.to_features(),
+    ),
+    (
+      "http",
+      HttpAllowlistConfig::all_features(),
+      allowlist.http.to_features(),
+    ),
+    (
+      "websocket",
+      WebsocketAllowlistConfig::all_features(),
+      allowlist.websocket.to_features(),
+    ),
+    (
+      "serial",
+      SerialAllowlistConfig::all_features(),
+      allowlist.serial.to_features(),
+    ),
+    (
+      "mdns",
+      MdnsAllowlistConfig::all_features(),
+      allowlist.mdns.to_features(),
+    ),
+    (
+      "store",
+      StoreAllowlistConfig::all_features(),
+      allowlist.store.to_features(),
+    ),
+    (
+      "oauth",
+      OAuthAllowlistConfig::all_features(),
+      allowlist.oauth.to_features(),
+    ),
+    (
+      "log",
+      LogAllowlistConfig::all_features(),
+      allowlist.log.to_features(),
+    ),
+    (
+      "notification",
+      NotificationAllowlistConfig::all_features(),
+      allowlist.notification.to_features(),
+    ),
+    (
+      "globalShortcut",
+      GlobalShortcutAllowlistConfig::all_features(),
+      allowlist.global_shortcut.to_features(),
+    ),
+    (
+      "os",
+      OsAllowlistConfig::all_features(),
+      allowlist.os.to_features(),
+    ),
+    (
+      "path",
+      PathAllowlistConfig::all_features(),
+      allowlist.path.to_features(),
+    ),
+    (
+      "protocol",
+      ProtocolAllowlistConfig::all_features(),
+      allowlist.protocol.to_features(),
+    ),
+    (
+      "process",
+      ProcessAllowlistConfig::all_features(),
+      allowlist.process.to_features(),
+    ),
+    (
+      "clipboard",
+      ClipboardAllowlistConfig::all_features(),
+      allowlist.clipboard.to_features(),
+    ),
+    (
+      "app",
+      AppAllowlistConfig::all_features(),
+      allowlist.app.to_features(),
+    ),
+  ];
+
+  let api_all = features.iter().any(|f| f == "api-all");
+  let mut report = Vec::new();
+  for (module, all_features, allowed) in modules {
+    let allowed = if allowlist.all {
+      all_features.clone()
+    } else {
+      allowed
+    };
+    let compiled = all_features
+      .into_iter()
+      .filter(|f| api_all || features.iter().any(|feature| feature == f))
+      .collect::<Vec<_>>();
+    if compiled.is_empty() && allowed.is_empty() {
+      continue;
+    }
+    let mut line = format!(
+      "{}: compiled [{}], allowlisted [{}]",
+      module,
+      compiled.join(", "),
+      allowed.join(", ")
+    );
+    let unused = compiled
+      .iter()
+      .filter(|f| !allowed.contains(f))
+      .copied()
+      .collect::<Vec<_>>();
+    if !unused.is_empty() {
+      line.push_str(&format!(", not allowlisted [{}]", unused.join(", ")));
+    }
+    report.push(line);
+  }
+  report
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 struct Diff {
   remove: Vec<String>,
@@ -451,9 +735,136 @@ fn features_diff(current: &[String], expected: &[String]) -> Diff {
   Diff { remove, add }
 }
 
+/// Whether the feature is enabled by the list, directly or through the `api-all` and `<module>-all` features.
+fn feature_enabled(feature: &str, features: &[String]) -> bool {
+  let allowlist_features = AllowlistConfig::all_features();
+  features.iter().any(|enabled| {
+    enabled == feature
+      || allowlist_features.contains(&feature)
+        && (enabled == "api-all"
+          || enabled.strip_suffix("all").map_or(false, |module| {
+            module.ends_with('-') && feature.starts_with(module)
+          }))
+  })
+}
+
+/// Compares the CLI-managed features compiled in the `tauri` crate with the allowlisted ones,
+/// returning the features to remove from the compiled ones and to add to them.
+fn strict_features_diff(compiled: &[String], allowlisted: &[String]) -> Diff {
+  let managed_features = tauri_utils::config::TauriConfig::all_features();
+  Diff {
+    remove: compiled
+      .iter()
+      .filter(|f| managed_features.contains(&f.as_str()) && !feature_enabled(f, allowlisted))
+      .cloned()
+      .collect(),
+    add: allowlisted
+      .iter()
+      .filter(|f| !feature_enabled(f, compiled))
+      .cloned()
+      .collect(),
+  }
+}
+
+/// Merges the `patch` on the `TAURI_CONFIG` environment variable value, ignoring it when it is not valid JSON.
+#[cfg(feature = "icons")]
+fn merge_env_config(env: Option<&str>, patch: &serde_json::Value) -> serde_json::Value {
+  let mut env_config = env
+    .and_then(|env| serde_json::from_str(env).ok())
+    .unwrap_or_else(|| serde_json::json!({}));
+  json_patch::merge(&mut env_config, patch);
+  env_config
+}
+
 #[cfg(test)]
 mod tests {
   use super::Diff;
+  use tauri_utils::config::AllowlistConfig;
+
+  #[test]
+  fn allowlist_report() {
+    let mut allowlist = AllowlistConfig::default();
+    allowlist.fs.read_file = true;
+    allowlist.os.all = true;
+
+    let report = super::allowlist_report(
+      &[
+        "fs-read-file".into(),
+        "fs-write-file".into(),
+        "os-all".into(),
+      ],
+      &allowlist,
+    );
+    assert_eq!(
+      report,
+      vec![
+        "fs: compiled [fs-read-file, fs-write-file], allowlisted [fs-read-file], not allowlisted [fs-write-file]",
+        "os: compiled [os-all], allowlisted [os-all]",
+      ]
+    );
+  }
+
+  #[cfg(feature = "icons")]
+  #[test]
+  fn icons_env_config() {
+    use serde_json::json;
+
+    let icon_config = json!({ "tauri": { "bundle": { "icon": ["out/icons/icon.ico"] } } });
+    assert_eq!(super::merge_env_config(None, &icon_config), icon_config);
+    assert_eq!(
+      super::merge_env_config(
+        Some(
+          r#"{ "tauri": { "bundle": { "identifier": "com.tauri.app", "icon": ["icon.png"] } } }"#
+        ),
+        &icon_config
+      ),
+      json!({ "tauri": { "bundle": { "identifier": "com.tauri.app", "icon": ["out/icons/icon.ico"] } } })
+    );
+    assert_eq!(
+      super::merge_env_config(Some("not json"), &icon_config),
+      icon_config
+    );
+  }
+
+  #[test]
+  fn strict_features_diff() {
+    let features = |features: &[&str]| features.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+    // the `*-all` features enable their APIs
+    let diff = super::strict_features_diff(
+      &features(&[
+        "api-all",
+        "fs-all",
+        "fs-read-file",
+        "custom-protocol",
+        "wry",
+      ]),
+      &features(&["api-all"]),
+    );
+    assert_eq!(diff, Diff::default());
+    let diff = super::strict_features_diff(
+      &features(&["fs-all", "fs-read-file", "fs-write-file"]),
+      &features(&["fs-read-file"]),
+    );
+    assert_eq!(
+      diff,
+      Diff {
+        remove: features(&["fs-all", "fs-write-file"]),
+        add: vec![],
+      }
+    );
+    let diff = super::strict_features_diff(
+      &features(&["global-shortcut-all", "cli"]),
+      &features(&["global-shortcut-all", "system-tray"]),
+    );
+    assert_eq!(
+      diff,
+      Diff {
+        remove: features(&["cli"]),
+        add: features(&["system-tray"]),
+      }
+    );
+  }
 
   #[test]
   fn array_diff() {
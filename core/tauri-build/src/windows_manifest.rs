@@ -0,0 +1,244 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Write;
+
+/// The DPI awareness of the application.
+///
+/// See <https://learn.microsoft.com/en-us/windows/win32/hidpi/setting-the-default-dpi-awareness-for-a-process>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DpiAwareness {
+  /// The application is not DPI aware, so Windows scales it.
+  Unaware,
+  /// The application uses the DPI of the primary display.
+  System,
+  /// The application handles DPI changes when moved between displays.
+  PerMonitor,
+  /// Like [`Self::PerMonitor`], with child window, dialog and non-client area scaling.
+  /// Falls back to [`Self::PerMonitor`] on Windows versions older than Windows 10 1703.
+  PerMonitorV2,
+}
+
+impl DpiAwareness {
+  fn dpi_aware(self) -> &'static str {
+    match self {
+      Self::Unaware => "false",
+      Self::System => "true",
+      Self::PerMonitor | Self::PerMonitorV2 => "true/pm",
+    }
+  }
+
+  fn dpi_awareness(self) -> &'static str {
+    match self {
+      Self::Unaware => "unaware",
+      Self::System => "system",
+      Self::PerMonitor => "PerMonitor",
+      Self::PerMonitorV2 => "PerMonitorV2, PerMonitor",
+    }
+  }
+}
+
+/// The UAC execution level requested by the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionLevel {
+  /// Runs with the same access token as the parent process.
+  AsInvoker,
+  /// Runs with the highest privileges the current user can obtain.
+  HighestAvailable,
+  /// Requires administrator privileges.
+  RequireAdministrator,
+}
+
+impl ExecutionLevel {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::AsInvoker => "asInvoker",
+      Self::HighestAvailable => "highestAvailable",
+      Self::RequireAdministrator => "requireAdministrator",
+    }
+  }
+}
+
+/// A Windows version the application is declared compatible with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupportedOs {
+  /// Windows Vista and Windows Server 2008.
+  WindowsVista,
+  /// Windows 7 and Windows Server 2008 R2.
+  Windows7,
+  /// Windows 8 and Windows Server 2012.
+  Windows8,
+  /// Windows 8.1 and Windows Server 2012 R2.
+  Windows81,
+  /// Windows 10, Windows 11 and Windows Server 2016 or newer.
+  Windows10,
+}
+
+impl SupportedOs {
+  fn guid(self) -> &'static str {
+    match self {
+      Self::WindowsVista => "{e2011457-1546-43c5-a5fe-008deee3d3f0}",
+      Self::Windows7 => "{35138b9a-5d96-4fbd-8e2d-a2440225f93a}",
+      Self::Windows8 => "{4a2f28e3-53b9-4441-ba9c-d69d4a4a6e38}",
+      Self::Windows81 => "{1f676c76-80e1-4239-95bb-83d0f6d0da78}",
+      Self::Windows10 => "{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}",
+    }
+  }
+}
+
+/// Options merged into the application manifest embedded on Windows.
+///
+/// The manifest always declares the dependency on the Common Controls v6 library.
+#[derive(Debug, Clone, Default)]
+pub struct WindowsManifest {
+  dpi_awareness: Option<DpiAwareness>,
+  execution_level: Option<ExecutionLevel>,
+  supported_os: Vec<SupportedOs>,
+  long_path_aware: bool,
+}
+
+impl WindowsManifest {
+  /// Creates the default manifest options.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the DPI awareness of the application.
+  #[must_use]
+  pub fn dpi_awareness(mut self, dpi_awareness: DpiAwareness) -> Self {
+    self.dpi_awareness.replace(dpi_awareness);
+    self
+  }
+
+  /// Sets the UAC execution level requested by the application.
+  #[must_use]
+  pub fn execution_level(mut self, execution_level: ExecutionLevel) -> Self {
+    self.execution_level.replace(execution_level);
+    self
+  }
+
+  /// Declares the application compatible with the given Windows version.
+  #[must_use]
+  pub fn supported_os(mut self, os: SupportedOs) -> Self {
+    if !self.supported_os.contains(&os) {
+      self.supported_os.push(os);
+    }
+    self
+  }
+
+  /// Opts into paths longer than `MAX_PATH` on Windows 10 1607 or newer,
+  /// which also requires the `LongPathsEnabled` system setting.
+  #[must_use]
+  pub fn long_path_aware(mut self, long_path_aware: bool) -> Self {
+    self.long_path_aware = long_path_aware;
+    self
+  }
+
+  #[cfg_attr(not(windows), allow(dead_code))]
+  pub(crate) fn render(&self) -> String {
+    let mut manifest = String::from(
+      r#"<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <dependency>
+    <dependentAssembly>
+      <assemblyIdentity
+        type="win32"
+        name="Microsoft.Windows.Common-Controls"
+        version="6.0.0.0"
+        processorArchitecture="*"
+        publicKeyToken="6595b64144ccf1df"
+        language="*"
+      />
+    </dependentAssembly>
+  </dependency>
+"#,
+    );
+
+    if let Some(execution_level) = self.execution_level {
+      let _ = write!(
+        manifest,
+        r#"  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel level="{}" uiAccess="false" />
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+"#,
+        execution_level.as_str()
+      );
+    }
+
+    if !self.supported_os.is_empty() {
+      manifest.push_str(
+        r#"  <compatibility xmlns="urn:schemas-microsoft-com:compatibility.v1">
+    <application>
+"#,
+      );
+      for os in &self.supported_os {
+        let _ = writeln!(manifest, r#"      <supportedOS Id="{}" />"#, os.guid());
+      }
+      manifest.push_str("    </application>\n  </compatibility>\n");
+    }
+
+    if self.dpi_awareness.is_some() || self.long_path_aware {
+      manifest.push_str(
+        r#"  <application xmlns="urn:schemas-microsoft-com:asm.v3">
+    <windowsSettings>
+"#,
+      );
+      if let Some(dpi_awareness) = self.dpi_awareness {
+        let _ = write!(
+          manifest,
+          r#"      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">{}</dpiAware>
+      <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">{}</dpiAwareness>
+"#,
+          dpi_awareness.dpi_aware(),
+          dpi_awareness.dpi_awareness()
+        );
+      }
+      if self.long_path_aware {
+        manifest.push_str(
+          r#"      <longPathAware xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">true</longPathAware>
+"#,
+        );
+      }
+      manifest.push_str("    </windowsSettings>\n  </application>\n");
+    }
+
+    manifest.push_str("</assembly>\n");
+    manifest
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{DpiAwareness, ExecutionLevel, SupportedOs, WindowsManifest};
+
+  #[test]
+  fn render() {
+    let manifest = WindowsManifest::new().render();
+    assert!(manifest.contains("Microsoft.Windows.Common-Controls"));
+    assert!(!manifest.contains("trustInfo"));
+    assert!(!manifest.contains("windowsSettings"));
+
+    let manifest = WindowsManifest::new()
+      .dpi_awareness(DpiAwareness::PerMonitorV2)
+      .execution_level(ExecutionLevel::RequireAdministrator)
+      .supported_os(SupportedOs::Windows10)
+      .supported_os(SupportedOs::Windows10)
+      .long_path_aware(true)
+      .render();
+    assert!(manifest.contains(r#"level="requireAdministrator""#));
+    assert_eq!(
+      manifest
+        .matches(r#"<supportedOS Id="{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}" />"#)
+        .count(),
+      1
+    );
+    assert!(manifest.contains(">true/pm</dpiAware>"));
+    assert!(manifest.contains(">PerMonitorV2, PerMonitor</dpiAwareness>"));
+    assert!(manifest.contains(">true</longPathAware>"));
+    assert!(manifest.ends_with("</assembly>\n"));
+  }
+}
@@ -14,9 +14,13 @@ use tauri_utils::config::{AppUrl, Config, PatternKind, WindowUrl};
 use tauri_utils::html::{
   inject_nonce_token, parse as parse_html, serialize_node as serialize_html_node,
 };
+use tauri_utils::inspect::InspectManifest;
+use tauri_utils::permissions::PermissionManifest;
 
 #[cfg(feature = "shell-scope")]
-use tauri_utils::config::{ShellAllowedArg, ShellAllowedArgs, ShellAllowlistScope};
+use tauri_utils::config::{
+  ShellAllowedArg, ShellAllowedArgs, ShellAllowlistScope, ShellCommandDefaults,
+};
 
 use crate::embedded_assets::{AssetOptions, CspHashes, EmbeddedAssets, EmbeddedAssetsError};
 
@@ -363,11 +367,11 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
   let pattern = match &options.pattern {
     PatternKind::Brownfield => quote!(#root::Pattern::Brownfield(std::marker::PhantomData)),
     #[cfg(not(feature = "isolation"))]
-    PatternKind::Isolation { dir: _ } => {
+    PatternKind::Isolation { .. } => {
       quote!(#root::Pattern::Brownfield(std::marker::PhantomData))
     }
     #[cfg(feature = "isolation")]
-    PatternKind::Isolation { dir } => {
+    PatternKind::Isolation { dir, .. } => {
       let dir = config_parent.join(dir);
       if !dir.exists() {
         panic!(
@@ -409,7 +413,7 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
     use regex::Regex;
     use tauri_utils::config::ShellAllowlistOpen;
 
-    let shell_scopes = get_allowed_clis(&root, &config.tauri.allowlist.shell.scope);
+    let shell_scopes = get_allowed_clis(&root, &config.tauri.allowlist.shell.scope, target);
 
     let shell_scope_open = match &config.tauri.allowlist.shell.open {
       ShellAllowlistOpen::Flag(false) => quote!(::std::option::Option::None),
@@ -438,17 +442,28 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
   #[cfg(not(feature = "shell-scope"))]
   let shell_scope_config = quote!();
 
-  Ok(quote!(#root::Context::new(
-    #config,
-    ::std::sync::Arc::new(#assets),
-    #default_window_icon,
-    #app_icon,
-    #system_tray_icon,
-    #package_info,
-    #info_plist,
-    #pattern,
-    #shell_scope_config
-  )))
+  let permissions_manifest = serde_json::to_string_pretty(&PermissionManifest::new(&config))
+    .expect("failed to serialize the permission manifest");
+  let inspect_manifest = InspectManifest::new(&config, assets.info())
+    .embed()
+    .expect("failed to serialize the inspect manifest");
+
+  Ok(quote!({
+    let mut context = #root::Context::new(
+      #config,
+      ::std::sync::Arc::new(#assets),
+      #default_window_icon,
+      #app_icon,
+      #system_tray_icon,
+      #package_info,
+      #info_plist,
+      #pattern,
+      #shell_scope_config
+    );
+    *context.permissions_manifest_mut() = ::std::option::Option::Some(#permissions_manifest);
+    *context.inspect_manifest_mut() = ::std::option::Option::Some(#inspect_manifest);
+    context
+  }))
 }
 
 fn ico_icon<P: AsRef<Path>>(
@@ -565,7 +580,11 @@ fn find_icon<F: Fn(&&String) -> bool>(
 }
 
 #[cfg(feature = "shell-scope")]
-fn get_allowed_clis(root: &TokenStream, scope: &ShellAllowlistScope) -> TokenStream {
+fn get_allowed_clis(
+  root: &TokenStream,
+  scope: &ShellAllowlistScope,
+  target: Target,
+) -> TokenStream {
   let commands = scope
     .0
     .iter()
@@ -611,15 +630,46 @@ fn get_allowed_clis(root: &TokenStream, scope: &ShellAllowlistScope) -> TokenStr
         _ => panic!("unknown shell scope command, unable to prepare"),
       };
 
+      let platforms = &scope.platforms;
+      // every platform is validated, so the errors do not depend on the build target
+      let errors = [&platforms.linux, &platforms.macos, &platforms.windows]
+        .into_iter()
+        .flatten()
+        .flat_map(|defaults| validate_command_defaults(&scope.name, defaults))
+        .map(|error| quote!(compile_error!(#error);));
+      let defaults = match target {
+        Target::Linux => platforms.linux.as_ref(),
+        Target::Darwin => platforms.macos.as_ref(),
+        Target::Windows => platforms.windows.as_ref(),
+        _ => None,
+      };
+      let default_args = defaults.map(|d| d.args.as_slice()).unwrap_or_default();
+      let env = defaults
+        .map(|d| {
+          let insertions = d
+            .env
+            .iter()
+            .map(|(key, value)| quote!(env.insert(#key.into(), #value.into());));
+          quote!({
+            let mut env = ::std::collections::HashMap::new();
+            #(#insertions)*
+            env
+          })
+        })
+        .unwrap_or_else(|| quote!(::std::collections::HashMap::new()));
+
       (
         quote!(#name),
-        quote!(
+        quote!({
+          #(#errors)*
           #root::scope::ShellScopeAllowedCommand {
             command: #command,
             args: #args,
             sidecar: #sidecar,
+            default_args: ::std::vec![#(#default_args.into()),*],
+            env: #env,
           }
-        ),
+        }),
       )
     })
     .collect::<Vec<_>>();
@@ -638,3 +688,63 @@ fn get_allowed_clis(root: &TokenStream, scope: &ShellAllowlistScope) -> TokenStr
     })
   }
 }
+
+/// The variables that can start the default argument and environment values of a shell scope command.
+#[cfg(feature = "shell-scope")]
+const BASE_DIRECTORY_VARIABLES: &[&str] = &[
+  "$AUDIO",
+  "$CACHE",
+  "$CONFIG",
+  "$DATA",
+  "$LOCALDATA",
+  "$DESKTOP",
+  "$DOCUMENT",
+  "$DOWNLOAD",
+  "$EXE",
+  "$FONT",
+  "$HOME",
+  "$PICTURE",
+  "$PUBLIC",
+  "$RUNTIME",
+  "$TEMPLATE",
+  "$VIDEO",
+  "$RESOURCE",
+  "$APP",
+  "$LOG",
+  "$TEMP",
+  "$APPCONFIG",
+  "$APPDATA",
+  "$APPLOCALDATA",
+  "$APPCACHE",
+  "$APPLOG",
+];
+
+/// Checks the variables and environment variable names of a shell scope command defaults.
+#[cfg(feature = "shell-scope")]
+fn validate_command_defaults(name: &str, defaults: &ShellCommandDefaults) -> Vec<String> {
+  let mut errors = Vec::new();
+
+  for key in defaults.env.keys() {
+    if key.is_empty() || key.contains(['=', '\0']) {
+      errors.push(format!(
+        "invalid environment variable name `{}` in the shell scope command `{}`",
+        key, name
+      ));
+    }
+  }
+
+  for value in defaults.args.iter().chain(defaults.env.values()) {
+    if let Some(variable) = value.split(['/', '\\']).next() {
+      if variable.starts_with('$') && !BASE_DIRECTORY_VARIABLES.contains(&variable) {
+        errors.push(format!(
+          "unknown variable `{}` in the shell scope command `{}`, expected one of {}",
+          variable,
+          name,
+          BASE_DIRECTORY_VARIABLES.join(", ")
+        ));
+      }
+    }
+  }
+
+  errors
+}
@@ -6,12 +6,13 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
 use sha2::{Digest, Sha256};
 use std::{
-  collections::HashMap,
+  collections::{HashMap, HashSet},
   fmt::Write,
   fs::File,
   path::{Path, PathBuf},
 };
 use tauri_utils::config::PatternKind;
+use tauri_utils::inspect::AssetInfo;
 use tauri_utils::{assets::AssetKey, config::DisabledCspModificationKind};
 use thiserror::Error;
 use walkdir::{DirEntry, WalkDir};
@@ -97,10 +98,13 @@ struct RawEmbeddedAssets {
 
 impl RawEmbeddedAssets {
   /// Creates a new list of (prefix, entry) from a collection of inputs.
+  ///
+  /// When several inputs contain a file with the same path relative to their prefix,
+  /// the file of the last input is used.
   fn new(input: EmbeddedAssetsInput, options: &AssetOptions) -> Result<Self, EmbeddedAssetsError> {
     let mut csp_hashes = CspHashes::default();
 
-    input
+    let mut paths = input
       .0
       .into_iter()
       .flat_map(|path| {
@@ -124,16 +128,7 @@ impl RawEmbeddedAssets {
           // we only serve files, not directory listings
           Ok(entry) if entry.file_type().is_dir() => None,
 
-          // compress all files encountered
-          Ok(entry) => {
-            if let Err(error) = csp_hashes
-              .add_if_applicable(&entry, &options.dangerous_disable_asset_csp_modification)
-            {
-              Some(Err(error))
-            } else {
-              Some(Ok((prefix, entry)))
-            }
-          }
+          Ok(entry) => Some(Ok((prefix, entry))),
 
           // pass down error through filter to fail when encountering any error
           Err(error) => Some(Err(EmbeddedAssetsError::Walkdir {
@@ -142,8 +137,28 @@ impl RawEmbeddedAssets {
           })),
         }
       })
-      .collect::<Result<Vec<(PathBuf, DirEntry)>, _>>()
-      .map(|paths| Self { paths, csp_hashes })
+      .collect::<Result<Vec<(PathBuf, DirEntry)>, _>>()?;
+
+    // later inputs override the files of the earlier ones
+    let mut seen = HashSet::new();
+    paths.reverse();
+    paths.retain(|(prefix, entry)| {
+      seen.insert(
+        entry
+          .path()
+          .strip_prefix(prefix)
+          .unwrap_or_else(|_| entry.path())
+          .to_path_buf(),
+      )
+    });
+    paths.reverse();
+
+    // only the embedded files contribute to the CSP hashes
+    for (_, entry) in &paths {
+      csp_hashes.add_if_applicable(entry, &options.dangerous_disable_asset_csp_modification)?;
+    }
+
+    Ok(Self { paths, csp_hashes })
   }
 }
 
@@ -277,6 +292,16 @@ impl EmbeddedAssets {
     Ok(Self { assets, csp_hashes })
   }
 
+  /// The path and sizes of the embedded assets.
+  pub fn info(&self) -> Vec<AssetInfo> {
+    let size = |path: &Path| std::fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+    self
+      .assets
+      .iter()
+      .map(|(key, (input, output))| AssetInfo::new(key.as_ref(), size(input), size(output)))
+      .collect()
+  }
+
   /// Use highest compression level for release, the fastest one for everything else
   #[cfg(feature = "compression")]
   fn compression_settings() -> BrotliEncoderParams {
@@ -436,3 +461,47 @@ impl ToTokens for EmbeddedAssets {
     }});
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{AssetOptions, RawEmbeddedAssets};
+  use tauri_utils::config::PatternKind;
+
+  #[test]
+  fn overlays_inputs() {
+    let dir = std::env::temp_dir().join(format!("tauri-codegen-assets-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (path, contents) in [
+      ("base/index.html", "base"),
+      ("base/app.js", "base"),
+      ("overlay/index.html", "overlay"),
+      ("overlay/nested/page.html", "overlay"),
+    ] {
+      let path = dir.join(path);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, contents).unwrap();
+    }
+
+    let raw = RawEmbeddedAssets::new(
+      vec![dir.join("base"), dir.join("overlay")].into(),
+      &AssetOptions::new(PatternKind::Brownfield),
+    )
+    .unwrap();
+    let mut paths = raw
+      .paths
+      .iter()
+      .map(|(_, entry)| entry.path().strip_prefix(&dir).unwrap().to_path_buf())
+      .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+      paths,
+      vec![
+        std::path::PathBuf::from("base/app.js"),
+        std::path::PathBuf::from("overlay/index.html"),
+        std::path::PathBuf::from("overlay/nested/page.html"),
+      ]
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
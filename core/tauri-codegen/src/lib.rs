@@ -58,11 +58,16 @@ pub fn get_config(path: &Path) -> Result<(Config, PathBuf), CodegenConfigError>
   // already unlikely unless the developer goes out of their way to run the cli on a different
   // project than the target crate.
   let mut config = serde_json::from_value(tauri_utils::config::parse::read_from(parent.clone())?)?;
+  tauri_utils::config::parse::apply_profile(
+    &mut config,
+    std::env::var("TAURI_PROFILE").ok().as_deref(),
+  )?;
   if let Ok(env) = std::env::var("TAURI_CONFIG") {
     let merge_config: serde_json::Value =
       serde_json::from_str(&env).map_err(CodegenConfigError::FormatInline)?;
     json_patch::merge(&mut config, &merge_config);
   }
+  tauri_utils::config::parse::interpolate_env(&mut config)?;
 
   let old_cwd = std::env::current_dir().map_err(CodegenConfigError::CurrentDir)?;
   // Set working directory to where `tauri.config.json` is, so that relative paths in it are parsed correctly.
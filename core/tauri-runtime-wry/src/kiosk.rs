@@ -0,0 +1,218 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Kiosk mode implementation.
+//!
+//! The system shortcuts are blocked while at least one kiosk window is open,
+//! so the state is kept on the main thread, where the windows are created and destroyed.
+
+#[cfg(not(target_os = "macos"))]
+use wry::application::window::Fullscreen;
+use wry::application::window::{Window, WindowBuilder as WryWindowBuilder};
+
+/// Configures the window builder for kiosk mode.
+pub fn window_builder(builder: WryWindowBuilder) -> WryWindowBuilder {
+  // on macOS the window covers the screen instead, see `enable`
+  #[cfg(not(target_os = "macos"))]
+  let builder = builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+  builder
+    .with_decorations(false)
+    .with_resizable(false)
+    .with_always_on_top(true)
+}
+
+/// Blocks the system shortcuts while the kiosk window is open, restoring them when dropped.
+#[derive(Debug)]
+pub struct KioskGuard {
+  #[cfg(any(windows, target_os = "macos"))]
+  id: isize,
+}
+
+/// Enters kiosk mode on the window.
+pub fn enable(window: &Window) -> KioskGuard {
+  #[cfg(windows)]
+  let id = win32::enable(window);
+  #[cfg(target_os = "macos")]
+  let id = macos::enable(window);
+  #[cfg(not(any(windows, target_os = "macos")))]
+  let _ = window;
+
+  KioskGuard {
+    #[cfg(any(windows, target_os = "macos"))]
+    id,
+  }
+}
+
+impl Drop for KioskGuard {
+  fn drop(&mut self) {
+    #[cfg(windows)]
+    win32::disable(self.id);
+    #[cfg(target_os = "macos")]
+    macos::disable(self.id);
+  }
+}
+
+#[cfg(windows)]
+mod win32 {
+  use std::cell::RefCell;
+
+  use wry::application::{
+    platform::windows::WindowExtWindows,
+    window::{Fullscreen, Window},
+  };
+
+  use windows::Win32::{
+    Foundation::{HINSTANCE, LPARAM, LRESULT, WPARAM},
+    UI::{
+      Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_ESCAPE, VK_F4, VK_LWIN, VK_RWIN, VK_TAB,
+      },
+      WindowsAndMessaging::{
+        CallNextHookEx, GetForegroundWindow, SetWindowsHookExW, UnhookWindowsHookEx, HHOOK,
+        KBDLLHOOKSTRUCT, LLKHF_ALTDOWN, WH_KEYBOARD_LL,
+      },
+    },
+  };
+
+  #[derive(Default)]
+  struct Kiosk {
+    hook: Option<HHOOK>,
+    windows: Vec<isize>,
+  }
+
+  thread_local! {
+    // the low level keyboard hook runs on the thread that installed it
+    static KIOSK: RefCell<Kiosk> = RefCell::new(Kiosk::default());
+  }
+
+  pub fn enable(window: &Window) -> isize {
+    // exclusive fullscreen on the current display mode of the monitor
+    if let Some(monitor) = window.current_monitor() {
+      let size = monitor.size();
+      if let Some(mode) = monitor
+        .video_modes()
+        .filter(|mode| mode.size() == size)
+        .max_by_key(|mode| (mode.refresh_rate(), mode.bit_depth()))
+      {
+        window.set_fullscreen(Some(Fullscreen::Exclusive(mode)));
+      }
+    }
+
+    let hwnd = window.hwnd();
+    KIOSK.with(|kiosk| {
+      let mut kiosk = kiosk.borrow_mut();
+      kiosk.windows.push(hwnd);
+      if kiosk.hook.is_none() {
+        kiosk.hook = unsafe {
+          SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook), HINSTANCE::default(), 0)
+        }
+        .ok();
+      }
+    });
+    hwnd
+  }
+
+  pub fn disable(hwnd: isize) {
+    KIOSK.with(|kiosk| {
+      let mut kiosk = kiosk.borrow_mut();
+      kiosk.windows.retain(|w| *w != hwnd);
+      if kiosk.windows.is_empty() {
+        if let Some(hook) = kiosk.hook.take() {
+          unsafe { UnhookWindowsHookEx(hook) };
+        }
+      }
+    });
+  }
+
+  /// Whether the key switches, closes or leaves the foreground app.
+  fn is_system_shortcut(info: &KBDLLHOOKSTRUCT) -> bool {
+    let key = VIRTUAL_KEY(info.vkCode as u16);
+    let alt = info.flags.0 & LLKHF_ALTDOWN.0 != 0;
+    let ctrl = unsafe { GetAsyncKeyState(VK_CONTROL.0 as i32) } < 0;
+    key == VK_LWIN
+      || key == VK_RWIN
+      || (alt && (key == VK_TAB || key == VK_F4 || key == VK_ESCAPE))
+      || (ctrl && key == VK_ESCAPE)
+  }
+
+  unsafe extern "system" fn keyboard_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+      let foreground = GetForegroundWindow().0;
+      let is_kiosk_focused = KIOSK
+        .try_with(|kiosk| {
+          kiosk
+            .try_borrow()
+            .map(|kiosk| kiosk.windows.contains(&foreground))
+            .unwrap_or(false)
+        })
+        .unwrap_or(false);
+      if is_kiosk_focused && is_system_shortcut(&*(lparam.0 as *const KBDLLHOOKSTRUCT)) {
+        return LRESULT(1);
+      }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use std::cell::RefCell;
+
+  use wry::application::{platform::macos::WindowExtMacOS, window::Window};
+
+  use cocoa::appkit::{NSApp, NSApplication, NSApplicationPresentationOptions};
+
+  #[derive(Default)]
+  struct Kiosk {
+    presentation_options: Option<NSApplicationPresentationOptions>,
+    windows: Vec<isize>,
+  }
+
+  thread_local! {
+    static KIOSK: RefCell<Kiosk> = RefCell::new(Kiosk::default());
+  }
+
+  pub fn enable(window: &Window) -> isize {
+    // the presentation options cannot be changed on a fullscreen space,
+    // so the window covers the monitor while the Dock and the menu bar are hidden
+    if let Some(monitor) = window.current_monitor() {
+      window.set_outer_position(monitor.position());
+      window.set_inner_size(monitor.size());
+    }
+
+    let ns_window = window.ns_window() as isize;
+    KIOSK.with(|kiosk| {
+      let mut kiosk = kiosk.borrow_mut();
+      kiosk.windows.push(ns_window);
+      if kiosk.presentation_options.is_none() {
+        unsafe {
+          let app = NSApp();
+          kiosk.presentation_options = Some(app.presentationOptions_());
+          app.setPresentationOptions_(
+            NSApplicationPresentationOptions::NSApplicationPresentationHideDock
+              | NSApplicationPresentationOptions::NSApplicationPresentationHideMenuBar
+              | NSApplicationPresentationOptions::NSApplicationPresentationDisableProcessSwitching
+              | NSApplicationPresentationOptions::NSApplicationPresentationDisableForceQuit
+              | NSApplicationPresentationOptions::NSApplicationPresentationDisableSessionTermination
+              | NSApplicationPresentationOptions::NSApplicationPresentationDisableHideApplication
+              | NSApplicationPresentationOptions::NSApplicationPresentationDisableAppleMenu,
+          );
+        }
+      }
+    });
+    ns_window
+  }
+
+  pub fn disable(ns_window: isize) {
+    KIOSK.with(|kiosk| {
+      let mut kiosk = kiosk.borrow_mut();
+      kiosk.windows.retain(|w| *w != ns_window);
+      if kiosk.windows.is_empty() {
+        if let Some(options) = kiosk.presentation_options.take() {
+          unsafe { NSApp().setPresentationOptions_(options) };
+        }
+      }
+    });
+  }
+}
@@ -12,7 +12,8 @@ use tauri_runtime::{
   webview::{WebviewIpcHandler, WindowBuilder, WindowBuilderBase},
   window::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
-    CursorIcon, DetachedWindow, FileDropEvent, JsEventListenerKey, PendingWindow, WindowEvent,
+    CursorIcon, DetachedWindow, DropEvent, FileDropEvent, JsEventListenerKey, PendingWindow,
+    WindowEvent,
   },
   Dispatch, Error, EventLoopProxy, ExitRequestedEventAction, Icon, Result, RunEvent, RunIteration,
   Runtime, RuntimeHandle, UserAttentionType, UserEvent,
@@ -37,6 +38,7 @@ use wry::application::platform::windows::{WindowBuilderExtWindows, WindowExtWind
 #[cfg(target_os = "macos")]
 use tauri_utils::TitleBarStyle;
 use tauri_utils::{config::WindowConfig, debug_eprintln, Theme};
+use url::Url;
 use uuid::Uuid;
 use wry::{
   application::{
@@ -79,7 +81,7 @@ pub use wry::application::platform::macos::{
 };
 
 use std::{
-  cell::RefCell,
+  cell::{Cell, RefCell},
   collections::{
     hash_map::Entry::{Occupied, Vacant},
     HashMap, HashSet,
@@ -95,6 +97,30 @@ use std::{
 };
 
 pub type WebviewId = u64;
+
+/// Overrides the hardware acceleration of all webviews when set to `true` or `false`.
+#[cfg(any(windows, target_os = "linux"))]
+const WEBVIEW_HARDWARE_ACCELERATION_ENV: &str = "TAURI_WEBVIEW_HARDWARE_ACCELERATION";
+#[cfg(windows)]
+const WEBVIEW2_BROWSER_ARGS_ENV: &str = "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS";
+
+thread_local! {
+  /// Whether a webview IPC handler is running on this thread.
+  static IN_IPC_HANDLER: Cell<bool> = Cell::new(false);
+}
+
+/// Whether a webview IPC handler is running on the current thread.
+fn in_ipc_handler() -> bool {
+  IN_IPC_HANDLER.with(Cell::get)
+}
+
+/// Runs `f` as a webview IPC handler, see [`in_ipc_handler`].
+fn run_ipc_handler<F: FnOnce()>(f: F) {
+  let in_ipc_handler = IN_IPC_HANDLER.with(|flag| flag.replace(true));
+  f();
+  IN_IPC_HANDLER.with(|flag| flag.set(in_ipc_handler));
+}
+
 type IpcHandler = dyn Fn(&Window, String) + 'static;
 type FileDropHandler = dyn Fn(&Window, WryFileDropEvent) -> bool + 'static;
 #[cfg(all(desktop, feature = "system-tray"))]
@@ -115,6 +141,10 @@ mod global_shortcut;
 #[cfg(all(desktop, feature = "global-shortcut"))]
 use global_shortcut::*;
 
+mod kiosk;
+use kiosk::KioskGuard;
+mod opacity;
+
 #[cfg(feature = "clipboard")]
 mod clipboard;
 #[cfg(feature = "clipboard")]
@@ -153,8 +183,15 @@ macro_rules! getter {
 
 macro_rules! window_getter {
   ($self: ident, $message: expr) => {{
-    let (tx, rx) = channel();
-    getter!($self, rx, Message::Window($self.window_id, $message(tx)))
+    if current_thread().id() == $self.context.main_thread_id
+      && $self.context.is_window_pending($self.window_id)
+    {
+      // the event loop can't answer before the window is created
+      Err(Error::WindowCreationPending)
+    } else {
+      let (tx, rx) = channel();
+      getter!($self, rx, Message::Window($self.window_id, $message(tx)))
+    }
   }};
 }
 
@@ -162,7 +199,11 @@ pub(crate) fn send_user_message<T: UserEvent>(
   context: &Context<T>,
   message: Message<T>,
 ) -> Result<()> {
-  if current_thread().id() == context.main_thread_id {
+  let window_pending = match &message {
+    Message::Window(id, _) | Message::Webview(id, _) => context.is_window_pending(*id),
+    _ => false,
+  };
+  if current_thread().id() == context.main_thread_id && !window_pending {
     handle_user_message(
       &context.main_thread.window_target,
       message,
@@ -193,6 +234,8 @@ pub struct Context<T: UserEvent> {
   main_thread_id: ThreadId,
   pub proxy: WryEventLoopProxy<Message<T>>,
   main_thread: DispatcherMainThreadContext<T>,
+  /// Windows queued for creation on the event loop by the main thread.
+  pending_windows: Arc<Mutex<HashSet<WebviewId>>>,
 }
 
 impl<T: UserEvent> Context<T> {
@@ -209,6 +252,10 @@ impl<T: UserEvent> Context<T> {
 }
 
 impl<T: UserEvent> Context<T> {
+  fn is_window_pending(&self, window_id: WebviewId) -> bool {
+    self.pending_windows.lock().unwrap().contains(&window_id)
+  }
+
   fn create_webview(&self, pending: PendingWindow<T, Wry<T>>) -> Result<DetachedWindow<T, Wry<T>>> {
     let label = pending.label.clone();
     let menu_ids = pending.menu_ids.clone();
@@ -216,15 +263,27 @@ impl<T: UserEvent> Context<T> {
     let context = self.clone();
     let window_id = rand::random();
 
-    send_user_message(
-      self,
-      Message::CreateWebview(
-        window_id,
-        Box::new(move |event_loop, web_context| {
-          create_webview(window_id, event_loop, web_context, context, pending)
-        }),
-      ),
-    )?;
+    let message = Message::CreateWebview(
+      window_id,
+      Box::new(move |event_loop, web_context| {
+        let webview = create_webview(window_id, event_loop, web_context, context.clone(), pending);
+        context.pending_windows.lock().unwrap().remove(&window_id);
+        webview
+      }),
+    );
+
+    // WebView2 deadlocks when a webview is created inside the IPC handler (e.g. in a synchronous command),
+    // so the window is created on the next event loop iteration instead.
+    // Messages sent to the window from the main thread are queued until then.
+    if current_thread().id() == self.main_thread_id && in_ipc_handler() {
+      self.pending_windows.lock().unwrap().insert(window_id);
+      self
+        .proxy
+        .send_event(message)
+        .map_err(|_| Error::FailedToSendMessage)?;
+    } else {
+      send_user_message(self, message)?;
+    }
 
     let dispatcher = WryDispatcher {
       window_id,
@@ -279,7 +338,7 @@ impl From<&WryRequest<Vec<u8>>> for HttpRequestWrapper {
       method: req.method().clone(),
       headers: req.headers().clone(),
     };
-    Self(HttpRequest::new_internal(parts, req.body().clone()))
+    Self(HttpRequest::from_parts(parts, req.body().clone()))
   }
 }
 
@@ -680,6 +739,7 @@ impl From<CursorIcon> for CursorIconWrapper {
 pub struct WindowBuilderWrapper {
   inner: WryWindowBuilder,
   center: bool,
+  kiosk: bool,
   #[cfg(target_os = "macos")]
   tabbing_identifier: Option<String>,
   menu: Option<Menu>,
@@ -705,6 +765,7 @@ impl WindowBuilder for WindowBuilderWrapper {
       .decorations(config.decorations)
       .maximized(config.maximized)
       .always_on_top(config.always_on_top)
+      .kiosk(config.kiosk)
       .skip_taskbar(config.skip_taskbar)
       .theme(config.theme);
 
@@ -839,6 +900,11 @@ impl WindowBuilder for WindowBuilderWrapper {
     self
   }
 
+  fn kiosk(mut self, kiosk: bool) -> Self {
+    self.kiosk = kiosk;
+    self
+  }
+
   #[cfg(windows)]
   fn parent_window(mut self, parent: HWND) -> Self {
     self.inner = self.inner.with_parent_window(parent);
@@ -1008,6 +1074,7 @@ pub enum WindowMessage {
   #[cfg(desktop)]
   WithWebview(Box<dyn FnOnce(Webview) + Send>),
   AddEventListener(Uuid, Box<dyn Fn(&WindowEvent) + Send>),
+  Drop(DropEvent),
   AddMenuEventListener(Uuid, Box<dyn Fn(&MenuEvent) + Send>),
   // Devtools
   #[cfg(any(debug_assertions, feature = "devtools"))]
@@ -1017,6 +1084,7 @@ pub enum WindowMessage {
   #[cfg(any(debug_assertions, feature = "devtools"))]
   IsDevToolsOpen(Sender<bool>),
   // Getters
+  Url(Sender<Url>),
   ScaleFactor(Sender<f64>),
   InnerPosition(Sender<Result<PhysicalPosition<i32>>>),
   OuterPosition(Sender<Result<PhysicalPosition<i32>>>),
@@ -1070,6 +1138,7 @@ pub enum WindowMessage {
   SetCursorIcon(CursorIcon),
   SetCursorPosition(Position),
   SetIgnoreCursorEvents(bool),
+  SetOpacity(f64),
   DragWindow,
   UpdateMenuItem(u16, MenuUpdate),
   RequestRedraw,
@@ -1182,6 +1251,13 @@ impl<T: UserEvent> Dispatch<T> for WryDispatcher<T> {
     id
   }
 
+  fn dispatch_drop(&self, event: DropEvent) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(self.window_id, WindowMessage::Drop(event)),
+    )
+  }
+
   fn on_menu_event<F: Fn(&MenuEvent) + Send + 'static>(&self, f: F) -> Uuid {
     let id = Uuid::new_v4();
     let _ = self.context.proxy.send_event(Message::Window(
@@ -1215,6 +1291,10 @@ impl<T: UserEvent> Dispatch<T> for WryDispatcher<T> {
 
   // Getters
 
+  fn url(&self) -> Result<Url> {
+    window_getter!(self, WindowMessage::Url)
+  }
+
   fn scale_factor(&self) -> Result<f64> {
     window_getter!(self, WindowMessage::ScaleFactor)
   }
@@ -1325,7 +1405,6 @@ impl<T: UserEvent> Dispatch<T> for WryDispatcher<T> {
   }
 
   // Creates a window by dispatching a message to the event loop.
-  // Note that this must be called from a separate thread, otherwise the channel will introduce a deadlock.
   fn create_window(
     &mut self,
     pending: PendingWindow<T, Self::Runtime>,
@@ -1523,6 +1602,13 @@ impl<T: UserEvent> Dispatch<T> for WryDispatcher<T> {
     )
   }
 
+  fn set_opacity(&self, opacity: f64) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(self.window_id, WindowMessage::SetOpacity(opacity)),
+    )
+  }
+
   fn start_dragging(&self) -> Result<()> {
     send_user_message(
       &self.context,
@@ -1607,6 +1693,7 @@ pub struct WindowWrapper {
   menu_items: Option<HashMap<u16, WryCustomMenuItem>>,
   window_event_listeners: WindowEventListeners,
   menu_event_listeners: WindowMenuEventListeners,
+  kiosk: Option<KioskGuard>,
 }
 
 impl fmt::Debug for WindowWrapper {
@@ -1758,7 +1845,6 @@ impl<T: UserEvent> RuntimeHandle<T> for WryHandle<T> {
   }
 
   // Creates a window by dispatching a message to the event loop.
-  // Note that this must be called from a separate thread, otherwise the channel will introduce a deadlock.
   fn create_window(
     &self,
     pending: PendingWindow<T, Self::Runtime>,
@@ -1842,6 +1928,7 @@ impl<T: UserEvent> Wry<T> {
         #[cfg(all(desktop, feature = "system-tray"))]
         system_tray_manager,
       },
+      pending_windows: Default::default(),
     };
 
     #[cfg(all(desktop, feature = "global-shortcut"))]
@@ -2290,6 +2377,13 @@ fn handle_user_message<T: UserEvent>(
               window_event_listeners.lock().unwrap().insert(id, listener);
             }
 
+            WindowMessage::Drop(event) => {
+              let event = WindowEvent::Drop(event);
+              for listener in window_event_listeners.lock().unwrap().values() {
+                listener(&event);
+              }
+            }
+
             WindowMessage::AddMenuEventListener(id, listener) => {
               menu_event_listeners.lock().unwrap().insert(id, listener);
             }
@@ -2315,6 +2409,11 @@ fn handle_user_message<T: UserEvent>(
               }
             }
             // Getters
+            WindowMessage::Url(tx) => {
+              if let WindowHandle::Webview { inner: w, .. } = &window {
+                tx.send(w.url()).unwrap();
+              }
+            }
             WindowMessage::ScaleFactor(tx) => tx.send(window.scale_factor()).unwrap(),
             WindowMessage::InnerPosition(tx) => tx
               .send(
@@ -2432,6 +2531,7 @@ fn handle_user_message<T: UserEvent>(
             WindowMessage::SetIgnoreCursorEvents(ignore) => {
               let _ = window.set_ignore_cursor_events(ignore);
             }
+            WindowMessage::SetOpacity(opacity) => opacity::set(&window, opacity),
             WindowMessage::DragWindow => {
               let _ = window.drag_window();
             }
@@ -2501,6 +2601,7 @@ fn handle_user_message<T: UserEvent>(
             menu_items: Default::default(),
             window_event_listeners: Default::default(),
             menu_event_listeners: Default::default(),
+            kiosk: None,
           },
         );
         sender.send(Ok(Arc::downgrade(&w))).unwrap();
@@ -2943,6 +3044,7 @@ fn create_webview<T: UserEvent>(
     uri_scheme_protocols,
     mut window_builder,
     ipc_handler,
+    navigation_handler,
     label,
     url,
     menu_ids,
@@ -2972,6 +3074,10 @@ fn create_webview<T: UserEvent>(
     }
   }
 
+  if window_builder.kiosk {
+    window_builder.inner = kiosk::window_builder(window_builder.inner);
+  }
+
   let is_window_transparent = window_builder.inner.window.transparent;
   let menu_items = if let Some(menu) = window_builder.menu {
     let mut menu_items = HashMap::new();
@@ -2988,6 +3094,11 @@ fn create_webview<T: UserEvent>(
   if window_builder.center {
     let _ = center_window(&window, window.inner_size());
   }
+  let kiosk = if window_builder.kiosk {
+    Some(kiosk::enable(&window))
+  } else {
+    None
+  };
   let mut webview_builder = WebViewBuilder::new(window)
     .map_err(|e| Error::CreateWebview(Box::new(e)))?
     .with_url(&url)
@@ -3001,6 +3112,13 @@ fn create_webview<T: UserEvent>(
   if let Some(user_agent) = webview_attributes.user_agent {
     webview_builder = webview_builder.with_user_agent(&user_agent);
   }
+  if let Some(navigation_handler) = navigation_handler {
+    webview_builder = webview_builder.with_navigation_handler(move |url| {
+      Url::parse(&url)
+        .map(|url| navigation_handler(url))
+        .unwrap_or(true)
+    });
+  }
   if let Some(handler) = ipc_handler {
     webview_builder = webview_builder.with_ipc_handler(create_ipc_handler(
       context,
@@ -3051,15 +3169,61 @@ fn create_webview<T: UserEvent>(
     webview_builder.webview.clipboard = true;
   }
 
+  #[cfg(any(windows, target_os = "linux"))]
+  let hardware_acceleration = match std::env::var(WEBVIEW_HARDWARE_ACCELERATION_ENV).as_deref() {
+    Ok("true") | Ok("1") => true,
+    Ok("false") | Ok("0") => false,
+    _ => webview_attributes.hardware_acceleration,
+  };
+  #[cfg(windows)]
+  let browser_args = webview2_browser_args(
+    hardware_acceleration,
+    webview_attributes.gpu_compositing,
+    webview_attributes.webgl,
+  );
+  // the WebView2 environment is created when building the webview, reading the arguments from the environment variable
+  #[cfg(windows)]
+  let previous_browser_args = std::env::var_os(WEBVIEW2_BROWSER_ARGS_ENV);
+  #[cfg(windows)]
+  if !browser_args.is_empty() {
+    let mut args = previous_browser_args.clone().unwrap_or_default();
+    if !args.is_empty() {
+      args.push(" ");
+    }
+    args.push(browser_args.join(" "));
+    std::env::set_var(WEBVIEW2_BROWSER_ARGS_ENV, args);
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   {
     webview_builder = webview_builder.with_devtools(true);
   }
 
-  let webview = webview_builder
-    .with_web_context(web_context)
-    .build()
-    .map_err(|e| Error::CreateWebview(Box::new(e)))?;
+  let webview = webview_builder.with_web_context(web_context).build();
+
+  #[cfg(windows)]
+  if !browser_args.is_empty() {
+    match previous_browser_args {
+      Some(args) => std::env::set_var(WEBVIEW2_BROWSER_ARGS_ENV, args),
+      None => std::env::remove_var(WEBVIEW2_BROWSER_ARGS_ENV),
+    }
+  }
+
+  let webview = webview.map_err(|e| Error::CreateWebview(Box::new(e)))?;
+
+  #[cfg(target_os = "linux")]
+  {
+    use webkit2gtk::{HardwareAccelerationPolicy, SettingsExt, WebViewExt};
+    use wry::webview::WebviewExtUnix;
+    if let Some(settings) = webview.webview().settings() {
+      if !(hardware_acceleration && webview_attributes.gpu_compositing) {
+        settings.set_hardware_acceleration_policy(HardwareAccelerationPolicy::Never);
+      }
+      if !webview_attributes.webgl {
+        settings.set_enable_webgl(false);
+      }
+    }
+  }
 
   #[cfg(windows)]
   {
@@ -3108,9 +3272,30 @@ fn create_webview<T: UserEvent>(
     menu_items,
     window_event_listeners,
     menu_event_listeners: Default::default(),
+    kiosk,
   })
 }
 
+/// The arguments of the WebView2 browser process disabling the GPU features.
+#[cfg(windows)]
+fn webview2_browser_args(
+  hardware_acceleration: bool,
+  gpu_compositing: bool,
+  webgl: bool,
+) -> Vec<&'static str> {
+  let mut args = Vec::new();
+  if !hardware_acceleration {
+    args.push("--disable-gpu");
+  }
+  if !gpu_compositing {
+    args.push("--disable-gpu-compositing");
+  }
+  if !webgl {
+    args.push("--disable-webgl");
+  }
+  args
+}
+
 /// Create a wry ipc handler from a tauri ipc handler.
 fn create_ipc_handler<T: UserEvent>(
   context: Context<T>,
@@ -3121,18 +3306,20 @@ fn create_ipc_handler<T: UserEvent>(
 ) -> Box<IpcHandler> {
   Box::new(move |window, request| {
     let window_id = context.webview_id_map.get(&window.id()).unwrap();
-    handler(
-      DetachedWindow {
-        dispatcher: WryDispatcher {
-          window_id,
-          context: context.clone(),
+    run_ipc_handler(|| {
+      handler(
+        DetachedWindow {
+          dispatcher: WryDispatcher {
+            window_id,
+            context: context.clone(),
+          },
+          label: label.clone(),
+          menu_ids: menu_ids.clone(),
+          js_event_listeners: js_event_listeners.clone(),
         },
-        label: label.clone(),
-        menu_ids: menu_ids.clone(),
-        js_event_listeners: js_event_listeners.clone(),
-      },
-      request,
-    );
+        request,
+      )
+    });
   })
 }
 
@@ -3151,3 +3338,38 @@ fn create_file_drop_handler(window_event_listeners: WindowEventListeners) -> Box
     has_listener
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{in_ipc_handler, run_ipc_handler};
+
+  #[test]
+  fn ipc_handler_flag() {
+    assert!(!in_ipc_handler());
+    run_ipc_handler(|| {
+      assert!(in_ipc_handler());
+      // nested handlers keep the flag of the outer handler
+      run_ipc_handler(|| assert!(in_ipc_handler()));
+      assert!(in_ipc_handler());
+      // the flag is per thread, so windows created from other threads aren't deferred
+      std::thread::spawn(|| assert!(!in_ipc_handler()))
+        .join()
+        .unwrap();
+    });
+    assert!(!in_ipc_handler());
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn webview2_browser_args() {
+    assert!(super::webview2_browser_args(true, true, true).is_empty());
+    assert_eq!(
+      super::webview2_browser_args(false, true, false),
+      vec!["--disable-gpu", "--disable-webgl"]
+    );
+    assert_eq!(
+      super::webview2_browser_args(true, false, true),
+      vec!["--disable-gpu-compositing"]
+    );
+  }
+}
@@ -0,0 +1,74 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Window opacity implementation.
+//!
+//! The window is composited with its webview, so the whole window fades without requiring a transparent window.
+
+use wry::application::window::Window;
+
+/// Sets the opacity of the window, clamped between `0.0` (transparent) and `1.0` (opaque).
+pub fn set(window: &Window, opacity: f64) {
+  let opacity = opacity.clamp(0.0, 1.0);
+
+  #[cfg(windows)]
+  win32::set(window, opacity);
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::{appkit::NSWindow, base::id};
+    use wry::application::platform::macos::WindowExtMacOS;
+    (window.ns_window() as id).setAlphaValue_(opacity);
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::WidgetExt;
+    use wry::application::platform::unix::WindowExtUnix;
+    // requires a compositing window manager
+    window.gtk_window().set_opacity(opacity);
+  }
+
+  #[cfg(not(any(
+    windows,
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  let _ = (window, opacity);
+}
+
+#[cfg(windows)]
+mod win32 {
+  use wry::application::{platform::windows::WindowExtWindows, window::Window};
+
+  use windows::Win32::{
+    Foundation::HWND,
+    UI::WindowsAndMessaging::{
+      GetWindowLongW, SetLayeredWindowAttributes, SetWindowLongW, GWL_EXSTYLE, LWA_ALPHA,
+      WS_EX_LAYERED,
+    },
+  };
+
+  pub fn set(window: &Window, opacity: f64) {
+    let hwnd = HWND(window.hwnd());
+    unsafe {
+      // the alpha of the window can only be set on layered windows
+      let style = GetWindowLongW(hwnd, GWL_EXSTYLE);
+      if style & WS_EX_LAYERED.0 as i32 == 0 {
+        SetWindowLongW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as i32);
+      }
+      SetLayeredWindowAttributes(hwnd, 0, (opacity * 255.0).round() as u8, LWA_ALPHA);
+    }
+  }
+}
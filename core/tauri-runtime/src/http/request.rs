@@ -49,13 +49,18 @@ impl Request {
 
   /// Creates a new `Request` with the given head and body.
   ///
-  /// # Stability
-  ///
-  /// This API is used internally. It may have breaking changes in the future.
+  /// Runtimes use it to forward the requests made by the webview to the custom protocol handlers.
+  #[inline]
+  pub fn from_parts(head: RequestParts, body: Vec<u8>) -> Request {
+    Request { head, body }
+  }
+
+  /// Creates a new `Request` with the given head and body.
   #[inline]
   #[doc(hidden)]
+  #[deprecated(since = "0.13.0", note = "use `Request::from_parts` instead")]
   pub fn new_internal(head: RequestParts, body: Vec<u8>) -> Request {
-    Request { head, body }
+    Self::from_parts(head, body)
   }
 
   /// Returns a reference to the associated HTTP method.
@@ -83,10 +88,6 @@ impl Request {
   }
 
   /// Consumes the request returning the head and body RequestParts.
-  ///
-  /// # Stability
-  ///
-  /// This API is used internally. It may have breaking changes in the future.
   #[inline]
   pub fn into_parts(self) -> (RequestParts, Vec<u8>) {
     (self.head, self.body)
@@ -130,3 +131,31 @@ impl fmt::Debug for RequestParts {
       .finish()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{Method, Request, RequestParts};
+
+  #[test]
+  fn from_parts() {
+    let mut headers = super::HeaderMap::new();
+    headers.insert("Accept", super::HeaderValue::from_static("text/html"));
+    let request = Request::from_parts(
+      RequestParts {
+        method: Method::POST,
+        uri: "tauri://localhost/index.html".into(),
+        headers,
+      },
+      b"body".to_vec(),
+    );
+    assert_eq!(request.method(), &Method::POST);
+    assert_eq!(request.uri(), "tauri://localhost/index.html");
+    assert_eq!(request.headers()["Accept"], "text/html");
+
+    let (head, body) = request.into_parts();
+    #[allow(deprecated)]
+    let request = Request::new_internal(head, body);
+    assert_eq!(request.uri(), "tauri://localhost/index.html");
+    assert_eq!(request.body(), b"body");
+  }
+}
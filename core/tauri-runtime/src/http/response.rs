@@ -76,11 +76,8 @@ impl Response {
 
   /// Consumes the response returning the head and body ResponseParts.
   ///
-  /// # Stability
-  ///
-  /// This API is used internally. It may have breaking changes in the future.
+  /// Runtimes use it to send the response of a custom protocol handler to the webview.
   #[inline]
-  #[doc(hidden)]
   pub fn into_parts(self) -> (ResponseParts, Vec<u8>) {
     (self.head, self.body)
   }
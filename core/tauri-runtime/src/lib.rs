@@ -10,6 +10,7 @@ use raw_window_handle::RawDisplayHandle;
 use serde::Deserialize;
 use std::{fmt::Debug, sync::mpsc::Sender};
 use tauri_utils::Theme;
+use url::Url;
 use uuid::Uuid;
 
 pub mod http;
@@ -193,6 +194,9 @@ pub enum Error {
   /// Failed to create window.
   #[error("failed to create window")]
   CreateWindow,
+  /// The window is queued for creation on the next event loop iteration, so it can't be queried yet.
+  #[error("the window has not been created yet")]
+  WindowCreationPending,
   /// The given window label is invalid.
   #[error("Window labels must only include alphanumeric characters, `-`, `/`, `:` and `_`.")]
   InvalidWindowLabel,
@@ -483,6 +487,9 @@ pub trait Dispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 'static
   /// Registers a window event handler.
   fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> Uuid;
 
+  /// Sends the drag and drop event of the webview to the window event handlers as a [`WindowEvent::Drop`].
+  fn dispatch_drop(&self, event: window::DropEvent) -> Result<()>;
+
   /// Registers a window event handler.
   fn on_menu_event<F: Fn(&window::MenuEvent) + Send + 'static>(&self, f: F) -> Uuid;
 
@@ -500,6 +507,9 @@ pub trait Dispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 'static
 
   // GETTERS
 
+  /// Returns the URL currently loaded on the webview.
+  fn url(&self) -> Result<Url>;
+
   /// Returns the scale factor that can be used to map logical pixels to physical pixels, and vice versa.
   fn scale_factor(&self) -> Result<f64>;
 
@@ -667,12 +677,25 @@ pub trait Dispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 'static
   /// Ignores the window cursor events.
   fn set_ignore_cursor_events(&self, ignore: bool) -> Result<()>;
 
+  /// Updates the opacity of the window and its webview, from `0.0` (transparent) to `1.0` (opaque).
+  fn set_opacity(&self, opacity: f64) -> Result<()>;
+
   /// Starts dragging the window.
   fn start_dragging(&self) -> Result<()>;
 
   /// Executes javascript on the window this [`Dispatch`] represents.
   fn eval_script<S: Into<String>>(&self, script: S) -> Result<()>;
 
+  /// Called after an event is emitted to the JavaScript listeners of the window this [`Dispatch`] represents,
+  /// so the runtime can record it. Does nothing by default.
+  fn on_event_emitted(
+    &self,
+    _event: &str,
+    _source_window_label: Option<&str>,
+    _payload: &serde_json::Value,
+  ) {
+  }
+
   /// Applies the specified `update` to the menu item associated with the given `id`.
   fn update_menu_item(&self, id: u16, update: menu::MenuUpdate) -> Result<()>;
 }
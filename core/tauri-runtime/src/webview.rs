@@ -9,7 +9,7 @@ use crate::{menu::Menu, window::DetachedWindow, Icon};
 #[cfg(target_os = "macos")]
 use tauri_utils::TitleBarStyle;
 use tauri_utils::{
-  config::{WindowConfig, WindowUrl},
+  config::{Csp, WindowConfig, WindowUrl},
   Theme,
 };
 
@@ -28,6 +28,13 @@ pub struct WebviewAttributes {
   pub file_drop_handler_enabled: bool,
   pub clipboard: bool,
   pub accept_first_mouse: bool,
+  /// The Content Security Policy of the HTML files served to the webview, overriding the app policy.
+  pub csp: Option<Csp>,
+  pub hardware_acceleration: bool,
+  pub gpu_compositing: bool,
+  pub webgl: bool,
+  /// The MIME types of the data other than files that can be dropped on the webview.
+  pub accept_drop_types: Vec<String>,
 }
 
 impl WebviewAttributes {
@@ -41,6 +48,11 @@ impl WebviewAttributes {
       file_drop_handler_enabled: true,
       clipboard: false,
       accept_first_mouse: false,
+      csp: None,
+      hardware_acceleration: true,
+      gpu_compositing: true,
+      webgl: true,
+      accept_drop_types: Vec::new(),
     }
   }
 
@@ -88,6 +100,43 @@ impl WebviewAttributes {
     self.accept_first_mouse = accept;
     self
   }
+
+  /// Sets the Content Security Policy of the HTML files served to the webview.
+  #[must_use]
+  pub fn csp(mut self, csp: Csp) -> Self {
+    self.csp.replace(csp);
+    self
+  }
+
+  /// Sets whether the webview renders with the GPU.
+  ///
+  /// Overridden by the `TAURI_WEBVIEW_HARDWARE_ACCELERATION` environment variable.
+  #[must_use]
+  pub fn hardware_acceleration(mut self, enabled: bool) -> Self {
+    self.hardware_acceleration = enabled;
+    self
+  }
+
+  /// Sets whether the webview composites its layers with the GPU.
+  #[must_use]
+  pub fn gpu_compositing(mut self, enabled: bool) -> Self {
+    self.gpu_compositing = enabled;
+    self
+  }
+
+  /// Sets whether WebGL is available to the webview.
+  #[must_use]
+  pub fn webgl(mut self, enabled: bool) -> Self {
+    self.webgl = enabled;
+    self
+  }
+
+  /// Sets the MIME types of the data other than files that can be dropped on the webview, e.g. `text/uri-list` or `text/*`.
+  #[must_use]
+  pub fn accept_drop_types(mut self, types: Vec<String>) -> Self {
+    self.accept_drop_types = types;
+    self
+  }
 }
 
 /// Do **NOT** implement this trait except for use in a custom [`Runtime`](crate::Runtime).
@@ -172,6 +221,11 @@ pub trait WindowBuilder: WindowBuilderBase {
   #[must_use]
   fn always_on_top(self, always_on_top: bool) -> Self;
 
+  /// Whether the window runs in kiosk mode: fullscreen, always on top, without decorations
+  /// and with the system shortcuts switching or closing the app blocked where the platform allows it.
+  #[must_use]
+  fn kiosk(self, kiosk: bool) -> Self;
+
   /// Sets the window icon.
   fn icon(self, icon: Icon) -> crate::Result<Self>;
 
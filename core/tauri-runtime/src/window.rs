@@ -12,6 +12,7 @@ use crate::{
 };
 use serde::{Deserialize, Deserializer, Serialize};
 use tauri_utils::{config::WindowConfig, Theme};
+use url::Url;
 
 use std::{
   collections::{HashMap, HashSet},
@@ -23,6 +24,9 @@ use std::{
 type UriSchemeProtocol =
   dyn Fn(&HttpRequest) -> Result<HttpResponse, Box<dyn std::error::Error>> + Send + Sync + 'static;
 
+/// A closure that decides whether the webview can navigate to the given URL.
+pub type NavigationHandler = dyn Fn(Url) -> bool + Send;
+
 /// UI scaling utilities.
 pub mod dpi;
 
@@ -59,6 +63,8 @@ pub enum WindowEvent {
   },
   /// An event associated with the file drop action.
   FileDrop(FileDropEvent),
+  /// Data other than files has been dragged over or dropped on the webview.
+  Drop(DropEvent),
   /// The system window theme has changed.
   ///
   /// Applications might wish to react to this to change the theme of the content of the window when the system changes the window theme.
@@ -77,6 +83,64 @@ pub enum FileDropEvent {
   Cancelled,
 }
 
+/// A drag and drop of data other than files on the webview.
+///
+/// The events are reported by a script on the page, so their data must be treated like any other input from the webview.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DropEvent {
+  /// A drag carrying data of the given MIME types entered the webview.
+  /// The data itself is only available when it is dropped.
+  Hovered {
+    /// The position of the cursor, relative to the webview.
+    position: dpi::LogicalPosition<f64>,
+    /// The MIME types of the dragged data.
+    types: Vec<String>,
+  },
+  /// The data has been dropped on the webview.
+  Dropped(DropData),
+}
+
+/// The data dropped on the webview, such as text and URLs dragged from other apps.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DropData {
+  /// The position of the cursor, relative to the webview.
+  pub position: dpi::LogicalPosition<f64>,
+  /// The dropped data by MIME type, e.g. `text/plain`, `text/uri-list` or a custom type.
+  pub items: HashMap<String, String>,
+}
+
+impl DropData {
+  /// The data of the MIME type.
+  pub fn get(&self, mime_type: &str) -> Option<&str> {
+    self.items.get(mime_type).map(String::as_str)
+  }
+
+  /// The dropped text.
+  pub fn text(&self) -> Option<&str> {
+    self.get("text/plain")
+  }
+
+  /// The dropped HTML.
+  pub fn html(&self) -> Option<&str> {
+    self.get("text/html")
+  }
+
+  /// The dropped URLs, skipping the comments of the `text/uri-list` data.
+  pub fn urls(&self) -> Vec<&str> {
+    self
+      .get("text/uri-list")
+      .map(|list| {
+        list
+          .lines()
+          .map(str::trim)
+          .filter(|line| !line.is_empty() && !line.starts_with('#'))
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+}
+
 /// A menu event.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -224,6 +288,9 @@ pub struct PendingWindow<T: UserEvent, R: Runtime<T>> {
   /// How to handle IPC calls on the webview window.
   pub ipc_handler: Option<WebviewIpcHandler<T, R>>,
 
+  /// Decides whether the webview can navigate to a URL.
+  pub navigation_handler: Option<Box<NavigationHandler>>,
+
   /// The resolved URL to load on the webview.
   pub url: String,
 
@@ -268,6 +335,7 @@ impl<T: UserEvent, R: Runtime<T>> PendingWindow<T, R> {
         uri_scheme_protocols: Default::default(),
         label,
         ipc_handler: None,
+        navigation_handler: None,
         url: "tauri://localhost".to_string(),
         menu_ids: Arc::new(Mutex::new(menu_ids)),
         js_event_listeners: Default::default(),
@@ -297,6 +365,7 @@ impl<T: UserEvent, R: Runtime<T>> PendingWindow<T, R> {
         uri_scheme_protocols: Default::default(),
         label,
         ipc_handler: None,
+        navigation_handler: None,
         url: "tauri://localhost".to_string(),
         menu_ids: Arc::new(Mutex::new(menu_ids)),
         js_event_listeners: Default::default(),
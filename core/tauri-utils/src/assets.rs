@@ -7,11 +7,21 @@
 
 #[doc(hidden)]
 pub use phf;
+#[cfg(feature = "compression")]
+use std::sync::Mutex;
 use std::{
   borrow::Cow,
   path::{Component, Path},
+  sync::Arc,
 };
 
+#[cfg(feature = "compression")]
+use once_cell::sync::OnceCell;
+
+/// The maximum size in bytes of the decompressed assets cached by [`EmbeddedAssets`].
+#[cfg(feature = "compression")]
+const DECOMPRESSED_CACHE_CAPACITY: usize = 4 * 1024 * 1024;
+
 /// Represent an asset file path in a normalized way.
 ///
 /// The following rules are enforced and added if needed:
@@ -109,11 +119,75 @@ pub trait Assets: Send + Sync + 'static {
   /// Get the content of the passed [`AssetKey`].
   fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>>;
 
+  /// Get the content of the passed [`AssetKey`] compressed with Brotli,
+  /// so it can be served with `Content-Encoding: br` without being decompressed.
+  ///
+  /// Returns `None` by default, meaning the assets are not stored compressed.
+  fn get_brotli(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    let _ = key;
+    None
+  }
+
+  /// Get the content of the passed [`AssetKey`] as bytes shared with a cache of the assets,
+  /// so it can be read without being copied.
+  ///
+  /// Returns `None` by default, meaning the assets are not cached and are read with [`Self::get`].
+  fn get_shared(&self, key: &AssetKey) -> Option<Arc<[u8]>> {
+    let _ = key;
+    None
+  }
+
   /// Gets the hashes for the CSP tag of the HTML on the given path.
   fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_>;
 }
 
+/// A least recently used cache of decompressed assets, bounded by their total size.
+#[cfg(feature = "compression")]
+#[derive(Debug)]
+struct DecompressedCache {
+  // ordered from the least to the most recently used
+  entries: Vec<(&'static str, Arc<[u8]>)>,
+  size: usize,
+  capacity: usize,
+}
+
+#[cfg(feature = "compression")]
+impl DecompressedCache {
+  fn new(capacity: usize) -> Self {
+    Self {
+      entries: Vec::new(),
+      size: 0,
+      capacity,
+    }
+  }
+
+  fn get(&mut self, key: &str) -> Option<Arc<[u8]>> {
+    let index = self.entries.iter().position(|(k, _)| *k == key)?;
+    let entry = self.entries.remove(index);
+    let bytes = entry.1.clone();
+    self.entries.push(entry);
+    Some(bytes)
+  }
+
+  fn insert(&mut self, key: &'static str, bytes: Arc<[u8]>) {
+    // large assets are decompressed on every access so they do not evict everything else
+    if bytes.len() > self.capacity / 8 {
+      return;
+    }
+    self.size += bytes.len();
+    self.entries.push((key, bytes));
+    while self.size > self.capacity {
+      let (_, evicted) = self.entries.remove(0);
+      self.size -= evicted.len();
+    }
+  }
+}
+
 /// [`Assets`] implementation that only contains compile-time compressed and embedded assets.
+///
+/// The assets are read from the binary on demand. With the `compression` feature,
+/// they are decompressed on each access and the recently used small assets are kept in a bounded cache,
+/// shared with the callers of [`Assets::get_shared`].
 #[derive(Debug)]
 pub struct EmbeddedAssets {
   assets: phf::Map<&'static str, &'static [u8]>,
@@ -121,6 +195,8 @@ pub struct EmbeddedAssets {
   global_hashes: &'static [CspHash<'static>],
   // Hashes that are associated to the CSP of the HTML file identified by the map key (the HTML asset key).
   html_hashes: phf::Map<&'static str, &'static [CspHash<'static>]>,
+  #[cfg(feature = "compression")]
+  cache: OnceCell<Mutex<DecompressedCache>>,
 }
 
 impl EmbeddedAssets {
@@ -134,33 +210,54 @@ impl EmbeddedAssets {
       assets: map,
       global_hashes,
       html_hashes,
+      #[cfg(feature = "compression")]
+      cache: OnceCell::new(),
     }
   }
 }
 
+#[cfg(feature = "compression")]
+fn decompress(mut asdf: &[u8]) -> Option<Vec<u8>> {
+  // with the exception of extremely small files, output should usually be
+  // at least as large as the compressed version.
+  let mut buf = Vec::with_capacity(asdf.len());
+  brotli::BrotliDecompress(&mut asdf, &mut buf).ok()?;
+  Some(buf)
+}
+
 impl Assets for EmbeddedAssets {
   #[cfg(feature = "compression")]
   fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
     self
       .assets
       .get(key.as_ref())
-      .map(|&(mut asdf)| {
-        // with the exception of extremely small files, output should usually be
-        // at least as large as the compressed version.
-        let mut buf = Vec::with_capacity(asdf.len());
-        brotli::BrotliDecompress(&mut asdf, &mut buf).map(|()| buf)
-      })
-      .and_then(Result::ok)
+      .and_then(|&asdf| decompress(asdf))
       .map(Cow::Owned)
   }
 
   #[cfg(not(feature = "compression"))]
   fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
-    self
-      .assets
-      .get(key.as_ref())
-      .copied()
-      .map(|a| Cow::Owned(a.to_vec()))
+    self.assets.get(key.as_ref()).map(|&a| Cow::Borrowed(a))
+  }
+
+  #[cfg(feature = "compression")]
+  fn get_brotli(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    self.assets.get(key.as_ref()).map(|&a| Cow::Borrowed(a))
+  }
+
+  #[cfg(feature = "compression")]
+  fn get_shared(&self, key: &AssetKey) -> Option<Arc<[u8]>> {
+    let (&key, &compressed) = self.assets.get_entry(key.as_ref())?;
+    let cache = self
+      .cache
+      .get_or_init(|| Mutex::new(DecompressedCache::new(DECOMPRESSED_CACHE_CAPACITY)));
+    if let Some(bytes) = cache.lock().unwrap().get(key) {
+      return Some(bytes);
+    }
+
+    let bytes: Arc<[u8]> = decompress(compressed)?.into();
+    cache.lock().unwrap().insert(key, bytes.clone());
+    Some(bytes)
   }
 
   fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
@@ -181,3 +278,28 @@ impl Assets for EmbeddedAssets {
     )
   }
 }
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+  use super::DecompressedCache;
+
+  #[test]
+  fn decompressed_cache_evicts_least_recently_used() {
+    let mut cache = DecompressedCache::new(80);
+    cache.insert("/a.js", vec![0; 10].into());
+    cache.insert("/b.js", vec![0; 10].into());
+    // too large to be cached
+    cache.insert("/c.js", vec![0; 11].into());
+    assert!(cache.get("/c.js").is_none());
+
+    assert!(cache.get("/a.js").is_some());
+    for key in [
+      "/d.js", "/e.js", "/f.js", "/g.js", "/h.js", "/i.js", "/j.js",
+    ] {
+      cache.insert(key, vec![0; 10].into());
+    }
+    assert_eq!(cache.size, 80);
+    assert!(cache.get("/a.js").is_some());
+    assert!(cache.get("/b.js").is_none());
+  }
+}
@@ -6,6 +6,9 @@
 //!
 //! It is pulled from a `tauri.conf.json` file and the [`Config`] struct is generated at compile time.
 //!
+//! String values can reference environment variables with `${NAME}` or `${NAME:-default}`,
+//! resolved at build time. See [`parse::interpolate_env`].
+//!
 //! # Stability
 //! This is a core functionality that is not considered part of the stable API.
 //! If you use it, note that it may include breaking changes in the future.
@@ -27,7 +30,7 @@ use std::{
   collections::HashMap,
   fmt::{self, Display},
   fs::read_to_string,
-  path::PathBuf,
+  path::{Path, PathBuf},
   str::FromStr,
 };
 
@@ -78,12 +81,20 @@ pub enum BundleType {
   AppImage,
   /// The Microsoft Installer bundle (.msi).
   Msi,
+  /// The NSIS installer bundle (.exe).
+  Nsis,
   /// The macOS application bundle (.app).
   App,
   /// The Apple Disk Image bundle (.dmg).
   Dmg,
   /// The Tauri updater bundle.
   Updater,
+  /// The Android application package (.apk).
+  Apk,
+  /// The Android App Bundle (.aab), the format published to the Play Store.
+  Aab,
+  /// The iOS app archive (.ipa), installable on the devices of its provisioning profile. It is not built by Xcode, so it can't be submitted to the App Store.
+  Ipa,
 }
 
 impl Display for BundleType {
@@ -95,9 +106,13 @@ impl Display for BundleType {
         Self::Deb => "deb",
         Self::AppImage => "appimage",
         Self::Msi => "msi",
+        Self::Nsis => "nsis",
         Self::App => "app",
         Self::Dmg => "dmg",
         Self::Updater => "updater",
+        Self::Apk => "apk",
+        Self::Aab => "aab",
+        Self::Ipa => "ipa",
       }
     )
   }
@@ -122,9 +137,13 @@ impl<'de> Deserialize<'de> for BundleType {
       "deb" => Ok(Self::Deb),
       "appimage" => Ok(Self::AppImage),
       "msi" => Ok(Self::Msi),
+      "nsis" => Ok(Self::Nsis),
       "app" => Ok(Self::App),
       "dmg" => Ok(Self::Dmg),
       "updater" => Ok(Self::Updater),
+      "apk" => Ok(Self::Apk),
+      "aab" => Ok(Self::Aab),
+      "ipa" => Ok(Self::Ipa),
       _ => Err(DeError::custom(format!("unknown bundle target '{}'", s))),
     }
   }
@@ -333,6 +352,43 @@ fn minimum_system_version() -> Option<String> {
   Some("10.13".into())
 }
 
+/// Configuration for the iOS bundles.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosConfig {
+  /// The minimum iOS version that the application supports. Defaults to `13.0`.
+  #[serde(
+    default = "ios_minimum_system_version",
+    alias = "minimum-system-version"
+  )]
+  pub minimum_system_version: String,
+  /// Identity to use for code signing, e.g. `Apple Development: My Name (TEAMID)`. The IPA is not signed if not set.
+  #[serde(alias = "signing-identity")]
+  pub signing_identity: Option<String>,
+  /// Path to the provisioning profile embedded in the application.
+  ///
+  /// The profile lists the devices allowed to install the app and grants its entitlements,
+  /// so it must match the signing identity and the bundle identifier.
+  #[serde(alias = "provisioning-profile")]
+  pub provisioning_profile: Option<PathBuf>,
+}
+
+impl Default for IosConfig {
+  fn default() -> Self {
+    Self {
+      minimum_system_version: ios_minimum_system_version(),
+      signing_identity: None,
+      provisioning_profile: None,
+    }
+  }
+}
+
+fn ios_minimum_system_version() -> String {
+  "13.0".into()
+}
+
 /// Configuration for a target language for the WiX build.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
@@ -414,6 +470,53 @@ pub struct WixConfig {
   /// The required dimensions are 493px × 312px.
   #[serde(alias = "dialog-image-path")]
   pub dialog_image_path: Option<PathBuf>,
+  /// The UpgradeCode GUID of the installer, identifying the versions of the app that upgrade each other.
+  ///
+  /// Defaults to a GUID derived from the main binary name, so renaming the binary breaks the upgrades.
+  /// Set it to the GUID of the already released installers to keep upgrading them.
+  #[serde(alias = "upgrade-code")]
+  pub upgrade_code: Option<String>,
+}
+
+/// Who the NSIS installer installs the app for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum NsisInstallMode {
+  /// Installs the app for the current user in `%LOCALAPPDATA%\Programs`, without administrator rights.
+  CurrentUser,
+  /// Installs the app for all the users in `%PROGRAMFILES%`, always requesting administrator rights.
+  PerMachine,
+  /// Lets the user choose between both modes, requesting administrator rights only when installing for all the users.
+  ///
+  /// The `/ALLUSERS` and `/CURRENTUSER` command line switches choose the mode of silent installations.
+  Both,
+}
+
+impl Default for NsisInstallMode {
+  fn default() -> Self {
+    Self::CurrentUser
+  }
+}
+
+/// Configuration for the installer generated with NSIS.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NsisConfig {
+  /// A custom .nsi template to use.
+  pub template: Option<PathBuf>,
+  /// Who the app is installed for. Defaults to `currentUser`.
+  #[serde(default, alias = "install-mode")]
+  pub install_mode: NsisInstallMode,
+  /// The files and directories of the installation directory kept by the uninstaller, relative to it,
+  /// e.g. the user data or the license files written by the app.
+  #[serde(default, alias = "preserved-paths")]
+  pub preserved_paths: Vec<PathBuf>,
+  /// The subkeys of the `Software\{publisher}\{productName}` registry key kept by the uninstaller,
+  /// which otherwise deletes the key. Only its direct subkeys can be preserved.
+  #[serde(default, alias = "preserved-registry-keys")]
+  pub preserved_registry_keys: Vec<String>,
 }
 
 /// Install modes for the Webview2 runtime.
@@ -510,8 +613,24 @@ pub struct WindowsConfig {
   /// The default value of this flag is `true`.
   #[serde(default = "default_allow_downgrades", alias = "allow-downgrades")]
   pub allow_downgrades: bool,
+  /// Whether installing the same version again replaces the installed app, reinstalling it.
+  /// Otherwise the installer is blocked with the `SameVersionErrorMessage` localization string.
+  ///
+  /// Only used when [`Self::allow_downgrades`] is `false`, since downgrades include reinstalls. Defaults to `true`.
+  #[serde(
+    default = "default_allow_same_version_upgrades",
+    alias = "allow-same-version-upgrades"
+  )]
+  pub allow_same_version_upgrades: bool,
+  /// The message shown when blocking a downgrade, instead of the `DowngradeErrorMessage` localization string.
+  ///
+  /// Only used when [`Self::allow_downgrades`] is `false`.
+  #[serde(alias = "downgrade-error-message")]
+  pub downgrade_error_message: Option<String>,
   /// Configuration for the MSI generated with WiX.
   pub wix: Option<WixConfig>,
+  /// Configuration for the installer generated with NSIS.
+  pub nsis: Option<NsisConfig>,
 }
 
 impl Default for WindowsConfig {
@@ -524,7 +643,22 @@ impl Default for WindowsConfig {
       webview_install_mode: Default::default(),
       webview_fixed_runtime_path: None,
       allow_downgrades: default_allow_downgrades(),
+      allow_same_version_upgrades: default_allow_same_version_upgrades(),
+      downgrade_error_message: None,
       wix: None,
+      nsis: None,
+    }
+  }
+}
+
+impl WindowsConfig {
+  /// The path to the WebView2 fixed version runtime bundled with the app, if any.
+  ///
+  /// Set by the [`WebviewInstallMode::FixedRuntime`] install mode or the deprecated [`Self::webview_fixed_runtime_path`] option.
+  pub fn fixed_runtime_path(&self) -> Option<&Path> {
+    match (&self.webview_fixed_runtime_path, &self.webview_install_mode) {
+      (Some(path), _) | (None, WebviewInstallMode::FixedRuntime { path }) => Some(path),
+      _ => None,
     }
   }
 }
@@ -533,6 +667,89 @@ fn default_allow_downgrades() -> bool {
   true
 }
 
+fn default_allow_same_version_upgrades() -> bool {
+  true
+}
+
+/// Configuration for the Android bundles.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AndroidConfig {
+  /// The minimum API level required to run the application. Defaults to `24` (Android 7.0).
+  #[serde(default = "default_min_sdk_version", alias = "min-sdk-version")]
+  pub min_sdk_version: u32,
+  /// The API level the application is built and tested against. Defaults to `33` (Android 13).
+  #[serde(default = "default_target_sdk_version", alias = "target-sdk-version")]
+  pub target_sdk_version: u32,
+  /// The internal version number, which must be increased on every release published to the Play Store.
+  ///
+  /// Defaults to `major * 1000000 + minor * 1000 + patch` of the package version.
+  #[serde(alias = "version-code")]
+  pub version_code: Option<u32>,
+  /// The key used to sign the release APK and AAB. The release bundles are unsigned if not set.
+  pub signing: Option<AndroidSigningConfig>,
+}
+
+impl Default for AndroidConfig {
+  fn default() -> Self {
+    Self {
+      min_sdk_version: default_min_sdk_version(),
+      target_sdk_version: default_target_sdk_version(),
+      version_code: None,
+      signing: None,
+    }
+  }
+}
+
+fn default_min_sdk_version() -> u32 {
+  24
+}
+
+fn default_target_sdk_version() -> u32 {
+  33
+}
+
+/// The signing key of the Android bundles.
+///
+/// The passwords are read from the `TAURI_ANDROID_KEYSTORE_PASSWORD` and `TAURI_ANDROID_KEY_PASSWORD` environment variables,
+/// the key password defaulting to the keystore password.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AndroidSigningConfig {
+  /// Path to the Java keystore (`.jks` or `.keystore`) containing the key.
+  pub keystore: PathBuf,
+  /// The alias of the key in the keystore.
+  #[serde(alias = "key-alias")]
+  pub key_alias: String,
+}
+
+/// The local toolchain used by the bundler instead of downloading its tools,
+/// for offline and reproducible builds.
+///
+/// The directory must contain the tools used by the bundle targets with their upstream file names:
+///
+/// - `wix311-binaries.zip` for the MSI installer;
+/// - `MicrosoftEdgeWebview2Setup.exe` for the `embedBootstrapper` WebView2 install mode;
+/// - `MicrosoftEdgeWebView2RuntimeInstallerX64.exe` or `MicrosoftEdgeWebView2RuntimeInstallerX86.exe`
+/// for the `offlineInstaller` WebView2 install mode;
+/// - `AppRun-<arch>`, `linuxdeploy-<arch>.AppImage` and `linuxdeploy-plugin-gtk.sh` for the AppImage bundle,
+/// and `linuxdeploy-plugin-gstreamer.sh` when bundling the media framework.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ToolchainConfig {
+  /// The directory containing the tools, relative to the `src-tauri` directory.
+  pub dir: PathBuf,
+  /// The SHA-256 checksums of the tools, as hex strings keyed by file name.
+  ///
+  /// A tool without a checksum is rejected, except for the WiX binaries whose checksum is pinned by the bundler.
+  #[serde(default)]
+  pub sha256: HashMap<String, String>,
+}
+
 /// Configuration for tauri-bundler.
 #[skip_serializing_none]
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
@@ -542,7 +759,10 @@ pub struct BundleConfig {
   /// Whether Tauri should bundle your application or just output the executable.
   #[serde(default)]
   pub active: bool,
-  /// The bundle targets, currently supports ["deb", "appimage", "msi", "app", "dmg", "updater"] or "all".
+  /// The bundle targets, currently supports ["deb", "appimage", "msi", "nsis", "app", "dmg", "updater", "apk", "aab", "ipa"] or "all".
+  ///
+  /// "all" only includes the Android and iOS bundles when building for an Android or iOS target,
+  /// and doesn't include the NSIS installer, which requires `makensis`.
   #[serde(default)]
   pub targets: BundleTarget,
   /// The application identifier in reverse domain name notation (e.g. `com.tauri.example`).
@@ -583,6 +803,9 @@ pub struct BundleConfig {
   /// Configuration for the macOS bundles.
   #[serde(rename = "macOS", default)]
   pub macos: MacConfig,
+  /// Configuration for the iOS bundles.
+  #[serde(rename = "iOS", default)]
+  pub ios: IosConfig,
   /// A list of—either absolute or relative—paths to binaries to embed with your application.
   ///
   /// Note that Tauri will look for system-specific binaries following the pattern "binary-name{-target-triple}{.system-extension}".
@@ -599,6 +822,11 @@ pub struct BundleConfig {
   /// Configuration for the Windows bundle.
   #[serde(default)]
   pub windows: WindowsConfig,
+  /// Configuration for the Android bundles.
+  #[serde(default)]
+  pub android: AndroidConfig,
+  /// Uses the tools of a local directory instead of downloading them at bundle time.
+  pub toolchain: Option<ToolchainConfig>,
 }
 
 /// A CLI argument definition.
@@ -859,6 +1087,14 @@ pub struct WindowConfig {
   /// Whether the window should always be on top of other windows.
   #[serde(default, alias = "always-on-top")]
   pub always_on_top: bool,
+  /// Whether the window runs in kiosk mode, taking over the screen for point-of-sale and signage deployments.
+  ///
+  /// The window is fullscreen, always on top and without decorations, the context menu of the webview is disabled,
+  /// and the system shortcuts switching or closing the app are blocked where possible:
+  /// Alt+Tab, Alt+F4, Ctrl+Esc and the Windows keys on Windows, and the process switching, Force Quit and Apple menu on macOS.
+  /// The system shortcuts are not blocked on Linux.
+  #[serde(default)]
+  pub kiosk: bool,
   /// If `true`, hides the window icon from the taskbar on Windows and Linux.
   #[serde(default, alias = "skip-taskbar")]
   pub skip_taskbar: bool,
@@ -881,6 +1117,31 @@ pub struct WindowConfig {
   /// [tabbing identifier]: <https://developer.apple.com/documentation/appkit/nswindow/1644704-tabbingidentifier>
   #[serde(default, alias = "tabbing-identifier")]
   pub tabbing_identifier: Option<String>,
+  /// The Content Security Policy of the HTML files served to this window, overriding [`csp`](#SecurityConfig.csp) and [`dev_csp`](#SecurityConfig.devCsp).
+  ///
+  /// The nonces and hashes of the inline scripts and styles are injected the same way as on the global policy,
+  /// so a window can load a stricter or looser policy than the rest of the app.
+  pub csp: Option<Csp>,
+  /// Whether the webview renders with the GPU, disable it when the window renders black or flickers with some drivers.
+  /// Not supported on macOS.
+  ///
+  /// The `TAURI_WEBVIEW_HARDWARE_ACCELERATION` environment variable set to `true` or `false` overrides it on all windows.
+  #[serde(
+    default = "default_hardware_acceleration",
+    alias = "hardware-acceleration"
+  )]
+  pub hardware_acceleration: bool,
+  /// Whether the webview composites its layers with the GPU.
+  /// Not supported on macOS, and disabling it also disables the hardware acceleration on Linux.
+  #[serde(default = "default_gpu_compositing", alias = "gpu-compositing")]
+  pub gpu_compositing: bool,
+  /// Whether WebGL is available to the webview. Not supported on macOS.
+  #[serde(default = "default_webgl")]
+  pub webgl: bool,
+  /// The MIME types of the data other than files that can be dropped on the webview, such as `text/uri-list` or `text/*`.
+  /// The drops are emitted as the `tauri://drop` event. On Windows, the file drop handler must be disabled with `fileDropEnabled`.
+  #[serde(default, alias = "accept-drop-types")]
+  pub accept_drop_types: Vec<String>,
 }
 
 impl Default for WindowConfig {
@@ -908,12 +1169,18 @@ impl Default for WindowConfig {
       visible: default_visible(),
       decorations: default_decorations(),
       always_on_top: false,
+      kiosk: false,
       skip_taskbar: false,
       theme: None,
       title_bar_style: Default::default(),
       hidden_title: false,
       accept_first_mouse: false,
       tabbing_identifier: None,
+      csp: None,
+      hardware_acceleration: default_hardware_acceleration(),
+      gpu_compositing: default_gpu_compositing(),
+      webgl: default_webgl(),
+      accept_drop_types: Vec::new(),
     }
   }
 }
@@ -954,6 +1221,18 @@ fn default_file_drop_enabled() -> bool {
   true
 }
 
+fn default_hardware_acceleration() -> bool {
+  true
+}
+
+fn default_gpu_compositing() -> bool {
+  true
+}
+
+fn default_webgl() -> bool {
+  true
+}
+
 /// A Content-Security-Policy directive source list.
 /// See <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/Sources#sources>.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
@@ -1132,6 +1411,87 @@ pub struct SecurityConfig {
   /// Your application might be vulnerable to XSS attacks without this Tauri protection.
   #[serde(default, alias = "dangerous-disable-asset-csp-modification")]
   pub dangerous_disable_asset_csp_modification: DisabledCspModificationKind,
+  /// Restricts which commands can be invoked by a set of windows and/or origins.
+  ///
+  /// By default every window can invoke every command. When one or more entries match
+  /// the window and origin of an IPC request, the command must be listed in one of them,
+  /// otherwise the request is rejected before its arguments are deserialized.
+  /// This is useful to limit the surface exposed to embedded third-party pages.
+  ///
+  /// The scope can be modified at runtime with [`Manager::command_scope`](https://docs.rs/tauri/1/tauri/trait.Manager.html#method.command_scope).
+  #[serde(default, alias = "command-scope")]
+  pub command_scope: Vec<CommandScopeEntry>,
+  /// Grants the permissions declared by plugins to a set of windows.
+  ///
+  /// Once a plugin declares permissions, each of its commands can only be invoked by the windows
+  /// granted a permission that includes the command. Plugins without permissions are not restricted.
+  #[serde(default, alias = "plugin-permissions")]
+  pub plugin_permissions: Vec<PluginPermissionEntry>,
+  /// How the filesystem and asset protocol scopes handle the symbolic links of the checked paths.
+  #[serde(default, alias = "symlink-policy")]
+  pub symlink_policy: SymlinkPolicy,
+}
+
+/// How the filesystem and asset protocol scopes handle symbolic links.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum SymlinkPolicy {
+  /// Existing paths are resolved before they are matched, so a link is allowed if its target is in scope.
+  /// Paths that do not exist are matched as is.
+  Follow,
+  /// The deepest existing ancestor of a path is resolved too, so a link in the scope
+  /// cannot be used to create files outside of it. Paths with dangling links are denied.
+  DenyEscape,
+  /// Paths going through a symbolic link are denied, including system links such as `/tmp` on macOS.
+  Deny,
+}
+
+impl Default for SymlinkPolicy {
+  fn default() -> Self {
+    Self::Follow
+  }
+}
+
+/// An entry of the IPC command scope.
+///
+/// An entry applies to an IPC request if its window label matches one of the `windows` patterns
+/// and its origin matches one of the `origins` patterns. An empty list matches everything.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CommandScopeEntry {
+  /// Glob patterns of the window labels this entry applies to.
+  #[serde(default)]
+  pub windows: Vec<String>,
+  /// Glob patterns of the origins this entry applies to, e.g. `https://*.tauri.app`.
+  ///
+  /// The origin is the `scheme://host[:port]` of the URL loaded by the webview.
+  #[serde(default)]
+  pub origins: Vec<String>,
+  /// Glob patterns of the commands that can be invoked.
+  ///
+  /// Plugin commands are identified as `plugin:<plugin-name>|<command>`
+  /// and the Tauri API modules as `tauri:<module>`, e.g. `tauri:event` or `tauri:fs`.
+  #[serde(default)]
+  pub commands: Vec<String>,
+}
+
+/// An entry of the plugin permissions configuration.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PluginPermissionEntry {
+  /// Glob patterns of the window labels this entry applies to. An empty list matches every window.
+  #[serde(default)]
+  pub windows: Vec<String>,
+  /// Glob patterns of the granted permissions.
+  ///
+  /// Permissions are identified as `<plugin-name>:<permission>`, e.g. `store:read` or `store:*`.
+  #[serde(default)]
+  pub permissions: Vec<String>,
 }
 
 /// Defines an allowlist type.
@@ -1238,6 +1598,12 @@ pub struct FsAllowlistConfig {
   /// Check if path exists on the local filesystem.
   #[serde(default)]
   pub exists: bool,
+  /// Move files and directories of the local filesystem to the trash.
+  #[serde(default)]
+  pub trash: bool,
+  /// Watch files and directories of the local filesystem for changes.
+  #[serde(default)]
+  pub watch: bool,
 }
 
 impl Allowlist for FsAllowlistConfig {
@@ -1254,6 +1620,8 @@ impl Allowlist for FsAllowlistConfig {
       remove_file: true,
       rename_file: true,
       exists: true,
+      trash: true,
+      watch: true,
     };
     let mut features = allowlist.to_features();
     features.push("fs-all");
@@ -1274,6 +1642,8 @@ impl Allowlist for FsAllowlistConfig {
       check_feature!(self, features, remove_file, "fs-remove-file");
       check_feature!(self, features, rename_file, "fs-rename-file");
       check_feature!(self, features, exists, "fs-exists");
+      check_feature!(self, features, trash, "fs-trash");
+      check_feature!(self, features, watch, "fs-watch");
       features
     }
   }
@@ -1505,6 +1875,10 @@ pub struct ShellAllowedCommand {
   /// If this command is a sidecar command.
   #[serde(default)]
   pub sidecar: bool,
+
+  /// The default arguments and environment variables of the command on each platform.
+  #[serde(default)]
+  pub platforms: ShellCommandPlatforms,
 }
 
 impl<'de> Deserialize<'de> for ShellAllowedCommand {
@@ -1521,6 +1895,8 @@ impl<'de> Deserialize<'de> for ShellAllowedCommand {
       args: ShellAllowedArgs,
       #[serde(default)]
       sidecar: bool,
+      #[serde(default)]
+      platforms: ShellCommandPlatforms,
     }
 
     let config = InnerShellAllowedCommand::deserialize(deserializer)?;
@@ -1536,10 +1912,42 @@ impl<'de> Deserialize<'de> for ShellAllowedCommand {
       command: config.command.unwrap_or_default(),
       args: config.args,
       sidecar: config.sidecar,
+      platforms: config.platforms,
     })
   }
 }
 
+/// The default arguments and environment variables of a shell scope command on each platform.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ShellCommandPlatforms {
+  /// The defaults used on Linux.
+  pub linux: Option<ShellCommandDefaults>,
+  /// The defaults used on macOS.
+  pub macos: Option<ShellCommandDefaults>,
+  /// The defaults used on Windows.
+  pub windows: Option<ShellCommandDefaults>,
+}
+
+/// The arguments and environment variables a shell scope command is always executed with.
+///
+/// Values can start with one of the base directory variables supported by the command name,
+/// e.g. `$APPDATA/cache` or `$RESOURCE/models`, which are resolved when the app starts.
+/// Unknown variables are rejected at build time.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ShellCommandDefaults {
+  /// Arguments passed before the arguments of each execution.
+  #[serde(default)]
+  pub args: Vec<String>,
+  /// Environment variables set on each execution,
+  /// overwritten by the variables with the same name set by the caller.
+  #[serde(default)]
+  pub env: HashMap<String, String>,
+}
+
 /// A set of command arguments allowed to be executed by the webview API.
 ///
 /// A value of `true` will allow any arguments to be passed to the command. `false` will disable all
@@ -1694,6 +2102,12 @@ pub struct DialogAllowlistConfig {
   /// Allows the API to show a dialog window with Ok/Cancel buttons.
   #[serde(default)]
   pub confirm: bool,
+  /// Allows the API to show the native color picker.
+  #[serde(default, alias = "color-picker")]
+  pub color_picker: bool,
+  /// Allows the API to show the native font picker.
+  #[serde(default, alias = "font-picker")]
+  pub font_picker: bool,
 }
 
 impl Allowlist for DialogAllowlistConfig {
@@ -1705,6 +2119,8 @@ impl Allowlist for DialogAllowlistConfig {
       message: true,
       ask: true,
       confirm: true,
+      color_picker: true,
+      font_picker: true,
     };
     let mut features = allowlist.to_features();
     features.push("dialog-all");
@@ -1721,6 +2137,8 @@ impl Allowlist for DialogAllowlistConfig {
       check_feature!(self, features, message, "dialog-message");
       check_feature!(self, features, ask, "dialog-ask");
       check_feature!(self, features, confirm, "dialog-confirm");
+      check_feature!(self, features, color_picker, "dialog-color-picker");
+      check_feature!(self, features, font_picker, "dialog-font-picker");
       features
     }
   }
@@ -1778,148 +2196,436 @@ impl Allowlist for HttpAllowlistConfig {
   }
 }
 
-/// Allowlist for the notification APIs.
+/// WebSocket API scope definition.
+/// It is a list of URLs that can be connected to by the webview when using the WebSocket APIs.
+/// The scoped URL is matched against the connection URL using a glob pattern.
+///
+/// Examples:
+/// - "wss://**": allows all secure WebSocket urls
+/// - "wss://*.example.com/socket": allows the `/socket` path of any subdomain of "example.com"
+/// - "ws://localhost:*/*": allows any local WebSocket server
+#[allow(rustdoc::bare_urls)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct WebsocketAllowlistScope(pub Vec<Url>);
+
+/// Allowlist for the WebSocket APIs.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
-pub struct NotificationAllowlistConfig {
-  /// Use this flag to enable all notification API features.
+pub struct WebsocketAllowlistConfig {
+  /// The access scope for the WebSocket APIs.
+  #[serde(default)]
+  pub scope: WebsocketAllowlistScope,
+  /// Use this flag to enable all WebSocket API features.
   #[serde(default)]
   pub all: bool,
+  /// Allows connecting to WebSocket servers.
+  #[serde(default)]
+  pub connect: bool,
 }
 
-impl Allowlist for NotificationAllowlistConfig {
+impl Allowlist for WebsocketAllowlistConfig {
   fn all_features() -> Vec<&'static str> {
-    let allowlist = Self { all: false };
+    let allowlist = Self {
+      scope: Default::default(),
+      all: false,
+      connect: true,
+    };
     let mut features = allowlist.to_features();
-    features.push("notification-all");
+    features.push("websocket-all");
     features
   }
 
   fn to_features(&self) -> Vec<&'static str> {
     if self.all {
-      vec!["notification-all"]
+      vec!["websocket-all"]
     } else {
-      vec![]
+      let mut features = Vec::new();
+      check_feature!(self, features, connect, "websocket-connect");
+      features
     }
   }
 }
 
-/// Allowlist for the global shortcut APIs.
+/// Serial API scope definition.
+/// It is a list of glob patterns matched against the path of a serial port,
+/// or against the `<vendor id>:<product id>` identifier of USB serial ports, as lowercase hexadecimal numbers.
+/// `*` does not match the path separator and paths with a `..` component are never allowed.
+/// The ports and USB devices not allowed by the scope are not listed either.
+///
+/// Examples:
+/// - "/dev/ttyUSB*": allows the USB serial ports on Linux
+/// - "COM*": allows all serial ports on Windows
+/// - "2341:*": allows the USB serial ports of any Arduino board
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct SerialAllowlistScope(pub Vec<String>);
+
+/// Allowlist for the serial port and USB device APIs.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
-pub struct GlobalShortcutAllowlistConfig {
-  /// Use this flag to enable all global shortcut API features.
+pub struct SerialAllowlistConfig {
+  /// The access scope for the serial port APIs.
+  #[serde(default)]
+  pub scope: SerialAllowlistScope,
+  /// Use this flag to enable all serial API features.
   #[serde(default)]
   pub all: bool,
+  /// Allows listing the serial ports and USB devices, and watching them being connected and disconnected.
+  #[serde(default)]
+  pub list: bool,
+  /// Allows opening the serial ports allowed on the scope.
+  #[serde(default)]
+  pub open: bool,
 }
 
-impl Allowlist for GlobalShortcutAllowlistConfig {
+impl Allowlist for SerialAllowlistConfig {
   fn all_features() -> Vec<&'static str> {
-    let allowlist = Self { all: false };
+    let allowlist = Self {
+      scope: Default::default(),
+      all: false,
+      list: true,
+      open: true,
+    };
     let mut features = allowlist.to_features();
-    features.push("global-shortcut-all");
+    features.push("serial-all");
     features
   }
 
   fn to_features(&self) -> Vec<&'static str> {
     if self.all {
-      vec!["global-shortcut-all"]
+      vec!["serial-all"]
     } else {
-      vec![]
+      let mut features = Vec::new();
+      check_feature!(self, features, list, "serial-list");
+      check_feature!(self, features, open, "serial-open");
+      features
     }
   }
 }
 
-/// Allowlist for the OS APIs.
+/// Store API scope definition.
+/// It is a list of glob patterns matched against the names of the stores the webviews can open.
+///
+/// Examples:
+/// - "settings": allows the `settings` store
+/// - "cache-*": allows the stores whose name starts with `cache-`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct StoreAllowlistScope(pub Vec<String>);
+
+/// Allowlist for the store APIs.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
-pub struct OsAllowlistConfig {
-  /// Use this flag to enable all OS API features.
+pub struct StoreAllowlistConfig {
+  /// The names of the stores the webviews can open.
+  #[serde(default)]
+  pub scope: StoreAllowlistScope,
+  /// Use this flag to enable all store API features.
   #[serde(default)]
   pub all: bool,
+  /// Allows reading and writing the key-value store of the stores allowed on the scope.
+  #[serde(default)]
+  pub key_value: bool,
+  /// Allows running migrations and SQL statements on the stores allowed on the scope.
+  #[serde(default)]
+  pub sql: bool,
 }
 
-impl Allowlist for OsAllowlistConfig {
+impl Allowlist for StoreAllowlistConfig {
   fn all_features() -> Vec<&'static str> {
-    let allowlist = Self { all: false };
+    let allowlist = Self {
+      scope: Default::default(),
+      all: false,
+      key_value: true,
+      sql: true,
+    };
     let mut features = allowlist.to_features();
-    features.push("os-all");
+    features.push("store-all");
     features
   }
 
   fn to_features(&self) -> Vec<&'static str> {
     if self.all {
-      vec!["os-all"]
+      vec!["store-all"]
     } else {
-      vec![]
+      let mut features = Vec::new();
+      check_feature!(self, features, key_value, "store-key-value");
+      check_feature!(self, features, sql, "store-sql");
+      features
     }
   }
 }
 
-/// Allowlist for the path APIs.
+/// OAuth API scope definition.
+/// It is a list of URLs of the authorization and token endpoints the webview can use when using the OAuth APIs.
+/// The scoped URL is matched against the endpoint URL using a glob pattern.
+///
+/// Examples:
+/// - "https://github.com/login/oauth/*": allows the GitHub endpoints
+/// - "https://*.example.com/oauth2/**": allows any OAuth endpoint of any subdomain of "example.com"
+#[allow(rustdoc::bare_urls)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct OAuthAllowlistScope(pub Vec<Url>);
+
+/// Allowlist for the OAuth APIs.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
-pub struct PathAllowlistConfig {
-  /// Use this flag to enable all path API features.
+pub struct OAuthAllowlistConfig {
+  /// The access scope for the OAuth APIs.
+  #[serde(default)]
+  pub scope: OAuthAllowlistScope,
+  /// Use this flag to enable all OAuth API features.
   #[serde(default)]
   pub all: bool,
+  /// Allows running the authorization code flow with the endpoints allowed on the scope.
+  #[serde(default)]
+  pub authenticate: bool,
 }
 
-impl Allowlist for PathAllowlistConfig {
+impl Allowlist for OAuthAllowlistConfig {
   fn all_features() -> Vec<&'static str> {
-    let allowlist = Self { all: false };
+    let allowlist = Self {
+      scope: Default::default(),
+      all: false,
+      authenticate: true,
+    };
     let mut features = allowlist.to_features();
-    features.push("path-all");
+    features.push("oauth-all");
     features
   }
 
   fn to_features(&self) -> Vec<&'static str> {
     if self.all {
-      vec!["path-all"]
+      vec!["oauth-all"]
     } else {
-      vec![]
+      let mut features = Vec::new();
+      check_feature!(self, features, authenticate, "oauth-authenticate");
+      features
     }
   }
 }
 
-/// Allowlist for the custom protocols.
+/// mDNS API scope definition.
+/// It is a list of glob patterns matched against the service types the webviews can advertise and browse,
+/// without the `.local.` domain.
+///
+/// Examples:
+/// - "_myapp._tcp": allows the `_myapp._tcp` services
+/// - "_myapp-*._udp": allows the UDP services whose name starts with `_myapp-`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct MdnsAllowlistScope(pub Vec<String>);
+
+/// Allowlist for the mDNS APIs.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
-pub struct ProtocolAllowlistConfig {
-  /// The access scope for the asset protocol.
-  #[serde(default, alias = "asset-scope")]
-  pub asset_scope: FsAllowlistScope,
-  /// Use this flag to enable all custom protocols.
+pub struct MdnsAllowlistConfig {
+  /// The service types the webviews can advertise and browse.
+  #[serde(default)]
+  pub scope: MdnsAllowlistScope,
+  /// Use this flag to enable all mDNS API features.
   #[serde(default)]
   pub all: bool,
-  /// Enables the asset protocol.
+  /// Allows advertising services on the local network.
   #[serde(default)]
-  pub asset: bool,
+  pub advertise: bool,
+  /// Allows browsing the services of the local network.
+  #[serde(default)]
+  pub browse: bool,
 }
 
-impl Allowlist for ProtocolAllowlistConfig {
+impl Allowlist for MdnsAllowlistConfig {
   fn all_features() -> Vec<&'static str> {
     let allowlist = Self {
-      asset_scope: Default::default(),
+      scope: Default::default(),
       all: false,
-      asset: true,
+      advertise: true,
+      browse: true,
     };
     let mut features = allowlist.to_features();
-    features.push("protocol-all");
+    features.push("mdns-all");
     features
   }
 
   fn to_features(&self) -> Vec<&'static str> {
     if self.all {
-      vec!["protocol-all"]
+      vec!["mdns-all"]
     } else {
       let mut features = Vec::new();
-      check_feature!(self, features, asset, "protocol-asset");
+      check_feature!(self, features, advertise, "mdns-advertise");
+      check_feature!(self, features, browse, "mdns-browse");
+      features
+    }
+  }
+}
+
+/// Allowlist for the notification APIs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NotificationAllowlistConfig {
+  /// Use this flag to enable all notification API features.
+  #[serde(default)]
+  pub all: bool,
+}
+
+impl Allowlist for NotificationAllowlistConfig {
+  fn all_features() -> Vec<&'static str> {
+    let allowlist = Self { all: false };
+    let mut features = allowlist.to_features();
+    features.push("notification-all");
+    features
+  }
+
+  fn to_features(&self) -> Vec<&'static str> {
+    if self.all {
+      vec!["notification-all"]
+    } else {
+      vec![]
+    }
+  }
+}
+
+/// Allowlist for the global shortcut APIs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GlobalShortcutAllowlistConfig {
+  /// Use this flag to enable all global shortcut API features.
+  #[serde(default)]
+  pub all: bool,
+}
+
+impl Allowlist for GlobalShortcutAllowlistConfig {
+  fn all_features() -> Vec<&'static str> {
+    let allowlist = Self { all: false };
+    let mut features = allowlist.to_features();
+    features.push("global-shortcut-all");
+    features
+  }
+
+  fn to_features(&self) -> Vec<&'static str> {
+    if self.all {
+      vec!["global-shortcut-all"]
+    } else {
+      vec![]
+    }
+  }
+}
+
+/// Allowlist for the OS APIs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OsAllowlistConfig {
+  /// Use this flag to enable all OS API features.
+  #[serde(default)]
+  pub all: bool,
+  /// Enables reading and watching the network status.
+  ///
+  /// The status is watched for the lifetime of the app, so this flag is not enabled by `all`.
+  #[serde(default)]
+  pub network_status: bool,
+}
+
+impl Allowlist for OsAllowlistConfig {
+  fn all_features() -> Vec<&'static str> {
+    let allowlist = Self {
+      all: false,
+      network_status: false,
+    };
+    let mut features = allowlist.to_features();
+    features.push("os-all");
+    features
+  }
+
+  fn to_features(&self) -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if self.all {
+      features.push("os-all");
+    }
+    check_feature!(self, features, network_status, "os-network-status");
+    features
+  }
+}
+
+/// Allowlist for the path APIs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PathAllowlistConfig {
+  /// Use this flag to enable all path API features.
+  #[serde(default)]
+  pub all: bool,
+  /// Enables listing and watching the mounted volumes.
+  ///
+  /// The volumes reveal the drives of the user, so this flag is not enabled by `all`.
+  #[serde(default)]
+  pub volumes: bool,
+}
+
+impl Allowlist for PathAllowlistConfig {
+  fn all_features() -> Vec<&'static str> {
+    let allowlist = Self {
+      all: false,
+      volumes: false,
+    };
+    let mut features = allowlist.to_features();
+    features.push("path-all");
+    features
+  }
+
+  fn to_features(&self) -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if self.all {
+      features.push("path-all");
+    }
+    check_feature!(self, features, volumes, "path-volumes");
+    features
+  }
+}
+
+/// Allowlist for the custom protocols.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProtocolAllowlistConfig {
+  /// The access scope for the asset protocol.
+  #[serde(default, alias = "asset-scope")]
+  pub asset_scope: FsAllowlistScope,
+  /// Use this flag to enable all custom protocols.
+  #[serde(default)]
+  pub all: bool,
+  /// Enables the asset protocol.
+  #[serde(default)]
+  pub asset: bool,
+}
+
+impl Allowlist for ProtocolAllowlistConfig {
+  fn all_features() -> Vec<&'static str> {
+    let allowlist = Self {
+      asset_scope: Default::default(),
+      all: false,
+      asset: true,
+    };
+    let mut features = allowlist.to_features();
+    features.push("protocol-all");
+    features
+  }
+
+  fn to_features(&self) -> Vec<&'static str> {
+    if self.all {
+      vec!["protocol-all"]
+    } else {
+      let mut features = Vec::new();
+      check_feature!(self, features, asset, "protocol-asset");
       features
     }
   }
@@ -1949,6 +2655,11 @@ pub struct ProcessAllowlistConfig {
   /// Enables the exit API.
   #[serde(default)]
   pub exit: bool,
+  /// Emits the `tauri://metrics` event to the webviews with the resource usage of the application.
+  ///
+  /// The metrics reveal the processes of the application, so this flag is not enabled by `all`.
+  #[serde(default)]
+  pub metrics: bool,
 }
 
 impl Allowlist for ProcessAllowlistConfig {
@@ -1958,6 +2669,7 @@ impl Allowlist for ProcessAllowlistConfig {
       relaunch: true,
       relaunch_dangerous_allow_symlink_macos: false,
       exit: true,
+      metrics: false,
     };
     let mut features = allowlist.to_features();
     features.push("process-all");
@@ -1965,7 +2677,7 @@ impl Allowlist for ProcessAllowlistConfig {
   }
 
   fn to_features(&self) -> Vec<&'static str> {
-    if self.all {
+    let mut features = if self.all {
       vec!["process-all"]
     } else {
       let mut features = Vec::new();
@@ -1978,6 +2690,43 @@ impl Allowlist for ProcessAllowlistConfig {
       );
       check_feature!(self, features, exit, "process-exit");
       features
+    };
+    check_feature!(self, features, metrics, "process-metrics");
+    features
+  }
+}
+
+/// Allowlist for the log APIs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LogAllowlistConfig {
+  /// Use this flag to enable all log APIs.
+  #[serde(default)]
+  pub all: bool,
+  /// Enables writing records to the application log, including the `console` messages forwarded with `tauri > log > webviewConsole`.
+  #[serde(default)]
+  pub write: bool,
+}
+
+impl Allowlist for LogAllowlistConfig {
+  fn all_features() -> Vec<&'static str> {
+    let allowlist = Self {
+      all: false,
+      write: true,
+    };
+    let mut features = allowlist.to_features();
+    features.push("log-all");
+    features
+  }
+
+  fn to_features(&self) -> Vec<&'static str> {
+    if self.all {
+      vec!["log-all"]
+    } else {
+      let mut features = Vec::new();
+      check_feature!(self, features, write, "log-write");
+      features
     }
   }
 }
@@ -2085,6 +2834,24 @@ pub struct AllowlistConfig {
   /// HTTP API allowlist.
   #[serde(default)]
   pub http: HttpAllowlistConfig,
+  /// WebSocket API allowlist.
+  #[serde(default)]
+  pub websocket: WebsocketAllowlistConfig,
+  /// Serial port and USB device API allowlist.
+  #[serde(default)]
+  pub serial: SerialAllowlistConfig,
+  /// mDNS API allowlist.
+  #[serde(default)]
+  pub mdns: MdnsAllowlistConfig,
+  /// Store API allowlist.
+  #[serde(default)]
+  pub store: StoreAllowlistConfig,
+  /// OAuth API allowlist.
+  #[serde(default)]
+  pub oauth: OAuthAllowlistConfig,
+  /// Log API allowlist.
+  #[serde(default)]
+  pub log: LogAllowlistConfig,
   /// Notification API allowlist.
   #[serde(default)]
   pub notification: NotificationAllowlistConfig,
@@ -2119,6 +2886,12 @@ impl Allowlist for AllowlistConfig {
     features.extend(ShellAllowlistConfig::all_features());
     features.extend(DialogAllowlistConfig::all_features());
     features.extend(HttpAllowlistConfig::all_features());
+    features.extend(WebsocketAllowlistConfig::all_features());
+    features.extend(SerialAllowlistConfig::all_features());
+    features.extend(MdnsAllowlistConfig::all_features());
+    features.extend(StoreAllowlistConfig::all_features());
+    features.extend(OAuthAllowlistConfig::all_features());
+    features.extend(LogAllowlistConfig::all_features());
     features.extend(NotificationAllowlistConfig::all_features());
     features.extend(GlobalShortcutAllowlistConfig::all_features());
     features.extend(OsAllowlistConfig::all_features());
@@ -2140,6 +2913,12 @@ impl Allowlist for AllowlistConfig {
       features.extend(self.shell.to_features());
       features.extend(self.dialog.to_features());
       features.extend(self.http.to_features());
+      features.extend(self.websocket.to_features());
+      features.extend(self.serial.to_features());
+      features.extend(self.mdns.to_features());
+      features.extend(self.store.to_features());
+      features.extend(self.oauth.to_features());
+      features.extend(self.log.to_features());
       features.extend(self.notification.to_features());
       features.extend(self.global_shortcut.to_features());
       features.extend(self.os.to_features());
@@ -2162,9 +2941,14 @@ pub enum PatternKind {
   /// Brownfield pattern.
   Brownfield,
   /// Isolation pattern. Recommended for security purposes.
+  #[serde(rename_all = "camelCase")]
   Isolation {
     /// The dir containing the index.html file that contains the secure isolation application.
     dir: PathBuf,
+    /// Generates a new AES-GCM key every time the isolation application is loaded on a window,
+    /// instead of using a single key for the lifetime of the app.
+    #[serde(default, alias = "rotate-key")]
+    rotate_key: bool,
   },
 }
 
@@ -2174,6 +2958,93 @@ impl Default for PatternKind {
   }
 }
 
+/// The splashscreen configuration object.
+///
+/// The splashscreen window is created before the other windows, with the reserved `__tauri_splashscreen` label.
+/// It is closed by `App::close_splashscreen`, or automatically when the main window finishes loading.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SplashscreenConfig {
+  /// The splashscreen URL, usually a lightweight page of the distDir.
+  #[serde(default = "default_splashscreen_url")]
+  pub url: WindowUrl,
+  /// The splashscreen width.
+  #[serde(default = "default_splashscreen_width")]
+  pub width: f64,
+  /// The splashscreen height.
+  #[serde(default = "default_splashscreen_height")]
+  pub height: f64,
+  /// Whether the splashscreen is transparent, e.g. to show a logo without a window background.
+  ///
+  /// Note that on `macOS` this requires the `macos-private-api` feature flag, enabled under `tauri > macOSPrivateApi`.
+  #[serde(default)]
+  pub transparent: bool,
+  /// Whether the splashscreen is always on top of the other windows.
+  #[serde(
+    default = "default_splashscreen_always_on_top",
+    alias = "always-on-top"
+  )]
+  pub always_on_top: bool,
+  /// The label of the window replacing the splashscreen.
+  ///
+  /// If it is defined in `tauri > windows` and visible, it is hidden until the splashscreen is closed.
+  #[serde(default = "default_splashscreen_main_window", alias = "main-window")]
+  pub main_window: String,
+  /// Whether the splashscreen is closed when the main window finishes loading.
+  ///
+  /// Disable it to close the splashscreen with `App::close_splashscreen` instead, e.g. after the frontend fetched its data.
+  #[serde(default = "default_splashscreen_auto_close", alias = "auto-close")]
+  pub auto_close: bool,
+  /// The duration in milliseconds of the splashscreen fade out. Disabled with `0`.
+  #[serde(default, alias = "fade-out-duration")]
+  pub fade_out_duration: u64,
+  /// The duration in milliseconds of the main window fade in. Disabled with `0`.
+  #[serde(default, alias = "fade-in-duration")]
+  pub fade_in_duration: u64,
+}
+
+impl Default for SplashscreenConfig {
+  fn default() -> Self {
+    Self {
+      url: default_splashscreen_url(),
+      width: default_splashscreen_width(),
+      height: default_splashscreen_height(),
+      transparent: false,
+      always_on_top: default_splashscreen_always_on_top(),
+      main_window: default_splashscreen_main_window(),
+      auto_close: default_splashscreen_auto_close(),
+      fade_out_duration: 0,
+      fade_in_duration: 0,
+    }
+  }
+}
+
+fn default_splashscreen_url() -> WindowUrl {
+  WindowUrl::App("splashscreen.html".into())
+}
+
+fn default_splashscreen_width() -> f64 {
+  400f64
+}
+
+fn default_splashscreen_height() -> f64 {
+  300f64
+}
+
+fn default_splashscreen_always_on_top() -> bool {
+  true
+}
+
+fn default_splashscreen_main_window() -> String {
+  "main".into()
+}
+
+fn default_splashscreen_auto_close() -> bool {
+  true
+}
+
 /// The Tauri configuration object.
 #[skip_serializing_none]
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize)]
@@ -2186,6 +3057,8 @@ pub struct TauriConfig {
   /// The windows configuration.
   #[serde(default)]
   pub windows: Vec<WindowConfig>,
+  /// The splashscreen shown while the main window loads.
+  pub splashscreen: Option<SplashscreenConfig>,
   /// The CLI configuration.
   pub cli: Option<CliConfig>,
   /// The bundler configuration.
@@ -2203,6 +3076,12 @@ pub struct TauriConfig {
   /// Configuration for app system tray.
   #[serde(alias = "system-tray")]
   pub system_tray: Option<SystemTrayConfig>,
+  /// The logging configuration.
+  pub log: Option<LogConfig>,
+  /// The localhost server configuration.
+  pub localhost: Option<LocalhostConfig>,
+  /// The localization configuration.
+  pub i18n: Option<I18nConfig>,
   /// MacOS private API configuration. Enables the transparent background API and sets the `fullScreenEnabled` preference to `true`.
   #[serde(rename = "macOSPrivateApi", alias = "macos-private-api", default)]
   pub macos_private_api: bool,
@@ -2217,6 +3096,8 @@ impl TauriConfig {
       "cli",
       "updater",
       "system-tray",
+      "localhost",
+      "i18n",
       "macos-private-api",
       "isolation",
     ]);
@@ -2236,6 +3117,12 @@ impl TauriConfig {
     if self.system_tray.is_some() {
       features.push("system-tray");
     }
+    if self.localhost.is_some() {
+      features.push("localhost");
+    }
+    if self.i18n.is_some() {
+      features.push("i18n");
+    }
     if self.macos_private_api {
       features.push("macos-private-api");
     }
@@ -2443,6 +3330,176 @@ impl Default for UpdaterConfig {
   }
 }
 
+/// The minimum level of the log records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+  /// The most verbose level, for tracing the execution.
+  Trace,
+  /// Information useful while debugging.
+  Debug,
+  /// Information about the normal operation of the application.
+  Info,
+  /// Unexpected situations the application recovered from.
+  Warn,
+  /// Errors.
+  Error,
+}
+
+impl Default for LogLevel {
+  fn default() -> Self {
+    Self::Info
+  }
+}
+
+/// The logging configuration of `tauri::log`.
+///
+/// When set, the records of the [`log`](https://docs.rs/log) macros are written to a rotating file
+/// in the app log directory and to the standard output.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LogConfig {
+  /// The minimum level of the records, `info` by default.
+  #[serde(default)]
+  pub level: LogLevel,
+  /// Whether the records are written to a file in the app log directory.
+  #[serde(default = "default_log_file")]
+  pub file: bool,
+  /// Whether the records are written to the standard output.
+  #[serde(default = "default_log_stdout")]
+  pub stdout: bool,
+  /// The size in bytes after which the log file is rotated.
+  #[serde(default = "default_log_max_file_size", alias = "max-file-size")]
+  pub max_file_size: u64,
+  /// The number of rotated log files kept, in addition to the current one.
+  #[serde(default = "default_log_max_files", alias = "max-files")]
+  pub max_files: usize,
+  /// Whether the `console` messages of the webviews are forwarded to the log. Requires the `log > write` allowlist.
+  #[serde(default, alias = "webview-console")]
+  pub webview_console: bool,
+}
+
+impl Default for LogConfig {
+  fn default() -> Self {
+    Self {
+      level: Default::default(),
+      file: default_log_file(),
+      stdout: default_log_stdout(),
+      max_file_size: default_log_max_file_size(),
+      max_files: default_log_max_files(),
+      webview_console: false,
+    }
+  }
+}
+
+fn default_log_file() -> bool {
+  true
+}
+
+fn default_log_stdout() -> bool {
+  true
+}
+
+fn default_log_max_file_size() -> u64 {
+  5 * 1024 * 1024
+}
+
+fn default_log_max_files() -> usize {
+  3
+}
+
+/// The configuration of the localhost server, serving the frontend over `http://localhost` instead of the custom protocol.
+///
+/// The server only accepts connections on the loopback interface.
+/// Requires the `localhost` Cargo feature, enabled by the CLI when this configuration is set.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LocalhostConfig {
+  /// The first port of the range the server binds to, `1440` by default.
+  ///
+  /// The origin of the frontend, and so its local storage, depends on the port,
+  /// so the range should be fixed and free on the user machines.
+  #[serde(default = "default_localhost_port_start", alias = "port-start")]
+  pub port_start: u16,
+  /// The last port of the range the server binds to, `1449` by default.
+  #[serde(default = "default_localhost_port_end", alias = "port-end")]
+  pub port_end: u16,
+  /// Whether the requests must carry the token generated on launch, so other local processes and websites cannot read the assets.
+  ///
+  /// The token is appended to the window URLs and stored on a cookie.
+  #[serde(default = "default_localhost_auth_token", alias = "auth-token")]
+  pub auth_token: bool,
+}
+
+impl Default for LocalhostConfig {
+  fn default() -> Self {
+    Self {
+      port_start: default_localhost_port_start(),
+      port_end: default_localhost_port_end(),
+      auth_token: default_localhost_auth_token(),
+    }
+  }
+}
+
+fn default_localhost_port_start() -> u16 {
+  1440
+}
+
+fn default_localhost_port_end() -> u16 {
+  1449
+}
+
+fn default_localhost_auth_token() -> bool {
+  true
+}
+
+/// The localization configuration.
+///
+/// The translations are `<locale>.ftl` [Fluent](https://projectfluent.org) or `<locale>.json` files,
+/// such as `en-US.ftl` or `fr.json`, bundled as resources.
+/// Requires the `i18n` Cargo feature, enabled by the CLI when this configuration is set.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct I18nConfig {
+  /// The directory of the translation files, relative to the `src-tauri` directory. Defaults to `locales`.
+  #[serde(default = "default_i18n_directory")]
+  pub directory: PathBuf,
+  /// The locale used when the system locale has no translations, and for the keys missing on the other locales. Defaults to `en`.
+  #[serde(default = "default_i18n_default_locale", alias = "default-locale")]
+  pub default_locale: String,
+}
+
+impl Default for I18nConfig {
+  fn default() -> Self {
+    Self {
+      directory: default_i18n_directory(),
+      default_locale: default_i18n_default_locale(),
+    }
+  }
+}
+
+impl I18nConfig {
+  /// The resource pattern matching the translation files.
+  pub fn resource_pattern(&self) -> String {
+    format!("{}/*", self.directory.display())
+  }
+}
+
+fn default_i18n_directory() -> PathBuf {
+  "locales".into()
+}
+
+fn default_i18n_default_locale() -> String {
+  "en".into()
+}
+
 /// Configuration for application system tray icon.
 #[skip_serializing_none]
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
@@ -2484,7 +3541,7 @@ fn default_dialog() -> bool {
 pub enum AppUrl {
   /// The app's external URL, or the path to the directory containing the app assets.
   Url(WindowUrl),
-  /// An array of files to embed on the app.
+  /// An array of files and directories to embed on the app, where later entries override the files of earlier ones.
   Files(Vec<PathBuf>),
 }
 
@@ -2557,6 +3614,8 @@ pub struct BuildConfig {
   ///
   /// You can also provide a list of paths to be embedded, which allows granular control over what files are added to the binary.
   /// In this case, all files are added to the root and you must reference it that way in your HTML files.
+  /// Directories in the list are embedded recursively and overlayed in order,
+  /// so a file in a later directory overrides the file with the same path in an earlier one.
   ///
   /// When an URL is provided, the application won't have bundled assets
   /// and the application will load that URL by default.
@@ -2728,6 +3787,12 @@ impl PackageConfig {
 /// (or `Tauri.linux.toml`, `Tauri.windows.toml` and `Tauri.macos.toml` if the `Tauri.toml` format is used),
 /// which gets merged with the main configuration object.
 ///
+/// ## Profiles
+///
+/// The `profiles` object maps a profile name to a configuration object
+/// that is merged over the base configuration when the profile is selected with the CLI `--profile` option.
+/// `tauri dev` selects the `dev` profile and `tauri build` selects the `release` profile by default.
+///
 /// ## Configuration Structure
 ///
 /// The configuration is composed of the following objects:
@@ -2780,6 +3845,11 @@ pub struct Config {
   /// The JSON schema for the Tauri config.
   #[serde(rename = "$schema")]
   pub schema: Option<String>,
+  /// The configuration files to extend, merged in order under this configuration.
+  ///
+  /// Paths are relative to the file that declares them.
+  /// The field is resolved when the configuration is read, so it is always empty at runtime.
+  pub extends: Option<ConfigExtends>,
   /// Package settings.
   #[serde(default)]
   pub package: PackageConfig,
@@ -2792,6 +3862,32 @@ pub struct Config {
   /// The plugins config.
   #[serde(default)]
   pub plugins: PluginConfig,
+  /// The configuration profiles, mapping a profile name to the configuration merged over the base configuration.
+  ///
+  /// The selected profile is applied by the CLI and the codegen, so it is always empty at runtime.
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub profiles: HashMap<String, JsonValue>,
+}
+
+/// The configuration files extended by a configuration file.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(untagged)]
+pub enum ConfigExtends {
+  /// A single configuration file path.
+  One(PathBuf),
+  /// A list of configuration file paths.
+  Many(Vec<PathBuf>),
+}
+
+impl ConfigExtends {
+  /// The list of extended configuration file paths.
+  pub fn paths(&self) -> &[PathBuf] {
+    match self {
+      Self::One(path) => std::slice::from_ref(path),
+      Self::Many(paths) => paths,
+    }
+  }
 }
 
 /// The plugin configs holds a HashMap mapping a plugin name to its configuration object.
@@ -3040,12 +4136,18 @@ mod build {
       let visible = self.visible;
       let decorations = self.decorations;
       let always_on_top = self.always_on_top;
+      let kiosk = self.kiosk;
       let skip_taskbar = self.skip_taskbar;
       let theme = opt_lit(self.theme.as_ref());
       let title_bar_style = &self.title_bar_style;
       let hidden_title = self.hidden_title;
       let accept_first_mouse = self.accept_first_mouse;
       let tabbing_identifier = opt_str_lit(self.tabbing_identifier.as_ref());
+      let csp = opt_lit(self.csp.as_ref());
+      let hardware_acceleration = self.hardware_acceleration;
+      let gpu_compositing = self.gpu_compositing;
+      let webgl = self.webgl;
+      let accept_drop_types = vec_lit(&self.accept_drop_types, str_lit);
 
       literal_struct!(
         tokens,
@@ -3072,12 +4174,18 @@ mod build {
         visible,
         decorations,
         always_on_top,
+        kiosk,
         skip_taskbar,
         theme,
         title_bar_style,
         hidden_title,
         accept_first_mouse,
-        tabbing_identifier
+        tabbing_identifier,
+        csp,
+        hardware_acceleration,
+        gpu_compositing,
+        webgl,
+        accept_drop_types
       );
     }
   }
@@ -3186,11 +4294,11 @@ mod build {
       tokens.append_all(match self {
         Self::Brownfield => quote! { #prefix::Brownfield },
         #[cfg(not(feature = "isolation"))]
-        Self::Isolation { dir: _ } => quote! { #prefix::Brownfield },
+        Self::Isolation { .. } => quote! { #prefix::Brownfield },
         #[cfg(feature = "isolation")]
-        Self::Isolation { dir } => {
+        Self::Isolation { dir, rotate_key } => {
           let dir = path_buf_lit(dir);
-          quote! { #prefix::Isolation { dir: #dir } }
+          quote! { #prefix::Isolation { dir: #dir, rotate_key: #rotate_key } }
         }
       })
     }
@@ -3251,8 +4359,11 @@ mod build {
       let appimage = quote!(Default::default());
       let deb = quote!(Default::default());
       let macos = quote!(Default::default());
+      let ios = quote!(Default::default());
       let external_bin = opt_vec_str_lit(self.external_bin.as_ref());
       let windows = &self.windows;
+      let android = quote!(Default::default());
+      let toolchain = quote!(None);
 
       literal_struct!(
         tokens,
@@ -3270,8 +4381,11 @@ mod build {
         appimage,
         deb,
         macos,
+        ios,
         external_bin,
-        windows
+        windows,
+        android,
+        toolchain
       );
     }
   }
@@ -3429,6 +4543,9 @@ mod build {
       let dev_csp = opt_lit(self.dev_csp.as_ref());
       let freeze_prototype = self.freeze_prototype;
       let dangerous_disable_asset_csp_modification = &self.dangerous_disable_asset_csp_modification;
+      let command_scope = vec_lit(&self.command_scope, identity);
+      let plugin_permissions = vec_lit(&self.plugin_permissions, identity);
+      let symlink_policy = &self.symlink_policy;
 
       literal_struct!(
         tokens,
@@ -3436,11 +4553,128 @@ mod build {
         csp,
         dev_csp,
         freeze_prototype,
-        dangerous_disable_asset_csp_modification
+        dangerous_disable_asset_csp_modification,
+        command_scope,
+        plugin_permissions,
+        symlink_policy
+      );
+    }
+  }
+
+  impl ToTokens for SymlinkPolicy {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::SymlinkPolicy };
+
+      tokens.append_all(match self {
+        Self::Follow => quote! { #prefix::Follow },
+        Self::DenyEscape => quote! { #prefix::DenyEscape },
+        Self::Deny => quote! { #prefix::Deny },
+      })
+    }
+  }
+
+  impl ToTokens for CommandScopeEntry {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let windows = vec_lit(&self.windows, str_lit);
+      let origins = vec_lit(&self.origins, str_lit);
+      let commands = vec_lit(&self.commands, str_lit);
+
+      literal_struct!(tokens, CommandScopeEntry, windows, origins, commands);
+    }
+  }
+
+  impl ToTokens for PluginPermissionEntry {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let windows = vec_lit(&self.windows, str_lit);
+      let permissions = vec_lit(&self.permissions, str_lit);
+
+      literal_struct!(tokens, PluginPermissionEntry, windows, permissions);
+    }
+  }
+
+  impl ToTokens for SplashscreenConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let url = &self.url;
+      let width = self.width;
+      let height = self.height;
+      let transparent = self.transparent;
+      let always_on_top = self.always_on_top;
+      let main_window = str_lit(&self.main_window);
+      let auto_close = self.auto_close;
+      let fade_out_duration = self.fade_out_duration;
+      let fade_in_duration = self.fade_in_duration;
+
+      literal_struct!(
+        tokens,
+        SplashscreenConfig,
+        url,
+        width,
+        height,
+        transparent,
+        always_on_top,
+        main_window,
+        auto_close,
+        fade_out_duration,
+        fade_in_duration
+      );
+    }
+  }
+
+  impl ToTokens for LogLevel {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::LogLevel };
+
+      tokens.append_all(match self {
+        Self::Trace => quote! { #prefix::Trace },
+        Self::Debug => quote! { #prefix::Debug },
+        Self::Info => quote! { #prefix::Info },
+        Self::Warn => quote! { #prefix::Warn },
+        Self::Error => quote! { #prefix::Error },
+      })
+    }
+  }
+
+  impl ToTokens for LogConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let level = &self.level;
+      let file = self.file;
+      let stdout = self.stdout;
+      let max_file_size = self.max_file_size;
+      let max_files = self.max_files;
+      let webview_console = self.webview_console;
+
+      literal_struct!(
+        tokens,
+        LogConfig,
+        level,
+        file,
+        stdout,
+        max_file_size,
+        max_files,
+        webview_console
       );
     }
   }
 
+  impl ToTokens for LocalhostConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let port_start = self.port_start;
+      let port_end = self.port_end;
+      let auth_token = self.auth_token;
+
+      literal_struct!(tokens, LocalhostConfig, port_start, port_end, auth_token);
+    }
+  }
+
+  impl ToTokens for I18nConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let directory = path_buf_lit(&self.directory);
+      let default_locale = str_lit(&self.default_locale);
+
+      literal_struct!(tokens, I18nConfig, directory, default_locale);
+    }
+  }
+
   impl ToTokens for SystemTrayConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let icon_as_template = self.icon_as_template;
@@ -3504,14 +4738,118 @@ mod build {
     }
   }
 
+  impl ToTokens for WebsocketAllowlistScope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let allowed_urls = vec_lit(&self.0, url_lit);
+      tokens.append_all(quote! { ::tauri::utils::config::WebsocketAllowlistScope(#allowed_urls) })
+    }
+  }
+
+  impl ToTokens for WebsocketAllowlistConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let scope = &self.scope;
+      tokens.append_all(quote! { ::tauri::utils::config::WebsocketAllowlistConfig { scope: #scope, ..Default::default() } })
+    }
+  }
+
+  impl ToTokens for SerialAllowlistScope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let allowed_ports = vec_lit(&self.0, str_lit);
+      tokens.append_all(quote! { ::tauri::utils::config::SerialAllowlistScope(#allowed_ports) })
+    }
+  }
+
+  impl ToTokens for SerialAllowlistConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let scope = &self.scope;
+      tokens.append_all(quote! { ::tauri::utils::config::SerialAllowlistConfig { scope: #scope, ..Default::default() } })
+    }
+  }
+
+  impl ToTokens for MdnsAllowlistScope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let allowed_service_types = vec_lit(&self.0, str_lit);
+      tokens
+        .append_all(quote! { ::tauri::utils::config::MdnsAllowlistScope(#allowed_service_types) })
+    }
+  }
+
+  impl ToTokens for MdnsAllowlistConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let scope = &self.scope;
+      tokens.append_all(quote! { ::tauri::utils::config::MdnsAllowlistConfig { scope: #scope, ..Default::default() } })
+    }
+  }
+
+  impl ToTokens for StoreAllowlistScope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let allowed_stores = vec_lit(&self.0, str_lit);
+      tokens.append_all(quote! { ::tauri::utils::config::StoreAllowlistScope(#allowed_stores) })
+    }
+  }
+
+  impl ToTokens for StoreAllowlistConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let scope = &self.scope;
+      tokens.append_all(quote! { ::tauri::utils::config::StoreAllowlistConfig { scope: #scope, ..Default::default() } })
+    }
+  }
+
+  impl ToTokens for OAuthAllowlistScope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let allowed_urls = vec_lit(&self.0, url_lit);
+      tokens.append_all(quote! { ::tauri::utils::config::OAuthAllowlistScope(#allowed_urls) })
+    }
+  }
+
+  impl ToTokens for OAuthAllowlistConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let scope = &self.scope;
+      tokens.append_all(quote! { ::tauri::utils::config::OAuthAllowlistConfig { scope: #scope, ..Default::default() } })
+    }
+  }
+
   impl ToTokens for ShellAllowedCommand {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let name = str_lit(&self.name);
       let command = path_buf_lit(&self.command);
       let args = &self.args;
       let sidecar = &self.sidecar;
+      let platforms = &self.platforms;
+
+      literal_struct!(
+        tokens,
+        ShellAllowedCommand,
+        name,
+        command,
+        args,
+        sidecar,
+        platforms
+      );
+    }
+  }
+
+  impl ToTokens for ShellCommandPlatforms {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let linux = opt_lit(self.linux.as_ref());
+      let macos = opt_lit(self.macos.as_ref());
+      let windows = opt_lit(self.windows.as_ref());
 
-      literal_struct!(tokens, ShellAllowedCommand, name, command, args, sidecar);
+      literal_struct!(tokens, ShellCommandPlatforms, linux, macos, windows);
+    }
+  }
+
+  impl ToTokens for ShellCommandDefaults {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let args = vec_lit(&self.args, str_lit);
+      let env = map_lit(
+        quote! { ::std::collections::HashMap },
+        &self.env,
+        str_lit,
+        str_lit,
+      );
+
+      literal_struct!(tokens, ShellCommandDefaults, args, env);
     }
   }
 
@@ -3576,9 +4914,14 @@ mod build {
       let fs = &self.fs;
       let protocol = &self.protocol;
       let http = &self.http;
+      let websocket = &self.websocket;
+      let serial = &self.serial;
+      let mdns = &self.mdns;
+      let store = &self.store;
+      let oauth = &self.oauth;
       let shell = &self.shell;
       tokens.append_all(
-        quote! { ::tauri::utils::config::AllowlistConfig { fs: #fs, protocol: #protocol, http: #http, shell: #shell, ..Default::default() } },
+        quote! { ::tauri::utils::config::AllowlistConfig { fs: #fs, protocol: #protocol, http: #http, websocket: #websocket, serial: #serial, mdns: #mdns, store: #store, oauth: #oauth, shell: #shell, ..Default::default() } },
       )
     }
   }
@@ -3587,11 +4930,15 @@ mod build {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let pattern = &self.pattern;
       let windows = vec_lit(&self.windows, identity);
+      let splashscreen = opt_lit(self.splashscreen.as_ref());
       let cli = opt_lit(self.cli.as_ref());
       let bundle = &self.bundle;
       let updater = &self.updater;
       let security = &self.security;
       let system_tray = opt_lit(self.system_tray.as_ref());
+      let log = opt_lit(self.log.as_ref());
+      let localhost = opt_lit(self.localhost.as_ref());
+      let i18n = opt_lit(self.i18n.as_ref());
       let allowlist = &self.allowlist;
       let macos_private_api = self.macos_private_api;
 
@@ -3600,11 +4947,15 @@ mod build {
         TauriConfig,
         pattern,
         windows,
+        splashscreen,
         cli,
         bundle,
         updater,
         security,
         system_tray,
+        log,
+        localhost,
+        i18n,
         allowlist,
         macos_private_api
       );
@@ -3635,12 +4986,14 @@ mod build {
   impl ToTokens for Config {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let schema = quote!(None);
+      let extends = quote!(None);
       let package = &self.package;
       let tauri = &self.tauri;
       let build = &self.build;
       let plugins = &self.plugins;
+      let profiles = quote!(Default::default());
 
-      literal_struct!(tokens, Config, schema, package, tauri, build, plugins);
+      literal_struct!(tokens, Config, schema, extends, package, tauri, build, plugins, profiles);
     }
   }
 }
@@ -3671,6 +5024,7 @@ mod test {
     let tauri = TauriConfig {
       pattern: Default::default(),
       windows: vec![],
+      splashscreen: None,
       bundle: BundleConfig {
         active: false,
         targets: Default::default(),
@@ -3685,8 +5039,11 @@ mod test {
         appimage: Default::default(),
         deb: Default::default(),
         macos: Default::default(),
+        ios: Default::default(),
         external_bin: None,
         windows: Default::default(),
+        android: Default::default(),
+        toolchain: None,
       },
       cli: None,
       updater: UpdaterConfig {
@@ -3701,9 +5058,15 @@ mod test {
         dev_csp: None,
         freeze_prototype: false,
         dangerous_disable_asset_csp_modification: DisabledCspModificationKind::Flag(false),
+        command_scope: Vec::new(),
+        plugin_permissions: Vec::new(),
+        symlink_policy: SymlinkPolicy::Follow,
       },
       allowlist: AllowlistConfig::default(),
       system_tray: None,
+      log: None,
+      localhost: None,
+      i18n: None,
       macos_private_api: false,
     };
 
@@ -3734,4 +5097,101 @@ mod test {
     );
     assert_eq!(d_windows, tauri.windows);
   }
+
+  #[test]
+  fn shell_command_platforms() {
+    let command: ShellAllowedCommand = serde_json::from_value(serde_json::json!({
+      "name": "binaries/server",
+      "sidecar": true,
+      "platforms": {
+        "windows": { "args": ["--data", "$APPDATA/server"] },
+        "linux": { "env": { "SERVER_DATA": "$APPDATA/server" } }
+      }
+    }))
+    .unwrap();
+
+    assert_eq!(
+      command.platforms.windows.unwrap().args,
+      vec!["--data".to_string(), "$APPDATA/server".to_string()]
+    );
+    assert_eq!(
+      command.platforms.linux.unwrap().env.get("SERVER_DATA"),
+      Some(&"$APPDATA/server".to_string())
+    );
+    assert!(command.platforms.macos.is_none());
+  }
+
+  #[test]
+  fn webview_fixed_runtime_path() {
+    let mut windows = WindowsConfig::default();
+    assert_eq!(windows.fixed_runtime_path(), None);
+
+    windows.webview_install_mode = WebviewInstallMode::FixedRuntime {
+      path: "./Microsoft.WebView2.FixedVersionRuntime.x64".into(),
+    };
+    assert_eq!(
+      windows.fixed_runtime_path(),
+      Some(Path::new("./Microsoft.WebView2.FixedVersionRuntime.x64"))
+    );
+
+    // the deprecated option takes precedence
+    windows.webview_fixed_runtime_path = Some("../webview2".into());
+    assert_eq!(windows.fixed_runtime_path(), Some(Path::new("../webview2")));
+  }
+
+  #[test]
+  fn window_gpu_options() {
+    let window: WindowConfig = serde_json::from_str("{}").unwrap();
+    assert!(window.hardware_acceleration);
+    assert!(window.gpu_compositing);
+    assert!(window.webgl);
+
+    let window: WindowConfig = serde_json::from_value(serde_json::json!({
+      "hardware-acceleration": false,
+      "gpuCompositing": false,
+      "webgl": false
+    }))
+    .unwrap();
+    assert!(!window.hardware_acceleration);
+    assert!(!window.gpu_compositing);
+    assert!(!window.webgl);
+  }
+
+  #[test]
+  fn window_kiosk() {
+    let window: WindowConfig = serde_json::from_str("{}").unwrap();
+    assert!(!window.kiosk);
+    assert!(!WindowConfig::default().kiosk);
+
+    let window: WindowConfig = serde_json::from_str(r#"{ "kiosk": true }"#).unwrap();
+    assert!(window.kiosk);
+  }
+
+  #[test]
+  fn nsis_config() {
+    let windows: WindowsConfig = serde_json::from_value(serde_json::json!({
+      "nsis": {
+        "installMode": "both",
+        "preservedPaths": ["data", "LICENSE.txt"],
+        "preservedRegistryKeys": ["Settings"]
+      }
+    }))
+    .unwrap();
+    let nsis = windows.nsis.unwrap();
+    assert_eq!(nsis.install_mode, NsisInstallMode::Both);
+    assert_eq!(
+      nsis.preserved_paths,
+      vec![PathBuf::from("data"), PathBuf::from("LICENSE.txt")]
+    );
+    assert_eq!(nsis.preserved_registry_keys, vec!["Settings".to_string()]);
+
+    let nsis: NsisConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(nsis.install_mode, NsisInstallMode::CurrentUser);
+    assert!(nsis.preserved_paths.is_empty());
+
+    assert_eq!(
+      serde_json::from_str::<BundleType>("\"nsis\"").unwrap(),
+      BundleType::Nsis
+    );
+  }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::config::Config;
+use crate::config::{Config, ConfigExtends};
 use json_patch::merge;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
@@ -130,6 +130,18 @@ pub enum ConfigError {
     feature: String,
   },
 
+  /// A config file extends itself, directly or through the files it extends.
+  #[error("Tauri config file at {0} extends itself")]
+  RecursiveExtends(PathBuf),
+
+  /// An environment variable referenced by the config without a default value is not set.
+  #[error("environment variable `{0}` referenced by the Tauri config is not set")]
+  MissingEnvVar(String),
+
+  /// The selected configuration profile is not defined in the `profiles` object.
+  #[error("configuration profile `{0}` is not defined in the `profiles` object")]
+  UndefinedProfile(String),
+
   /// A generic IO error with context of what caused it.
   #[error("unable to read Tauri config file at {path} because {error}")]
   Io {
@@ -179,11 +191,112 @@ pub fn is_configuration_file(path: &Path) -> bool {
 ///
 /// [JSON Merge Patch (RFC 7396)]: https://datatracker.ietf.org/doc/html/rfc7396.
 pub fn read_from(root_dir: PathBuf) -> Result<Value, ConfigError> {
-  let mut config: Value = parse_value(root_dir.join("tauri.conf.json"))?.0;
-  if let Some((platform_config, _)) = read_platform(root_dir)? {
+  read_from_with_extends(root_dir).map(|(config, _)| config)
+}
+
+/// Reads the configuration like [`read_from`], also returning the paths of the files listed on the `extends` fields,
+/// so build scripts can rerun when they change.
+pub fn read_from_with_extends(root_dir: PathBuf) -> Result<(Value, Vec<PathBuf>), ConfigError> {
+  let mut extended = Vec::new();
+  let (mut config, _) = parse_value_with_extends(root_dir.join("tauri.conf.json"), &mut extended)?;
+  let platform_config_path = root_dir.join(ConfigFormat::Json.into_platform_file_name());
+  if does_supported_file_name_exist(&platform_config_path) {
+    let (platform_config, _) = parse_value_with_extends(platform_config_path, &mut extended)?;
     merge(&mut config, &platform_config);
   }
-  Ok(config)
+  Ok((config, extended))
+}
+
+/// The profiles selected by default by `tauri dev` and `tauri build`, which do not need to be defined.
+pub const DEFAULT_PROFILES: &[&str] = &["dev", "release"];
+
+/// Removes the `profiles` object from the configuration, merging the given profile over it
+/// with [JSON Merge Patch (RFC 7396)].
+///
+/// Errors if the profile is not defined, unless it is one of the [`DEFAULT_PROFILES`].
+///
+/// [JSON Merge Patch (RFC 7396)]: https://datatracker.ietf.org/doc/html/rfc7396.
+pub fn apply_profile(config: &mut Value, profile: Option<&str>) -> Result<(), ConfigError> {
+  let profiles = config.as_object_mut().and_then(|c| c.remove("profiles"));
+  match profile {
+    Some(profile) => match profiles.as_ref().and_then(|p| p.get(profile)) {
+      Some(profile_config) => {
+        merge(config, profile_config);
+        Ok(())
+      }
+      None if DEFAULT_PROFILES.contains(&profile) => Ok(()),
+      None => Err(ConfigError::UndefinedProfile(profile.into())),
+    },
+    None => Ok(()),
+  }
+}
+
+/// Replaces the `${NAME}` references on the configuration string values with the value of the `NAME` environment variable.
+///
+/// A default can be set with `${NAME:-default}`, used when the variable is not set or is empty.
+/// Use `$${NAME}` to keep a literal `${NAME}` on the value.
+///
+/// Returns the names of the referenced variables, so build scripts can rerun when they change.
+pub fn interpolate_env(config: &mut Value) -> Result<Vec<String>, ConfigError> {
+  let mut names = Vec::new();
+  interpolate_env_value(config, &mut names)?;
+  Ok(names)
+}
+
+fn interpolate_env_value(config: &mut Value, names: &mut Vec<String>) -> Result<(), ConfigError> {
+  match config {
+    Value::String(value) if value.contains("${") => *value = interpolate_env_str(value, names)?,
+    Value::Array(values) => {
+      for value in values {
+        interpolate_env_value(value, names)?;
+      }
+    }
+    Value::Object(values) => {
+      for value in values.values_mut() {
+        interpolate_env_value(value, names)?;
+      }
+    }
+    _ => {}
+  }
+  Ok(())
+}
+
+fn interpolate_env_str(value: &str, names: &mut Vec<String>) -> Result<String, ConfigError> {
+  let mut interpolated = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(start) = rest.find('$') {
+    interpolated.push_str(&rest[..start]);
+    rest = &rest[start..];
+    if let Some(escaped) = rest.strip_prefix("$${") {
+      interpolated.push_str("${");
+      rest = escaped;
+    } else if let Some((expr, remaining)) = rest
+      .strip_prefix("${")
+      .and_then(|r| r.find('}').map(|end| (&r[..end], &r[end + 1..])))
+    {
+      let (name, default) = match expr.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (expr, None),
+      };
+      if !names.iter().any(|n| n == name) {
+        names.push(name.into());
+      }
+      let var = std::env::var(name)
+        .ok()
+        .filter(|v| default.is_none() || !v.is_empty());
+      match (var, default) {
+        (Some(var), _) => interpolated.push_str(&var),
+        (None, Some(default)) => interpolated.push_str(default),
+        (None, None) => return Err(ConfigError::MissingEnvVar(name.into())),
+      }
+      rest = remaining;
+    } else {
+      interpolated.push('$');
+      rest = &rest[1..];
+    }
+  }
+  interpolated.push_str(rest);
+  Ok(interpolated)
 }
 
 /// Reads the platform-specific configuration file from the given root directory if it exists.
@@ -234,13 +347,100 @@ pub fn does_supported_file_name_exist(path: impl Into<PathBuf>) -> bool {
 ///   a. Parse it with `toml`
 ///   b. Return error if all above steps failed
 /// 4. Return error if all above steps failed
+///
+/// The files listed on the `extends` field are merged in order under the configuration.
 pub fn parse(path: impl Into<PathBuf>) -> Result<(Config, PathBuf), ConfigError> {
-  do_parse(path.into())
+  let (config, path) = parse_value(path)?;
+  serde_json::from_value(config)
+    .map(|config| (config, path.clone()))
+    .map_err(|error| ConfigError::FormatJson { path, error })
 }
 
 /// See [`parse`] for specifics, returns a JSON [`Value`] instead of [`Config`].
 pub fn parse_value(path: impl Into<PathBuf>) -> Result<(Value, PathBuf), ConfigError> {
-  do_parse(path.into())
+  parse_value_with_extends(path.into(), &mut Vec::new())
+}
+
+/// See [`parse_value`], pushing the paths of the extended files to `extended`.
+fn parse_value_with_extends(
+  path: PathBuf,
+  extended: &mut Vec<PathBuf>,
+) -> Result<(Value, PathBuf), ConfigError> {
+  let (mut config, path) = do_parse(path)?;
+  resolve_extends(&mut config, &path, &mut Vec::new(), extended)?;
+  Ok((config, path))
+}
+
+/// Merges the files listed on the `extends` field of the configuration read from `path` under it,
+/// removing the field.
+///
+/// `stack` holds the canonical paths of the files being resolved, to detect recursive extends.
+/// The paths of the extended files are pushed to `extended`.
+fn resolve_extends(
+  config: &mut Value,
+  path: &Path,
+  stack: &mut Vec<PathBuf>,
+  extended: &mut Vec<PathBuf>,
+) -> Result<(), ConfigError> {
+  let extends = match config.as_object_mut().and_then(|c| c.remove("extends")) {
+    Some(extends) => extends,
+    None => return Ok(()),
+  };
+  let extends: ConfigExtends =
+    serde_json::from_value(extends).map_err(|error| ConfigError::FormatJson {
+      path: path.into(),
+      error,
+    })?;
+
+  stack.push(canonicalize(path)?);
+  let mut base = Value::Object(Default::default());
+  for extended_path in extends.paths() {
+    let extended_path = path
+      .parent()
+      .map(|dir| dir.join(extended_path))
+      .unwrap_or_else(|| extended_path.clone());
+    if stack.contains(&canonicalize(&extended_path)?) {
+      return Err(ConfigError::RecursiveExtends(extended_path));
+    }
+    let mut extended_config = parse_file(&extended_path)?;
+    resolve_extends(&mut extended_config, &extended_path, stack, extended)?;
+    merge(&mut base, &extended_config);
+    extended.push(extended_path);
+  }
+  stack.pop();
+
+  merge(&mut base, config);
+  *config = base;
+  Ok(())
+}
+
+/// Parses the file at the given path with the format matching its extension.
+fn parse_file(path: &Path) -> Result<Value, ConfigError> {
+  let raw = read_to_string(path)?;
+  match path.extension().and_then(OsStr::to_str) {
+    #[cfg(feature = "config-json5")]
+    Some("json5") => do_parse_json5(&raw, path),
+    #[cfg(not(feature = "config-json5"))]
+    Some("json5") => Err(ConfigError::DisabledFormat {
+      extension: ".json5".into(),
+      feature: "config-json5".into(),
+    }),
+    #[cfg(feature = "config-toml")]
+    Some("toml") => do_parse_toml(&raw, path),
+    #[cfg(not(feature = "config-toml"))]
+    Some("toml") => Err(ConfigError::DisabledFormat {
+      extension: ".toml".into(),
+      feature: "config-toml".into(),
+    }),
+    _ => do_parse_json(&raw, path),
+  }
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, ConfigError> {
+  path.canonicalize().map_err(|error| ConfigError::Io {
+    path: path.into(),
+    error,
+  })
 }
 
 fn do_parse<D: DeserializeOwned>(path: PathBuf) -> Result<(D, PathBuf), ConfigError> {
@@ -386,3 +586,137 @@ fn read_to_string(path: &Path) -> Result<String, ConfigError> {
     error,
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  #[test]
+  fn extends() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    std::fs::create_dir_all(dir.join("app")).unwrap();
+    std::fs::write(
+      dir.join("base.json"),
+      r#"{ "package": { "version": "1.0.0" }, "tauri": { "bundle": { "publisher": "Tauri" } } }"#,
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("identity.json"),
+      r#"{ "extends": "base.json", "tauri": { "bundle": { "identifier": "app.tauri" } } }"#,
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("app/tauri.conf.json"),
+      r#"{ "extends": ["../identity.json"], "package": { "productName": "app" } }"#,
+    )
+    .unwrap();
+
+    let (config, extended) = super::read_from_with_extends(dir.join("app")).unwrap();
+    assert_eq!(
+      config,
+      json!({
+        "package": { "productName": "app", "version": "1.0.0" },
+        "tauri": { "bundle": { "identifier": "app.tauri", "publisher": "Tauri" } }
+      })
+    );
+    assert_eq!(
+      extended,
+      vec![
+        dir.join("app/../base.json"),
+        dir.join("app/../identity.json")
+      ]
+    );
+
+    std::fs::write(
+      dir.join("base.json"),
+      r#"{ "extends": "app/tauri.conf.json" }"#,
+    )
+    .unwrap();
+    assert!(matches!(
+      super::parse_value(dir.join("app/tauri.conf.json")),
+      Err(super::ConfigError::RecursiveExtends(_))
+    ));
+  }
+
+  #[test]
+  fn apply_profile() {
+    let config = json!({
+      "package": { "productName": "app" },
+      "tauri": { "updater": { "endpoints": ["https://example.com"] } },
+      "profiles": {
+        "staging": {
+          "package": { "productName": "app-staging" },
+          "tauri": { "updater": { "endpoints": ["https://staging.example.com"] } }
+        }
+      }
+    });
+
+    let mut staging = config.clone();
+    super::apply_profile(&mut staging, Some("staging")).unwrap();
+    assert_eq!(
+      staging,
+      json!({
+        "package": { "productName": "app-staging" },
+        "tauri": { "updater": { "endpoints": ["https://staging.example.com"] } }
+      })
+    );
+
+    let mut release = config.clone();
+    super::apply_profile(&mut release, Some("release")).unwrap();
+    assert_eq!(
+      release,
+      json!({
+        "package": { "productName": "app" },
+        "tauri": { "updater": { "endpoints": ["https://example.com"] } }
+      })
+    );
+
+    let mut config = config;
+    assert!(matches!(
+      super::apply_profile(&mut config, Some("production")),
+      Err(super::ConfigError::UndefinedProfile(profile)) if profile == "production"
+    ));
+  }
+
+  #[test]
+  fn interpolate_env() {
+    std::env::set_var("TAURI_TEST_INTERPOLATE_NAME", "Tauri Staging");
+    std::env::set_var("TAURI_TEST_INTERPOLATE_EMPTY", "");
+
+    let mut config = json!({
+      "package": { "productName": "${TAURI_TEST_INTERPOLATE_NAME}" },
+      "tauri": {
+        "allowlist": { "fs": { "scope": ["$APPDATA/*", "$${HOME}"] } },
+        "updater": {
+          "endpoints": ["https://${TAURI_TEST_INTERPOLATE_HOST:-releases.example.com}/${TAURI_TEST_INTERPOLATE_EMPTY:-stable}"]
+        }
+      }
+    });
+    let names = super::interpolate_env(&mut config).unwrap();
+    assert_eq!(
+      names,
+      vec![
+        "TAURI_TEST_INTERPOLATE_NAME",
+        "TAURI_TEST_INTERPOLATE_HOST",
+        "TAURI_TEST_INTERPOLATE_EMPTY"
+      ]
+    );
+    assert_eq!(
+      config,
+      json!({
+        "package": { "productName": "Tauri Staging" },
+        "tauri": {
+          "allowlist": { "fs": { "scope": ["$APPDATA/*", "${HOME}"] } },
+          "updater": { "endpoints": ["https://releases.example.com/stable"] }
+        }
+      })
+    );
+
+    let mut config = json!({ "package": { "version": "${TAURI_TEST_INTERPOLATE_MISSING}" } });
+    assert!(matches!(
+      super::interpolate_env(&mut config),
+      Err(super::ConfigError::MissingEnvVar(name)) if name == "TAURI_TEST_INTERPOLATE_MISSING"
+    ));
+  }
+}
@@ -0,0 +1,169 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Generates the application icons from a single source image.
+//!
+//! Used by the `tauri icon` command and the `icons` feature of `tauri-build`.
+
+use std::{
+  fs::File,
+  io::{BufWriter, Write},
+  path::Path,
+};
+
+use icns::{IconFamily, IconType};
+pub use image::DynamicImage;
+use image::{
+  codecs::{
+    ico::{IcoEncoder, IcoFrame},
+    png::{CompressionType, FilterType as PngFilterType, PngEncoder},
+  },
+  imageops::{overlay, FilterType},
+  ColorType, ImageEncoder, RgbaImage,
+};
+
+/// The icon generation errors.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+  /// Failed to read or write an icon file.
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  /// Failed to decode or encode an image.
+  #[error(transparent)]
+  Image(#[from] image::ImageError),
+  /// The source image is not square.
+  #[error("the source image must be square")]
+  NotSquare,
+}
+
+/// Convenient type alias of Result type for the icon generation.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The `.icns` entries as (size, OSType) pairs.
+pub const ICNS_ENTRIES: &[(u32, &str)] = &[
+  (16, "is32"),
+  (32, "ic11"),
+  (32, "il32"),
+  (64, "ic12"),
+  (128, "ic07"),
+  (256, "ic13"),
+  (256, "ic08"),
+  (512, "ic14"),
+  (512, "ic09"),
+  (1024, "ic10"),
+];
+
+/// The layer sizes of the `icon.ico` app icon.
+pub const ICO_SIZES: &[u32] = &[32, 16, 24, 48, 64, 256];
+
+/// The PNG app icons as (size, file name) pairs, matching the default `tauri > bundle > icon` configuration.
+pub const PNG_ICONS: &[(u32, &str)] = &[
+  (32, "32x32.png"),
+  (128, "128x128.png"),
+  (256, "128x128@2x.png"),
+  (512, "icon.png"),
+];
+
+/// Decodes the square source image, in any of the PNG, JPEG, BMP and ICO formats.
+pub fn open(path: &Path) -> Result<DynamicImage> {
+  // convert to rgba8 so the encoders below always get 4 bytes per pixel
+  let source = DynamicImage::ImageRgba8(image::open(path)?.into_rgba8());
+  if source.height() != source.width() {
+    return Err(Error::NotSquare);
+  }
+  Ok(source)
+}
+
+/// Writes an `.icns` file with the [`ICNS_ENTRIES`].
+pub fn write_icns(source: &DynamicImage, file_path: &Path) -> Result<()> {
+  let mut family = IconFamily::new();
+
+  for (size, ostype) in ICNS_ENTRIES {
+    let mut buf = Vec::new();
+    let image = source.resize_exact(*size, *size, FilterType::Lanczos3);
+    write_png(image.as_bytes(), &mut buf, *size)?;
+
+    let image = icns::Image::read_png(&buf[..])?;
+    family.add_icon_with_type(
+      &image,
+      IconType::from_ostype(ostype.parse().unwrap()).unwrap(),
+    )?;
+  }
+
+  let mut out_file = BufWriter::new(File::create(file_path)?);
+  family.write(&mut out_file)?;
+  out_file.flush()?;
+
+  Ok(())
+}
+
+/// Writes an `.ico` file with a layer for each size.
+pub fn write_ico(source: &DynamicImage, sizes: &[u32], file_path: &Path) -> Result<()> {
+  let mut frames = Vec::new();
+
+  for &size in sizes {
+    let image = source.resize_exact(size, size, FilterType::Lanczos3);
+
+    // Only the 256px layer can be compressed according to the ico specs.
+    if size == 256 {
+      let mut buf = Vec::new();
+      write_png(image.as_bytes(), &mut buf, size)?;
+      frames.push(IcoFrame::with_encoded(buf, size, size, ColorType::Rgba8)?)
+    } else {
+      frames.push(IcoFrame::as_png(
+        image.as_bytes(),
+        size,
+        size,
+        ColorType::Rgba8,
+      )?);
+    }
+  }
+
+  let mut out_file = BufWriter::new(File::create(file_path)?);
+  let encoder = IcoEncoder::new(&mut out_file);
+  encoder.encode_images(&frames)?;
+  out_file.flush()?;
+
+  Ok(())
+}
+
+/// Resizes the image and saves it as PNG.
+pub fn resize_and_save_png(source: &DynamicImage, size: u32, file_path: &Path) -> Result<()> {
+  let image = source.resize_exact(size, size, FilterType::Lanczos3);
+  save_png(&image, file_path)
+}
+
+/// Saves a square image as PNG as is.
+pub fn save_png(image: &DynamicImage, file_path: &Path) -> Result<()> {
+  let mut out_file = BufWriter::new(File::create(file_path)?);
+  write_png(image.as_bytes(), &mut out_file, image.width())?;
+  out_file.flush()?;
+  Ok(())
+}
+
+/// Keeps only the shape of the image, filled with the given gray level.
+pub fn silhouette(source: &DynamicImage, luma: u8) -> DynamicImage {
+  let mut image = source.to_rgba8();
+  for pixel in image.pixels_mut() {
+    pixel.0 = [luma, luma, luma, pixel.0[3]];
+  }
+  DynamicImage::ImageRgba8(image)
+}
+
+/// Centers the resized image on a transparent square.
+pub fn padded(source: &DynamicImage, size: u32, content_size: u32) -> DynamicImage {
+  let mut image = RgbaImage::new(size, size);
+  let content = source.resize_exact(content_size, content_size, FilterType::Lanczos3);
+  let offset = ((size - content_size) / 2) as i64;
+  overlay(&mut image, &content.to_rgba8(), offset, offset);
+  DynamicImage::ImageRgba8(image)
+}
+
+/// Encodes the image data as PNG with compression.
+fn write_png<W: Write>(image_data: &[u8], w: W, size: u32) -> Result<()> {
+  let encoder = PngEncoder::new_with_quality(w, CompressionType::Best, PngFilterType::Adaptive);
+  encoder.write_image(image_data, size, size, ColorType::Rgba8)?;
+  Ok(())
+}
@@ -0,0 +1,167 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The manifest of what an application was built with, embedded in its binary.
+//!
+//! The manifest is stored between markers, so it can be found by reading the binary
+//! without running it, e.g. with the `tauri inspect` command.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, permissions::PermissionManifest};
+
+/// The command line flag that prints the inspect manifest and exits.
+pub const INSPECT_FLAG: &str = "--tauri-inspect";
+
+/// The marker preceding the manifest in the binary.
+pub const MANIFEST_START: &str = "__TAURI_INSPECT_MANIFEST_START__";
+
+/// The marker following the manifest in the binary.
+pub const MANIFEST_END: &str = "__TAURI_INSPECT_MANIFEST_END__";
+
+/// An asset embedded in the application.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AssetInfo {
+  /// The asset path.
+  pub path: String,
+  /// The size of the source file in bytes.
+  pub size: u64,
+  /// The size of the embedded data in bytes, after compression.
+  pub compressed_size: u64,
+}
+
+impl AssetInfo {
+  /// Creates the information of an asset.
+  pub fn new(path: impl Into<String>, size: u64, compressed_size: u64) -> Self {
+    Self {
+      path: path.into(),
+      size,
+      compressed_size,
+    }
+  }
+}
+
+/// The configuration, permissions and assets an application was built with.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InspectManifest<'a> {
+  /// The application configuration.
+  pub config: &'a Config,
+  /// The permissions granted to the webview.
+  pub permissions: PermissionManifest<'a>,
+  /// The embedded assets, sorted by path.
+  pub assets: Vec<AssetInfo>,
+}
+
+impl<'a> InspectManifest<'a> {
+  /// Creates the manifest of the given configuration and assets.
+  pub fn new(config: &'a Config, mut assets: Vec<AssetInfo>) -> Self {
+    assets.sort_by(|a, b| a.path.cmp(&b.path));
+    Self {
+      config,
+      permissions: PermissionManifest::new(config),
+      assets,
+    }
+  }
+
+  /// Serializes the manifest between its markers, ready to be embedded in the binary.
+  pub fn embed(&self) -> serde_json::Result<String> {
+    Ok(format!(
+      "{}{}{}",
+      MANIFEST_START,
+      serde_json::to_string(self)?,
+      MANIFEST_END
+    ))
+  }
+}
+
+/// Removes the markers of an embedded manifest.
+pub fn strip_markers(embedded: &str) -> &str {
+  embedded
+    .strip_prefix(MANIFEST_START)
+    .and_then(|m| m.strip_suffix(MANIFEST_END))
+    .unwrap_or(embedded)
+}
+
+/// Finds the manifest embedded in the contents of a binary.
+pub fn find(binary: &[u8]) -> Option<serde_json::Value> {
+  let start = MANIFEST_START.as_bytes();
+  let end = MANIFEST_END.as_bytes();
+  let mut offset = 0;
+  // the markers themselves may also be in the binary, so the first occurrence is not always the manifest
+  while let Some(position) = position(&binary[offset..], start) {
+    let manifest_start = offset + position + start.len();
+    if let Some(length) = position_of_end(&binary[manifest_start..], end) {
+      let manifest = &binary[manifest_start..manifest_start + length];
+      if let Ok(value) = serde_json::from_slice(manifest) {
+        return Some(value);
+      }
+    }
+    offset = manifest_start;
+  }
+  None
+}
+
+fn position(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn position_of_end(haystack: &[u8], end: &[u8]) -> Option<usize> {
+  // the manifest is a JSON object
+  if haystack.first() != Some(&b'{') {
+    return None;
+  }
+  position(haystack, end)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{find, strip_markers, AssetInfo, InspectManifest, MANIFEST_START};
+  use crate::config::Config;
+  use serde_json::json;
+
+  #[test]
+  fn finds_embedded_manifest() {
+    let config: Config = serde_json::from_value(json!({
+      "package": { "version": "1.0.0" },
+      "tauri": {
+        "updater": { "active": true, "pubkey": "dW50cnVzdGVkIGNvbW1lbnQ=" }
+      }
+    }))
+    .unwrap();
+    let manifest = InspectManifest::new(
+      &config,
+      vec![
+        AssetInfo::new("/main.js", 2048, 512),
+        AssetInfo::new("/index.html", 1024, 256),
+      ],
+    )
+    .embed()
+    .unwrap();
+
+    let mut binary = b"\x7fELF".to_vec();
+    binary.extend_from_slice(MANIFEST_START.as_bytes());
+    binary.extend_from_slice(b"\0\0");
+    binary.extend_from_slice(manifest.as_bytes());
+    binary.extend_from_slice(b"\0\0");
+
+    let found = find(&binary).unwrap();
+    assert_eq!(found["config"]["package"]["version"], "1.0.0");
+    assert_eq!(
+      found["config"]["tauri"]["updater"]["pubkey"],
+      "dW50cnVzdGVkIGNvbW1lbnQ="
+    );
+    assert_eq!(found["assets"][0]["path"], "/index.html");
+    assert_eq!(found["assets"][1]["compressedSize"], 512);
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(strip_markers(&manifest)).unwrap(),
+      found
+    );
+
+    assert!(find(b"no manifest").is_none());
+  }
+}
@@ -13,8 +13,12 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub mod assets;
 pub mod config;
 pub mod html;
+#[cfg(feature = "icons")]
+pub mod icons;
+pub mod inspect;
 pub mod io;
 pub mod mime_type;
+pub mod permissions;
 pub mod platform;
 /// Prepare application resources and sidecars.
 #[cfg(feature = "resources")]
@@ -0,0 +1,547 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The manifest of the permissions granted to the webview, generated from the configuration at build time.
+//!
+//! It is embedded in the application binary and printed by running it with the `--tauri-permissions` flag,
+//! so a shipped application can be audited without its source code.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::config::{
+  Allowlist, CommandScopeEntry, Config, FsAllowlistConfig, FsAllowlistScope, HttpAllowlistConfig,
+  PluginPermissionEntry, ProtocolAllowlistConfig, ShellAllowedCommand, ShellAllowlistOpen,
+  WindowUrl,
+};
+
+/// The command line flag that prints the permission manifest and exits.
+pub const PERMISSIONS_FLAG: &str = "--tauri-permissions";
+
+/// The permissions granted to the webview.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PermissionManifest<'a> {
+  /// The application identifier.
+  pub identifier: &'a str,
+  /// The application version.
+  pub version: Option<&'a str>,
+  /// The enabled API features, e.g. `fs-read-file`.
+  pub apis: Vec<&'a str>,
+  /// The IPC endpoint commands the webview can invoke, by module.
+  pub endpoints: BTreeMap<&'static str, Vec<&'static str>>,
+  /// The scopes of the enabled APIs.
+  pub scopes: Scopes<'a>,
+  /// The IPC command scope.
+  pub command_scope: &'a [CommandScopeEntry],
+  /// The permissions granted to plugins.
+  pub plugin_permissions: &'a [PluginPermissionEntry],
+  /// The origins of the remote URLs loaded by windows, requested by the HTTP API or allowed to use the IPC.
+  pub remote_domains: Vec<String>,
+  /// The Content Security Policy injected on the application assets.
+  pub csp: Option<String>,
+}
+
+/// The scopes of the enabled APIs. A scope is omitted when its API is not enabled.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Scopes<'a> {
+  /// The file system API scope.
+  pub fs: Option<&'a FsAllowlistScope>,
+  /// The asset protocol scope.
+  pub asset_protocol: Option<&'a FsAllowlistScope>,
+  /// The URLs the HTTP API can request.
+  pub http: Option<&'a [Url]>,
+  /// The programs the shell API can execute.
+  pub shell: Option<&'a [ShellAllowedCommand]>,
+  /// The validation of the paths opened with the shell API.
+  pub shell_open: Option<&'a ShellAllowlistOpen>,
+}
+
+impl<'a> PermissionManifest<'a> {
+  /// Creates the manifest of the given configuration.
+  pub fn new(config: &'a Config) -> Self {
+    let tauri = &config.tauri;
+    let allowlist = &tauri.allowlist;
+    let apis = tauri.features();
+    let enabled = |features: &[&str]| {
+      apis
+        .iter()
+        .any(|api| *api == "api-all" || features.contains(api))
+    };
+
+    let scopes = Scopes {
+      fs: enabled(&FsAllowlistConfig::all_features()).then(|| &allowlist.fs.scope),
+      asset_protocol: enabled(&ProtocolAllowlistConfig::all_features())
+        .then(|| &allowlist.protocol.asset_scope),
+      http: enabled(&HttpAllowlistConfig::all_features())
+        .then(|| allowlist.http.scope.0.as_slice()),
+      shell: enabled(&["shell-all", "shell-execute", "shell-sidecar"])
+        .then(|| allowlist.shell.scope.0.as_slice()),
+      shell_open: enabled(&["shell-all", "shell-open"]).then(|| &allowlist.shell.open),
+    };
+
+    let mut endpoints = BTreeMap::<_, Vec<_>>::new();
+    for (module, features, commands) in ENDPOINTS {
+      if features.is_empty() || enabled(features) {
+        endpoints
+          .entry(*module)
+          .or_default()
+          .extend_from_slice(commands);
+      }
+    }
+
+    let mut remote_domains = tauri
+      .windows
+      .iter()
+      .filter_map(|w| match &w.url {
+        WindowUrl::External(url) => Some(origin(url)),
+        WindowUrl::App(_) => None,
+      })
+      .chain(scopes.http.unwrap_or_default().iter().map(origin))
+      .chain(
+        tauri
+          .security
+          .command_scope
+          .iter()
+          .flat_map(|entry| entry.origins.iter().cloned()),
+      )
+      .collect::<Vec<_>>();
+    remote_domains.sort();
+    remote_domains.dedup();
+
+    Self {
+      identifier: &tauri.bundle.identifier,
+      version: config.package.version.as_deref(),
+      apis,
+      endpoints,
+      scopes,
+      command_scope: &tauri.security.command_scope,
+      plugin_permissions: &tauri.security.plugin_permissions,
+      remote_domains,
+      csp: tauri.security.csp.as_ref().map(ToString::to_string),
+    }
+  }
+}
+
+/// The IPC endpoint commands as (module, features, commands) entries.
+///
+/// The commands are enabled when any of the features is enabled, or always when the entry has no features.
+/// The window management commands are listed as `manage > <command>`.
+const ENDPOINTS: &[(&str, &[&str], &[&str])] = &[
+  (
+    "App",
+    &[],
+    &["getAppVersion", "getAppName", "getTauriVersion"],
+  ),
+  ("App", &["app-all", "app-show"], &["show"]),
+  ("App", &["app-all", "app-hide"], &["hide"]),
+  ("Channel", &[], &["send", "ack", "close"]),
+  ("Cli", &["cli"], &["cliMatches"]),
+  (
+    "Clipboard",
+    &["clipboard-all", "clipboard-write-text"],
+    &["writeText"],
+  ),
+  (
+    "Clipboard",
+    &["clipboard-all", "clipboard-read-text"],
+    &["readText"],
+  ),
+  ("Dialog", &["dialog-all", "dialog-open"], &["openDialog"]),
+  ("Dialog", &["dialog-all", "dialog-save"], &["saveDialog"]),
+  (
+    "Dialog",
+    &["dialog-all", "dialog-message"],
+    &["messageDialog"],
+  ),
+  ("Dialog", &["dialog-all", "dialog-ask"], &["askDialog"]),
+  (
+    "Dialog",
+    &["dialog-all", "dialog-confirm"],
+    &["confirmDialog"],
+  ),
+  (
+    "Dialog",
+    &["dialog-all", "dialog-color-picker"],
+    &["colorPickerDialog"],
+  ),
+  (
+    "Dialog",
+    &["dialog-all", "dialog-font-picker"],
+    &["fontPickerDialog"],
+  ),
+  ("Event", &[], &["listen", "unlisten", "emit"]),
+  (
+    "Fs",
+    &["fs-all", "fs-read-file"],
+    &[
+      "readFile",
+      "readTextFile",
+      "openReadStream",
+      "readStreamChunk",
+      "closeReadStream",
+    ],
+  ),
+  (
+    "Fs",
+    &["fs-all", "fs-write-file"],
+    &[
+      "writeFile",
+      "openWriteStream",
+      "writeStreamChunk",
+      "closeWriteStream",
+    ],
+  ),
+  ("Fs", &["fs-all", "fs-read-dir"], &["readDir"]),
+  ("Fs", &["fs-all", "fs-copy-file"], &["copyFile"]),
+  ("Fs", &["fs-all", "fs-create-dir"], &["createDir"]),
+  ("Fs", &["fs-all", "fs-remove-dir"], &["removeDir"]),
+  ("Fs", &["fs-all", "fs-remove-file"], &["removeFile"]),
+  ("Fs", &["fs-all", "fs-trash"], &["trash"]),
+  ("Fs", &["fs-all", "fs-rename-file"], &["renameFile"]),
+  ("Fs", &["fs-all", "fs-exists"], &["exists"]),
+  ("Fs", &["fs-all", "fs-watch"], &["watch", "unwatch"]),
+  (
+    "GlobalShortcut",
+    &["global-shortcut-all"],
+    &[
+      "register",
+      "registerAll",
+      "unregister",
+      "unregisterAll",
+      "isRegistered",
+    ],
+  ),
+  (
+    "Http",
+    &["http-all", "http-request"],
+    &[
+      "createClient",
+      "dropClient",
+      "httpRequest",
+      "getCookies",
+      "clearCookies",
+    ],
+  ),
+  (
+    "I18n",
+    &["i18n"],
+    &["translate", "locale", "setLocale", "availableLocales"],
+  ),
+  ("Log", &["log-all", "log-write"], &["log"]),
+  (
+    "Mdns",
+    &["mdns-all", "mdns-advertise"],
+    &["advertise", "unadvertise"],
+  ),
+  (
+    "Mdns",
+    &["mdns-all", "mdns-browse"],
+    &["browse", "stopBrowse"],
+  ),
+  (
+    "Notification",
+    &[],
+    &[
+      "requestNotificationPermission",
+      "isNotificationPermissionGranted",
+    ],
+  ),
+  ("Notification", &["notification-all"], &["notification"]),
+  (
+    "OAuth",
+    &["oauth-all", "oauth-authenticate"],
+    &["authenticate"],
+  ),
+  (
+    "Os",
+    &["os-all"],
+    &["platform", "version", "osType", "arch", "tempdir"],
+  ),
+  ("Os", &["os-all", "os-network-status"], &["networkStatus"]),
+  (
+    "Path",
+    &["path-all"],
+    &[
+      "resolvePath",
+      "resolve",
+      "normalize",
+      "join",
+      "dirname",
+      "extname",
+      "basename",
+      "isAbsolute",
+    ],
+  ),
+  (
+    "Path",
+    &["path-all", "path-volumes"],
+    &["volumes", "watchVolumes", "unwatchVolumes"],
+  ),
+  (
+    "Process",
+    &["process-all", "process-relaunch"],
+    &["relaunch"],
+  ),
+  ("Process", &["process-all", "process-exit"], &["exit"]),
+  (
+    "Serial",
+    &["serial-all", "serial-list"],
+    &["ports", "usbDevices", "watchDevices", "unwatchDevices"],
+  ),
+  (
+    "Serial",
+    &["serial-all", "serial-open"],
+    &["open", "write", "close"],
+  ),
+  (
+    "Shell",
+    &["shell-all", "shell-execute", "shell-sidecar"],
+    &[
+      "execute",
+      "stdinWrite",
+      "stdinClose",
+      "resizePty",
+      "killChild",
+      "killChildTree",
+    ],
+  ),
+  ("Shell", &["shell-all", "shell-open"], &["open"]),
+  (
+    "Store",
+    &["store-all", "store-sql"],
+    &["migrate", "execute", "select"],
+  ),
+  (
+    "Store",
+    &["store-all", "store-key-value"],
+    &["get", "set", "delete", "keys"],
+  ),
+  (
+    "Websocket",
+    &["websocket-all", "websocket-connect"],
+    &["connect", "send", "disconnect"],
+  ),
+  (
+    "Window",
+    &[],
+    &[
+      "manage > scaleFactor",
+      "manage > innerPosition",
+      "manage > outerPosition",
+      "manage > innerSize",
+      "manage > outerSize",
+      "manage > isFullscreen",
+      "manage > isMaximized",
+      "manage > isDecorated",
+      "manage > isResizable",
+      "manage > isVisible",
+      "manage > currentMonitor",
+      "manage > primaryMonitor",
+      "manage > availableMonitors",
+      "manage > theme",
+      "manage > group",
+    ],
+  ),
+  (
+    "Window",
+    &["window-all", "window-create"],
+    &["createWebview"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-center"],
+    &["manage > center"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-request-user-attention"],
+    &["manage > requestUserAttention"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-resizable"],
+    &["manage > setResizable"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-title"],
+    &["manage > setTitle"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-maximize"],
+    &["manage > maximize"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-unmaximize"],
+    &["manage > unmaximize"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-minimize"],
+    &["manage > minimize"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-unminimize"],
+    &["manage > unminimize"],
+  ),
+  ("Window", &["window-all", "window-show"], &["manage > show"]),
+  ("Window", &["window-all", "window-hide"], &["manage > hide"]),
+  (
+    "Window",
+    &["window-all", "window-close"],
+    &["manage > close"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-decorations"],
+    &["manage > setDecorations"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-always-on-top"],
+    &["manage > setAlwaysOnTop"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-size"],
+    &["manage > setSize"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-min-size"],
+    &["manage > setMinSize"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-max-size"],
+    &["manage > setMaxSize"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-position"],
+    &["manage > setPosition"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-fullscreen"],
+    &["manage > setFullscreen"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-focus"],
+    &["manage > setFocus"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-icon"],
+    &["manage > setIcon"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-skip-taskbar"],
+    &["manage > setSkipTaskbar"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-cursor-grab"],
+    &["manage > setCursorGrab"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-cursor-visible"],
+    &["manage > setCursorVisible"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-cursor-icon"],
+    &["manage > setCursorIcon"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-cursor-position"],
+    &["manage > setCursorPosition"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-set-ignore-cursor-events"],
+    &["manage > setIgnoreCursorEvents"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-start-dragging"],
+    &["manage > startDragging"],
+  ),
+  (
+    "Window",
+    &["window-all", "window-print"],
+    &["manage > print"],
+  ),
+];
+
+fn origin(url: &Url) -> String {
+  url.origin().ascii_serialization()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PermissionManifest;
+  use crate::config::Config;
+  use serde_json::json;
+
+  #[test]
+  fn manifest() {
+    let config: Config = serde_json::from_value(json!({
+      "package": { "version": "1.0.0" },
+      "tauri": {
+        "allowlist": {
+          "fs": { "readFile": true, "scope": ["$APPDATA/*"] },
+          "http": { "scope": ["https://api.tauri.app/v1/*", "https://tauri.app/*"] },
+          "shell": { "scope": [{ "name": "ls", "cmd": "ls" }] }
+        },
+        "windows": [{ "url": "https://tauri.app/start" }],
+        "security": {
+          "commandScope": [{ "origins": ["https://*.tauri.app"], "commands": ["greet"] }]
+        }
+      }
+    }))
+    .unwrap();
+
+    let manifest = serde_json::to_value(PermissionManifest::new(&config)).unwrap();
+    assert_eq!(manifest["version"], "1.0.0");
+    assert_eq!(manifest["apis"], json!(["fs-read-file"]));
+    assert_eq!(
+      manifest["endpoints"]["Fs"],
+      json!([
+        "readFile",
+        "readTextFile",
+        "openReadStream",
+        "readStreamChunk",
+        "closeReadStream"
+      ])
+    );
+    // the always enabled commands are listed, the disabled ones are not
+    assert_eq!(
+      manifest["endpoints"]["Event"],
+      json!(["listen", "unlisten", "emit"])
+    );
+    assert_eq!(manifest["endpoints"]["Shell"], json!(null));
+    assert_eq!(manifest["scopes"]["fs"], json!(["$APPDATA/*"]));
+    // the scopes of disabled APIs are not listed
+    assert_eq!(manifest["scopes"]["http"], json!(null));
+    assert_eq!(manifest["scopes"]["shell"], json!(null));
+    assert_eq!(
+      manifest["remoteDomains"],
+      json!(["https://*.tauri.app", "https://tauri.app"])
+    );
+  }
+}
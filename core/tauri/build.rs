@@ -38,6 +38,10 @@ fn alias(alias: &str, has_feature: bool) {
 fn main() {
   alias("custom_protocol", has_feature("custom-protocol"));
   alias("dev", !has_feature("custom-protocol"));
+  alias(
+    "config_reload",
+    !has_feature("custom-protocol") && has_feature("dev-config-reload"),
+  );
   alias("updater", has_feature("updater"));
 
   let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
@@ -60,6 +64,8 @@ fn main() {
       "remove-file",
       "rename-file",
       "exists",
+      "trash",
+      "watch",
     ],
     api_all,
   );
@@ -109,25 +115,49 @@ fn main() {
   if !mobile {
     alias_module(
       "dialog",
-      &["open", "save", "message", "ask", "confirm"],
+      &[
+        "open",
+        "save",
+        "message",
+        "ask",
+        "confirm",
+        "color-picker",
+        "font-picker",
+      ],
       api_all,
     );
   }
 
   alias_module("http", &["request"], api_all);
+  alias_module("websocket", &["connect"], api_all);
+  alias_module("mdns", &["advertise", "browse"], api_all);
+  alias_module("store", &["key-value", "sql"], api_all);
+  alias_module("oauth", &["authenticate"], api_all);
+  alias_module("log", &["write"], api_all);
 
   alias("cli", has_feature("cli"));
 
   if !mobile {
     alias_module("notification", &[], api_all);
     alias_module("global-shortcut", &[], api_all);
+    alias_module("serial", &["list", "open"], api_all);
   }
   alias_module("os", &[], api_all);
+  // the status is watched for the lifetime of the app, so it is not enabled by `os-all` or `api-all`
+  let os_network_status = has_feature("os-network-status");
+  alias("os_network_status", os_network_status);
+  alias("os_any", os_network_status);
   alias_module("path", &[], api_all);
+  // the volumes reveal the drives of the user, so they are not enabled by `path-all` or `api-all`
+  let path_volumes = has_feature("path-volumes");
+  alias("path_volumes", path_volumes);
+  alias("path_any", path_volumes);
 
   alias_module("protocol", &["asset"], api_all);
 
   alias_module("process", &["relaunch", "exit"], api_all);
+  // the metrics are only measured with `Builder::metrics_interval`, so they are not enabled by `process-all` or `api-all`
+  alias("process_metrics", has_feature("process-metrics"));
 
   alias_module("clipboard", &["write-text", "read-text"], api_all);
 
@@ -140,6 +170,17 @@ fn main() {
     &CHECKED_FEATURES.get().unwrap().lock().unwrap().join(","),
   )
   .expect("failed to write checked_features file");
+
+  // the features compiled in, checked against the allowlist by the `strict_allowlist` attribute of `tauri-build`
+  let mut enabled_features = std::env::vars()
+    .filter_map(|(key, _)| {
+      key
+        .strip_prefix("CARGO_FEATURE_")
+        .map(|feature| feature.to_lowercase().replace('_', "-"))
+    })
+    .collect::<Vec<_>>();
+  enabled_features.sort();
+  println!("cargo:features={}", enabled_features.join(","));
 }
 
 // create aliases for the given module with its apis.
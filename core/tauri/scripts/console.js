@@ -0,0 +1,46 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+;(function () {
+  function stringify(arg) {
+    if (typeof arg === 'string') {
+      return arg
+    }
+    if (arg instanceof Error) {
+      return arg.stack || arg.message
+    }
+    try {
+      return JSON.stringify(arg)
+    } catch (_) {
+      return String(arg)
+    }
+  }
+
+  function forward(method, level) {
+    var original = console[method]
+    console[method] = function () {
+      original.apply(console, arguments)
+      if (!window.__TAURI_INVOKE__) {
+        return
+      }
+      window
+        .__TAURI_INVOKE__('tauri', {
+          __tauriModule: 'Log',
+          message: {
+            cmd: 'log',
+            level: level,
+            message: Array.prototype.map.call(arguments, stringify).join(' ')
+          }
+        })
+        .catch(function () {})
+    }
+  }
+
+  forward('trace', 'trace')
+  forward('debug', 'debug')
+  forward('log', 'info')
+  forward('info', 'info')
+  forward('warn', 'warn')
+  forward('error', 'error')
+})()
@@ -35,6 +35,59 @@
     return identifier
   }
 
+  // channels created on the webview side or returned by the Rust side
+  const channels = Object.create(null)
+  // messages delivered to channels that are not registered yet
+  const pendingChannelMessages = Object.create(null)
+
+  function channelPending(id) {
+    if (!pendingChannelMessages[id]) {
+      pendingChannelMessages[id] = { messages: [], closed: false }
+    }
+    return pendingChannelMessages[id]
+  }
+
+  Object.defineProperty(window, '__TAURI_CHANNEL_MESSAGE__', {
+    value: (id, message) => {
+      if (channels[id]) {
+        channels[id].onmessage(message)
+      } else {
+        channelPending(id).messages.push(message)
+      }
+    }
+  })
+
+  Object.defineProperty(window, '__TAURI_CHANNEL_CLOSE__', {
+    value: (id) => {
+      if (channels[id]) {
+        channels[id].onclose()
+      } else {
+        channelPending(id).closed = true
+      }
+    }
+  })
+
+  Object.defineProperty(window, '__TAURI_CHANNEL_REGISTER__', {
+    value: (id, handlers) => {
+      if (handlers) {
+        channels[id] = handlers
+        const pending = pendingChannelMessages[id]
+        if (pending) {
+          delete pendingChannelMessages[id]
+          for (const message of pending.messages) {
+            handlers.onmessage(message)
+          }
+          if (pending.closed) {
+            handlers.onclose()
+          }
+        }
+      } else {
+        delete channels[id]
+        delete pendingChannelMessages[id]
+      }
+    }
+  })
+
   const ipcQueue = []
   let isWaitingForIpc = false
 
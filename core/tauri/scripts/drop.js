@@ -0,0 +1,94 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// called with the MIME types accepted by the window, e.g. `['text/plain', 'text/*']`
+;(function (acceptedTypes) {
+  function isAccepted(type) {
+    return acceptedTypes.some(function (accepted) {
+      if (accepted === '*' || accepted === type) {
+        return true
+      }
+      return (
+        accepted.endsWith('/*') &&
+        type.startsWith(accepted.slice(0, accepted.length - 1))
+      )
+    })
+  }
+
+  // the files are handled by the file drop handler
+  function droppedTypes(dataTransfer) {
+    return Array.prototype.filter.call(
+      (dataTransfer && dataTransfer.types) || [],
+      function (type) {
+        return type !== 'Files' && isAccepted(type)
+      }
+    )
+  }
+
+  function send(payload) {
+    window
+      .__TAURI_INVOKE__('tauri', {
+        __tauriModule: 'Window',
+        message: {
+          cmd: 'manage',
+          data: {
+            cmd: {
+              type: '__drop',
+              payload: payload
+            }
+          }
+        }
+      })
+      .catch(function () {})
+  }
+
+  // the drag is accepted by cancelling the `dragenter` and `dragover` events,
+  // unless the page already handles it
+  function accept(e) {
+    var types = droppedTypes(e.dataTransfer)
+    if (!e.defaultPrevented && types.length > 0) {
+      e.preventDefault()
+      e.dataTransfer.dropEffect = 'copy'
+    }
+    return types
+  }
+
+  // `dragenter` and `dragleave` are also fired when the cursor moves between the elements of the page,
+  // so the hover is only reported when the drag enters the webview
+  var depth = 0
+  window.addEventListener('dragenter', function (e) {
+    var handled = e.defaultPrevented
+    var types = accept(e)
+    depth += 1
+    if (depth === 1 && !handled && types.length > 0) {
+      send({
+        type: 'hovered',
+        position: { x: e.clientX, y: e.clientY },
+        types: types
+      })
+    }
+  })
+  window.addEventListener('dragleave', function () {
+    depth = Math.max(depth - 1, 0)
+  })
+  window.addEventListener('dragover', accept)
+  window.addEventListener('drop', function (e) {
+    depth = 0
+    var types = droppedTypes(e.dataTransfer)
+    if (e.defaultPrevented || types.length === 0) {
+      return
+    }
+    e.preventDefault()
+
+    var items = {}
+    types.forEach(function (type) {
+      items[type] = e.dataTransfer.getData(type)
+    })
+    send({
+      type: 'dropped',
+      position: { x: e.clientX, y: e.clientY },
+      items: items
+    })
+  })
+})
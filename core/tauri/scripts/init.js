@@ -19,11 +19,15 @@
 
     __RAW_event_initialization_script__
 
+    const pageLoadPayload = {
+      url: window.location.href,
+      isMainFrame: window.top === window
+    }
     if (window.ipc) {
-      window.__TAURI_INVOKE__('__initialized', { url: window.location.href })
+      window.__TAURI_INVOKE__('__initialized', pageLoadPayload)
     } else {
       window.addEventListener('DOMContentLoaded', function () {
-        window.__TAURI_INVOKE__('__initialized', { url: window.location.href })
+        window.__TAURI_INVOKE__('__initialized', pageLoadPayload)
       })
     }
 
@@ -0,0 +1,265 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * MessagePack serializer for the invoke system.
+ * Messages are exchanged as base64 strings since the IPC channel only transfers text.
+ */
+;(function () {
+  const textEncoder = new TextEncoder()
+  const textDecoder = new TextDecoder()
+
+  function Writer() {
+    this.bytes = new Uint8Array(256)
+    this.view = new DataView(this.bytes.buffer)
+    this.length = 0
+  }
+
+  Writer.prototype.reserve = function (size) {
+    if (this.length + size <= this.bytes.length) {
+      return
+    }
+    let capacity = this.bytes.length * 2
+    while (capacity < this.length + size) {
+      capacity *= 2
+    }
+    const bytes = new Uint8Array(capacity)
+    bytes.set(this.bytes.subarray(0, this.length))
+    this.bytes = bytes
+    this.view = new DataView(bytes.buffer)
+  }
+
+  Writer.prototype.u8 = function (value) {
+    this.reserve(1)
+    this.view.setUint8(this.length, value)
+    this.length += 1
+  }
+
+  Writer.prototype.typed = function (type, size, setter, value) {
+    this.reserve(size + 1)
+    this.view.setUint8(this.length, type)
+    this.view[setter](this.length + 1, value)
+    this.length += size + 1
+  }
+
+  Writer.prototype.raw = function (bytes) {
+    this.reserve(bytes.length)
+    this.bytes.set(bytes, this.length)
+    this.length += bytes.length
+  }
+
+  Writer.prototype.header = function (length, fix, fixMax, type16, type32) {
+    if (length <= fixMax) {
+      this.u8(fix | length)
+    } else if (length <= 0xffff) {
+      this.typed(type16, 2, 'setUint16', length)
+    } else {
+      this.typed(type32, 4, 'setUint32', length)
+    }
+  }
+
+  Writer.prototype.number = function (value) {
+    if (!Number.isSafeInteger(value)) {
+      this.typed(0xcb, 8, 'setFloat64', value)
+    } else if (value >= 0) {
+      if (value < 0x80) {
+        this.u8(value)
+      } else if (value <= 0xff) {
+        this.typed(0xcc, 1, 'setUint8', value)
+      } else if (value <= 0xffff) {
+        this.typed(0xcd, 2, 'setUint16', value)
+      } else if (value <= 0xffffffff) {
+        this.typed(0xce, 4, 'setUint32', value)
+      } else {
+        this.typed(0xcf, 8, 'setBigUint64', BigInt(value))
+      }
+    } else if (value >= -0x20) {
+      this.u8(value & 0xff)
+    } else if (value >= -0x80) {
+      this.typed(0xd0, 1, 'setInt8', value)
+    } else if (value >= -0x8000) {
+      this.typed(0xd1, 2, 'setInt16', value)
+    } else if (value >= -0x80000000) {
+      this.typed(0xd2, 4, 'setInt32', value)
+    } else {
+      this.typed(0xd3, 8, 'setBigInt64', BigInt(value))
+    }
+  }
+
+  Writer.prototype.string = function (value) {
+    const bytes = textEncoder.encode(value)
+    if (bytes.length < 0x20) {
+      this.u8(0xa0 | bytes.length)
+    } else if (bytes.length <= 0xff) {
+      this.typed(0xd9, 1, 'setUint8', bytes.length)
+    } else {
+      this.header(bytes.length, 0, -1, 0xda, 0xdb)
+    }
+    this.raw(bytes)
+  }
+
+  Writer.prototype.value = function (value) {
+    if (value === null || value === undefined) {
+      this.u8(0xc0)
+    } else if (typeof value === 'boolean') {
+      this.u8(value ? 0xc3 : 0xc2)
+    } else if (typeof value === 'number') {
+      this.number(value)
+    } else if (typeof value === 'bigint') {
+      this.typed(value < 0 ? 0xd3 : 0xcf, 8, value < 0 ? 'setBigInt64' : 'setBigUint64', value)
+    } else if (typeof value === 'string') {
+      this.string(value)
+    } else if (Array.isArray(value) || (ArrayBuffer.isView(value) && !(value instanceof DataView))) {
+      this.header(value.length, 0x90, 0x0f, 0xdc, 0xdd)
+      for (let i = 0; i < value.length; i++) {
+        this.value(value[i])
+      }
+    } else if (typeof value.toJSON === 'function') {
+      this.value(value.toJSON())
+    } else if (typeof value === 'object') {
+      const keys = Object.keys(value).filter(
+        (key) => value[key] !== undefined && typeof value[key] !== 'function'
+      )
+      this.header(keys.length, 0x80, 0x0f, 0xde, 0xdf)
+      for (const key of keys) {
+        this.string(key)
+        this.value(value[key])
+      }
+    } else {
+      this.u8(0xc0)
+    }
+  }
+
+  function Reader(bytes) {
+    this.bytes = bytes
+    this.view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength)
+    this.offset = 0
+  }
+
+  Reader.prototype.read = function (getter, size) {
+    const value = this.view[getter](this.offset)
+    this.offset += size
+    return value
+  }
+
+  Reader.prototype.string = function (length) {
+    const value = textDecoder.decode(
+      this.bytes.subarray(this.offset, this.offset + length)
+    )
+    this.offset += length
+    return value
+  }
+
+  Reader.prototype.bin = function (length) {
+    const value = this.bytes.slice(this.offset, this.offset + length)
+    this.offset += length
+    return value
+  }
+
+  Reader.prototype.array = function (length) {
+    const value = new Array(length)
+    for (let i = 0; i < length; i++) {
+      value[i] = this.value()
+    }
+    return value
+  }
+
+  Reader.prototype.map = function (length) {
+    const value = {}
+    for (let i = 0; i < length; i++) {
+      const key = this.value()
+      value[key] = this.value()
+    }
+    return value
+  }
+
+  Reader.prototype.value = function () {
+    const type = this.read('getUint8', 1)
+    if (type < 0x80) return type
+    if (type < 0x90) return this.map(type & 0x0f)
+    if (type < 0xa0) return this.array(type & 0x0f)
+    if (type < 0xc0) return this.string(type & 0x1f)
+    if (type >= 0xe0) return type - 0x100
+    switch (type) {
+      case 0xc0:
+        return null
+      case 0xc2:
+        return false
+      case 0xc3:
+        return true
+      case 0xc4:
+        return this.bin(this.read('getUint8', 1))
+      case 0xc5:
+        return this.bin(this.read('getUint16', 2))
+      case 0xc6:
+        return this.bin(this.read('getUint32', 4))
+      case 0xca:
+        return this.read('getFloat32', 4)
+      case 0xcb:
+        return this.read('getFloat64', 8)
+      case 0xcc:
+        return this.read('getUint8', 1)
+      case 0xcd:
+        return this.read('getUint16', 2)
+      case 0xce:
+        return this.read('getUint32', 4)
+      case 0xcf:
+        return Number(this.read('getBigUint64', 8))
+      case 0xd0:
+        return this.read('getInt8', 1)
+      case 0xd1:
+        return this.read('getInt16', 2)
+      case 0xd2:
+        return this.read('getInt32', 4)
+      case 0xd3:
+        return Number(this.read('getBigInt64', 8))
+      case 0xd9:
+        return this.string(this.read('getUint8', 1))
+      case 0xda:
+        return this.string(this.read('getUint16', 2))
+      case 0xdb:
+        return this.string(this.read('getUint32', 4))
+      case 0xdc:
+        return this.array(this.read('getUint16', 2))
+      case 0xdd:
+        return this.array(this.read('getUint32', 4))
+      case 0xde:
+        return this.map(this.read('getUint16', 2))
+      case 0xdf:
+        return this.map(this.read('getUint32', 4))
+      default:
+        throw new Error(`unsupported MessagePack type 0x${type.toString(16)}`)
+    }
+  }
+
+  function toBase64(bytes) {
+    let binary = ''
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+      binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000))
+    }
+    return btoa(binary)
+  }
+
+  function fromBase64(data) {
+    const binary = atob(data)
+    const bytes = new Uint8Array(binary.length)
+    for (let i = 0; i < binary.length; i++) {
+      bytes[i] = binary.charCodeAt(i)
+    }
+    return bytes
+  }
+
+  Object.defineProperty(window, '__TAURI_IPC_SERIALIZER__', {
+    value: Object.freeze({
+      serialize(value) {
+        const writer = new Writer()
+        writer.value(value)
+        return toBase64(writer.bytes.subarray(0, writer.length))
+      },
+      deserialize(data) {
+        return new Reader(fromBase64(data)).value()
+      }
+    })
+  })
+})()
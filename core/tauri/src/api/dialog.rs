@@ -2,13 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-//! Use native message and file open/save dialogs.
+//! Use native message, file open/save, color and font picker dialogs.
 //!
 //! This module exposes non-blocking APIs on its root, relying on callback closures
 //! to give results back. This is particularly useful when running dialogs from the main thread.
 //! When using on asynchronous contexts such as async commands, the [`blocking`] APIs are recommended.
 
 pub use nonblocking::*;
+pub use picker::{Color, Font};
+
+mod message;
+mod picker;
 
 #[cfg(not(target_os = "linux"))]
 macro_rules! run_dialog {
@@ -127,18 +131,24 @@ macro_rules! file_dialog_builder {
 macro_rules! message_dialog_builder {
   () => {
     /// A builder for message dialogs.
-    pub struct MessageDialogBuilder(rfd::MessageDialog);
+    pub struct MessageDialogBuilder {
+      dialog: rfd::MessageDialog,
+      custom: super::message::CustomMessageDialog,
+      custom_buttons: Option<MessageDialogCustomButtons>,
+    }
 
     impl MessageDialogBuilder {
       /// Creates a new message dialog builder.
       pub fn new(title: impl AsRef<str>, message: impl AsRef<str>) -> Self {
         let title = title.as_ref().to_string();
         let message = message.as_ref().to_string();
-        Self(
-          rfd::MessageDialog::new()
+        Self {
+          dialog: rfd::MessageDialog::new()
             .set_title(&title)
             .set_description(&message),
-        )
+          custom: super::message::CustomMessageDialog::new(title, message),
+          custom_buttons: None,
+        }
       }
 
       /// Set parent windows explicitly (optional)
@@ -147,13 +157,28 @@ macro_rules! message_dialog_builder {
       ///
       /// - **Linux:** Unsupported.
       pub fn parent<W: raw_window_handle::HasRawWindowHandle>(mut self, parent: &W) -> Self {
-        self.0 = self.0.set_parent(parent);
+        self.dialog = self.dialog.set_parent(parent);
+        self
+          .custom
+          .parent
+          .replace(raw_window_handle::HasRawWindowHandle::raw_window_handle(
+            parent,
+          ));
         self
       }
 
       /// Set the set of button that will be displayed on the dialog.
       pub fn buttons(mut self, buttons: MessageDialogButtons) -> Self {
-        self.0 = self.0.set_buttons(buttons.into());
+        self.dialog = self.dialog.set_buttons(buttons.into());
+        self.custom_buttons = None;
+        self
+      }
+
+      /// Set the buttons with custom labels that will be displayed on the dialog,
+      /// replacing the ones set with [`Self::buttons`].
+      #[must_use]
+      pub fn custom_buttons(mut self, buttons: MessageDialogCustomButtons) -> Self {
+        self.custom_buttons = Some(buttons);
         self
       }
 
@@ -162,8 +187,27 @@ macro_rules! message_dialog_builder {
       /// Depending on the system it can result in type specific icon to show up,
       /// the will inform user it message is a error, warning or just information.
       pub fn kind(mut self, kind: MessageDialogKind) -> Self {
-        self.0 = self.0.set_level(kind.into());
+        self.dialog = self.dialog.set_level(kind.into());
+        self.custom.kind = kind;
+        self
+      }
+
+      /// The index of the default button.
+      fn default_button(&self) -> usize {
         self
+          .custom_buttons
+          .as_ref()
+          .map(|buttons| buttons.roles().0)
+          .unwrap_or_default()
+      }
+
+      /// Shows the dialog, returning the index of the pressed button.
+      fn run(self) -> usize {
+        match self.custom_buttons {
+          Some(buttons) => self.custom.show(&buttons),
+          None if self.dialog.show() => 0,
+          None => 1,
+        }
       }
     }
   };
@@ -191,6 +235,74 @@ impl From<MessageDialogButtons> for rfd::MessageButtons {
   }
 }
 
+/// Up to three buttons with custom labels for message dialogs,
+/// see [`MessageDialogBuilder::custom_buttons`](blocking::MessageDialogBuilder::custom_buttons).
+///
+/// The platform decides where each button is placed. The default button is focused,
+/// and the cancel button is pressed when the dialog is closed or <kbd>Esc</kbd> is pressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageDialogCustomButtons {
+  labels: Vec<String>,
+  default: usize,
+  cancel: Option<usize>,
+}
+
+impl MessageDialogCustomButtons {
+  fn new(labels: Vec<String>) -> Self {
+    Self {
+      labels,
+      default: 0,
+      cancel: None,
+    }
+  }
+
+  /// A single button, which is both the default and the cancel button.
+  pub fn one(label: impl Into<String>) -> Self {
+    Self::new(vec![label.into()])
+  }
+
+  /// Two buttons.
+  pub fn two(first: impl Into<String>, second: impl Into<String>) -> Self {
+    Self::new(vec![first.into(), second.into()])
+  }
+
+  /// Three buttons.
+  pub fn three(
+    first: impl Into<String>,
+    second: impl Into<String>,
+    third: impl Into<String>,
+  ) -> Self {
+    Self::new(vec![first.into(), second.into(), third.into()])
+  }
+
+  /// Sets the index of the default button. Defaults to the first button.
+  #[must_use]
+  pub fn default_button(mut self, index: usize) -> Self {
+    self.default = index;
+    self
+  }
+
+  /// Sets the index of the cancel button. Defaults to the last button that is not the default one.
+  #[must_use]
+  pub fn cancel_button(mut self, index: usize) -> Self {
+    self.cancel.replace(index);
+    self
+  }
+
+  /// The indexes of the default, other and cancel buttons, ignoring invalid indexes.
+  fn roles(&self) -> (usize, Option<usize>, usize) {
+    let len = self.labels.len();
+    let default = if self.default < len { self.default } else { 0 };
+    let cancel = self
+      .cancel
+      .filter(|cancel| *cancel < len && *cancel != default)
+      .or_else(|| (0..len).rev().find(|i| *i != default))
+      .unwrap_or(default);
+    let other = (0..len).find(|i| *i != default && *i != cancel);
+    (default, other, cancel)
+  }
+}
+
 /// Types of message, ask and confirm dialogs.
 #[non_exhaustive]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -221,7 +333,7 @@ impl From<MessageDialogKind> for rfd::MessageLevel {
 /// **NOTE:** You cannot block the main thread when executing the dialog APIs, so you must use the [`crate::api::dialog`] methods instead.
 /// Examples of main thread context are the [`crate::App::run`] closure and non-async commands.
 pub mod blocking {
-  use super::{MessageDialogButtons, MessageDialogKind};
+  use super::{Color, Font, MessageDialogButtons, MessageDialogCustomButtons, MessageDialogKind};
   use crate::{Runtime, Window};
   use std::path::{Path, PathBuf};
   use std::sync::mpsc::sync_channel;
@@ -354,12 +466,41 @@ pub mod blocking {
     /// - In `Ok` dialog, it will return `true` when `OK` was pressed.
     /// - In `OkCancel` dialog, it will return `true` when `OK` was pressed.
     /// - In `YesNo` dialog, it will return `true` when `Yes` was pressed.
+    /// - With custom buttons, it will return `true` when the default button was pressed.
     pub fn show(self) -> bool {
+      let default = self.default_button();
+      self.choose() == default
+    }
+
+    /// Shows a message dialog, returning the index of the pressed button.
+    ///
+    /// The index refers to the [`MessageDialogCustomButtons`] when they are set,
+    /// otherwise it is `0` for the `OK` and `Yes` buttons and `1` for the others.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tauri::api::dialog::{blocking::MessageDialogBuilder, MessageDialogCustomButtons};
+    /// #[tauri::command]
+    /// async fn close_document() {
+    ///   let buttons = MessageDialogCustomButtons::three("Save", "Don't Save", "Cancel")
+    ///     .cancel_button(2);
+    ///   match MessageDialogBuilder::new("Unsaved changes", "Save the changes before closing?")
+    ///     .custom_buttons(buttons)
+    ///     .choose()
+    ///   {
+    ///     0 => { /* save and close */ }
+    ///     1 => { /* close */ }
+    ///     _ => { /* keep the document open */ }
+    ///   }
+    /// }
+    /// ```
+    pub fn choose(self) -> usize {
       let (tx, rx) = sync_channel(1);
-      let f = move |response| {
-        tx.send(response).unwrap();
+      let f = move |index| {
+        tx.send(index).unwrap();
       };
-      run_dialog!(self.0.show(), f);
+      run_dialog!(self.run(), f);
       rx.recv().unwrap()
     }
   }
@@ -432,6 +573,50 @@ pub mod blocking {
     let _ = run_message_dialog(parent_window, title, message, rfd::MessageButtons::Ok);
   }
 
+  /// Shows the native color picker and returns the selected color,
+  /// or `None` if the user cancelled the picker.
+  /// This is a blocking operation,
+  /// and should *NOT* be used when running on the main thread context.
+  ///
+  /// See [`crate::api::dialog::color_picker`] for the platform-specific behavior.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::api::dialog::{blocking::color_picker, Color};
+  /// #[tauri::command]
+  /// async fn pick_background<R: tauri::Runtime>(window: tauri::Window<R>) -> Option<Color> {
+  ///   color_picker(&window, None).unwrap_or_default()
+  /// }
+  /// ```
+  pub fn color_picker<R: Runtime>(
+    window: &Window<R>,
+    default: Option<Color>,
+  ) -> crate::Result<Option<Color>> {
+    let (tx, rx) = sync_channel(1);
+    super::nonblocking::color_picker(window, default, move |color| {
+      tx.send(color).unwrap();
+    })?;
+    Ok(rx.recv().unwrap_or_default())
+  }
+
+  /// Shows the native font picker and returns the selected font,
+  /// or `None` if the user cancelled the picker.
+  /// This is a blocking operation,
+  /// and should *NOT* be used when running on the main thread context.
+  ///
+  /// See [`crate::api::dialog::font_picker`] for the platform-specific behavior.
+  pub fn font_picker<R: Runtime>(
+    window: &Window<R>,
+    default: Option<Font>,
+  ) -> crate::Result<Option<Font>> {
+    let (tx, rx) = sync_channel(1);
+    super::nonblocking::font_picker(window, default, move |font| {
+      tx.send(font).unwrap();
+    })?;
+    Ok(rx.recv().unwrap_or_default())
+  }
+
   #[allow(unused_variables)]
   fn run_message_dialog<R: Runtime>(
     parent_window: Option<&Window<R>>,
@@ -455,7 +640,7 @@ pub mod blocking {
 }
 
 mod nonblocking {
-  use super::{MessageDialogButtons, MessageDialogKind};
+  use super::{Color, Font, MessageDialogButtons, MessageDialogCustomButtons, MessageDialogKind};
   use crate::{Runtime, Window};
   use std::path::{Path, PathBuf};
 
@@ -597,8 +782,19 @@ mod nonblocking {
     /// - In `Ok` dialog, it will call the closure with `true` when `OK` was pressed
     /// - In `OkCancel` dialog, it will call the closure with `true` when `OK` was pressed
     /// - In `YesNo` dialog, it will call the closure with `true` when `Yes` was pressed
+    /// - With custom buttons, it will call the closure with `true` when the default button was pressed
     pub fn show<F: FnOnce(bool) + Send + 'static>(self, f: F) {
-      run_dialog!(self.0.show(), f);
+      let default = self.default_button();
+      let f = move |index| f(index == default);
+      run_dialog!(self.run(), f);
+    }
+
+    /// Shows a message dialog, calling the closure with the index of the pressed button.
+    ///
+    /// The index refers to the [`MessageDialogCustomButtons`] when they are set,
+    /// otherwise it is `0` for the `OK` and `Yes` buttons and `1` for the others.
+    pub fn choose<F: FnOnce(usize) + Send + 'static>(self, f: F) {
+      run_dialog!(self.run(), f);
     }
   }
 
@@ -695,6 +891,71 @@ mod nonblocking {
     )
   }
 
+  /// Shows the native color picker, calling the closure with the selected color,
+  /// or `None` if the user cancelled the picker.
+  ///
+  /// The picker is shown on the main thread, attached to the given window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The color panel has no cancel button, closing it selects the current color.
+  /// - **Windows:** The alpha component cannot be picked and is always `255`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::api::dialog::color_picker;
+  /// # let app = tauri::Builder::default().build(tauri::generate_context!("test/fixture/src-tauri/tauri.conf.json")).unwrap();
+  /// # let window = tauri::Manager::get_window(&app, "main").unwrap();
+  /// color_picker(&window, None, |color| {
+  ///   // the color is `None` if the user cancelled the picker
+  /// })
+  /// .unwrap();
+  /// ```
+  pub fn color_picker<R: Runtime, F: FnOnce(Option<Color>) + Send + 'static>(
+    window: &Window<R>,
+    default: Option<Color>,
+    f: F,
+  ) -> crate::Result<()> {
+    let window_ = window.clone();
+    window.run_on_main_thread(move || f(super::picker::pick_color(&window_, default)))
+  }
+
+  /// Shows the native font picker, calling the closure with the selected font,
+  /// or `None` if the user cancelled the picker.
+  ///
+  /// The picker is shown on the main thread, attached to the given window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The font panel has no cancel button, closing it selects the current font.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::api::dialog::{font_picker, Font};
+  /// # let app = tauri::Builder::default().build(tauri::generate_context!("test/fixture/src-tauri/tauri.conf.json")).unwrap();
+  /// # let window = tauri::Manager::get_window(&app, "main").unwrap();
+  /// let default = Font {
+  ///   family: "Helvetica".into(),
+  ///   size: 14.0,
+  ///   weight: 400,
+  ///   italic: false,
+  /// };
+  /// font_picker(&window, Some(default), |font| {
+  ///   // the font is `None` if the user cancelled the picker
+  /// })
+  /// .unwrap();
+  /// ```
+  pub fn font_picker<R: Runtime, F: FnOnce(Option<Font>) + Send + 'static>(
+    window: &Window<R>,
+    default: Option<Font>,
+    f: F,
+  ) -> crate::Result<()> {
+    let window_ = window.clone();
+    window.run_on_main_thread(move || f(super::picker::pick_font(&window_, default)))
+  }
+
   #[allow(unused_variables)]
   pub(crate) fn run_message_dialog<R: Runtime, F: FnOnce(bool) + Send + 'static>(
     parent_window: Option<&Window<R>>,
@@ -723,3 +984,22 @@ mod nonblocking {
     run_dialog!(builder.show(), f)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::MessageDialogCustomButtons;
+
+  #[test]
+  fn custom_buttons_roles() {
+    let buttons = MessageDialogCustomButtons::three("Save", "Don't Save", "Cancel");
+    assert_eq!(buttons.roles(), (0, Some(1), 2));
+    let buttons = buttons.default_button(2).cancel_button(0);
+    assert_eq!(buttons.roles(), (2, Some(1), 0));
+    // invalid indexes are ignored
+    let buttons = MessageDialogCustomButtons::two("Retry", "Abort")
+      .default_button(3)
+      .cancel_button(0);
+    assert_eq!(buttons.roles(), (0, None, 1));
+    assert_eq!(MessageDialogCustomButtons::one("Ok").roles(), (0, None, 0));
+  }
+}
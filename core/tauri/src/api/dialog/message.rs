@@ -0,0 +1,271 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The native message dialogs with custom button labels, which rfd does not support.
+
+use raw_window_handle::RawWindowHandle;
+
+use super::{MessageDialogCustomButtons, MessageDialogKind};
+
+/// The title, message, kind and parent of a message dialog with custom buttons.
+pub(super) struct CustomMessageDialog {
+  pub title: String,
+  pub message: String,
+  pub kind: MessageDialogKind,
+  #[allow(dead_code)]
+  pub parent: Option<RawWindowHandle>,
+}
+
+// SAFETY: the parent handle is only given to the platform dialog, the same way rfd does.
+unsafe impl Send for CustomMessageDialog {}
+
+impl CustomMessageDialog {
+  pub fn new(title: String, message: String) -> Self {
+    Self {
+      title,
+      message,
+      kind: MessageDialogKind::Info,
+      parent: None,
+    }
+  }
+
+  /// Shows the dialog, returning the index of the pressed button.
+  ///
+  /// On Linux, this must be called on the main thread.
+  pub fn show(self, buttons: &MessageDialogCustomButtons) -> usize {
+    let (default, other, cancel) = buttons.roles();
+    // the default button first, which is where the platforms expect it
+    let mut order = vec![default];
+    order.extend(other);
+    if cancel != default {
+      order.push(cancel);
+    }
+    platform::show(&self, buttons, &order, cancel)
+  }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+  use super::{CustomMessageDialog, MessageDialogCustomButtons, MessageDialogKind};
+  use gtk::prelude::*;
+
+  pub fn show(
+    dialog: &CustomMessageDialog,
+    buttons: &MessageDialogCustomButtons,
+    order: &[usize],
+    cancel: usize,
+  ) -> usize {
+    let message_type = match dialog.kind {
+      MessageDialogKind::Info => gtk::MessageType::Info,
+      MessageDialogKind::Warning => gtk::MessageType::Warning,
+      MessageDialogKind::Error => gtk::MessageType::Error,
+    };
+    let native = gtk::MessageDialog::new(
+      None::<&gtk::Window>,
+      gtk::DialogFlags::MODAL,
+      message_type,
+      gtk::ButtonsType::None,
+      &dialog.message,
+    );
+    native.set_title(&dialog.title);
+    // GTK places the buttons from left to right, so the default one goes last
+    for index in order.iter().rev() {
+      native.add_button(
+        &buttons.labels[*index],
+        gtk::ResponseType::Other(*index as u16),
+      );
+    }
+    native.set_default_response(gtk::ResponseType::Other(order[0] as u16));
+
+    let response = native.run();
+    native.close();
+
+    match response {
+      gtk::ResponseType::Other(index) => index as usize,
+      // closed with the window manager or Esc
+      _ => cancel,
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{CustomMessageDialog, MessageDialogCustomButtons, MessageDialogKind};
+  use cocoa::{
+    base::{id, nil, BOOL, NO},
+    foundation::NSString,
+  };
+  use objc::{class, msg_send, sel, sel_impl};
+  use std::ffi::c_void;
+
+  const NS_ALERT_FIRST_BUTTON_RETURN: isize = 1000;
+
+  #[link(name = "System", kind = "dylib")]
+  extern "C" {
+    static _dispatch_main_q: c_void;
+    fn dispatch_sync_f(
+      queue: *const c_void,
+      context: *mut c_void,
+      work: extern "C" fn(*mut c_void),
+    );
+  }
+
+  pub fn show(
+    dialog: &CustomMessageDialog,
+    buttons: &MessageDialogCustomButtons,
+    order: &[usize],
+    cancel: usize,
+  ) -> usize {
+    let mut response = None;
+    run_on_main_thread(|| response = Some(run_alert(dialog, buttons, order)));
+    response
+      .and_then(|button| order.get(button))
+      .copied()
+      .unwrap_or(cancel)
+  }
+
+  /// Runs the alert as a modal window, returning the position of the pressed button on `order`.
+  fn run_alert(
+    dialog: &CustomMessageDialog,
+    buttons: &MessageDialogCustomButtons,
+    order: &[usize],
+  ) -> usize {
+    unsafe {
+      let alert: id = msg_send![class!(NSAlert), new];
+      let style: usize = match dialog.kind {
+        MessageDialogKind::Warning => 0,
+        MessageDialogKind::Info => 1,
+        MessageDialogKind::Error => 2,
+      };
+      let _: () = msg_send![alert, setAlertStyle: style];
+      let title = NSString::alloc(nil).init_str(&dialog.title);
+      let _: () = msg_send![alert, setMessageText: title];
+      let message = NSString::alloc(nil).init_str(&dialog.message);
+      let _: () = msg_send![alert, setInformativeText: message];
+
+      // the first button is the default one, on the right
+      for (position, index) in order.iter().enumerate() {
+        let label = NSString::alloc(nil).init_str(&buttons.labels[*index]);
+        let button: id = msg_send![alert, addButtonWithTitle: label];
+        if position > 0 && position == order.len() - 1 {
+          let escape = NSString::alloc(nil).init_str("\u{1b}");
+          let _: () = msg_send![button, setKeyEquivalent: escape];
+          let _: () = msg_send![escape, release];
+        }
+        let _: () = msg_send![label, release];
+      }
+
+      let response: isize = msg_send![alert, runModal];
+      let _: () = msg_send![title, release];
+      let _: () = msg_send![message, release];
+      let _: () = msg_send![alert, release];
+      (response - NS_ALERT_FIRST_BUTTON_RETURN) as usize
+    }
+  }
+
+  /// Runs the closure on the main thread, waiting for it to return.
+  fn run_on_main_thread<F: FnOnce()>(f: F) {
+    extern "C" fn work<F: FnOnce()>(context: *mut c_void) {
+      // SAFETY: the context is the `Option<F>` below, alive until `dispatch_sync_f` returns
+      let f = unsafe { &mut *(context as *mut Option<F>) };
+      if let Some(f) = f.take() {
+        f();
+      }
+    }
+
+    let is_main_thread: BOOL = unsafe { msg_send![class!(NSThread), isMainThread] };
+    if is_main_thread != NO {
+      f();
+    } else {
+      let mut f = Some(f);
+      unsafe {
+        dispatch_sync_f(
+          &_dispatch_main_q,
+          &mut f as *mut Option<F> as *mut c_void,
+          work::<F>,
+        );
+      }
+    }
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{CustomMessageDialog, MessageDialogCustomButtons, MessageDialogKind};
+  use raw_window_handle::RawWindowHandle;
+  use windows::{
+    core::PCWSTR,
+    Win32::{
+      Foundation::HWND,
+      UI::Controls::{
+        TaskDialogIndirect, TASKDIALOGCONFIG, TASKDIALOGCONFIG_0, TASKDIALOG_BUTTON,
+        TDF_ALLOW_DIALOG_CANCELLATION, TD_ERROR_ICON, TD_INFORMATION_ICON, TD_WARNING_ICON,
+      },
+    },
+  };
+
+  /// The identifier of the first button, above the identifiers of the common buttons.
+  const FIRST_BUTTON_ID: i32 = 100;
+
+  pub fn show(
+    dialog: &CustomMessageDialog,
+    buttons: &MessageDialogCustomButtons,
+    order: &[usize],
+    cancel: usize,
+  ) -> usize {
+    let wide = |s: &str| s.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+    let title = wide(&dialog.title);
+    let message = wide(&dialog.message);
+    let labels = order
+      .iter()
+      .map(|index| wide(&buttons.labels[*index]))
+      .collect::<Vec<_>>();
+    let native_buttons = labels
+      .iter()
+      .zip(order)
+      .map(|(label, index)| TASKDIALOG_BUTTON {
+        nButtonID: FIRST_BUTTON_ID + *index as i32,
+        pszButtonText: PCWSTR(label.as_ptr()),
+      })
+      .collect::<Vec<_>>();
+
+    let config = TASKDIALOGCONFIG {
+      cbSize: std::mem::size_of::<TASKDIALOGCONFIG>() as u32,
+      hwndParent: match dialog.parent {
+        Some(RawWindowHandle::Win32(handle)) => HWND(handle.hwnd as isize),
+        _ => HWND::default(),
+      },
+      dwFlags: TDF_ALLOW_DIALOG_CANCELLATION,
+      pszWindowTitle: PCWSTR(title.as_ptr()),
+      Anonymous1: TASKDIALOGCONFIG_0 {
+        pszMainIcon: match dialog.kind {
+          MessageDialogKind::Info => TD_INFORMATION_ICON,
+          MessageDialogKind::Warning => TD_WARNING_ICON,
+          MessageDialogKind::Error => TD_ERROR_ICON,
+        },
+      },
+      pszContent: PCWSTR(message.as_ptr()),
+      cButtons: native_buttons.len() as u32,
+      pButtons: native_buttons.as_ptr(),
+      nDefaultButton: FIRST_BUTTON_ID + order[0] as i32,
+      ..Default::default()
+    };
+
+    let mut pressed = 0;
+    // SAFETY: the strings and buttons outlive the dialog
+    let result = unsafe {
+      TaskDialogIndirect(
+        &config,
+        &mut pressed,
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+      )
+    };
+    match result {
+      // closed with Esc or the close button, which return `IDCANCEL`
+      Ok(()) if pressed >= FIRST_BUTTON_ID => (pressed - FIRST_BUTTON_ID) as usize,
+      _ => cancel,
+    }
+  }
+}
@@ -0,0 +1,395 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The native color and font pickers.
+//!
+//! The pickers must be shown on the main thread.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Runtime, Window};
+
+/// A color selected with the color picker.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Color {
+  /// The red component.
+  pub red: u8,
+  /// The green component.
+  pub green: u8,
+  /// The blue component.
+  pub blue: u8,
+  /// The alpha component, where `255` is fully opaque.
+  #[serde(default = "opaque")]
+  pub alpha: u8,
+}
+
+fn opaque() -> u8 {
+  u8::MAX
+}
+
+/// A font selected with the font picker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Font {
+  /// The font family name.
+  pub family: String,
+  /// The size in points.
+  pub size: f64,
+  /// The weight, from `100` (thin) to `900` (black), where `400` is regular and `700` is bold.
+  #[serde(default = "regular")]
+  pub weight: u16,
+  /// Whether the font is italic.
+  #[serde(default)]
+  pub italic: bool,
+}
+
+fn regular() -> u16 {
+  400
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub(super) use linux::{pick_color, pick_font};
+#[cfg(target_os = "macos")]
+pub(super) use macos::{pick_color, pick_font};
+#[cfg(windows)]
+pub(super) use win32::{pick_color, pick_font};
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod linux {
+  use super::{Color, Font, Runtime, Window};
+  use gtk::{pango, prelude::*};
+
+  pub fn pick_color<R: Runtime>(window: &Window<R>, default: Option<Color>) -> Option<Color> {
+    let parent = window.gtk_window().ok();
+    let dialog = gtk::ColorChooserDialog::new(None, parent.as_ref());
+    dialog.set_use_alpha(true);
+    if let Some(color) = default {
+      dialog.set_rgba(&gtk::gdk::RGBA::new(
+        component(color.red),
+        component(color.green),
+        component(color.blue),
+        component(color.alpha),
+      ));
+    }
+
+    let response = dialog.run();
+    let rgba = dialog.rgba();
+    dialog.close();
+
+    (response == gtk::ResponseType::Ok).then(|| Color {
+      red: byte(rgba.red()),
+      green: byte(rgba.green()),
+      blue: byte(rgba.blue()),
+      alpha: byte(rgba.alpha()),
+    })
+  }
+
+  pub fn pick_font<R: Runtime>(window: &Window<R>, default: Option<Font>) -> Option<Font> {
+    let parent = window.gtk_window().ok();
+    let dialog = gtk::FontChooserDialog::new(None, parent.as_ref());
+    if let Some(font) = default {
+      let mut description = pango::FontDescription::new();
+      description.set_family(&font.family);
+      description.set_size((font.size * pango::SCALE as f64).round() as i32);
+      // SAFETY: pango accepts any weight between 100 and 1000
+      description.set_weight(unsafe {
+        glib::translate::FromGlib::from_glib(font.weight.clamp(100, 1000) as i32)
+      });
+      if font.italic {
+        description.set_style(pango::Style::Italic);
+      }
+      dialog.set_font_desc(&description);
+    }
+
+    let response = dialog.run();
+    let description = dialog.font_desc();
+    dialog.close();
+
+    if response != gtk::ResponseType::Ok {
+      return None;
+    }
+    description.and_then(|description| {
+      let size = description.size() as f64 / pango::SCALE as f64;
+      Some(Font {
+        family: description.family()?.to_string(),
+        // absolute sizes are in device units, which are pixels at 96 DPI
+        size: if description.is_size_absolute() {
+          size * 72.0 / 96.0
+        } else {
+          size
+        },
+        weight: glib::translate::IntoGlib::into_glib(description.weight()) as u16,
+        italic: description.style() != pango::Style::Normal,
+      })
+    })
+  }
+
+  fn component(byte: u8) -> f64 {
+    byte as f64 / 255.0
+  }
+
+  fn byte(component: f64) -> u8 {
+    (component.clamp(0.0, 1.0) * 255.0).round() as u8
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use super::{Color, Font, Runtime, Window};
+  use cocoa::{
+    base::{id, nil, NO, YES},
+    foundation::NSString,
+  };
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Class, Object, Sel},
+    sel, sel_impl,
+  };
+  use std::ffi::CStr;
+
+  /// The AppKit weights, from 0 to 15, of the CSS weights from 100 to 900.
+  const WEIGHTS: [isize; 9] = [2, 3, 4, 5, 6, 8, 9, 10, 11];
+
+  const ITALIC_FONT_MASK: usize = 1;
+
+  pub fn pick_color<R: Runtime>(_window: &Window<R>, default: Option<Color>) -> Option<Color> {
+    unsafe {
+      let panel: id = msg_send![class!(NSColorPanel), sharedColorPanel];
+      let _: () = msg_send![panel, setShowsAlpha: YES];
+      if let Some(color) = default {
+        let color: id = msg_send![class!(NSColor),
+          colorWithSRGBRed: component(color.red)
+          green: component(color.green)
+          blue: component(color.blue)
+          alpha: component(color.alpha)
+        ];
+        let _: () = msg_send![panel, setColor: color];
+      }
+
+      run_modal(panel);
+
+      let color: id = msg_send![panel, color];
+      let color_space: id = msg_send![class!(NSColorSpace), sRGBColorSpace];
+      let color: id = msg_send![color, colorUsingColorSpace: color_space];
+      if color == nil {
+        return None;
+      }
+      let (mut red, mut green, mut blue, mut alpha) = (0f64, 0f64, 0f64, 0f64);
+      let _: () = msg_send![color,
+        getRed: &mut red as *mut f64
+        green: &mut green as *mut f64
+        blue: &mut blue as *mut f64
+        alpha: &mut alpha as *mut f64
+      ];
+      Some(Color {
+        red: byte(red),
+        green: byte(green),
+        blue: byte(blue),
+        alpha: byte(alpha),
+      })
+    }
+  }
+
+  pub fn pick_font<R: Runtime>(_window: &Window<R>, default: Option<Font>) -> Option<Font> {
+    unsafe {
+      let manager: id = msg_send![class!(NSFontManager), sharedFontManager];
+      let mut initial: id = nil;
+      if let Some(font) = &default {
+        let family = NSString::alloc(nil).init_str(&font.family);
+        let weight = WEIGHTS[(font.weight.clamp(100, 900) as usize + 50) / 100 - 1];
+        let traits = if font.italic { ITALIC_FONT_MASK } else { 0 };
+        initial = msg_send![manager,
+          fontWithFamily: family
+          traits: traits
+          weight: weight
+          size: font.size
+        ];
+        let _: () = msg_send![family, release];
+      }
+      if initial == nil {
+        initial = msg_send![class!(NSFont), systemFontOfSize: 0f64];
+      }
+      let _: () = msg_send![manager, setSelectedFont: initial isMultiple: NO];
+
+      let panel: id = msg_send![manager, fontPanel: YES];
+      run_modal(panel);
+
+      let font: id = msg_send![panel, panelConvertFont: initial];
+      if font == nil {
+        return None;
+      }
+      let family: id = msg_send![font, familyName];
+      let family: *const std::os::raw::c_char = msg_send![family, UTF8String];
+      let size: f64 = msg_send![font, pointSize];
+      let weight: isize = msg_send![manager, weightOfFont: font];
+      let traits: usize = msg_send![manager, traitsOfFont: font];
+      Some(Font {
+        family: CStr::from_ptr(family).to_string_lossy().into_owned(),
+        size,
+        weight: WEIGHTS
+          .iter()
+          .position(|w| *w >= weight)
+          .map(|i| (i as u16 + 1) * 100)
+          .unwrap_or(900),
+        italic: traits & ITALIC_FONT_MASK != 0,
+      })
+    }
+  }
+
+  /// Runs the panel as a modal window until it is closed.
+  ///
+  /// The color and font panels have no button to confirm the selection,
+  /// so closing them accepts it.
+  unsafe fn run_modal(panel: id) {
+    let delegate: id = msg_send![delegate_class(), new];
+    let previous_delegate: id = msg_send![panel, delegate];
+    let _: () = msg_send![panel, setDelegate: delegate];
+    let app: id = msg_send![class!(NSApplication), sharedApplication];
+    let _: isize = msg_send![app, runModalForWindow: panel];
+    let _: () = msg_send![panel, setDelegate: previous_delegate];
+    let _: () = msg_send![delegate, release];
+  }
+
+  /// A window delegate stopping the modal session when the panel is closed.
+  fn delegate_class() -> &'static Class {
+    const NAME: &str = "TauriPickerPanelDelegate";
+    Class::get(NAME).unwrap_or_else(|| {
+      let mut decl = ClassDecl::new(NAME, class!(NSObject)).unwrap();
+      unsafe {
+        decl.add_method(
+          sel!(windowWillClose:),
+          window_will_close as extern "C" fn(&Object, Sel, id),
+        );
+      }
+      decl.register()
+    })
+  }
+
+  extern "C" fn window_will_close(_this: &Object, _sel: Sel, _notification: id) {
+    unsafe {
+      let app: id = msg_send![class!(NSApplication), sharedApplication];
+      let _: () = msg_send![app, stopModal];
+    }
+  }
+
+  fn component(byte: u8) -> f64 {
+    byte as f64 / 255.0
+  }
+
+  fn byte(component: f64) -> u8 {
+    (component.clamp(0.0, 1.0) * 255.0).round() as u8
+  }
+}
+
+#[cfg(windows)]
+mod win32 {
+  use super::{Color, Font, Runtime, Window};
+  use windows::Win32::{
+    Graphics::Gdi::LOGFONTW,
+    UI::Controls::Dialogs::{
+      ChooseColorW, ChooseFontW, CC_ANYCOLOR, CC_FULLOPEN, CC_RGBINIT, CF_INITTOLOGFONTSTRUCT,
+      CF_SCREENFONTS, CHOOSECOLORW, CHOOSEFONTW,
+    },
+  };
+
+  pub fn pick_color<R: Runtime>(window: &Window<R>, default: Option<Color>) -> Option<Color> {
+    // the custom colors slots of the dialog, white until the user defines them
+    let mut custom_colors = [0x00ff_ffffu32; 16];
+    let mut options = CHOOSECOLORW {
+      lStructSize: std::mem::size_of::<CHOOSECOLORW>() as u32,
+      hwndOwner: window.hwnd().unwrap_or_default(),
+      lpCustColors: custom_colors.as_mut_ptr(),
+      Flags: CC_ANYCOLOR | CC_FULLOPEN,
+      ..Default::default()
+    };
+    if let Some(color) = default {
+      options.rgbResult = color.red as u32 | (color.green as u32) << 8 | (color.blue as u32) << 16;
+      options.Flags |= CC_RGBINIT;
+    }
+
+    unsafe { ChooseColorW(&mut options) }
+      .as_bool()
+      .then(|| Color {
+        red: (options.rgbResult & 0xff) as u8,
+        green: (options.rgbResult >> 8 & 0xff) as u8,
+        blue: (options.rgbResult >> 16 & 0xff) as u8,
+        alpha: u8::MAX,
+      })
+  }
+
+  pub fn pick_font<R: Runtime>(window: &Window<R>, default: Option<Font>) -> Option<Font> {
+    let mut log_font = LOGFONTW::default();
+    let mut options = CHOOSEFONTW {
+      lStructSize: std::mem::size_of::<CHOOSEFONTW>() as u32,
+      hwndOwner: window.hwnd().unwrap_or_default(),
+      lpLogFont: &mut log_font,
+      Flags: CF_SCREENFONTS,
+      ..Default::default()
+    };
+    if let Some(font) = default {
+      // the height is in logical units, which are pixels at 96 DPI
+      log_font.lfHeight = -(font.size * 96.0 / 72.0).round() as i32;
+      log_font.lfWeight = font.weight as i32;
+      log_font.lfItalic = font.italic as u8;
+      for (dst, src) in log_font
+        .lfFaceName
+        .iter_mut()
+        .zip(font.family.encode_utf16().take(31))
+      {
+        *dst = src;
+      }
+      options.Flags |= CF_INITTOLOGFONTSTRUCT;
+    }
+
+    if !unsafe { ChooseFontW(&mut options) }.as_bool() {
+      return None;
+    }
+    let face_name_len = log_font
+      .lfFaceName
+      .iter()
+      .position(|c| *c == 0)
+      .unwrap_or(log_font.lfFaceName.len());
+    Some(Font {
+      family: String::from_utf16_lossy(&log_font.lfFaceName[..face_name_len]),
+      size: options.iPointSize as f64 / 10.0,
+      weight: match log_font.lfWeight {
+        0 => 400,
+        weight => weight as u16,
+      },
+      italic: log_font.lfItalic != 0,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Color, Font};
+
+  #[test]
+  fn deserialize_defaults() {
+    let color: Color = serde_json::from_str(r#"{ "red": 255, "green": 128, "blue": 0 }"#).unwrap();
+    assert_eq!(
+      color,
+      Color {
+        red: 255,
+        green: 128,
+        blue: 0,
+        alpha: 255
+      }
+    );
+
+    let font: Font = serde_json::from_str(r#"{ "family": "Inter", "size": 12 }"#).unwrap();
+    assert_eq!(
+      font,
+      Font {
+        family: "Inter".into(),
+        size: 12.0,
+        weight: 400,
+        italic: false
+      }
+    );
+  }
+}
@@ -0,0 +1,782 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A download manager, queueing downloads to files with a concurrency limit.
+//!
+//! Downloads are written to a `<path>.part` file renamed when the transfer is complete,
+//! so paused and failed downloads resume with an HTTP `Range` request instead of starting over.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use tauri::api::download::{DownloadEvent, DownloadManagerBuilder, DownloadRequest};
+//!
+//! # fn run(checksum: &str) -> tauri::api::Result<()> {
+//! let manager = DownloadManagerBuilder::new()
+//!   .max_concurrent(2)
+//!   .on_event(|event| {
+//!     if let DownloadEvent::Completed { path, .. } = event {
+//!       println!("downloaded {}", path.display());
+//!     }
+//!   })
+//!   .build()?;
+//!
+//! let id = manager.enqueue(
+//!   DownloadRequest::new("https://tauri.app/meta/tauri_logo_dark.svg", "/tmp/logo.svg")?
+//!     .sha256(checksum),
+//! )?;
+//! manager.pause(id)?;
+//! manager.resume(id)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+  collections::{HashMap, VecDeque},
+  fmt,
+  io::{Read, Write},
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc, Mutex,
+  },
+};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use super::http::{Client, ClientBuilder, HttpRequestBuilder, Progress, StatusCode};
+use crate::scope::FsScope;
+
+/// The download identifier.
+pub type DownloadId = u32;
+
+/// The default maximum number of downloads running at the same time.
+const DEFAULT_MAX_CONCURRENT: usize = 3;
+
+/// Progress is reported every time this many bytes are received when the total length is unknown.
+const UNKNOWN_LENGTH_REPORT_INTERVAL: u64 = 1024 * 1024;
+
+// values of `Download::control`
+const CONTROL_RUN: u8 = 0;
+const CONTROL_PAUSE: u8 = 1;
+const CONTROL_CANCEL: u8 = 2;
+
+/// A file to download.
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+  url: Url,
+  path: PathBuf,
+  headers: HashMap<String, String>,
+  sha256: Option<String>,
+}
+
+impl DownloadRequest {
+  /// Creates a request to download the URL to the given path.
+  pub fn new(url: impl AsRef<str>, path: impl Into<PathBuf>) -> crate::api::Result<Self> {
+    Ok(Self {
+      url: Url::parse(url.as_ref())?,
+      path: path.into(),
+      headers: Default::default(),
+      sha256: None,
+    })
+  }
+
+  /// Adds a request header.
+  #[must_use]
+  pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    self.headers.insert(key.into(), value.into());
+    self
+  }
+
+  /// Sets the expected SHA-256 checksum of the file, as a hex string.
+  ///
+  /// The download fails and the file is removed if it does not match.
+  #[must_use]
+  pub fn sha256(mut self, checksum: impl Into<String>) -> Self {
+    self.sha256.replace(checksum.into().to_lowercase());
+    self
+  }
+
+  /// The downloaded URL.
+  pub fn url(&self) -> &Url {
+    &self.url
+  }
+
+  /// The destination path.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn part_path(&self) -> PathBuf {
+    let mut file_name = self.path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".part");
+    self.path.with_file_name(file_name)
+  }
+}
+
+/// The status of a download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum DownloadStatus {
+  /// Waiting for a download slot.
+  Queued,
+  /// Transferring.
+  Running,
+  /// Paused with [`DownloadManager::pause`].
+  Paused,
+  /// The file was downloaded.
+  Completed,
+  /// The download failed. It can be retried with [`DownloadManager::resume`].
+  Failed,
+  /// Cancelled with [`DownloadManager::cancel`].
+  Cancelled,
+}
+
+/// An event emitted by the [`DownloadManager`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum DownloadEvent {
+  /// The transfer started or resumed.
+  Started {
+    /// The download identifier.
+    id: DownloadId,
+  },
+  /// Bytes were received. Reported at most once per percent of the total.
+  Progress {
+    /// The download identifier.
+    id: DownloadId,
+    /// The transfer progress, including the bytes received before resuming.
+    progress: Progress,
+  },
+  /// The download was paused.
+  Paused {
+    /// The download identifier.
+    id: DownloadId,
+  },
+  /// The file was downloaded and its checksum matches.
+  Completed {
+    /// The download identifier.
+    id: DownloadId,
+    /// The destination path.
+    path: PathBuf,
+  },
+  /// The download failed.
+  Failed {
+    /// The download identifier.
+    id: DownloadId,
+    /// The error message.
+    error: String,
+  },
+  /// The download was cancelled and its partial file removed.
+  Cancelled {
+    /// The download identifier.
+    id: DownloadId,
+  },
+}
+
+type EventListener = Box<dyn Fn(DownloadEvent) + Send + Sync>;
+
+/// The builder of [`DownloadManager`].
+#[derive(Default)]
+pub struct DownloadManagerBuilder {
+  client: Option<Client>,
+  max_concurrent: Option<usize>,
+  scope: Option<FsScope>,
+  listeners: Vec<EventListener>,
+}
+
+impl fmt::Debug for DownloadManagerBuilder {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("DownloadManagerBuilder")
+      .field("client", &self.client)
+      .field("max_concurrent", &self.max_concurrent)
+      .field("scope", &self.scope)
+      .finish()
+  }
+}
+
+impl DownloadManagerBuilder {
+  /// Creates a new download manager builder with the default options.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Sets the HTTP client used to download the files. Defaults to a client with the default options.
+  #[must_use]
+  pub fn client(mut self, client: Client) -> Self {
+    self.client.replace(client);
+    self
+  }
+
+  /// Sets the maximum number of downloads running at the same time. Defaults to 3.
+  #[must_use]
+  pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+    self.max_concurrent.replace(max_concurrent.max(1));
+    self
+  }
+
+  /// Only allows downloading to the paths allowed by the given scope,
+  /// usually the application's [`crate::Manager::fs_scope`].
+  #[must_use]
+  pub fn scope(mut self, scope: FsScope) -> Self {
+    self.scope.replace(scope);
+    self
+  }
+
+  /// Registers a listener for the download events.
+  #[must_use]
+  pub fn on_event<F: Fn(DownloadEvent) + Send + Sync + 'static>(mut self, f: F) -> Self {
+    self.listeners.push(Box::new(f));
+    self
+  }
+
+  /// Builds the download manager.
+  pub fn build(self) -> crate::api::Result<DownloadManager> {
+    let client = match self.client {
+      Some(client) => client,
+      None => ClientBuilder::new().build()?,
+    };
+    Ok(DownloadManager {
+      inner: Arc::new(Inner {
+        client,
+        max_concurrent: self.max_concurrent.unwrap_or(DEFAULT_MAX_CONCURRENT),
+        scope: self.scope,
+        listeners: self.listeners,
+        state: Default::default(),
+      }),
+    })
+  }
+}
+
+struct Download {
+  request: DownloadRequest,
+  status: DownloadStatus,
+  control: Arc<AtomicU8>,
+}
+
+#[derive(Default)]
+struct State {
+  next_id: DownloadId,
+  downloads: HashMap<DownloadId, Download>,
+  queue: VecDeque<DownloadId>,
+  running: usize,
+}
+
+struct Inner {
+  client: Client,
+  max_concurrent: usize,
+  scope: Option<FsScope>,
+  listeners: Vec<EventListener>,
+  state: Mutex<State>,
+}
+
+impl Inner {
+  fn emit(&self, event: DownloadEvent) {
+    for listener in &self.listeners {
+      listener(event.clone());
+    }
+  }
+}
+
+/// Downloads files in the background, queueing them when the concurrency limit is reached.
+///
+/// The manager can be cloned to share it, e.g. with [`crate::Manager::manage`].
+#[derive(Clone)]
+pub struct DownloadManager {
+  inner: Arc<Inner>,
+}
+
+impl fmt::Debug for DownloadManager {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("DownloadManager")
+      .field("max_concurrent", &self.inner.max_concurrent)
+      .finish()
+  }
+}
+
+impl DownloadManager {
+  /// Queues a download, starting it if the concurrency limit allows it.
+  pub fn enqueue(&self, request: DownloadRequest) -> crate::api::Result<DownloadId> {
+    if let Some(scope) = &self.inner.scope {
+      if !scope.is_allowed(&request.path) {
+        return Err(crate::api::Error::Download(format!(
+          "path not allowed on the configured scope: {}",
+          request.path.display()
+        )));
+      }
+    }
+
+    let mut state = self.inner.state.lock().unwrap();
+    state.next_id += 1;
+    let id = state.next_id;
+    state.downloads.insert(
+      id,
+      Download {
+        request,
+        status: DownloadStatus::Queued,
+        control: Default::default(),
+      },
+    );
+    state.queue.push_back(id);
+    schedule(&self.inner, &mut state);
+    Ok(id)
+  }
+
+  /// Pauses a queued or running download, keeping the received bytes.
+  pub fn pause(&self, id: DownloadId) -> crate::api::Result<()> {
+    let mut state = self.inner.state.lock().unwrap();
+    let download = get(&mut state, id)?;
+    match download.status {
+      DownloadStatus::Queued => {
+        download.status = DownloadStatus::Paused;
+        state.queue.retain(|queued| *queued != id);
+        drop(state);
+        self.inner.emit(DownloadEvent::Paused { id });
+      }
+      // the task emits the event when it stops
+      DownloadStatus::Running => download.control.store(CONTROL_PAUSE, Ordering::SeqCst),
+      _ => {}
+    }
+    Ok(())
+  }
+
+  /// Queues a paused or failed download again, resuming the transfer where it stopped.
+  pub fn resume(&self, id: DownloadId) -> crate::api::Result<()> {
+    let mut state = self.inner.state.lock().unwrap();
+    let download = get(&mut state, id)?;
+    match download.status {
+      DownloadStatus::Paused | DownloadStatus::Failed => {
+        download.status = DownloadStatus::Queued;
+        state.queue.push_back(id);
+        schedule(&self.inner, &mut state);
+      }
+      // not stopped yet
+      DownloadStatus::Running => download.control.store(CONTROL_RUN, Ordering::SeqCst),
+      _ => {}
+    }
+    Ok(())
+  }
+
+  /// Cancels a download and removes its partial file.
+  pub fn cancel(&self, id: DownloadId) -> crate::api::Result<()> {
+    let mut state = self.inner.state.lock().unwrap();
+    let download = get(&mut state, id)?;
+    match download.status {
+      DownloadStatus::Running => download.control.store(CONTROL_CANCEL, Ordering::SeqCst),
+      DownloadStatus::Queued | DownloadStatus::Paused | DownloadStatus::Failed => {
+        download.status = DownloadStatus::Cancelled;
+        let part_path = download.request.part_path();
+        state.queue.retain(|queued| *queued != id);
+        drop(state);
+        let _ = std::fs::remove_file(part_path);
+        self.inner.emit(DownloadEvent::Cancelled { id });
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+
+  /// The status of a download.
+  pub fn status(&self, id: DownloadId) -> Option<DownloadStatus> {
+    let state = self.inner.state.lock().unwrap();
+    state.downloads.get(&id).map(|d| d.status)
+  }
+
+  /// The request of a download.
+  pub fn request(&self, id: DownloadId) -> Option<DownloadRequest> {
+    let state = self.inner.state.lock().unwrap();
+    state.downloads.get(&id).map(|d| d.request.clone())
+  }
+}
+
+fn get(state: &mut State, id: DownloadId) -> crate::api::Result<&mut Download> {
+  state
+    .downloads
+    .get_mut(&id)
+    .ok_or_else(|| crate::api::Error::Download(format!("unknown download id {}", id)))
+}
+
+/// Starts queued downloads while there are free slots.
+fn schedule(inner: &Arc<Inner>, state: &mut State) {
+  while state.running < inner.max_concurrent {
+    let id = match state.queue.pop_front() {
+      Some(id) => id,
+      None => break,
+    };
+    let download = state
+      .downloads
+      .get_mut(&id)
+      .expect("queued download exists");
+    download.status = DownloadStatus::Running;
+    download.control.store(CONTROL_RUN, Ordering::SeqCst);
+    let request = download.request.clone();
+    let control = download.control.clone();
+    state.running += 1;
+
+    let inner = inner.clone();
+    // the file is written with blocking calls, and so is the attohttpc body read,
+    // so the download runs on the blocking executor
+    crate::async_runtime::spawn_blocking(move || {
+      inner.emit(DownloadEvent::Started { id });
+      let result = transfer(&inner.client, &request, &control, |progress| {
+        inner.emit(DownloadEvent::Progress { id, progress })
+      });
+
+      let mut state = inner.state.lock().unwrap();
+      state.running -= 1;
+      let control = control.load(Ordering::SeqCst);
+      if matches!(result, Ok(false)) && control == CONTROL_RUN {
+        // resumed before the transfer stopped
+        if let Some(download) = state.downloads.get_mut(&id) {
+          download.status = DownloadStatus::Queued;
+          state.queue.push_front(id);
+        }
+        schedule(&inner, &mut state);
+        return;
+      }
+      let (status, event) = match (result, control) {
+        (Ok(true), _) => (
+          DownloadStatus::Completed,
+          DownloadEvent::Completed {
+            id,
+            path: request.path.clone(),
+          },
+        ),
+        (_, CONTROL_CANCEL) => {
+          let _ = std::fs::remove_file(request.part_path());
+          (DownloadStatus::Cancelled, DownloadEvent::Cancelled { id })
+        }
+        (Ok(false), _) => (DownloadStatus::Paused, DownloadEvent::Paused { id }),
+        (Err(error), _) => (
+          DownloadStatus::Failed,
+          DownloadEvent::Failed {
+            id,
+            error: error.to_string(),
+          },
+        ),
+      };
+      if let Some(download) = state.downloads.get_mut(&id) {
+        download.status = status;
+      }
+      schedule(&inner, &mut state);
+      drop(state);
+      inner.emit(event);
+    });
+  }
+}
+
+/// Downloads the file, returning `false` if it was interrupted by a pause or cancellation.
+///
+/// Blocks the current thread, so it must run on the blocking executor.
+fn transfer<F: FnMut(Progress)>(
+  client: &Client,
+  request: &DownloadRequest,
+  control: &AtomicU8,
+  mut on_progress: F,
+) -> crate::api::Result<bool> {
+  let part_path = request.part_path();
+  if let Some(parent) = part_path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let offset = std::fs::metadata(&part_path)
+    .map(|m| m.len())
+    .unwrap_or_default();
+
+  let mut http_request = HttpRequestBuilder::new("GET", request.url.as_str())?;
+  for (key, value) in &request.headers {
+    http_request = http_request.header(key, value)?;
+  }
+  if offset > 0 {
+    http_request = http_request.header("Range", format!("bytes={}-", offset))?;
+  }
+  let response = crate::async_runtime::block_on(client.send(http_request))?;
+
+  let status = response.status();
+  let offset = if status == StatusCode::PARTIAL_CONTENT {
+    offset
+  } else if status.is_success() {
+    // the server does not support ranges, start over
+    0
+  } else if status == StatusCode::RANGE_NOT_SATISFIABLE {
+    // the partial file does not match the remote file, the next attempt starts over
+    std::fs::remove_file(&part_path)?;
+    return Err(crate::api::Error::Download(
+      "the partial download does not match the remote file".into(),
+    ));
+  } else {
+    return Err(crate::api::Error::Download(format!(
+      "request failed with status {}",
+      status
+    )));
+  };
+
+  let total = response
+    .headers()
+    .get("Content-Length")
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.parse::<u64>().ok())
+    .map(|len| offset + len);
+  let report_interval = total.map_or(UNKNOWN_LENGTH_REPORT_INTERVAL, |total| (total / 100).max(1));
+
+  let mut file = std::fs::OpenOptions::new()
+    .create(true)
+    .write(true)
+    .append(offset > 0)
+    .truncate(offset == 0)
+    .open(&part_path)?;
+  let mut transferred = offset;
+  let mut next_report = offset;
+  let mut interrupted = false;
+  let result = crate::async_runtime::block_on(response.read_chunks(|chunk| {
+    if control.load(Ordering::SeqCst) != CONTROL_RUN {
+      interrupted = true;
+      return Err(
+        std::io::Error::new(std::io::ErrorKind::Interrupted, "download interrupted").into(),
+      );
+    }
+    file.write_all(chunk)?;
+    transferred += chunk.len() as u64;
+    if transferred >= next_report || Some(transferred) == total {
+      next_report = transferred + report_interval;
+      on_progress(Progress { transferred, total });
+    }
+    Ok(())
+  }));
+  file.flush()?;
+  drop(file);
+
+  if interrupted {
+    return Ok(false);
+  }
+  result?;
+
+  if let Some(expected) = &request.sha256 {
+    let checksum = sha256(&part_path)?;
+    if &checksum != expected {
+      std::fs::remove_file(&part_path)?;
+      return Err(crate::api::Error::Download(format!(
+        "checksum mismatch: expected {}, got {}",
+        expected, checksum
+      )));
+    }
+  }
+
+  std::fs::rename(&part_path, &request.path)?;
+  Ok(true)
+}
+
+/// The hex SHA-256 checksum of a file.
+fn sha256(path: &Path) -> crate::api::Result<String> {
+  let mut file = std::fs::File::open(path)?;
+  let mut hasher = Sha256::new();
+  let mut buf = vec![0; 64 * 1024];
+  loop {
+    let read = file.read(&mut buf)?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buf[..read]);
+  }
+  Ok(
+    hasher
+      .finalize()
+      .iter()
+      .map(|b| format!("{:02x}", b))
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    sha256, DownloadEvent, DownloadManager, DownloadManagerBuilder, DownloadRequest, DownloadStatus,
+  };
+  use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::PathBuf,
+    sync::{
+      mpsc::{channel, Receiver},
+      Mutex,
+    },
+    time::Duration,
+  };
+
+  fn manager() -> (DownloadManager, Receiver<DownloadEvent>) {
+    let (tx, rx) = channel();
+    let tx = Mutex::new(tx);
+    let manager = DownloadManagerBuilder::new()
+      .on_event(move |event| {
+        let _ = tx.lock().unwrap().send(event);
+      })
+      .build()
+      .unwrap();
+    (manager, rx)
+  }
+
+  fn wait_for<F: Fn(&DownloadEvent) -> bool>(events: &Receiver<DownloadEvent>, f: F) {
+    loop {
+      let event = events
+        .recv_timeout(Duration::from_secs(10))
+        .expect("download event not received");
+      if let DownloadEvent::Failed { error, .. } = &event {
+        panic!("download failed: {}", error);
+      }
+      if f(&event) {
+        break;
+      }
+    }
+  }
+
+  fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tauri-download-{}-{}", std::process::id(), name))
+  }
+
+  /// Serves the body, honoring the `Range` requests and sending their header values to the returned channel.
+  /// The first response stops after `split` bytes until `gate` receives a message.
+  fn serve(
+    body: &'static [u8],
+    split: usize,
+    gate: Receiver<()>,
+  ) -> (String, Receiver<Option<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/file", listener.local_addr().unwrap());
+    let (tx, rx) = channel();
+    std::thread::spawn(move || {
+      for (i, stream) in listener.incoming().enumerate() {
+        let mut stream = stream.unwrap();
+        let mut range = None;
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        loop {
+          let mut line = String::new();
+          reader.read_line(&mut line).unwrap();
+          let line = line.trim_end();
+          if line.is_empty() {
+            break;
+          }
+          if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("range") {
+              range.replace(value.trim().to_string());
+            }
+          }
+        }
+        let start = range
+          .as_deref()
+          .and_then(|range| range.strip_prefix("bytes="))
+          .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok())
+          .unwrap_or_default();
+        let _ = tx.send(range);
+
+        let status = if start > 0 {
+          "206 Partial Content"
+        } else {
+          "200 OK"
+        };
+        let _ = write!(
+          stream,
+          "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+          status,
+          body.len() - start
+        );
+        if i == 0 {
+          let _ = stream.write_all(&body[start..split]);
+          let _ = stream.flush();
+          let _ = gate.recv();
+          let _ = stream.write_all(&body[split..]);
+        } else {
+          let _ = stream.write_all(&body[start..]);
+        }
+      }
+    });
+    (url, rx)
+  }
+
+  #[test]
+  fn part_path() {
+    let request = DownloadRequest::new("https://tauri.app/app.tar.gz", "/tmp/app.tar.gz").unwrap();
+    assert_eq!(
+      request.part_path(),
+      std::path::PathBuf::from("/tmp/app.tar.gz.part")
+    );
+  }
+
+  #[test]
+  fn checksum() {
+    let path = std::env::temp_dir().join(format!("tauri-download-{}.txt", std::process::id()));
+    std::fs::write(&path, "tauri").unwrap();
+    assert_eq!(
+      sha256(&path).unwrap(),
+      "238bc7b5d614886683a514ef66cbe5ff1771798bf05499486fcedee3cfed6175"
+    );
+    std::fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn resume_partial_file() {
+    let _m = mockito::mock("GET", "/download")
+      .match_header("Range", "bytes=5-")
+      .with_status(206)
+      .with_body(" app!")
+      .create();
+
+    let path = temp_path("range.txt");
+    let request = DownloadRequest::new(format!("{}/download", mockito::server_url()), &path)
+      .unwrap()
+      .sha256("ac67c0498eceb2edcb71e51e28b9e38d7af9c8a55bb78f6c00c7077a981f750c");
+    std::fs::write(request.part_path(), "tauri").unwrap();
+
+    let (manager, events) = manager();
+    let id = manager.enqueue(request).unwrap();
+    wait_for(&events, |event| {
+      matches!(event, DownloadEvent::Completed { .. })
+    });
+    assert_eq!(manager.status(id), Some(DownloadStatus::Completed));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "tauri app!");
+    std::fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn pause_and_resume() {
+    let body = b"0123456789abcdefghij";
+    let (gate, gate_rx) = channel();
+    let (url, ranges) = serve(body, 10, gate_rx);
+
+    let path = temp_path("pause.txt");
+    let request = DownloadRequest::new(url, &path).unwrap();
+    let part_path = request.part_path();
+    let (manager, events) = manager();
+    let id = manager.enqueue(request).unwrap();
+
+    // the first half is received, then the download is paused before writing the second one
+    wait_for(
+      &events,
+      |event| matches!(event, DownloadEvent::Progress { progress, .. } if progress.transferred == 10),
+    );
+    manager.pause(id).unwrap();
+    gate.send(()).unwrap();
+    wait_for(&events, |event| {
+      matches!(event, DownloadEvent::Paused { .. })
+    });
+    assert_eq!(manager.status(id), Some(DownloadStatus::Paused));
+    assert_eq!(std::fs::read(&part_path).unwrap(), &body[..10]);
+
+    // resuming requests the rest of the file
+    manager.resume(id).unwrap();
+    wait_for(&events, |event| {
+      matches!(event, DownloadEvent::Completed { .. })
+    });
+    assert_eq!(manager.status(id), Some(DownloadStatus::Completed));
+    assert_eq!(std::fs::read(&path).unwrap(), body);
+    assert!(!part_path.exists());
+    assert_eq!(
+      ranges.try_iter().collect::<Vec<_>>(),
+      vec![None, Some("bytes=10-".to_string())]
+    );
+    std::fs::remove_file(path).unwrap();
+  }
+}
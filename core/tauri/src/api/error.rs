@@ -30,6 +30,21 @@ pub enum Error {
   #[cfg_attr(doc_cfg, doc(cfg(feature = "reqwest-client")))]
   #[error("Network Error: {0}")]
   Network(#[from] reqwest::Error),
+  /// Download manager error.
+  #[cfg(feature = "download-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "download-api")))]
+  #[error("download error: {0}")]
+  Download(String),
+  /// WebSocket error.
+  #[cfg(feature = "websocket-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "websocket-api")))]
+  #[error("WebSocket error: {0}")]
+  WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+  /// mDNS error.
+  #[cfg(feature = "mdns-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "mdns-api")))]
+  #[error("mDNS error: {0}")]
+  Mdns(#[from] mdns_sd::Error),
   /// HTTP method error.
   #[error(transparent)]
   HttpMethod(#[from] http::method::InvalidMethod),
@@ -67,10 +82,50 @@ pub enum Error {
   #[cfg(feature = "fs-extract-api")]
   #[error("Failed to extract: {0}")]
   Extract(String),
+  /// File system watcher error.
+  #[cfg(feature = "fs-watch-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "fs-watch-api")))]
+  #[error(transparent)]
+  Watch(#[from] notify::Error),
+  /// Trash error.
+  #[cfg(all(desktop, feature = "fs-trash-api"))]
+  #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "fs-trash-api"))))]
+  #[error(transparent)]
+  Trash(#[from] trash::Error),
+  /// Credential store error.
+  #[cfg(all(desktop, feature = "keyring-api"))]
+  #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "keyring-api"))))]
+  #[error(transparent)]
+  Keyring(#[from] keyring::Error),
+  /// Serial port error.
+  #[cfg(all(desktop, feature = "serial-api"))]
+  #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "serial-api"))))]
+  #[error("serial port error: {0}")]
+  Serial(#[from] serialport::Error),
+  /// USB error.
+  #[cfg(all(desktop, feature = "serial-api"))]
+  #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "serial-api"))))]
+  #[error("USB error: {0}")]
+  Usb(#[from] rusb::Error),
   /// Notification error.
   #[cfg(notification_all)]
   #[error(transparent)]
   Notification(#[from] notify_rust::error::Error),
+  /// SQLite error.
+  #[cfg(feature = "store-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "store-api")))]
+  #[error(transparent)]
+  Sqlite(#[from] rusqlite::Error),
+  /// Store error.
+  #[cfg(feature = "store-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "store-api")))]
+  #[error("store error: {0}")]
+  Store(String),
+  /// OAuth authorization error.
+  #[cfg(feature = "oauth-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "oauth-api")))]
+  #[error("OAuth error: {0}")]
+  OAuth(String),
   /// Url error.
   #[error(transparent)]
   Url(#[from] url::ParseError),
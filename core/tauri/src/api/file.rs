@@ -7,6 +7,8 @@
 #[cfg(feature = "fs-extract-api")]
 mod extract;
 mod file_move;
+#[cfg(feature = "fs-watch-api")]
+mod watch;
 
 use std::{
   fs,
@@ -16,6 +18,9 @@ use std::{
 #[cfg(feature = "fs-extract-api")]
 pub use extract::*;
 pub use file_move::*;
+#[cfg(feature = "fs-watch-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "fs-watch-api")))]
+pub use watch::*;
 
 use serde::{de::Error as DeError, Deserialize, Deserializer};
 
@@ -71,6 +76,18 @@ pub fn read_binary<P: AsRef<Path>>(file: P) -> crate::api::Result<Vec<u8>> {
   fs::read(file).map_err(Into::into)
 }
 
+/// Moves a file or directory to the trash of the system, so it can be restored by the user.
+///
+/// ## Platform-specific
+///
+/// - **Windows**: Moves it to the Recycle Bin.
+/// - **Linux**: Follows the [FreeDesktop.org trash specification](https://specifications.freedesktop.org/trash-spec/trashspec-latest.html).
+#[cfg(all(desktop, feature = "fs-trash-api"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "fs-trash-api"))))]
+pub fn trash<P: AsRef<Path>>(path: P) -> crate::api::Result<()> {
+  ::trash::delete(path).map_err(Into::into)
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -150,4 +167,15 @@ mod test {
       assert_eq!(e.to_string(), "Is a directory (os error 21)".to_string());
     }
   }
+
+  #[cfg(all(desktop, feature = "fs-trash-api"))]
+  #[test]
+  fn check_trash() {
+    let path = std::env::temp_dir().join(format!("tauri-trash-{}.txt", std::process::id()));
+    std::fs::write(&path, "trashed").unwrap();
+    trash(&path).unwrap();
+    assert!(!path.exists());
+
+    assert!(matches!(trash(&path), Err(crate::api::Error::Trash(_))));
+  }
 }
@@ -0,0 +1,331 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Watching files and directories for changes.
+
+use std::{
+  collections::HashMap,
+  fmt,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    mpsc::{channel, Receiver, RecvTimeoutError},
+    Arc, Mutex,
+  },
+  time::{Duration, Instant},
+};
+
+use notify::{
+  event::{EventKind, ModifyKind, RenameMode},
+  RecommendedWatcher, RecursiveMode, Watcher as _,
+};
+use serde::Serialize;
+
+/// The identifier of a [`Watcher`] subscription.
+pub type SubscriptionId = u32;
+
+type Subscriber = Box<dyn Fn(&[WatchEvent]) + Send>;
+type Subscribers = Arc<Mutex<HashMap<SubscriptionId, Subscriber>>>;
+
+/// The kind of a [`WatchEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum WatchEventKind {
+  /// The path was created, or renamed to.
+  Create,
+  /// The contents or the metadata of the path changed.
+  Modify,
+  /// The path was removed, or renamed from.
+  Remove,
+}
+
+/// A change of a watched path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct WatchEvent {
+  /// The kind of the change.
+  pub kind: WatchEventKind,
+  /// The path that changed.
+  pub path: PathBuf,
+}
+
+/// The options of [`watch`].
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+  recursive: bool,
+  debounce: Duration,
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    Self {
+      recursive: true,
+      debounce: Duration::from_millis(500),
+    }
+  }
+}
+
+impl WatchOptions {
+  /// Whether the directories are watched recursively. Defaults to `true`.
+  #[must_use]
+  pub fn recursive(mut self, recursive: bool) -> Self {
+    self.recursive = recursive;
+    self
+  }
+
+  /// How long the changes are collected before they are delivered. Defaults to 500 milliseconds.
+  ///
+  /// The changes of a path within that time are merged into a single event,
+  /// e.g. a file created then modified is reported as created.
+  #[must_use]
+  pub fn debounce(mut self, debounce: Duration) -> Self {
+    self.debounce = debounce;
+    self
+  }
+}
+
+/// Watches paths for changes, delivering them to its subscribers until it is dropped.
+pub struct Watcher {
+  _watcher: RecommendedWatcher,
+  subscribers: Subscribers,
+  next_id: AtomicU32,
+}
+
+impl fmt::Debug for Watcher {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Watcher").finish()
+  }
+}
+
+impl Watcher {
+  /// Calls the handler with each batch of debounced events.
+  pub fn subscribe<F: Fn(&[WatchEvent]) + Send + 'static>(&self, handler: F) -> SubscriptionId {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    self
+      .subscribers
+      .lock()
+      .unwrap()
+      .insert(id, Box::new(handler));
+    id
+  }
+
+  /// Stops calling the handler of the given subscription.
+  pub fn unsubscribe(&self, id: SubscriptionId) {
+    self.subscribers.lock().unwrap().remove(&id);
+  }
+}
+
+/// Watches the given files and directories for changes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::file::{watch, WatchOptions};
+///
+/// let watcher = watch(["/home/user/notes"], WatchOptions::default()).unwrap();
+/// watcher.subscribe(|events| {
+///   for event in events {
+///     println!("{:?} {}", event.kind, event.path.display());
+///   }
+/// });
+/// ```
+pub fn watch<P: AsRef<Path>>(
+  paths: impl IntoIterator<Item = P>,
+  options: WatchOptions,
+) -> crate::api::Result<Watcher> {
+  let (tx, rx) = channel();
+  let mut watcher = notify::recommended_watcher(move |event| {
+    let _ = tx.send(event);
+  })?;
+  let mode = if options.recursive {
+    RecursiveMode::Recursive
+  } else {
+    RecursiveMode::NonRecursive
+  };
+  for path in paths {
+    watcher.watch(path.as_ref(), mode)?;
+  }
+
+  let subscribers = Subscribers::default();
+  let subscribers_ = subscribers.clone();
+  std::thread::spawn(move || debounce(rx, options.debounce, subscribers_));
+
+  Ok(Watcher {
+    _watcher: watcher,
+    subscribers,
+    next_id: AtomicU32::new(0),
+  })
+}
+
+/// Collects the events for the debounce duration after the first one, then delivers them.
+/// Returns when the notify watcher is dropped.
+fn debounce(
+  rx: Receiver<notify::Result<notify::Event>>,
+  delay: Duration,
+  subscribers: Subscribers,
+) {
+  let mut pending = Vec::new();
+  let mut deadline = Instant::now();
+  loop {
+    let received = if pending.is_empty() {
+      rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+    } else {
+      rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+    };
+    match received {
+      Ok(Ok(event)) => {
+        if pending.is_empty() {
+          deadline = Instant::now() + delay;
+        }
+        for event in convert(event) {
+          merge(&mut pending, event);
+        }
+      }
+      // the errors of the platform watcher do not prevent the next events
+      Ok(Err(_)) => (),
+      Err(RecvTimeoutError::Timeout) => deliver(&subscribers, &mut pending),
+      Err(RecvTimeoutError::Disconnected) => {
+        deliver(&subscribers, &mut pending);
+        break;
+      }
+    }
+  }
+}
+
+fn deliver(subscribers: &Subscribers, pending: &mut Vec<WatchEvent>) {
+  if !pending.is_empty() {
+    for handler in subscribers.lock().unwrap().values() {
+      handler(pending);
+    }
+    pending.clear();
+  }
+}
+
+/// Maps a notify event to the changes of its paths.
+fn convert(event: notify::Event) -> Vec<WatchEvent> {
+  let kind = match event.kind {
+    EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+      WatchEventKind::Create
+    }
+    EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+      WatchEventKind::Remove
+    }
+    EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+      let mut paths = event.paths.into_iter();
+      return paths
+        .next()
+        .map(|path| WatchEvent {
+          kind: WatchEventKind::Remove,
+          path,
+        })
+        .into_iter()
+        .chain(paths.map(|path| WatchEvent {
+          kind: WatchEventKind::Create,
+          path,
+        }))
+        .collect();
+    }
+    // some platforms do not tell which side of the rename the path is
+    EventKind::Modify(ModifyKind::Name(_)) => {
+      return event
+        .paths
+        .into_iter()
+        .map(|path| WatchEvent {
+          kind: if path.exists() {
+            WatchEventKind::Create
+          } else {
+            WatchEventKind::Remove
+          },
+          path,
+        })
+        .collect();
+    }
+    EventKind::Modify(_) | EventKind::Any => WatchEventKind::Modify,
+    EventKind::Access(_) | EventKind::Other => return Vec::new(),
+  };
+  event
+    .paths
+    .into_iter()
+    .map(|path| WatchEvent { kind, path })
+    .collect()
+}
+
+/// Merges the event into the pending change of its path.
+fn merge(pending: &mut Vec<WatchEvent>, event: WatchEvent) {
+  let index = match pending.iter().position(|e| e.path == event.path) {
+    Some(index) => index,
+    None => {
+      pending.push(event);
+      return;
+    }
+  };
+  let kind = match (pending[index].kind, event.kind) {
+    // the path only existed between two deliveries
+    (WatchEventKind::Create, WatchEventKind::Remove) => {
+      pending.remove(index);
+      return;
+    }
+    (WatchEventKind::Create, _) => WatchEventKind::Create,
+    (_, WatchEventKind::Remove) => WatchEventKind::Remove,
+    _ => WatchEventKind::Modify,
+  };
+  pending[index].kind = kind;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{merge, watch, WatchEvent, WatchEventKind, WatchOptions};
+  use std::{path::PathBuf, sync::mpsc::channel, time::Duration};
+
+  fn event(kind: WatchEventKind, path: &str) -> WatchEvent {
+    WatchEvent {
+      kind,
+      path: PathBuf::from(path),
+    }
+  }
+
+  #[test]
+  fn merges_events() {
+    use WatchEventKind::*;
+
+    let mut pending = Vec::new();
+    merge(&mut pending, event(Create, "a"));
+    merge(&mut pending, event(Modify, "a"));
+    merge(&mut pending, event(Modify, "b"));
+    merge(&mut pending, event(Remove, "b"));
+    merge(&mut pending, event(Remove, "c"));
+    merge(&mut pending, event(Create, "c"));
+    merge(&mut pending, event(Create, "d"));
+    merge(&mut pending, event(Remove, "d"));
+
+    assert_eq!(
+      pending,
+      vec![event(Create, "a"), event(Remove, "b"), event(Modify, "c")]
+    );
+  }
+
+  #[test]
+  fn watches_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let watcher = watch(
+      [dir.path()],
+      WatchOptions::default().debounce(Duration::from_millis(100)),
+    )
+    .unwrap();
+    let (tx, rx) = channel();
+    watcher.subscribe(move |events| {
+      let _ = tx.send(events.to_vec());
+    });
+
+    let file = dir.path().join("file.txt");
+    std::fs::write(&file, "contents").unwrap();
+
+    let events = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let file = file.canonicalize().unwrap();
+    assert!(events.iter().any(
+      |e| e.kind == WatchEventKind::Create && e.path.canonicalize().ok() == Some(file.clone())
+    ));
+  }
+}
@@ -4,6 +4,11 @@
 
 //! Types and functions related to HTTP request.
 
+mod body;
+mod cookies;
+
+use body::StreamBody;
+pub use cookies::{Cookie, CookieStore};
 use http::Method;
 pub use http::StatusCode;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -11,7 +16,13 @@ use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use url::Url;
 
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+use std::{
+  collections::HashMap,
+  fmt,
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
+};
 
 #[cfg(feature = "reqwest-client")]
 pub use reqwest::header;
@@ -50,6 +61,9 @@ pub struct ClientBuilder {
   /// Connect timeout for the request.
   #[serde(deserialize_with = "deserialize_duration", default)]
   pub connect_timeout: Option<Duration>,
+  /// The store of the cookies sent and received by the client.
+  #[serde(skip)]
+  pub cookie_store: Option<Arc<CookieStore>>,
 }
 
 impl ClientBuilder {
@@ -72,6 +86,15 @@ impl ClientBuilder {
     self
   }
 
+  /// Sets the cookie store, so the cookies set by the servers are sent on the following requests.
+  ///
+  /// The store can be shared between clients.
+  #[must_use]
+  pub fn cookie_store(mut self, cookie_store: Arc<CookieStore>) -> Self {
+    self.cookie_store.replace(cookie_store);
+    self
+  }
+
   /// Builds the Client.
   #[cfg(not(feature = "reqwest-client"))]
   pub fn build(self) -> crate::api::Result<Client> {
@@ -96,14 +119,14 @@ impl ClientBuilder {
     }
 
     let client = client_builder.build()?;
-    Ok(Client(client))
+    Ok(Client(client, self.cookie_store))
   }
 }
 
 /// The HTTP client based on [`reqwest`].
 #[cfg(feature = "reqwest-client")]
 #[derive(Debug, Clone)]
-pub struct Client(reqwest::Client);
+pub struct Client(reqwest::Client, Option<Arc<CookieStore>>);
 
 /// The HTTP client.
 #[cfg(not(feature = "reqwest-client"))]
@@ -130,8 +153,9 @@ impl Client {
   ///   }
   /// }
   /// ```
-  pub async fn send(&self, request: HttpRequestBuilder) -> crate::api::Result<Response> {
+  pub async fn send(&self, mut request: HttpRequestBuilder) -> crate::api::Result<Response> {
     let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
+    let stream_body = request.take_stream_body()?;
 
     let mut request_builder = attohttpc::RequestBuilder::try_new(method, &request.url)?;
 
@@ -145,6 +169,10 @@ impl Client {
       }
     }
 
+    if let Some(cookie) = self.cookie_header(&request.url, request.headers.as_ref()) {
+      request_builder = request_builder.header(header::COOKIE, cookie);
+    }
+
     if let Some(max_redirections) = self.0.max_redirections {
       if max_redirections == 0 {
         request_builder = request_builder.follow_redirects(false);
@@ -157,61 +185,18 @@ impl Client {
       request_builder = request_builder.timeout(timeout);
     }
 
-    let response = if let Some(body) = request.body {
+    let response = if let Some(body) = stream_body {
+      request_builder.body(body).send()?
+    } else if let Some(body) = request.body {
       match body {
         Body::Bytes(data) => request_builder.body(attohttpc::body::Bytes(data)).send()?,
         Body::Text(text) => request_builder.body(attohttpc::body::Bytes(text)).send()?,
         Body::Json(json) => request_builder.json(&json)?.send()?,
         Body::Form(form_body) => {
-          #[allow(unused_variables)]
           fn send_form(
             request_builder: attohttpc::RequestBuilder,
-            headers: &Option<HeaderMap>,
             form_body: FormBody,
           ) -> crate::api::Result<attohttpc::Response> {
-            #[cfg(feature = "http-multipart")]
-            if matches!(
-              headers
-                .as_ref()
-                .and_then(|h| h.0.get("content-type"))
-                .map(|v| v.as_bytes()),
-              Some(b"multipart/form-data")
-            ) {
-              let mut multipart = attohttpc::MultipartBuilder::new();
-              let mut byte_cache: HashMap<String, Vec<u8>> = Default::default();
-
-              for (name, part) in &form_body.0 {
-                if let FormPart::File { file, .. } = part {
-                  byte_cache.insert(name.to_string(), file.clone().try_into()?);
-                }
-              }
-              for (name, part) in &form_body.0 {
-                multipart = match part {
-                  FormPart::File {
-                    file,
-                    mime,
-                    file_name,
-                  } => {
-                    // safe to unwrap: always set by previous loop
-                    let mut file =
-                      attohttpc::MultipartFile::new(name, byte_cache.get(name).unwrap());
-                    if let Some(mime) = mime {
-                      file = file.with_type(mime)?;
-                    }
-                    if let Some(file_name) = file_name {
-                      file = file.with_filename(file_name);
-                    }
-                    multipart.with_file(file)
-                  }
-                  FormPart::Text(value) => multipart.with_text(name, value),
-                };
-              }
-              return request_builder
-                .body(multipart.build()?)
-                .send()
-                .map_err(Into::into);
-            }
-
             let mut form = Vec::new();
             for (name, part) in form_body.0 {
               match part {
@@ -225,13 +210,18 @@ impl Client {
             request_builder.form(&form)?.send().map_err(Into::into)
           }
 
-          send_form(request_builder, &request.headers, form_body)?
+          send_form(request_builder, form_body)?
         }
       }
     } else {
       request_builder.send()?
     };
 
+    if let Some(cookie_store) = self.cookie_store() {
+      // the cookies of a redirected request are set by the final URL
+      cookie_store.store_response_cookies(response.url(), response.headers());
+    }
+
     Ok(Response(
       request.response_type.unwrap_or(ResponseType::Json),
       response,
@@ -247,6 +237,7 @@ impl Client {
   /// # Examples
   pub async fn send(&self, mut request: HttpRequestBuilder) -> crate::api::Result<Response> {
     let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
+    let stream_body = request.take_stream_body()?;
 
     let mut request_builder = self.0.request(method, request.url.as_str());
 
@@ -258,56 +249,20 @@ impl Client {
       request_builder = request_builder.timeout(timeout);
     }
 
-    if let Some(body) = request.body {
+    if let Some(body) = stream_body {
+      request_builder = request_builder
+        .header(header::CONTENT_LENGTH, body.len())
+        .body(reqwest::Body::wrap_stream(body.into_stream()));
+    } else if let Some(body) = request.body {
       request_builder = match body {
         Body::Bytes(data) => request_builder.body(bytes::Bytes::from(data)),
         Body::Text(text) => request_builder.body(bytes::Bytes::from(text)),
         Body::Json(json) => request_builder.json(&json),
         Body::Form(form_body) => {
-          #[allow(unused_variables)]
           fn send_form(
             request_builder: reqwest::RequestBuilder,
-            headers: &mut Option<HeaderMap>,
             form_body: FormBody,
           ) -> crate::api::Result<reqwest::RequestBuilder> {
-            #[cfg(feature = "http-multipart")]
-            if matches!(
-              headers
-                .as_ref()
-                .and_then(|h| h.0.get("content-type"))
-                .map(|v| v.as_bytes()),
-              Some(b"multipart/form-data")
-            ) {
-              // the Content-Type header will be set by reqwest in the `.multipart` call
-              headers.as_mut().map(|h| h.0.remove("content-type"));
-              let mut multipart = reqwest::multipart::Form::new();
-
-              for (name, part) in form_body.0 {
-                let part = match part {
-                  FormPart::File {
-                    file,
-                    mime,
-                    file_name,
-                  } => {
-                    let bytes: Vec<u8> = file.try_into()?;
-                    let mut part = reqwest::multipart::Part::bytes(bytes);
-                    if let Some(mime) = mime {
-                      part = part.mime_str(&mime)?;
-                    }
-                    if let Some(file_name) = file_name {
-                      part = part.file_name(file_name);
-                    }
-                    part
-                  }
-                  FormPart::Text(value) => reqwest::multipart::Part::text(value),
-                };
-
-                multipart = multipart.part(name, part);
-              }
-
-              return Ok(request_builder.multipart(multipart));
-            }
-
             let mut form = Vec::new();
             for (name, part) in form_body.0 {
               match part {
@@ -320,11 +275,15 @@ impl Client {
             }
             Ok(request_builder.form(&form))
           }
-          send_form(request_builder, &mut request.headers, form_body)?
+          send_form(request_builder, form_body)?
         }
       };
     }
 
+    if let Some(cookie) = self.cookie_header(&request.url, request.headers.as_ref()) {
+      request_builder = request_builder.header(header::COOKIE, cookie);
+    }
+
     if let Some(headers) = request.headers {
       request_builder = request_builder.headers(headers.0);
     }
@@ -333,6 +292,10 @@ impl Client {
 
     let response = self.0.execute(http_request).await?;
 
+    if let Some(cookie_store) = self.cookie_store() {
+      cookie_store.store_response_cookies(response.url(), response.headers());
+    }
+
     Ok(Response(
       request.response_type.unwrap_or(ResponseType::Json),
       response,
@@ -340,6 +303,25 @@ impl Client {
   }
 }
 
+impl Client {
+  fn cookie_store(&self) -> Option<&Arc<CookieStore>> {
+    #[cfg(feature = "reqwest-client")]
+    let cookie_store = &self.1;
+    #[cfg(not(feature = "reqwest-client"))]
+    let cookie_store = &self.0.cookie_store;
+    cookie_store.as_ref()
+  }
+
+  /// The `Cookie` header of a request, unless it is set explicitly.
+  fn cookie_header(&self, url: &Url, headers: Option<&HeaderMap>) -> Option<String> {
+    if headers.map_or(false, |h| h.0.contains_key(header::COOKIE)) {
+      None
+    } else {
+      self.cookie_store()?.header(url)
+    }
+  }
+}
+
 #[derive(Serialize_repr, Deserialize_repr, Clone, Debug)]
 #[repr(u16)]
 #[non_exhaustive]
@@ -422,6 +404,38 @@ pub enum Body {
   Bytes(Vec<u8>),
 }
 
+/// The progress of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Progress {
+  /// The number of bytes transferred so far.
+  pub transferred: u64,
+  /// The total number of bytes, if known.
+  pub total: Option<u64>,
+}
+
+/// A closure called with the [`Progress`] of a transfer.
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressHandler {
+  /// Creates a handler from the given closure.
+  pub fn new<F: Fn(Progress) + Send + Sync + 'static>(f: F) -> Self {
+    Self(Arc::new(f))
+  }
+
+  pub(crate) fn call(&self, progress: Progress) {
+    (self.0)(progress)
+  }
+}
+
+impl fmt::Debug for ProgressHandler {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ProgressHandler").finish()
+  }
+}
+
 /// A set of HTTP headers.
 #[derive(Debug, Default)]
 pub struct HeaderMap(header::HeaderMap);
@@ -487,6 +501,9 @@ pub struct HttpRequestBuilder {
   pub timeout: Option<Duration>,
   /// The response type (defaults to Json)
   pub response_type: Option<ResponseType>,
+  /// The handler of the upload progress.
+  #[serde(skip)]
+  pub upload_progress: Option<ProgressHandler>,
 }
 
 impl HttpRequestBuilder {
@@ -500,6 +517,7 @@ impl HttpRequestBuilder {
       body: None,
       timeout: None,
       response_type: None,
+      upload_progress: None,
     })
   }
 
@@ -555,6 +573,73 @@ impl HttpRequestBuilder {
     self.response_type = Some(response_type);
     self
   }
+
+  /// Sets a closure called with the progress of the body upload.
+  ///
+  /// The progress is reported for [`Body::Bytes`], [`Body::Text`], [`Body::Json`]
+  /// and `multipart/form-data` [`Body::Form`] bodies, at most once per percent.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::api::http::{Body, FilePart, FormBody, FormPart, HttpRequestBuilder};
+  /// use std::collections::HashMap;
+  ///
+  /// let mut form = HashMap::new();
+  /// form.insert("video".into(), FormPart::File {
+  ///   file: FilePart::Path("/home/user/video.mp4".into()),
+  ///   mime: Some("video/mp4".into()),
+  ///   file_name: None,
+  /// });
+  /// let request = HttpRequestBuilder::new("POST", "https://example.com/upload")
+  ///   .unwrap()
+  ///   .header("Content-Type", "multipart/form-data")
+  ///   .unwrap()
+  ///   .body(Body::Form(FormBody::new(form)))
+  ///   .on_upload_progress(|progress| println!("uploaded {} bytes", progress.transferred));
+  /// ```
+  #[must_use]
+  pub fn on_upload_progress<F: Fn(Progress) + Send + Sync + 'static>(mut self, f: F) -> Self {
+    self.upload_progress.replace(ProgressHandler::new(f));
+    self
+  }
+
+  /// Takes the body if it must be streamed, which is the case for multipart forms and
+  /// when the upload progress is tracked, setting its `Content-Type` header.
+  fn take_stream_body(&mut self) -> crate::api::Result<Option<StreamBody>> {
+    let progress = self.upload_progress.take();
+    let body = match self.body.take() {
+      #[cfg(feature = "http-multipart")]
+      Some(Body::Form(form)) if self.is_multipart() => StreamBody::multipart(form)?,
+      Some(Body::Bytes(data)) if progress.is_some() => StreamBody::bytes(data, None),
+      Some(Body::Text(text)) if progress.is_some() => StreamBody::bytes(text.into_bytes(), None),
+      Some(Body::Json(json)) if progress.is_some() => {
+        StreamBody::bytes(serde_json::to_vec(&json)?, Some("application/json"))
+      }
+      body => {
+        self.body = body;
+        return Ok(None);
+      }
+    };
+
+    if let Some(content_type) = body.content_type() {
+      self
+        .headers
+        .get_or_insert_with(Default::default)
+        .0
+        .insert(header::CONTENT_TYPE, HeaderValue::from_str(content_type)?);
+    }
+    Ok(Some(body.with_progress(progress)))
+  }
+
+  #[cfg(feature = "http-multipart")]
+  fn is_multipart(&self) -> bool {
+    self
+      .headers
+      .as_ref()
+      .and_then(|h| h.0.get(header::CONTENT_TYPE))
+      .map_or(false, |v| v.as_bytes().starts_with(b"multipart/form-data"))
+  }
 }
 
 /// The HTTP response.
@@ -587,49 +672,101 @@ impl Response {
     Ok(RawResponse { status, data })
   }
 
-  #[cfg(not(feature = "reqwest-client"))]
-  #[allow(dead_code)]
-  pub(crate) fn reader(self) -> attohttpc::ResponseReader {
-    let (_, _, reader) = self.1.split();
-    reader
-  }
-
-  // Convert the response into a Stream of [`bytes::Bytes`] from the body.
-  //
-  // # Examples
-  //
-  // ```no_run
-  // use futures_util::StreamExt;
-  //
-  // # async fn run() -> Result<(), Box<dyn std::error::Error>> {
-  // let client = tauri::api::http::ClientBuilder::new().build()?;
-  // let mut stream = client.send(tauri::api::http::HttpRequestBuilder::new("GET", "http://httpbin.org/ip")?)
-  //   .await?
-  //   .bytes_stream();
-  //
-  // while let Some(item) = stream.next().await {
-  //     println!("Chunk: {:?}", item?);
-  // }
-  // # Ok(())
-  // # }
-  // ```
-  #[cfg(feature = "reqwest-client")]
-  #[allow(dead_code)]
-  pub(crate) fn bytes_stream(
+  /// Reads the response body in chunks as they are received, so it is never buffered in memory.
+  ///
+  /// The returned [`ResponseData::data`] is [`Value::Null`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::api::http::{ClientBuilder, HttpRequestBuilder};
+  /// async fn run_request() -> tauri::api::Result<()> {
+  ///   let client = ClientBuilder::new().build()?;
+  ///   let mut len = 0;
+  ///   client
+  ///     .send(HttpRequestBuilder::new("GET", "https://tauri.app")?)
+  ///     .await?
+  ///     .read_chunks(|chunk| {
+  ///       len += chunk.len();
+  ///       Ok(())
+  ///     })
+  ///     .await?;
+  ///   println!("received {} bytes", len);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub async fn read_chunks<F: FnMut(&[u8]) -> crate::api::Result<()>>(
     self,
-  ) -> impl futures_util::Stream<Item = crate::api::Result<bytes::Bytes>> {
-    use futures_util::StreamExt;
-    self.1.bytes_stream().map(|res| res.map_err(Into::into))
+    mut on_chunk: F,
+  ) -> crate::api::Result<ResponseData> {
+    let response_data = self.metadata()?;
+
+    #[cfg(feature = "reqwest-client")]
+    {
+      let mut response = self.1;
+      while let Some(chunk) = response.chunk().await? {
+        on_chunk(&chunk)?;
+      }
+    }
+
+    #[cfg(not(feature = "reqwest-client"))]
+    {
+      use std::io::Read;
+      let (_, _, mut reader) = self.1.split();
+      let mut buf = vec![0; 64 * 1024];
+      loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+          break;
+        }
+        on_chunk(&buf[..read])?;
+      }
+    }
+
+    Ok(response_data)
   }
 
-  /// Reads the response.
+  /// Writes the response body to a file as it is received, creating or truncating it.
   ///
-  /// Note that the body is serialized to a [`Value`].
-  pub async fn read(self) -> crate::api::Result<ResponseData> {
+  /// The returned [`ResponseData::data`] is [`Value::Null`].
+  pub async fn write_to_file<P: AsRef<Path>>(self, path: P) -> crate::api::Result<ResponseData> {
+    let response_data = self.metadata()?;
+
+    #[cfg(feature = "reqwest-client")]
+    {
+      use tokio::io::AsyncWriteExt;
+      let mut file = tokio::io::BufWriter::new(tokio::fs::File::create(path).await?);
+      let mut response = self.1;
+      while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+      }
+      file.flush().await?;
+    }
+
+    // attohttpc reads the body with blocking calls, so the copy runs on the blocking executor
+    #[cfg(not(feature = "reqwest-client"))]
+    {
+      let path = path.as_ref().to_path_buf();
+      let (_, _, mut reader) = self.1.split();
+      crate::async_runtime::spawn_blocking(move || {
+        use std::io::Write;
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        std::io::copy(&mut reader, &mut file)?;
+        file.flush()
+      })
+      .await
+      .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))??;
+    }
+
+    Ok(response_data)
+  }
+
+  /// The response URL, status and headers, without the data.
+  fn metadata(&self) -> crate::api::Result<ResponseData> {
     #[cfg(feature = "reqwest-client")]
     let url = self.1.url().clone();
     #[cfg(not(feature = "reqwest-client"))]
-    let url = self.2;
+    let url = self.2.clone();
 
     let mut headers = HashMap::new();
     let mut raw_headers = HashMap::new();
@@ -649,7 +786,21 @@ impl Response {
           .collect::<crate::api::Result<Vec<String>>>()?,
       );
     }
-    let status = self.1.status().as_u16();
+
+    Ok(ResponseData {
+      url,
+      status: self.1.status().as_u16(),
+      headers,
+      raw_headers,
+      data: Value::Null,
+    })
+  }
+
+  /// Reads the response.
+  ///
+  /// Note that the body is serialized to a [`Value`].
+  pub async fn read(self) -> crate::api::Result<ResponseData> {
+    let mut response_data = self.metadata()?;
 
     #[cfg(feature = "reqwest-client")]
     let data = match self.0 {
@@ -665,13 +816,8 @@ impl Response {
       ResponseType::Binary => serde_json::to_value(&self.1.bytes()?)?,
     };
 
-    Ok(ResponseData {
-      url,
-      status,
-      headers,
-      raw_headers,
-      data,
-    })
+    response_data.data = data;
+    Ok(response_data)
   }
 }
 
@@ -704,15 +850,80 @@ pub struct ResponseData {
 
 #[cfg(test)]
 mod test {
-  use super::ClientBuilder;
+  use super::{ClientBuilder, HttpRequestBuilder};
   use quickcheck::{Arbitrary, Gen};
+  use serde_json::Value;
 
   impl Arbitrary for ClientBuilder {
     fn arbitrary(g: &mut Gen) -> Self {
       Self {
         max_redirections: Option::arbitrary(g),
         connect_timeout: Option::arbitrary(g),
+        cookie_store: None,
       }
     }
   }
+
+  /// Serves a single response with the given body on a local port, returning its URL.
+  fn serve_once(body: &'static [u8]) -> String {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      // the request head is read before answering
+      let mut request = Vec::new();
+      let mut buf = [0; 1024];
+      while !request.ends_with(b"\r\n\r\n") {
+        let read = stream.read(&mut buf).unwrap();
+        if read == 0 {
+          break;
+        }
+        request.extend_from_slice(&buf[..read]);
+      }
+      write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+      )
+      .unwrap();
+      stream.write_all(body).unwrap();
+    });
+    url
+  }
+
+  #[test]
+  fn streamed_response() {
+    crate::async_runtime::block_on(async {
+      let client = ClientBuilder::new().build().unwrap();
+
+      let mut body = Vec::new();
+      let response = client
+        .send(HttpRequestBuilder::new("GET", serve_once(b"streamed body")).unwrap())
+        .await
+        .unwrap()
+        .read_chunks(|chunk| {
+          body.extend_from_slice(chunk);
+          Ok(())
+        })
+        .await
+        .unwrap();
+      assert_eq!(response.status, 200);
+      assert_eq!(response.data, Value::Null);
+      assert_eq!(body, b"streamed body");
+
+      let path = std::env::temp_dir().join(format!("tauri-http-response-{}", std::process::id()));
+      let response = client
+        .send(HttpRequestBuilder::new("GET", serve_once(b"saved body")).unwrap())
+        .await
+        .unwrap()
+        .write_to_file(&path)
+        .await
+        .unwrap();
+      assert_eq!(response.status, 200);
+      assert_eq!(std::fs::read(&path).unwrap(), b"saved body");
+      std::fs::remove_file(path).unwrap();
+    });
+  }
 }
@@ -0,0 +1,345 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Request bodies written in chunks, so files are streamed instead of loaded in memory.
+
+use std::{fmt::Write as _, path::PathBuf};
+
+use super::{FilePart, FormBody, FormPart, Progress, ProgressHandler};
+
+/// The size of the chunks read from files.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+enum Segment {
+  Bytes(Vec<u8>),
+  /// A file path and the length read from it.
+  File(PathBuf, u64),
+}
+
+/// A request body with a known length, made of in-memory bytes and files.
+#[derive(Debug)]
+pub(crate) struct StreamBody {
+  segments: Vec<Segment>,
+  len: u64,
+  content_type: Option<String>,
+  progress: Option<ProgressHandler>,
+}
+
+impl StreamBody {
+  fn new(content_type: Option<String>) -> Self {
+    Self {
+      segments: Vec::new(),
+      len: 0,
+      content_type,
+      progress: None,
+    }
+  }
+
+  pub fn bytes(bytes: Vec<u8>, content_type: Option<&str>) -> Self {
+    let mut body = Self::new(content_type.map(Into::into));
+    body.push_bytes(bytes);
+    body
+  }
+
+  /// Encodes the form as `multipart/form-data`.
+  /// The file paths are only opened when the body is written.
+  pub fn multipart(form: FormBody) -> crate::api::Result<Self> {
+    let boundary = format!(
+      "{:016x}{:016x}",
+      rand::random::<u64>(),
+      rand::random::<u64>()
+    );
+    let mut body = Self::new(Some(format!("multipart/form-data; boundary={}", boundary)));
+
+    for (name, part) in form.0 {
+      let mut headers = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+        boundary,
+        escape(&name)
+      );
+      match part {
+        FormPart::Text(value) => {
+          headers.push_str("\r\n\r\n");
+          body.push_bytes(headers.into_bytes());
+          body.push_bytes(value.into_bytes());
+        }
+        FormPart::File {
+          file,
+          mime,
+          file_name,
+        } => {
+          let file_name = file_name.or_else(|| match &file {
+            FilePart::Path(path) => path
+              .file_name()
+              .map(|name| name.to_string_lossy().into_owned()),
+            FilePart::Contents(_) => None,
+          });
+          if let Some(file_name) = file_name {
+            let _ = write!(headers, "; filename=\"{}\"", escape(&file_name));
+          }
+          let _ = write!(
+            headers,
+            "\r\nContent-Type: {}\r\n\r\n",
+            escape(mime.as_deref().unwrap_or("application/octet-stream"))
+          );
+          body.push_bytes(headers.into_bytes());
+          match file {
+            FilePart::Path(path) => {
+              let len = std::fs::metadata(&path)?.len();
+              body.len += len;
+              body.segments.push(Segment::File(path, len));
+            }
+            FilePart::Contents(bytes) => body.push_bytes(bytes),
+          }
+        }
+      }
+      body.push_bytes(b"\r\n".to_vec());
+    }
+    body.push_bytes(format!("--{}--\r\n", boundary).into_bytes());
+
+    Ok(body)
+  }
+
+  fn push_bytes(&mut self, bytes: Vec<u8>) {
+    self.len += bytes.len() as u64;
+    self.segments.push(Segment::Bytes(bytes));
+  }
+
+  pub fn with_progress(mut self, progress: Option<ProgressHandler>) -> Self {
+    self.progress = progress;
+    self
+  }
+
+  pub fn len(&self) -> u64 {
+    self.len
+  }
+
+  pub fn content_type(&self) -> Option<&str> {
+    self.content_type.as_deref()
+  }
+
+  fn tracker(&self) -> Tracker {
+    Tracker {
+      transferred: 0,
+      total: self.len,
+      next_report: 0,
+      handler: self.progress.clone(),
+    }
+  }
+
+  /// Streams the body as [`bytes::Bytes`] chunks.
+  #[cfg(feature = "reqwest-client")]
+  pub fn into_stream(
+    self,
+  ) -> impl futures_util::Stream<Item = std::io::Result<bytes::Bytes>> + Send + Sync + 'static {
+    use tokio::io::AsyncReadExt;
+
+    enum Source {
+      Bytes(bytes::Bytes),
+      File(tokio::io::Take<tokio::fs::File>, u64),
+    }
+
+    let tracker = self.tracker();
+    let state = (self.segments.into_iter(), None, tracker);
+    futures_util::stream::try_unfold(
+      state,
+      |(mut segments, mut source, mut tracker)| async move {
+        loop {
+          let chunk = match &mut source {
+            None => match segments.next() {
+              Some(Segment::Bytes(bytes)) => {
+                source = Some(Source::Bytes(bytes.into()));
+                continue;
+              }
+              Some(Segment::File(path, len)) => {
+                let file = tokio::fs::File::open(path).await?;
+                source = Some(Source::File(file.take(len), len));
+                continue;
+              }
+              None => return Ok(None),
+            },
+            Some(Source::Bytes(bytes)) if !bytes.is_empty() => {
+              Some(bytes.split_to(CHUNK_SIZE.min(bytes.len())))
+            }
+            Some(Source::File(file, remaining)) if *remaining > 0 => {
+              let mut buf = vec![0; CHUNK_SIZE.min(*remaining as usize)];
+              let read = file.read(&mut buf).await?;
+              if read == 0 {
+                return Err(truncated());
+              }
+              buf.truncate(read);
+              *remaining -= read as u64;
+              Some(bytes::Bytes::from(buf))
+            }
+            Some(_) => None,
+          };
+
+          match chunk {
+            Some(chunk) => {
+              tracker.advance(chunk.len());
+              return Ok(Some((chunk, (segments, source, tracker))));
+            }
+            None => source = None,
+          }
+        }
+      },
+    )
+  }
+
+  /// Writes the body, reading the files in chunks.
+  #[cfg(not(feature = "reqwest-client"))]
+  fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+    use std::io::Read;
+
+    let mut tracker = self.tracker();
+    let mut buf = vec![0; CHUNK_SIZE];
+    for segment in &self.segments {
+      match segment {
+        Segment::Bytes(bytes) => {
+          for chunk in bytes.chunks(CHUNK_SIZE) {
+            writer.write_all(chunk)?;
+            tracker.advance(chunk.len());
+          }
+        }
+        Segment::File(path, len) => {
+          let mut file = std::fs::File::open(path)?.take(*len);
+          let mut remaining = *len;
+          while remaining > 0 {
+            let read = file.read(&mut buf)?;
+            if read == 0 {
+              return Err(truncated());
+            }
+            writer.write_all(&buf[..read])?;
+            remaining -= read as u64;
+            tracker.advance(read);
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(not(feature = "reqwest-client"))]
+impl attohttpc::body::Body for StreamBody {
+  fn kind(&mut self) -> std::io::Result<attohttpc::body::BodyKind> {
+    Ok(attohttpc::body::BodyKind::KnownLength(self.len()))
+  }
+
+  fn write<W: std::io::Write>(&mut self, writer: W) -> std::io::Result<()> {
+    self.write_to(writer)
+  }
+}
+
+fn truncated() -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::UnexpectedEof,
+    "the file was truncated while it was being sent",
+  )
+}
+
+/// Percent-encodes the characters that would end a quoted header value.
+fn escape(value: &str) -> String {
+  value
+    .replace('"', "%22")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+/// Reports the progress at most once per percent of the total.
+struct Tracker {
+  transferred: u64,
+  total: u64,
+  next_report: u64,
+  handler: Option<ProgressHandler>,
+}
+
+impl Tracker {
+  fn advance(&mut self, len: usize) {
+    self.transferred += len as u64;
+    if let Some(handler) = &self.handler {
+      if self.transferred >= self.next_report || self.transferred == self.total {
+        self.next_report = self.transferred + (self.total / 100).max(1);
+        handler.call(Progress {
+          transferred: self.transferred,
+          total: Some(self.total),
+        });
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+  };
+
+  use super::StreamBody;
+  use crate::api::http::{FilePart, FormBody, FormPart, ProgressHandler};
+
+  #[test]
+  fn multipart() {
+    let path = std::env::temp_dir().join(format!("tauri-multipart-{}.txt", std::process::id()));
+    std::fs::write(&path, "file contents").unwrap();
+
+    let mut form = HashMap::new();
+    form.insert(
+      "upload".to_string(),
+      FormPart::File {
+        file: FilePart::Path(path.clone()),
+        mime: Some("text/plain".into()),
+        file_name: None,
+      },
+    );
+    let body = StreamBody::multipart(FormBody::new(form)).unwrap();
+    let boundary = body
+      .content_type()
+      .unwrap()
+      .strip_prefix("multipart/form-data; boundary=")
+      .unwrap()
+      .to_string();
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let reports_ = reports.clone();
+    let body = body.with_progress(Some(ProgressHandler::new(move |progress| {
+      reports_.lock().unwrap().push(progress.transferred)
+    })));
+
+    #[cfg(not(feature = "reqwest-client"))]
+    let written = {
+      let mut written = Vec::new();
+      body.write_to(&mut written).unwrap();
+      written
+    };
+    #[cfg(feature = "reqwest-client")]
+    let written = {
+      use futures_util::TryStreamExt;
+      let len = body.len();
+      let chunks: Vec<bytes::Bytes> =
+        crate::async_runtime::block_on(body.into_stream().try_collect()).unwrap();
+      let written = chunks.concat();
+      assert_eq!(written.len() as u64, len);
+      written
+    };
+
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    assert_eq!(
+      String::from_utf8(written.clone()).unwrap(),
+      format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"{f}\"\r\nContent-Type: text/plain\r\n\r\nfile contents\r\n--{b}--\r\n",
+        b = boundary,
+        f = file_name
+      )
+    );
+    assert_eq!(
+      reports.lock().unwrap().last(),
+      Some(&(written.len() as u64))
+    );
+
+    std::fs::remove_file(path).unwrap();
+  }
+}
@@ -0,0 +1,454 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A cookie store following [RFC 6265](https://www.rfc-editor.org/rfc/rfc6265), optionally persisted to a file.
+
+use std::{
+  path::{Path, PathBuf},
+  sync::{mpsc, Arc, Mutex},
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::header::HeaderMap;
+
+/// The delay before the cookies received on responses are written to the file,
+/// so the cookies of consecutive responses are saved at once.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// A cookie set by a server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Cookie {
+  /// The cookie name.
+  pub name: String,
+  /// The cookie value.
+  pub value: String,
+  /// The domain the cookie is sent to.
+  pub domain: String,
+  /// Whether the cookie is only sent to `domain` and not to its subdomains.
+  pub host_only: bool,
+  /// The path prefix of the URLs the cookie is sent to.
+  pub path: String,
+  /// Whether the cookie is only sent over HTTPS.
+  pub secure: bool,
+  /// Whether the server asked to hide the cookie from scripts.
+  pub http_only: bool,
+  /// The expiration time as seconds since the UNIX epoch. Session cookies do not expire.
+  pub expires: Option<u64>,
+}
+
+impl Cookie {
+  /// Parses a `Set-Cookie` header received from `url`.
+  fn parse(header: &str, url: &Url, now: u64) -> Option<Self> {
+    let mut attributes = header.split(';');
+    let (name, value) = attributes.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+      return None;
+    }
+    let host = url.host_str()?.to_lowercase();
+
+    let mut cookie = Self {
+      name: name.into(),
+      value: value.trim().into(),
+      domain: host.clone(),
+      host_only: true,
+      path: default_path(url),
+      secure: false,
+      http_only: false,
+      expires: None,
+    };
+    let mut max_age = None;
+    for attribute in attributes {
+      let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+      let value = value.trim();
+      match key.trim().to_lowercase().as_str() {
+        "domain" if !value.is_empty() => {
+          let domain = value.trim_start_matches('.').to_lowercase();
+          // a server can't set cookies for another domain
+          if !domain_match(&host, &domain) {
+            return None;
+          }
+          // nor for all the domains of a public suffix, such as `com` or `github.io`
+          if psl::suffix_str(&domain) == Some(domain.as_str()) {
+            if domain != host {
+              return None;
+            }
+          } else {
+            cookie.domain = domain;
+            cookie.host_only = false;
+          }
+        }
+        "path" if value.starts_with('/') => cookie.path = value.into(),
+        "secure" => cookie.secure = true,
+        "httponly" => cookie.http_only = true,
+        "max-age" => {
+          if let Ok(seconds) = value.parse::<i64>() {
+            max_age.replace(if seconds <= 0 {
+              0
+            } else {
+              now.saturating_add(seconds as u64)
+            });
+          }
+        }
+        "expires" if cookie.expires.is_none() => cookie.expires = parse_date(value),
+        _ => {}
+      }
+    }
+    // Max-Age has precedence over Expires
+    if max_age.is_some() {
+      cookie.expires = max_age;
+    }
+    Some(cookie)
+  }
+
+  fn is_expired(&self, now: u64) -> bool {
+    self.expires.map_or(false, |expires| expires <= now)
+  }
+
+  fn matches(&self, url: &Url) -> bool {
+    let host = match url.host_str() {
+      Some(host) => host.to_lowercase(),
+      None => return false,
+    };
+    let domain_matches = if self.host_only {
+      host == self.domain
+    } else {
+      domain_match(&host, &self.domain)
+    };
+    domain_matches
+      && path_match(url.path(), &self.path)
+      && (!self.secure || url.scheme() == "https")
+  }
+}
+
+/// The store of the cookies received by the HTTP clients, sent back on the following requests.
+///
+/// Enable it on a client with [`super::ClientBuilder::cookie_store`].
+#[derive(Debug, Default)]
+pub struct CookieStore {
+  cookies: Arc<Mutex<Vec<Cookie>>>,
+  persistence: Option<Persistence>,
+}
+
+/// The file of a persisted [`CookieStore`].
+#[derive(Debug)]
+struct Persistence {
+  path: PathBuf,
+  /// Wakes up the thread saving the cookies received on responses.
+  save_tx: Mutex<mpsc::Sender<()>>,
+}
+
+impl CookieStore {
+  /// Creates an in-memory cookie store.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a cookie store persisted to the given JSON file, loading the cookies it contains.
+  ///
+  /// Session cookies are persisted too, so sessions survive application restarts.
+  /// The cookies received on responses are saved on a background thread, at most once per second,
+  /// see [`Self::flush`] to save them immediately.
+  pub fn load<P: AsRef<Path>>(path: P) -> crate::api::Result<Self> {
+    let path = path.as_ref();
+    let now = now();
+    let cookies = match std::fs::read(path) {
+      Ok(contents) => serde_json::from_slice::<Vec<Cookie>>(&contents)?
+        .into_iter()
+        .filter(|c| !c.is_expired(now))
+        .collect(),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+      Err(e) => return Err(e.into()),
+    };
+    let cookies = Arc::new(Mutex::new(cookies));
+
+    let (save_tx, save_rx) = mpsc::channel::<()>();
+    let cookies_ = cookies.clone();
+    let path_ = path.to_path_buf();
+    std::thread::spawn(move || {
+      while save_rx.recv().is_ok() {
+        std::thread::sleep(SAVE_DELAY);
+        while save_rx.try_recv().is_ok() {}
+        let cookies = cookies_.lock().unwrap().clone();
+        let _ = write(&path_, &cookies);
+      }
+    });
+
+    Ok(Self {
+      cookies,
+      persistence: Some(Persistence {
+        path: path.to_path_buf(),
+        save_tx: Mutex::new(save_tx),
+      }),
+    })
+  }
+
+  /// Writes the cookies to the file of the store, without waiting for the pending save.
+  pub fn flush(&self) -> crate::api::Result<()> {
+    let cookies = self.cookies.lock().unwrap();
+    self.save(&cookies)
+  }
+
+  /// The cookies sent on requests to the given URL.
+  pub fn cookies(&self, url: &Url) -> Vec<Cookie> {
+    let now = now();
+    let mut cookies = self
+      .cookies
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|c| !c.is_expired(now) && c.matches(url))
+      .cloned()
+      .collect::<Vec<_>>();
+    // cookies with longer paths are listed first
+    cookies.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+    cookies
+  }
+
+  /// Removes the cookies sent on requests to the given URL.
+  pub fn clear(&self, url: &Url) -> crate::api::Result<()> {
+    let mut cookies = self.cookies.lock().unwrap();
+    cookies.retain(|c| !c.matches(url));
+    self.save(&cookies)
+  }
+
+  /// Removes all cookies.
+  pub fn clear_all(&self) -> crate::api::Result<()> {
+    let mut cookies = self.cookies.lock().unwrap();
+    cookies.clear();
+    self.save(&cookies)
+  }
+
+  /// The `Cookie` header value of a request to the given URL.
+  pub(crate) fn header(&self, url: &Url) -> Option<String> {
+    let cookies = self.cookies(url);
+    if cookies.is_empty() {
+      None
+    } else {
+      Some(
+        cookies
+          .iter()
+          .map(|c| format!("{}={}", c.name, c.value))
+          .collect::<Vec<_>>()
+          .join("; "),
+      )
+    }
+  }
+
+  /// Stores the cookies of the `Set-Cookie` response headers.
+  pub(crate) fn store_response_cookies(&self, url: &Url, headers: &HeaderMap) {
+    let now = now();
+    let received = headers
+      .get_all("set-cookie")
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .filter_map(|value| Cookie::parse(value, url, now))
+      .collect::<Vec<_>>();
+    if received.is_empty() {
+      return;
+    }
+
+    let mut cookies = self.cookies.lock().unwrap();
+    for cookie in received {
+      cookies
+        .retain(|c| !(c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path));
+      // an expired cookie deletes the stored one
+      if !cookie.is_expired(now) {
+        cookies.push(cookie);
+      }
+    }
+    cookies.retain(|c| !c.is_expired(now));
+    if let Some(persistence) = &self.persistence {
+      let _ = persistence.save_tx.lock().unwrap().send(());
+    }
+  }
+
+  fn save(&self, cookies: &[Cookie]) -> crate::api::Result<()> {
+    match &self.persistence {
+      Some(persistence) => write(&persistence.path, cookies),
+      None => Ok(()),
+    }
+  }
+}
+
+fn write(path: &Path, cookies: &[Cookie]) -> crate::api::Result<()> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::fs::write(path, serde_json::to_vec(cookies)?)?;
+  Ok(())
+}
+
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+fn domain_match(host: &str, domain: &str) -> bool {
+  host == domain
+    || (host.ends_with(domain)
+      && host[..host.len() - domain.len()].ends_with('.')
+      && host.parse::<std::net::IpAddr>().is_err())
+}
+
+fn path_match(path: &str, cookie_path: &str) -> bool {
+  path == cookie_path
+    || (path.starts_with(cookie_path)
+      && (cookie_path.ends_with('/') || path[cookie_path.len()..].starts_with('/')))
+}
+
+fn default_path(url: &Url) -> String {
+  let path = url.path();
+  match path.rfind('/') {
+    Some(0) | None => "/".into(),
+    Some(i) => path[..i].into(),
+  }
+}
+
+/// Parses a cookie date, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`, as seconds since the UNIX epoch.
+fn parse_date(value: &str) -> Option<u64> {
+  const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+  ];
+
+  let (mut time, mut day, mut month, mut year) = (None, None, None, None);
+  for token in value
+    .split(|c: char| !c.is_ascii_alphanumeric() && c != ':')
+    .filter(|t| !t.is_empty())
+  {
+    if time.is_none() && token.contains(':') {
+      let mut parts = token.split(':').map(|p| p.parse::<u64>().ok());
+      if let (Some(Some(h)), Some(Some(m)), Some(Some(s))) =
+        (parts.next(), parts.next(), parts.next())
+      {
+        time = Some(h * 3600 + m * 60 + s);
+      }
+    } else if let Ok(number) = token.parse::<u64>() {
+      if day.is_none() && token.len() <= 2 {
+        day = Some(number);
+      } else if year.is_none() {
+        year = Some(match number {
+          0..=69 => number + 2000,
+          70..=99 => number + 1900,
+          _ => number,
+        });
+      }
+    } else if month.is_none() {
+      let token = token.to_lowercase();
+      month = MONTHS
+        .iter()
+        .position(|m| token.starts_with(m))
+        .map(|m| m as u64 + 1);
+    }
+  }
+
+  let (time, day, month, year) = (time?, day?, month?, year?);
+  if !(1..=31).contains(&day) || year < 1970 {
+    return None;
+  }
+  // days since the epoch of the proleptic Gregorian date, see http://howardhinnant.github.io/date_algorithms.html
+  let (y, m) = if month <= 2 {
+    (year - 1, month + 9)
+  } else {
+    (year, month - 3)
+  };
+  let era = y / 400;
+  let year_of_era = y - era * 400;
+  let day_of_year = (153 * m + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  let days = era * 146097 + day_of_era - 719468;
+  Some(days * 86400 + time)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_date, Cookie, CookieStore};
+  use crate::api::http::header::{HeaderMap, HeaderValue};
+
+  #[test]
+  fn dates() {
+    assert_eq!(
+      parse_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+      Some(1445412480)
+    );
+    assert_eq!(parse_date("Thursday, 01-Jan-70 00:00:01 GMT"), Some(1));
+    assert_eq!(parse_date("not a date"), None);
+  }
+
+  #[test]
+  fn parse() {
+    let url = "https://api.tauri.app/v1/login".parse().unwrap();
+    let cookie = Cookie::parse(
+      "session=abc; Domain=.tauri.app; Secure; HttpOnly; Max-Age=60",
+      &url,
+      100,
+    )
+    .unwrap();
+    assert_eq!(cookie.domain, "tauri.app");
+    assert!(!cookie.host_only);
+    assert_eq!(cookie.path, "/v1");
+    assert!(cookie.secure && cookie.http_only);
+    assert_eq!(cookie.expires, Some(160));
+
+    // cookies for other domains are rejected
+    assert!(Cookie::parse("session=abc; Domain=github.com", &url, 0).is_none());
+    // and so are cookies for public suffixes
+    assert!(Cookie::parse("session=abc; Domain=.app", &url, 0).is_none());
+    let url = "https://tauri.github.io/".parse().unwrap();
+    assert!(Cookie::parse("session=abc; Domain=github.io", &url, 0).is_none());
+    let url = "https://localhost/".parse().unwrap();
+    let cookie = Cookie::parse("session=abc; Domain=localhost", &url, 0).unwrap();
+    assert!(cookie.host_only);
+  }
+
+  #[test]
+  fn store() {
+    let path = std::env::temp_dir().join(format!("tauri-cookies-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = CookieStore::load(&path).unwrap();
+
+    let mut headers = HeaderMap::new();
+    headers.append(
+      "set-cookie",
+      HeaderValue::from_static("session=abc; Path=/"),
+    );
+    headers.append(
+      "set-cookie",
+      HeaderValue::from_static("theme=dark; Path=/settings"),
+    );
+    store.store_response_cookies(&"https://tauri.app/login".parse().unwrap(), &headers);
+
+    let url = "https://tauri.app/settings/theme".parse().unwrap();
+    assert_eq!(
+      store.header(&url).as_deref(),
+      Some("theme=dark; session=abc")
+    );
+    assert_eq!(
+      store
+        .header(&"https://tauri.app/".parse().unwrap())
+        .as_deref(),
+      Some("session=abc")
+    );
+    assert_eq!(store.header(&"https://github.com/".parse().unwrap()), None);
+
+    // the cookies are persisted
+    store.flush().unwrap();
+    let store = CookieStore::load(&path).unwrap();
+    assert_eq!(store.cookies(&url).len(), 2);
+
+    store
+      .clear(&"https://tauri.app/settings".parse().unwrap())
+      .unwrap();
+    assert_eq!(store.header(&url).as_deref(), Some("session=abc"));
+
+    std::fs::remove_file(path).unwrap();
+  }
+}
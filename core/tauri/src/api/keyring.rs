@@ -0,0 +1,107 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Secure storage of secrets such as passwords and tokens in the credential store of the operating system.
+//!
+//! The secrets are stored on the macOS Keychain, the Windows Credential Manager
+//! and the Secret Service on Linux, e.g. GNOME Keyring or KWallet.
+//!
+//! The service name of the entries is the bundle identifier, so the secrets of different apps do not clash.
+
+use crate::Config;
+
+/// The credential store of the app, keyed by account.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::keyring::Keyring;
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let keyring = Keyring::new(&app.config());
+///     keyring.set_password("user@example.com", "access-token")?;
+///     assert_eq!(
+///       keyring.get_password("user@example.com")?.as_deref(),
+///       Some("access-token")
+///     );
+///     Ok(())
+///   });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keyring {
+  service: String,
+}
+
+/// The service name of the entries, `<identifier>` or `<identifier>.<scope>`.
+fn service_name(identifier: &str, scope: Option<&str>) -> String {
+  match scope {
+    Some(scope) if !scope.is_empty() => format!("{}.{}", identifier, scope),
+    _ => identifier.into(),
+  }
+}
+
+impl Keyring {
+  /// The credential store scoped to the bundle identifier.
+  pub fn new(config: &Config) -> Self {
+    Self {
+      service: service_name(&config.tauri.bundle.identifier, None),
+    }
+  }
+
+  /// The credential store scoped to the bundle identifier and the given scope,
+  /// so accounts with the same name can be stored for multiple services, e.g. `github` and `gitlab`.
+  pub fn scoped(config: &Config, scope: &str) -> Self {
+    Self {
+      service: service_name(&config.tauri.bundle.identifier, Some(scope)),
+    }
+  }
+
+  /// The service name of the entries.
+  pub fn service(&self) -> &str {
+    &self.service
+  }
+
+  fn entry(&self, account: &str) -> keyring::Entry {
+    keyring::Entry::new(&self.service, account)
+  }
+
+  /// Stores the password of the account, replacing the previous one.
+  pub fn set_password(&self, account: &str, password: &str) -> crate::api::Result<()> {
+    self.entry(account).set_password(password)?;
+    Ok(())
+  }
+
+  /// Reads the password of the account, or `None` if it is not stored.
+  pub fn get_password(&self, account: &str) -> crate::api::Result<Option<String>> {
+    match self.entry(account).get_password() {
+      Ok(password) => Ok(Some(password)),
+      Err(keyring::Error::NoEntry) => Ok(None),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Deletes the password of the account, returning whether it was stored.
+  pub fn delete_password(&self, account: &str) -> crate::api::Result<bool> {
+    match self.entry(account).delete_password() {
+      Ok(()) => Ok(true),
+      Err(keyring::Error::NoEntry) => Ok(false),
+      Err(e) => Err(e.into()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::service_name;
+
+  #[test]
+  fn scopes_service_names() {
+    assert_eq!(service_name("com.tauri.dev", None), "com.tauri.dev");
+    assert_eq!(
+      service_name("com.tauri.dev", Some("github")),
+      "com.tauri.dev.github"
+    );
+    assert_eq!(service_name("com.tauri.dev", Some("")), "com.tauri.dev");
+  }
+}
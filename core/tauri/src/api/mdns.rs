@@ -0,0 +1,433 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Multicast DNS (Bonjour) service advertisement and discovery on the local network.
+//!
+//! The services are advertised and browsed by a daemon thread answering the queries of the other hosts,
+//! so the app does not depend on a system service such as Bonjour or Avahi.
+//! On Windows, the firewall may ask the user to allow the app on the network the first time it is used.
+
+use std::{
+  collections::HashMap,
+  net::IpAddr,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
+};
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+
+/// Returns the fully qualified service type, e.g. `_http._tcp.local.` for `_http._tcp`.
+fn service_type(service_type: &str) -> String {
+  let service_type = service_type.trim_end_matches('.');
+  let service_type = service_type.strip_suffix(".local").unwrap_or(service_type);
+  format!("{}.local.", service_type)
+}
+
+/// Returns the name of the computer on the local network, e.g. `My-Laptop.local.` for `My-Laptop.example.com`.
+fn host_name() -> Option<String> {
+  machine_host_name().and_then(|name| local_host_name(&name))
+}
+
+/// Returns the `.local.` host name of a computer name, replacing its domain.
+fn local_host_name(name: &str) -> Option<String> {
+  let label = name
+    .split('.')
+    .next()
+    .unwrap_or_default()
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+    .collect::<String>();
+  let label = label.trim_matches('-');
+  if label.is_empty() {
+    None
+  } else {
+    Some(format!("{}.local.", label))
+  }
+}
+
+#[cfg(unix)]
+fn machine_host_name() -> Option<String> {
+  let mut name = [0u8; 256];
+  if unsafe { libc::gethostname(name.as_mut_ptr() as *mut libc::c_char, name.len()) } != 0 {
+    return None;
+  }
+  let len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+  String::from_utf8(name[..len].to_vec()).ok()
+}
+
+#[cfg(windows)]
+fn machine_host_name() -> Option<String> {
+  use windows::{
+    core::PWSTR,
+    Win32::System::SystemInformation::{ComputerNamePhysicalDnsHostname, GetComputerNameExW},
+  };
+  let mut len = 0;
+  // the first call fails, returning the length of the name
+  unsafe { GetComputerNameExW(ComputerNamePhysicalDnsHostname, PWSTR::null(), &mut len) };
+  let mut name = vec![0u16; len as usize];
+  if !unsafe {
+    GetComputerNameExW(
+      ComputerNamePhysicalDnsHostname,
+      PWSTR(name.as_mut_ptr()),
+      &mut len,
+    )
+  }
+  .as_bool()
+  {
+    return None;
+  }
+  String::from_utf16(&name[..len as usize]).ok()
+}
+
+/// A service discovered on the local network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Service {
+  /// The instance name, e.g. `Living Room`.
+  pub instance_name: String,
+  /// The fully qualified service type, e.g. `_http._tcp.local.`.
+  pub service_type: String,
+  /// The host name of the service, e.g. `living-room.local.`.
+  pub host_name: String,
+  /// The port of the service.
+  pub port: u16,
+  /// The addresses of the host.
+  pub addresses: Vec<IpAddr>,
+  /// The TXT record properties.
+  pub properties: HashMap<String, String>,
+}
+
+impl From<&ServiceInfo> for Service {
+  fn from(info: &ServiceInfo) -> Self {
+    let instance_name = info
+      .get_fullname()
+      .strip_suffix(info.get_type())
+      .unwrap_or_else(|| info.get_fullname())
+      .trim_end_matches('.')
+      .to_string();
+    Self {
+      instance_name,
+      service_type: info.get_type().into(),
+      host_name: info.get_hostname().into(),
+      port: info.get_port(),
+      addresses: info
+        .get_addresses()
+        .iter()
+        .map(|address| IpAddr::from(*address))
+        .collect(),
+      properties: info
+        .get_properties()
+        .iter()
+        .map(|property| (property.key().into(), property.val_str().into()))
+        .collect(),
+    }
+  }
+}
+
+/// A change of the services found by [`Mdns::browse`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum BrowseEvent {
+  /// The service was found and its host resolved.
+  #[serde(rename_all = "camelCase")]
+  Resolved {
+    /// The service.
+    service: Service,
+  },
+  /// The service is no longer advertised.
+  #[serde(rename_all = "camelCase")]
+  Removed {
+    /// The fully qualified service type.
+    service_type: String,
+    /// The instance name.
+    instance_name: String,
+  },
+}
+
+/// A service to advertise on the local network, see [`Mdns::advertise`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Advertisement {
+  /// The service type, e.g. `_myapp._tcp`.
+  pub service_type: String,
+  /// The instance name, unique on the local network, e.g. the name of the app and of the user.
+  pub instance_name: String,
+  /// The port of the service.
+  pub port: u16,
+  /// The host name. Defaults to the name of the computer on the `.local` domain, e.g. `My-Laptop.local.`.
+  pub host_name: Option<String>,
+  /// The TXT record properties, e.g. the version of the protocol.
+  #[serde(default)]
+  pub properties: HashMap<String, String>,
+}
+
+impl Advertisement {
+  /// Creates the advertisement of a service.
+  pub fn new(service_type: impl Into<String>, instance_name: impl Into<String>, port: u16) -> Self {
+    Self {
+      service_type: service_type.into(),
+      instance_name: instance_name.into(),
+      port,
+      host_name: None,
+      properties: Default::default(),
+    }
+  }
+
+  /// Sets the host name.
+  #[must_use]
+  pub fn host_name(mut self, host_name: impl Into<String>) -> Self {
+    self.host_name.replace(host_name.into());
+    self
+  }
+
+  /// Adds a TXT record property.
+  #[must_use]
+  pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    self.properties.insert(key.into(), value.into());
+    self
+  }
+}
+
+/// The mDNS daemon, advertising and browsing services on a background thread until [`Mdns::shutdown`] is called.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::mdns::{Advertisement, BrowseEvent, Mdns};
+///
+/// let mdns = Mdns::new().unwrap();
+/// let registration = mdns
+///   .advertise(Advertisement::new("_myapp._tcp", "My App", 8080).property("version", "1"))
+///   .unwrap();
+/// let browser = mdns
+///   .browse("_myapp._tcp", |event| {
+///     if let BrowseEvent::Resolved { service } = event {
+///       println!("found {} on {:?}", service.instance_name, service.addresses);
+///     }
+///   })
+///   .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Mdns {
+  daemon: ServiceDaemon,
+  browse_groups: BrowseGroups,
+  next_browser_id: Arc<AtomicU64>,
+}
+
+type BrowseHandler = Box<dyn Fn(BrowseEvent) + Send>;
+
+/// The browsers of a service type, sharing the query of the daemon, which only supports one per type.
+#[derive(Default)]
+struct BrowseGroup {
+  handlers: HashMap<u64, BrowseHandler>,
+  /// The resolved services by full name, delivered to the browsers added after they were found.
+  services: HashMap<String, Service>,
+}
+
+type BrowseGroups = Arc<Mutex<HashMap<String, Arc<Mutex<BrowseGroup>>>>>;
+
+impl std::fmt::Debug for Mdns {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Mdns").finish()
+  }
+}
+
+impl Mdns {
+  /// Starts the mDNS daemon.
+  pub fn new() -> crate::api::Result<Self> {
+    Ok(Self {
+      daemon: ServiceDaemon::new()?,
+      browse_groups: Default::default(),
+      next_browser_id: Default::default(),
+    })
+  }
+
+  /// Advertises the service on the addresses of the computer, until the registration is dropped.
+  pub fn advertise(&self, advertisement: Advertisement) -> crate::api::Result<Registration> {
+    let host_name = match advertisement.host_name.or_else(host_name) {
+      Some(host_name) => host_name,
+      None => {
+        return Err(crate::api::Error::Mdns(mdns_sd::Error::Msg(
+          "failed to read the host name of the computer".into(),
+        )))
+      }
+    };
+    let info = ServiceInfo::new(
+      &service_type(&advertisement.service_type),
+      &advertisement.instance_name,
+      &host_name,
+      "",
+      advertisement.port,
+      advertisement.properties,
+    )?
+    .enable_addr_auto();
+    let fullname = info.get_fullname().to_string();
+    self.daemon.register(info)?;
+    Ok(Registration {
+      daemon: self.daemon.clone(),
+      fullname,
+    })
+  }
+
+  /// Stops the daemon, removing the advertised services from the network.
+  pub fn shutdown(&self) -> crate::api::Result<()> {
+    self.daemon.shutdown()?;
+    Ok(())
+  }
+
+  /// Browses the services of the given type, e.g. `_http._tcp`, calling the handler when one is found or removed,
+  /// until the browser is dropped.
+  ///
+  /// The browsers of the same type share a query, so a new browser is first called with the services already found.
+  /// The handler must not drop a browser.
+  pub fn browse<F: Fn(BrowseEvent) + Send + 'static>(
+    &self,
+    service_type: &str,
+    handler: F,
+  ) -> crate::api::Result<Browser> {
+    let service_type = self::service_type(service_type);
+    let id = self.next_browser_id.fetch_add(1, Ordering::Relaxed);
+    let mut groups = self.browse_groups.lock().unwrap();
+    if let Some(group) = groups.get(&service_type) {
+      let mut group = group.lock().unwrap();
+      for service in group.services.values() {
+        handler(BrowseEvent::Resolved {
+          service: service.clone(),
+        });
+      }
+      group.handlers.insert(id, Box::new(handler));
+    } else {
+      let receiver = self.daemon.browse(&service_type)?;
+      let group = Arc::new(Mutex::new(BrowseGroup::default()));
+      group.lock().unwrap().handlers.insert(id, Box::new(handler));
+      groups.insert(service_type.clone(), group.clone());
+      std::thread::spawn(move || {
+        while let Ok(event) = receiver.recv() {
+          let mut group = group.lock().unwrap();
+          let event = match event {
+            ServiceEvent::ServiceResolved(info) => {
+              let service = Service::from(&info);
+              group
+                .services
+                .insert(info.get_fullname().into(), service.clone());
+              BrowseEvent::Resolved { service }
+            }
+            ServiceEvent::ServiceRemoved(service_type, fullname) => {
+              group.services.remove(&fullname);
+              let instance_name = fullname
+                .strip_suffix(&service_type)
+                .unwrap_or(&fullname)
+                .trim_end_matches('.')
+                .to_string();
+              BrowseEvent::Removed {
+                service_type,
+                instance_name,
+              }
+            }
+            ServiceEvent::SearchStopped(_) => break,
+            _ => continue,
+          };
+          for handler in group.handlers.values() {
+            handler(event.clone());
+          }
+        }
+      });
+    }
+    Ok(Browser {
+      daemon: self.daemon.clone(),
+      browse_groups: self.browse_groups.clone(),
+      service_type,
+      id,
+    })
+  }
+}
+
+/// An advertised service, unregistered when dropped. See [`Mdns::advertise`].
+pub struct Registration {
+  daemon: ServiceDaemon,
+  fullname: String,
+}
+
+impl std::fmt::Debug for Registration {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Registration")
+      .field("fullname", &self.fullname)
+      .finish()
+  }
+}
+
+impl Drop for Registration {
+  fn drop(&mut self) {
+    let _ = self.daemon.unregister(&self.fullname);
+  }
+}
+
+/// Browses services until it is dropped, see [`Mdns::browse`].
+pub struct Browser {
+  daemon: ServiceDaemon,
+  browse_groups: BrowseGroups,
+  service_type: String,
+  id: u64,
+}
+
+impl std::fmt::Debug for Browser {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Browser")
+      .field("service_type", &self.service_type)
+      .finish()
+  }
+}
+
+impl Drop for Browser {
+  fn drop(&mut self) {
+    let mut groups = self.browse_groups.lock().unwrap();
+    let stopped = match groups.get(&self.service_type) {
+      Some(group) => {
+        let mut group = group.lock().unwrap();
+        group.handlers.remove(&self.id);
+        group.handlers.is_empty()
+      }
+      None => false,
+    };
+    // the query is only stopped once the last browser of the type is dropped
+    if stopped {
+      groups.remove(&self.service_type);
+      let _ = self.daemon.stop_browse(&self.service_type);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{local_host_name, service_type};
+
+  #[test]
+  fn qualifies_service_type() {
+    assert_eq!(service_type("_http._tcp"), "_http._tcp.local.");
+    assert_eq!(service_type("_http._tcp.local"), "_http._tcp.local.");
+    assert_eq!(service_type("_http._tcp.local."), "_http._tcp.local.");
+  }
+
+  #[test]
+  fn local_host_names() {
+    assert_eq!(
+      local_host_name("My-Laptop").as_deref(),
+      Some("My-Laptop.local.")
+    );
+    assert_eq!(
+      local_host_name("build-01.example.com").as_deref(),
+      Some("build-01.local.")
+    );
+    assert_eq!(
+      local_host_name("Jo's PC").as_deref(),
+      Some("Jo-s-PC.local.")
+    );
+    assert_eq!(local_host_name(""), None);
+  }
+}
@@ -8,17 +8,41 @@
 #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "dialog"))))]
 pub mod dialog;
 pub mod dir;
+#[cfg(feature = "download-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "download-api")))]
+pub mod download;
 pub mod file;
 #[cfg(feature = "http-api")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "http-api")))]
 pub mod http;
 pub mod ipc;
+#[cfg(all(desktop, feature = "keyring-api"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "keyring-api"))))]
+pub mod keyring;
+#[cfg(feature = "mdns-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "mdns-api")))]
+pub mod mdns;
+#[cfg(all(desktop, feature = "net-status-api"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "net-status-api"))))]
+pub mod net;
+#[cfg(feature = "oauth-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "oauth-api")))]
+pub mod oauth;
 pub mod path;
 pub mod process;
+#[cfg(all(desktop, feature = "serial-api"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "serial-api"))))]
+pub mod serial;
 #[cfg(feature = "shell-open-api")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "shell-open-api")))]
 pub mod shell;
+#[cfg(feature = "store-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "store-api")))]
+pub mod store;
 pub mod version;
+#[cfg(feature = "websocket-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "websocket-api")))]
+pub mod websocket;
 
 #[cfg(feature = "cli")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "cli")))]
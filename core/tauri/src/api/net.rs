@@ -0,0 +1,678 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The network status of the system, read from the native APIs.
+//!
+//! `navigator.onLine` only reports whether the webview has a network interface,
+//! so the status is also emitted to the webviews as the [`NETWORK_STATUS_EVENT`]
+//! and to the app as [`RunEvent::NetworkStatusChanged`](crate::RunEvent#variant.NetworkStatusChanged).
+
+use std::sync::mpsc::{channel, Sender};
+
+use serde::Serialize;
+
+/// The event emitted to the webviews when the network status changes, with the [`NetworkStatus`] as payload.
+pub const NETWORK_STATUS_EVENT: &str = "tauri://network-status";
+
+/// The type of the connection used to reach the internet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ConnectionType {
+  /// The device is offline.
+  None,
+  /// A Wi-Fi connection.
+  Wifi,
+  /// A wired Ethernet connection.
+  Ethernet,
+  /// A cellular connection.
+  Cellular,
+  /// Any other connection, such as a VPN or a Bluetooth tether.
+  Other,
+}
+
+/// The network status of the system.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NetworkStatus {
+  /// Whether the internet is reachable.
+  pub online: bool,
+  /// The type of the connection used to reach the internet.
+  pub connection_type: ConnectionType,
+  /// Whether the data usage of the connection is limited or charged, as on cellular connections and hotspots.
+  ///
+  /// Apps should defer large downloads and background syncs on metered connections.
+  pub metered: bool,
+}
+
+impl NetworkStatus {
+  fn offline() -> Self {
+    Self {
+      online: false,
+      connection_type: ConnectionType::None,
+      metered: false,
+    }
+  }
+}
+
+/// Returns the current network status.
+///
+/// ## Platform-specific
+///
+/// - **Windows:** The connection type is the type of the interface routing the internet traffic.
+/// - **macOS:** Requires macOS 10.14 or later.
+/// - **Linux:** The online and metered flags are read from GIO, which uses NetworkManager when it is running.
+/// Cellular connections are always metered.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::net::{status, ConnectionType};
+///
+/// let status = status();
+/// if status.online && !status.metered {
+///   println!("syncing over {:?}", status.connection_type);
+/// }
+/// ```
+pub fn status() -> NetworkStatus {
+  platform::status()
+}
+
+enum Message {
+  Changed,
+  Stop,
+}
+
+/// Watches the network status until it is dropped, see [`watch`].
+#[derive(Debug)]
+pub struct NetworkWatcher {
+  tx: Sender<Message>,
+  _notifier: Option<platform::Notifier>,
+}
+
+impl Drop for NetworkWatcher {
+  fn drop(&mut self) {
+    let _ = self.tx.send(Message::Stop);
+  }
+}
+
+/// Calls the handler when the network status changes.
+///
+/// The status is checked again when the system notifies a change of the network interfaces, addresses or routes,
+/// and the handler is only called when it differs.
+///
+/// The app already emits the changes as [`RunEvent::NetworkStatusChanged`](crate::RunEvent#variant.NetworkStatusChanged),
+/// this function is meant for code running without an app, such as sync engines.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::net::watch;
+///
+/// let watcher = watch(|status| {
+///   println!("online: {}, metered: {}", status.online, status.metered);
+/// });
+/// ```
+pub fn watch<F: Fn(NetworkStatus) + Send + 'static>(handler: F) -> NetworkWatcher {
+  let (tx, rx) = channel();
+  let tx_ = tx.clone();
+  let notifier = platform::notify_changes(move || tx_.send(Message::Changed).is_ok());
+
+  std::thread::spawn(move || {
+    let mut previous = status();
+    while let Ok(Message::Changed) = rx.recv() {
+      let current = status();
+      if current != previous {
+        handler(current.clone());
+        previous = current;
+      }
+    }
+  });
+
+  NetworkWatcher {
+    tx,
+    _notifier: notifier,
+  }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+use linux as platform;
+#[cfg(target_os = "macos")]
+use macos as platform;
+#[cfg(windows)]
+use win32 as platform;
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod linux {
+  use super::{ConnectionType, NetworkStatus};
+  use gtk::gio::{prelude::*, NetworkConnectivity, NetworkMonitor};
+  use std::{fs, io, path::Path};
+
+  const RTF_UP: u32 = 0x0001;
+  const RTF_REJECT: u32 = 0x0200;
+
+  pub fn status() -> NetworkStatus {
+    let interface = fs::read_to_string("/proc/net/route")
+      .ok()
+      .and_then(|table| default_interface(&table, false))
+      .or_else(|| {
+        fs::read_to_string("/proc/net/ipv6_route")
+          .ok()
+          .and_then(|table| default_interface(&table, true))
+      });
+    let interface = match interface {
+      Some(interface) => interface,
+      None => return NetworkStatus::offline(),
+    };
+
+    // the connectivity is limited behind captive portals
+    let monitor = NetworkMonitor::default();
+    if monitor.connectivity() != NetworkConnectivity::Full {
+      return NetworkStatus::offline();
+    }
+    let connection_type = connection_type(&interface);
+    NetworkStatus {
+      online: true,
+      connection_type,
+      metered: monitor.is_network_metered() || connection_type == ConnectionType::Cellular,
+    }
+  }
+
+  /// The interface of the default route with the lowest metric, read from `/proc/net/route` or `/proc/net/ipv6_route`.
+  pub(super) fn default_interface(table: &str, ipv6: bool) -> Option<String> {
+    table
+      .lines()
+      .filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (is_default, flags, metric, interface) = if ipv6 {
+          (
+            fields.first()?.chars().all(|c| c == '0') && *fields.get(1)? == "00",
+            u32::from_str_radix(fields.get(8)?, 16).ok()?,
+            u32::from_str_radix(fields.get(5)?, 16).ok()?,
+            *fields.get(9)?,
+          )
+        } else {
+          (
+            *fields.get(1)? == "00000000",
+            u32::from_str_radix(fields.get(3)?, 16).ok()?,
+            fields.get(6)?.parse().ok()?,
+            *fields.first()?,
+          )
+        };
+        if is_default && flags & RTF_UP != 0 && flags & RTF_REJECT == 0 && interface != "lo" {
+          Some((metric, interface))
+        } else {
+          None
+        }
+      })
+      .min_by_key(|(metric, _)| *metric)
+      .map(|(_, interface)| interface.to_string())
+  }
+
+  fn connection_type(interface: &str) -> ConnectionType {
+    let device = Path::new("/sys/class/net").join(interface);
+    if device.join("wireless").exists() || device.join("phy80211").exists() {
+      ConnectionType::Wifi
+    } else if interface.starts_with("ww") {
+      ConnectionType::Cellular
+    } else if fs::read_to_string(device.join("type")).ok().as_deref() == Some("1\n")
+      && device.join("device").exists()
+    {
+      // a physical interface with the ARPHRD_ETHER hardware type
+      ConnectionType::Ethernet
+    } else {
+      ConnectionType::Other
+    }
+  }
+
+  /// Stops reading the netlink socket when dropped.
+  #[derive(Debug)]
+  pub struct Notifier {
+    /// The write end of the pipe waking the reading thread up, closed to stop it.
+    stop: libc::c_int,
+  }
+
+  impl Drop for Notifier {
+    fn drop(&mut self) {
+      unsafe {
+        libc::close(self.stop);
+      }
+    }
+  }
+
+  /// Calls `on_change` on the changes of the links, addresses and routes, read from a netlink socket,
+  /// until it returns `false` or the notifier is dropped.
+  pub fn notify_changes<F: Fn() -> bool + Send + 'static>(on_change: F) -> Option<Notifier> {
+    let fd = unsafe {
+      libc::socket(
+        libc::AF_NETLINK,
+        libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+        libc::NETLINK_ROUTE,
+      )
+    };
+    if fd < 0 {
+      return None;
+    }
+    // SAFETY: `sockaddr_nl` is a plain C struct
+    let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    address.nl_groups = (libc::RTMGRP_LINK
+      | libc::RTMGRP_IPV4_IFADDR
+      | libc::RTMGRP_IPV6_IFADDR
+      | libc::RTMGRP_IPV4_ROUTE
+      | libc::RTMGRP_IPV6_ROUTE) as u32;
+    let bound = unsafe {
+      libc::bind(
+        fd,
+        &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+        std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+      )
+    };
+    let mut pipe = [0; 2];
+    if bound < 0 || unsafe { libc::pipe2(pipe.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+      unsafe { libc::close(fd) };
+      return None;
+    }
+    let [stop_reader, stop] = pipe;
+
+    std::thread::spawn(move || {
+      let mut fds = [
+        libc::pollfd {
+          fd,
+          events: libc::POLLIN,
+          revents: 0,
+        },
+        libc::pollfd {
+          fd: stop_reader,
+          events: libc::POLLIN,
+          revents: 0,
+        },
+      ];
+      let mut buffer = [0u8; 8192];
+      loop {
+        if unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) } < 0 {
+          if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+            continue;
+          }
+          break;
+        }
+        // the notifier was dropped, closing the write end of the pipe
+        if fds[1].revents != 0 {
+          break;
+        }
+        if fds[0].revents == 0 {
+          continue;
+        }
+        let read = unsafe {
+          libc::recv(
+            fd,
+            buffer.as_mut_ptr().cast(),
+            buffer.len(),
+            libc::MSG_DONTWAIT,
+          )
+        };
+        if read < 0 {
+          match io::Error::last_os_error().raw_os_error() {
+            Some(libc::EINTR) | Some(libc::EAGAIN) => continue,
+            // the socket buffer overflowed, so some changes were missed
+            Some(libc::ENOBUFS) => {}
+            _ => break,
+          }
+        }
+        if !on_change() {
+          break;
+        }
+      }
+      unsafe {
+        libc::close(fd);
+        libc::close(stop_reader);
+      }
+    });
+    Some(Notifier { stop })
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use super::{ConnectionType, NetworkStatus};
+  use block::{Block, ConcreteBlock, RcBlock};
+  use once_cell::sync::Lazy;
+  use std::{
+    ffi::c_void,
+    os::raw::{c_char, c_int},
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+  };
+
+  type Object = *mut c_void;
+
+  const NW_PATH_STATUS_SATISFIED: c_int = 1;
+  const NW_INTERFACE_TYPE_WIFI: c_int = 1;
+  const NW_INTERFACE_TYPE_CELLULAR: c_int = 2;
+  const NW_INTERFACE_TYPE_WIRED: c_int = 3;
+
+  #[link(name = "Network", kind = "framework")]
+  extern "C" {
+    fn nw_path_monitor_create() -> Object;
+    fn nw_path_monitor_set_queue(monitor: Object, queue: Object);
+    fn nw_path_monitor_set_update_handler(monitor: Object, handler: &Block<(Object,), ()>);
+    fn nw_path_monitor_start(monitor: Object);
+    fn nw_path_monitor_cancel(monitor: Object);
+    fn nw_path_get_status(path: Object) -> c_int;
+    fn nw_path_is_expensive(path: Object) -> bool;
+    fn nw_path_uses_interface_type(path: Object, interface_type: c_int) -> bool;
+    fn nw_release(object: Object);
+  }
+
+  extern "C" {
+    fn dispatch_queue_create(label: *const c_char, attr: Object) -> Object;
+    fn dispatch_release(object: Object);
+  }
+
+  /// A Network framework path monitor, calling the handler on a serial queue.
+  pub struct Notifier {
+    monitor: Object,
+    queue: Object,
+    _handler: RcBlock<(Object,), ()>,
+  }
+
+  // SAFETY: the Network framework objects are thread safe
+  unsafe impl Send for Notifier {}
+
+  impl std::fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.debug_struct("Notifier").finish()
+    }
+  }
+
+  impl Drop for Notifier {
+    fn drop(&mut self) {
+      unsafe {
+        nw_path_monitor_cancel(self.monitor);
+        nw_release(self.monitor);
+        dispatch_release(self.queue);
+      }
+    }
+  }
+
+  fn monitor<F: Fn(NetworkStatus) + 'static>(handler: F) -> Notifier {
+    let handler =
+      ConcreteBlock::new(move |path: Object| handler(unsafe { path_status(path) })).copy();
+    unsafe {
+      let monitor = nw_path_monitor_create();
+      let queue = dispatch_queue_create(
+        b"app.tauri.network-status\0".as_ptr().cast(),
+        std::ptr::null_mut(),
+      );
+      nw_path_monitor_set_queue(monitor, queue);
+      nw_path_monitor_set_update_handler(monitor, &handler);
+      nw_path_monitor_start(monitor);
+      Notifier {
+        monitor,
+        queue,
+        _handler: handler,
+      }
+    }
+  }
+
+  unsafe fn path_status(path: Object) -> NetworkStatus {
+    if nw_path_get_status(path) != NW_PATH_STATUS_SATISFIED {
+      return NetworkStatus::offline();
+    }
+    let connection_type = if nw_path_uses_interface_type(path, NW_INTERFACE_TYPE_WIFI) {
+      ConnectionType::Wifi
+    } else if nw_path_uses_interface_type(path, NW_INTERFACE_TYPE_CELLULAR) {
+      ConnectionType::Cellular
+    } else if nw_path_uses_interface_type(path, NW_INTERFACE_TYPE_WIRED) {
+      ConnectionType::Ethernet
+    } else {
+      ConnectionType::Other
+    };
+    NetworkStatus {
+      online: true,
+      connection_type,
+      metered: nw_path_is_expensive(path),
+    }
+  }
+
+  /// The latest status delivered by the monitor shared by the calls to [`status`].
+  type SharedStatus = Arc<(Mutex<Option<NetworkStatus>>, Condvar)>;
+
+  fn shared_status() -> &'static SharedStatus {
+    static STATUS: Lazy<SharedStatus> = Lazy::new(|| {
+      let status = SharedStatus::default();
+      let status_ = status.clone();
+      let notifier = monitor(move |current| {
+        let (status, updated) = &*status_;
+        status.lock().unwrap().replace(current);
+        updated.notify_all();
+      });
+      // the monitor runs for the lifetime of the process
+      std::mem::forget(notifier);
+      status
+    });
+    &STATUS
+  }
+
+  pub fn status() -> NetworkStatus {
+    let (status, updated) = &**shared_status();
+    // the monitor delivers the current path as soon as it starts
+    let (status, _) = updated
+      .wait_timeout_while(status.lock().unwrap(), Duration::from_secs(2), |status| {
+        status.is_none()
+      })
+      .unwrap();
+    status.clone().unwrap_or_else(NetworkStatus::offline)
+  }
+
+  pub fn notify_changes<F: Fn() -> bool + Send + 'static>(on_change: F) -> Option<Notifier> {
+    Some(monitor(move |_| {
+      on_change();
+    }))
+  }
+}
+
+#[cfg(windows)]
+mod win32 {
+  use super::{ConnectionType, NetworkStatus};
+  use std::{
+    cell::UnsafeCell,
+    sync::{Arc, Mutex},
+  };
+  use windows::{
+    core::{Interface, PCWSTR},
+    Win32::{
+      Foundation::{CloseHandle, ERROR_IO_PENDING, HANDLE},
+      NetworkManagement::IpHelper::{
+        CancelIPChangeNotify, GetBestInterface, GetIfEntry, NotifyAddrChange, MIB_IFROW,
+      },
+      Networking::NetworkListManager::{
+        INetworkCostManager, INetworkListManager, NetworkListManager, NLM_CONNECTION_COST_UNKNOWN,
+        NLM_CONNECTION_COST_UNRESTRICTED, NLM_CONNECTIVITY_IPV4_INTERNET,
+        NLM_CONNECTIVITY_IPV6_INTERNET,
+      },
+      System::{
+        Com::{CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED},
+        Threading::CreateEventW,
+        IO::{GetOverlappedResult, OVERLAPPED},
+      },
+    },
+  };
+
+  // the interface types of `ipifcons.h`
+  const IF_TYPE_ETHERNET_CSMACD: u32 = 6;
+  const IF_TYPE_IEEE80211: u32 = 71;
+  const IF_TYPE_WWANPP: u32 = 243;
+  const IF_TYPE_WWANPP2: u32 = 244;
+
+  pub fn status() -> NetworkStatus {
+    let interface = best_interface();
+    let (online, metered) = network_list_status().unwrap_or((interface.is_some(), false));
+    if !online {
+      return NetworkStatus::offline();
+    }
+    NetworkStatus {
+      online,
+      connection_type: interface
+        .map(connection_type)
+        .unwrap_or(ConnectionType::Other),
+      metered,
+    }
+  }
+
+  /// Whether the internet is reachable and whether the connection is metered, read from the Network List Manager.
+  fn network_list_status() -> windows::core::Result<(bool, bool)> {
+    // fails if the thread is a single-threaded apartment, which works as well
+    let initialized = unsafe { CoInitializeEx(std::ptr::null(), COINIT_MULTITHREADED) }.is_ok();
+    // the COM objects are released before uninitializing
+    let status = unsafe { query_network_list() };
+    if initialized {
+      unsafe { CoUninitialize() };
+    }
+    status
+  }
+
+  unsafe fn query_network_list() -> windows::core::Result<(bool, bool)> {
+    let manager: INetworkListManager = CoCreateInstance(&NetworkListManager, None, CLSCTX_ALL)?;
+    let connectivity = manager.GetConnectivity()?;
+    let online =
+      connectivity.0 & (NLM_CONNECTIVITY_IPV4_INTERNET.0 | NLM_CONNECTIVITY_IPV6_INTERNET.0) != 0;
+    let mut cost = 0;
+    manager
+      .cast::<INetworkCostManager>()?
+      .GetCost(&mut cost, std::ptr::null())?;
+    let metered = cost != NLM_CONNECTION_COST_UNKNOWN.0 as u32
+      && cost & NLM_CONNECTION_COST_UNRESTRICTED.0 as u32 == 0;
+    Ok((online, metered))
+  }
+
+  /// The index of the interface routing the internet traffic.
+  fn best_interface() -> Option<u32> {
+    // a public address, the route table is only looked up and no packet is sent
+    let destination = u32::from_ne_bytes([8, 8, 8, 8]);
+    let mut index = 0;
+    if unsafe { GetBestInterface(destination, &mut index) } == 0 {
+      Some(index)
+    } else {
+      None
+    }
+  }
+
+  fn connection_type(index: u32) -> ConnectionType {
+    let mut row = MIB_IFROW {
+      dwIndex: index,
+      ..Default::default()
+    };
+    if unsafe { GetIfEntry(&mut row) } != 0 {
+      return ConnectionType::Other;
+    }
+    match row.dwType {
+      IF_TYPE_IEEE80211 => ConnectionType::Wifi,
+      IF_TYPE_ETHERNET_CSMACD => ConnectionType::Ethernet,
+      IF_TYPE_WWANPP | IF_TYPE_WWANPP2 => ConnectionType::Cellular,
+      _ => ConnectionType::Other,
+    }
+  }
+
+  /// The change notification requested by the waiting thread.
+  struct Request {
+    /// Whether the notifier was dropped, locked while a notification is requested.
+    stopped: Mutex<bool>,
+    overlapped: UnsafeCell<OVERLAPPED>,
+  }
+
+  // SAFETY: the overlapped structure is only written by the system, and read once the request completes
+  unsafe impl Send for Request {}
+  unsafe impl Sync for Request {}
+
+  impl Drop for Request {
+    fn drop(&mut self) {
+      unsafe {
+        CloseHandle(self.overlapped.get_mut().hEvent);
+      }
+    }
+  }
+
+  /// Cancels the pending change notification when dropped, stopping the waiting thread.
+  pub struct Notifier(Arc<Request>);
+
+  impl std::fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.debug_struct("Notifier").finish()
+    }
+  }
+
+  impl Drop for Notifier {
+    fn drop(&mut self) {
+      let mut stopped = self.0.stopped.lock().unwrap();
+      *stopped = true;
+      // completes the pending request with an error
+      unsafe {
+        CancelIPChangeNotify(self.0.overlapped.get());
+      }
+    }
+  }
+
+  /// Calls `on_change` on the changes of the IP addresses until it returns `false` or the notifier is dropped.
+  pub fn notify_changes<F: Fn() -> bool + Send + 'static>(on_change: F) -> Option<Notifier> {
+    let event = unsafe { CreateEventW(std::ptr::null(), false, false, PCWSTR::null()) }.ok()?;
+    let request = Arc::new(Request {
+      stopped: Mutex::new(false),
+      overlapped: UnsafeCell::new(OVERLAPPED {
+        hEvent: event,
+        ..Default::default()
+      }),
+    });
+
+    let request_ = request.clone();
+    std::thread::spawn(move || loop {
+      let mut handle = HANDLE::default();
+      {
+        let stopped = request_.stopped.lock().unwrap();
+        if *stopped
+          || unsafe { NotifyAddrChange(&mut handle, request_.overlapped.get()) }
+            != ERROR_IO_PENDING.0
+        {
+          break;
+        }
+      }
+      // blocks until the address table changes or the request is cancelled
+      let mut transferred = 0;
+      let changed =
+        unsafe { GetOverlappedResult(handle, request_.overlapped.get(), &mut transferred, true) }
+          .as_bool();
+      if !changed || !on_change() {
+        break;
+      }
+    });
+    Some(Notifier(request))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn finds_default_interface() {
+    use super::linux::default_interface;
+
+    let table =
+      "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\t0\t0\t0
+eth0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+";
+    assert_eq!(default_interface(table, false).as_deref(), Some("eth0"));
+    assert_eq!(
+      default_interface(table.lines().next().unwrap(), false),
+      None
+    );
+
+    let table = "00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003    wlan0
+";
+    assert_eq!(default_interface(table, true).as_deref(), Some("wlan0"));
+  }
+}
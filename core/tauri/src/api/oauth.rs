@@ -0,0 +1,489 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! OAuth 2.0 authorization code flow for native apps, as described by [RFC 8252](https://www.rfc-editor.org/rfc/rfc8252).
+//!
+//! The authorization page is opened on the default browser, which redirects to a listener on the loopback interface.
+//! The authorization code is then exchanged for the tokens, protected by [PKCE](https://www.rfc-editor.org/rfc/rfc7636).
+
+use std::{
+  collections::HashMap,
+  io::{BufRead, BufReader, Write},
+  net::{Ipv4Addr, TcpListener, TcpStream},
+  time::{Duration, Instant},
+};
+
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::api::http::{Body, ClientBuilder, FormBody, FormPart, HttpRequestBuilder, ResponseType};
+
+/// The path of the redirect URI on the loopback listener.
+const REDIRECT_PATH: &str = "/callback";
+/// The default time to wait for the user to authorize the app, in seconds.
+const DEFAULT_TIMEOUT: u64 = 300;
+
+const SUCCESS_PAGE: &str = "<!DOCTYPE html><html><body><p>Authorization complete, you can close this window.</p></body></html>";
+const FAILURE_PAGE: &str =
+  "<!DOCTYPE html><html><body><p>Authorization failed, you can close this window.</p></body></html>";
+
+/// A random string of unreserved characters, as required for the PKCE verifier and the state.
+fn random_string(len: usize) -> String {
+  rand::thread_rng()
+    .sample_iter(&Alphanumeric)
+    .take(len)
+    .map(char::from)
+    .collect()
+}
+
+/// A PKCE code verifier and its `S256` challenge.
+#[derive(Debug, Clone)]
+pub struct Pkce {
+  /// The code verifier, sent on the token request.
+  pub verifier: String,
+  /// The code challenge, sent on the authorization request.
+  pub challenge: String,
+}
+
+impl Pkce {
+  /// Generates a random code verifier.
+  pub fn new() -> Self {
+    Self::from_verifier(random_string(64))
+  }
+
+  /// The challenge of the given code verifier.
+  pub fn from_verifier(verifier: impl Into<String>) -> Self {
+    let verifier = verifier.into();
+    let challenge =
+      base64::encode_config(Sha256::digest(verifier.as_bytes()), base64::URL_SAFE_NO_PAD);
+    Self {
+      verifier,
+      challenge,
+    }
+  }
+}
+
+impl Default for Pkce {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// The authorization request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthRequest {
+  /// The authorization endpoint of the provider, e.g. `https://github.com/login/oauth/authorize`.
+  pub authorization_url: Url,
+  /// The token endpoint of the provider, e.g. `https://github.com/login/oauth/access_token`.
+  pub token_url: Url,
+  /// The client identifier of the app.
+  pub client_id: String,
+  /// The client secret, if the provider requires one for native apps.
+  #[serde(default)]
+  pub client_secret: Option<String>,
+  /// The requested scopes.
+  #[serde(default)]
+  pub scopes: Vec<String>,
+  /// Additional parameters of the authorization request, e.g. `prompt` or `audience`.
+  #[serde(default)]
+  pub params: HashMap<String, String>,
+  /// The port of the loopback listener. Defaults to a random port.
+  ///
+  /// Providers that do not allow any port on loopback redirect URIs require a fixed one.
+  #[serde(default)]
+  pub port: Option<u16>,
+  /// The time to wait for the user to authorize the app, in seconds. Defaults to 5 minutes.
+  #[serde(default)]
+  pub timeout: Option<u64>,
+}
+
+impl AuthRequest {
+  /// Creates an authorization request with the default options.
+  pub fn new(authorization_url: Url, token_url: Url, client_id: impl Into<String>) -> Self {
+    Self {
+      authorization_url,
+      token_url,
+      client_id: client_id.into(),
+      client_secret: None,
+      scopes: Vec::new(),
+      params: HashMap::new(),
+      port: None,
+      timeout: None,
+    }
+  }
+
+  /// Sets the client secret.
+  #[must_use]
+  pub fn client_secret(mut self, client_secret: impl Into<String>) -> Self {
+    self.client_secret.replace(client_secret.into());
+    self
+  }
+
+  /// Adds a scope to request.
+  #[must_use]
+  pub fn scope(mut self, scope: impl Into<String>) -> Self {
+    self.scopes.push(scope.into());
+    self
+  }
+
+  /// Adds a parameter to the authorization request.
+  #[must_use]
+  pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    self.params.insert(key.into(), value.into());
+    self
+  }
+
+  /// Sets the port of the loopback listener.
+  #[must_use]
+  pub fn port(mut self, port: u16) -> Self {
+    self.port.replace(port);
+    self
+  }
+
+  /// Sets the time to wait for the user to authorize the app.
+  #[must_use]
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout.replace(timeout.as_secs());
+    self
+  }
+
+  /// The URL of the authorization page.
+  fn url(&self, redirect_uri: &str, state: &str, pkce: &Pkce) -> Url {
+    let mut url = self.authorization_url.clone();
+    {
+      let mut query = url.query_pairs_mut();
+      query
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &self.client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("state", state)
+        .append_pair("code_challenge", &pkce.challenge)
+        .append_pair("code_challenge_method", "S256");
+      if !self.scopes.is_empty() {
+        query.append_pair("scope", &self.scopes.join(" "));
+      }
+      for (key, value) in &self.params {
+        query.append_pair(key, value);
+      }
+    }
+    url
+  }
+}
+
+/// The tokens issued by the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TokenResponse {
+  /// The access token.
+  #[serde(alias = "access_token")]
+  pub access_token: String,
+  /// The token type, usually `Bearer`.
+  #[serde(alias = "token_type")]
+  pub token_type: String,
+  /// The lifetime of the access token in seconds.
+  #[serde(alias = "expires_in", default)]
+  pub expires_in: Option<u64>,
+  /// The refresh token.
+  #[serde(alias = "refresh_token", default)]
+  pub refresh_token: Option<String>,
+  /// The granted scopes, if they differ from the requested ones.
+  #[serde(default)]
+  pub scope: Option<String>,
+  /// The OpenID Connect ID token.
+  #[serde(alias = "id_token", default)]
+  pub id_token: Option<String>,
+  /// The other fields of the response.
+  #[serde(flatten)]
+  pub extra: HashMap<String, JsonValue>,
+}
+
+/// Checks that the endpoint uses HTTPS, or HTTP on the loopback interface.
+fn validate_endpoint(url: &Url) -> crate::api::Result<()> {
+  let loopback = match url.host() {
+    Some(url::Host::Domain(domain)) => domain == "localhost",
+    Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+    Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+    None => false,
+  };
+  match url.scheme() {
+    "https" => Ok(()),
+    "http" if loopback => Ok(()),
+    _ => Err(crate::api::Error::OAuth(format!(
+      "the endpoint `{}` must use https",
+      url
+    ))),
+  }
+}
+
+/// Reads the authorization code from the target of the redirect request, e.g. `/callback?code=abc&state=xyz`.
+///
+/// Returns `Ok(None)` if the request is not the redirect of this flow,
+/// such as a `favicon.ico` request or a request with another state.
+fn parse_redirect(target: &str, state: &str) -> crate::api::Result<Option<String>> {
+  let url = match Url::parse(&format!("http://{}{}", Ipv4Addr::LOCALHOST, target)) {
+    Ok(url) => url,
+    Err(_) => return Ok(None),
+  };
+  if url.path() != REDIRECT_PATH {
+    return Ok(None);
+  }
+  let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+  // any local process can connect to the listener, so only the redirect of this flow can end it
+  if params.get("state").map(String::as_str) != Some(state) {
+    return Ok(None);
+  }
+  if let Some(error) = params.get("error") {
+    return Err(crate::api::Error::OAuth(
+      match params.get("error_description") {
+        Some(description) => format!("{}: {}", error, description),
+        None => error.clone(),
+      },
+    ));
+  }
+  params
+    .get("code")
+    .cloned()
+    .map(Some)
+    .ok_or_else(|| crate::api::Error::OAuth("the redirect has no authorization code".into()))
+}
+
+/// Handles a connection to the loopback listener, returning the authorization code of the redirect.
+///
+/// I/O errors, such as the request not being sent before the read timeout, are returned as [`crate::api::Error::Io`].
+fn handle_connection(stream: TcpStream, state: &str) -> crate::api::Result<Option<String>> {
+  stream.set_nonblocking(false)?;
+  stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+  let mut request_line = String::new();
+  BufReader::new(&stream).read_line(&mut request_line)?;
+  // GET /callback?code=abc&state=xyz HTTP/1.1
+  let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+  let result = parse_redirect(target, state);
+
+  let (status, page) = match &result {
+    Ok(Some(_)) => ("200 OK", SUCCESS_PAGE),
+    Ok(None) => ("404 Not Found", ""),
+    Err(_) => ("400 Bad Request", FAILURE_PAGE),
+  };
+  let mut stream = stream;
+  let _ = write!(
+    stream,
+    "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    status,
+    page.len(),
+    page
+  );
+  let _ = stream.flush();
+  result
+}
+
+/// Waits for the redirect on the loopback listener.
+fn wait_for_code(
+  listener: TcpListener,
+  state: &str,
+  timeout: Duration,
+) -> crate::api::Result<String> {
+  listener.set_nonblocking(true)?;
+  let deadline = Instant::now() + timeout;
+  loop {
+    match listener.accept() {
+      Ok((stream, _)) => match handle_connection(stream, state) {
+        Ok(Some(code)) => return Ok(code),
+        // another request, or a connection that failed: keep waiting for the redirect
+        Ok(None) | Err(crate::api::Error::Io(_)) => (),
+        Err(e) => return Err(e),
+      },
+      Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+        if Instant::now() >= deadline {
+          return Err(crate::api::Error::OAuth(
+            "timed out waiting for the authorization".into(),
+          ));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+      }
+      Err(e)
+        if matches!(
+          e.kind(),
+          std::io::ErrorKind::ConnectionAborted | std::io::ErrorKind::ConnectionReset
+        ) => {}
+      Err(e) => return Err(e.into()),
+    }
+  }
+}
+
+/// Runs the authorization code flow: opens the authorization page on the default browser,
+/// waits for the redirect on a loopback listener and exchanges the code for the tokens.
+///
+/// The authorization and token endpoints must use HTTPS, or HTTP on the loopback interface.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::oauth::{authenticate, AuthRequest};
+/// tauri::async_runtime::block_on(async {
+///   let request = AuthRequest::new(
+///     "https://github.com/login/oauth/authorize".parse().unwrap(),
+///     "https://github.com/login/oauth/access_token".parse().unwrap(),
+///     "client-id",
+///   )
+///   .scope("read:user");
+///   let tokens = authenticate(request).await.unwrap();
+///   println!("{}", tokens.access_token);
+/// });
+/// ```
+pub async fn authenticate(request: AuthRequest) -> crate::api::Result<TokenResponse> {
+  validate_endpoint(&request.authorization_url)?;
+  validate_endpoint(&request.token_url)?;
+
+  let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, request.port.unwrap_or(0)))?;
+  let redirect_uri = format!(
+    "http://{}:{}{}",
+    Ipv4Addr::LOCALHOST,
+    listener.local_addr()?.port(),
+    REDIRECT_PATH
+  );
+  let state = random_string(32);
+  let pkce = Pkce::new();
+
+  open::that(request.url(&redirect_uri, &state, &pkce).as_str())?;
+
+  let timeout = Duration::from_secs(request.timeout.unwrap_or(DEFAULT_TIMEOUT));
+  let code = {
+    let state = state.clone();
+    crate::async_runtime::spawn_blocking(move || wait_for_code(listener, &state, timeout))
+      .await
+      .map_err(|e| crate::api::Error::OAuth(e.to_string()))??
+  };
+
+  let mut form = HashMap::new();
+  form.insert(
+    "grant_type".into(),
+    FormPart::Text("authorization_code".into()),
+  );
+  form.insert("code".into(), FormPart::Text(code));
+  form.insert("redirect_uri".into(), FormPart::Text(redirect_uri));
+  form.insert("client_id".into(), FormPart::Text(request.client_id));
+  form.insert("code_verifier".into(), FormPart::Text(pkce.verifier));
+  if let Some(client_secret) = request.client_secret {
+    form.insert("client_secret".into(), FormPart::Text(client_secret));
+  }
+
+  let token_request = HttpRequestBuilder::new("POST", request.token_url.as_str())?
+    .header("Accept", "application/json")?
+    .body(Body::Form(FormBody::new(form)))
+    .response_type(ResponseType::Json);
+  let response = ClientBuilder::new()
+    .build()?
+    .send(token_request)
+    .await?
+    .read()
+    .await?;
+  if !(200..300).contains(&response.status) {
+    return Err(crate::api::Error::OAuth(format!(
+      "the token request failed with status {}: {}",
+      response.status, response.data
+    )));
+  }
+  serde_json::from_value(response.data).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_redirect, validate_endpoint, AuthRequest, Pkce, TokenResponse};
+
+  #[test]
+  fn computes_pkce_challenge() {
+    let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mJ0kCGA7xPfjW1yhIHk6w5Ge1uVnlo");
+    assert_eq!(
+      pkce.challenge,
+      "bpzhyGFxbMHQzhRpBiCHSENdq5AOtssUcwPdW6LaHF8"
+    );
+
+    let pkce = Pkce::new();
+    assert_eq!(pkce.verifier.len(), 64);
+    assert_ne!(pkce.verifier, Pkce::new().verifier);
+  }
+
+  #[test]
+  fn builds_authorization_url() {
+    let request = AuthRequest::new(
+      "https://example.com/authorize?tenant=1".parse().unwrap(),
+      "https://example.com/token".parse().unwrap(),
+      "client",
+    )
+    .scope("openid")
+    .scope("email");
+    let pkce = Pkce::from_verifier("verifier");
+    let url = request.url("http://127.0.0.1:8080/callback", "state", &pkce);
+    assert_eq!(
+      url.as_str(),
+      format!(
+        "https://example.com/authorize?tenant=1&response_type=code&client_id=client&redirect_uri=http%3A%2F%2F127.0.0.1%3A8080%2Fcallback&state=state&code_challenge={}&code_challenge_method=S256&scope=openid+email",
+        pkce.challenge
+      )
+    );
+  }
+
+  #[test]
+  fn parses_redirect() {
+    assert_eq!(
+      parse_redirect("/callback?code=abc&state=xyz", "xyz").unwrap(),
+      Some("abc".into())
+    );
+    assert_eq!(parse_redirect("/favicon.ico", "xyz").unwrap(), None);
+    assert_eq!(
+      parse_redirect("/callback?code=abc&state=other", "xyz").unwrap(),
+      None
+    );
+    assert_eq!(
+      parse_redirect("/callback?error=access_denied&state=other", "xyz").unwrap(),
+      None
+    );
+    assert!(parse_redirect("/callback?state=xyz", "xyz").is_err());
+    let error = parse_redirect(
+      "/callback?error=access_denied&error_description=denied&state=xyz",
+      "xyz",
+    )
+    .unwrap_err();
+    assert_eq!(error.to_string(), "OAuth error: access_denied: denied");
+  }
+
+  #[test]
+  fn validates_endpoints() {
+    for url in [
+      "https://example.com/token",
+      "http://localhost:8080/token",
+      "http://127.0.0.1/token",
+      "http://[::1]/token",
+    ] {
+      assert!(validate_endpoint(&url.parse().unwrap()).is_ok(), "{}", url);
+    }
+    for url in [
+      "http://example.com/token",
+      "http://localhost.example.com/token",
+      "file:///token",
+    ] {
+      assert!(validate_endpoint(&url.parse().unwrap()).is_err(), "{}", url);
+    }
+  }
+
+  #[test]
+  fn deserializes_token_response() {
+    let response: TokenResponse = serde_json::from_str(
+      r#"{"access_token":"token","token_type":"bearer","expires_in":3600,"refresh_token":"refresh","scope":"read:user","custom":true}"#,
+    )
+    .unwrap();
+    assert_eq!(response.access_token, "token");
+    assert_eq!(response.expires_in, Some(3600));
+    assert_eq!(response.refresh_token.as_deref(), Some("refresh"));
+    assert_eq!(
+      response.extra.get("custom"),
+      Some(&serde_json::Value::Bool(true))
+    );
+  }
+}
@@ -13,6 +13,12 @@ use crate::{Config, Env, PackageInfo};
 
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+#[cfg(feature = "path-volumes-api")]
+mod volume;
+#[cfg(feature = "path-volumes-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "path-volumes-api")))]
+pub use volume::*;
+
 // we have to wrap the BaseDirectory enum in a module for #[allow(deprecated)]
 // to work, because the procedural macros on the enum prevent it from working directly
 // TODO: remove this workaround in v2 along with deprecated variants
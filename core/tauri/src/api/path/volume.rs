@@ -0,0 +1,408 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Enumeration of the mounted drives and volumes.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use sysinfo::{DiskExt, System, SystemExt};
+
+/// A mounted drive or volume.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Volume {
+  /// The name of the volume, or of its device when it has no label.
+  pub name: String,
+  /// The path where the volume is mounted.
+  pub mount_point: PathBuf,
+  /// The capacity in bytes.
+  pub total_space: u64,
+  /// The space available to the current user in bytes.
+  pub available_space: u64,
+  /// Whether the volume is on a removable drive, such as a USB stick or an SD card.
+  pub removable: bool,
+}
+
+/// A change of the mounted volumes, delivered by [`watch_volumes`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "volume", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum VolumeEvent {
+  /// The volume was mounted.
+  Mounted(Volume),
+  /// The volume was unmounted.
+  Unmounted(Volume),
+}
+
+/// Returns the mounted drives and volumes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::path::volumes;
+///
+/// for volume in volumes() {
+///   println!("{} mounted on {}", volume.name, volume.mount_point.display());
+/// }
+/// ```
+pub fn volumes() -> Vec<Volume> {
+  let mut system = System::new();
+  system.refresh_disks_list();
+  system
+    .disks()
+    .iter()
+    .map(|disk| Volume {
+      name: disk.name().to_string_lossy().into_owned(),
+      mount_point: disk.mount_point().to_path_buf(),
+      total_space: disk.total_space(),
+      available_space: disk.available_space(),
+      removable: disk.is_removable(),
+    })
+    .collect()
+}
+
+/// Watches the mounted volumes until it is dropped, see [`watch_volumes`].
+#[derive(Debug)]
+pub struct VolumeWatcher {
+  /// The write end of the pipe waking the watcher thread up, closed to stop it.
+  #[cfg(unix)]
+  stop: libc::c_int,
+  /// The hidden window receiving the device notifications, closed to stop the watcher thread.
+  #[cfg(windows)]
+  window: isize,
+}
+
+impl Drop for VolumeWatcher {
+  fn drop(&mut self) {
+    #[cfg(unix)]
+    unsafe {
+      libc::close(self.stop);
+    }
+    #[cfg(windows)]
+    unsafe {
+      use windows::Win32::{
+        Foundation::{HWND, LPARAM, WPARAM},
+        UI::WindowsAndMessaging::{PostMessageW, WM_CLOSE},
+      };
+      PostMessageW(HWND(self.window), WM_CLOSE, WPARAM(0), LPARAM(0));
+    }
+  }
+}
+
+/// Calls the handler when a volume is mounted or unmounted, until the watcher is dropped.
+///
+/// The volumes are compared by mount point when the system reports a change of the mount table,
+/// so a volume remounted at the same path is not reported.
+///
+/// # Platform-specific
+///
+/// - **Linux:** Watches `/proc/self/mounts`.
+/// - **macOS:** Watches the file system mount events with `kqueue`.
+/// - **Windows:** Listens to the device notifications broadcast to a hidden window.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::path::{watch_volumes, VolumeEvent};
+///
+/// let watcher = watch_volumes(|event| match event {
+///   VolumeEvent::Mounted(volume) => println!("mounted {}", volume.name),
+///   VolumeEvent::Unmounted(volume) => println!("unmounted {}", volume.name),
+///   _ => (),
+/// })
+/// .unwrap();
+/// ```
+pub fn watch_volumes<F: Fn(VolumeEvent) + Send + 'static>(
+  handler: F,
+) -> crate::api::Result<VolumeWatcher> {
+  let mut previous = volumes();
+  let refresh = move || {
+    let current = volumes();
+    for event in diff(&previous, &current) {
+      handler(event);
+    }
+    previous = current;
+  };
+  platform::watch(refresh).map_err(Into::into)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod platform {
+  use super::VolumeWatcher;
+  use std::io::{Error, Result};
+
+  pub fn watch<F: FnMut() + Send + 'static>(mut refresh: F) -> Result<VolumeWatcher> {
+    let mounts = unsafe {
+      libc::open(
+        b"/proc/self/mounts\0".as_ptr() as *const libc::c_char,
+        libc::O_RDONLY | libc::O_CLOEXEC,
+      )
+    };
+    if mounts < 0 {
+      return Err(Error::last_os_error());
+    }
+    let mut pipe = [0; 2];
+    if unsafe { libc::pipe2(pipe.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+      let error = Error::last_os_error();
+      unsafe { libc::close(mounts) };
+      return Err(error);
+    }
+    let [stop_reader, stop] = pipe;
+
+    std::thread::spawn(move || {
+      // the kernel reports a change of the mount table as an exceptional condition
+      let mut fds = [
+        libc::pollfd {
+          fd: mounts,
+          events: libc::POLLPRI,
+          revents: 0,
+        },
+        libc::pollfd {
+          fd: stop_reader,
+          events: libc::POLLIN,
+          revents: 0,
+        },
+      ];
+      loop {
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) };
+        if ready < 0 {
+          if Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+            continue;
+          }
+          break;
+        }
+        // the watcher was dropped, closing the write end of the pipe
+        if fds[1].revents != 0 {
+          break;
+        }
+        if fds[0].revents & (libc::POLLPRI | libc::POLLERR) != 0 {
+          refresh();
+        }
+      }
+      unsafe {
+        libc::close(mounts);
+        libc::close(stop_reader);
+      }
+    });
+
+    Ok(VolumeWatcher { stop })
+  }
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+mod platform {
+  use super::VolumeWatcher;
+  use std::io::{Error, Result};
+
+  pub fn watch<F: FnMut() + Send + 'static>(mut refresh: F) -> Result<VolumeWatcher> {
+    let mut pipe = [0; 2];
+    if unsafe { libc::pipe(pipe.as_mut_ptr()) } != 0 {
+      return Err(Error::last_os_error());
+    }
+    let [stop_reader, stop] = pipe;
+    let close = || unsafe {
+      libc::close(stop_reader);
+      libc::close(stop);
+    };
+
+    let queue = unsafe { libc::kqueue() };
+    if queue < 0 {
+      let error = Error::last_os_error();
+      close();
+      return Err(error);
+    }
+    // SAFETY: `kevent` is a plain C struct
+    let mut mount_filter: libc::kevent = unsafe { std::mem::zeroed() };
+    mount_filter.filter = libc::EVFILT_FS;
+    mount_filter.flags = libc::EV_ADD | libc::EV_CLEAR;
+    let mut stop_filter: libc::kevent = unsafe { std::mem::zeroed() };
+    stop_filter.ident = stop_reader as libc::uintptr_t;
+    stop_filter.filter = libc::EVFILT_READ;
+    stop_filter.flags = libc::EV_ADD;
+    let changes = [mount_filter, stop_filter];
+    if unsafe {
+      libc::kevent(
+        queue,
+        changes.as_ptr(),
+        changes.len() as _,
+        std::ptr::null_mut(),
+        0,
+        std::ptr::null(),
+      )
+    } < 0
+    {
+      let error = Error::last_os_error();
+      unsafe { libc::close(queue) };
+      close();
+      return Err(error);
+    }
+
+    std::thread::spawn(move || {
+      loop {
+        let mut event: libc::kevent = unsafe { std::mem::zeroed() };
+        let ready =
+          unsafe { libc::kevent(queue, std::ptr::null(), 0, &mut event, 1, std::ptr::null()) };
+        if ready < 0 {
+          if Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+            continue;
+          }
+          break;
+        }
+        // the watcher was dropped, closing the write end of the pipe
+        if event.filter == libc::EVFILT_READ {
+          break;
+        }
+        refresh();
+      }
+      unsafe {
+        libc::close(queue);
+        libc::close(stop_reader);
+      }
+    });
+
+    Ok(VolumeWatcher { stop })
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::VolumeWatcher;
+  use std::{cell::RefCell, io::Result};
+  use windows::{
+    core::PCWSTR,
+    Win32::{
+      Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+      System::LibraryLoader::GetModuleHandleW,
+      UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW,
+        RegisterClassW, DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE, MSG, WINDOW_EX_STYLE,
+        WM_CLOSE, WM_DEVICECHANGE, WNDCLASSW, WS_OVERLAPPED,
+      },
+    },
+  };
+
+  thread_local! {
+    /// The refresh function of the watcher running on the current thread.
+    static REFRESH: RefCell<Option<Box<dyn FnMut()>>> = RefCell::new(None);
+  }
+
+  unsafe extern "system" fn window_proc(
+    window: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+  ) -> LRESULT {
+    if message == WM_DEVICECHANGE
+      && (wparam.0 as u32 == DBT_DEVICEARRIVAL || wparam.0 as u32 == DBT_DEVICEREMOVECOMPLETE)
+    {
+      REFRESH.with(|refresh| {
+        if let Some(refresh) = refresh.borrow_mut().as_mut() {
+          refresh();
+        }
+      });
+    }
+    DefWindowProcW(window, message, wparam, lparam)
+  }
+
+  pub fn watch<F: FnMut() + Send + 'static>(refresh: F) -> Result<VolumeWatcher> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || unsafe {
+      let class_name = "TauriVolumeWatcher\0".encode_utf16().collect::<Vec<u16>>();
+      let instance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+      // registering the class again fails, which is fine
+      RegisterClassW(&WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        hInstance: instance,
+        lpszClassName: PCWSTR(class_name.as_ptr()),
+        ..Default::default()
+      });
+      // the volume notifications are only broadcast to top-level windows, so it can't be a message-only window
+      let window = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        PCWSTR(class_name.as_ptr()),
+        PCWSTR::null(),
+        WS_OVERLAPPED,
+        0,
+        0,
+        0,
+        0,
+        HWND::default(),
+        None,
+        instance,
+        std::ptr::null(),
+      );
+      if window.0 == 0 {
+        let _ = tx.send(Err(std::io::Error::last_os_error()));
+        return;
+      }
+      REFRESH.with(|r| r.replace(Some(Box::new(refresh))));
+      let _ = tx.send(Ok(window.0));
+
+      let mut message = MSG::default();
+      while GetMessageW(&mut message, HWND::default(), 0, 0).as_bool() {
+        // posted by the watcher when it is dropped
+        if message.hwnd == window && message.message == WM_CLOSE {
+          break;
+        }
+        DispatchMessageW(&message);
+      }
+      DestroyWindow(window);
+      REFRESH.with(|r| r.replace(None));
+    });
+
+    let window = rx.recv().map_err(|_| {
+      std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "the volume watcher thread exited",
+      )
+    })??;
+    Ok(VolumeWatcher { window })
+  }
+}
+
+fn diff(previous: &[Volume], current: &[Volume]) -> Vec<VolumeEvent> {
+  let unmounted = previous
+    .iter()
+    .filter(|v| !current.iter().any(|c| c.mount_point == v.mount_point))
+    .cloned()
+    .map(VolumeEvent::Unmounted);
+  let mounted = current
+    .iter()
+    .filter(|v| !previous.iter().any(|p| p.mount_point == v.mount_point))
+    .cloned()
+    .map(VolumeEvent::Mounted);
+  unmounted.chain(mounted).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{diff, Volume, VolumeEvent};
+
+  fn volume(name: &str, mount_point: &str, available_space: u64) -> Volume {
+    Volume {
+      name: name.into(),
+      mount_point: mount_point.into(),
+      total_space: 1024,
+      available_space,
+      removable: true,
+    }
+  }
+
+  #[test]
+  fn diffs_by_mount_point() {
+    let previous = vec![volume("root", "/", 100), volume("usb", "/media/usb", 10)];
+    let current = vec![volume("root", "/", 50), volume("sd", "/media/sd", 20)];
+
+    assert_eq!(
+      diff(&previous, &current),
+      vec![
+        VolumeEvent::Unmounted(volume("usb", "/media/usb", 10)),
+        VolumeEvent::Mounted(volume("sd", "/media/sd", 20)),
+      ]
+    );
+    assert!(diff(&current, &current).is_empty());
+  }
+}
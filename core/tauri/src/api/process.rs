@@ -14,6 +14,11 @@ mod command;
 #[cfg(feature = "process-command-api")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "process-command-api")))]
 pub use command::*;
+mod relaunch;
+#[cfg(feature = "process-command-api")]
+mod tree;
+
+pub use relaunch::{relaunch, restart_with, RestartOptions};
 
 /// Finds the current running binary's path.
 ///
@@ -66,6 +71,7 @@ pub fn current_binary(_env: &Env) -> std::io::Result<PathBuf> {
 ///
 /// See [`current_binary`] for platform specific behavior, and
 /// [`tauri_utils::platform::current_exe`] for possible security implications.
+/// Use [`restart_with`] to change the arguments, environment variables and privileges of the new process.
 ///
 /// # Examples
 ///
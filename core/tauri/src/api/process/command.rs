@@ -4,6 +4,7 @@
 
 use std::{
   collections::HashMap,
+  fmt,
   io::{BufReader, Write},
   path::PathBuf,
   process::{Command as StdCommand, Stdio},
@@ -21,12 +22,14 @@ const CREATE_NO_WINDOW: u32 = 0x0800_0000;
 
 use crate::async_runtime::{block_on as block_on_task, channel, Receiver, Sender};
 pub use encoding_rs::Encoding;
-use os_pipe::{pipe, PipeReader, PipeWriter};
+use os_pipe::{pipe, PipeReader};
 use serde::Serialize;
 use shared_child::SharedChild;
 use tauri_utils::platform;
 
-type ChildStore = Arc<Mutex<HashMap<u32, Arc<SharedChild>>>>;
+use super::tree::ProcessTree;
+
+type ChildStore = Arc<Mutex<HashMap<u32, ChildHandle>>>;
 
 fn commands() -> &'static ChildStore {
   use once_cell::sync::Lazy;
@@ -34,13 +37,112 @@ fn commands() -> &'static ChildStore {
   &STORE
 }
 
-/// Kills all child processes created with [`Command`].
+/// The trees of the children spawned with [`Command::kill_tree_on_exit`] which exited before their descendants.
+type TreeStore = Mutex<Vec<Arc<ProcessTree>>>;
+
+fn exited_trees() -> &'static TreeStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<TreeStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// Removes the child from the store, keeping its tree to kill it on exit if its descendants are still running.
+fn remove_child(pid: u32) {
+  let handle = commands().lock().unwrap().remove(&pid);
+  let mut trees = exited_trees().lock().unwrap();
+  trees.retain(|tree| tree.is_alive());
+  if let Some(tree) = handle.and_then(|handle| handle.tree) {
+    if tree.is_alive() {
+      trees.push(tree);
+    }
+  }
+}
+
+/// Kills all child processes created with [`Command`],
+/// and the descendants of the ones spawned with [`Command::kill_tree_on_exit`].
 /// By default it's called before the [`crate::App`] exits.
 pub fn kill_children() {
   let commands = commands().lock().unwrap();
   let children = commands.values();
   for child in children {
-    let _ = child.kill();
+    let _ = if child.kill_tree_on_exit {
+      child.kill_tree()
+    } else {
+      child.kill()
+    };
+  }
+  for tree in exited_trees().lock().unwrap().drain(..) {
+    if tree.is_alive() {
+      let _ = tree.kill();
+    }
+  }
+}
+
+#[derive(Clone)]
+enum Killer {
+  Process(Arc<SharedChild>),
+  #[cfg(feature = "process-pty")]
+  Pty(Arc<Mutex<Box<dyn portable_pty::ChildKiller + Send + Sync>>>),
+}
+
+/// A handle to kill a child process and its descendants.
+#[derive(Clone)]
+struct ChildHandle {
+  killer: Killer,
+  tree: Option<Arc<ProcessTree>>,
+  kill_tree_on_exit: bool,
+}
+
+impl ChildHandle {
+  fn new(killer: Killer, pid: u32, kill_tree_on_exit: bool) -> Self {
+    Self {
+      killer,
+      // the process is only killed alone if its tree isn't tracked
+      tree: if kill_tree_on_exit {
+        ProcessTree::new(pid).ok().map(Arc::new)
+      } else {
+        None
+      },
+      kill_tree_on_exit,
+    }
+  }
+
+  fn kill(&self) -> std::io::Result<()> {
+    match &self.killer {
+      Killer::Process(child) => child.kill(),
+      #[cfg(feature = "process-pty")]
+      Killer::Pty(killer) => killer.lock().unwrap().kill(),
+    }
+  }
+
+  fn kill_tree(&self) -> std::io::Result<()> {
+    match &self.tree {
+      Some(tree) => tree.kill(),
+      None => self.kill(),
+    }
+  }
+}
+
+/// The size of the pseudo terminal of a command spawned with [`Command::pty`].
+#[cfg(feature = "process-pty")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "process-pty")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtySize {
+  /// The number of rows.
+  pub rows: u16,
+  /// The number of columns.
+  pub cols: u16,
+}
+
+#[cfg(feature = "process-pty")]
+impl From<PtySize> for portable_pty::PtySize {
+  fn from(size: PtySize) -> Self {
+    Self {
+      rows: size.rows,
+      cols: size.cols,
+      pixel_width: 0,
+      pixel_height: 0,
+    }
   }
 }
 
@@ -61,6 +163,8 @@ pub enum CommandEvent {
   /// Stderr bytes until a newline (\n) or carriage return (\r) is found.
   Stderr(String),
   /// Stdout bytes until a newline (\n) or carriage return (\r) is found.
+  ///
+  /// In PTY mode, the terminal output as it is written, including stderr.
   Stdout(String),
   /// An error happened waiting for the command to finish or converting the stdout/stderr bytes to an UTF-8 string.
   Error(String),
@@ -77,31 +181,135 @@ pub struct Command {
   env: HashMap<String, String>,
   current_dir: Option<PathBuf>,
   encoding: Option<&'static Encoding>,
+  kill_tree_on_exit: bool,
+  #[cfg(feature = "process-pty")]
+  pty: Option<PtySize>,
+}
+
+/// A writable handle to the stdin of a child process.
+///
+/// It can be cloned to write from other threads while the [`CommandChild`] is kept elsewhere.
+#[derive(Clone)]
+pub struct ChildStdin(Arc<Mutex<Option<Box<dyn Write + Send>>>>);
+
+impl fmt::Debug for ChildStdin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ChildStdin")
+      .field("closed", &self.0.lock().unwrap().is_none())
+      .finish()
+  }
+}
+
+impl ChildStdin {
+  fn new(writer: Box<dyn Write + Send>) -> Self {
+    Self(Arc::new(Mutex::new(Some(writer))))
+  }
+
+  /// Closes the stdin, so the process reads an end of file. Writing afterwards fails.
+  ///
+  /// In PTY mode the terminal stays open; write the `EOF` control character (`\x04`) instead.
+  pub fn close(&self) {
+    self.0.lock().unwrap().take();
+  }
+}
+
+impl Write for ChildStdin {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    match self.0.lock().unwrap().as_mut() {
+      Some(writer) => writer.write(buf),
+      None => Err(stdin_closed()),
+    }
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    match self.0.lock().unwrap().as_mut() {
+      Some(writer) => writer.flush(),
+      None => Err(stdin_closed()),
+    }
+  }
+}
+
+fn stdin_closed() -> std::io::Error {
+  std::io::Error::new(std::io::ErrorKind::BrokenPipe, "the stdin was closed")
 }
 
 /// Spawned child process.
-#[derive(Debug)]
 pub struct CommandChild {
-  inner: Arc<SharedChild>,
-  stdin_writer: PipeWriter,
+  handle: ChildHandle,
+  pid: u32,
+  stdin: ChildStdin,
+  #[cfg(feature = "process-pty")]
+  pty: Option<Arc<Mutex<Box<dyn portable_pty::MasterPty + Send>>>>,
+}
+
+impl fmt::Debug for CommandChild {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CommandChild")
+      .field("pid", &self.pid)
+      .field("stdin", &self.stdin)
+      .finish()
+  }
 }
 
 impl CommandChild {
   /// Writes to process stdin.
   pub fn write(&mut self, buf: &[u8]) -> crate::api::Result<()> {
-    self.stdin_writer.write_all(buf)?;
+    self.stdin.write_all(buf)?;
+    self.stdin.flush()?;
     Ok(())
   }
 
+  /// Returns a handle to the process stdin.
+  pub fn stdin(&self) -> ChildStdin {
+    self.stdin.clone()
+  }
+
+  /// Closes the process stdin. See [`ChildStdin::close`].
+  pub fn close_stdin(&self) {
+    self.stdin.close();
+  }
+
+  /// Resizes the pseudo terminal of a command spawned with [`Command::pty`].
+  #[cfg(feature = "process-pty")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "process-pty")))]
+  pub fn resize(&self, size: PtySize) -> crate::api::Result<()> {
+    let pty = self.pty.as_ref().ok_or_else(|| {
+      crate::api::Error::Command("the process was not spawned in PTY mode".into())
+    })?;
+    pty
+      .lock()
+      .unwrap()
+      .resize(size.into())
+      .map_err(|e| crate::api::Error::Command(e.to_string()))
+  }
+
   /// Sends a kill signal to the child.
+  ///
+  /// The processes it spawned keep running, see [`Self::kill_tree`].
   pub fn kill(self) -> crate::api::Result<()> {
-    self.inner.kill()?;
+    self.handle.kill()?;
+    Ok(())
+  }
+
+  /// Kills the child and all its descendants.
+  ///
+  /// The descendants are only tracked for commands spawned with [`Command::kill_tree_on_exit`],
+  /// otherwise only the child is killed.
+  ///
+  /// # Platform-specific
+  ///
+  /// - **Linux / macOS:** Kills the process group of the child.
+  ///   Descendants that moved to another process group or session (e.g. daemons) are not killed.
+  /// - **Windows:** Terminates the job object the child was assigned to, before it started running.
+  ///   Descendants that broke away from the job are not killed.
+  pub fn kill_tree(self) -> crate::api::Result<()> {
+    self.handle.kill_tree()?;
     Ok(())
   }
 
   /// Returns the process pid.
   pub fn pid(&self) -> u32 {
-    self.inner.id()
+    self.pid
   }
 }
 
@@ -174,6 +382,9 @@ impl Command {
       env: Default::default(),
       current_dir: None,
       encoding: None,
+      kill_tree_on_exit: false,
+      #[cfg(feature = "process-pty")]
+      pty: None,
     }
   }
 
@@ -205,13 +416,20 @@ impl Command {
     self
   }
 
-  /// Adds or updates multiple environment variable mappings.
+  /// Sets the environment variable mappings, replacing the ones already set.
   #[must_use]
   pub fn envs(mut self, env: HashMap<String, String>) -> Self {
     self.env = env;
     self
   }
 
+  /// Adds or updates multiple environment variable mappings, keeping the other mappings already set.
+  #[must_use]
+  pub fn extend_envs(mut self, env: HashMap<String, String>) -> Self {
+    self.env.extend(env);
+    self
+  }
+
   /// Sets the working directory for the child process.
   #[must_use]
   pub fn current_dir(mut self, current_dir: PathBuf) -> Self {
@@ -226,6 +444,33 @@ impl Command {
     self
   }
 
+  /// Tracks the descendants of the process, to kill them with [`CommandChild::kill_tree`] and when the app exits,
+  /// even if the process exited before them.
+  ///
+  /// # Platform-specific
+  ///
+  /// - **Linux / macOS:** The process is the leader of a new process group,
+  ///   so it doesn't receive the signals sent to the group of the app, e.g. on Ctrl+C in a terminal.
+  /// - **Windows:** The descendants are also killed when the app crashes.
+  #[must_use]
+  pub fn kill_tree_on_exit(mut self) -> Self {
+    self.kill_tree_on_exit = true;
+    self
+  }
+
+  /// Runs the command in a pseudo terminal of the given size, so interactive programs
+  /// (shells, REPLs, `ssh`, etc.) behave like they do in a terminal emulator.
+  ///
+  /// In PTY mode stderr is merged into stdout and [`CommandEvent::Stdout`] is emitted as the output is written,
+  /// not split into lines, so it can be fed to a terminal UI.
+  #[cfg(feature = "process-pty")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "process-pty")))]
+  #[must_use]
+  pub fn pty(mut self, size: PtySize) -> Self {
+    self.pty.replace(size);
+    self
+  }
+
   /// Spawns the command.
   ///
   /// # Examples
@@ -252,8 +497,21 @@ impl Command {
   /// });
   /// ```
   pub fn spawn(self) -> crate::api::Result<(Receiver<CommandEvent>, CommandChild)> {
+    #[cfg(feature = "process-pty")]
+    if let Some(size) = self.pty {
+      return self.spawn_pty(size);
+    }
+
     let encoding = self.encoding;
+    let kill_tree_on_exit = self.kill_tree_on_exit;
     let mut command: StdCommand = self.into();
+    if kill_tree_on_exit {
+      #[cfg(unix)]
+      super::tree::set_process_group(&mut command);
+      // the child is resumed once it is assigned to the job of its tree
+      #[cfg(windows)]
+      command.creation_flags(CREATE_NO_WINDOW | super::tree::CREATE_SUSPENDED_FLAG);
+    }
     let (stdout_reader, stdout_writer) = pipe()?;
     let (stderr_reader, stderr_writer) = pipe()?;
     let (stdin_reader, stdin_writer) = pipe()?;
@@ -266,7 +524,22 @@ impl Command {
     let child_ = child.clone();
     let guard = Arc::new(RwLock::new(()));
 
-    commands().lock().unwrap().insert(child.id(), child.clone());
+    let handle = ChildHandle::new(
+      Killer::Process(child.clone()),
+      child.id(),
+      kill_tree_on_exit,
+    );
+    #[cfg(windows)]
+    if kill_tree_on_exit {
+      if let Err(e) = super::tree::resume(child.id()) {
+        let _ = child.kill();
+        return Err(e.into());
+      }
+    }
+    commands()
+      .lock()
+      .unwrap()
+      .insert(child.id(), handle.clone());
 
     let (tx, rx) = channel(1);
 
@@ -289,7 +562,7 @@ impl Command {
       let _ = match child_.wait() {
         Ok(status) => {
           let _l = guard.write().unwrap();
-          commands().lock().unwrap().remove(&child_.id());
+          remove_child(child_.id());
           block_on_task(async move {
             tx.send(CommandEvent::Terminated(TerminatedPayload {
               code: status.code(),
@@ -311,8 +584,88 @@ impl Command {
     Ok((
       rx,
       CommandChild {
-        inner: child,
-        stdin_writer,
+        handle,
+        pid: child.id(),
+        stdin: ChildStdin::new(Box::new(stdin_writer)),
+        #[cfg(feature = "process-pty")]
+        pty: None,
+      },
+    ))
+  }
+
+  #[cfg(feature = "process-pty")]
+  fn spawn_pty(self, size: PtySize) -> crate::api::Result<(Receiver<CommandEvent>, CommandChild)> {
+    let pty_error = |e: anyhow::Error| crate::api::Error::Command(e.to_string());
+
+    let pair = portable_pty::native_pty_system()
+      .openpty(size.into())
+      .map_err(pty_error)?;
+    let mut command = portable_pty::CommandBuilder::new(&self.program);
+    command.args(&self.args);
+    if self.env_clear {
+      command.env_clear();
+    }
+    for (key, value) in &self.env {
+      command.env(key, value);
+    }
+    if let Some(current_dir) = &self.current_dir {
+      command.cwd(current_dir);
+    }
+    let mut child = pair.slave.spawn_command(command).map_err(pty_error)?;
+    // the child process holds the only handle to the terminal, so reading ends when it exits
+    drop(pair.slave);
+
+    // the process id identifies the child on the commands store, so it can't be defaulted
+    let pid = match child.process_id() {
+      Some(pid) => pid,
+      None => {
+        let _ = child.kill();
+        return Err(crate::api::Error::Command(
+          "failed to get the process id of the child process".into(),
+        ));
+      }
+    };
+    // the child is the leader of a new session. On Windows, it is assigned to the job of its tree
+    // after it started, since the pseudo console spawns it, so the processes it creates right away may not be tracked
+    let handle = ChildHandle::new(
+      Killer::Pty(Arc::new(Mutex::new(child.clone_killer()))),
+      pid,
+      self.kill_tree_on_exit,
+    );
+    let reader = pair.master.try_clone_reader().map_err(pty_error)?;
+    let writer = pair.master.try_clone_writer().map_err(pty_error)?;
+
+    commands().lock().unwrap().insert(pid, handle.clone());
+
+    let (tx, rx) = channel(1);
+    let guard = Arc::new(RwLock::new(()));
+    spawn_pty_reader(tx.clone(), guard.clone(), reader, self.encoding);
+
+    spawn(move || {
+      let event = match child.wait() {
+        Ok(status) => CommandEvent::Terminated(TerminatedPayload {
+          code: Some(status.exit_code() as i32),
+          signal: None,
+        }),
+        Err(e) => CommandEvent::Error(e.to_string()),
+      };
+      // ConPTY only closes the output once the pseudo console is closed,
+      // so the reader can't be awaited on Windows
+      #[cfg(not(windows))]
+      let _l = guard.write().unwrap();
+      #[cfg(windows)]
+      drop(guard);
+      remove_child(pid);
+      let _ = block_on_task(async move { tx.send(event).await });
+    });
+
+    Ok((
+      rx,
+      CommandChild {
+        handle,
+        pid,
+        stdin: ChildStdin::new(writer),
+        pty: Some(Arc::new(Mutex::new(pair.master))),
       },
     ))
   }
@@ -426,6 +779,44 @@ fn spawn_pipe_reader<F: Fn(String) -> CommandEvent + Send + Copy + 'static>(
   });
 }
 
+/// Reads the terminal output in chunks, decoding multi-byte characters split between reads.
+#[cfg(feature = "process-pty")]
+fn spawn_pty_reader(
+  tx: Sender<CommandEvent>,
+  guard: Arc<RwLock<()>>,
+  mut reader: Box<dyn std::io::Read + Send>,
+  character_encoding: Option<&'static Encoding>,
+) {
+  spawn(move || {
+    let _lock = guard.read().unwrap();
+    let mut decoder = character_encoding
+      .unwrap_or(encoding_rs::UTF_8)
+      .new_decoder();
+
+    let mut buf = [0; 4096];
+    loop {
+      let (n, last) = match reader.read(&mut buf) {
+        Ok(0) => (0, true),
+        Ok(n) => (n, false),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+        // reading fails with EIO on Linux when the terminal is closed
+        Err(_) => (0, true),
+      };
+      let mut output = String::with_capacity(decoder.max_utf8_buffer_length(n).unwrap_or_default());
+      let _ = decoder.decode_to_string(&buf[..n], &mut output, last);
+      if !output.is_empty() {
+        let tx_ = tx.clone();
+        block_on_task(async move {
+          let _ = tx_.send(CommandEvent::Stdout(output)).await;
+        });
+      }
+      if last {
+        break;
+      }
+    }
+  });
+}
+
 // tests for the commands functions.
 #[cfg(test)]
 mod test {
@@ -475,4 +866,78 @@ mod test {
       }
     });
   }
+
+  #[cfg(not(windows))]
+  #[test]
+  fn test_cmd_kill_tree() {
+    // the background `sleep` inherits stdout, so the events end only once it is killed too
+    let cmd = Command::new("sh")
+      .args(["-c", "sleep 30 & echo started; wait"])
+      .kill_tree_on_exit();
+    let (mut rx, child) = cmd.spawn().unwrap();
+
+    crate::async_runtime::block_on(async move {
+      let mut child = Some(child);
+      let mut signal = None;
+      while let Some(event) = rx.recv().await {
+        match event {
+          CommandEvent::Stdout(line) => {
+            assert_eq!(line, "started");
+            child.take().unwrap().kill_tree().unwrap();
+          }
+          CommandEvent::Terminated(payload) => signal = payload.signal,
+          _ => {}
+        }
+      }
+      assert_eq!(signal, Some(9));
+    });
+  }
+
+  #[cfg(not(windows))]
+  #[test]
+  fn test_cmd_stdin() {
+    let (mut rx, child) = Command::new("cat").spawn().unwrap();
+    let mut stdin = child.stdin();
+    stdin.write_all(b"from the stdin handle\n").unwrap();
+    // closing the stdin ends `cat`
+    child.close_stdin();
+    assert!(stdin.write_all(b"closed").is_err());
+
+    crate::async_runtime::block_on(async move {
+      let mut output = Vec::new();
+      let mut code = None;
+      while let Some(event) = rx.recv().await {
+        match event {
+          CommandEvent::Stdout(line) => output.push(line),
+          CommandEvent::Terminated(payload) => code = payload.code,
+          _ => {}
+        }
+      }
+      assert_eq!(output, vec!["from the stdin handle".to_string()]);
+      assert_eq!(code, Some(0));
+    });
+  }
+
+  #[cfg(all(not(windows), feature = "process-pty"))]
+  #[test]
+  fn test_cmd_pty() {
+    let cmd = Command::new("sh")
+      .args(["-c", "test -t 0 && echo terminal"])
+      .pty(PtySize { rows: 24, cols: 80 });
+    let (mut rx, _child) = cmd.spawn().unwrap();
+
+    crate::async_runtime::block_on(async move {
+      let mut output = String::new();
+      let mut code = None;
+      while let Some(event) = rx.recv().await {
+        match event {
+          CommandEvent::Stdout(chunk) => output.push_str(&chunk),
+          CommandEvent::Terminated(payload) => code = payload.code,
+          _ => {}
+        }
+      }
+      assert!(output.contains("terminal"));
+      assert_eq!(code, Some(0));
+    });
+  }
 }
@@ -0,0 +1,359 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::current_binary;
+use crate::Env;
+
+use std::{
+  ffi::OsString,
+  path::Path,
+  process::{exit, Command},
+};
+
+/// The options of [`restart_with`] and [`relaunch`].
+#[derive(Debug, Clone, Default)]
+pub struct RestartOptions {
+  args: Option<Vec<OsString>>,
+  envs: Vec<(OsString, OsString)>,
+  elevated: bool,
+  wait_for_exit: bool,
+}
+
+impl RestartOptions {
+  /// Creates the default options, launching the app with the arguments of the current process.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the arguments of the new process, replacing the arguments of the current process.
+  #[must_use]
+  pub fn args<I, S>(mut self, args: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+  {
+    self
+      .args
+      .replace(args.into_iter().map(Into::into).collect());
+    self
+  }
+
+  /// Sets an environment variable on the new process.
+  #[must_use]
+  pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+    self.envs.push((key.into(), value.into()));
+    self
+  }
+
+  /// Launches the new process with administrator privileges, prompting the user for them.
+  ///
+  /// The app is not notified if the user denies the privileges.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Launched with the `runas` verb. The environment variables are not set on the elevated process.
+  /// - **macOS:** Launched with `osascript`, as the root user.
+  /// - **Linux:** Launched with `pkexec`, which must be installed.
+  #[must_use]
+  pub fn elevated(mut self, elevated: bool) -> Self {
+    self.elevated = elevated;
+    self
+  }
+
+  /// Waits for the current process to exit before launching the new one,
+  /// so the new process does not race with the shutdown of the current one, e.g. for files locked by it after an update.
+  #[must_use]
+  pub fn wait_for_exit(mut self, wait_for_exit: bool) -> Self {
+    self.wait_for_exit = wait_for_exit;
+    self
+  }
+}
+
+/// Launches a new instance of the currently running binary, without exiting the current process.
+///
+/// With [`RestartOptions::wait_for_exit`], the new instance is launched once the current process exits.
+///
+/// See [`current_binary`] for platform specific behavior, and
+/// [`tauri_utils::platform::current_exe`] for possible security implications.
+pub fn relaunch(env: &Env, options: &RestartOptions) -> std::io::Result<()> {
+  let binary = current_binary(env)?;
+  let args = options
+    .args
+    .clone()
+    .unwrap_or_else(|| env.args.iter().map(Into::into).collect());
+  relaunch_command(&binary, &args, options, std::process::id())
+    .spawn()
+    .map(|_| ())
+}
+
+/// Restarts the currently running binary with the given options.
+///
+/// The current process exits once the new instance is launched,
+/// so this function only returns if the new instance could not be launched.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::{api::process::{restart_with, RestartOptions}, Manager};
+///
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let options = RestartOptions::new()
+///       .args(["--safe-mode"])
+///       .env("APP_RESTARTED", "1")
+///       .wait_for_exit(true);
+///     restart_with(&app.env(), &options)?;
+///     Ok(())
+///   });
+/// ```
+pub fn restart_with(env: &Env, options: &RestartOptions) -> std::io::Result<()> {
+  relaunch(env, options)?;
+  exit(0);
+}
+
+/// The command launching the binary, waiting for the process `pid` to exit if requested.
+#[cfg(windows)]
+fn relaunch_command(
+  binary: &Path,
+  args: &[OsString],
+  options: &RestartOptions,
+  pid: u32,
+) -> Command {
+  use std::os::windows::process::CommandExt;
+
+  const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+  let mut command = if options.elevated || options.wait_for_exit {
+    // PowerShell waits for the process and elevates the new one with `Start-Process -Verb RunAs`
+    let mut script = String::new();
+    if options.wait_for_exit {
+      script.push_str(&format!(
+        "Wait-Process -Id {} -ErrorAction SilentlyContinue; ",
+        pid
+      ));
+    }
+    script.push_str(&format!(
+      "Start-Process -FilePath {}",
+      powershell_quote(&binary.to_string_lossy())
+    ));
+    if !args.is_empty() {
+      let command_line = args
+        .iter()
+        .map(|arg| quote_arg(&arg.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ");
+      script.push_str(&format!(
+        " -ArgumentList {}",
+        powershell_quote(&command_line)
+      ));
+    }
+    if options.elevated {
+      script.push_str(" -Verb RunAs");
+    }
+
+    let powershell_path = std::env::var("SYSTEMROOT").map_or_else(
+      |_| "powershell.exe".to_string(),
+      |p| format!("{p}\\System32\\WindowsPowerShell\\v1.0\\powershell.exe"),
+    );
+    let mut command = Command::new(powershell_path);
+    command
+      .args(["-NoProfile", "-NonInteractive", "-WindowStyle", "Hidden"])
+      .arg("-Command")
+      .arg(script)
+      .creation_flags(CREATE_NO_WINDOW);
+    command
+  } else {
+    let mut command = Command::new(binary);
+    command.args(args);
+    command
+  };
+  command.envs(options.envs.iter().map(|(key, value)| (key, value)));
+  command
+}
+
+/// Quotes a string for PowerShell.
+#[cfg(windows)]
+fn powershell_quote(value: &str) -> String {
+  format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Quotes an argument of a Windows command line, following the rules of `CommandLineToArgvW`.
+#[cfg(windows)]
+fn quote_arg(arg: &str) -> String {
+  if !arg.is_empty() && !arg.contains(|c| matches!(c, ' ' | '\t' | '"')) {
+    return arg.into();
+  }
+  let mut quoted = String::from('"');
+  let mut backslashes = 0;
+  for c in arg.chars() {
+    if c == '\\' {
+      backslashes += 1;
+    } else {
+      if c == '"' {
+        quoted.extend(std::iter::repeat('\\').take(backslashes + 1));
+      }
+      backslashes = 0;
+    }
+    quoted.push(c);
+  }
+  quoted.extend(std::iter::repeat('\\').take(backslashes));
+  quoted.push('"');
+  quoted
+}
+
+/// Polls the process passed as `$0` until it exits, then runs the remaining arguments.
+#[cfg(unix)]
+const WAIT_FOR_EXIT_SCRIPT: &str =
+  r#"while kill -0 "$0" 2>/dev/null; do sleep 0.1; done; exec "$@""#;
+
+/// The command launching the binary, waiting for the process `pid` to exit if requested.
+#[cfg(unix)]
+fn relaunch_command(
+  binary: &Path,
+  args: &[OsString],
+  options: &RestartOptions,
+  pid: u32,
+) -> Command {
+  let launched = if options.elevated {
+    elevated_command(binary, args, &options.envs)
+  } else {
+    std::iter::once(binary.as_os_str().to_os_string())
+      .chain(args.iter().cloned())
+      .collect()
+  };
+
+  let mut command = if options.wait_for_exit {
+    let mut command = Command::new("/bin/sh");
+    command
+      .arg("-c")
+      .arg(WAIT_FOR_EXIT_SCRIPT)
+      .arg(pid.to_string())
+      .args(&launched);
+    command
+  } else {
+    let mut command = Command::new(&launched[0]);
+    command.args(&launched[1..]);
+    command
+  };
+  command.envs(options.envs.iter().map(|(key, value)| (key, value)));
+  command
+}
+
+/// The environment variables needed by the elevated process to connect to the desktop session.
+#[cfg(all(unix, not(target_os = "macos")))]
+const SESSION_ENV: &[&str] = &[
+  "DISPLAY",
+  "XAUTHORITY",
+  "WAYLAND_DISPLAY",
+  "XDG_RUNTIME_DIR",
+  "DBUS_SESSION_BUS_ADDRESS",
+];
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn elevated_command(
+  binary: &Path,
+  args: &[OsString],
+  envs: &[(OsString, OsString)],
+) -> Vec<OsString> {
+  // pkexec clears the environment, so the variables are set with `env`
+  let mut command: Vec<OsString> = vec!["pkexec".into(), "env".into()];
+  let session_envs = SESSION_ENV
+    .iter()
+    .filter_map(|key| Some((OsString::from(*key), std::env::var_os(key)?)));
+  for (key, value) in session_envs.chain(envs.iter().cloned()) {
+    let mut variable = key;
+    variable.push("=");
+    variable.push(value);
+    command.push(variable);
+  }
+  command.push(binary.into());
+  command.extend(args.iter().cloned());
+  command
+}
+
+#[cfg(target_os = "macos")]
+fn elevated_command(
+  binary: &Path,
+  args: &[OsString],
+  envs: &[(OsString, OsString)],
+) -> Vec<OsString> {
+  fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+  }
+
+  let mut shell = String::from("env");
+  for (key, value) in envs {
+    shell.push(' ');
+    shell.push_str(&shell_quote(&format!(
+      "{}={}",
+      key.to_string_lossy(),
+      value.to_string_lossy()
+    )));
+  }
+  shell.push(' ');
+  shell.push_str(&shell_quote(&binary.to_string_lossy()));
+  for arg in args {
+    shell.push(' ');
+    shell.push_str(&shell_quote(&arg.to_string_lossy()));
+  }
+  // `do shell script` waits for the command to exit unless its output is redirected
+  shell.push_str(" > /dev/null 2>&1 &");
+
+  let script = format!(
+    "do shell script \"{}\" with administrator privileges",
+    shell.replace('\\', "\\\\").replace('"', "\\\"")
+  );
+  vec!["osascript".into(), "-e".into(), script.into()]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{relaunch_command, RestartOptions};
+  use std::{
+    ffi::{OsStr, OsString},
+    path::Path,
+  };
+
+  #[cfg(unix)]
+  #[test]
+  fn waits_for_exit() {
+    let args: Vec<OsString> = vec!["--restarted".into()];
+    let options = RestartOptions::new().env("UPDATED", "1");
+
+    let command = relaunch_command(Path::new("/opt/app/bin"), &args, &options, 42);
+    assert_eq!(command.get_program(), "/opt/app/bin");
+    assert_eq!(command.get_args().collect::<Vec<_>>(), ["--restarted"]);
+    assert_eq!(
+      command.get_envs().collect::<Vec<_>>(),
+      [(OsStr::new("UPDATED"), Some(OsStr::new("1")))]
+    );
+
+    let options = options.wait_for_exit(true);
+    let command = relaunch_command(Path::new("/opt/app/bin"), &args, &options, 42);
+    assert_eq!(command.get_program(), "/bin/sh");
+    assert_eq!(
+      command.get_args().skip(2).collect::<Vec<_>>(),
+      ["42", "/opt/app/bin", "--restarted"]
+    );
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn quotes_windows_arguments() {
+    use super::quote_arg;
+
+    assert_eq!(quote_arg("--flag"), "--flag");
+    assert_eq!(quote_arg(""), r#""""#);
+    assert_eq!(quote_arg(r"C:\Program Files\"), r#""C:\Program Files\\""#);
+    assert_eq!(quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+
+    let args: Vec<OsString> = vec!["--name".into(), "it's me".into()];
+    let options = RestartOptions::new().elevated(true).wait_for_exit(true);
+    let command = relaunch_command(Path::new(r"C:\app.exe"), &args, &options, 42);
+    assert_eq!(
+      command.get_args().last().unwrap(),
+      r#"Wait-Process -Id 42 -ErrorAction SilentlyContinue; Start-Process -FilePath 'C:\app.exe' -ArgumentList '--name "it''s me"' -Verb RunAs"#
+    );
+  }
+}
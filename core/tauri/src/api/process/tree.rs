@@ -0,0 +1,181 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Tracking of the descendants of a child process, so they can be killed with it.
+//!
+//! On Unix the child is the leader of a new process group, inherited by its descendants.
+//! On Windows the child is spawned suspended and assigned to a job object before it runs,
+//! so every process it creates is part of the job.
+
+use std::io::Result;
+
+#[cfg(windows)]
+use windows::{
+  core::PCWSTR,
+  Win32::{
+    Foundation::{CloseHandle, HANDLE},
+    System::{
+      Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+      },
+      JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicAccountingInformation,
+        JobObjectExtendedLimitInformation, QueryInformationJobObject, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+      },
+      Threading::{
+        OpenProcess, OpenThread, ResumeThread, CREATE_SUSPENDED, PROCESS_SET_QUOTA,
+        PROCESS_TERMINATE, THREAD_SUSPEND_RESUME,
+      },
+    },
+  },
+};
+
+/// Makes the command spawn its process as the leader of a new process group.
+#[cfg(unix)]
+pub(super) fn set_process_group(command: &mut std::process::Command) {
+  use std::os::unix::process::CommandExt;
+  // SAFETY: `setpgid` is async-signal-safe
+  unsafe {
+    command.pre_exec(|| {
+      if libc::setpgid(0, 0) == 0 {
+        Ok(())
+      } else {
+        Err(std::io::Error::last_os_error())
+      }
+    });
+  }
+}
+
+/// The creation flag spawning the process suspended, so it can be assigned to the job before it runs,
+/// see [`resume`].
+#[cfg(windows)]
+pub(super) const CREATE_SUSPENDED_FLAG: u32 = CREATE_SUSPENDED.0;
+
+/// Resumes the threads of a process spawned with [`CREATE_SUSPENDED_FLAG`].
+#[cfg(windows)]
+pub(super) fn resume(pid: u32) -> Result<()> {
+  unsafe {
+    let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0)?;
+    let mut entry = THREADENTRY32 {
+      dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+      ..Default::default()
+    };
+    let mut more = Thread32First(snapshot, &mut entry).as_bool();
+    while more {
+      if entry.th32OwnerProcessID == pid {
+        if let Ok(thread) = OpenThread(THREAD_SUSPEND_RESUME, false, entry.th32ThreadID) {
+          ResumeThread(thread);
+          CloseHandle(thread);
+        }
+      }
+      more = Thread32Next(snapshot, &mut entry).as_bool();
+    }
+    CloseHandle(snapshot);
+  }
+  Ok(())
+}
+
+/// The process tree of a child process.
+#[derive(Debug)]
+pub(super) struct ProcessTree {
+  #[cfg(unix)]
+  pgid: libc::pid_t,
+  #[cfg(windows)]
+  job: HANDLE,
+}
+
+// SAFETY: the job handle is only used to query and terminate the job and closed on drop
+#[cfg(windows)]
+unsafe impl Send for ProcessTree {}
+#[cfg(windows)]
+unsafe impl Sync for ProcessTree {}
+
+impl ProcessTree {
+  /// Tracks the tree of a child process which is the leader of its process group,
+  /// either spawned after [`set_process_group`] or in a new session.
+  #[cfg(unix)]
+  pub fn new(pid: u32) -> Result<Self> {
+    Ok(Self {
+      pgid: pid as libc::pid_t,
+    })
+  }
+
+  /// Tracks the tree of a child process, assigning it to a new job object killing its processes when it is closed,
+  /// which happens when the app exits, even if it crashes.
+  ///
+  /// Processes it created before being assigned are not part of the job, see [`CREATE_SUSPENDED_FLAG`].
+  #[cfg(windows)]
+  pub fn new(pid: u32) -> Result<Self> {
+    unsafe {
+      let job = CreateJobObjectW(std::ptr::null(), PCWSTR::null())?;
+      let tree = Self { job };
+      let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+      limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+      if !SetInformationJobObject(
+        job,
+        JobObjectExtendedLimitInformation,
+        &limits as *const _ as *const std::ffi::c_void,
+        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+      )
+      .as_bool()
+      {
+        return Err(std::io::Error::last_os_error());
+      }
+      let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, false, pid)?;
+      let assigned = AssignProcessToJobObject(job, process).as_bool();
+      CloseHandle(process);
+      if assigned {
+        Ok(tree)
+      } else {
+        Err(std::io::Error::last_os_error())
+      }
+    }
+  }
+
+  /// Whether processes of the tree are still running.
+  ///
+  /// On Unix, the process group could be reused once all its processes exited, so it is only killed while it has members.
+  pub fn is_alive(&self) -> bool {
+    #[cfg(unix)]
+    let alive = unsafe { libc::killpg(self.pgid, 0) == 0 };
+    #[cfg(windows)]
+    let alive = unsafe {
+      let mut info = JOBOBJECT_BASIC_ACCOUNTING_INFORMATION::default();
+      QueryInformationJobObject(
+        self.job,
+        JobObjectBasicAccountingInformation,
+        &mut info as *mut _ as *mut std::ffi::c_void,
+        std::mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+        std::ptr::null_mut(),
+      )
+      .as_bool()
+        && info.ActiveProcesses > 0
+    };
+    alive
+  }
+
+  /// Kills all the processes of the tree.
+  pub fn kill(&self) -> Result<()> {
+    #[cfg(unix)]
+    let killed = unsafe { libc::killpg(self.pgid, libc::SIGKILL) == 0 };
+    #[cfg(windows)]
+    let killed = unsafe { TerminateJobObject(self.job, 1).as_bool() };
+    if killed {
+      Ok(())
+    } else {
+      Err(std::io::Error::last_os_error())
+    }
+  }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessTree {
+  fn drop(&mut self) {
+    unsafe {
+      CloseHandle(self.job);
+    }
+  }
+}
@@ -0,0 +1,691 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Serial ports and USB devices.
+//!
+//! Lists the serial ports and the USB devices connected to the computer, opens serial ports
+//! and watches the devices being connected and disconnected.
+
+use std::{
+  io::{ErrorKind, Read, Write},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
+
+use rusb::UsbContext;
+
+use serde::{Deserialize, Serialize};
+
+/// The information of a USB device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UsbInfo {
+  /// The vendor identifier.
+  pub vendor_id: u16,
+  /// The product identifier.
+  pub product_id: u16,
+  /// The serial number, if the device reports one.
+  pub serial_number: Option<String>,
+  /// The manufacturer name, if the device reports one.
+  pub manufacturer: Option<String>,
+  /// The product name, if the device reports one.
+  pub product: Option<String>,
+}
+
+impl UsbInfo {
+  /// The `<vendor id>:<product id>` identifier of the device, as lowercase hexadecimal numbers.
+  pub fn id(&self) -> String {
+    format!("{:04x}:{:04x}", self.vendor_id, self.product_id)
+  }
+}
+
+/// A serial port.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SerialPortInfo {
+  /// The path of the port, e.g. `/dev/ttyUSB0` or `COM3`.
+  pub path: String,
+  /// The USB device of the port, when it is a USB serial adapter.
+  pub usb: Option<UsbInfo>,
+}
+
+impl SerialPortInfo {
+  /// The `<vendor id>:<product id>` identifier of the USB device of the port, as lowercase hexadecimal numbers.
+  pub fn usb_id(&self) -> Option<String> {
+    self.usb.as_ref().map(UsbInfo::id)
+  }
+}
+
+/// A USB device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UsbDevice {
+  /// The number of the bus the device is connected to.
+  pub bus_number: u8,
+  /// The address of the device on its bus.
+  pub address: u8,
+  /// The information of the device.
+  #[serde(flatten)]
+  pub info: UsbInfo,
+}
+
+/// A device delivered by [`watch_devices`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum Device {
+  /// A serial port.
+  SerialPort(SerialPortInfo),
+  /// A USB device.
+  Usb(UsbDevice),
+}
+
+impl Device {
+  /// Whether both values describe the same connected device.
+  fn is_same(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::SerialPort(a), Self::SerialPort(b)) => a.path == b.path,
+      (Self::Usb(a), Self::Usb(b)) => {
+        a.bus_number == b.bus_number
+          && a.address == b.address
+          && a.info.vendor_id == b.info.vendor_id
+          && a.info.product_id == b.info.product_id
+      }
+      _ => false,
+    }
+  }
+}
+
+/// A change of the connected devices, delivered by [`watch_devices`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "device", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum DeviceEvent {
+  /// The device was connected.
+  Connected(Device),
+  /// The device was disconnected.
+  Disconnected(Device),
+}
+
+/// Returns the serial ports.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::serial::ports;
+///
+/// for port in ports().unwrap() {
+///   println!("{} ({:?})", port.path, port.usb_id());
+/// }
+/// ```
+pub fn ports() -> crate::api::Result<Vec<SerialPortInfo>> {
+  let ports = serialport::available_ports()?
+    .into_iter()
+    .map(|port| SerialPortInfo {
+      path: port.port_name,
+      usb: match port.port_type {
+        serialport::SerialPortType::UsbPort(usb) => Some(UsbInfo {
+          vendor_id: usb.vid,
+          product_id: usb.pid,
+          serial_number: usb.serial_number,
+          manufacturer: usb.manufacturer,
+          product: usb.product,
+        }),
+        _ => None,
+      },
+    })
+    .collect();
+  Ok(ports)
+}
+
+/// Returns the USB devices.
+///
+/// The devices are not opened, so the serial number, manufacturer and product names are only read on Linux,
+/// where the kernel exposes them on sysfs.
+pub fn usb_devices() -> crate::api::Result<Vec<UsbDevice>> {
+  let mut devices = Vec::new();
+  for device in rusb::devices()?.iter() {
+    let descriptor = match device.device_descriptor() {
+      Ok(descriptor) => descriptor,
+      Err(_) => continue,
+    };
+    let strings = usb_strings(&device);
+    devices.push(UsbDevice {
+      bus_number: device.bus_number(),
+      address: device.address(),
+      info: UsbInfo {
+        vendor_id: descriptor.vendor_id(),
+        product_id: descriptor.product_id(),
+        serial_number: strings.serial_number,
+        manufacturer: strings.manufacturer,
+        product: strings.product,
+      },
+    });
+  }
+  Ok(devices)
+}
+
+#[derive(Default)]
+struct UsbStrings {
+  serial_number: Option<String>,
+  manufacturer: Option<String>,
+  product: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+fn usb_strings<T: UsbContext>(device: &rusb::Device<T>) -> UsbStrings {
+  // e.g. `/sys/bus/usb/devices/1-2.3` for the device on the port 3 of the hub on the port 2 of the bus 1
+  let ports = match device.port_numbers() {
+    Ok(ports) if !ports.is_empty() => ports,
+    _ => return Default::default(),
+  };
+  let dir = std::path::PathBuf::from(format!(
+    "/sys/bus/usb/devices/{}-{}",
+    device.bus_number(),
+    ports
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>()
+      .join(".")
+  ));
+  let read = |name: &str| {
+    std::fs::read_to_string(dir.join(name))
+      .ok()
+      .map(|s| s.trim().to_string())
+      .filter(|s| !s.is_empty())
+  };
+  UsbStrings {
+    serial_number: read("serial"),
+    manufacturer: read("manufacturer"),
+    product: read("product"),
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn usb_strings<T: UsbContext>(_device: &rusb::Device<T>) -> UsbStrings {
+  Default::default()
+}
+
+fn devices() -> Vec<Device> {
+  let ports = ports()
+    .unwrap_or_default()
+    .into_iter()
+    .map(Device::SerialPort);
+  let usb = usb_devices()
+    .unwrap_or_default()
+    .into_iter()
+    .map(Device::Usb);
+  ports.chain(usb).collect()
+}
+
+/// The delay after a hotplug event to check the devices again, since a serial port is only created
+/// once the driver of its USB device is loaded.
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// The interval of the device checks on the platforms without hotplug events.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watches the connected devices until it is dropped, see [`watch_devices`].
+pub struct DeviceWatcher {
+  stop: Arc<AtomicBool>,
+  context: rusb::Context,
+}
+
+impl std::fmt::Debug for DeviceWatcher {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("DeviceWatcher").finish()
+  }
+}
+
+impl Drop for DeviceWatcher {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    self.context.interrupt_handle_events();
+  }
+}
+
+/// Marks the devices as changed on the USB hotplug events.
+struct HotplugListener(Arc<AtomicBool>);
+
+impl<T: UsbContext> rusb::Hotplug<T> for HotplugListener {
+  fn device_arrived(&mut self, _device: rusb::Device<T>) {
+    self.0.store(true, Ordering::Relaxed);
+  }
+
+  fn device_left(&mut self, _device: rusb::Device<T>) {
+    self.0.store(true, Ordering::Relaxed);
+  }
+}
+
+/// Calls the handler when a serial port or a USB device is connected or disconnected, until the watcher is dropped.
+///
+/// The devices are checked on the USB hotplug events, so serial ports that are not USB adapters are only reported
+/// along with the changes of the USB devices.
+///
+/// # Platform-specific
+///
+/// - **Windows:** libusb has no hotplug events, so the devices are checked every second.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::serial::{watch_devices, Device, DeviceEvent};
+///
+/// let watcher = watch_devices(|event| match event {
+///   DeviceEvent::Connected(Device::SerialPort(port)) => println!("connected {}", port.path),
+///   DeviceEvent::Disconnected(Device::SerialPort(port)) => println!("disconnected {}", port.path),
+///   _ => (),
+/// })
+/// .unwrap();
+/// ```
+pub fn watch_devices<F: Fn(DeviceEvent) + Send + 'static>(
+  handler: F,
+) -> crate::api::Result<DeviceWatcher> {
+  let context = rusb::Context::new()?;
+  let hotplug = rusb::has_hotplug();
+  let changed = Arc::new(AtomicBool::new(false));
+  let registration = if hotplug {
+    Some(
+      rusb::HotplugBuilder::new()
+        .enumerate(false)
+        .register(context.clone(), Box::new(HotplugListener(changed.clone())))?,
+    )
+  } else {
+    None
+  };
+  let stop = Arc::new(AtomicBool::new(false));
+
+  let stop_ = stop.clone();
+  let context_ = context.clone();
+  std::thread::spawn(move || {
+    let _registration = registration;
+    let mut previous = devices();
+    let mut settling = false;
+    while !stop_.load(Ordering::Relaxed) {
+      let timeout = if settling {
+        Some(SETTLE_DELAY)
+      } else if hotplug {
+        None
+      } else {
+        Some(POLL_INTERVAL)
+      };
+      if context_.handle_events(timeout).is_err() || stop_.load(Ordering::Relaxed) {
+        break;
+      }
+      let changed = changed.swap(false, Ordering::Relaxed);
+      if changed || settling || !hotplug {
+        let current = devices();
+        for event in diff(&previous, &current) {
+          handler(event);
+        }
+        previous = current;
+      }
+      settling = changed;
+    }
+  });
+
+  Ok(DeviceWatcher { stop, context })
+}
+
+fn diff(previous: &[Device], current: &[Device]) -> Vec<DeviceEvent> {
+  let disconnected = previous
+    .iter()
+    .filter(|d| !current.iter().any(|c| c.is_same(d)))
+    .cloned()
+    .map(DeviceEvent::Disconnected);
+  let connected = current
+    .iter()
+    .filter(|d| !previous.iter().any(|p| p.is_same(d)))
+    .cloned()
+    .map(DeviceEvent::Connected);
+  disconnected.chain(connected).collect()
+}
+
+/// The number of data bits of each character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataBits {
+  /// 5 data bits.
+  Five,
+  /// 6 data bits.
+  Six,
+  /// 7 data bits.
+  Seven,
+  /// 8 data bits.
+  Eight,
+}
+
+impl From<DataBits> for serialport::DataBits {
+  fn from(data_bits: DataBits) -> Self {
+    match data_bits {
+      DataBits::Five => Self::Five,
+      DataBits::Six => Self::Six,
+      DataBits::Seven => Self::Seven,
+      DataBits::Eight => Self::Eight,
+    }
+  }
+}
+
+/// The parity checking mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Parity {
+  /// No parity bit.
+  None,
+  /// Odd parity.
+  Odd,
+  /// Even parity.
+  Even,
+}
+
+impl From<Parity> for serialport::Parity {
+  fn from(parity: Parity) -> Self {
+    match parity {
+      Parity::None => Self::None,
+      Parity::Odd => Self::Odd,
+      Parity::Even => Self::Even,
+    }
+  }
+}
+
+/// The number of stop bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StopBits {
+  /// One stop bit.
+  One,
+  /// Two stop bits.
+  Two,
+}
+
+impl From<StopBits> for serialport::StopBits {
+  fn from(stop_bits: StopBits) -> Self {
+    match stop_bits {
+      StopBits::One => Self::One,
+      StopBits::Two => Self::Two,
+    }
+  }
+}
+
+/// The flow control mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlowControl {
+  /// No flow control.
+  None,
+  /// XON/XOFF flow control.
+  Software,
+  /// RTS/CTS flow control.
+  Hardware,
+}
+
+impl From<FlowControl> for serialport::FlowControl {
+  fn from(flow_control: FlowControl) -> Self {
+    match flow_control {
+      FlowControl::None => Self::None,
+      FlowControl::Software => Self::Software,
+      FlowControl::Hardware => Self::Hardware,
+    }
+  }
+}
+
+/// The options of [`SerialPort::open`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortOptions {
+  /// The baud rate. Defaults to 9600.
+  pub baud_rate: Option<u32>,
+  /// The number of data bits. Defaults to 8.
+  pub data_bits: Option<DataBits>,
+  /// The parity checking mode. Defaults to no parity.
+  pub parity: Option<Parity>,
+  /// The number of stop bits. Defaults to 1.
+  pub stop_bits: Option<StopBits>,
+  /// The flow control mode. Defaults to no flow control.
+  pub flow_control: Option<FlowControl>,
+  /// The timeout of the read and write operations in milliseconds. Defaults to 1 second.
+  pub timeout: Option<u64>,
+}
+
+impl PortOptions {
+  /// Creates the default options.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the baud rate.
+  #[must_use]
+  pub fn baud_rate(mut self, baud_rate: u32) -> Self {
+    self.baud_rate.replace(baud_rate);
+    self
+  }
+
+  /// Sets the number of data bits.
+  #[must_use]
+  pub fn data_bits(mut self, data_bits: DataBits) -> Self {
+    self.data_bits.replace(data_bits);
+    self
+  }
+
+  /// Sets the parity checking mode.
+  #[must_use]
+  pub fn parity(mut self, parity: Parity) -> Self {
+    self.parity.replace(parity);
+    self
+  }
+
+  /// Sets the number of stop bits.
+  #[must_use]
+  pub fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+    self.stop_bits.replace(stop_bits);
+    self
+  }
+
+  /// Sets the flow control mode.
+  #[must_use]
+  pub fn flow_control(mut self, flow_control: FlowControl) -> Self {
+    self.flow_control.replace(flow_control);
+    self
+  }
+
+  /// Sets the timeout of the read and write operations.
+  #[must_use]
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout.replace(timeout.as_millis() as u64);
+    self
+  }
+}
+
+/// The data read by [`SerialPort::listen`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "data")]
+#[non_exhaustive]
+pub enum SerialEvent {
+  /// Bytes received on the port.
+  Data(Vec<u8>),
+  /// The port was closed or disconnected. This is the last event.
+  Closed,
+}
+
+/// An open serial port.
+///
+/// Implements [`Read`] and [`Write`], with the timeout of [`PortOptions::timeout`].
+pub struct SerialPort {
+  path: String,
+  timeout: Duration,
+  port: Box<dyn serialport::SerialPort>,
+}
+
+impl std::fmt::Debug for SerialPort {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SerialPort")
+      .field("path", &self.path)
+      .finish()
+  }
+}
+
+impl SerialPort {
+  /// Opens the serial port at the given path.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::io::Write;
+  /// use tauri::api::serial::{PortOptions, SerialPort};
+  ///
+  /// let mut port = SerialPort::open("/dev/ttyUSB0", &PortOptions::new().baud_rate(115_200)).unwrap();
+  /// port.write_all(b"ping\n").unwrap();
+  /// ```
+  pub fn open(path: &str, options: &PortOptions) -> crate::api::Result<Self> {
+    let timeout = Duration::from_millis(options.timeout.unwrap_or(1000));
+    let port = serialport::new(path, options.baud_rate.unwrap_or(9600))
+      .data_bits(options.data_bits.unwrap_or(DataBits::Eight).into())
+      .parity(options.parity.unwrap_or(Parity::None).into())
+      .stop_bits(options.stop_bits.unwrap_or(StopBits::One).into())
+      .flow_control(options.flow_control.unwrap_or(FlowControl::None).into())
+      .timeout(timeout)
+      .open()?;
+    Ok(Self {
+      path: path.into(),
+      timeout,
+      port,
+    })
+  }
+
+  /// The path of the port.
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+
+  /// Creates another handle to the port, e.g. to read and write it from different threads.
+  pub fn try_clone(&self) -> crate::api::Result<Self> {
+    Ok(Self {
+      path: self.path.clone(),
+      timeout: self.timeout,
+      port: self.port.try_clone()?,
+    })
+  }
+
+  /// Reads the port on a new thread, calling the handler with the received bytes until the listener is dropped
+  /// or the port is disconnected.
+  ///
+  /// The listener stops at most after the timeout of the port.
+  pub fn listen<F: Fn(SerialEvent) + Send + 'static>(
+    &self,
+    handler: F,
+  ) -> crate::api::Result<PortListener> {
+    let mut reader = self.try_clone()?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_ = stop.clone();
+    std::thread::spawn(move || {
+      let mut buf = [0; 4096];
+      while !stop_.load(Ordering::Relaxed) {
+        match reader.read(&mut buf) {
+          Ok(0) => break,
+          Ok(n) => handler(SerialEvent::Data(buf[..n].to_vec())),
+          Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::Interrupted) => (),
+          Err(_) => break,
+        }
+      }
+      handler(SerialEvent::Closed);
+    });
+    Ok(PortListener { stop })
+  }
+}
+
+impl Read for SerialPort {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    self.port.read(buf)
+  }
+}
+
+impl Write for SerialPort {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.port.write(buf)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    self.port.flush()
+  }
+}
+
+/// Reads a serial port until it is dropped, see [`SerialPort::listen`].
+#[derive(Debug)]
+pub struct PortListener {
+  stop: Arc<AtomicBool>,
+}
+
+impl Drop for PortListener {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{diff, Device, DeviceEvent, SerialPortInfo, UsbDevice, UsbInfo};
+
+  fn usb_info(vendor_id: u16, product_id: u16, product: Option<&str>) -> UsbInfo {
+    UsbInfo {
+      vendor_id,
+      product_id,
+      serial_number: None,
+      manufacturer: None,
+      product: product.map(Into::into),
+    }
+  }
+
+  fn port(path: &str) -> Device {
+    Device::SerialPort(SerialPortInfo {
+      path: path.into(),
+      usb: Some(usb_info(0x2341, 0x0043, None)),
+    })
+  }
+
+  fn usb(address: u8, product: Option<&str>) -> Device {
+    Device::Usb(UsbDevice {
+      bus_number: 1,
+      address,
+      info: usb_info(0x2341, 0x0043, product),
+    })
+  }
+
+  #[test]
+  fn diffs_devices() {
+    let previous = vec![port("/dev/ttyACM0"), usb(4, Some("Uno")), usb(5, None)];
+    // the same device with strings that could not be read is not reported
+    let current = vec![port("/dev/ttyACM1"), usb(4, None), usb(6, None)];
+
+    assert_eq!(
+      diff(&previous, &current),
+      vec![
+        DeviceEvent::Disconnected(port("/dev/ttyACM0")),
+        DeviceEvent::Disconnected(usb(5, None)),
+        DeviceEvent::Connected(port("/dev/ttyACM1")),
+        DeviceEvent::Connected(usb(6, None)),
+      ]
+    );
+  }
+
+  #[test]
+  fn usb_id() {
+    let port = SerialPortInfo {
+      path: "COM3".into(),
+      usb: Some(usb_info(0x2341, 0x0043, None)),
+    };
+    assert_eq!(port.usb_id().as_deref(), Some("2341:0043"));
+
+    let port = SerialPortInfo {
+      path: "COM1".into(),
+      usb: None,
+    };
+    assert_eq!(port.usb_id(), None);
+  }
+}
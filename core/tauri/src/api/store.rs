@@ -0,0 +1,429 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Durable storage on SQLite databases in the app data directory.
+//!
+//! A [`Store`] is both a key-value store of JSON values and a SQL database with versioned [`Migration`]s.
+//! The queries run on the blocking thread pool of the [async runtime](crate::async_runtime).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use tauri::api::store::{Migration, Store};
+//! tauri::Builder::default()
+//!   .setup(|app| {
+//!     let store = Store::open(&app.config(), "notes")?;
+//!     tauri::async_runtime::block_on(async move {
+//!       store
+//!         .migrate(vec![Migration::new(
+//!           1,
+//!           "create the notes table",
+//!           "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL)",
+//!         )])
+//!         .await?;
+//!       store
+//!         .execute("INSERT INTO notes (body) VALUES (?)", vec!["hello".into()])
+//!         .await?;
+//!       store.set("last-opened", &"notes").await?;
+//!       Ok::<(), tauri::api::Error>(())
+//!     })?;
+//!     Ok(())
+//!   });
+//! ```
+
+use std::{
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+};
+
+use rusqlite::{
+  limits::Limit,
+  params, params_from_iter,
+  types::{Value as SqlValue, ValueRef},
+  Connection, OptionalExtension,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{api::path::app_data_dir, Config};
+
+/// The table of the key-value store.
+const KV_TABLE: &str = "tauri_kv";
+
+/// A versioned change of the database schema.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Migration {
+  /// The version of the schema after the migration, starting at `1`.
+  pub version: u32,
+  /// The description of the migration.
+  pub description: String,
+  /// The SQL statements of the migration.
+  pub sql: String,
+}
+
+impl Migration {
+  /// Creates a migration.
+  pub fn new(version: u32, description: impl Into<String>, sql: impl Into<String>) -> Self {
+    Self {
+      version,
+      description: description.into(),
+      sql: sql.into(),
+    }
+  }
+}
+
+/// The result of a statement that does not return rows.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ExecuteResult {
+  /// The number of rows changed.
+  pub rows_affected: usize,
+  /// The rowid of the last inserted row.
+  pub last_insert_id: i64,
+}
+
+/// A row returned by [`Store::select`], keyed by column name.
+pub type Row = Map<String, JsonValue>;
+
+/// A SQLite database in the app data directory.
+///
+/// Cloning the store shares its connection.
+#[derive(Debug, Clone)]
+pub struct Store {
+  path: PathBuf,
+  connection: Arc<Mutex<Connection>>,
+}
+
+/// Checks that the store name is a file name, so the database stays in the app data directory.
+fn validate_name(name: &str) -> crate::api::Result<()> {
+  let valid = !name.is_empty()
+    && name != "."
+    && name != ".."
+    && !name.contains(|c| matches!(c, '/' | '\\' | ':' | '\0'));
+  if valid {
+    Ok(())
+  } else {
+    Err(crate::api::Error::Path(format!(
+      "invalid store name `{}`",
+      name
+    )))
+  }
+}
+
+fn to_sql(value: JsonValue) -> SqlValue {
+  match value {
+    JsonValue::Null => SqlValue::Null,
+    JsonValue::Bool(b) => SqlValue::Integer(b.into()),
+    JsonValue::Number(n) => match n.as_i64() {
+      Some(i) => SqlValue::Integer(i),
+      None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+    },
+    JsonValue::String(s) => SqlValue::Text(s),
+    value => SqlValue::Text(value.to_string()),
+  }
+}
+
+fn to_json(value: ValueRef<'_>) -> JsonValue {
+  match value {
+    ValueRef::Null => JsonValue::Null,
+    ValueRef::Integer(i) => i.into(),
+    ValueRef::Real(f) => f.into(),
+    ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+    ValueRef::Blob(bytes) => bytes.to_vec().into(),
+  }
+}
+
+impl Store {
+  /// Opens the store with the given name, the `<name>.db` file in the [app data directory](crate::api::path::app_data_dir).
+  pub fn open(config: &Config, name: &str) -> crate::api::Result<Self> {
+    validate_name(name)?;
+    let dir = app_data_dir(config).ok_or_else(|| {
+      crate::api::Error::Path("unable to determine the app data directory".into())
+    })?;
+    std::fs::create_dir_all(&dir)?;
+    Self::open_path(dir.join(format!("{}.db", name)))
+  }
+
+  /// Opens the store on the given database file.
+  ///
+  /// `ATTACH DATABASE` is disabled, so the statements can only reach this database file.
+  pub fn open_path(path: impl Into<PathBuf>) -> crate::api::Result<Self> {
+    let path = path.into();
+    let connection = Connection::open(&path)?;
+    connection.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+    connection.execute(
+      &format!(
+        "CREATE TABLE IF NOT EXISTS {} (key TEXT PRIMARY KEY NOT NULL, value TEXT NOT NULL)",
+        KV_TABLE
+      ),
+      [],
+    )?;
+    Ok(Self {
+      path,
+      connection: Arc::new(Mutex::new(connection)),
+    })
+  }
+
+  /// The path of the database file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Runs the closure with the connection on the blocking thread pool.
+  async fn run<T, F>(&self, f: F) -> crate::api::Result<T>
+  where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> crate::api::Result<T> + Send + 'static,
+  {
+    let connection = self.connection.clone();
+    crate::async_runtime::spawn_blocking(move || f(&mut connection.lock().unwrap()))
+      .await
+      .map_err(|e| crate::api::Error::Store(e.to_string()))?
+  }
+
+  /// Applies the migrations newer than the schema version of the database, in a single transaction each.
+  ///
+  /// Returns the schema version after the migrations.
+  pub async fn migrate(&self, mut migrations: Vec<Migration>) -> crate::api::Result<u32> {
+    migrations.sort_by_key(|m| m.version);
+    self
+      .run(move |connection| {
+        let mut version: u32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for migration in migrations.into_iter().filter(|m| m.version > version) {
+          let transaction = connection.transaction()?;
+          transaction.execute_batch(&migration.sql).map_err(|e| {
+            crate::api::Error::Store(format!(
+              "migration {} ({}) failed: {}",
+              migration.version, migration.description, e
+            ))
+          })?;
+          transaction.pragma_update(None, "user_version", migration.version)?;
+          transaction.commit()?;
+          version = migration.version;
+        }
+        Ok(version)
+      })
+      .await
+  }
+
+  /// Executes a statement, binding the `?` parameters.
+  pub async fn execute(
+    &self,
+    sql: impl Into<String>,
+    params: Vec<JsonValue>,
+  ) -> crate::api::Result<ExecuteResult> {
+    let sql = sql.into();
+    self
+      .run(move |connection| {
+        let rows_affected =
+          connection.execute(&sql, params_from_iter(params.into_iter().map(to_sql)))?;
+        Ok(ExecuteResult {
+          rows_affected,
+          last_insert_id: connection.last_insert_rowid(),
+        })
+      })
+      .await
+  }
+
+  /// Runs a query, binding the `?` parameters, and returns the rows.
+  ///
+  /// Blobs are returned as arrays of bytes.
+  pub async fn select(
+    &self,
+    sql: impl Into<String>,
+    params: Vec<JsonValue>,
+  ) -> crate::api::Result<Vec<Row>> {
+    let sql = sql.into();
+    self
+      .run(move |connection| {
+        let mut statement = connection.prepare(&sql)?;
+        let columns: Vec<String> = statement
+          .column_names()
+          .into_iter()
+          .map(Into::into)
+          .collect();
+        let mut rows = statement.query(params_from_iter(params.into_iter().map(to_sql)))?;
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+          let mut values = Row::new();
+          for (index, column) in columns.iter().enumerate() {
+            values.insert(column.clone(), to_json(row.get_ref(index)?));
+          }
+          result.push(values);
+        }
+        Ok(result)
+      })
+      .await
+  }
+
+  /// Reads the value of the key, or `None` if it is not set.
+  pub async fn get<T: DeserializeOwned + Send + 'static>(
+    &self,
+    key: impl Into<String>,
+  ) -> crate::api::Result<Option<T>> {
+    let key = key.into();
+    self
+      .run(move |connection| {
+        let value: Option<String> = connection
+          .query_row(
+            &format!("SELECT value FROM {} WHERE key = ?", KV_TABLE),
+            params![key],
+            |row| row.get(0),
+          )
+          .optional()?;
+        value
+          .map(|value| serde_json::from_str(&value).map_err(Into::into))
+          .transpose()
+      })
+      .await
+  }
+
+  /// Sets the value of the key.
+  pub async fn set<T: Serialize + ?Sized>(
+    &self,
+    key: impl Into<String>,
+    value: &T,
+  ) -> crate::api::Result<()> {
+    let key = key.into();
+    let value = serde_json::to_string(value)?;
+    self
+      .run(move |connection| {
+        connection.execute(
+          &format!(
+            "INSERT INTO {} (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = ?2",
+            KV_TABLE
+          ),
+          params![key, value],
+        )?;
+        Ok(())
+      })
+      .await
+  }
+
+  /// Deletes the key, returning whether it was set.
+  pub async fn delete(&self, key: impl Into<String>) -> crate::api::Result<bool> {
+    let key = key.into();
+    self
+      .run(move |connection| {
+        let deleted = connection.execute(
+          &format!("DELETE FROM {} WHERE key = ?", KV_TABLE),
+          params![key],
+        )?;
+        Ok(deleted > 0)
+      })
+      .await
+  }
+
+  /// The keys of the key-value store, sorted.
+  pub async fn keys(&self) -> crate::api::Result<Vec<String>> {
+    self
+      .run(|connection| {
+        let mut statement =
+          connection.prepare(&format!("SELECT key FROM {} ORDER BY key", KV_TABLE))?;
+        let keys = statement
+          .query_map([], |row| row.get(0))?
+          .collect::<Result<Vec<String>, _>>()?;
+        Ok(keys)
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{validate_name, Migration, Store};
+  use crate::async_runtime::block_on;
+  use serde_json::json;
+
+  fn store() -> (tempfile::TempDir, Store) {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::open_path(dir.path().join("test.db")).unwrap();
+    (dir, store)
+  }
+
+  #[test]
+  fn validates_names() {
+    assert!(validate_name("settings").is_ok());
+    assert!(validate_name("cache.v2").is_ok());
+    for name in ["", ".", "..", "../secrets", "a/b", "a\\b", "C:db"] {
+      assert!(validate_name(name).is_err(), "{}", name);
+    }
+  }
+
+  #[test]
+  fn stores_values() {
+    let (_dir, store) = store();
+    block_on(async {
+      assert_eq!(store.get::<String>("theme").await.unwrap(), None);
+      store.set("theme", "dark").await.unwrap();
+      store.set("size", &json!({ "width": 800 })).await.unwrap();
+      store.set("theme", "light").await.unwrap();
+      assert_eq!(
+        store.get::<String>("theme").await.unwrap().as_deref(),
+        Some("light")
+      );
+      assert_eq!(store.keys().await.unwrap(), vec!["size", "theme"]);
+      assert!(store.delete("theme").await.unwrap());
+      assert!(!store.delete("theme").await.unwrap());
+    });
+  }
+
+  #[test]
+  fn denies_attach() {
+    let (dir, store) = store();
+    let other = dir.path().join("other.db");
+    let sql = format!("ATTACH DATABASE '{}' AS other", other.display());
+    assert!(block_on(store.execute(sql, Vec::new())).is_err());
+    assert!(!other.exists());
+  }
+
+  #[test]
+  fn migrates_and_queries() {
+    let (_dir, store) = store();
+    let migrations = vec![
+      Migration::new(
+        2,
+        "add the done column",
+        "ALTER TABLE todos ADD COLUMN done INTEGER NOT NULL DEFAULT 0",
+      ),
+      Migration::new(
+        1,
+        "create the todos table",
+        "CREATE TABLE todos (id INTEGER PRIMARY KEY, title TEXT NOT NULL)",
+      ),
+    ];
+    block_on(async {
+      assert_eq!(store.migrate(migrations.clone()).await.unwrap(), 2);
+      // already applied migrations are skipped
+      assert_eq!(store.migrate(migrations).await.unwrap(), 2);
+
+      let result = store
+        .execute(
+          "INSERT INTO todos (title, done) VALUES (?, ?)",
+          vec!["write tests".into(), true.into()],
+        )
+        .await
+        .unwrap();
+      assert_eq!(result.rows_affected, 1);
+      assert_eq!(result.last_insert_id, 1);
+
+      let rows = store
+        .select(
+          "SELECT id, title, done FROM todos WHERE done = ?",
+          vec![1.into()],
+        )
+        .await
+        .unwrap();
+      assert_eq!(
+        serde_json::Value::Array(rows.into_iter().map(Into::into).collect()),
+        json!([{ "id": 1, "title": "write tests", "done": 1 }])
+      );
+
+      let failing = vec![Migration::new(3, "broken", "CREATE TABLE")];
+      assert!(store.migrate(failing).await.is_err());
+      assert_eq!(store.migrate(Vec::new()).await.unwrap(), 2);
+    });
+  }
+}
@@ -0,0 +1,303 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Types and functions related to WebSocket connections.
+//!
+//! The connections are made from Rust, so they are not subject to the CORS and mixed content rules of the webview.
+
+use std::collections::HashMap;
+
+use futures_util::{
+  stream::{SplitSink, SplitStream},
+  SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::{
+  connect_async_with_config,
+  tungstenite::{
+    self,
+    client::IntoClientRequest,
+    http::{header::HeaderName, HeaderValue},
+    protocol::{frame::coding::CloseCode, WebSocketConfig},
+  },
+  MaybeTlsStream, WebSocketStream,
+};
+use url::Url;
+
+type Stream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// The options of a WebSocket connection.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionConfig {
+  /// The maximum size of a message. Defaults to 64 MiB.
+  pub max_message_size: Option<usize>,
+  /// The maximum size of a single message frame. Defaults to 16 MiB.
+  pub max_frame_size: Option<usize>,
+  /// Additional headers of the handshake request, e.g. `Authorization`.
+  #[serde(default)]
+  pub headers: HashMap<String, String>,
+}
+
+impl ConnectionConfig {
+  /// Creates the default options.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the maximum size of a message.
+  #[must_use]
+  pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+    self.max_message_size.replace(max_message_size);
+    self
+  }
+
+  /// Sets the maximum size of a single message frame.
+  #[must_use]
+  pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+    self.max_frame_size.replace(max_frame_size);
+    self
+  }
+
+  /// Adds a header to the handshake request.
+  #[must_use]
+  pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    self.headers.insert(key.into(), value.into());
+    self
+  }
+}
+
+/// The close frame of a connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloseFrame {
+  /// The status code, see <https://www.rfc-editor.org/rfc/rfc6455#section-7.4>.
+  pub code: u16,
+  /// The reason.
+  pub reason: String,
+}
+
+/// Serializes the binary data as base64, which is much smaller than an array of numbers.
+mod base64_data {
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&base64::encode(data))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let data = String::deserialize(deserializer)?;
+    base64::decode(data).map_err(serde::de::Error::custom)
+  }
+}
+
+/// A WebSocket message.
+///
+/// The data of the binary, ping and pong messages is serialized as base64.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+#[non_exhaustive]
+pub enum Message {
+  /// A text message.
+  Text(String),
+  /// A binary message.
+  Binary(#[serde(with = "base64_data")] Vec<u8>),
+  /// A ping message.
+  Ping(#[serde(with = "base64_data")] Vec<u8>),
+  /// A pong message.
+  Pong(#[serde(with = "base64_data")] Vec<u8>),
+  /// A close message.
+  Close(Option<CloseFrame>),
+}
+
+impl From<Message> for tungstenite::Message {
+  fn from(message: Message) -> Self {
+    match message {
+      Message::Text(text) => Self::Text(text),
+      Message::Binary(data) => Self::Binary(data),
+      Message::Ping(data) => Self::Ping(data),
+      Message::Pong(data) => Self::Pong(data),
+      Message::Close(frame) => Self::Close(frame.map(|frame| tungstenite::protocol::CloseFrame {
+        code: CloseCode::from(frame.code),
+        reason: frame.reason.into(),
+      })),
+    }
+  }
+}
+
+impl Message {
+  /// Converts the message, returning `None` for raw frames, which are not returned when reading.
+  fn from_tungstenite(message: tungstenite::Message) -> Option<Self> {
+    match message {
+      tungstenite::Message::Text(text) => Some(Self::Text(text)),
+      tungstenite::Message::Binary(data) => Some(Self::Binary(data)),
+      tungstenite::Message::Ping(data) => Some(Self::Ping(data)),
+      tungstenite::Message::Pong(data) => Some(Self::Pong(data)),
+      tungstenite::Message::Close(frame) => Some(Self::Close(frame.map(|frame| CloseFrame {
+        code: frame.code.into(),
+        reason: frame.reason.into_owned(),
+      }))),
+      tungstenite::Message::Frame(_) => None,
+    }
+  }
+}
+
+/// A WebSocket connection.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::api::websocket::{ConnectionConfig, Message, WebSocket};
+/// tauri::async_runtime::block_on(async {
+///   let url = "wss://echo.websocket.events".parse().unwrap();
+///   let mut socket = WebSocket::connect(&url, ConnectionConfig::new()).await.unwrap();
+///   socket.send(Message::Text("hello".into())).await.unwrap();
+///   while let Some(message) = socket.receive().await.unwrap() {
+///     println!("{:?}", message);
+///   }
+/// });
+/// ```
+#[derive(Debug)]
+pub struct WebSocket(Stream);
+
+impl WebSocket {
+  /// Connects to the WebSocket server.
+  pub async fn connect(url: &Url, config: ConnectionConfig) -> crate::api::Result<Self> {
+    let mut request = url.as_str().into_client_request()?;
+    for (key, value) in config.headers {
+      let key = HeaderName::from_bytes(key.as_bytes())
+        .map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+      let value =
+        HeaderValue::from_str(&value).map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+      request.headers_mut().insert(key, value);
+    }
+    let mut websocket_config = WebSocketConfig::default();
+    if let Some(max_message_size) = config.max_message_size {
+      websocket_config.max_message_size.replace(max_message_size);
+    }
+    if let Some(max_frame_size) = config.max_frame_size {
+      websocket_config.max_frame_size.replace(max_frame_size);
+    }
+    let (stream, _) = connect_async_with_config(request, Some(websocket_config)).await?;
+    Ok(Self(stream))
+  }
+
+  /// Sends a message.
+  pub async fn send(&mut self, message: Message) -> crate::api::Result<()> {
+    self.0.send(message.into()).await.map_err(Into::into)
+  }
+
+  /// Reads the next message, or `None` if the connection is closed.
+  pub async fn receive(&mut self) -> crate::api::Result<Option<Message>> {
+    receive(&mut self.0).await
+  }
+
+  /// Closes the connection.
+  pub async fn close(&mut self) -> crate::api::Result<()> {
+    self.0.close(None).await.map_err(Into::into)
+  }
+
+  /// Splits the connection, so messages can be sent while another task reads them.
+  pub fn split(self) -> (WebSocketSender, WebSocketReceiver) {
+    let (sink, stream) = self.0.split();
+    (WebSocketSender(sink), WebSocketReceiver(stream))
+  }
+}
+
+async fn receive<S>(stream: &mut S) -> crate::api::Result<Option<Message>>
+where
+  S: futures_util::Stream<Item = Result<tungstenite::Message, tungstenite::Error>> + Unpin,
+{
+  while let Some(message) = stream.next().await {
+    match message {
+      Ok(message) => {
+        if let Some(message) = Message::from_tungstenite(message) {
+          return Ok(Some(message));
+        }
+      }
+      Err(tungstenite::Error::ConnectionClosed) => return Ok(None),
+      Err(e) => return Err(e.into()),
+    }
+  }
+  Ok(None)
+}
+
+/// The sending half of a [`WebSocket`].
+#[derive(Debug)]
+pub struct WebSocketSender(SplitSink<Stream, tungstenite::Message>);
+
+impl WebSocketSender {
+  /// Sends a message.
+  pub async fn send(&mut self, message: Message) -> crate::api::Result<()> {
+    self.0.send(message.into()).await.map_err(Into::into)
+  }
+
+  /// Closes the connection.
+  pub async fn close(&mut self) -> crate::api::Result<()> {
+    self
+      .0
+      .send(tungstenite::Message::Close(None))
+      .await
+      .map_err(Into::into)
+  }
+}
+
+/// The receiving half of a [`WebSocket`].
+#[derive(Debug)]
+pub struct WebSocketReceiver(SplitStream<Stream>);
+
+impl WebSocketReceiver {
+  /// Reads the next message, or `None` if the connection is closed.
+  pub async fn receive(&mut self) -> crate::api::Result<Option<Message>> {
+    receive(&mut self.0).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{CloseFrame, Message};
+  use tokio_tungstenite::tungstenite;
+
+  #[test]
+  fn converts_messages() {
+    let messages = vec![
+      Message::Text("hello".into()),
+      Message::Binary(vec![1, 2, 3]),
+      Message::Ping(vec![4]),
+      Message::Pong(vec![5]),
+      Message::Close(None),
+      Message::Close(Some(CloseFrame {
+        code: 1000,
+        reason: "bye".into(),
+      })),
+    ];
+    for message in messages {
+      let converted: tungstenite::Message = message.clone().into();
+      assert_eq!(Message::from_tungstenite(converted), Some(message));
+    }
+  }
+
+  #[test]
+  fn serializes_messages() {
+    assert_eq!(
+      serde_json::to_value(Message::Text("hello".into())).unwrap(),
+      serde_json::json!({ "type": "Text", "data": "hello" })
+    );
+    assert_eq!(
+      serde_json::to_value(Message::Binary(vec![1, 2, 3])).unwrap(),
+      serde_json::json!({ "type": "Binary", "data": "AQID" })
+    );
+    let message: Message = serde_json::from_str(r#"{ "type": "Ping", "data": "BA==" }"#).unwrap();
+    assert_eq!(message, Message::Ping(vec![4]));
+    let message: Message =
+      serde_json::from_str(r#"{ "type": "Close", "data": { "code": 1001, "reason": "away" } }"#)
+        .unwrap();
+    assert_eq!(
+      message,
+      Message::Close(Some(CloseFrame {
+        code: 1001,
+        reason: "away".into()
+      }))
+    );
+  }
+}
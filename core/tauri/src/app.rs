@@ -9,17 +9,20 @@ use crate::{
   api::ipc::CallbackFn,
   command::{CommandArg, CommandItem},
   hooks::{
-    window_invoke_responder, InvokeHandler, InvokeResponder, OnPageLoad, PageLoadPayload, SetupHook,
+    window_invoke_responder, AssetFallback, AsyncSetupHook, InvokeHandler, InvokeMetrics,
+    InvokeResponder, InvokeSerializer, IpcOriginValidator, IsolationValidator, OnAssetResponse,
+    OnInvokeMetrics, OnPageLoad, PageLoadPayload, SetupHook,
   },
   manager::{Asset, CustomProtocol, WindowManager},
   plugin::{Plugin, PluginStore},
+  protocol::{AsyncProtocolError, AsyncResponse},
   runtime::{
     http::{Request as HttpRequest, Response as HttpResponse},
     webview::WebviewAttributes,
     window::{PendingWindow, WindowEvent as RuntimeWindowEvent},
     ExitRequestedEventAction, RunEvent as RuntimeRunEvent,
   },
-  scope::FsScope,
+  scope::{CommandScope, FsScope, PluginPermissionScope},
   sealed::{ManagerBase, RuntimeOrDispatch},
   utils::config::Config,
   utils::{assets::Assets, resources::resource_relpath, Env},
@@ -34,13 +37,16 @@ use raw_window_handle::HasRawDisplayHandle;
 use tauri_macros::default_runtime;
 use tauri_runtime::window::{
   dpi::{PhysicalPosition, PhysicalSize},
-  FileDropEvent,
+  DropEvent, FileDropEvent,
 };
 use tauri_utils::PackageInfo;
+use url::Url;
 
 use std::{
   collections::HashMap,
+  future::Future,
   path::{Path, PathBuf},
+  pin::Pin,
   sync::{mpsc::Sender, Arc, Weak},
 };
 
@@ -51,6 +57,9 @@ use crate::runtime::RuntimeHandle;
 #[cfg(updater)]
 use crate::updater;
 
+#[cfg(feature = "isolation")]
+use crate::hooks::IsolationRequest;
+
 #[cfg(target_os = "macos")]
 use crate::ActivationPolicy;
 
@@ -58,6 +67,8 @@ pub(crate) type GlobalMenuEventListener<R> = Box<dyn Fn(WindowMenuEvent<R>) + Se
 pub(crate) type GlobalWindowEventListener<R> = Box<dyn Fn(GlobalWindowEvent<R>) + Send + Sync>;
 #[cfg(all(desktop, feature = "system-tray"))]
 type SystemTrayEventListener<R> = Box<dyn Fn(&AppHandle<R>, tray::SystemTrayEvent) + Send + Sync>;
+type AsyncStateInitializer<R> =
+  Box<dyn FnOnce(AppHandle<R>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
 
 /// Api exposed on the `ExitRequested` event.
 #[derive(Debug)]
@@ -117,6 +128,12 @@ pub enum WindowEvent {
   },
   /// An event associated with the file drop action.
   FileDrop(FileDropEvent),
+  /// Data other than files, such as text and URLs, has been dragged over or dropped on the webview.
+  ///
+  /// Only the MIME types accepted with [`crate::window::WindowBuilder::accept_drop_types`] are reported,
+  /// and plugins can refuse the events with [`crate::plugin::Builder::on_data_drop`].
+  /// They are reported by a script on the page, so their data must be treated like any other input from the webview.
+  Drop(DropEvent),
   /// The system window theme has changed. Only delivered if the window [`theme`](`crate::window::WindowBuilder#method.theme`) is `None`.
   ///
   /// Applications might wish to react to this to change the theme of the content of the window when the system changes the window theme.
@@ -145,6 +162,7 @@ impl From<RuntimeWindowEvent> for WindowEvent {
         new_inner_size,
       },
       RuntimeWindowEvent::FileDrop(event) => Self::FileDrop(event),
+      RuntimeWindowEvent::Drop(event) => Self::Drop(event),
       RuntimeWindowEvent::ThemeChanged(theme) => Self::ThemeChanged(theme),
     }
   }
@@ -184,6 +202,28 @@ pub enum RunEvent {
   #[cfg(updater)]
   #[cfg_attr(doc_cfg, doc(cfg(feature = "updater")))]
   Updater(crate::UpdaterEvent),
+  /// The configuration file changed while running `tauri dev`.
+  ///
+  /// The allowlist scopes and the configuration returned by [`AppHandle::config`] are already updated
+  /// when this event is emitted, so new windows use the reloaded window defaults.
+  /// Any other change requires restarting the application.
+  #[non_exhaustive]
+  ConfigChanged {
+    /// The reloaded configuration.
+    config: Arc<Config>,
+    /// The changed sections, such as `plugins` or `tauri > allowlist`.
+    sections: Vec<String>,
+  },
+  /// The resource usage measured periodically when [`Builder::metrics_interval`] is set.
+  #[cfg(feature = "process-metrics-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "process-metrics-api")))]
+  Metrics(crate::Metrics),
+  /// The network status of the system changed.
+  ///
+  /// The change is also emitted to the webviews as the [`crate::api::net::NETWORK_STATUS_EVENT`].
+  #[cfg(all(desktop, feature = "net-status-api"))]
+  #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "net-status-api"))))]
+  NetworkStatusChanged(crate::api::net::NetworkStatus),
 }
 
 impl From<EventLoopMessage> for RunEvent {
@@ -191,6 +231,13 @@ impl From<EventLoopMessage> for RunEvent {
     match event {
       #[cfg(updater)]
       EventLoopMessage::Updater(event) => RunEvent::Updater(event),
+      EventLoopMessage::ConfigChanged { config, sections } => {
+        RunEvent::ConfigChanged { config, sections }
+      }
+      #[cfg(feature = "process-metrics-api")]
+      EventLoopMessage::Metrics(metrics) => RunEvent::Metrics(metrics),
+      #[cfg(all(desktop, feature = "net-status-api"))]
+      EventLoopMessage::NetworkStatusChanged(status) => RunEvent::NetworkStatusChanged(status),
     }
   }
 }
@@ -377,7 +424,7 @@ pub struct AppHandle<R: Runtime> {
 }
 
 impl<R: Runtime> AppHandle<R> {
-  // currently only used on the updater
+  // currently only used on the updater and the dev configuration watcher
   #[allow(dead_code)]
   pub(crate) fn create_proxy(&self) -> R::EventLoopProxy {
     self.runtime_handle.create_proxy()
@@ -539,6 +586,15 @@ impl<R: Runtime> AppHandle<R> {
     crate::api::process::restart(&self.env());
   }
 
+  /// Restarts the app with the given options, performing cleanup on this application.
+  ///
+  /// See [`crate::api::process::restart_with`]. This function only returns if the new instance could not be launched.
+  pub fn restart_with(&self, options: &crate::api::process::RestartOptions) -> crate::Result<()> {
+    crate::api::process::relaunch(&self.env(), options)?;
+    self.cleanup_before_exit();
+    std::process::exit(0);
+  }
+
   /// Runs necessary cleanup tasks before exiting the process
   fn cleanup_before_exit(&self) {
     #[cfg(any(shell_execute, shell_sidecar))]
@@ -742,6 +798,66 @@ macro_rules! shared_app_impl {
         self.manager.package_info()
       }
 
+      /// Closes the splashscreen configured on `tauri > splashscreen` and shows the main window.
+      ///
+      /// The splashscreen is closed automatically when the main window finishes loading,
+      /// unless `autoClose` is disabled. Calling this more than once has no effect.
+      ///
+      /// # Examples
+      ///
+      /// ```no_run
+      /// tauri::Builder::default()
+      ///   .setup(|app| {
+      ///     let handle = app.handle();
+      ///     std::thread::spawn(move || {
+      ///       // load the data the main window needs
+      ///       handle.close_splashscreen().unwrap();
+      ///     });
+      ///     Ok(())
+      ///   });
+      /// ```
+      #[cfg(desktop)]
+      pub fn close_splashscreen(&self) -> crate::Result<()> {
+        crate::splashscreen::close(self)
+      }
+
+      /// Measures the CPU and memory usage of the application and its webview processes.
+      ///
+      /// The CPU usage is measured since the previous call, so the first measure is always `0`.
+      ///
+      /// # Examples
+      ///
+      /// ```no_run
+      /// tauri::Builder::default()
+      ///   .setup(|app| {
+      ///     let metrics = app.metrics()?;
+      ///     println!("{} bytes used by {} processes", metrics.memory, metrics.processes.len());
+      ///     Ok(())
+      ///   });
+      /// ```
+      #[cfg(feature = "process-metrics-api")]
+      #[cfg_attr(doc_cfg, doc(cfg(feature = "process-metrics-api")))]
+      pub fn metrics(&self) -> crate::Result<crate::Metrics> {
+        crate::metrics::collect(&self.manager)
+      }
+
+      /// The translations configured on `tauri > i18n`.
+      ///
+      /// # Examples
+      ///
+      /// ```rust,no_run
+      /// tauri::Builder::default()
+      ///   .setup(|app| {
+      ///     println!("{}", app.i18n().translate("greeting", &[("name", "Ana".into())]));
+      ///     Ok(())
+      ///   });
+      /// ```
+      #[cfg(feature = "i18n")]
+      #[cfg_attr(doc_cfg, doc(cfg(feature = "i18n")))]
+      pub fn i18n(&self) -> crate::State<'_, crate::I18n> {
+        self.state::<crate::I18n>()
+      }
+
       /// The application's asset resolver.
       pub fn asset_resolver(&self) -> AssetResolver<R> {
         AssetResolver {
@@ -749,6 +865,47 @@ macro_rules! shared_app_impl {
         }
       }
 
+      /// Registers a URI scheme protocol available to the webviews created after this call,
+      /// replacing any protocol previously registered with the same scheme.
+      ///
+      /// This allows plugins loaded after [`Builder::build`] to add their own schemes.
+      /// See [`Builder::register_uri_scheme_protocol`] for the arguments.
+      ///
+      /// ## Platform-specific
+      ///
+      /// The webviews that already exist cannot load the new scheme on any platform.
+      ///
+      /// # Examples
+      /// ```
+      /// use tauri::http::ResponseBuilder;
+      ///
+      /// tauri::Builder::default()
+      ///   .setup(|app| {
+      ///     app.register_uri_scheme_protocol("plugin-assets", |_app, _request| {
+      ///       ResponseBuilder::new().mimetype("text/plain").body(b"hello".to_vec())
+      ///     });
+      ///     Ok(())
+      ///   });
+      /// ```
+      pub fn register_uri_scheme_protocol<
+        N: Into<String>,
+        H: Fn(&AppHandle<R>, &HttpRequest) -> Result<HttpResponse, Box<dyn std::error::Error>>
+          + Send
+          + Sync
+          + 'static,
+      >(
+        &self,
+        uri_scheme: N,
+        protocol: H,
+      ) {
+        self.manager.register_uri_scheme_protocol(
+          uri_scheme.into(),
+          Arc::new(CustomProtocol {
+            protocol: Box::new(protocol),
+          }),
+        );
+      }
+
       /// Shows the application, but does not automatically focus it.
       #[cfg(target_os = "macos")]
       pub fn show(&self) -> crate::Result<()> {
@@ -966,9 +1123,33 @@ pub struct Builder<R: Runtime> {
   /// The script that initializes the `window.__TAURI_POST_MESSAGE__` function.
   invoke_initialization_script: String,
 
+  /// The serialization format of the invoke messages.
+  invoke_serializer: Option<Arc<dyn InvokeSerializer>>,
+
+  /// Validates the origin of the IPC messages.
+  ipc_origin_validator: Option<Box<IpcOriginValidator<R>>>,
+
+  /// Validates the invoke requests on the isolation application.
+  isolation_validator: Option<Box<IsolationValidator<R>>>,
+
+  /// Receives the metrics of the invoke requests.
+  on_invoke_metrics: Option<Box<OnInvokeMetrics<R>>>,
+
+  /// Modifies the asset responses.
+  on_asset_response: Option<Box<OnAssetResponse<R>>>,
+
+  /// Generates the responses for missing assets.
+  asset_fallback: Option<Box<AssetFallback<R>>>,
+
   /// The setup hook.
   setup: SetupHook<R>,
 
+  /// The asynchronous setup hook.
+  setup_async: Option<AsyncSetupHook<R>>,
+
+  /// The state initialized asynchronously.
+  async_state: Vec<AsyncStateInitializer<R>>,
+
   /// Page load hook.
   on_page_load: Box<OnPageLoad<R>>,
 
@@ -1008,6 +1189,14 @@ pub struct Builder<R: Runtime> {
   /// The updater configuration.
   #[cfg(updater)]
   updater_settings: UpdaterSettings,
+
+  /// The crash reporter.
+  #[cfg(all(desktop, feature = "crash-reporter"))]
+  crash_reporter: Option<crate::CrashReporter<R>>,
+
+  /// The interval of the resource usage measures.
+  #[cfg(feature = "process-metrics-api")]
+  metrics_interval: Option<std::time::Duration>,
 }
 
 impl<R: Runtime> Builder<R> {
@@ -1017,10 +1206,18 @@ impl<R: Runtime> Builder<R> {
       #[cfg(any(windows, target_os = "linux"))]
       runtime_any_thread: false,
       setup: Box::new(|_| Ok(())),
+      setup_async: None,
+      async_state: Vec::new(),
       invoke_handler: Box::new(|_| ()),
       invoke_responder: Arc::new(window_invoke_responder),
       invoke_initialization_script:
-        "Object.defineProperty(window, '__TAURI_POST_MESSAGE__', { value: (message) => window.ipc.postMessage(JSON.stringify(message)) })".into(),
+        "Object.defineProperty(window, '__TAURI_POST_MESSAGE__', { value: (message) => window.ipc.postMessage(window.__TAURI_IPC_SERIALIZER__ ? window.__TAURI_IPC_SERIALIZER__.serialize(message) : JSON.stringify(message)) })".into(),
+      invoke_serializer: None,
+      ipc_origin_validator: None,
+      isolation_validator: None,
+      on_invoke_metrics: None,
+      on_asset_response: None,
+      asset_fallback: None,
       on_page_load: Box::new(|_, _| ()),
       pending_windows: Default::default(),
       plugins: PluginStore::default(),
@@ -1036,6 +1233,10 @@ impl<R: Runtime> Builder<R> {
       system_tray_event_listeners: Vec::new(),
       #[cfg(updater)]
       updater_settings: Default::default(),
+      #[cfg(all(desktop, feature = "crash-reporter"))]
+      crash_reporter: None,
+      #[cfg(feature = "process-metrics-api")]
+      metrics_interval: None,
     }
   }
 
@@ -1091,6 +1292,158 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Defines the format used to exchange invoke requests and responses with the webview.
+  ///
+  /// By default the messages are exchanged as JSON.
+  /// Note that a custom [`Self::invoke_system`] must use `window.__TAURI_IPC_SERIALIZER__` to serialize its messages.
+  /// The serializer is ignored when the application uses the isolation pattern.
+  #[cfg_attr(
+    feature = "ipc-msgpack",
+    doc = r#"
+# Examples
+
+```
+tauri::Builder::default()
+  .invoke_serializer(tauri::MessagePackSerializer);
+```"#
+  )]
+  #[must_use]
+  pub fn invoke_serializer<S: InvokeSerializer>(mut self, serializer: S) -> Self {
+    self.invoke_serializer.replace(Arc::new(serializer));
+    self
+  }
+
+  /// Defines a callback that decides whether IPC messages from a webview are accepted.
+  ///
+  /// The callback receives the URL currently loaded by the webview and the window it belongs to.
+  /// Commands invoked by a rejected webview fail without being executed.
+  /// It is consulted before the [`tauri > security > commandScope`](crate::utils::config::SecurityConfig#structfield.command_scope) configuration.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .ipc_origin_validator(|url, _window| {
+  ///     url.scheme() == "tauri" || url.domain() == Some("tauri.localhost")
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn ipc_origin_validator<F>(mut self, validator: F) -> Self
+  where
+    F: Fn(&Url, &Window<R>) -> bool + Send + Sync + 'static,
+  {
+    self.ipc_origin_validator.replace(Box::new(validator));
+    self
+  }
+
+  /// Defines a callback that checks the invoke requests of the isolation pattern before they reach the core.
+  ///
+  /// The callback receives the command and its arguments once the request is decrypted,
+  /// so they are exactly the ones forwarded by the isolation application,
+  /// and the request is rejected when it returns `false`.
+  /// It complements the `window.__TAURI_ISOLATION_HOOK__` function, which runs on the isolation application.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .isolation_validator(|window, request| {
+  ///     window.label() == "main" || !request.command.starts_with("tauri:")
+  ///   });
+  /// ```
+  #[cfg(feature = "isolation")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "isolation")))]
+  #[must_use]
+  pub fn isolation_validator<F>(mut self, validator: F) -> Self
+  where
+    F: Fn(&Window<R>, &IsolationRequest<'_>) -> bool + Send + Sync + 'static,
+  {
+    self.isolation_validator.replace(Box::new(validator));
+    self
+  }
+
+  /// Defines a callback that receives the [`InvokeMetrics`] of every resolved invoke request,
+  /// so the slowest or largest IPC calls can be exported to a profiling backend.
+  ///
+  /// With the `ipc-tracing` feature, each request is also recorded as an `ipc.request` [`tracing`](https://docs.rs/tracing) span.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .on_invoke_metrics(|window, metrics| {
+  ///     if metrics.duration.as_millis() > 16 {
+  ///       println!("{} blocked {} for {:?}", metrics.command, window.label(), metrics.duration);
+  ///     }
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn on_invoke_metrics<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(&Window<R>, &InvokeMetrics) + Send + Sync + 'static,
+  {
+    self.on_invoke_metrics.replace(Box::new(handler));
+    self
+  }
+
+  /// Defines a callback that can modify the responses of the `tauri` and `asset` protocols
+  /// before they reach the webview, e.g. to inject a CSP nonce in HTML files or set headers per path.
+  ///
+  /// It runs for every window, before the window's [`on_web_resource_request`](crate::window::WindowBuilder#method.on_web_resource_request) handler.
+  /// When it is set, the embedded assets are not served Brotli-compressed so the callback always receives the plain body.
+  ///
+  /// # Known limitations
+  ///
+  /// On Linux, the response status and headers are ignored unless the `linux-protocol-headers` feature is enabled.
+  /// The `Content-Security-Policy` header is still applied to HTML files as a meta tag.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .on_asset_response(|_app, request, response| {
+  ///     if request.uri().ends_with(".wasm") {
+  ///       response
+  ///         .headers_mut()
+  ///         .insert("Cross-Origin-Embedder-Policy", "require-corp".parse().unwrap());
+  ///     }
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn on_asset_response<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(&AppHandle<R>, &HttpRequest, &mut HttpResponse) + Send + Sync + 'static,
+  {
+    self.on_asset_response.replace(Box::new(handler));
+    self
+  }
+
+  /// Defines a callback that generates the response of the `tauri` protocol when the requested asset is not embedded,
+  /// so embedded assets can be mixed with content generated on the fly.
+  ///
+  /// It is called when neither the path nor its `.html` and `/index.html` variants exist.
+  /// Returning `None` keeps the default fallback to `index.html` used by single-page applications.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::http::ResponseBuilder;
+  /// tauri::Builder::default()
+  ///   .asset_fallback(|_app, request| {
+  ///     if request.uri().starts_with("tauri://localhost/generated/") {
+  ///       ResponseBuilder::new()
+  ///         .mimetype("text/plain")
+  ///         .body(b"generated".to_vec())
+  ///         .ok()
+  ///     } else {
+  ///       None
+  ///     }
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn asset_fallback<F>(mut self, fallback: F) -> Self
+  where
+    F: Fn(&AppHandle<R>, &HttpRequest) -> Option<HttpResponse> + Send + Sync + 'static,
+  {
+    self.asset_fallback.replace(Box::new(fallback));
+    self
+  }
+
   /// Defines the setup hook.
   ///
   /// # Examples
@@ -1115,6 +1468,33 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Defines an asynchronous setup hook, awaited on the [`async_runtime`](crate::async_runtime) after the [`Self::setup`] hook.
+  ///
+  /// The windows are already created when the hook runs, but the event loop only starts once it resolves.
+  /// The state registered with [`Self::manage_async`] is available when the hook runs.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::Manager;
+  /// tauri::Builder::default()
+  ///   .setup_async(|app| async move {
+  ///     let config = tauri::async_runtime::spawn(async { "remote config".to_string() }).await?;
+  ///     println!("{} loaded for {}", config, app.package_info().name);
+  ///     Ok(())
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn setup_async<F, Fut>(mut self, setup: F) -> Self
+  where
+    F: FnOnce(AppHandle<R>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send + 'static,
+  {
+    self
+      .setup_async
+      .replace(Box::new(move |app| Box::pin(setup(app))));
+    self
+  }
+
   /// Defines the page load hook.
   #[must_use]
   pub fn on_page_load<F>(mut self, on_page_load: F) -> Self
@@ -1263,6 +1643,45 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Adds a state whose value is initialized by the given future, awaited on the [`async_runtime`](crate::async_runtime) while the app is being built.
+  ///
+  /// The futures of all the asynchronously managed states run concurrently,
+  /// and the states are available to the plugins and the [`Self::setup`] hooks.
+  ///
+  /// # Panics
+  ///
+  /// * Panics if state of type `T` is already being managed.
+  ///
+  /// # Examples
+  /// ```
+  /// struct DbPool(Vec<String>);
+  ///
+  /// async fn connect(url: &str) -> DbPool {
+  ///   DbPool(vec![url.into()])
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .manage_async(connect("sqlite://app.db"));
+  /// ```
+  #[must_use]
+  pub fn manage_async<T, F>(mut self, state: F) -> Self
+  where
+    T: Send + Sync + 'static,
+    F: Future<Output = T> + Send + 'static,
+  {
+    self.async_state.push(Box::new(move |app| {
+      Box::pin(async move {
+        let type_name = std::any::type_name::<T>();
+        assert!(
+          app.manage(state.await),
+          "state for type '{}' is already being managed",
+          type_name
+        );
+      })
+    }));
+    self
+  }
+
   /// Sets the given system tray to be built before the app runs.
   ///
   /// Prefer the [`SystemTray#method.build`](crate::SystemTray#method.build) method to create the tray at runtime instead.
@@ -1389,6 +1808,62 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Saves the position, size, maximization and fullscreen state of each window by label
+  /// to the app config directory, restoring them when a window with the same label is created.
+  ///
+  /// A saved position is only restored when the window would be visible on one of the connected monitors.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .persist_window_state(tauri::WindowStateOptions::default());
+  /// ```
+  #[cfg(desktop)]
+  #[must_use]
+  pub fn persist_window_state(self, options: crate::WindowStateOptions) -> Self {
+    self.plugin(crate::window_state::WindowStatePlugin::new(options))
+  }
+
+  /// Captures the panics and native crashes of the application,
+  /// calling [`crate::CrashReporter::on_report`] with the reports on the next launch.
+  ///
+  /// Native crashes are captured by a copy of the executable started with the `--tauri-crash-monitor` flag,
+  /// so the code running before [`Self::build`] on `main` must not have side effects the monitor would repeat.
+  /// If the crash reporter can't be installed, the error is logged and the app starts without it.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .crash_reporter(tauri::CrashReporter::new().on_report(|_app, report| {
+  ///     println!("crashed on {}: {:?}", report.timestamp, report.message);
+  ///   }));
+  /// ```
+  #[cfg(all(desktop, feature = "crash-reporter"))]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "crash-reporter")))]
+  #[must_use]
+  pub fn crash_reporter(mut self, crash_reporter: crate::CrashReporter<R>) -> Self {
+    self.crash_reporter.replace(crash_reporter);
+    self
+  }
+
+  /// Measures the resource usage of the application at the given interval, of at least 500 milliseconds,
+  /// emitting [`RunEvent::Metrics`] and, when the `process > metrics` allowlist flag is enabled,
+  /// the [`crate::METRICS_EVENT`] to the webviews.
+  ///
+  /// # Examples
+  /// ```
+  /// use std::time::Duration;
+  /// tauri::Builder::default()
+  ///   .metrics_interval(Duration::from_secs(5));
+  /// ```
+  #[cfg(feature = "process-metrics-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "process-metrics-api")))]
+  #[must_use]
+  pub fn metrics_interval(mut self, interval: std::time::Duration) -> Self {
+    self.metrics_interval.replace(interval);
+    self
+  }
+
   /// Registers a system tray event handler.
   ///
   /// Prefer the [`SystemTray#method.on_event`](crate::SystemTray#method.on_event) method when creating a tray at runtime instead.
@@ -1450,6 +1925,73 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Registers a URI scheme protocol available to all webviews, whose handler runs on the [`async_runtime`](crate::async_runtime).
+  ///
+  /// Unlike [`Self::register_uri_scheme_protocol`], the handler can await I/O and return a [`ResponseBody`](crate::protocol::ResponseBody)
+  /// read from an [`AsyncRead`](tokio::io::AsyncRead) or sent as chunks over a channel, so it can proxy large files or remote resources.
+  ///
+  /// ## Platform-specific
+  ///
+  /// The webview runtime only accepts complete responses, so the event loop waits for the response.
+  /// When the webview requests a range of a reader or chunks body with the `Range` header, as media elements do,
+  /// only that range is read, at most 1 MiB, and sent as a `206 Partial Content` response.
+  /// Set the `Content-Length` header so the webview knows the size of the whole body.
+  /// Other bodies are sent once they have been fully read.
+  ///
+  /// # Arguments
+  ///
+  /// * `uri_scheme` The URI scheme to register, such as `example`.
+  /// * `protocol` the protocol associated with the given URI scheme. It's a function that takes an URL such as `example://localhost/asset.css`.
+  ///
+  /// # Examples
+  /// ```rust,no_run
+  /// use tauri::protocol::{AsyncResponseBuilder, ResponseBody};
+  ///
+  /// tauri::Builder::default()
+  ///   .register_asynchronous_uri_scheme_protocol("video", |_app, request| async move {
+  ///     let path = request.uri().trim_start_matches("video://localhost/").to_string();
+  ///     let file = tokio::fs::File::open(path).await?;
+  ///     let size = file.metadata().await?.len();
+  ///     let response = AsyncResponseBuilder::new()
+  ///       .header("Content-Type", "video/mp4")
+  ///       .header("Content-Length", size)
+  ///       .body(ResponseBody::reader(file))?;
+  ///     Ok(response)
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn register_asynchronous_uri_scheme_protocol<N, H, F>(
+    mut self,
+    uri_scheme: N,
+    protocol: H,
+  ) -> Self
+  where
+    N: Into<String>,
+    H: Fn(AppHandle<R>, HttpRequest) -> F + Send + Sync + 'static,
+    F: Future<Output = Result<AsyncResponse, AsyncProtocolError>> + Send + 'static,
+  {
+    self.uri_scheme_protocols.insert(
+      uri_scheme.into(),
+      Arc::new(CustomProtocol {
+        protocol: Box::new(move |app, request| {
+          let range = request
+            .headers()
+            .get("range")
+            .and_then(|range| range.to_str().ok())
+            .map(Into::into);
+          crate::protocol::block_on_response(
+            Box::pin(protocol(
+              app.clone(),
+              crate::protocol::owned_request(request),
+            )),
+            range,
+          )
+        }),
+      }),
+    );
+    self
+  }
+
   /// Sets the current platform's target name for the updater.
   ///
   /// See [`UpdateBuilder::target`](crate::updater::UpdateBuilder#method.target) for more information.
@@ -1487,11 +2029,56 @@ impl<R: Runtime> Builder<R> {
   }
 
   /// Builds the application.
+  ///
+  /// When the application is started with the `--tauri-permissions` or `--tauri-inspect` flag,
+  /// the permission or inspect manifest embedded by [`generate_context`](crate::generate_context) is printed and the process exits.
   #[allow(clippy::type_complexity)]
   pub fn build<A: Assets>(mut self, context: Context<A>) -> crate::Result<App<R>> {
+    if let Some(permissions_manifest) = context.permissions_manifest {
+      if std::env::args_os()
+        .skip(1)
+        .any(|arg| arg == crate::utils::permissions::PERMISSIONS_FLAG)
+      {
+        println!("{}", permissions_manifest);
+        std::process::exit(0);
+      }
+    }
+    if let Some(inspect_manifest) = context.inspect_manifest {
+      if std::env::args_os()
+        .skip(1)
+        .any(|arg| arg == crate::utils::inspect::INSPECT_FLAG)
+      {
+        println!("{}", crate::utils::inspect::strip_markers(inspect_manifest));
+        std::process::exit(0);
+      }
+    }
+
+    #[cfg(all(desktop, feature = "crash-reporter"))]
+    if self.crash_reporter.is_some() {
+      crate::crash_reporter::run_monitor_if_requested();
+    }
+
+    #[cfg(feature = "i18n")]
+    let i18n = crate::i18n::load(
+      context.config().tauri.i18n.as_ref(),
+      context.package_info(),
+      &Env::default(),
+    );
+
     #[cfg(target_os = "macos")]
     if self.menu.is_none() && self.enable_macos_default_menu {
-      self.menu = Some(Menu::os_default(&context.package_info().name));
+      let menu = Menu::os_default(&context.package_info().name);
+      #[cfg(feature = "i18n")]
+      let menu = i18n.localize_menu(menu);
+      self.menu = Some(menu);
+    }
+
+    #[cfg(feature = "i18n")]
+    self.state.set(i18n);
+
+    #[cfg(all(dev, desktop))]
+    if let Some(dev_session) = crate::dev_session::DevSessionPlugin::from_env() {
+      self.plugins.register(dev_session);
     }
 
     #[cfg(shell_scope)]
@@ -1506,22 +2093,83 @@ impl<R: Runtime> Builder<R> {
       self.state,
       self.window_event_listeners,
       (self.menu, self.menu_event_listeners),
-      (self.invoke_responder, self.invoke_initialization_script),
+      (
+        self.invoke_responder,
+        self.invoke_initialization_script,
+        self.invoke_serializer,
+      ),
+      self.ipc_origin_validator,
+      self.isolation_validator,
+      self.on_invoke_metrics,
+      self.on_asset_response,
+      self.asset_fallback,
     );
 
+    crate::log::init(&manager.config(), manager.package_info())?;
+
+    #[cfg(all(desktop, feature = "crash-reporter"))]
+    let crash_reports = match self.crash_reporter.take() {
+      // the app still starts without crash reports
+      Some(crash_reporter) => match crate::crash_reporter::install(
+        crash_reporter.native,
+        &manager.config(),
+        manager.package_info(),
+      ) {
+        Ok(reports) => Some((crash_reporter.on_report, reports)),
+        Err(e) => {
+          crate::log::error!("failed to install the crash reporter: {}", e);
+          None
+        }
+      },
+      None => None,
+    };
+
+    #[cfg(all(feature = "localhost", not(dev)))]
+    if let Some(config) = &manager.config().tauri.localhost {
+      let server = crate::localhost::start(manager.clone(), config)?;
+      manager.state().set(server);
+    }
+
+    #[allow(unused_mut)]
+    let mut windows = manager.config().tauri.windows.clone();
+
+    // the splashscreen is created first so it shows up while the other webviews load
+    #[cfg(desktop)]
+    if let Some(config) = manager.config().tauri.splashscreen.clone() {
+      let show_main_window =
+        crate::splashscreen::hide_main_window(&mut windows, &config.main_window);
+      self
+        .pending_windows
+        .insert(0, crate::splashscreen::pending_window(&config)?);
+      manager.state().set(crate::splashscreen::Splashscreen::new(
+        config,
+        show_main_window,
+      ));
+    }
+
     // set up all the windows defined in the config
-    for config in manager.config().tauri.windows.clone() {
+    for config in windows {
       let url = config.url.clone();
       let label = config.label.clone();
 
-      let mut webview_attributes =
-        WebviewAttributes::new(url).accept_first_mouse(config.accept_first_mouse);
+      let mut webview_attributes = WebviewAttributes::new(url)
+        .accept_first_mouse(config.accept_first_mouse)
+        .hardware_acceleration(config.hardware_acceleration)
+        .gpu_compositing(config.gpu_compositing)
+        .webgl(config.webgl);
       if let Some(ua) = &config.user_agent {
         webview_attributes = webview_attributes.user_agent(&ua.to_string());
       }
       if !config.file_drop_enabled {
         webview_attributes = webview_attributes.disable_file_drop_handler();
       }
+      if let Some(csp) = &config.csp {
+        webview_attributes = webview_attributes.csp(csp.clone());
+      }
+      webview_attributes = webview_attributes.accept_drop_types(config.accept_drop_types.clone());
+      if config.kiosk {
+        webview_attributes = webview_attributes.initialization_script(crate::window::KIOSK_SCRIPT);
+      }
 
       self.pending_windows.push(PendingWindow::with_config(
         config,
@@ -1568,6 +2216,10 @@ impl<R: Runtime> Builder<R> {
 
     let env = Env::default();
     app.manage(Scopes {
+      command: CommandScope::new(&app.config().tauri.security.command_scope)?,
+      plugin_permissions: PluginPermissionScope::new(
+        &app.config().tauri.security.plugin_permissions,
+      )?,
       fs: FsScope::for_fs_api(
         &app.manager.config(),
         app.package_info(),
@@ -1583,10 +2235,23 @@ impl<R: Runtime> Builder<R> {
       )?,
       #[cfg(http_request)]
       http: crate::scope::HttpScope::for_http_api(&app.config().tauri.allowlist.http.scope),
+      #[cfg(websocket_connect)]
+      websocket: crate::scope::WebsocketScope::for_websocket_api(
+        &app.config().tauri.allowlist.websocket.scope,
+      ),
+      #[cfg(oauth_authenticate)]
+      oauth: crate::scope::OAuthScope::for_oauth_api(&app.config().tauri.allowlist.oauth.scope),
+      #[cfg(serial_any)]
+      serial: crate::scope::SerialScope::for_serial_api(&app.config().tauri.allowlist.serial.scope),
+      #[cfg(mdns_any)]
+      mdns: crate::scope::MdnsScope::for_mdns_api(&app.config().tauri.allowlist.mdns.scope),
+      #[cfg(store_any)]
+      store: crate::scope::StoreScope::for_store_api(&app.config().tauri.allowlist.store.scope),
       #[cfg(shell_scope)]
       shell: ShellScope::new(&app.manager.config(), app.package_info(), &env, shell_scope),
     });
     app.manage(env);
+    app.manage(crate::channel::Channels::default());
 
     #[cfg(windows)]
     {
@@ -1599,9 +2264,10 @@ impl<R: Runtime> Builder<R> {
         .webview_install_mode
       {
         if let Some(resource_dir) = app.path_resolver().resource_dir() {
+          // the runtime is bundled as a resource, so `../` is stored as `_up_`
           std::env::set_var(
             "WEBVIEW2_BROWSER_EXECUTABLE_FOLDER",
-            resource_dir.join(path),
+            resource_dir.join(crate::utils::resources::resource_relpath(path)),
           );
         } else {
           #[cfg(debug_assertions)]
@@ -1636,6 +2302,16 @@ impl<R: Runtime> Builder<R> {
       }
     }
 
+    if !self.async_state.is_empty() {
+      let handle = app.handle();
+      crate::async_runtime::safe_block_on(futures_util::future::join_all(
+        self
+          .async_state
+          .into_iter()
+          .map(move |init| init(handle.clone())),
+      ));
+    }
+
     app.manager.initialize_plugins(&app.handle())?;
 
     let window_labels = self
@@ -1655,9 +2331,45 @@ impl<R: Runtime> Builder<R> {
 
     (self.setup)(&mut app).map_err(|e| crate::Error::Setup(e.into()))?;
 
+    if let Some(setup) = self.setup_async {
+      crate::async_runtime::safe_block_on(setup(app.handle())).map_err(|e| {
+        let e: Box<dyn std::error::Error> = e;
+        crate::Error::Setup(e.into())
+      })?;
+    }
+
+    #[cfg(all(desktop, feature = "crash-reporter"))]
+    if let Some((on_report, reports)) = crash_reports {
+      let handle = app.handle();
+      for report in reports {
+        on_report(&handle, report);
+      }
+    }
+
+    #[cfg(feature = "process-metrics-api")]
+    if let Some(interval) = self.metrics_interval {
+      crate::metrics::spawn(app.handle(), interval);
+    }
+
+    #[cfg(all(desktop, feature = "net-status-api"))]
+    {
+      let handle = app.handle();
+      let watcher = crate::api::net::watch(move |status| {
+        let _ = handle.emit_all(crate::api::net::NETWORK_STATUS_EVENT, &status);
+        let _ = handle
+          .create_proxy()
+          .send_event(EventLoopMessage::NetworkStatusChanged(status));
+      });
+      // the status is watched for the lifetime of the app
+      std::mem::forget(watcher);
+    }
+
     #[cfg(updater)]
     app.run_updater();
 
+    #[cfg(config_reload)]
+    crate::config_watcher::spawn(app.handle());
+
     Ok(app)
   }
 
@@ -1755,6 +2467,81 @@ impl Default for Builder<crate::Wry> {
 
 #[cfg(test)]
 mod tests {
+  #[test]
+  fn invoke_metrics() {
+    use crate::{
+      test::{invoke, mock_builder, mock_context, noop_assets},
+      Manager,
+    };
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    let metrics = Arc::new(Mutex::new(Vec::new()));
+    let metrics_ = metrics.clone();
+    let app = mock_builder()
+      .invoke_handler(|invoke| match invoke.message.command() {
+        "ping" => invoke.resolver.resolve("pong"),
+        command => invoke
+          .resolver
+          .reject(format!("unknown command {}", command)),
+      })
+      .on_invoke_metrics(move |_window, metrics| metrics_.lock().unwrap().push(metrics.clone()))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+
+    invoke(&window, "ping", json!({ "value": 1 })).unwrap();
+    invoke(&window, "pong", json!({})).unwrap_err();
+
+    let metrics = metrics.lock().unwrap();
+    assert_eq!(metrics.len(), 2);
+    assert_eq!(metrics[0].command, "ping");
+    assert_eq!(metrics[0].window, "main");
+    assert_eq!(metrics[0].payload_size, r#"{"value":1}"#.len());
+    assert_eq!(metrics[0].error, None);
+    assert_eq!(metrics[1].command, "pong");
+    assert_eq!(metrics[1].error.as_deref(), Some("unknown command pong"));
+  }
+
+  #[test]
+  fn async_setup() {
+    use crate::{
+      test::{mock_builder, mock_context, noop_assets},
+      Manager,
+    };
+    use std::sync::{
+      atomic::{AtomicBool, Ordering},
+      Arc,
+    };
+
+    struct Counter(u32);
+    struct Label(&'static str);
+
+    let ran = Arc::new(AtomicBool::new(false));
+    let ran_ = ran.clone();
+    let app = mock_builder()
+      .manage_async(async { Counter(1) })
+      .manage_async(async { Label("async") })
+      .setup(|app| {
+        assert_eq!(app.state::<Counter>().0, 1);
+        Ok(())
+      })
+      .setup_async(move |app| async move {
+        assert_eq!(app.state::<Label>().0, "async");
+        ran_.store(true, Ordering::SeqCst);
+        Ok(())
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    assert!(ran.load(Ordering::SeqCst));
+    assert_eq!(app.state::<Counter>().0, 1);
+
+    let result = mock_builder()
+      .setup_async(|_app| async { Err("failed to connect".into()) })
+      .build(mock_context(noop_assets()));
+    assert!(matches!(result, Err(crate::Error::Setup(_))));
+  }
+
   #[test]
   fn is_send_sync() {
     crate::test_utils::assert_send::<super::AppHandle>();
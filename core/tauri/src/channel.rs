@@ -0,0 +1,448 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Bidirectional channels to stream data between Rust and the webview.
+//!
+//! A channel can be created on the Rust side with [`Channel::new`] and returned to the webview,
+//! or created on the JavaScript side with `new Channel()` from `@tauri-apps/api/tauri` and passed as a command argument.
+//!
+//! Both directions are bounded by the channel capacity:
+//! - [`Sender::send`] waits until the webview has consumed enough messages;
+//! - the JavaScript `channel.send()` promise resolves only when the [`Receiver`] has room for the message.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use tauri::channel::Channel;
+//!
+//! #[tauri::command]
+//! async fn echo(channel: Channel<String>) {
+//!   let (sender, mut receiver) = channel.split();
+//!   while let Some(Ok(message)) = receiver.recv().await {
+//!     if sender.send(message).await.is_err() {
+//!       break;
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::{
+  collections::HashMap,
+  fmt,
+  marker::PhantomData,
+  sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use serde_json::Value as JsonValue;
+use tauri_macros::default_runtime;
+use tokio::sync::{mpsc, Semaphore};
+
+use crate::{
+  api::ipc::serialize_js_with,
+  command::{CommandArg, CommandItem},
+  InvokeError, Manager, Runtime, Window,
+};
+
+/// The key that identifies a serialized channel.
+const CHANNEL_ID_KEY: &str = "__TAURI_CHANNEL_ID__";
+
+/// The default channel capacity.
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// The maximum channel capacity.
+pub const MAX_CAPACITY: usize = 1024;
+
+type ChannelKey = (String, u32);
+
+struct ChannelState {
+  capacity: usize,
+  /// Messages sent by the webview.
+  incoming_tx: mpsc::Sender<JsonValue>,
+  incoming_rx: Option<mpsc::Receiver<JsonValue>>,
+  /// Permits for messages sent to the webview that it has not consumed yet.
+  outgoing_permits: Arc<Semaphore>,
+}
+
+impl ChannelState {
+  fn new(capacity: usize) -> Self {
+    let capacity = capacity.clamp(1, MAX_CAPACITY);
+    let (incoming_tx, incoming_rx) = mpsc::channel(capacity);
+    Self {
+      capacity,
+      incoming_tx,
+      incoming_rx: Some(incoming_rx),
+      outgoing_permits: Arc::new(Semaphore::new(capacity)),
+    }
+  }
+}
+
+/// The channels opened by the webviews.
+#[derive(Default)]
+pub(crate) struct Channels(Mutex<HashMap<ChannelKey, ChannelState>>);
+
+impl Channels {
+  /// Opens the channel if it is not open yet, and runs the closure on its state.
+  ///
+  /// Channels are only opened on the Rust side, so the webview cannot allocate them.
+  fn open<T, F: FnOnce(&mut ChannelState) -> T>(
+    &self,
+    window_label: &str,
+    id: u32,
+    capacity: usize,
+    f: F,
+  ) -> T {
+    let mut channels = self.0.lock().unwrap();
+    let state = channels
+      .entry((window_label.into(), id))
+      .or_insert_with(|| ChannelState::new(capacity));
+    f(state)
+  }
+
+  /// Runs the closure on the state of an open channel.
+  fn with_state<T, F: FnOnce(&mut ChannelState) -> T>(
+    &self,
+    window_label: &str,
+    id: u32,
+    f: F,
+  ) -> crate::Result<T> {
+    let mut channels = self.0.lock().unwrap();
+    channels
+      .get_mut(&(window_label.into(), id))
+      .map(f)
+      .ok_or(crate::Error::ChannelClosed)
+  }
+
+  /// The sender of the messages coming from the webview.
+  pub(crate) fn incoming(
+    &self,
+    window_label: &str,
+    id: u32,
+  ) -> crate::Result<mpsc::Sender<JsonValue>> {
+    self.with_state(window_label, id, |state| state.incoming_tx.clone())
+  }
+
+  /// Marks `count` messages as consumed by the webview.
+  ///
+  /// The count is capped to the number of messages sent and not acknowledged yet.
+  pub(crate) fn ack(&self, window_label: &str, id: u32, count: usize) -> crate::Result<()> {
+    self.with_state(window_label, id, |state| {
+      let pending = state
+        .capacity
+        .saturating_sub(state.outgoing_permits.available_permits());
+      state.outgoing_permits.add_permits(count.min(pending))
+    })
+  }
+
+  /// Closes the channel, waking up any pending [`Sender::send`] calls.
+  pub(crate) fn close(&self, window_label: &str, id: u32) {
+    if let Some(state) = self
+      .0
+      .lock()
+      .unwrap()
+      .remove(&(window_label.to_string(), id))
+    {
+      state.outgoing_permits.close();
+    }
+  }
+
+  /// Closes all channels of the given window.
+  pub(crate) fn close_window(&self, window_label: &str) {
+    self.0.lock().unwrap().retain(|(label, _), state| {
+      let keep = label != window_label;
+      if !keep {
+        state.outgoing_permits.close();
+      }
+      keep
+    });
+  }
+}
+
+/// A bidirectional channel between Rust and the webview.
+///
+/// It is serialized as a reference to the channel, so it can be returned from a command
+/// and used on the webview side with `Channel.from(value)`.
+#[default_runtime(crate::Wry, wry)]
+pub struct Channel<T, R: Runtime> {
+  id: u32,
+  capacity: usize,
+  window: Window<R>,
+  _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, R: Runtime> fmt::Debug for Channel<T, R> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Channel")
+      .field("id", &self.id)
+      .field("capacity", &self.capacity)
+      .field("window", &self.window.label())
+      .finish()
+  }
+}
+
+impl<T: Serialize + DeserializeOwned, R: Runtime> Channel<T, R> {
+  /// Creates a channel with the given capacity to stream data to and from the given window.
+  ///
+  /// The capacity is clamped between `1` and [`MAX_CAPACITY`].
+  pub fn new(window: &Window<R>, capacity: usize) -> Self {
+    Self::with_id(window.clone(), rand::random(), capacity)
+  }
+
+  fn with_id(window: Window<R>, id: u32, capacity: usize) -> Self {
+    let capacity = capacity.clamp(1, MAX_CAPACITY);
+    // make sure the channel state exists so the webview can send and acknowledge messages
+    window
+      .state::<Channels>()
+      .open(window.label(), id, capacity, |_| ());
+    Self {
+      id,
+      capacity,
+      window,
+      _marker: PhantomData,
+    }
+  }
+
+  /// The channel identifier.
+  pub fn id(&self) -> u32 {
+    self.id
+  }
+
+  /// The maximum number of messages buffered on each direction.
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Splits the channel into its sending and receiving halves.
+  ///
+  /// The receiver can only be taken once, so calling this function again on a channel
+  /// that refers to the same webview channel returns `None` as the receiver.
+  pub fn split(self) -> (Sender<T, R>, Receiver<T>) {
+    let (permits, rx) =
+      self
+        .window
+        .state::<Channels>()
+        .open(self.window.label(), self.id, self.capacity, |state| {
+          (state.outgoing_permits.clone(), state.incoming_rx.take())
+        });
+    (
+      Sender {
+        id: self.id,
+        window: self.window,
+        permits,
+        _marker: PhantomData,
+      },
+      Receiver {
+        rx,
+        _marker: PhantomData,
+      },
+    )
+  }
+}
+
+impl<T, R: Runtime> Serialize for Channel<T, R> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let mut s = serializer.serialize_struct("Channel", 2)?;
+    s.serialize_field(CHANNEL_ID_KEY, &self.id)?;
+    s.serialize_field("capacity", &self.capacity)?;
+    s.end()
+  }
+}
+
+#[derive(Deserialize)]
+struct ChannelReference {
+  #[serde(rename = "__TAURI_CHANNEL_ID__")]
+  id: u32,
+  #[serde(default = "default_capacity")]
+  capacity: usize,
+}
+
+fn default_capacity() -> usize {
+  DEFAULT_CAPACITY
+}
+
+impl<'de, T: Serialize + DeserializeOwned, R: Runtime> CommandArg<'de, R> for Channel<T, R> {
+  /// Grabs the [`Channel`] passed by the webview as the command argument.
+  fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+    let name = command.name;
+    let arg = command.key;
+    let window = command.message.window();
+    let reference = ChannelReference::deserialize(command)
+      .map_err(|e| crate::Error::InvalidArgs(name, arg, e))?;
+    if !(1..=MAX_CAPACITY).contains(&reference.capacity) {
+      return Err(
+        crate::Error::InvalidArgs(
+          name,
+          arg,
+          serde::de::Error::custom(format!(
+            "the channel capacity must be between 1 and {}",
+            MAX_CAPACITY
+          )),
+        )
+        .into(),
+      );
+    }
+    Ok(Self::with_id(window, reference.id, reference.capacity))
+  }
+}
+
+/// The sending half of a [`Channel`], delivering messages to the webview.
+#[default_runtime(crate::Wry, wry)]
+pub struct Sender<T, R: Runtime> {
+  id: u32,
+  window: Window<R>,
+  permits: Arc<Semaphore>,
+  _marker: PhantomData<fn(T)>,
+}
+
+impl<T, R: Runtime> Clone for Sender<T, R> {
+  fn clone(&self) -> Self {
+    Self {
+      id: self.id,
+      window: self.window.clone(),
+      permits: self.permits.clone(),
+      _marker: PhantomData,
+    }
+  }
+}
+
+impl<T, R: Runtime> fmt::Debug for Sender<T, R> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Sender")
+      .field("id", &self.id)
+      .field("window", &self.window.label())
+      .finish()
+  }
+}
+
+impl<T: Serialize, R: Runtime> Sender<T, R> {
+  /// Sends a message to the webview, waiting until the channel has room for it.
+  pub async fn send(&self, message: T) -> crate::Result<()> {
+    self
+      .permits
+      .acquire()
+      .await
+      .map_err(|_| crate::Error::ChannelClosed)?
+      .forget();
+    self.deliver(&message)
+  }
+
+  /// Sends a message to the webview, failing with [`crate::Error::ChannelFull`]
+  /// if the webview has not consumed enough messages yet.
+  pub fn try_send(&self, message: T) -> crate::Result<()> {
+    match self.permits.try_acquire() {
+      Ok(permit) => permit.forget(),
+      Err(tokio::sync::TryAcquireError::NoPermits) => return Err(crate::Error::ChannelFull),
+      Err(tokio::sync::TryAcquireError::Closed) => return Err(crate::Error::ChannelClosed),
+    }
+    self.deliver(&message)
+  }
+
+  /// The number of messages that can be sent before [`Self::send`] starts waiting.
+  pub fn available(&self) -> usize {
+    self.permits.available_permits()
+  }
+
+  /// Closes the channel, notifying the webview that no more messages will be sent.
+  pub fn close(self) -> crate::Result<()> {
+    self
+      .window
+      .state::<Channels>()
+      .close(self.window.label(), self.id);
+    self
+      .window
+      .eval(&format!("window.__TAURI_CHANNEL_CLOSE__({})", self.id))
+  }
+
+  fn deliver(&self, message: &T) -> crate::Result<()> {
+    let js = serialize_js_with(message, Default::default(), |message| {
+      format!("window.__TAURI_CHANNEL_MESSAGE__({}, {})", self.id, message)
+    })?;
+    self.window.eval(&js)
+  }
+}
+
+/// The receiving half of a [`Channel`], yielding the messages sent by the webview.
+pub struct Receiver<T> {
+  rx: Option<mpsc::Receiver<JsonValue>>,
+  _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Receiver").finish()
+  }
+}
+
+impl<T: DeserializeOwned> Receiver<T> {
+  /// Receives the next message sent by the webview.
+  ///
+  /// Returns `None` when the channel is closed or when the receiver was already taken by another [`Channel::split`] call.
+  pub async fn recv(&mut self) -> Option<crate::Result<T>> {
+    let message = self.rx.as_mut()?.recv().await?;
+    Some(serde_json::from_value(message).map_err(Into::into))
+  }
+
+  /// Blocking variant of [`Self::recv`]. Must not be called from an async context.
+  pub fn blocking_recv(&mut self) -> Option<crate::Result<T>> {
+    let message = self.rx.as_mut()?.blocking_recv()?;
+    Some(serde_json::from_value(message).map_err(Into::into))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Channels, MAX_CAPACITY};
+
+  #[test]
+  fn backpressure() {
+    let channels = Channels::default();
+    let permits = channels.open("main", 1, 2, |state| state.outgoing_permits.clone());
+    assert!(permits.try_acquire().map(|p| p.forget()).is_ok());
+    assert!(permits.try_acquire().map(|p| p.forget()).is_ok());
+    assert!(permits.try_acquire().is_err());
+
+    channels.ack("main", 1, 1).unwrap();
+    assert_eq!(permits.available_permits(), 1);
+    // the webview cannot acknowledge more messages than it received
+    channels.ack("main", 1, usize::MAX).unwrap();
+    assert_eq!(permits.available_permits(), 2);
+
+    channels.close_window("main");
+    assert!(matches!(
+      permits.try_acquire(),
+      Err(tokio::sync::TryAcquireError::Closed)
+    ));
+  }
+
+  #[test]
+  fn incoming_is_bounded() {
+    let channels = Channels::default();
+    channels.open("main", 1, 1, |_| ());
+    let tx = channels.incoming("main", 1).unwrap();
+    assert!(tx.try_send(1.into()).is_ok());
+    assert!(tx.try_send(2.into()).is_err());
+
+    let mut rx = channels
+      .with_state("main", 1, |state| state.incoming_rx.take())
+      .unwrap()
+      .unwrap();
+    assert_eq!(rx.try_recv().unwrap(), 1);
+    channels.close("main", 1);
+    assert!(channels.incoming("main", 1).is_err());
+  }
+
+  #[test]
+  fn webview_cannot_open_channels() {
+    let channels = Channels::default();
+    assert!(channels.incoming("main", 1).is_err());
+    assert!(channels.ack("main", 1, 1).is_err());
+    assert!(channels.0.lock().unwrap().is_empty());
+
+    let capacity = channels.open("main", 1, usize::MAX, |state| state.capacity);
+    assert_eq!(capacity, MAX_CAPACITY);
+  }
+}
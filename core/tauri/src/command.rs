@@ -7,11 +7,13 @@
 //! You usually don't need to create these items yourself. These are created from [command](../attr.command.html)
 //! attribute macro along the way and used by [`crate::generate_handler`] macro.
 
-use crate::hooks::InvokeError;
+use crate::hooks::{InvokeArgs, InvokeError};
 use crate::InvokeMessage;
 use crate::Runtime;
 use serde::de::Visitor;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value as JsonValue;
+use std::fmt;
 
 /// Represents a custom command.
 pub struct CommandItem<'a, R: Runtime> {
@@ -56,7 +58,75 @@ impl<'de, D: Deserialize<'de>, R: Runtime> CommandArg<'de, R> for D {
   }
 }
 
-/// Pass the result of [`serde_json::Value::get`] into [`serde_json::Value`]'s deserializer.
+/// A structured error returned by a command.
+///
+/// The promise returned by `invoke` on the JavaScript side is rejected with an object
+/// containing the `code`, `message` and `data` fields, so the frontend can branch on the error kind.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::command::CommandError;
+///
+/// #[tauri::command]
+/// fn read_config(path: String) -> Result<String, CommandError> {
+///   std::fs::read_to_string(&path).map_err(|e| {
+///     CommandError::new("CONFIG_UNREADABLE", e.to_string()).with_data(&path)
+///   })
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandError {
+  code: String,
+  message: String,
+  data: JsonValue,
+}
+
+impl CommandError {
+  /// Creates an error with the given machine-readable code and human-readable message.
+  pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      code: code.into(),
+      message: message.into(),
+      data: JsonValue::Null,
+    }
+  }
+
+  /// Attaches data to the error, available on the `data` field of the rejection.
+  ///
+  /// If `data` cannot be serialized, the error is sent without data.
+  #[must_use]
+  pub fn with_data<T: Serialize>(mut self, data: T) -> Self {
+    self.data = serde_json::to_value(data).unwrap_or_default();
+    self
+  }
+
+  /// The error code.
+  pub fn code(&self) -> &str {
+    &self.code
+  }
+
+  /// The error message.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+
+  /// The data attached to the error.
+  pub fn data(&self) -> &JsonValue {
+    &self.data
+  }
+}
+
+impl fmt::Display for CommandError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.code, self.message)
+  }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Pass the result of [`serde_json::Value::get`] into [`serde_json::Value`]'s deserializer,
+/// or the MessagePack value of the key into the MessagePack deserializer.
 ///
 /// Returns an error if the [`CommandItem`]'s key does not exist in the value.
 macro_rules! pass {
@@ -71,14 +141,25 @@ macro_rules! pass {
           )))
       }
 
-      match self.message.payload.get(self.key) {
-        Some(value) => value.$fn($($arg),*),
-        None => {
-          Err(serde_json::Error::custom(format!(
-            "command {} missing required key {}",
-            self.name, self.key
-          )))
-        }
+      let missing = || {
+        serde_json::Error::custom(format!(
+          "command {} missing required key {}",
+          self.name, self.key
+        ))
+      };
+
+      match &self.message.args {
+        InvokeArgs::Json(payload) => match payload.get(self.key) {
+          Some(value) => value.$fn($($arg),*),
+          None => Err(missing()),
+        },
+        #[cfg(feature = "ipc-msgpack")]
+        InvokeArgs::MessagePack(args) => match args.get(self.key) {
+          Some(value) => (&mut rmp_serde::Deserializer::from_read_ref(value))
+            .$fn($($arg),*)
+            .map_err(serde_json::Error::custom),
+          None => Err(missing()),
+        },
       }
     }
   }
@@ -88,7 +169,8 @@ macro_rules! pass {
 ///
 /// If the key doesn't exist, an error will be returned if the deserialized type is not expecting
 /// an optional item. If the key does exist, the value will be called with
-/// [`Value`](serde_json::Value)'s [`Deserializer`] implementation.
+/// [`Value`](serde_json::Value)'s [`Deserializer`] implementation,
+/// or the MessagePack one for the arguments of the `MessagePackSerializer`.
 impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
   type Error = serde_json::Error;
 
@@ -111,9 +193,18 @@ impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
   pass!(deserialize_byte_buf, visitor: V);
 
   fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-    match self.message.payload.get(self.key) {
-      Some(value) => value.deserialize_option(visitor),
-      None => visitor.visit_none(),
+    match &self.message.args {
+      InvokeArgs::Json(payload) => match payload.get(self.key) {
+        Some(value) => value.deserialize_option(visitor),
+        None => visitor.visit_none(),
+      },
+      #[cfg(feature = "ipc-msgpack")]
+      InvokeArgs::MessagePack(args) => match args.get(self.key) {
+        Some(value) => (&mut rmp_serde::Deserializer::from_read_ref(value))
+          .deserialize_option(visitor)
+          .map_err(<serde_json::Error as serde::de::Error>::custom),
+        None => visitor.visit_none(),
+      },
     }
   }
 
@@ -294,3 +385,26 @@ pub mod private {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::CommandError;
+  use crate::{InvokeError, InvokeResponse};
+  use serde_json::json;
+
+  #[test]
+  fn command_error_is_structured() {
+    let error = CommandError::new("NOT_FOUND", "user not found").with_data(json!({ "id": 42 }));
+    assert_eq!(error.to_string(), "NOT_FOUND: user not found");
+
+    let response = InvokeResponse::from(Result::<(), InvokeError>::Err(error.into()));
+    assert_eq!(
+      response.into_result(),
+      Err(json!({
+        "code": "NOT_FOUND",
+        "message": "user not found",
+        "data": { "id": 42 }
+      }))
+    );
+  }
+}
@@ -0,0 +1,236 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Reloads the configuration written by the CLI when `tauri.conf.json` changes during `tauri dev`.
+
+use std::{
+  collections::BTreeSet,
+  path::{Path, PathBuf},
+  sync::{mpsc::channel, Arc},
+  time::{Duration, Instant},
+};
+
+use notify::{RecursiveMode, Watcher as _};
+use serde_json::Value as JsonValue;
+use tauri_utils::config::FsAllowlistScope;
+
+use crate::{
+  log::{error, warn},
+  runtime::EventLoopProxy,
+  scope::FsScope,
+  utils::{config::Config, Env},
+  AppHandle, EventLoopMessage, Manager, Runtime, Scopes,
+};
+
+/// The environment variable the CLI uses to tell the app where the reloaded configuration is written.
+const DEV_CONFIG_PATH_ENV: &str = "TAURI_DEV_CONFIG_PATH";
+/// How long the events are collected after a change, so the file is read once it is fully written.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Whether the event is a change of the file with the given name.
+///
+/// The file names are compared since the watcher may report the canonical path of the directory.
+fn is_change(event: &notify::Result<notify::Event>, file_name: Option<&std::ffi::OsStr>) -> bool {
+  match event {
+    Ok(event) => {
+      (event.kind.is_create() || event.kind.is_modify())
+        && event.paths.iter().any(|path| path.file_name() == file_name)
+    }
+    Err(_) => false,
+  }
+}
+
+/// Spawns a thread that watches the configuration file written by the CLI,
+/// applying the runtime-safe changes and emitting [`RunEvent::ConfigChanged`](crate::RunEvent#variant.ConfigChanged).
+pub(crate) fn spawn<R: Runtime>(app_handle: AppHandle<R>) {
+  let path = match std::env::var_os(DEV_CONFIG_PATH_ENV) {
+    Some(path) => PathBuf::from(path),
+    None => return,
+  };
+
+  // the CLI creates the file on the first change, so its directory is watched
+  let dir = match path.parent() {
+    Some(dir) => dir.to_path_buf(),
+    None => return,
+  };
+  let (tx, rx) = channel();
+  let watcher = notify::recommended_watcher(move |event| {
+    let _ = tx.send(event);
+  })
+  .and_then(|mut watcher| {
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+  });
+  let watcher = match watcher {
+    Ok(watcher) => watcher,
+    Err(e) => {
+      warn!("failed to watch the configuration: {}", e);
+      return;
+    }
+  };
+
+  std::thread::spawn(move || {
+    // the directory is watched until the thread returns
+    let _watcher = watcher;
+    let file_name = path.file_name();
+    while let Ok(event) = rx.recv() {
+      if !is_change(&event, file_name) {
+        continue;
+      }
+      let deadline = Instant::now() + DEBOUNCE;
+      while rx
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .is_ok()
+      {}
+
+      match reload(&app_handle, &path) {
+        Ok(Some(message)) => {
+          if app_handle.create_proxy().send_event(message).is_err() {
+            break;
+          }
+        }
+        Ok(None) => {}
+        Err(e) => error!("failed to reload the configuration: {}", e),
+      }
+    }
+  });
+}
+
+fn reload<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  path: &Path,
+) -> crate::Result<Option<EventLoopMessage>> {
+  let config: Config = serde_json::from_slice(&std::fs::read(path)?)?;
+  let previous = app_handle.config();
+
+  let sections = changed_sections(
+    &serde_json::to_value(&*previous)?,
+    &serde_json::to_value(&config)?,
+  );
+  if sections.is_empty() {
+    return Ok(None);
+  }
+
+  update_scopes(app_handle, &previous, &config)?;
+  let config = Arc::new(config);
+  app_handle.manager.set_config(config.clone());
+
+  Ok(Some(EventLoopMessage::ConfigChanged { config, sections }))
+}
+
+/// Replaces the allowlist scope entries of the previous configuration with the reloaded ones.
+fn update_scopes<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  previous: &Config,
+  config: &Config,
+) -> crate::Result<()> {
+  let scopes = app_handle.state::<Scopes>();
+  let env = app_handle.state::<Env>();
+  let fs_scope = |config: &Config, scope: &FsAllowlistScope| {
+    FsScope::for_fs_api(config, app_handle.package_info(), &env, scope)
+  };
+
+  scopes.fs.update(
+    &fs_scope(previous, &previous.tauri.allowlist.fs.scope)?,
+    &fs_scope(config, &config.tauri.allowlist.fs.scope)?,
+  );
+  #[cfg(protocol_asset)]
+  scopes.asset_protocol.update(
+    &fs_scope(previous, &previous.tauri.allowlist.protocol.asset_scope)?,
+    &fs_scope(config, &config.tauri.allowlist.protocol.asset_scope)?,
+  );
+  #[cfg(http_request)]
+  scopes.http.replace(crate::scope::HttpScope::for_http_api(
+    &config.tauri.allowlist.http.scope,
+  ));
+  #[cfg(websocket_connect)]
+  scopes
+    .websocket
+    .replace(crate::scope::WebsocketScope::for_websocket_api(
+      &config.tauri.allowlist.websocket.scope,
+    ));
+  #[cfg(oauth_authenticate)]
+  scopes
+    .oauth
+    .replace(crate::scope::OAuthScope::for_oauth_api(
+      &config.tauri.allowlist.oauth.scope,
+    ));
+  #[cfg(serial_any)]
+  scopes
+    .serial
+    .replace(crate::scope::SerialScope::for_serial_api(
+      &config.tauri.allowlist.serial.scope,
+    ));
+  #[cfg(mdns_any)]
+  scopes.mdns.replace(crate::scope::MdnsScope::for_mdns_api(
+    &config.tauri.allowlist.mdns.scope,
+  ));
+  #[cfg(store_any)]
+  scopes
+    .store
+    .replace(crate::scope::StoreScope::for_store_api(
+      &config.tauri.allowlist.store.scope,
+    ));
+
+  Ok(())
+}
+
+fn keys(previous: &JsonValue, config: &JsonValue) -> BTreeSet<String> {
+  previous
+    .as_object()
+    .into_iter()
+    .chain(config.as_object())
+    .flat_map(|object| object.keys().cloned())
+    .collect()
+}
+
+/// Lists the changed top-level sections, splitting the `tauri` object into its own sections.
+fn changed_sections(previous: &JsonValue, config: &JsonValue) -> Vec<String> {
+  let mut sections = Vec::new();
+  for key in keys(previous, config) {
+    let (previous, config) = (&previous[&key], &config[&key]);
+    if previous == config {
+      continue;
+    }
+    if key == "tauri" {
+      for tauri_key in keys(previous, config) {
+        if previous[&tauri_key] != config[&tauri_key] {
+          sections.push(format!("tauri > {}", tauri_key));
+        }
+      }
+    } else {
+      sections.push(key);
+    }
+  }
+  sections
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  #[test]
+  fn changed_sections() {
+    let previous = json!({
+      "build": { "devPath": "http://localhost:4000" },
+      "tauri": { "allowlist": { "fs": { "scope": [] } }, "windows": [] },
+      "plugins": {}
+    });
+    assert!(super::changed_sections(&previous, &previous).is_empty());
+
+    let config = json!({
+      "build": { "devPath": "http://localhost:4000" },
+      "tauri": {
+        "allowlist": { "fs": { "scope": ["$APPDATA/*"] } },
+        "windows": [],
+        "security": { "csp": null }
+      },
+      "plugins": { "store": { "ttl": 10 } }
+    });
+    assert_eq!(
+      super::changed_sections(&previous, &config),
+      vec!["plugins", "tauri > allowlist", "tauri > security"]
+    );
+  }
+}
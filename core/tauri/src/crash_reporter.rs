@@ -0,0 +1,400 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Captures the panics and native crashes of the application, delivering the reports on the next launch.
+//!
+//! The reports are stored in the `crashes` folder of the [app data directory](crate::api::path::app_data_dir).
+//! Native crashes are written as minidumps by a monitor process, a copy of the application
+//! started with the `--tauri-crash-monitor` flag, since a crashed process can't reliably write its own dump.
+
+use std::{
+  any::Any,
+  fmt,
+  fs::File,
+  path::{Path, PathBuf},
+  process::Command,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{api::path::app_data_dir, AppHandle, Config, PackageInfo, Runtime};
+
+/// The command line flag starting the crash monitor process.
+const CRASH_MONITOR_FLAG: &str = "--tauri-crash-monitor";
+
+/// How long the application waits for the monitor process to accept connections.
+const MONITOR_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type OnCrashReport<R> = dyn Fn(&AppHandle<R>, CrashReport) + Send + Sync;
+
+/// The crash reporter of [`Builder::crash_reporter`](crate::Builder#method.crash_reporter).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::CrashReporter;
+/// tauri::Builder::default()
+///   .crash_reporter(CrashReporter::new().on_report(|_app, report| {
+///     println!("the app crashed on its last run: {:?}", report.message);
+///     // upload the report and its minidump, then delete them
+///     report.remove().unwrap();
+///   }));
+/// ```
+pub struct CrashReporter<R: Runtime> {
+  pub(crate) native: bool,
+  pub(crate) on_report: Box<OnCrashReport<R>>,
+}
+
+impl<R: Runtime> fmt::Debug for CrashReporter<R> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CrashReporter")
+      .field("native", &self.native)
+      .finish()
+  }
+}
+
+impl<R: Runtime> Default for CrashReporter<R> {
+  fn default() -> Self {
+    Self {
+      native: true,
+      on_report: Box::new(|_, _| ()),
+    }
+  }
+}
+
+impl<R: Runtime> CrashReporter<R> {
+  /// Creates a crash reporter capturing panics and native crashes.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Whether native crashes are captured as minidumps, which requires starting the monitor process.
+  /// Panics are always captured. Enabled by default.
+  #[must_use]
+  pub fn native(mut self, native: bool) -> Self {
+    self.native = native;
+    self
+  }
+
+  /// Sets the function called on startup for each report of the previous runs, e.g. to upload it.
+  ///
+  /// The reports are delivered until they are deleted with [`CrashReport::remove`].
+  #[must_use]
+  pub fn on_report<F: Fn(&AppHandle<R>, CrashReport) + Send + Sync + 'static>(
+    mut self,
+    on_report: F,
+  ) -> Self {
+    self.on_report = Box::new(on_report);
+    self
+  }
+}
+
+/// The kind of crash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum CrashKind {
+  /// A Rust panic.
+  Panic,
+  /// A native crash, e.g. a segmentation fault, with a minidump.
+  Native,
+}
+
+/// A crash of a previous run of the application.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CrashReport {
+  /// The kind of crash.
+  pub kind: CrashKind,
+  /// When the crash happened, in seconds since the Unix epoch.
+  pub timestamp: u64,
+  /// The version of the application that crashed.
+  pub version: String,
+  /// The panic message.
+  pub message: Option<String>,
+  /// The source location of the panic.
+  pub location: Option<String>,
+  /// The name of the thread that panicked.
+  pub thread: Option<String>,
+  /// The minidump of a native crash.
+  #[serde(skip)]
+  pub minidump: Option<PathBuf>,
+  #[serde(skip)]
+  path: PathBuf,
+}
+
+impl CrashReport {
+  fn new(kind: CrashKind, version: String) -> Self {
+    Self {
+      kind,
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default(),
+      version,
+      message: None,
+      location: None,
+      thread: None,
+      minidump: None,
+      path: PathBuf::new(),
+    }
+  }
+
+  /// The path of the report file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Deletes the report and its minidump, so it is not delivered again.
+  pub fn remove(self) -> crate::Result<()> {
+    if let Some(minidump) = &self.minidump {
+      std::fs::remove_file(minidump)?;
+    }
+    std::fs::remove_file(&self.path)?;
+    Ok(())
+  }
+
+  fn save(&mut self, dir: &Path, id: &str) -> crate::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    self.path = dir.join(format!("{}.json", id));
+    std::fs::write(&self.path, serde_json::to_vec(self)?)?;
+    Ok(())
+  }
+}
+
+fn report_id() -> String {
+  format!(
+    "{}-{}",
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_millis())
+      .unwrap_or_default(),
+    Uuid::new_v4().simple()
+  )
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> Option<String> {
+  payload
+    .downcast_ref::<&str>()
+    .map(|s| s.to_string())
+    .or_else(|| payload.downcast_ref::<String>().cloned())
+}
+
+/// The reports left by the previous runs, oldest first.
+fn pending_reports(dir: &Path) -> Vec<CrashReport> {
+  let mut reports: Vec<CrashReport> = std::fs::read_dir(dir)
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| entry.ok().map(|e| e.path()))
+    .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+    .filter_map(|path| {
+      let mut report: CrashReport = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+      let minidump = path.with_extension("dmp");
+      if minidump.exists() {
+        report.minidump.replace(minidump);
+      }
+      report.path = path;
+      Some(report)
+    })
+    .collect();
+  reports.sort_by_key(|report| report.timestamp);
+  reports
+}
+
+/// Runs the crash monitor and exits if the process was started as the monitor of the application.
+pub(crate) fn run_monitor_if_requested() {
+  let mut args = std::env::args_os().skip(1);
+  if args.next().map_or(true, |arg| arg != CRASH_MONITOR_FLAG) {
+    return;
+  }
+  let (socket, dir, version) = match (args.next(), args.next(), args.next()) {
+    (Some(socket), Some(dir), Some(version)) => (
+      PathBuf::from(socket),
+      PathBuf::from(dir),
+      version.to_string_lossy().into_owned(),
+    ),
+    _ => std::process::exit(1),
+  };
+  let code = match run_monitor(&socket, dir, version) {
+    Ok(()) => 0,
+    Err(e) => {
+      crate::log::error!("crash monitor failed: {}", e);
+      1
+    }
+  };
+  std::process::exit(code);
+}
+
+fn run_monitor(socket: &Path, dir: PathBuf, version: String) -> crate::Result<()> {
+  let mut server = minidumper::Server::with_name(socket)
+    .map_err(|e| crate::Error::CrashReporter(e.to_string()))?;
+  let shutdown = std::sync::atomic::AtomicBool::new(false);
+  server
+    .run(Box::new(MonitorHandler { dir, version }), &shutdown, None)
+    .map_err(|e| crate::Error::CrashReporter(e.to_string()))
+}
+
+struct MonitorHandler {
+  dir: PathBuf,
+  version: String,
+}
+
+impl minidumper::ServerHandler for MonitorHandler {
+  fn create_minidump_file(&self) -> Result<(File, PathBuf), std::io::Error> {
+    std::fs::create_dir_all(&self.dir)?;
+    let path = self.dir.join(format!("{}.dmp", report_id()));
+    Ok((File::create(&path)?, path))
+  }
+
+  fn on_minidump_created(
+    &self,
+    result: Result<minidumper::MinidumpBinary, minidumper::Error>,
+  ) -> minidumper::LoopAction {
+    match result {
+      Ok(binary) => {
+        let id = binary
+          .path
+          .file_stem()
+          .map(|stem| stem.to_string_lossy().into_owned())
+          .unwrap_or_else(report_id);
+        let mut report = CrashReport::new(CrashKind::Native, self.version.clone());
+        if let Err(e) = report.save(&self.dir, &id) {
+          crate::log::error!("failed to save the crash report: {}", e);
+        }
+      }
+      Err(e) => crate::log::error!("failed to write the minidump: {}", e),
+    }
+    minidumper::LoopAction::Exit
+  }
+
+  fn on_message(&self, _kind: u32, _buffer: Vec<u8>) {}
+
+  fn on_client_disconnected(&self, num_clients: usize) -> minidumper::LoopAction {
+    // the application exited without crashing
+    if num_clients == 0 {
+      minidumper::LoopAction::Exit
+    } else {
+      minidumper::LoopAction::Continue
+    }
+  }
+}
+
+/// Starts the monitor process and attaches the native crash handler requesting it a minidump.
+fn attach_native_handler(dir: &Path, version: &str) -> crate::Result<()> {
+  let socket = std::env::temp_dir().join(format!("tauri-crash-{}.sock", std::process::id()));
+  let mut monitor = Command::new(std::env::current_exe()?)
+    .arg(CRASH_MONITOR_FLAG)
+    .arg(&socket)
+    .arg(dir)
+    .arg(version)
+    .spawn()?;
+
+  let started = SystemTime::now();
+  let client = loop {
+    match minidumper::Client::with_name(socket.as_path()) {
+      Ok(client) => break client,
+      Err(e) => {
+        if started.elapsed().unwrap_or_default() > MONITOR_CONNECT_TIMEOUT {
+          let _ = monitor.kill();
+          return Err(crate::Error::CrashReporter(e.to_string()));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+      }
+    }
+  };
+
+  // SAFETY: the handler only sends the crash context to the monitor, without allocating
+  let handler = crash_handler::CrashHandler::attach(unsafe {
+    crash_handler::make_crash_event(move |context: &crash_handler::CrashContext| {
+      crash_handler::CrashEventResult::Handled(client.request_dump(context).is_ok())
+    })
+  })
+  .map_err(|e| crate::Error::CrashReporter(e.to_string()))?;
+  // the monitor reads the memory of the crashed process
+  #[cfg(target_os = "linux")]
+  handler.set_ptracer(Some(monitor.id()));
+  // the handler stays attached until the process exits
+  std::mem::forget(handler);
+  Ok(())
+}
+
+fn install_panic_hook(dir: PathBuf, version: String) {
+  let previous = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    let mut report = CrashReport::new(CrashKind::Panic, version.clone());
+    report.message = panic_message(info.payload());
+    report.location = info.location().map(|l| l.to_string());
+    report.thread = std::thread::current().name().map(Into::into);
+    if let Err(e) = report.save(&dir, &report_id()) {
+      crate::log::error!("failed to save the crash report: {}", e);
+    }
+    previous(info);
+  }));
+}
+
+/// Installs the crash handlers, returning the reports of the previous runs.
+pub(crate) fn install(
+  native: bool,
+  config: &Config,
+  package_info: &PackageInfo,
+) -> crate::Result<Vec<CrashReport>> {
+  let dir = app_data_dir(config)
+    .ok_or_else(|| crate::Error::CrashReporter("failed to resolve the app data directory".into()))?
+    .join("crashes");
+  let reports = pending_reports(&dir);
+  let version = package_info.version.to_string();
+  if native {
+    attach_native_handler(&dir, &version)?;
+  }
+  install_panic_hook(dir, version);
+  Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{panic_message, pending_reports, CrashKind, CrashReport};
+
+  #[test]
+  fn reports_are_delivered_until_removed() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut native = CrashReport::new(CrashKind::Native, "1.0.0".into());
+    native.timestamp = 2;
+    native.save(dir.path(), "native").unwrap();
+    std::fs::write(dir.path().join("native.dmp"), b"MDMP").unwrap();
+    let mut panic = CrashReport::new(CrashKind::Panic, "1.0.0".into());
+    panic.timestamp = 1;
+    panic.message = Some("index out of bounds".into());
+    panic.save(dir.path(), "panic").unwrap();
+
+    let mut reports = pending_reports(dir.path());
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].kind, CrashKind::Panic);
+    assert_eq!(reports[0].message.as_deref(), Some("index out of bounds"));
+    assert!(reports[0].minidump.is_none());
+    assert_eq!(
+      reports[1].minidump.as_deref(),
+      Some(dir.path().join("native.dmp").as_path())
+    );
+
+    reports.remove(1).remove().unwrap();
+    let reports = pending_reports(dir.path());
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].kind, CrashKind::Panic);
+    assert!(!dir.path().join("native.dmp").exists());
+  }
+
+  #[test]
+  fn extracts_panic_message() {
+    let payload: Box<dyn std::any::Any + Send> = Box::new("static message");
+    assert_eq!(panic_message(&*payload).as_deref(), Some("static message"));
+    let payload: Box<dyn std::any::Any + Send> = Box::new(format!("formatted {}", 1));
+    assert_eq!(panic_message(&*payload).as_deref(), Some("formatted 1"));
+    let payload: Box<dyn std::any::Any + Send> = Box::new(1);
+    assert!(panic_message(&*payload).is_none());
+  }
+}
@@ -0,0 +1,247 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Keeps the windows across the restarts of `tauri dev` when the Rust code changes.
+//!
+//! The CLI kills the app before relaunching it, so the geometry and URL of the windows are saved
+//! shortly after they change on a file shared by the app processes of a `tauri dev` session,
+//! and restored when a window with the same label is created again.
+//! The file is removed when the session ends.
+
+use std::{
+  collections::HashMap,
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+  time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use url::Url;
+
+use crate::{
+  log::warn,
+  plugin::{Plugin, Result as PluginResult},
+  runtime::webview::WindowBuilder,
+  window_state::{WindowState, WindowStateOptions},
+  AppHandle, Manager, PageLoadPayload, RunEvent, Runtime, Window, WindowEvent,
+};
+
+/// The environment variable the CLI uses to tell the app where the session is saved.
+const DEV_SESSION_PATH_ENV: &str = "TAURI_DEV_SESSION_PATH";
+
+/// How long the changes are batched before the session is saved, e.g. while a window is dragged.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// The saved session of a window.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowSession {
+  #[serde(flatten)]
+  state: WindowState,
+  /// The URL loaded on the webview, so the frontend route is kept.
+  url: Option<Url>,
+}
+
+/// The saved URL to load on a window that was created on `current`.
+///
+/// Only URLs of the same origin are restored, so changing the `devPath` or the window URL is not overridden.
+fn restored_url<'a>(saved: &'a Url, current: &Url) -> Option<&'a Url> {
+  if saved.origin() == current.origin() && saved != current {
+    Some(saved)
+  } else {
+    None
+  }
+}
+
+/// The windows of the `tauri dev` session, managed as a state so they are restored before the windows are created.
+pub(crate) struct DevSession {
+  path: PathBuf,
+  windows: Mutex<HashMap<String, WindowSession>>,
+  save_scheduled: AtomicBool,
+  /// Whether the session ended, so the scheduled save does not write the file again.
+  ended: AtomicBool,
+}
+
+impl DevSession {
+  /// The saved URL to load on the window instead of `url`.
+  pub(crate) fn restored_url(&self, label: &str, url: &Url) -> Option<Url> {
+    let windows = self.windows.lock().unwrap();
+    let saved = windows.get(label)?.url.as_ref()?;
+    restored_url(saved, url).cloned()
+  }
+
+  /// The saved state of the window, read without holding the lock while the window is changed or queried.
+  fn state(&self, label: &str) -> Option<WindowState> {
+    let windows = self.windows.lock().unwrap();
+    windows.get(label).map(|session| session.state.clone())
+  }
+
+  /// Applies the saved geometry of the window to its builder.
+  pub(crate) fn restore<W: WindowBuilder>(&self, label: &str, builder: W) -> W {
+    match self.state(label) {
+      Some(state) => state.apply(builder, &WindowStateOptions::default()),
+      None => builder,
+    }
+  }
+
+  fn update<R: Runtime>(&self, window: &Window<R>) -> crate::Result<()> {
+    let mut state = self.state(window.label()).unwrap_or_default();
+    state.update(window)?;
+    let url = window.url()?;
+    let mut windows = self.windows.lock().unwrap();
+    let session = windows.entry(window.label().into()).or_default();
+    session.state = state;
+    session.url.replace(url);
+    Ok(())
+  }
+
+  /// Saves the session after [`SAVE_DELAY`], along with the changes made in the meantime.
+  fn schedule_save(self: &Arc<Self>) {
+    if !self.save_scheduled.swap(true, Ordering::SeqCst) {
+      let session = self.clone();
+      std::thread::spawn(move || {
+        std::thread::sleep(SAVE_DELAY);
+        session.save_scheduled.store(false, Ordering::SeqCst);
+        if let Err(e) = session.save() {
+          warn!("failed to save the dev session: {}", e);
+        }
+      });
+    }
+  }
+
+  fn save(&self) -> crate::Result<()> {
+    let windows = self.windows.lock().unwrap();
+    if self.ended.load(Ordering::SeqCst) {
+      return Ok(());
+    }
+    std::fs::write(&self.path, serde_json::to_vec(&*windows)?)?;
+    Ok(())
+  }
+}
+
+pub(crate) struct DevSessionPlugin {
+  session: Arc<DevSession>,
+}
+
+impl DevSessionPlugin {
+  /// Loads the session of the previous app process, if the app was started by `tauri dev`.
+  pub(crate) fn from_env() -> Option<Self> {
+    let path = PathBuf::from(std::env::var_os(DEV_SESSION_PATH_ENV)?);
+    // the file is missing on the first launch
+    let windows = std::fs::read(&path)
+      .ok()
+      .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+      .unwrap_or_default();
+    Some(Self {
+      session: Arc::new(DevSession {
+        path,
+        windows: Mutex::new(windows),
+        save_scheduled: Default::default(),
+        ended: Default::default(),
+      }),
+    })
+  }
+}
+
+impl<R: Runtime> Plugin<R> for DevSessionPlugin {
+  fn name(&self) -> &'static str {
+    "tauri-dev-session"
+  }
+
+  fn initialize(&mut self, app: &AppHandle<R>, _config: JsonValue) -> PluginResult<()> {
+    app.manage(self.session.clone());
+    Ok(())
+  }
+
+  fn created(&mut self, window: Window<R>) {
+    let result = match self.session.state(window.label()) {
+      Some(state) => state.keep_on_screen(&window, &WindowStateOptions::default()),
+      None => Ok(()),
+    };
+    if let Err(e) = result {
+      warn!(
+        "failed to restore the session of window `{}`: {}",
+        window.label(),
+        e
+      );
+    }
+  }
+
+  fn on_page_load(&mut self, window: Window<R>, payload: PageLoadPayload) {
+    if let Ok(url) = payload.url().parse() {
+      let mut windows = self.session.windows.lock().unwrap();
+      let session = windows.entry(window.label().into()).or_default();
+      session.url.replace(url);
+      drop(windows);
+      self.session.schedule_save();
+    }
+  }
+
+  fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
+    match event {
+      RunEvent::WindowEvent { label, event, .. } => match (event, app.get_window(label)) {
+        // the focus is lost when switching to the editor, catching the client-side navigation
+        (
+          WindowEvent::Moved(_) | WindowEvent::Resized(_) | WindowEvent::Focused(false),
+          Some(window),
+        ) => match self.session.update(&window) {
+          Ok(()) => self.session.schedule_save(),
+          Err(e) => warn!("failed to update the dev session: {}", e),
+        },
+        // windows closed by the user are not reopened
+        (WindowEvent::Destroyed, _) => {
+          if self.session.windows.lock().unwrap().remove(label).is_some() {
+            self.session.schedule_save();
+          }
+        }
+        _ => (),
+      },
+      // the app was closed, ending the `tauri dev` session
+      RunEvent::Exit => {
+        let _windows = self.session.windows.lock().unwrap();
+        self.session.ended.store(true, Ordering::SeqCst);
+        let _ = std::fs::remove_file(&self.session.path);
+      }
+      _ => (),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{restored_url, WindowSession};
+
+  #[test]
+  fn restores_same_origin_urls() {
+    let saved = "http://localhost:1420/settings?tab=2".parse().unwrap();
+    assert_eq!(
+      restored_url(&saved, &"http://localhost:1420/".parse().unwrap()),
+      Some(&saved)
+    );
+    assert_eq!(restored_url(&saved, &saved), None);
+    // the devPath changed
+    assert_eq!(
+      restored_url(&saved, &"http://localhost:5173/".parse().unwrap()),
+      None
+    );
+  }
+
+  #[test]
+  fn session_format() {
+    let session: WindowSession = serde_json::from_str(
+      r#"{ "x": 10, "y": 20, "width": 800, "height": 600, "maximized": false, "fullscreen": false, "url": "tauri://localhost/" }"#,
+    )
+    .unwrap();
+    assert_eq!(session.url.as_ref().unwrap().as_str(), "tauri://localhost/");
+    let saved = serde_json::to_string(&session).unwrap();
+    assert_eq!(
+      serde_json::from_str::<WindowSession>(&saved).unwrap(),
+      session
+    );
+  }
+}
@@ -13,6 +13,7 @@ use serde_json::Value as JsonValue;
 use std::sync::Arc;
 
 mod app;
+mod channel;
 #[cfg(cli)]
 mod cli;
 #[cfg(clipboard_any)]
@@ -26,15 +27,29 @@ mod file_system;
 mod global_shortcut;
 #[cfg(http_any)]
 mod http;
+#[cfg(feature = "i18n")]
+mod i18n;
+#[cfg(log_any)]
+mod log;
+#[cfg(mdns_any)]
+mod mdns;
 mod notification;
+#[cfg(oauth_any)]
+mod oauth;
 #[cfg(os_any)]
 mod operating_system;
 #[cfg(path_any)]
 mod path;
 #[cfg(process_any)]
 mod process;
+#[cfg(serial_any)]
+mod serial;
 #[cfg(shell_any)]
 mod shell;
+#[cfg(store_any)]
+mod store;
+#[cfg(websocket_any)]
+mod websocket;
 mod window;
 
 /// The context passed to the invoke handler.
@@ -84,6 +99,7 @@ enum Module {
   #[cfg(shell_any)]
   Shell(shell::Cmd),
   Event(event::Cmd),
+  Channel(channel::Cmd),
   #[cfg(dialog_any)]
   Dialog(dialog::Cmd),
   #[cfg(cli)]
@@ -91,10 +107,24 @@ enum Module {
   Notification(notification::Cmd),
   #[cfg(http_any)]
   Http(http::Cmd),
+  #[cfg(websocket_any)]
+  Websocket(websocket::Cmd),
+  #[cfg(serial_any)]
+  Serial(serial::Cmd),
+  #[cfg(mdns_any)]
+  Mdns(mdns::Cmd),
   #[cfg(global_shortcut_any)]
   GlobalShortcut(global_shortcut::Cmd),
   #[cfg(clipboard_any)]
   Clipboard(clipboard::Cmd),
+  #[cfg(log_any)]
+  Log(log::Cmd),
+  #[cfg(feature = "i18n")]
+  I18n(i18n::Cmd),
+  #[cfg(oauth_any)]
+  OAuth(oauth::Cmd),
+  #[cfg(store_any)]
+  Store(store::Cmd),
 }
 
 impl Module {
@@ -165,6 +195,13 @@ impl Module {
           .and_then(|r| r.json)
           .map_err(InvokeError::from_anyhow)
       }),
+      Self::Channel(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .await
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
       #[cfg(dialog_any)]
       Self::Dialog(cmd) => resolver.respond_async(async move {
         cmd
@@ -193,6 +230,28 @@ impl Module {
           .and_then(|r| r.json)
           .map_err(InvokeError::from_anyhow)
       }),
+      #[cfg(websocket_any)]
+      Self::Websocket(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .await
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
+      #[cfg(serial_any)]
+      Self::Serial(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
+      #[cfg(mdns_any)]
+      Self::Mdns(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
       #[cfg(global_shortcut_any)]
       Self::GlobalShortcut(cmd) => resolver.respond_async(async move {
         cmd
@@ -207,10 +266,56 @@ impl Module {
           .and_then(|r| r.json)
           .map_err(InvokeError::from_anyhow)
       }),
+      #[cfg(log_any)]
+      Self::Log(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
+      #[cfg(feature = "i18n")]
+      Self::I18n(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
+      #[cfg(oauth_any)]
+      Self::OAuth(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .await
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
+      #[cfg(store_any)]
+      Self::Store(cmd) => resolver.respond_async(async move {
+        cmd
+          .run(context)
+          .await
+          .and_then(|r| r.json)
+          .map_err(InvokeError::from_anyhow)
+      }),
     }
   }
 }
 
+/// Releases the resources the endpoints hold for the window, when it is closed.
+pub(crate) fn on_window_close(#[allow(unused_variables)] label: &str) {
+  #[cfg(fs_any)]
+  file_system::close_window(label);
+  #[cfg(store_any)]
+  store::close_window(label);
+  #[cfg(path_any)]
+  path::close_window(label);
+  #[cfg(serial_any)]
+  serial::close_window(label);
+  #[cfg(mdns_any)]
+  mdns::close_window(label);
+  #[cfg(websocket_any)]
+  websocket::close_window(label);
+}
+
 pub(crate) fn handle<R: Runtime>(
   module: String,
   invoke: Invoke<R>,
@@ -218,11 +323,8 @@ pub(crate) fn handle<R: Runtime>(
   package_info: &PackageInfo,
 ) {
   let Invoke { message, resolver } = invoke;
-  let InvokeMessage {
-    mut payload,
-    window,
-    ..
-  } = message;
+  let InvokeMessage { args, window, .. } = message;
+  let mut payload = args.into_json();
 
   if let JsonValue::Object(ref mut obj) = payload {
     obj.insert("module".to_string(), JsonValue::String(module.clone()));
@@ -0,0 +1,57 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::InvokeContext;
+use crate::{channel::Channels, Manager, Runtime};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use tauri_macros::{command_enum, CommandModule};
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[cmd(async)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Send a message to the Rust side of the channel, waiting until it has room for it.
+  Send { id: u32, message: JsonValue },
+  /// Acknowledge that the webview consumed messages sent by the Rust side of the channel.
+  Ack { id: u32, count: usize },
+  /// Close the channel.
+  Close { id: u32 },
+}
+
+impl Cmd {
+  async fn send<R: Runtime>(
+    context: InvokeContext<R>,
+    id: u32,
+    message: JsonValue,
+  ) -> super::Result<()> {
+    let sender = context
+      .window
+      .state::<Channels>()
+      .incoming(context.window.label(), id)
+      .map_err(crate::Error::into_anyhow)?;
+    sender
+      .send(message)
+      .await
+      .map_err(|_| crate::Error::ChannelClosed.into_anyhow())
+  }
+
+  async fn ack<R: Runtime>(context: InvokeContext<R>, id: u32, count: usize) -> super::Result<()> {
+    context
+      .window
+      .state::<Channels>()
+      .ack(context.window.label(), id, count)
+      .map_err(crate::Error::into_anyhow)
+  }
+
+  async fn close<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    context
+      .window
+      .state::<Channels>()
+      .close(context.window.label(), id);
+    Ok(())
+  }
+}
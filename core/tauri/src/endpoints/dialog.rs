@@ -13,6 +13,15 @@ use tauri_macros::{command_enum, module_command_handler, CommandModule};
 
 use std::path::PathBuf;
 
+#[cfg(dialog_color_picker)]
+use crate::api::dialog::Color;
+#[cfg(not(dialog_color_picker))]
+type Color = ();
+#[cfg(dialog_font_picker)]
+use crate::api::dialog::Font;
+#[cfg(not(dialog_font_picker))]
+type Font = ();
+
 macro_rules! message_dialog {
   ($fn_name: ident, $allowlist: ident, $buttons: expr) => {
     #[module_command_handler($allowlist)]
@@ -83,6 +92,22 @@ pub struct SaveDialogOptions {
   pub default_path: Option<PathBuf>,
 }
 
+/// The options for the color picker API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorPickerOptions {
+  /// The initially selected color.
+  pub default_color: Option<Color>,
+}
+
+/// The options for the font picker API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontPickerOptions {
+  /// The initially selected font.
+  pub default_font: Option<Font>,
+}
+
 /// Types of message, ask and confirm dialogs.
 #[non_exhaustive]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -154,6 +179,12 @@ pub enum Cmd {
     #[serde(rename = "type")]
     level: Option<MessageDialogType>,
   },
+  /// The color picker API.
+  #[cmd(dialog_color_picker, "dialog > colorPicker")]
+  ColorPickerDialog { options: ColorPickerOptions },
+  /// The font picker API.
+  #[cmd(dialog_font_picker, "dialog > fontPicker")]
+  FontPickerDialog { options: FontPickerOptions },
 }
 
 impl Cmd {
@@ -269,6 +300,24 @@ impl Cmd {
     dialog_confirm,
     crate::api::dialog::MessageDialogButtons::OkCancel
   );
+
+  #[module_command_handler(dialog_color_picker)]
+  fn color_picker_dialog<R: Runtime>(
+    context: InvokeContext<R>,
+    options: ColorPickerOptions,
+  ) -> super::Result<Option<Color>> {
+    crate::api::dialog::blocking::color_picker(&context.window, options.default_color)
+      .map_err(crate::error::into_anyhow)
+  }
+
+  #[module_command_handler(dialog_font_picker)]
+  fn font_picker_dialog<R: Runtime>(
+    context: InvokeContext<R>,
+    options: FontPickerOptions,
+  ) -> super::Result<Option<Font>> {
+    crate::api::dialog::blocking::font_picker(&context.window, options.default_font)
+      .map_err(crate::error::into_anyhow)
+  }
 }
 
 #[cfg(any(dialog_open, dialog_save))]
@@ -293,7 +342,7 @@ fn set_default_path(
 
 #[cfg(test)]
 mod tests {
-  use super::{OpenDialogOptions, SaveDialogOptions};
+  use super::{ColorPickerOptions, FontPickerOptions, OpenDialogOptions, SaveDialogOptions};
   use quickcheck::{Arbitrary, Gen};
 
   impl Arbitrary for OpenDialogOptions {
@@ -319,6 +368,20 @@ mod tests {
     }
   }
 
+  impl Arbitrary for ColorPickerOptions {
+    fn arbitrary(_g: &mut Gen) -> Self {
+      Self {
+        default_color: None,
+      }
+    }
+  }
+
+  impl Arbitrary for FontPickerOptions {
+    fn arbitrary(_g: &mut Gen) -> Self {
+      Self { default_font: None }
+    }
+  }
+
   #[tauri_macros::module_command_test(dialog_open, "dialog > open")]
   #[quickcheck_macros::quickcheck]
   fn open_dialog(_options: OpenDialogOptions) {}
@@ -326,4 +389,12 @@ mod tests {
   #[tauri_macros::module_command_test(dialog_save, "dialog > save")]
   #[quickcheck_macros::quickcheck]
   fn save_dialog(_options: SaveDialogOptions) {}
+
+  #[tauri_macros::module_command_test(dialog_color_picker, "dialog > colorPicker")]
+  #[quickcheck_macros::quickcheck]
+  fn color_picker_dialog(_options: ColorPickerOptions) {}
+
+  #[tauri_macros::module_command_test(dialog_font_picker, "dialog > fontPicker")]
+  #[quickcheck_macros::quickcheck]
+  fn font_picker_dialog(_options: FontPickerOptions) {}
 }
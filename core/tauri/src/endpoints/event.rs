@@ -68,12 +68,13 @@ pub enum Cmd {
   /// Unlisten to an event.
   #[serde(rename_all = "camelCase")]
   Unlisten { event: EventId, event_id: u64 },
-  /// Emit an event to the webview associated with the given window.
-  /// If the window_label is omitted, the event will be triggered on all listeners.
+  /// Emit an event to the webview associated with the given window, or to the windows of the given group.
+  /// If both the window_label and the group are omitted, the event will be triggered on all listeners.
   #[serde(rename_all = "camelCase")]
   Emit {
     event: EventId,
     window_label: Option<WindowLabel>,
+    group: Option<String>,
     payload: Option<JsonValue>,
   },
 }
@@ -128,6 +129,7 @@ impl Cmd {
     context: InvokeContext<R>,
     event: EventId,
     window_label: Option<WindowLabel>,
+    group: Option<String>,
     payload: Option<JsonValue>,
   ) -> super::Result<()> {
     // dispatch the event to Rust listeners
@@ -150,6 +152,11 @@ impl Cmd {
         .window
         .emit_to(&target.0, &event.0, payload)
         .map_err(crate::error::into_anyhow)?;
+    } else if let Some(group) = group {
+      context
+        .window
+        .emit_to_group(&group, &event.0, payload)
+        .map_err(crate::error::into_anyhow)?;
     } else {
       context
         .window
@@ -8,6 +8,7 @@ use crate::{
   api::{
     dir,
     file::{self, SafePathBuf},
+    ipc::CallbackFn,
     path::BaseDirectory,
   },
   scope::Scopes,
@@ -51,6 +52,113 @@ pub struct FileOperationOptions {
   pub dir: Option<BaseDirectory>,
 }
 
+/// The options for the watch function on the file system API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOperationOptions {
+  /// Whether the directories are watched recursively. Defaults to `true`.
+  pub recursive: Option<bool>,
+  /// How long the changes are collected before they are delivered, in milliseconds.
+  pub delay_ms: Option<u64>,
+  /// The base directory of the operation.
+  /// The directory path of the BaseDirectory will be the prefix of the defined paths.
+  pub dir: Option<BaseDirectory>,
+}
+
+/// The watchers of the webviews, by window label and identifier.
+#[cfg(fs_watch)]
+type WatcherStore = std::sync::Mutex<std::collections::HashMap<(String, u32), file::Watcher>>;
+
+#[cfg(fs_watch)]
+fn watcher_store() -> &'static WatcherStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<WatcherStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// The size of the chunks returned by the read stream API.
+#[cfg(fs_read_file)]
+const READ_STREAM_CHUNK_SIZE: usize = 512 * 1024;
+
+/// The files opened by the stream APIs, by the label of their window and an id referenced on the frontend.
+#[cfg(any(fs_read_file, fs_write_file))]
+struct StreamTable<T> {
+  next_id: std::sync::atomic::AtomicU32,
+  #[allow(clippy::type_complexity)]
+  streams: std::sync::Mutex<std::collections::HashMap<(String, u32), Arc<std::sync::Mutex<T>>>>,
+}
+
+#[cfg(any(fs_read_file, fs_write_file))]
+impl<T> StreamTable<T> {
+  fn new() -> Self {
+    Self {
+      next_id: Default::default(),
+      streams: Default::default(),
+    }
+  }
+
+  fn insert(&self, label: &str, stream: T) -> u32 {
+    let id = self
+      .next_id
+      .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    self
+      .streams
+      .lock()
+      .unwrap()
+      .insert((label.into(), id), Arc::new(std::sync::Mutex::new(stream)));
+    id
+  }
+
+  fn get(&self, label: &str, id: u32) -> super::Result<Arc<std::sync::Mutex<T>>> {
+    self
+      .streams
+      .lock()
+      .unwrap()
+      .get(&(label.into(), id))
+      .cloned()
+      .ok_or_else(|| anyhow::anyhow!("stream {} not found", id))
+  }
+
+  fn remove(&self, label: &str, id: u32) -> Option<Arc<std::sync::Mutex<T>>> {
+    self.streams.lock().unwrap().remove(&(label.into(), id))
+  }
+
+  fn close_window(&self, label: &str) {
+    self
+      .streams
+      .lock()
+      .unwrap()
+      .retain(|(window_label, _), _| window_label != label);
+  }
+}
+
+#[cfg(fs_read_file)]
+fn read_streams() -> &'static StreamTable<File> {
+  use once_cell::sync::Lazy;
+  static STREAMS: Lazy<StreamTable<File>> = Lazy::new(StreamTable::new);
+  &STREAMS
+}
+
+#[cfg(fs_write_file)]
+fn write_streams() -> &'static StreamTable<std::io::BufWriter<File>> {
+  use once_cell::sync::Lazy;
+  static STREAMS: Lazy<StreamTable<std::io::BufWriter<File>>> = Lazy::new(StreamTable::new);
+  &STREAMS
+}
+
+/// Closes the streams and stops the watchers of the window, flushing the written files.
+pub(crate) fn close_window(#[allow(unused_variables)] label: &str) {
+  #[cfg(fs_watch)]
+  watcher_store()
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
+  #[cfg(fs_read_file)]
+  read_streams().close_window(label);
+  #[cfg(fs_write_file)]
+  write_streams().close_window(label);
+}
+
 /// The API descriptor.
 #[command_enum]
 #[derive(Deserialize, CommandModule)]
@@ -75,6 +183,30 @@ pub(crate) enum Cmd {
     contents: Vec<u8>,
     options: Option<FileOperationOptions>,
   },
+  /// The open read stream API.
+  #[cmd(fs_read_file, "fs > readFile")]
+  OpenReadStream {
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+  },
+  /// The read stream chunk API.
+  #[cmd(fs_read_file, "fs > readFile")]
+  ReadStreamChunk { id: u32 },
+  /// The close read stream API.
+  #[cmd(fs_read_file, "fs > readFile")]
+  CloseReadStream { id: u32 },
+  /// The open write stream API.
+  #[cmd(fs_write_file, "fs > writeFile")]
+  OpenWriteStream {
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+  },
+  /// The write stream chunk API.
+  #[cmd(fs_write_file, "fs > writeFile")]
+  WriteStreamChunk { id: u32, contents: Vec<u8> },
+  /// The close write stream API.
+  #[cmd(fs_write_file, "fs > writeFile")]
+  CloseWriteStream { id: u32 },
   /// The read dir API.
   #[cmd(fs_read_dir, "fs > readDir")]
   ReadDir {
@@ -106,6 +238,12 @@ pub(crate) enum Cmd {
     path: SafePathBuf,
     options: Option<FileOperationOptions>,
   },
+  /// The trash API.
+  #[cmd(fs_trash, "fs > trash")]
+  Trash {
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+  },
   /// The rename file API.
   #[cmd(fs_rename_file, "fs > renameFile")]
   #[serde(rename_all = "camelCase")]
@@ -120,6 +258,16 @@ pub(crate) enum Cmd {
     path: SafePathBuf,
     options: Option<FileOperationOptions>,
   },
+  /// The watch API.
+  #[cmd(fs_watch, "fs > watch")]
+  Watch {
+    paths: Vec<SafePathBuf>,
+    options: Option<WatchOperationOptions>,
+    handler: CallbackFn,
+  },
+  /// The unwatch API.
+  #[cmd(fs_watch, "fs > watch")]
+  Unwatch { id: u32 },
 }
 
 impl Cmd {
@@ -179,6 +327,89 @@ impl Cmd {
       .and_then(|mut f| f.write_all(&contents).map_err(|err| err.into()))
   }
 
+  #[module_command_handler(fs_read_file)]
+  fn open_read_stream<R: Runtime>(
+    context: InvokeContext<R>,
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+  ) -> super::Result<u32> {
+    let resolved_path = resolve_path(
+      &context.config,
+      &context.package_info,
+      &context.window,
+      path,
+      options.and_then(|o| o.dir),
+    )?;
+    let file =
+      File::open(&resolved_path).with_context(|| format!("path: {}", resolved_path.display()))?;
+    Ok(read_streams().insert(context.window.label(), file))
+  }
+
+  /// Reads the next chunk of the stream as base64, closing it when the end of the file is reached.
+  #[module_command_handler(fs_read_file)]
+  fn read_stream_chunk<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<String> {
+    use std::io::Read;
+
+    let label = context.window.label();
+    let stream = read_streams().get(label, id)?;
+    let mut chunk = Vec::with_capacity(READ_STREAM_CHUNK_SIZE);
+    stream
+      .lock()
+      .unwrap()
+      .by_ref()
+      .take(READ_STREAM_CHUNK_SIZE as u64)
+      .read_to_end(&mut chunk)?;
+    if chunk.is_empty() {
+      read_streams().remove(label, id);
+    }
+    Ok(base64::encode(chunk))
+  }
+
+  #[module_command_handler(fs_read_file)]
+  fn close_read_stream<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    read_streams().remove(context.window.label(), id);
+    Ok(())
+  }
+
+  #[module_command_handler(fs_write_file)]
+  fn open_write_stream<R: Runtime>(
+    context: InvokeContext<R>,
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+  ) -> super::Result<u32> {
+    let resolved_path = resolve_path(
+      &context.config,
+      &context.package_info,
+      &context.window,
+      path,
+      options.and_then(|o| o.dir),
+    )?;
+    let file =
+      File::create(&resolved_path).with_context(|| format!("path: {}", resolved_path.display()))?;
+    Ok(write_streams().insert(context.window.label(), std::io::BufWriter::new(file)))
+  }
+
+  #[module_command_handler(fs_write_file)]
+  fn write_stream_chunk<R: Runtime>(
+    context: InvokeContext<R>,
+    id: u32,
+    contents: Vec<u8>,
+  ) -> super::Result<()> {
+    let stream = write_streams().get(context.window.label(), id)?;
+    let result = stream.lock().unwrap().write_all(&contents);
+    result.map_err(Into::into)
+  }
+
+  /// Flushes and closes the stream.
+  #[module_command_handler(fs_write_file)]
+  fn close_write_stream<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    if let Some(stream) = write_streams().remove(context.window.label(), id) {
+      let result = stream.lock().unwrap().flush();
+      result?;
+    }
+    Ok(())
+  }
+
   #[module_command_handler(fs_read_dir)]
   fn read_dir<R: Runtime>(
     context: InvokeContext<R>,
@@ -315,6 +546,30 @@ impl Cmd {
     Ok(())
   }
 
+  #[module_command_handler(fs_trash)]
+  fn trash<R: Runtime>(
+    context: InvokeContext<R>,
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+  ) -> super::Result<()> {
+    let resolved_path = resolve_path(
+      &context.config,
+      &context.package_info,
+      &context.window,
+      path,
+      options.and_then(|o| o.dir),
+    )?;
+    if cfg!(mobile) {
+      return Err(anyhow::anyhow!(
+        "cannot move {} to the trash, it is not supported on mobile",
+        resolved_path.display()
+      ));
+    }
+    #[cfg(desktop)]
+    file::trash(&resolved_path).with_context(|| format!("path: {}", resolved_path.display()))?;
+    Ok(())
+  }
+
   #[module_command_handler(fs_rename_file)]
   fn rename_file<R: Runtime>(
     context: InvokeContext<R>,
@@ -361,6 +616,73 @@ impl Cmd {
     )?;
     Ok(resolved_path.as_ref().exists())
   }
+
+  #[module_command_handler(fs_watch)]
+  fn watch<R: Runtime>(
+    context: InvokeContext<R>,
+    paths: Vec<SafePathBuf>,
+    options: Option<WatchOperationOptions>,
+    handler: CallbackFn,
+  ) -> super::Result<u32> {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+    let (recursive, delay_ms, dir) = match options {
+      Some(o) => (o.recursive, o.delay_ms, o.dir),
+      None => (None, None, None),
+    };
+    let paths = paths
+      .into_iter()
+      .map(|path| {
+        resolve_path(
+          &context.config,
+          &context.package_info,
+          &context.window,
+          path,
+          dir,
+        )
+      })
+      .collect::<super::Result<Vec<_>>>()?;
+
+    let mut options = file::WatchOptions::default();
+    if let Some(recursive) = recursive {
+      options = options.recursive(recursive);
+    }
+    if let Some(delay_ms) = delay_ms {
+      options = options.debounce(std::time::Duration::from_millis(delay_ms));
+    }
+    let watcher = file::watch(&paths, options)?;
+
+    let label = context.window.label().to_string();
+    let window = context.window;
+    watcher.subscribe(move |events| {
+      // a recursive watch can report paths the scope forbids
+      let scope = window.state::<Scopes>();
+      let events = events
+        .iter()
+        .filter(|event| scope.fs.is_allowed(&event.path))
+        .collect::<Vec<_>>();
+      if !events.is_empty() {
+        if let Ok(js) = crate::api::ipc::format_callback(handler, &events) {
+          let _ = window.eval(js.as_str());
+        }
+      }
+    });
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    watcher_store().lock().unwrap().insert((label, id), watcher);
+    Ok(id)
+  }
+
+  #[module_command_handler(fs_watch)]
+  fn unwatch<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    // dropping the watcher stops it
+    watcher_store()
+      .lock()
+      .unwrap()
+      .remove(&(context.window.label().to_string(), id));
+    Ok(())
+  }
 }
 
 #[allow(dead_code)]
@@ -392,7 +714,10 @@ fn resolve_path<R: Runtime>(
 
 #[cfg(test)]
 mod tests {
-  use super::{BaseDirectory, DirOperationOptions, FileOperationOptions, SafePathBuf};
+  use super::{
+    BaseDirectory, DirOperationOptions, FileOperationOptions, SafePathBuf, WatchOperationOptions,
+  };
+  use crate::api::ipc::CallbackFn;
 
   use quickcheck::{Arbitrary, Gen};
 
@@ -423,6 +748,16 @@ mod tests {
     }
   }
 
+  impl Arbitrary for WatchOperationOptions {
+    fn arbitrary(g: &mut Gen) -> Self {
+      Self {
+        recursive: Option::arbitrary(g),
+        delay_ms: Option::arbitrary(g),
+        dir: Option::arbitrary(g),
+      }
+    }
+  }
+
   #[tauri_macros::module_command_test(fs_read_file, "fs > readFile")]
   #[quickcheck_macros::quickcheck]
   fn read_file(path: SafePathBuf, options: Option<FileOperationOptions>) {
@@ -437,6 +772,48 @@ mod tests {
     crate::test_utils::assert_not_allowlist_error(res);
   }
 
+  #[tauri_macros::module_command_test(fs_read_file, "fs > readFile")]
+  #[quickcheck_macros::quickcheck]
+  fn open_read_stream(path: SafePathBuf, options: Option<FileOperationOptions>) {
+    let res = super::Cmd::open_read_stream(crate::test::mock_invoke_context(), path, options);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[tauri_macros::module_command_test(fs_read_file, "fs > readFile")]
+  #[quickcheck_macros::quickcheck]
+  fn read_stream_chunk(id: u32) {
+    let res = super::Cmd::read_stream_chunk(crate::test::mock_invoke_context(), id);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[tauri_macros::module_command_test(fs_read_file, "fs > readFile")]
+  #[quickcheck_macros::quickcheck]
+  fn close_read_stream(id: u32) {
+    let res = super::Cmd::close_read_stream(crate::test::mock_invoke_context(), id);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[tauri_macros::module_command_test(fs_write_file, "fs > writeFile")]
+  #[quickcheck_macros::quickcheck]
+  fn open_write_stream(path: SafePathBuf, options: Option<FileOperationOptions>) {
+    let res = super::Cmd::open_write_stream(crate::test::mock_invoke_context(), path, options);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[tauri_macros::module_command_test(fs_write_file, "fs > writeFile")]
+  #[quickcheck_macros::quickcheck]
+  fn write_stream_chunk(id: u32, contents: Vec<u8>) {
+    let res = super::Cmd::write_stream_chunk(crate::test::mock_invoke_context(), id, contents);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[tauri_macros::module_command_test(fs_write_file, "fs > writeFile")]
+  #[quickcheck_macros::quickcheck]
+  fn close_write_stream(id: u32) {
+    let res = super::Cmd::close_write_stream(crate::test::mock_invoke_context(), id);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
   #[tauri_macros::module_command_test(fs_read_dir, "fs > readDir")]
   #[quickcheck_macros::quickcheck]
   fn read_dir(path: SafePathBuf, options: Option<DirOperationOptions>) {
@@ -481,6 +858,13 @@ mod tests {
     crate::test_utils::assert_not_allowlist_error(res);
   }
 
+  #[tauri_macros::module_command_test(fs_trash, "fs > trash")]
+  #[quickcheck_macros::quickcheck]
+  fn trash(path: SafePathBuf, options: Option<FileOperationOptions>) {
+    let res = super::Cmd::trash(crate::test::mock_invoke_context(), path, options);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
   #[tauri_macros::module_command_test(fs_rename_file, "fs > renameFile")]
   #[quickcheck_macros::quickcheck]
   fn rename_file(
@@ -503,4 +887,39 @@ mod tests {
     let res = super::Cmd::exists(crate::test::mock_invoke_context(), path, options);
     crate::test_utils::assert_not_allowlist_error(res);
   }
+
+  #[tauri_macros::module_command_test(fs_watch, "fs > watch")]
+  #[quickcheck_macros::quickcheck]
+  fn watch(paths: Vec<SafePathBuf>, options: Option<WatchOperationOptions>, handler: CallbackFn) {
+    let res = super::Cmd::watch(crate::test::mock_invoke_context(), paths, options, handler);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[tauri_macros::module_command_test(fs_watch, "fs > watch")]
+  #[quickcheck_macros::quickcheck]
+  fn unwatch(id: u32) {
+    let res = super::Cmd::unwatch(crate::test::mock_invoke_context(), id);
+    crate::test_utils::assert_not_allowlist_error(res);
+  }
+
+  #[cfg(any(fs_read_file, fs_write_file))]
+  #[test]
+  fn stream_table() {
+    let table = super::StreamTable::new();
+    let main = table.insert("main", "main stream");
+    let other = table.insert("other", "other stream");
+    assert_ne!(main, other);
+
+    assert_eq!(
+      *table.get("main", main).unwrap().lock().unwrap(),
+      "main stream"
+    );
+    // a window can't use the streams of another window
+    assert!(table.get("main", other).is_err());
+
+    table.close_window("main");
+    assert!(table.get("main", main).is_err());
+    assert!(table.remove("other", other).is_some());
+    assert!(table.get("other", other).is_err());
+  }
 }
@@ -5,10 +5,12 @@
 #![allow(unused_imports)]
 
 use super::InvokeContext;
-use crate::Runtime;
+use crate::{api::ipc::CallbackFn, Runtime};
 use serde::Deserialize;
 use tauri_macros::{command_enum, module_command_handler, CommandModule};
 
+use std::path::PathBuf;
+
 #[cfg(http_request)]
 use std::{
   collections::HashMap,
@@ -16,10 +18,13 @@ use std::{
 };
 
 #[cfg(http_request)]
-use crate::api::http::{ClientBuilder, HttpRequestBuilder, ResponseData};
+use crate::api::http::{ClientBuilder, Cookie, CookieStore, HttpRequestBuilder, ResponseData};
 #[cfg(not(http_request))]
 type ClientBuilder = ();
 #[cfg(not(http_request))]
+#[allow(dead_code)]
+type Cookie = ();
+#[cfg(not(http_request))]
 type HttpRequestBuilder = ();
 #[cfg(not(http_request))]
 #[allow(dead_code)]
@@ -36,6 +41,19 @@ fn clients() -> &'static ClientStore {
   &STORE
 }
 
+/// The cookie store shared by the clients created with `cookies: true`, persisted in the app data directory.
+#[cfg(http_request)]
+fn cookie_store(config: &crate::Config) -> crate::api::Result<&'static Arc<CookieStore>> {
+  use once_cell::sync::OnceCell;
+  static STORE: OnceCell<Arc<CookieStore>> = OnceCell::new();
+  STORE.get_or_try_init(|| {
+    let path = crate::api::path::app_data_dir(config)
+      .ok_or_else(|| crate::api::Error::Path("unable to determine the app data directory".into()))?
+      .join("cookies.json");
+    Ok(Arc::new(CookieStore::load(path)?))
+  })
+}
+
 /// The API descriptor.
 #[command_enum]
 #[derive(Deserialize, CommandModule)]
@@ -44,7 +62,10 @@ fn clients() -> &'static ClientStore {
 pub enum Cmd {
   /// Create a new HTTP client.
   #[cmd(http_request, "http > request")]
-  CreateClient { options: Option<ClientBuilder> },
+  CreateClient {
+    options: Option<ClientBuilder>,
+    cookies: Option<bool>,
+  },
   /// Drop a HTTP client.
   #[cmd(http_request, "http > request")]
   DropClient { client: ClientId },
@@ -53,16 +74,30 @@ pub enum Cmd {
   HttpRequest {
     client: ClientId,
     options: Box<HttpRequestBuilder>,
+    upload_progress_handler: Option<CallbackFn>,
+    chunk_handler: Option<CallbackFn>,
+    save_to: Option<PathBuf>,
   },
+  /// Get the stored cookies sent to a URL, except the HttpOnly ones.
+  #[cmd(http_request, "http > request")]
+  GetCookies { url: url::Url },
+  /// Remove the stored cookies sent to a URL.
+  #[cmd(http_request, "http > request")]
+  ClearCookies { url: url::Url },
 }
 
 impl Cmd {
   #[module_command_handler(http_request)]
   async fn create_client<R: Runtime>(
-    _context: InvokeContext<R>,
+    context: InvokeContext<R>,
     options: Option<ClientBuilder>,
+    cookies: Option<bool>,
   ) -> super::Result<ClientId> {
-    let client = options.unwrap_or_default().build()?;
+    let mut options = options.unwrap_or_default();
+    if cookies.unwrap_or_default() {
+      options = options.cookie_store(cookie_store(&context.config)?.clone());
+    }
+    let client = options.build()?;
     let mut store = clients().lock().unwrap();
     let id = rand::random::<ClientId>();
     store.insert(id, client);
@@ -84,6 +119,9 @@ impl Cmd {
     context: InvokeContext<R>,
     client_id: ClientId,
     options: Box<HttpRequestBuilder>,
+    upload_progress_handler: Option<CallbackFn>,
+    chunk_handler: Option<CallbackFn>,
+    save_to: Option<PathBuf>,
   ) -> super::Result<ResponseData> {
     use crate::Manager;
     let scopes = context.window.state::<crate::Scopes>();
@@ -94,7 +132,23 @@ impl Cmd {
         .get(&client_id)
         .ok_or_else(|| crate::Error::HttpClientNotInitialized.into_anyhow())?
         .clone();
-      let options = *options;
+      let mut options = *options;
+      if let Some(handler) = upload_progress_handler {
+        let window = context.window.clone();
+        options = options.on_upload_progress(move |progress| {
+          if let Ok(js) = crate::api::ipc::format_callback(handler, &progress) {
+            let _ = window.eval(&js);
+          }
+        });
+      }
+      let check_path = |path: &PathBuf| {
+        if crate::api::file::SafePathBuf::new(path.clone()).is_err() || !scopes.fs.is_allowed(path)
+        {
+          Err(crate::Error::PathNotAllowed(path.clone()).into_anyhow())
+        } else {
+          Ok(())
+        }
+      };
       if let Some(crate::api::http::Body::Form(form)) = &options.body {
         for value in form.0.values() {
           if let crate::api::http::FormPart::File {
@@ -102,20 +156,77 @@ impl Cmd {
             ..
           } = value
           {
-            if crate::api::file::SafePathBuf::new(path.clone()).is_err()
-              || !scopes.fs.is_allowed(path)
-            {
-              return Err(crate::Error::PathNotAllowed(path.clone()).into_anyhow());
-            }
+            check_path(path)?;
           }
         }
       }
+      if let Some(path) = &save_to {
+        check_path(path)?;
+      }
       let response = client.send(options).await?;
-      Ok(response.read().await?)
+      let response_data = if let Some(path) = save_to {
+        response.write_to_file(path).await?
+      } else if let Some(handler) = chunk_handler {
+        response
+          .read_chunks(|chunk| {
+            // base64 is about a third of the size of a JSON array of numbers
+            let js = crate::api::ipc::format_callback(handler, &base64::encode(chunk))?;
+            let _ = context.window.eval(&js);
+            Ok(())
+          })
+          .await?
+      } else {
+        response.read().await?
+      };
+      Ok(response_data)
     } else {
       Err(crate::Error::UrlNotAllowed(options.url).into_anyhow())
     }
   }
+
+  #[module_command_handler(http_request)]
+  async fn get_cookies<R: Runtime>(
+    context: InvokeContext<R>,
+    url: url::Url,
+  ) -> super::Result<Vec<Cookie>> {
+    use crate::Manager;
+    if context
+      .window
+      .state::<crate::Scopes>()
+      .http
+      .is_allowed(&url)
+    {
+      // the HttpOnly cookies are hidden from scripts, like on browsers
+      Ok(
+        cookie_store(&context.config)?
+          .cookies(&url)
+          .into_iter()
+          .filter(|cookie| !cookie.http_only)
+          .collect(),
+      )
+    } else {
+      Err(crate::Error::UrlNotAllowed(url).into_anyhow())
+    }
+  }
+
+  #[module_command_handler(http_request)]
+  async fn clear_cookies<R: Runtime>(
+    context: InvokeContext<R>,
+    url: url::Url,
+  ) -> super::Result<()> {
+    use crate::Manager;
+    if context
+      .window
+      .state::<crate::Scopes>()
+      .http
+      .is_allowed(&url)
+    {
+      cookie_store(&context.config)?.clear(&url)?;
+      Ok(())
+    } else {
+      Err(crate::Error::UrlNotAllowed(url).into_anyhow())
+    }
+  }
 }
 
 #[cfg(test)]
@@ -127,7 +238,8 @@ mod tests {
   fn create_client(options: Option<ClientBuilder>) {
     assert!(crate::async_runtime::block_on(super::Cmd::create_client(
       crate::test::mock_invoke_context(),
-      options
+      options,
+      None
     ))
     .is_ok());
   }
@@ -141,7 +253,7 @@ mod tests {
           .await
           .is_ok()
       );
-      let id = super::Cmd::create_client(crate::test::mock_invoke_context(), None)
+      let id = super::Cmd::create_client(crate::test::mock_invoke_context(), None, None)
         .await
         .unwrap();
       assert!(
@@ -0,0 +1,57 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::InvokeContext;
+use crate::{I18n, Manager, Runtime};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use tauri_macros::{command_enum, CommandModule};
+
+use std::collections::HashMap;
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Translate a message.
+  Translate {
+    key: String,
+    #[serde(default)]
+    args: HashMap<String, JsonValue>,
+  },
+  /// Get the selected locale.
+  Locale,
+  /// Select the locale.
+  SetLocale { locale: String },
+  /// List the locales with translations.
+  AvailableLocales,
+}
+
+impl Cmd {
+  fn translate<R: Runtime>(
+    context: InvokeContext<R>,
+    key: String,
+    args: HashMap<String, JsonValue>,
+  ) -> super::Result<String> {
+    let args: Vec<(&str, JsonValue)> = args
+      .iter()
+      .map(|(name, value)| (name.as_str(), value.clone()))
+      .collect();
+    Ok(context.window.state::<I18n>().translate(&key, &args))
+  }
+
+  fn locale<R: Runtime>(context: InvokeContext<R>) -> super::Result<String> {
+    Ok(context.window.state::<I18n>().locale())
+  }
+
+  fn set_locale<R: Runtime>(context: InvokeContext<R>, locale: String) -> super::Result<()> {
+    context.window.state::<I18n>().set_locale(locale);
+    Ok(())
+  }
+
+  fn available_locales<R: Runtime>(context: InvokeContext<R>) -> super::Result<Vec<String>> {
+    Ok(context.window.state::<I18n>().available_locales())
+  }
+}
@@ -0,0 +1,65 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#![allow(unused_imports)]
+
+use super::InvokeContext;
+use crate::{utils::config::LogLevel, Runtime};
+use serde::Deserialize;
+use tauri_macros::{command_enum, module_command_handler, CommandModule};
+
+/// The target of the records written by the webviews, so they cannot be mistaken for the records of the app.
+const WEBVIEW_TARGET: &str = "webview";
+
+/// Escapes the line breaks of the message, so a webview cannot forge log lines.
+fn escape(message: &str) -> String {
+  message.replace('\r', "\\r").replace('\n', "\\n")
+}
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Write a record to the log configured on `tauri > log`.
+  #[cmd(log_write, "log > write")]
+  Log { level: LogLevel, message: String },
+}
+
+impl Cmd {
+  #[module_command_handler(log_write)]
+  fn log<R: Runtime>(
+    context: InvokeContext<R>,
+    level: LogLevel,
+    message: String,
+  ) -> super::Result<()> {
+    crate::log::log!(
+      target: WEBVIEW_TARGET,
+      crate::log::level(level),
+      "[{}] {}",
+      context.window.label(),
+      escape(&message)
+    );
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::config::LogLevel;
+
+  #[test]
+  fn escapes_line_breaks() {
+    assert_eq!(
+      super::escape("done\r\n[2022-01-01T00:00:00.000Z][INFO][app] forged"),
+      "done\\r\\n[2022-01-01T00:00:00.000Z][INFO][app] forged"
+    );
+  }
+
+  #[tauri_macros::module_command_test(log_write, "log > write")]
+  #[quickcheck_macros::quickcheck]
+  fn log(message: String) {
+    super::Cmd::log(crate::test::mock_invoke_context(), LogLevel::Info, message).unwrap();
+  }
+}
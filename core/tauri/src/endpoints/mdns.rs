@@ -0,0 +1,213 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#![allow(unused_imports)]
+
+use super::InvokeContext;
+use crate::{
+  api::{
+    ipc::CallbackFn,
+    mdns::{Advertisement, Browser, Mdns, Registration},
+  },
+  Manager, Runtime, Window,
+};
+use serde::Deserialize;
+use tauri_macros::{command_enum, module_command_handler, CommandModule};
+
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+  },
+};
+
+/// The daemon shared by the advertisements and browsers of the webviews.
+#[allow(dead_code)]
+fn daemon() -> super::Result<&'static Mdns> {
+  use once_cell::sync::OnceCell;
+  static DAEMON: OnceCell<Mdns> = OnceCell::new();
+  DAEMON.get_or_try_init(Mdns::new).map_err(Into::into)
+}
+
+/// The advertisements of the webviews, by window label and identifier.
+#[cfg(mdns_advertise)]
+type RegistrationStore = Mutex<HashMap<(String, u32), Registration>>;
+
+#[cfg(mdns_advertise)]
+fn registration_store() -> &'static RegistrationStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<RegistrationStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// The browsers of the webviews, by window label and identifier.
+#[cfg(mdns_browse)]
+type BrowserStore = Mutex<HashMap<(String, u32), Browser>>;
+
+#[cfg(mdns_browse)]
+fn browser_store() -> &'static BrowserStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<BrowserStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// Stops the advertisements and browsers of the window.
+pub(crate) fn close_window(#[allow(unused_variables)] label: &str) {
+  #[cfg(mdns_advertise)]
+  registration_store()
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
+  #[cfg(mdns_browse)]
+  browser_store()
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
+}
+
+/// Checks that the service type is allowed on the `mdns` scope.
+#[allow(dead_code)]
+fn check_scope<R: Runtime>(window: &Window<R>, service_type: &str) -> super::Result<()> {
+  if window
+    .state::<crate::Scopes>()
+    .mdns
+    .is_allowed(service_type)
+  {
+    Ok(())
+  } else {
+    Err(crate::Error::MdnsServiceTypeNotAllowed(service_type.into()).into_anyhow())
+  }
+}
+
+/// The identifier of the advertisements and browsers.
+#[allow(dead_code)]
+fn next_id() -> u32 {
+  static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+  NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Advertise a service of the app.
+  #[cmd(mdns_advertise, "mdns > advertise")]
+  #[serde(rename_all = "camelCase")]
+  Advertise {
+    service_type: String,
+    port: u16,
+    instance_name: Option<String>,
+    properties: Option<HashMap<String, String>>,
+  },
+  /// Stop advertising a service.
+  #[cmd(mdns_advertise, "mdns > advertise")]
+  Unadvertise { id: u32 },
+  /// Browse the services of a type.
+  #[cmd(mdns_browse, "mdns > browse")]
+  #[serde(rename_all = "camelCase")]
+  Browse {
+    service_type: String,
+    handler: CallbackFn,
+  },
+  /// Stop browsing.
+  #[cmd(mdns_browse, "mdns > browse")]
+  StopBrowse { id: u32 },
+}
+
+impl Cmd {
+  #[module_command_handler(mdns_advertise)]
+  fn advertise<R: Runtime>(
+    context: InvokeContext<R>,
+    service_type: String,
+    port: u16,
+    instance_name: Option<String>,
+    properties: Option<HashMap<String, String>>,
+  ) -> super::Result<u32> {
+    check_scope(&context.window, &service_type)?;
+    let mut advertisement = Advertisement::new(
+      service_type,
+      instance_name.unwrap_or_else(|| context.package_info.name.clone()),
+      port,
+    );
+    advertisement.properties = properties.unwrap_or_default();
+    let registration = daemon()?.advertise(advertisement)?;
+
+    let id = next_id();
+    registration_store()
+      .lock()
+      .unwrap()
+      .insert((context.window.label().to_string(), id), registration);
+    Ok(id)
+  }
+
+  #[module_command_handler(mdns_advertise)]
+  fn unadvertise<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    // dropping the registration unregisters the service
+    registration_store()
+      .lock()
+      .unwrap()
+      .remove(&(context.window.label().to_string(), id));
+    Ok(())
+  }
+
+  #[module_command_handler(mdns_browse)]
+  fn browse<R: Runtime>(
+    context: InvokeContext<R>,
+    service_type: String,
+    handler: CallbackFn,
+  ) -> super::Result<u32> {
+    check_scope(&context.window, &service_type)?;
+    let label = context.window.label().to_string();
+    let window = context.window;
+    let browser = daemon()?.browse(&service_type, move |event| {
+      if let Ok(js) = crate::api::ipc::format_callback(handler, &event) {
+        let _ = window.eval(js.as_str());
+      }
+    })?;
+
+    let id = next_id();
+    browser_store().lock().unwrap().insert((label, id), browser);
+    Ok(id)
+  }
+
+  #[module_command_handler(mdns_browse)]
+  fn stop_browse<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    // dropping the browser stops it
+    browser_store()
+      .lock()
+      .unwrap()
+      .remove(&(context.window.label().to_string(), id));
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[tauri_macros::module_command_test(mdns_browse, "mdns > browse")]
+  #[quickcheck_macros::quickcheck]
+  fn browse(handler: crate::api::ipc::CallbackFn) {
+    // the mock context has no mDNS scope
+    let result = super::Cmd::browse(
+      crate::test::mock_invoke_context(),
+      "_myapp._tcp".into(),
+      handler,
+    );
+    assert!(result.unwrap_err().to_string().contains("not allowed"));
+  }
+
+  #[tauri_macros::module_command_test(mdns_advertise, "mdns > advertise")]
+  #[quickcheck_macros::quickcheck]
+  fn unadvertise(id: u32) {
+    // unknown identifiers are ignored
+    super::Cmd::unadvertise(crate::test::mock_invoke_context(), id).unwrap();
+  }
+
+  #[tauri_macros::module_command_test(mdns_browse, "mdns > browse")]
+  #[quickcheck_macros::quickcheck]
+  fn stop_browse(id: u32) {
+    super::Cmd::stop_browse(crate::test::mock_invoke_context(), id).unwrap();
+  }
+}
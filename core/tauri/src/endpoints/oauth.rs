@@ -0,0 +1,65 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#![allow(unused_imports)]
+
+use super::InvokeContext;
+use crate::{
+  api::oauth::{AuthRequest, TokenResponse},
+  Runtime,
+};
+use serde::Deserialize;
+use tauri_macros::{command_enum, module_command_handler, CommandModule};
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[cmd(async)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Run the authorization code flow.
+  #[cmd(oauth_authenticate, "oauth > authenticate")]
+  Authenticate { request: Box<AuthRequest> },
+}
+
+impl Cmd {
+  #[module_command_handler(oauth_authenticate)]
+  async fn authenticate<R: Runtime>(
+    context: InvokeContext<R>,
+    request: Box<AuthRequest>,
+  ) -> super::Result<TokenResponse> {
+    use crate::Manager;
+
+    let scopes = context.window.state::<crate::Scopes>();
+    for url in [&request.authorization_url, &request.token_url] {
+      if !scopes.oauth.is_allowed(url) {
+        return Err(crate::Error::UrlNotAllowed(url.clone()).into_anyhow());
+      }
+    }
+    crate::api::oauth::authenticate(*request)
+      .await
+      .map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::oauth::AuthRequest;
+
+  #[tauri_macros::module_command_test(oauth_authenticate, "oauth > authenticate")]
+  #[quickcheck_macros::quickcheck]
+  fn authenticate(client_id: String) {
+    // the mock scope does not allow any endpoint
+    let request = AuthRequest::new(
+      "https://example.com/authorize".parse().unwrap(),
+      "https://example.com/token".parse().unwrap(),
+      client_id,
+    );
+    assert!(crate::async_runtime::block_on(super::Cmd::authenticate(
+      crate::test::mock_invoke_context(),
+      Box::new(request)
+    ))
+    .is_err());
+  }
+}
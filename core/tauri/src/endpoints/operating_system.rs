@@ -10,6 +10,11 @@ use serde::Deserialize;
 use std::path::PathBuf;
 use tauri_macros::{command_enum, module_command_handler, CommandModule};
 
+#[cfg(all(os_network_status, desktop))]
+use crate::api::net::NetworkStatus;
+#[cfg(not(all(os_network_status, desktop)))]
+type NetworkStatus = ();
+
 /// The API descriptor.
 #[command_enum]
 #[derive(Deserialize, CommandModule)]
@@ -20,6 +25,8 @@ pub enum Cmd {
   OsType,
   Arch,
   Tempdir,
+  #[cmd(os_network_status, "os > networkStatus")]
+  NetworkStatus,
 }
 
 #[cfg(os_all)]
@@ -68,6 +75,22 @@ impl Cmd {
   }
 }
 
+impl Cmd {
+  #[cfg(desktop)]
+  #[module_command_handler(os_network_status)]
+  fn network_status<R: Runtime>(_context: InvokeContext<R>) -> super::Result<NetworkStatus> {
+    Ok(crate::api::net::status())
+  }
+
+  #[cfg(not(desktop))]
+  #[module_command_handler(os_network_status)]
+  fn network_status<R: Runtime>(_context: InvokeContext<R>) -> super::Result<NetworkStatus> {
+    Err(anyhow::anyhow!(
+      "the network status is not supported on this platform"
+    ))
+  }
+}
+
 #[cfg(os_all)]
 fn os_type() -> &'static str {
   #[cfg(target_os = "linux")]
@@ -110,4 +133,8 @@ mod tests {
   #[tauri_macros::module_command_test(os_all, "os > all", runtime)]
   #[quickcheck_macros::quickcheck]
   fn tempdir() {}
+
+  #[tauri_macros::module_command_test(os_network_status, "os > networkStatus", runtime)]
+  #[quickcheck_macros::quickcheck]
+  fn network_status() {}
 }
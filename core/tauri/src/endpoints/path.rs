@@ -4,13 +4,21 @@
 
 #![allow(unused_imports)]
 
-use crate::{api::path::BaseDirectory, Runtime};
+use crate::{
+  api::{ipc::CallbackFn, path::BaseDirectory},
+  Runtime,
+};
 #[cfg(path_all)]
 use crate::{Env, Manager};
 use std::path::PathBuf;
 #[cfg(path_all)]
 use std::path::{Component, Path, MAIN_SEPARATOR};
 
+#[cfg(path_volumes)]
+use crate::api::path::Volume;
+#[cfg(not(path_volumes))]
+type Volume = ();
+
 use super::InvokeContext;
 use serde::Deserialize;
 use tauri_macros::{command_enum, module_command_handler, CommandModule};
@@ -39,6 +47,33 @@ pub enum Cmd {
   Basename { path: String, ext: Option<String> },
   #[cmd(path_all, "path > all")]
   IsAbsolute { path: String },
+  #[cmd(path_volumes, "path > volumes")]
+  Volumes,
+  #[cmd(path_volumes, "path > volumes")]
+  WatchVolumes { handler: CallbackFn },
+  #[cmd(path_volumes, "path > volumes")]
+  UnwatchVolumes { id: u32 },
+}
+
+/// The volume watchers of the webviews, by window label and identifier.
+#[cfg(path_volumes)]
+type VolumeWatcherStore =
+  std::sync::Mutex<std::collections::HashMap<(String, u32), crate::api::path::VolumeWatcher>>;
+
+#[cfg(path_volumes)]
+fn volume_watcher_store() -> &'static VolumeWatcherStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<VolumeWatcherStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// Stops the volume watchers of the window.
+pub(crate) fn close_window(#[allow(unused_variables)] label: &str) {
+  #[cfg(path_volumes)]
+  volume_watcher_store()
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
 }
 
 impl Cmd {
@@ -169,6 +204,45 @@ impl Cmd {
   fn is_absolute<R: Runtime>(_context: InvokeContext<R>, path: String) -> super::Result<bool> {
     Ok(Path::new(&path).is_absolute())
   }
+
+  #[module_command_handler(path_volumes)]
+  fn volumes<R: Runtime>(_context: InvokeContext<R>) -> super::Result<Vec<Volume>> {
+    Ok(crate::api::path::volumes())
+  }
+
+  #[module_command_handler(path_volumes)]
+  fn watch_volumes<R: Runtime>(
+    context: InvokeContext<R>,
+    handler: CallbackFn,
+  ) -> super::Result<u32> {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+    let label = context.window.label().to_string();
+    let window = context.window;
+    let watcher = crate::api::path::watch_volumes(move |event| {
+      if let Ok(js) = crate::api::ipc::format_callback(handler, &event) {
+        let _ = window.eval(js.as_str());
+      }
+    })?;
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    volume_watcher_store()
+      .lock()
+      .unwrap()
+      .insert((label, id), watcher);
+    Ok(id)
+  }
+
+  #[module_command_handler(path_volumes)]
+  fn unwatch_volumes<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    // dropping the watcher stops it
+    volume_watcher_store()
+      .lock()
+      .unwrap()
+      .remove(&(context.window.label().to_string(), id));
+    Ok(())
+  }
 }
 
 /// Normalize a path, removing things like `.` and `..`, this snippet is taken from cargo's paths util.
@@ -247,7 +321,7 @@ fn normalize_path_no_absolute(path: &Path) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-  use crate::api::path::BaseDirectory;
+  use crate::api::{ipc::CallbackFn, path::BaseDirectory};
 
   #[tauri_macros::module_command_test(path_all, "path > all")]
   #[quickcheck_macros::quickcheck]
@@ -280,4 +354,16 @@ mod tests {
   #[tauri_macros::module_command_test(path_all, "path > all")]
   #[quickcheck_macros::quickcheck]
   fn is_absolute(_path: String) {}
+
+  #[tauri_macros::module_command_test(path_volumes, "path > volumes")]
+  #[quickcheck_macros::quickcheck]
+  fn volumes() {}
+
+  #[tauri_macros::module_command_test(path_volumes, "path > volumes")]
+  #[quickcheck_macros::quickcheck]
+  fn watch_volumes(_handler: CallbackFn) {}
+
+  #[tauri_macros::module_command_test(path_volumes, "path > volumes")]
+  #[quickcheck_macros::quickcheck]
+  fn unwatch_volumes(_id: u32) {}
 }
@@ -0,0 +1,273 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::InvokeContext;
+#[cfg(serial_list)]
+use crate::api::serial::UsbDevice;
+#[cfg(serial_open)]
+use crate::api::serial::{PortListener, SerialPort};
+use crate::{
+  api::{
+    ipc::CallbackFn,
+    serial::{PortOptions, SerialPortInfo},
+  },
+  Manager, Runtime,
+};
+use serde::Deserialize;
+use tauri_macros::{command_enum, module_command_handler, CommandModule};
+
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+  },
+};
+#[cfg(serial_open)]
+use std::{io::Write, sync::Arc};
+
+type PortId = u32;
+
+#[cfg(serial_open)]
+struct OpenPort {
+  port: Arc<Mutex<SerialPort>>,
+  _listener: PortListener,
+}
+
+/// The ports opened by the webviews, by window label and identifier.
+#[cfg(serial_open)]
+type PortStore = Mutex<HashMap<(String, PortId), OpenPort>>;
+
+#[cfg(serial_open)]
+fn port_store() -> &'static PortStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<PortStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+#[cfg(serial_open)]
+fn port(label: &str, id: PortId) -> super::Result<Arc<Mutex<SerialPort>>> {
+  port_store()
+    .lock()
+    .unwrap()
+    .get(&(label.to_string(), id))
+    .map(|open| open.port.clone())
+    .ok_or_else(|| crate::Error::SerialPortNotOpened(id).into_anyhow())
+}
+
+/// The device watchers of the webviews, by window label and identifier.
+#[cfg(serial_list)]
+type DeviceWatcherStore = Mutex<HashMap<(String, u32), crate::api::serial::DeviceWatcher>>;
+
+#[cfg(serial_list)]
+fn device_watcher_store() -> &'static DeviceWatcherStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<DeviceWatcherStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// Closes the ports and stops the device watchers of the window.
+pub(crate) fn close_window(#[allow(unused_variables)] label: &str) {
+  #[cfg(serial_open)]
+  port_store()
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
+  #[cfg(serial_list)]
+  device_watcher_store()
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
+}
+
+/// The identifier of the open ports and device watchers.
+#[allow(dead_code)]
+fn next_id() -> u32 {
+  static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+  NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// List the serial ports.
+  #[cmd(serial_list, "serial > list")]
+  Ports,
+  /// List the USB devices.
+  #[cmd(serial_list, "serial > list")]
+  UsbDevices,
+  /// Watch the serial ports and USB devices being connected and disconnected.
+  #[cmd(serial_list, "serial > list")]
+  WatchDevices { handler: CallbackFn },
+  /// Stop a device watcher.
+  #[cmd(serial_list, "serial > list")]
+  UnwatchDevices { id: u32 },
+  /// Open a serial port, delivering the received bytes to the callback.
+  #[cmd(serial_open, "serial > open")]
+  Open {
+    path: String,
+    options: Option<PortOptions>,
+    callback: CallbackFn,
+  },
+  /// Write bytes to an open port.
+  #[cmd(serial_open, "serial > open")]
+  Write { id: PortId, data: Vec<u8> },
+  /// Close an open port.
+  #[cmd(serial_open, "serial > open")]
+  Close { id: PortId },
+}
+
+impl Cmd {
+  /// Only the ports and devices allowed on the `serial` scope are listed.
+  #[module_command_handler(serial_list)]
+  fn ports<R: Runtime>(context: InvokeContext<R>) -> super::Result<Vec<SerialPortInfo>> {
+    let scope = context.window.state::<crate::Scopes>().serial.clone();
+    let ports = crate::api::serial::ports()?;
+    Ok(ports.into_iter().filter(|p| scope.is_allowed(p)).collect())
+  }
+
+  #[module_command_handler(serial_list)]
+  fn usb_devices<R: Runtime>(context: InvokeContext<R>) -> super::Result<Vec<UsbDevice>> {
+    let scope = context.window.state::<crate::Scopes>().serial.clone();
+    let devices = crate::api::serial::usb_devices()?;
+    Ok(
+      devices
+        .into_iter()
+        .filter(|d| scope.is_usb_allowed(&d.info))
+        .collect(),
+    )
+  }
+
+  #[module_command_handler(serial_list)]
+  fn watch_devices<R: Runtime>(
+    context: InvokeContext<R>,
+    handler: CallbackFn,
+  ) -> super::Result<u32> {
+    let scope = context.window.state::<crate::Scopes>().serial.clone();
+    let label = context.window.label().to_string();
+    let window = context.window;
+    let watcher = crate::api::serial::watch_devices(move |event| {
+      let device = match &event {
+        crate::api::serial::DeviceEvent::Connected(device)
+        | crate::api::serial::DeviceEvent::Disconnected(device) => device,
+      };
+      if !scope.is_device_allowed(device) {
+        return;
+      }
+      if let Ok(js) = crate::api::ipc::format_callback(handler, &event) {
+        let _ = window.eval(js.as_str());
+      }
+    })?;
+
+    let id = next_id();
+    device_watcher_store()
+      .lock()
+      .unwrap()
+      .insert((label, id), watcher);
+    Ok(id)
+  }
+
+  #[module_command_handler(serial_list)]
+  fn unwatch_devices<R: Runtime>(context: InvokeContext<R>, id: u32) -> super::Result<()> {
+    // dropping the watcher stops it
+    device_watcher_store()
+      .lock()
+      .unwrap()
+      .remove(&(context.window.label().to_string(), id));
+    Ok(())
+  }
+
+  #[module_command_handler(serial_open)]
+  fn open<R: Runtime>(
+    context: InvokeContext<R>,
+    path: String,
+    options: Option<PortOptions>,
+    callback: CallbackFn,
+  ) -> super::Result<PortId> {
+    // the USB identifier is only known for the enumerated ports
+    let info = crate::api::serial::ports()
+      .unwrap_or_default()
+      .into_iter()
+      .find(|port| port.path == path)
+      .unwrap_or(SerialPortInfo { path, usb: None });
+    if !context
+      .window
+      .state::<crate::Scopes>()
+      .serial
+      .is_allowed(&info)
+    {
+      return Err(crate::Error::SerialPortNotAllowed(info.path).into_anyhow());
+    }
+
+    let port = SerialPort::open(&info.path, &options.unwrap_or_default())?;
+    let id = next_id();
+    let label = context.window.label().to_string();
+    let window = context.window;
+    let key = (label, id);
+    let key_ = key.clone();
+    let listener = port.listen(move |event| {
+      if event == crate::api::serial::SerialEvent::Closed {
+        port_store().lock().unwrap().remove(&key_);
+      }
+      if let Ok(js) = crate::api::ipc::format_callback(callback, &event) {
+        let _ = window.eval(&js);
+      }
+    })?;
+    port_store().lock().unwrap().insert(
+      key,
+      OpenPort {
+        port: Arc::new(Mutex::new(port)),
+        _listener: listener,
+      },
+    );
+    Ok(id)
+  }
+
+  #[module_command_handler(serial_open)]
+  fn write<R: Runtime>(context: InvokeContext<R>, id: PortId, data: Vec<u8>) -> super::Result<()> {
+    let port = port(context.window.label(), id)?;
+    let mut port = port.lock().unwrap();
+    port.write_all(&data)?;
+    port.flush()?;
+    Ok(())
+  }
+
+  #[module_command_handler(serial_open)]
+  fn close<R: Runtime>(context: InvokeContext<R>, id: PortId) -> super::Result<()> {
+    // dropping the listener stops it, closing the port once its last handle is dropped
+    port_store()
+      .lock()
+      .unwrap()
+      .remove(&(context.window.label().to_string(), id))
+      .map(|_| ())
+      .ok_or_else(|| crate::Error::SerialPortNotOpened(id).into_anyhow())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::ipc::CallbackFn;
+
+  #[tauri_macros::module_command_test(serial_open, "serial > open")]
+  #[quickcheck_macros::quickcheck]
+  fn open(callback: CallbackFn) {
+    // the mock context has no serial scope
+    let result = super::Cmd::open(
+      crate::test::mock_invoke_context(),
+      "/dev/ttyUSB0".into(),
+      None,
+      callback,
+    );
+    assert!(result.unwrap_err().to_string().contains("not allowed"));
+  }
+
+  #[tauri_macros::module_command_test(serial_open, "serial > open")]
+  #[quickcheck_macros::quickcheck]
+  fn write(id: super::PortId, data: Vec<u8>) {
+    let result = super::Cmd::write(crate::test::mock_invoke_context(), id, data);
+    assert!(result.is_err());
+  }
+}
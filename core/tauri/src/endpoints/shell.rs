@@ -56,6 +56,18 @@ pub struct CommandOptions {
   env: Option<HashMap<String, String>>,
   // Character encoding for stdout/stderr
   encoding: Option<String>,
+  // Runs the command in a pseudo terminal of the given size
+  pty: Option<PtySize>,
+  // Kills the process descendants when the app exits
+  #[serde(default)]
+  kill_tree_on_exit: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PtySize {
+  rows: u16,
+  cols: u16,
 }
 
 /// The API descriptor.
@@ -76,7 +88,13 @@ pub enum Cmd {
   #[cmd(shell_script, "shell > execute or shell > sidecar")]
   StdinWrite { pid: ChildId, buffer: Buffer },
   #[cmd(shell_script, "shell > execute or shell > sidecar")]
+  StdinClose { pid: ChildId },
+  #[cmd(shell_script, "shell > execute or shell > sidecar")]
+  ResizePty { pid: ChildId, rows: u16, cols: u16 },
+  #[cmd(shell_script, "shell > execute or shell > sidecar")]
   KillChild { pid: ChildId },
+  #[cmd(shell_script, "shell > execute or shell > sidecar")]
+  KillChildTree { pid: ChildId },
   #[cmd(shell_open, "shell > open")]
   Open { path: String, with: Option<String> },
 }
@@ -146,7 +164,8 @@ impl Cmd {
         command = command.current_dir(cwd);
       }
       if let Some(env) = options.env {
-        command = command.envs(env);
+        // keep the variables of the scope command
+        command = command.extend_envs(env);
       } else {
         command = command.env_clear();
       }
@@ -157,6 +176,19 @@ impl Cmd {
           return Err(anyhow::anyhow!(format!("unknown encoding {}", encoding)));
         }
       }
+      if options.kill_tree_on_exit {
+        command = command.kill_tree_on_exit();
+      }
+      if let Some(PtySize { rows, cols }) = options.pty {
+        #[cfg(feature = "process-pty")]
+        {
+          command = command.pty(crate::api::process::PtySize { rows, cols });
+        }
+        #[cfg(not(feature = "process-pty"))]
+        return Err(anyhow::anyhow!(
+          "running a command in a pseudo terminal requires the `process-pty` feature"
+        ));
+      }
       let (mut rx, child) = command.spawn()?;
 
       let pid = child.pid();
@@ -193,6 +225,35 @@ impl Cmd {
     Ok(())
   }
 
+  #[module_command_handler(shell_script)]
+  fn stdin_close<R: Runtime>(_context: InvokeContext<R>, pid: ChildId) -> super::Result<()> {
+    if let Some(child) = command_child_store().lock().unwrap().get(&pid) {
+      child.close_stdin();
+    }
+    Ok(())
+  }
+
+  #[module_command_handler(shell_script)]
+  #[allow(unused_variables)]
+  fn resize_pty<R: Runtime>(
+    _context: InvokeContext<R>,
+    pid: ChildId,
+    rows: u16,
+    cols: u16,
+  ) -> super::Result<()> {
+    #[cfg(not(feature = "process-pty"))]
+    return Err(anyhow::anyhow!(
+      "resizing a pseudo terminal requires the `process-pty` feature"
+    ));
+    #[cfg(feature = "process-pty")]
+    {
+      if let Some(child) = command_child_store().lock().unwrap().get(&pid) {
+        child.resize(crate::api::process::PtySize { rows, cols })?;
+      }
+      Ok(())
+    }
+  }
+
   #[module_command_handler(shell_script)]
   fn kill_child<R: Runtime>(_context: InvokeContext<R>, pid: ChildId) -> super::Result<()> {
     if let Some(child) = command_child_store().lock().unwrap().remove(&pid) {
@@ -201,6 +262,14 @@ impl Cmd {
     Ok(())
   }
 
+  #[module_command_handler(shell_script)]
+  fn kill_child_tree<R: Runtime>(_context: InvokeContext<R>, pid: ChildId) -> super::Result<()> {
+    if let Some(child) = command_child_store().lock().unwrap().remove(&pid) {
+      child.kill_tree()?;
+    }
+    Ok(())
+  }
+
   /// Open a (url) path with a default or specific browser opening program.
   ///
   /// See [`crate::api::shell::open`] for how it handles security-related measures.
@@ -239,6 +308,8 @@ mod tests {
         cwd: Option::arbitrary(g),
         env: Option::arbitrary(g),
         encoding: Option::arbitrary(g),
+        pty: None,
+        kill_tree_on_exit: bool::arbitrary(g),
       }
     }
   }
@@ -270,10 +341,22 @@ mod tests {
   #[quickcheck_macros::quickcheck]
   fn stdin_write(_pid: ChildId, _buffer: Buffer) {}
 
+  #[tauri_macros::module_command_test(shell_execute, "shell > execute or shell > sidecar")]
+  #[quickcheck_macros::quickcheck]
+  fn stdin_close(_pid: ChildId) {}
+
+  #[tauri_macros::module_command_test(shell_execute, "shell > execute or shell > sidecar")]
+  #[quickcheck_macros::quickcheck]
+  fn resize_pty(_pid: ChildId, _rows: u16, _cols: u16) {}
+
   #[tauri_macros::module_command_test(shell_execute, "shell > execute or shell > sidecar")]
   #[quickcheck_macros::quickcheck]
   fn kill_child(_pid: ChildId) {}
 
+  #[tauri_macros::module_command_test(shell_execute, "shell > execute or shell > sidecar")]
+  #[quickcheck_macros::quickcheck]
+  fn kill_child_tree(_pid: ChildId) {}
+
   #[tauri_macros::module_command_test(shell_open, "shell > open")]
   #[quickcheck_macros::quickcheck]
   fn open(_path: String, _with: Option<String>) {}
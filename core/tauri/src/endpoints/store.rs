@@ -0,0 +1,178 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#![allow(unused_imports)]
+
+use super::InvokeContext;
+use crate::{
+  api::store::{ExecuteResult, Migration, Row, Store},
+  Runtime, Window,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use tauri_macros::{command_enum, module_command_handler, CommandModule};
+
+use std::{collections::HashMap, sync::Mutex};
+
+/// The stores opened by the webviews, by window label and name.
+static STORES: Lazy<Mutex<HashMap<(String, String), Store>>> = Lazy::new(Default::default);
+
+/// Opens the store with the given name for the window, if it is allowed on the `store` scope.
+#[allow(dead_code)]
+fn store<R: Runtime>(window: &Window<R>, name: &str) -> super::Result<Store> {
+  use crate::Manager;
+
+  if !window.state::<crate::Scopes>().store.is_allowed(name) {
+    return Err(crate::Error::StoreNotAllowed(name.into()).into_anyhow());
+  }
+
+  let mut stores = STORES.lock().unwrap();
+  let key = (window.label().to_string(), name.to_string());
+  if let Some(store) = stores.get(&key) {
+    return Ok(store.clone());
+  }
+  let store = Store::open(&window.config(), name)?;
+  stores.insert(key, store.clone());
+  Ok(store)
+}
+
+/// Closes the stores opened by the window.
+pub(crate) fn close_window(label: &str) {
+  STORES
+    .lock()
+    .unwrap()
+    .retain(|(window_label, _), _| window_label != label);
+}
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[cmd(async)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Apply the migrations newer than the schema version.
+  #[cmd(store_sql, "store > sql")]
+  Migrate {
+    store: String,
+    migrations: Vec<Migration>,
+  },
+  /// Execute a statement.
+  #[cmd(store_sql, "store > sql")]
+  Execute {
+    store: String,
+    sql: String,
+    #[serde(default)]
+    params: Vec<JsonValue>,
+  },
+  /// Run a query.
+  #[cmd(store_sql, "store > sql")]
+  Select {
+    store: String,
+    sql: String,
+    #[serde(default)]
+    params: Vec<JsonValue>,
+  },
+  /// Read a value.
+  #[cmd(store_key_value, "store > keyValue")]
+  Get { store: String, key: String },
+  /// Set a value.
+  #[cmd(store_key_value, "store > keyValue")]
+  Set {
+    store: String,
+    key: String,
+    value: JsonValue,
+  },
+  /// Delete a value.
+  #[cmd(store_key_value, "store > keyValue")]
+  Delete { store: String, key: String },
+  /// List the keys.
+  #[cmd(store_key_value, "store > keyValue")]
+  Keys { store: String },
+}
+
+impl Cmd {
+  #[module_command_handler(store_sql)]
+  async fn migrate<R: Runtime>(
+    context: InvokeContext<R>,
+    name: String,
+    migrations: Vec<Migration>,
+  ) -> super::Result<u32> {
+    let store = store(&context.window, &name)?;
+    store.migrate(migrations).await.map_err(Into::into)
+  }
+
+  #[module_command_handler(store_sql)]
+  async fn execute<R: Runtime>(
+    context: InvokeContext<R>,
+    name: String,
+    sql: String,
+    params: Vec<JsonValue>,
+  ) -> super::Result<ExecuteResult> {
+    let store = store(&context.window, &name)?;
+    store.execute(sql, params).await.map_err(Into::into)
+  }
+
+  #[module_command_handler(store_sql)]
+  async fn select<R: Runtime>(
+    context: InvokeContext<R>,
+    name: String,
+    sql: String,
+    params: Vec<JsonValue>,
+  ) -> super::Result<Vec<Row>> {
+    let store = store(&context.window, &name)?;
+    store.select(sql, params).await.map_err(Into::into)
+  }
+
+  #[module_command_handler(store_key_value)]
+  async fn get<R: Runtime>(
+    context: InvokeContext<R>,
+    name: String,
+    key: String,
+  ) -> super::Result<Option<JsonValue>> {
+    let store = store(&context.window, &name)?;
+    store.get(key).await.map_err(Into::into)
+  }
+
+  #[module_command_handler(store_key_value)]
+  async fn set<R: Runtime>(
+    context: InvokeContext<R>,
+    name: String,
+    key: String,
+    value: JsonValue,
+  ) -> super::Result<()> {
+    let store = store(&context.window, &name)?;
+    store.set(key, &value).await.map_err(Into::into)
+  }
+
+  #[module_command_handler(store_key_value)]
+  async fn delete<R: Runtime>(
+    context: InvokeContext<R>,
+    name: String,
+    key: String,
+  ) -> super::Result<bool> {
+    let store = store(&context.window, &name)?;
+    store.delete(key).await.map_err(Into::into)
+  }
+
+  #[module_command_handler(store_key_value)]
+  async fn keys<R: Runtime>(context: InvokeContext<R>, name: String) -> super::Result<Vec<String>> {
+    let store = store(&context.window, &name)?;
+    store.keys().await.map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[tauri_macros::module_command_test(store_key_value, "store > keyValue")]
+  #[quickcheck_macros::quickcheck]
+  fn keys(name: String) {
+    // the mock scope does not allow any store
+    assert!(crate::async_runtime::block_on(super::Cmd::keys(
+      crate::test::mock_invoke_context(),
+      name
+    ))
+    .is_err());
+  }
+}
@@ -0,0 +1,193 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#![allow(unused_imports)]
+
+use super::InvokeContext;
+use crate::{api::ipc::CallbackFn, Runtime};
+use serde::Deserialize;
+use tauri_macros::{command_enum, module_command_handler, CommandModule};
+
+#[cfg(websocket_connect)]
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
+#[cfg(websocket_connect)]
+use crate::api::websocket::{ConnectionConfig, Message, WebSocket, WebSocketSender};
+#[cfg(not(websocket_connect))]
+type ConnectionConfig = ();
+#[cfg(not(websocket_connect))]
+type Message = ();
+
+type ConnectionId = u32;
+/// The connections of the webviews, by window label and identifier.
+#[cfg(websocket_connect)]
+type ConnectionStore =
+  Arc<Mutex<HashMap<(String, ConnectionId), Arc<tokio::sync::Mutex<WebSocketSender>>>>>;
+
+#[cfg(websocket_connect)]
+fn connections() -> &'static ConnectionStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<ConnectionStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+#[cfg(websocket_connect)]
+fn connection(
+  label: &str,
+  id: ConnectionId,
+) -> super::Result<Arc<tokio::sync::Mutex<WebSocketSender>>> {
+  connections()
+    .lock()
+    .unwrap()
+    .get(&(label.into(), id))
+    .cloned()
+    .ok_or_else(|| crate::Error::WebsocketNotConnected(id).into_anyhow())
+}
+
+/// Closes the connections of the window.
+pub(crate) fn close_window(#[allow(unused_variables)] label: &str) {
+  #[cfg(websocket_connect)]
+  {
+    let mut connections = connections().lock().unwrap();
+    let keys = connections
+      .keys()
+      .filter(|(window_label, _)| window_label == label)
+      .cloned()
+      .collect::<Vec<_>>();
+    for key in keys {
+      if let Some(sender) = connections.remove(&key) {
+        // the reading task stops when the server acknowledges the close message
+        crate::async_runtime::spawn(async move {
+          let _ = sender.lock().await.close().await;
+        });
+      }
+    }
+  }
+}
+
+/// The API descriptor.
+#[command_enum]
+#[derive(Deserialize, CommandModule)]
+#[cmd(async)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Connect to a WebSocket server.
+  #[cmd(websocket_connect, "websocket > connect")]
+  Connect {
+    url: url::Url,
+    callback: CallbackFn,
+    config: Option<ConnectionConfig>,
+  },
+  /// Send a message on a connection.
+  #[cmd(websocket_connect, "websocket > connect")]
+  Send { id: ConnectionId, message: Message },
+  /// Close a connection.
+  #[cmd(websocket_connect, "websocket > connect")]
+  Disconnect { id: ConnectionId },
+}
+
+impl Cmd {
+  #[module_command_handler(websocket_connect)]
+  async fn connect<R: Runtime>(
+    context: InvokeContext<R>,
+    url: url::Url,
+    callback: CallbackFn,
+    config: Option<ConnectionConfig>,
+  ) -> super::Result<ConnectionId> {
+    use crate::Manager;
+    if !context
+      .window
+      .state::<crate::Scopes>()
+      .websocket
+      .is_allowed(&url)
+    {
+      return Err(crate::Error::UrlNotAllowed(url).into_anyhow());
+    }
+
+    let socket = WebSocket::connect(&url, config.unwrap_or_default()).await?;
+    let (sender, mut receiver) = socket.split();
+    let id = rand::random::<ConnectionId>();
+    let label = context.window.label().to_string();
+    connections().lock().unwrap().insert(
+      (label.clone(), id),
+      Arc::new(tokio::sync::Mutex::new(sender)),
+    );
+
+    let window = context.window;
+    crate::async_runtime::spawn(async move {
+      loop {
+        // errors are forwarded as a close message, since the connection can't be used anymore
+        let (message, closed) = match receiver.receive().await {
+          Ok(Some(Message::Close(frame))) => (Message::Close(frame), true),
+          Ok(Some(message)) => (message, false),
+          Ok(None) | Err(_) => (Message::Close(None), true),
+        };
+        if let Ok(js) = crate::api::ipc::format_callback(callback, &message) {
+          let _ = window.eval(&js);
+        }
+        if closed {
+          connections().lock().unwrap().remove(&(label, id));
+          break;
+        }
+      }
+    });
+
+    Ok(id)
+  }
+
+  #[module_command_handler(websocket_connect)]
+  async fn send<R: Runtime>(
+    context: InvokeContext<R>,
+    id: ConnectionId,
+    message: Message,
+  ) -> super::Result<()> {
+    let sender = connection(context.window.label(), id)?;
+    let mut sender = sender.lock().await;
+    sender.send(message).await?;
+    Ok(())
+  }
+
+  #[module_command_handler(websocket_connect)]
+  async fn disconnect<R: Runtime>(
+    context: InvokeContext<R>,
+    id: ConnectionId,
+  ) -> super::Result<()> {
+    let sender = connection(context.window.label(), id)?;
+    let mut sender = sender.lock().await;
+    sender.close().await?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::ipc::CallbackFn;
+
+  #[tauri_macros::module_command_test(websocket_connect, "websocket > connect")]
+  #[quickcheck_macros::quickcheck]
+  fn connect(callback: CallbackFn) {
+    // the mock context has no WebSocket scope
+    let result = crate::async_runtime::block_on(super::Cmd::connect(
+      crate::test::mock_invoke_context(),
+      "ws://localhost:8080".parse().unwrap(),
+      callback,
+      None,
+    ));
+    assert!(result.unwrap_err().to_string().contains("not allowed"));
+  }
+
+  #[tauri_macros::module_command_test(websocket_connect, "websocket > connect")]
+  #[quickcheck_macros::quickcheck]
+  fn send(id: super::ConnectionId) {
+    let result = crate::async_runtime::block_on(super::Cmd::send(
+      crate::test::mock_invoke_context(),
+      id,
+      super::Message::Text("hello".into()),
+    ));
+    assert!(result.is_err());
+  }
+}
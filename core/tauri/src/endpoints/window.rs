@@ -6,11 +6,14 @@
 
 use super::{InvokeContext, InvokeResponse};
 #[cfg(window_create)]
-use crate::runtime::{webview::WindowBuilder, Dispatch};
+use crate::runtime::webview::WindowBuilder;
 use crate::{
   runtime::{
-    window::dpi::{Position, Size},
-    UserAttentionType,
+    window::{
+      dpi::{Position, Size},
+      DropEvent,
+    },
+    Dispatch, UserAttentionType,
   },
   utils::config::WindowConfig,
   CursorIcon, Icon, Manager, Runtime,
@@ -71,6 +74,7 @@ pub enum WindowManagerCmd {
   PrimaryMonitor,
   AvailableMonitors,
   Theme,
+  Group,
   // Setters
   #[cfg(window_center)]
   Center,
@@ -140,6 +144,8 @@ pub enum WindowManagerCmd {
   #[cfg(any(debug_assertions, feature = "devtools"))]
   #[serde(rename = "__toggleDevtools")]
   InternalToggleDevtools,
+  #[serde(rename = "__drop")]
+  InternalDrop(DropEvent),
 }
 
 pub fn into_allowlist_error(variant: &str) -> crate::Error {
@@ -205,13 +211,20 @@ impl Cmd {
   #[module_command_handler(window_create)]
   async fn create_webview<R: Runtime>(
     context: InvokeContext<R>,
-    options: Box<WindowConfig>,
+    mut options: Box<WindowConfig>,
   ) -> super::Result<()> {
     let label = options.label.clone();
     let url = options.url.clone();
     let file_drop_enabled = options.file_drop_enabled;
+    // `options.csp` is ignored, so the frontend cannot loosen the policy of the windows it creates,
+    // and so is `options.kiosk`, so it cannot take over the screen and block the system shortcuts
+    options.kiosk = false;
 
-    let mut builder = crate::window::Window::builder(&context.window, label, url);
+    let mut builder = crate::window::Window::builder(&context.window, label, url)
+      .hardware_acceleration(options.hardware_acceleration)
+      .gpu_compositing(options.gpu_compositing)
+      .webgl(options.webgl)
+      .accept_drop_types(options.accept_drop_types.clone());
     if !file_drop_enabled {
       builder = builder.disable_file_drop_handler();
     }
@@ -238,6 +251,7 @@ impl Cmd {
     label: Option<String>,
     cmd: WindowManagerCmd,
   ) -> crate::Result<InvokeResponse> {
+    let source_label = context.window.label().to_string();
     let window = match label {
       Some(l) if !l.is_empty() => context
         .window
@@ -261,6 +275,7 @@ impl Cmd {
       WindowManagerCmd::PrimaryMonitor => return Ok(window.primary_monitor()?.into()),
       WindowManagerCmd::AvailableMonitors => return Ok(window.available_monitors()?.into()),
       WindowManagerCmd::Theme => return Ok(window.theme()?.into()),
+      WindowManagerCmd::Group => return Ok(window.group().into()),
       // Setters
       #[cfg(window_center)]
       WindowManagerCmd::Center => window.center()?,
@@ -345,6 +360,13 @@ impl Cmd {
           window.open_devtools();
         }
       }
+      WindowManagerCmd::InternalDrop(event) => {
+        // only the drop script of the window itself reports its drags and drops
+        if window.label() != source_label {
+          return Err(crate::Error::DropNotAccepted);
+        }
+        window.handle_drop(event)?
+      }
     }
     #[allow(unreachable_code)]
     Ok(().into())
@@ -43,6 +43,9 @@ pub enum Error {
   /// Can't access webview dispatcher because the webview was closed or not found.
   #[error("webview not found: invalid label or it was closed")]
   WebviewNotFound,
+  /// The window does not accept the dragged or dropped data.
+  #[error("the window does not accept the dropped data")]
+  DropNotAccepted,
   /// Failed to send message to webview.
   #[error("failed to send message to the webview")]
   FailedToSendMessage,
@@ -71,6 +74,12 @@ pub enum Error {
   /// Client with specified ID not found.
   #[error("http client dropped or not initialized")]
   HttpClientNotInitialized,
+  /// WebSocket connection with specified ID not found.
+  #[error("websocket connection {0} closed or not initialized")]
+  WebsocketNotConnected(u32),
+  /// Serial port with specified ID not found.
+  #[error("serial port {0} closed or not opened")]
+  SerialPortNotOpened(u32),
   /// API not whitelisted on tauri.conf.json
   #[error("'{0}' not in the allowlist (https://tauri.app/docs/api/config#tauri.allowlist)")]
   ApiNotAllowlisted(String),
@@ -88,6 +97,9 @@ pub enum Error {
   /// Error initializing plugin.
   #[error("failed to initialize plugin `{0}`: {1}")]
   PluginInitialization(String, String),
+  /// The plugin configuration does not match the plugin JSON Schema.
+  #[error("invalid configuration for plugin `{0}`:\n{1}")]
+  PluginConfig(String, String),
   /// A part of the URL is malformed or invalid. This may occur when parsing and combining
   /// user-provided URLs and paths.
   #[error("invalid url: {0}")]
@@ -104,6 +116,15 @@ pub enum Error {
   /// URL not allowed by the scope.
   #[error("url not allowed on the configured scope: {0}")]
   UrlNotAllowed(url::Url),
+  /// Serial port not allowed by the scope.
+  #[error("serial port not allowed on the configured scope: {0}")]
+  SerialPortNotAllowed(String),
+  /// mDNS service type not allowed by the scope.
+  #[error("mDNS service type not allowed on the configured scope: {0}")]
+  MdnsServiceTypeNotAllowed(String),
+  /// Store not allowed by the scope.
+  #[error("store not allowed on the configured scope: {0}")]
+  StoreNotAllowed(String),
   /// Sidecar not allowed by the configuration.
   #[error("sidecar not configured under `tauri.conf.json > tauri > bundle > externalBin`: {0}")]
   SidecarNotAllowed(PathBuf),
@@ -131,6 +152,34 @@ pub enum Error {
   /// The Window's raw handle is invalid for the platform.
   #[error("Unexpected `raw_window_handle` for the current platform")]
   InvalidWindowHandle,
+  /// The channel was closed.
+  #[error("channel closed")]
+  ChannelClosed,
+  /// The channel has no room for more messages.
+  #[error("channel is full")]
+  ChannelFull,
+  /// Failed to serialize or deserialize an invoke message with the custom [`crate::InvokeSerializer`].
+  #[error("invoke serialization error: {0}")]
+  InvokeSerialization(Box<dyn std::error::Error + Send + Sync>),
+  /// Failed to set up the crash reporter.
+  #[cfg(feature = "crash-reporter")]
+  #[error("crash reporter error: {0}")]
+  CrashReporter(String),
+  /// Failed to measure the resource usage.
+  #[cfg(feature = "process-metrics-api")]
+  #[error("failed to measure the metrics: {0}")]
+  Metrics(String),
+  /// Failed to start the localhost server.
+  #[cfg(feature = "localhost")]
+  #[error("failed to start the localhost server: {0}")]
+  Localhost(String),
+  /// Failed to load the translations.
+  #[cfg(feature = "i18n")]
+  #[error("failed to load the translations: {0}")]
+  I18n(String),
+  /// Failed to capture the window contents.
+  #[error("failed to capture the window: {0}")]
+  WindowCapture(String),
 }
 
 pub(crate) fn into_anyhow<T: std::fmt::Display>(err: T) -> anyhow::Error {
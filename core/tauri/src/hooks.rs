@@ -4,13 +4,23 @@
 
 use crate::{
   api::ipc::{format_callback, format_callback_result, CallbackFn},
-  app::App,
+  app::{App, AppHandle},
+  runtime::http::{Request as HttpRequest, Response as HttpResponse},
   Runtime, StateManager, Window,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use serialize_to_javascript::{default_template, Template};
-use std::{future::Future, sync::Arc};
+use std::{
+  future::Future,
+  pin::Pin,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+use url::Url;
+
+#[cfg(feature = "ipc-msgpack")]
+use std::{collections::HashMap, ops::Range};
 
 use tauri_macros::default_runtime;
 
@@ -18,6 +28,15 @@ use tauri_macros::default_runtime;
 pub type SetupHook<R> =
   Box<dyn FnOnce(&mut App<R>) -> Result<(), Box<dyn std::error::Error>> + Send>;
 
+/// A closure that is run when the Tauri application is setting up, returning a future awaited on the async runtime.
+pub type AsyncSetupHook<R> = Box<
+  dyn FnOnce(
+      AppHandle<R>,
+    ) -> Pin<
+      Box<dyn Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send>,
+    > + Send,
+>;
+
 /// A closure that is run every time Tauri receives a message it doesn't explicitly handle.
 pub type InvokeHandler<R> = dyn Fn(Invoke<R>) + Send + Sync + 'static;
 
@@ -28,6 +47,215 @@ pub type InvokeResponder<R> =
 /// A closure that is run once every time a window is created and loaded.
 pub type OnPageLoad<R> = dyn Fn(Window<R>, PageLoadPayload) + Send + Sync + 'static;
 
+/// A closure that is run every time an invoke request is resolved, receiving its [`InvokeMetrics`].
+pub type OnInvokeMetrics<R> = dyn Fn(&Window<R>, &InvokeMetrics) + Send + Sync + 'static;
+
+/// A closure that can modify the responses of the `tauri` and `asset` protocols before they reach the webview.
+pub type OnAssetResponse<R> =
+  dyn Fn(&AppHandle<R>, &HttpRequest, &mut HttpResponse) + Send + Sync + 'static;
+
+/// A closure that generates the response of the `tauri` protocol for a missing asset,
+/// or returns `None` to fall back to `index.html`.
+pub type AssetFallback<R> =
+  dyn Fn(&AppHandle<R>, &HttpRequest) -> Option<HttpResponse> + Send + Sync + 'static;
+
+/// A closure that decides whether IPC messages from a webview are accepted, given the URL it has loaded.
+pub type IpcOriginValidator<R> = dyn Fn(&Url, &Window<R>) -> bool + Send + Sync + 'static;
+
+/// A closure that decides whether a decrypted invoke request of the isolation pattern reaches the core.
+pub type IsolationValidator<R> =
+  dyn Fn(&Window<R>, &IsolationRequest<'_>) -> bool + Send + Sync + 'static;
+
+/// The format used to exchange invoke requests and responses with the webview.
+///
+/// The webview side of the format is defined by [`Self::initialization_script`],
+/// which must define `window.__TAURI_IPC_SERIALIZER__` as an object with a
+/// `serialize(message: object): string` and a `deserialize(data: string): any` function.
+///
+/// The serializer is only used with the `brownfield` pattern.
+pub trait InvokeSerializer: Send + Sync + 'static {
+  /// The script that defines `window.__TAURI_IPC_SERIALIZER__`.
+  fn initialization_script(&self) -> String;
+
+  /// Deserializes an invoke request sent by the webview.
+  fn deserialize_request(&self, request: &str) -> crate::Result<InvokePayload>;
+
+  /// Serializes an invoke response, which is given to the webview `deserialize` function.
+  fn serialize_response(&self, response: &JsonValue) -> crate::Result<String>;
+
+  /// Whether the requests are MessagePack maps whose arguments are deserialized directly into the command arguments.
+  #[cfg(feature = "ipc-msgpack")]
+  #[doc(hidden)]
+  fn is_message_pack(&self) -> bool {
+    false
+  }
+}
+
+/// An [`InvokeSerializer`] that exchanges the invoke messages as base64-encoded [MessagePack](https://msgpack.org).
+///
+/// Numeric arrays (including JavaScript typed arrays) are sent as MessagePack arrays,
+/// skipping the JSON stringify and parse steps on both sides.
+/// The command arguments are deserialized directly from the MessagePack values.
+/// The messages are base64-encoded since the IPC channel only transfers text.
+///
+/// # Examples
+/// ```rust,no_run
+/// tauri::Builder::default()
+///   .invoke_serializer(tauri::MessagePackSerializer);
+/// ```
+#[cfg(feature = "ipc-msgpack")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "ipc-msgpack")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MessagePackSerializer;
+
+#[cfg(feature = "ipc-msgpack")]
+impl InvokeSerializer for MessagePackSerializer {
+  fn initialization_script(&self) -> String {
+    include_str!("../scripts/msgpack.js").into()
+  }
+
+  fn deserialize_request(&self, request: &str) -> crate::Result<InvokePayload> {
+    let (mut payload, args) = MessagePackArgs::decode(request)?;
+    payload.inner = args.into_json();
+    Ok(payload)
+  }
+
+  fn serialize_response(&self, response: &JsonValue) -> crate::Result<String> {
+    rmp_serde::to_vec(response)
+      .map(base64::encode)
+      .map_err(|e| crate::Error::InvokeSerialization(Box::new(e)))
+  }
+
+  fn is_message_pack(&self) -> bool {
+    true
+  }
+}
+
+/// The arguments of a MessagePack invoke request, kept encoded
+/// so the command arguments are deserialized directly from them.
+#[cfg(feature = "ipc-msgpack")]
+#[derive(Debug)]
+pub(crate) struct MessagePackArgs {
+  bytes: Vec<u8>,
+  /// The range of each argument value on `bytes`, by key.
+  args: HashMap<String, Range<usize>>,
+  /// The arguments decoded as JSON, for [`InvokeMessage::payload`].
+  json: once_cell::sync::OnceCell<JsonValue>,
+}
+
+#[cfg(feature = "ipc-msgpack")]
+impl MessagePackArgs {
+  /// Decodes a base64-encoded MessagePack request map, splitting the invoke fields from the arguments.
+  pub(crate) fn decode(request: &str) -> crate::Result<(InvokePayload, Self)> {
+    use serde::de::{DeserializeOwned, IgnoredAny};
+
+    fn error<E: std::error::Error + Send + Sync + 'static>(e: E) -> crate::Error {
+      crate::Error::InvokeSerialization(Box::new(e))
+    }
+    fn required<T: DeserializeOwned>(
+      bytes: &[u8],
+      range: Option<Range<usize>>,
+      key: &str,
+    ) -> crate::Result<T> {
+      match range {
+        Some(range) => rmp_serde::from_slice(&bytes[range]).map_err(error),
+        None => Err(error(rmp_serde::decode::Error::Syntax(format!(
+          "missing field `{}`",
+          key
+        )))),
+      }
+    }
+    fn optional<T: DeserializeOwned>(
+      bytes: &[u8],
+      range: Option<Range<usize>>,
+    ) -> crate::Result<Option<T>> {
+      match range {
+        Some(range) => rmp_serde::from_slice(&bytes[range]).map_err(error),
+        None => Ok(None),
+      }
+    }
+
+    let bytes = base64::decode(request).map_err(error)?;
+    let mut reader = bytes.as_slice();
+    let len = match reader.split_first() {
+      Some((&marker, rest)) if marker & 0xf0 == 0x80 => {
+        reader = rest;
+        usize::from(marker & 0x0f)
+      }
+      Some((&0xde, rest)) if rest.len() >= 2 => {
+        reader = &rest[2..];
+        usize::from(u16::from_be_bytes([rest[0], rest[1]]))
+      }
+      Some((&0xdf, rest)) if rest.len() >= 4 => {
+        reader = &rest[4..];
+        u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize
+      }
+      _ => {
+        return Err(error(rmp_serde::decode::Error::Syntax(
+          "the invoke request is not a map".into(),
+        )))
+      }
+    };
+
+    // every entry takes at least two bytes, so a forged length cannot reserve more than the request
+    let mut args = HashMap::with_capacity(len.min(reader.len() / 2));
+    for _ in 0..len {
+      let key =
+        String::deserialize(&mut rmp_serde::Deserializer::new(&mut reader)).map_err(error)?;
+      let start = bytes.len() - reader.len();
+      IgnoredAny::deserialize(&mut rmp_serde::Deserializer::new(&mut reader)).map_err(error)?;
+      args.insert(key, start..bytes.len() - reader.len());
+    }
+
+    let payload = InvokePayload {
+      cmd: required(&bytes, args.remove("cmd"), "cmd")?,
+      tauri_module: optional(&bytes, args.remove("__tauriModule"))?,
+      callback: required(&bytes, args.remove("callback"), "callback")?,
+      error: required(&bytes, args.remove("error"), "error")?,
+      inner: JsonValue::Null,
+    };
+    Ok((
+      payload,
+      Self {
+        bytes,
+        args,
+        json: Default::default(),
+      },
+    ))
+  }
+
+  /// The encoded value of the argument.
+  pub(crate) fn get(&self, key: &str) -> Option<&[u8]> {
+    self.args.get(key).map(|range| &self.bytes[range.clone()])
+  }
+
+  /// The size of the request.
+  fn size(&self) -> usize {
+    self.bytes.len()
+  }
+
+  /// The arguments decoded as JSON.
+  fn json(&self) -> &JsonValue {
+    self.json.get_or_init(|| self.decode_json())
+  }
+
+  fn into_json(mut self) -> JsonValue {
+    self.json.take().unwrap_or_else(|| self.decode_json())
+  }
+
+  fn decode_json(&self) -> JsonValue {
+    self
+      .args
+      .iter()
+      .map(|(key, range)| {
+        let value = rmp_serde::from_slice(&self.bytes[range.clone()]).unwrap_or_default();
+        (key.clone(), value)
+      })
+      .collect::<serde_json::Map<_, _>>()
+      .into()
+  }
+}
+
 // todo: why is this derive broken but the output works manually?
 #[derive(Template)]
 #[default_template("../scripts/ipc.js")]
@@ -46,8 +274,15 @@ pub(crate) struct IsolationJavascript<'a> {
 
 /// The payload for the [`OnPageLoad`] hook.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PageLoadPayload {
   url: String,
+  #[serde(default = "default_main_frame")]
+  is_main_frame: bool,
+}
+
+fn default_main_frame() -> bool {
+  true
 }
 
 impl PageLoadPayload {
@@ -55,6 +290,91 @@ impl PageLoadPayload {
   pub fn url(&self) -> &str {
     &self.url
   }
+
+  /// Whether the page was loaded on the main frame of the webview instead of a child frame such as an iframe.
+  pub fn is_main_frame(&self) -> bool {
+    self.is_main_frame
+  }
+}
+
+/// Measurements of an invoke request, given to the [`OnInvokeMetrics`] hook.
+#[derive(Debug, Clone)]
+pub struct InvokeMetrics {
+  /// The invoked command. Core API calls are reported as `tauri:<module>`.
+  pub command: String,
+  /// The label of the window that sent the request.
+  pub window: String,
+  /// The size in bytes of the JSON arguments.
+  pub payload_size: usize,
+  /// The time between receiving the request and resolving it.
+  pub duration: Duration,
+  /// The rejection value if the request failed.
+  pub error: Option<String>,
+}
+
+/// A decrypted invoke request of the isolation pattern, given to the [`IsolationValidator`] hook.
+#[derive(Debug, Clone, Copy)]
+pub struct IsolationRequest<'a> {
+  /// The invoked command. Core API calls are reported as `tauri:<module>`.
+  pub command: &'a str,
+  /// The arguments of the command.
+  pub args: &'a JsonValue,
+}
+
+/// The measurement of an in-flight invoke request.
+#[derive(Debug)]
+pub(crate) struct InvokeMeasurement {
+  command: String,
+  payload_size: usize,
+  started: Instant,
+  #[cfg(feature = "ipc-tracing")]
+  span: tracing::Span,
+}
+
+impl InvokeMeasurement {
+  pub(crate) fn new(command: String, window_label: &str, payload_size: usize) -> Self {
+    Self {
+      #[cfg(feature = "ipc-tracing")]
+      span: tracing::info_span!(
+        "ipc.request",
+        command = %command,
+        window = %window_label,
+        payload_size,
+        duration_ms = tracing::field::Empty,
+        error = tracing::field::Empty,
+      ),
+      command,
+      payload_size,
+      started: Instant::now(),
+    }
+  }
+
+  fn finish<R: Runtime>(&self, window: &Window<R>, response: &InvokeResponse) {
+    let duration = self.started.elapsed();
+    let error = match response {
+      InvokeResponse::Ok(_) => None,
+      InvokeResponse::Err(InvokeError(JsonValue::String(e))) => Some(e.clone()),
+      InvokeResponse::Err(InvokeError(e)) => Some(e.to_string()),
+    };
+
+    #[cfg(feature = "ipc-tracing")]
+    {
+      self
+        .span
+        .record("duration_ms", &(duration.as_secs_f64() * 1000.0));
+      if let Some(error) = &error {
+        self.span.record("error", &error.as_str());
+      }
+    }
+
+    window.run_on_invoke_metrics(&InvokeMetrics {
+      command: self.command.clone(),
+      window: window.label().into(),
+      payload_size: self.payload_size,
+      duration,
+      error,
+    });
+  }
 }
 
 /// The payload used on the IPC invoke.
@@ -74,6 +394,49 @@ pub struct InvokePayload {
   pub inner: JsonValue,
 }
 
+/// The arguments of an invoke request.
+#[derive(Debug)]
+pub(crate) enum InvokeArgs {
+  /// The arguments decoded as JSON.
+  Json(JsonValue),
+  /// The MessagePack arguments, deserialized directly into the command arguments.
+  #[cfg(feature = "ipc-msgpack")]
+  MessagePack(MessagePackArgs),
+}
+
+impl InvokeArgs {
+  /// Splits the JSON arguments from the payload.
+  pub(crate) fn split(mut payload: InvokePayload) -> (InvokePayload, Self) {
+    let args = Self::Json(payload.inner.take());
+    (payload, args)
+  }
+
+  /// The size of the request, reported on the [`InvokeMetrics`].
+  pub(crate) fn size(&self) -> usize {
+    match self {
+      Self::Json(payload) => payload.to_string().len(),
+      #[cfg(feature = "ipc-msgpack")]
+      Self::MessagePack(args) => args.size(),
+    }
+  }
+
+  fn json(&self) -> &JsonValue {
+    match self {
+      Self::Json(payload) => payload,
+      #[cfg(feature = "ipc-msgpack")]
+      Self::MessagePack(args) => args.json(),
+    }
+  }
+
+  pub(crate) fn into_json(self) -> JsonValue {
+    match self {
+      Self::Json(payload) => payload,
+      #[cfg(feature = "ipc-msgpack")]
+      Self::MessagePack(args) => args.into_json(),
+    }
+  }
+}
+
 /// The message and resolver given to a custom command.
 #[default_runtime(crate::Wry, wry)]
 #[derive(Debug)]
@@ -165,6 +528,7 @@ pub struct InvokeResolver<R: Runtime> {
   window: Window<R>,
   pub(crate) callback: CallbackFn,
   pub(crate) error: CallbackFn,
+  measurement: Option<InvokeMeasurement>,
 }
 
 impl<R: Runtime> InvokeResolver<R> {
@@ -173,17 +537,36 @@ impl<R: Runtime> InvokeResolver<R> {
       window,
       callback,
       error,
+      measurement: None,
     }
   }
 
+  /// Measures the request until it is resolved.
+  pub(crate) fn measure(mut self, measurement: InvokeMeasurement) -> Self {
+    self.measurement.replace(measurement);
+    self
+  }
+
+  /// The span of the request.
+  #[cfg(feature = "ipc-tracing")]
+  pub(crate) fn span(&self) -> Option<&tracing::Span> {
+    self.measurement.as_ref().map(|m| &m.span)
+  }
+
   /// Reply to the invoke promise with an async task.
   pub fn respond_async<T, F>(self, task: F)
   where
     T: Serialize,
     F: Future<Output = Result<T, InvokeError>> + Send + 'static,
   {
+    #[cfg(feature = "ipc-tracing")]
+    let task = tracing::Instrument::instrument(
+      task,
+      self.span().cloned().unwrap_or_else(tracing::Span::none),
+    );
     crate::async_runtime::spawn(async move {
-      Self::return_task(self.window, task, self.callback, self.error).await;
+      let response = task.await.into();
+      self.finish(response);
     });
   }
 
@@ -192,38 +575,45 @@ impl<R: Runtime> InvokeResolver<R> {
   where
     F: Future<Output = Result<JsonValue, InvokeError>> + Send + 'static,
   {
+    #[cfg(feature = "ipc-tracing")]
+    let task = tracing::Instrument::instrument(
+      task,
+      self.span().cloned().unwrap_or_else(tracing::Span::none),
+    );
     crate::async_runtime::spawn(async move {
       let response = match task.await {
         Ok(ok) => InvokeResponse::Ok(ok),
         Err(err) => InvokeResponse::Err(err),
       };
-      Self::return_result(self.window, response, self.callback, self.error)
+      self.finish(response);
     });
   }
 
   /// Reply to the invoke promise with a serializable value.
   pub fn respond<T: Serialize>(self, value: Result<T, InvokeError>) {
-    Self::return_result(self.window, value.into(), self.callback, self.error)
+    self.finish(value.into())
   }
 
   /// Resolve the invoke promise with a value.
   pub fn resolve<T: Serialize>(self, value: T) {
-    Self::return_result(self.window, Ok(value).into(), self.callback, self.error)
+    self.finish(Ok(value).into())
   }
 
   /// Reject the invoke promise with a value.
   pub fn reject<T: Serialize>(self, value: T) {
-    Self::return_result(
-      self.window,
-      Result::<(), _>::Err(value.into()).into(),
-      self.callback,
-      self.error,
-    )
+    self.finish(Result::<(), _>::Err(value.into()).into())
   }
 
   /// Reject the invoke promise with an [`InvokeError`].
   pub fn invoke_error(self, error: InvokeError) {
-    Self::return_result(self.window, error.into(), self.callback, self.error)
+    self.finish(error.into())
+  }
+
+  fn finish(self, response: InvokeResponse) {
+    if let Some(measurement) = &self.measurement {
+      measurement.finish(&self.window, &response);
+    }
+    Self::return_result(self.window, response, self.callback, self.error)
   }
 
   /// Asynchronously executes the given task
@@ -269,16 +659,46 @@ pub fn window_invoke_responder<R: Runtime>(
   success_callback: CallbackFn,
   error_callback: CallbackFn,
 ) {
-  let callback_string =
+  let callback_string = if let Some(serializer) = window.invoke_serializer() {
+    let (callback, value) = match response.into_result() {
+      Ok(value) => (success_callback, value),
+      Err(value) => (error_callback, value),
+    };
+    match format_serialized_callback(&*serializer, callback, &value) {
+      Ok(callback_string) => callback_string,
+      Err(e) => format_callback(error_callback, &e.to_string())
+        .expect("unable to serialize response string to json"),
+    }
+  } else {
     match format_callback_result(response.into_result(), success_callback, error_callback) {
       Ok(callback_string) => callback_string,
       Err(e) => format_callback(error_callback, &e.to_string())
         .expect("unable to serialize response string to json"),
-    };
+    }
+  };
 
   let _ = window.eval(&callback_string);
 }
 
+/// Formats a callback call whose argument is decoded by `window.__TAURI_IPC_SERIALIZER__`.
+fn format_serialized_callback(
+  serializer: &dyn InvokeSerializer,
+  callback: CallbackFn,
+  value: &JsonValue,
+) -> crate::Result<String> {
+  let data = serializer.serialize_response(value)?;
+  Ok(format!(
+    r#"
+    if (window["_{fn}"]) {{
+      window["_{fn}"](window.__TAURI_IPC_SERIALIZER__.deserialize({data}))
+    }} else {{
+      console.warn("[TAURI] Couldn't find callback id {fn} in window. This happens when the app is reloaded while Rust is running an asynchronous operation.")
+    }}"#,
+    fn = callback.0,
+    data = serde_json::to_string(&data)?,
+  ))
+}
+
 /// An invoke message.
 #[default_runtime(crate::Wry, wry)]
 #[derive(Debug)]
@@ -289,8 +709,8 @@ pub struct InvokeMessage<R: Runtime> {
   pub(crate) state: Arc<StateManager>,
   /// The IPC command.
   pub(crate) command: String,
-  /// The JSON argument passed on the invoke message.
-  pub(crate) payload: JsonValue,
+  /// The arguments passed on the invoke message.
+  pub(crate) args: InvokeArgs,
 }
 
 impl<R: Runtime> InvokeMessage<R> {
@@ -299,13 +719,13 @@ impl<R: Runtime> InvokeMessage<R> {
     window: Window<R>,
     state: Arc<StateManager>,
     command: String,
-    payload: JsonValue,
+    args: InvokeArgs,
   ) -> Self {
     Self {
       window,
       state,
       command,
-      payload,
+      args,
     }
   }
 
@@ -330,7 +750,7 @@ impl<R: Runtime> InvokeMessage<R> {
   /// A reference to the payload the invoke received.
   #[inline(always)]
   pub fn payload(&self) -> &JsonValue {
-    &self.payload
+    self.args.json()
   }
 
   /// The state manager associated with the application
@@ -345,3 +765,80 @@ impl<R: Runtime> InvokeMessage<R> {
     &self.state
   }
 }
+
+#[cfg(all(test, feature = "ipc-msgpack"))]
+mod tests {
+  use super::{
+    InvokeArgs, InvokeMessage, InvokeSerializer, MessagePackArgs, MessagePackSerializer,
+  };
+  use crate::{
+    command::{CommandArg, CommandItem},
+    Manager,
+  };
+  use serde_json::json;
+
+  fn request() -> String {
+    let request = json!({
+      "cmd": "sum",
+      "callback": 1234,
+      "error": 5678,
+      "values": [1.5, -2, 3]
+    });
+    base64::encode(rmp_serde::to_vec(&request).unwrap())
+  }
+
+  #[test]
+  fn message_pack_request() {
+    let payload = MessagePackSerializer
+      .deserialize_request(&request())
+      .unwrap();
+    assert_eq!(payload.cmd, "sum");
+    assert_eq!(payload.tauri_module, None);
+    assert_eq!(payload.callback.0, 1234);
+    assert_eq!(payload.error.0, 5678);
+    assert_eq!(payload.inner, json!({ "values": [1.5, -2, 3] }));
+
+    let response = MessagePackSerializer
+      .serialize_response(&payload.inner)
+      .unwrap();
+    let decoded: serde_json::Value =
+      rmp_serde::from_slice(&base64::decode(response).unwrap()).unwrap();
+    assert_eq!(decoded, payload.inner);
+  }
+
+  #[test]
+  fn message_pack_command_args() {
+    let (payload, args) = MessagePackArgs::decode(&request()).unwrap();
+    assert_eq!(payload.cmd, "sum");
+    assert!(args.get("cmd").is_none());
+
+    let app = crate::test::mock_app();
+    let message = InvokeMessage::new(
+      app.get_window("main").unwrap(),
+      app.handle().manager.state(),
+      payload.cmd,
+      InvokeArgs::MessagePack(args),
+    );
+    let item = |key| CommandItem {
+      name: "sum",
+      key,
+      message: &message,
+    };
+    let values = Vec::<f64>::from_command(item("values")).unwrap();
+    assert_eq!(values, vec![1.5, -2.0, 3.0]);
+    let missing = Option::<u32>::from_command(item("missing")).unwrap();
+    assert_eq!(missing, None);
+    assert!(u32::from_command(item("missing")).is_err());
+
+    // the JSON payload is only decoded when requested
+    assert_eq!(message.payload(), &json!({ "values": [1.5, -2, 3] }));
+  }
+
+  #[test]
+  fn message_pack_invalid_request() {
+    let request = base64::encode(rmp_serde::to_vec(&json!([1, 2])).unwrap());
+    assert!(MessagePackArgs::decode(&request).is_err());
+    let request = base64::encode(rmp_serde::to_vec(&json!({ "cmd": "sum" })).unwrap());
+    assert!(MessagePackArgs::decode(&request).is_err());
+  }
+}
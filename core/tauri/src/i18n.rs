@@ -0,0 +1,384 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The translations configured on `tauri > i18n`.
+
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::RwLock,
+};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use serde_json::Value as JsonValue;
+use unic_langid::LanguageIdentifier;
+
+use crate::{
+  log::warn,
+  utils::{config::I18nConfig, platform::resource_dir},
+  Env, PackageInfo,
+};
+
+/// The translations of a locale.
+enum Catalog {
+  Fluent(Box<FluentBundle<FluentResource>>),
+  /// The messages by key, with the nested objects flattened to dotted keys.
+  Json(HashMap<String, String>),
+}
+
+impl Catalog {
+  fn parse_fluent(locale: &str, source: String) -> crate::Result<Self> {
+    let error = |message: String| crate::Error::I18n(format!("{}: {}", locale, message));
+    let language: LanguageIdentifier = locale.parse().map_err(|e| error(format!("{}", e)))?;
+    let resource =
+      FluentResource::try_new(source).map_err(|(_, errors)| error(format!("{:?}", errors)))?;
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // the Unicode isolation marks are visible on the native menus and dialogs
+    bundle.set_use_isolating(false);
+    bundle
+      .add_resource(resource)
+      .map_err(|errors| error(format!("{:?}", errors)))?;
+    Ok(Self::Fluent(Box::new(bundle)))
+  }
+
+  fn parse_json(locale: &str, source: &str) -> crate::Result<Self> {
+    fn flatten(prefix: &str, value: JsonValue, messages: &mut HashMap<String, String>) {
+      match value {
+        JsonValue::Object(object) => {
+          for (key, value) in object {
+            let key = if prefix.is_empty() {
+              key
+            } else {
+              format!("{}.{}", prefix, key)
+            };
+            flatten(&key, value, messages);
+          }
+        }
+        JsonValue::String(message) => {
+          messages.insert(prefix.into(), message);
+        }
+        value => {
+          messages.insert(prefix.into(), value.to_string());
+        }
+      }
+    }
+
+    let value =
+      serde_json::from_str(source).map_err(|e| crate::Error::I18n(format!("{}: {}", locale, e)))?;
+    let mut messages = HashMap::new();
+    flatten("", value, &mut messages);
+    Ok(Self::Json(messages))
+  }
+
+  fn translate(&self, key: &str, args: &[(&str, JsonValue)]) -> Option<String> {
+    match self {
+      Self::Fluent(bundle) => {
+        let pattern = bundle.get_message(key)?.value()?;
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+          let value = match value {
+            JsonValue::Number(number) => FluentValue::from(number.as_f64().unwrap_or_default()),
+            JsonValue::String(string) => FluentValue::from(string.clone()),
+            value => FluentValue::from(value.to_string()),
+          };
+          fluent_args.set(*name, value);
+        }
+        let mut errors = Vec::new();
+        Some(
+          bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .into_owned(),
+        )
+      }
+      Self::Json(messages) => messages.get(key).map(|message| substitute(message, args)),
+    }
+  }
+}
+
+/// Replaces the `{name}` placeholders of the message with the arguments in a single pass,
+/// so the placeholders on the argument values are kept as is.
+fn substitute(message: &str, args: &[(&str, JsonValue)]) -> String {
+  let mut substituted = String::with_capacity(message.len());
+  let mut rest = message;
+  while let Some(start) = rest.find('{') {
+    substituted.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let argument = rest.find('}').and_then(|end| {
+      let (_, value) = args.iter().find(|(name, _)| *name == &rest[1..end])?;
+      Some((end, value))
+    });
+    match argument {
+      Some((end, value)) => {
+        match value {
+          JsonValue::String(string) => substituted.push_str(string),
+          value => substituted.push_str(&value.to_string()),
+        }
+        rest = &rest[end + 1..];
+      }
+      // not a placeholder of the arguments
+      None => {
+        substituted.push('{');
+        rest = &rest[1..];
+      }
+    }
+  }
+  substituted.push_str(rest);
+  substituted
+}
+
+/// Normalizes a locale for comparisons, e.g. `en_us` to `en-US`.
+fn normalize(locale: &str) -> String {
+  let mut parts = locale.split(|c| c == '-' || c == '_');
+  let mut normalized = parts.next().unwrap_or_default().to_lowercase();
+  for part in parts {
+    normalized.push('-');
+    if part.len() == 2 {
+      normalized.push_str(&part.to_uppercase());
+    } else {
+      normalized.push_str(part);
+    }
+  }
+  normalized
+}
+
+/// The translations of the app, bundled from the directory configured on `tauri > i18n`.
+///
+/// The messages are looked up on the selected locale, then on its language, such as `fr` for `fr-CA`,
+/// and then on the default locale, returning the key itself when it is missing everywhere.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let i18n = app.i18n();
+///     println!("{}", i18n.translate("greeting", &[("name", "Ana".into())]));
+///     i18n.set_locale("fr");
+///     Ok(())
+///   });
+/// ```
+pub struct I18n {
+  default_locale: String,
+  locale: RwLock<String>,
+  catalogs: HashMap<String, Catalog>,
+}
+
+impl std::fmt::Debug for I18n {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("I18n")
+      .field("default_locale", &self.default_locale)
+      .field("locale", &self.locale)
+      .field("available_locales", &self.available_locales())
+      .finish()
+  }
+}
+
+impl I18n {
+  /// Loads the `<locale>.ftl` and `<locale>.json` files of the directory.
+  ///
+  /// A missing directory has no translations, so the keys are returned as is.
+  /// The files that cannot be loaded are logged and skipped, falling back to the default locale.
+  fn load(config: &I18nConfig, directory: &Path) -> Self {
+    let mut catalogs = HashMap::new();
+    if let Ok(entries) = std::fs::read_dir(directory) {
+      for path in entries.flatten().map(|entry| entry.path()) {
+        let locale = match path.file_stem().and_then(|stem| stem.to_str()) {
+          Some(stem) => normalize(stem),
+          None => continue,
+        };
+        let catalog = match path.extension().and_then(|ext| ext.to_str()) {
+          Some("ftl") => std::fs::read_to_string(&path)
+            .map_err(Into::into)
+            .and_then(|source| Catalog::parse_fluent(&locale, source)),
+          Some("json") => std::fs::read_to_string(&path)
+            .map_err(Into::into)
+            .and_then(|source| Catalog::parse_json(&locale, &source)),
+          _ => continue,
+        };
+        match catalog {
+          Ok(catalog) => {
+            catalogs.insert(locale, catalog);
+          }
+          Err(e) => warn!(
+            "failed to load the translations of {}: {}",
+            path.display(),
+            e
+          ),
+        }
+      }
+    }
+
+    let default_locale = normalize(&config.default_locale);
+    let mut i18n = Self {
+      locale: RwLock::new(default_locale.clone()),
+      default_locale,
+      catalogs,
+    };
+    if let Some(locale) = sys_locale::get_locale() {
+      if i18n.resolve(&normalize(&locale)).is_some() {
+        i18n.locale = RwLock::new(normalize(&locale));
+      }
+    }
+    i18n
+  }
+
+  /// The catalog of the locale or of its language.
+  fn resolve(&self, locale: &str) -> Option<&Catalog> {
+    self.catalogs.get(locale).or_else(|| {
+      let (language, _) = locale.split_once('-')?;
+      self.catalogs.get(language)
+    })
+  }
+
+  /// The selected locale, the system locale by default.
+  pub fn locale(&self) -> String {
+    self.locale.read().unwrap().clone()
+  }
+
+  /// Selects the locale, overriding the system locale.
+  pub fn set_locale(&self, locale: impl AsRef<str>) {
+    *self.locale.write().unwrap() = normalize(locale.as_ref());
+  }
+
+  /// The locales with translations, sorted.
+  pub fn available_locales(&self) -> Vec<String> {
+    let mut locales: Vec<String> = self.catalogs.keys().cloned().collect();
+    locales.sort();
+    locales
+  }
+
+  /// Translates the message, returning `None` if it is missing on the selected and default locales.
+  pub fn try_translate(&self, key: &str, args: &[(&str, JsonValue)]) -> Option<String> {
+    let locale = self.locale();
+    self
+      .resolve(&locale)
+      .and_then(|catalog| catalog.translate(key, args))
+      .or_else(|| {
+        self
+          .resolve(&self.default_locale)
+          .and_then(|catalog| catalog.translate(key, args))
+      })
+  }
+
+  /// Translates the message with the arguments, returning the key if it is missing.
+  ///
+  /// The arguments are Fluent variables, or `{name}` placeholders on JSON files.
+  pub fn translate(&self, key: &str, args: &[(&str, JsonValue)]) -> String {
+    self
+      .try_translate(key, args)
+      .unwrap_or_else(|| key.to_string())
+  }
+
+  /// Translates the titles of the default menu, using the `tauri-menu-<title>` keys such as `tauri-menu-edit`.
+  #[cfg(target_os = "macos")]
+  pub(crate) fn localize_menu(&self, mut menu: crate::Menu) -> crate::Menu {
+    for entry in &mut menu.items {
+      if let crate::MenuEntry::Submenu(submenu) = entry {
+        let key = format!("tauri-menu-{}", submenu.title.to_lowercase());
+        if let Some(title) = self.try_translate(&key, &[]) {
+          submenu.title = title;
+        }
+      }
+    }
+    menu
+  }
+}
+
+/// Loads the translations from the resources.
+pub(crate) fn load(config: Option<&I18nConfig>, package_info: &PackageInfo, env: &Env) -> I18n {
+  let config = config.cloned().unwrap_or_default();
+  let directory: PathBuf = match resource_dir(package_info, env) {
+    Ok(resource_dir) => resource_dir.join(&config.directory),
+    Err(e) => {
+      warn!("failed to resolve the translations directory: {}", e);
+      PathBuf::new()
+    }
+  };
+  I18n::load(&config, &directory)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{normalize, substitute, Catalog, I18n};
+  use std::{collections::HashMap, sync::RwLock};
+
+  fn i18n() -> I18n {
+    let mut catalogs = HashMap::new();
+    catalogs.insert(
+      "en".to_string(),
+      Catalog::parse_fluent(
+        "en",
+        "greeting = Hello, { $name }!\nfarewell = Goodbye\ntauri-menu-edit = Edit\n".into(),
+      )
+      .unwrap(),
+    );
+    catalogs.insert(
+      "fr".to_string(),
+      Catalog::parse_json(
+        "fr",
+        r#"{ "greeting": "Bonjour, {name} !", "menu": { "file": "Fichier" } }"#,
+      )
+      .unwrap(),
+    );
+    I18n {
+      default_locale: "en".into(),
+      locale: RwLock::new("en".into()),
+      catalogs,
+    }
+  }
+
+  #[test]
+  fn normalizes_locales() {
+    assert_eq!(normalize("en_us"), "en-US");
+    assert_eq!(normalize("zh-Hant-TW"), "zh-Hant-TW");
+    assert_eq!(normalize("FR"), "fr");
+  }
+
+  #[test]
+  fn translates() {
+    let i18n = i18n();
+    assert_eq!(
+      i18n.translate("greeting", &[("name", "Ana".into())]),
+      "Hello, Ana!"
+    );
+    assert_eq!(i18n.translate("missing", &[]), "missing");
+
+    // the language of the locale
+    i18n.set_locale("fr_CA");
+    assert_eq!(i18n.locale(), "fr-CA");
+    assert_eq!(
+      i18n.translate("greeting", &[("name", "Ana".into())]),
+      "Bonjour, Ana !"
+    );
+    assert_eq!(i18n.translate("menu.file", &[]), "Fichier");
+    // the default locale
+    assert_eq!(i18n.translate("farewell", &[]), "Goodbye");
+
+    assert_eq!(i18n.available_locales(), vec!["en", "fr"]);
+  }
+
+  #[test]
+  fn substitutes_placeholders_once() {
+    let args = [("name", "{count}".into()), ("count", 2.into())];
+    assert_eq!(
+      substitute("{name} has {count} {unknown} {", &args),
+      "{count} has 2 {unknown} {"
+    );
+  }
+
+  #[test]
+  fn skips_invalid_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("en.ftl"), "farewell = Goodbye\n").unwrap();
+    std::fs::write(dir.path().join("fr.json"), "{").unwrap();
+    let i18n = I18n::load(&Default::default(), dir.path());
+    assert_eq!(i18n.available_locales(), vec!["en"]);
+  }
+
+  #[test]
+  fn rejects_invalid_files() {
+    assert!(Catalog::parse_fluent("en", "greeting = {".into()).is_err());
+    assert!(Catalog::parse_json("en", "{").is_err());
+  }
+}
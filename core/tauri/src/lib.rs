@@ -10,38 +10,59 @@
 //!
 //! The following are a list of [Cargo features](https://doc.rust-lang.org/stable/cargo/reference/manifest.html#the-features-section) that can be enabled or disabled:
 //!
-//! - **wry** *(enabled by default)*: Enables the [wry](https://github.com/tauri-apps/wry) runtime. Only disable it if you want a custom runtime.
+//! - **wry** *(enabled by default)*: Enables the [wry](https://github.com/tauri-apps/wry) runtime. Only disable it if you want a custom runtime, in which case the runtime features such as **system-tray** only enable the [`Runtime`] APIs and do not pull in wry.
 //! - **dox**: Internal feature to generate Rust documentation without linking on Linux.
 //! - **objc-exception**: Wrap each msg_send! in a @try/@catch and panics if an exception is caught, preventing Objective-C from unwinding into Rust.
 //! - **linux-protocol-headers**: Enables headers support for custom protocol requests on Linux. Requires webkit2gtk v2.36 or above.
 //! - **isolation**: Enables the isolation pattern. Enabled by default if the `tauri > pattern > use` config option is set to `isolation` on the `tauri.conf.json` file.
 //! - **custom-protocol**: Feature managed by the Tauri CLI. When enabled, Tauri assumes a production environment instead of a development one.
+//! - **dev-config-reload**: Feature managed by the Tauri CLI. Enabled on `tauri dev` to reload the `tauri.conf.json` changes in the running app.
 //! - **updater**: Enables the application auto updater. Enabled by default if the `updater` config is defined on the `tauri.conf.json` file.
 //! - **devtools**: Enables the developer tools (Web inspector) and [`Window::open_devtools`]. Enabled by default on debug builds.
 //! On macOS it uses private APIs, so you can't enable it if your app will be published to the App Store.
 //! - **shell-open-api**: Enables the [`api::shell`] module.
 //! - **http-api**: Enables the [`api::http`] module.
-//! - **http-multipart**: Adds support to `multipart/form-data` requests.
+//! - **http-multipart**: Adds support to `multipart/form-data` requests, streaming the files from disk.
+//! - **websocket-api**: Enables the [`api::websocket`] module.
+//! - **serial-api**: Enables the [`api::serial`] module.
+//! - **mdns-api**: Enables the [`api::mdns`] module.
+//! - **download-api**: Enables the [`api::download`] module.
 //! - **reqwest-client**: Uses `reqwest` as HTTP client on the `http` APIs. Improves performance, but increases the bundle size.
 //! - **native-tls-vendored**: Compile and statically link to a vendored copy of OpenSSL (applies to the default HTTP client).
 //! - **reqwest-native-tls-vendored**: Compile and statically link to a vendored copy of OpenSSL (applies to the `reqwest` HTTP client).
 //! - **process-command-api**: Enables the [`api::process::Command`] APIs.
+//! - **process-pty**: Enables running [`api::process::Command`]s in a pseudo terminal.
 //! - **global-shortcut**: Enables the global shortcut APIs.
 //! - **clipboard**: Enables the clipboard APIs.
 //! - **process-relaunch-dangerous-allow-symlink-macos**: Allows the [`api::process::current_binary`] function to allow symlinks on macOS (this is dangerous, see the Security section in the documentation website).
 //! - **dialog**: Enables the [`api::dialog`] module.
 //! - **notification**: Enables the [`api::notification`] module.
 //! - **fs-extract-api**: Enabled the `tauri::api::file::Extract` API.
+//! - **fs-watch-api**: Enables the [`api::file::watch`] function.
+//! - **fs-trash-api**: Enables the [`api::file::trash`] function.
+//! - **path-volumes-api**: Enables the [`api::path::volumes`] and [`api::path::watch_volumes`] functions.
+//! - **net-status-api**: Enables the [`api::net`] module and [`RunEvent::NetworkStatusChanged`], reading the network status from the native APIs.
+//! - **store-api**: Enables the [`api::store`] module, storing values and SQL tables on SQLite databases in the app data directory.
+//! - **oauth-api**: Enables the [`api::oauth`] module, running OAuth authorization code flows with a loopback redirect.
+//! - **keyring-api**: Enables the [`api::keyring`] module, storing secrets in the credential store of the operating system.
 //! - **cli**: Enables usage of `clap` for CLI argument parsing. Enabled by default if the `cli` config is defined on the `tauri.conf.json` file.
 //! - **system-tray**: Enables application system tray API. Enabled by default if the `systemTray` config is defined on the `tauri.conf.json` file.
+//! - **localhost**: Serves the assets on a loopback `http://localhost` server instead of the custom protocol. Enabled by default if the `tauri > localhost` config is defined on the `tauri.conf.json` file.
+//! - **i18n**: Enables [`I18n`], loading the translations bundled from the directory configured on `tauri > i18n`, and its JavaScript API. Enabled by default if the `tauri > i18n` config is defined on the `tauri.conf.json` file.
 //! - **macos-private-api**: Enables features only available in **macOS**'s private APIs, currently the `transparent` window functionality and the `fullScreenEnabled` preference setting to `true`. Enabled by default if the `tauri > macosPrivateApi` config flag is set to `true` on the `tauri.conf.json` file.
 //! - **windows7-compat**: Enables compatibility with Windows 7 for the notification API.
 //! - **window-data-url**: Enables usage of data URLs on the webview.
-//! - **compression** *(enabled by default): Enables asset compression. You should only disable this if you want faster compile times in release builds - it produces larger binaries.
+//! - **compression** *(enabled by default): Enables asset compression. The assets are embedded compressed with Brotli and served with `Content-Encoding: br` when the webview accepts it. You should only disable this if you want faster compile times in release builds - it produces larger binaries.
 //! - **config-json5**: Adds support to JSON5 format for `tauri.conf.json`.
 //! - **config-toml**: Adds support to TOML format for the configuration `Tauri.toml`.
 //! - **icon-ico**: Adds support to set `.ico` window icons. Enables [`Icon::File`] and [`Icon::Raw`] variants.
 //! - **icon-png**: Adds support to set `.png` window icons. Enables [`Icon::File`] and [`Icon::Raw`] variants.
+//! - **ipc-msgpack**: Enables the [`MessagePackSerializer`] invoke serializer.
+//! - **ipc-tracing**: Records every invoke request as an `ipc.request` [`tracing`](https://docs.rs/tracing) span with the command, window, payload size, duration and error.
+//! - **test**: Enables the [`test`] module exposing the [`test::MockRuntime`], a runtime without native windows used to test applications on headless environments.
+//! - **process-metrics-api**: Enables `App::metrics` and [`Builder::metrics_interval`], measuring the CPU and memory usage of the application and its webview processes.
+//! - **crash-reporter**: Enables [`Builder::crash_reporter`], capturing panics and native crashes as minidumps.
+//! - **plugin-config-schema**: Validates the `tauri.conf.json > plugins` configuration against the JSON Schema supplied by each plugin.
 //!
 //! ## Cargo allowlist features
 //!
@@ -60,7 +81,9 @@
 //!
 //! - **dialog-all**: Enables all [Dialog APIs](https://tauri.app/en/docs/api/js/modules/dialog).
 //! - **dialog-ask**: Enables the [`ask` API](https://tauri.app/en/docs/api/js/modules/dialog#ask).
+//! - **dialog-color-picker**: Enables the [`colorPicker` API](https://tauri.app/en/docs/api/js/modules/dialog#colorpicker).
 //! - **dialog-confirm**: Enables the [`confirm` API](https://tauri.app/en/docs/api/js/modules/dialog#confirm).
+//! - **dialog-font-picker**: Enables the [`fontPicker` API](https://tauri.app/en/docs/api/js/modules/dialog#fontpicker).
 //! - **dialog-message**: Enables the [`message` API](https://tauri.app/en/docs/api/js/modules/dialog#message).
 //! - **dialog-open**: Enables the [`open` API](https://tauri.app/en/docs/api/js/modules/dialog#open).
 //! - **dialog-save**: Enables the [`save` API](https://tauri.app/en/docs/api/js/modules/dialog#save).
@@ -76,6 +99,8 @@
 //! - **fs-remove-dir**: Enables the [`removeDir` API](https://tauri.app/en/docs/api/js/modules/fs#removedir).
 //! - **fs-remove-file**: Enables the [`removeFile` API](https://tauri.app/en/docs/api/js/modules/fs#removefile).
 //! - **fs-rename-file**: Enables the [`renameFile` API](https://tauri.app/en/docs/api/js/modules/fs#renamefile).
+//! - **fs-trash**: Enables the [`trash` API](https://tauri.app/en/docs/api/js/modules/fs#trash).
+//! - **fs-watch**: Enables the [`watch` API](https://tauri.app/en/docs/api/js/modules/fs#watch).
 //! - **fs-write-file**: Enables the [`writeFile` API](https://tauri.app/en/docs/api/js/modules/fs#writefile) and the [`writeBinaryFile` API](https://tauri.app/en/docs/api/js/modules/fs#writebinaryfile).
 //!
 //! ### Global shortcut allowlist
@@ -87,6 +112,39 @@
 //! - **http-all**: Enables all [HTTP APIs](https://tauri.app/en/docs/api/js/modules/http).
 //! - **http-request**: Enables the [`request` APIs](https://tauri.app/en/docs/api/js/classes/http.client/).
 //!
+//! ### WebSocket allowlist
+//!
+//! - **websocket-all**: Enables all [WebSocket APIs](https://tauri.app/en/docs/api/js/modules/websocket).
+//! - **websocket-connect**: Enables the [`connect` API](https://tauri.app/en/docs/api/js/classes/websocket.WebSocket#connect).
+//!
+//! ### Serial allowlist
+//!
+//! - **serial-all**: Enables all [Serial APIs](https://tauri.app/en/docs/api/js/modules/serial).
+//! - **serial-list**: Enables the APIs listing and watching the serial ports and USB devices.
+//! - **serial-open**: Enables the [`open` API](https://tauri.app/en/docs/api/js/classes/serial.SerialPort#open).
+//!
+//! ### mDNS allowlist
+//!
+//! - **mdns-all**: Enables all [mDNS APIs](https://tauri.app/en/docs/api/js/modules/mdns).
+//! - **mdns-advertise**: Enables the [`advertise` API](https://tauri.app/en/docs/api/js/modules/mdns#advertise).
+//! - **mdns-browse**: Enables the [`browse` API](https://tauri.app/en/docs/api/js/modules/mdns#browse).
+//!
+//! ### Store allowlist
+//!
+//! - **store-all**: Enables all [Store APIs](https://tauri.app/en/docs/api/js/modules/store).
+//! - **store-key-value**: Enables the [`get`](https://tauri.app/en/docs/api/js/classes/store.Store#get), [`set`](https://tauri.app/en/docs/api/js/classes/store.Store#set), [`delete`](https://tauri.app/en/docs/api/js/classes/store.Store#delete) and [`keys`](https://tauri.app/en/docs/api/js/classes/store.Store#keys) APIs.
+//! - **store-sql**: Enables the [`migrate`](https://tauri.app/en/docs/api/js/classes/store.Store#migrate), [`execute`](https://tauri.app/en/docs/api/js/classes/store.Store#execute) and [`select`](https://tauri.app/en/docs/api/js/classes/store.Store#select) APIs.
+//!
+//! ### OAuth allowlist
+//!
+//! - **oauth-all**: Enables all [OAuth APIs](https://tauri.app/en/docs/api/js/modules/oauth).
+//! - **oauth-authenticate**: Enables the [`authenticate` API](https://tauri.app/en/docs/api/js/modules/oauth#authenticate).
+//!
+//! ### Log allowlist
+//!
+//! - **log-all**: Enables all [Log APIs](https://tauri.app/en/docs/api/js/modules/log).
+//! - **log-write**: Enables the [`log` API](https://tauri.app/en/docs/api/js/modules/log#log) and its shorthands, and forwarding the `console` messages with `tauri > log > webviewConsole`.
+//!
 //! ### Notification allowlist
 //!
 //! - **notification-all**: Enables all [Notification APIs](https://tauri.app/en/docs/api/js/modules/notification).
@@ -94,15 +152,18 @@
 //! ### OS allowlist
 //!
 //! - **os-all**: Enables all [OS APIs](https://tauri.app/en/docs/api/js/modules/os).
+//! - **os-network-status**: Enables the [`networkStatus` API](https://tauri.app/en/docs/api/js/modules/os#networkstatus) and the [`onNetworkStatusChange` API](https://tauri.app/en/docs/api/js/modules/os#onnetworkstatuschange). Not enabled by `os-all`.
 //!
 //! ### Path allowlist
 //!
 //! - **path-all**: Enables all [Path APIs](https://tauri.app/en/docs/api/js/modules/path).
+//! - **path-volumes**: Enables the [`volumes` API](https://tauri.app/en/docs/api/js/modules/path#volumes) and the [`onVolumeChange` API](https://tauri.app/en/docs/api/js/modules/path#onvolumechange). Not enabled by `path-all`.
 //!
 //! ### Process allowlist
 //!
 //! - **process-all**: Enables all [Process APIs](https://tauri.app/en/docs/api/js/modules/process).
 //! - **process-exit**: Enables the [`exit` API](https://tauri.app/en/docs/api/js/modules/process#exit).
+//! - **process-metrics**: Emits the `tauri://metrics` event to the webviews with the measures of [`Builder::metrics_interval`]. Not enabled by `process-all`.
 //! - **process-relaunch**: Enables the [`relaunch` API](https://tauri.app/en/docs/api/js/modules/process#relaunch).
 //!
 //! ### Protocol allowlist
@@ -172,19 +233,38 @@ pub use tauri_macros::{command, generate_handler};
 pub mod api;
 pub(crate) mod app;
 pub mod async_runtime;
+pub mod channel;
 pub mod command;
+#[cfg(config_reload)]
+mod config_watcher;
+#[cfg(all(desktop, feature = "crash-reporter"))]
+mod crash_reporter;
+#[cfg(all(dev, desktop))]
+mod dev_session;
 /// The Tauri API endpoints.
 mod endpoints;
 mod error;
 mod event;
 mod hooks;
+#[cfg(feature = "i18n")]
+mod i18n;
+#[cfg(feature = "localhost")]
+mod localhost;
+pub mod log;
 mod manager;
+#[cfg(feature = "process-metrics-api")]
+mod metrics;
 mod pattern;
 pub mod plugin;
+pub mod protocol;
 pub mod window;
+#[cfg(desktop)]
+mod window_state;
 use tauri_runtime as runtime;
 /// The allowlist scopes.
 pub mod scope;
+#[cfg(desktop)]
+mod splashscreen;
 mod state;
 #[cfg(updater)]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "updater")))]
@@ -233,15 +313,17 @@ pub use {
     RunEvent, WindowEvent,
   },
   self::hooks::{
-    Invoke, InvokeError, InvokeHandler, InvokeMessage, InvokePayload, InvokeResolver,
-    InvokeResponder, InvokeResponse, OnPageLoad, PageLoadPayload, SetupHook,
+    AssetFallback, AsyncSetupHook, Invoke, InvokeError, InvokeHandler, InvokeMessage,
+    InvokeMetrics, InvokePayload, InvokeResolver, InvokeResponder, InvokeResponse,
+    InvokeSerializer, IpcOriginValidator, IsolationRequest, IsolationValidator, OnAssetResponse,
+    OnInvokeMetrics, OnPageLoad, PageLoadPayload, SetupHook,
   },
   self::manager::Asset,
   self::runtime::{
     webview::WebviewAttributes,
     window::{
       dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Pixel, Position, Size},
-      CursorIcon, FileDropEvent,
+      CursorIcon, DropData, DropEvent, FileDropEvent,
     },
     RunIteration, UserAttentionType,
   },
@@ -255,6 +337,10 @@ pub use {
   scope::*,
 };
 
+#[cfg(feature = "ipc-msgpack")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "ipc-msgpack")))]
+pub use self::hooks::MessagePackSerializer;
+
 #[cfg(feature = "clipboard")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "clipboard")))]
 pub use self::runtime::ClipboardManager;
@@ -263,6 +349,21 @@ pub use self::runtime::ClipboardManager;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "global-shortcut")))]
 pub use self::runtime::GlobalShortcutManager;
 
+#[cfg(desktop)]
+pub use self::window_state::WindowStateOptions;
+
+#[cfg(all(desktop, feature = "crash-reporter"))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "crash-reporter")))]
+pub use self::crash_reporter::{CrashKind, CrashReport, CrashReporter};
+
+#[cfg(feature = "process-metrics-api")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "process-metrics-api")))]
+pub use self::metrics::{Metrics, ProcessMetrics, METRICS_EVENT};
+
+#[cfg(feature = "i18n")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "i18n")))]
+pub use self::i18n::I18n;
+
 /// Updater events.
 #[cfg(updater)]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "updater")))]
@@ -318,6 +419,21 @@ pub enum EventLoopMessage {
   #[cfg(updater)]
   #[cfg_attr(doc_cfg, doc(cfg(feature = "updater")))]
   Updater(UpdaterEvent),
+  /// The configuration was reloaded while running `tauri dev`.
+  ConfigChanged {
+    /// The reloaded configuration.
+    config: Arc<Config>,
+    /// The changed configuration sections.
+    sections: Vec<String>,
+  },
+  /// The periodic resource usage measure.
+  #[cfg(feature = "process-metrics-api")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "process-metrics-api")))]
+  Metrics(Metrics),
+  /// The network status changed.
+  #[cfg(all(desktop, feature = "net-status-api"))]
+  #[cfg_attr(doc_cfg, doc(cfg(all(desktop, feature = "net-status-api"))))]
+  NetworkStatusChanged(api::net::NetworkStatus),
 }
 
 /// The webview runtime interface. A wrapper around [`runtime::Runtime`] with the proper user event type associated.
@@ -464,6 +580,8 @@ pub struct Context<A: Assets> {
   pub(crate) pattern: Pattern,
   #[cfg(shell_scope)]
   pub(crate) shell_scope: scope::ShellScopeConfig,
+  pub(crate) permissions_manifest: Option<&'static str>,
+  pub(crate) inspect_manifest: Option<&'static str>,
 }
 
 impl<A: Assets> fmt::Debug for Context<A> {
@@ -548,6 +666,35 @@ impl<A: Assets> Context<A> {
     &self.pattern
   }
 
+  /// The JSON manifest of the permissions granted to the webview, generated at build time.
+  ///
+  /// It is printed when the application is started with the `--tauri-permissions` flag.
+  #[inline(always)]
+  pub fn permissions_manifest(&self) -> Option<&'static str> {
+    self.permissions_manifest
+  }
+
+  /// A mutable reference to the permission manifest.
+  #[inline(always)]
+  pub fn permissions_manifest_mut(&mut self) -> &mut Option<&'static str> {
+    &mut self.permissions_manifest
+  }
+
+  /// The configuration, permissions and assets the application was built with, generated at build time.
+  ///
+  /// It is stored between markers so `tauri inspect` can read it from the binary,
+  /// and printed without them when the application is started with the `--tauri-inspect` flag.
+  #[inline(always)]
+  pub fn inspect_manifest(&self) -> Option<&'static str> {
+    self.inspect_manifest
+  }
+
+  /// A mutable reference to the inspect manifest.
+  #[inline(always)]
+  pub fn inspect_manifest_mut(&mut self) -> &mut Option<&'static str> {
+    &mut self.inspect_manifest
+  }
+
   /// The scoped shell commands, where the `HashMap` key is the name each configuration.
   #[cfg(shell_scope)]
   #[inline(always)]
@@ -580,6 +727,8 @@ impl<A: Assets> Context<A> {
       pattern,
       #[cfg(shell_scope)]
       shell_scope,
+      permissions_manifest: None,
+      inspect_manifest: None,
     }
   }
 }
@@ -609,6 +758,30 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
       .emit_filter(event, None, payload, |w| label == w.label())
   }
 
+  /// Emits an event to the windows of a group, see [`Window::set_group`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::Manager;
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     app.emit_to_group("editors", "settings-changed", ())?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  fn emit_to_group<S: Serialize + Clone>(
+    &self,
+    group: &str,
+    event: &str,
+    payload: S,
+  ) -> Result<()> {
+    let labels = self.manager().window_labels_in_group(group);
+    self
+      .manager()
+      .emit_filter(event, None, payload, |w| labels.contains(w.label()))
+  }
+
   /// Listen to a global event.
   fn listen_global<F>(&self, event: impl Into<String>, handler: F) -> EventHandler
   where
@@ -645,6 +818,11 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.manager().windows()
   }
 
+  /// Fetch the windows of a group, see [`Window::set_group`].
+  fn windows_in_group(&self, group: &str) -> HashMap<String, Window<R>> {
+    self.manager().windows_in_group(group)
+  }
+
   /// Add `state` to the state managed by the application.
   ///
   /// This method can be called any number of times as long as each call
@@ -774,6 +952,11 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.state::<Env>().inner().clone()
   }
 
+  /// Gets the scope for the IPC commands.
+  fn command_scope(&self) -> CommandScope {
+    self.state::<Scopes>().inner().command.clone()
+  }
+
   /// Gets the scope for the filesystem APIs.
   fn fs_scope(&self) -> FsScope {
     self.state::<Scopes>().inner().fs.clone()
@@ -819,7 +1002,8 @@ pub(crate) mod sealed {
 }
 
 /// Utilities for unit testing on Tauri applications.
-#[cfg(test)]
+#[cfg(any(test, feature = "test"))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test;
 
 #[cfg(test)]
@@ -883,9 +1067,19 @@ mod tests {
     // features that look like an allowlist feature, but are not
     let allowed = [
       "fs-extract-api",
+      "fs-watch-api",
+      "fs-trash-api",
       "http-api",
       "http-multipart",
+      "websocket-api",
+      "serial-api",
+      "mdns-api",
+      "store-api",
+      "oauth-api",
+      "path-volumes-api",
       "process-command-api",
+      "process-metrics-api",
+      "process-pty",
       "process-relaunch-dangerous-allow-symlink-macos",
       "window-data-url",
     ];
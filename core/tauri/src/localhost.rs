@@ -0,0 +1,280 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The localhost server configured on `tauri > localhost`, serving the assets over `http://localhost`.
+
+// the server is not started on development, where the assets are served on the `devPath`
+#![cfg_attr(dev, allow(dead_code))]
+
+use std::net::Ipv4Addr;
+
+use rand::{distributions::Alphanumeric, Rng};
+use tiny_http::{Header, Request, Response, Server};
+use url::Url;
+
+use crate::{manager::WindowManager, utils::config::LocalhostConfig, Runtime};
+
+/// The name of the query parameter and cookie carrying the token.
+pub(crate) const TOKEN_NAME: &str = "__tauri_token";
+
+/// The running server, managed by the app.
+pub(crate) struct LocalhostServer {
+  /// The URL of the server, `http://localhost:<port>`.
+  pub(crate) url: Url,
+  token: Option<String>,
+}
+
+impl LocalhostServer {
+  /// Appends the token to a window URL on the server, so the first request is authorized.
+  pub(crate) fn authorize_url(&self, url: &mut Url) {
+    if let Some(token) = &self.token {
+      if url.origin() == self.url.origin() {
+        url.query_pairs_mut().append_pair(TOKEN_NAME, token);
+      }
+    }
+  }
+}
+
+/// The result of the authorization of a request.
+#[derive(Debug, PartialEq, Eq)]
+enum Authorization {
+  /// The request is rejected.
+  Denied,
+  /// The request is authorized by its cookie, or no token is required.
+  Allowed,
+  /// The request is authorized by the token on its URL, which is then stored on a cookie.
+  SetCookie,
+}
+
+/// Authorizes a request to the server on `port`.
+///
+/// The `Host` header must be the loopback address, so the assets cannot be read by websites through DNS rebinding.
+fn authorize(
+  port: u16,
+  token: Option<&str>,
+  host: Option<&str>,
+  url: &str,
+  cookie: Option<&str>,
+) -> Authorization {
+  let valid_host = matches!(
+    host,
+    Some(host) if host == format!("localhost:{}", port) || host == format!("127.0.0.1:{}", port)
+  );
+  if !valid_host {
+    return Authorization::Denied;
+  }
+  let token = match token {
+    Some(token) => token,
+    None => return Authorization::Allowed,
+  };
+
+  let cookie_token =
+    cookie
+      .into_iter()
+      .flat_map(|c| c.split(';'))
+      .find_map(|c| match c.trim().split_once('=') {
+        Some((TOKEN_NAME, value)) => Some(value),
+        _ => None,
+      });
+  if cookie_token == Some(token) {
+    return Authorization::Allowed;
+  }
+
+  let query_token = url.split_once('?').and_then(|(_, query)| {
+    url::form_urlencoded::parse(query.as_bytes())
+      .find(|(name, _)| name == TOKEN_NAME)
+      .map(|(_, value)| value.into_owned())
+  });
+  if query_token.as_deref() == Some(token) {
+    Authorization::SetCookie
+  } else {
+    Authorization::Denied
+  }
+}
+
+/// Binds to the first available port of the configured range on the loopback interface.
+fn bind(config: &LocalhostConfig) -> crate::Result<(Server, u16)> {
+  if config.port_start > config.port_end {
+    return Err(crate::Error::Localhost(format!(
+      "invalid port range {}-{}",
+      config.port_start, config.port_end
+    )));
+  }
+  for port in config.port_start..=config.port_end {
+    if let Ok(server) = Server::http((Ipv4Addr::LOCALHOST, port)) {
+      return Ok((server, port));
+    }
+  }
+  Err(crate::Error::Localhost(format!(
+    "no available port in the range {}-{}",
+    config.port_start, config.port_end
+  )))
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+  request
+    .headers()
+    .iter()
+    .find(|header| header.field.equiv(name))
+    .map(|header| header.value.as_str())
+}
+
+fn handle<R: Runtime>(
+  manager: &WindowManager<R>,
+  request: Request,
+  port: u16,
+  token: Option<&str>,
+) {
+  let authorization = authorize(
+    port,
+    token,
+    header(&request, "Host"),
+    request.url(),
+    header(&request, "Cookie"),
+  );
+  if authorization == Authorization::Denied {
+    let _ = request.respond(Response::empty(403));
+    return;
+  }
+
+  let path = request
+    .url()
+    .split('?')
+    .next()
+    .unwrap_or_default()
+    .to_string();
+  let asset = match manager.get_asset(path) {
+    Ok(asset) => asset,
+    Err(_) => {
+      let _ = request.respond(Response::empty(404));
+      return;
+    }
+  };
+
+  let mut response = Response::from_data(asset.bytes)
+    .with_header(Header::from_bytes("Content-Type", asset.mime_type).unwrap())
+    .with_header(Header::from_bytes("X-Content-Type-Options", "nosniff").unwrap());
+  if let Some(csp) = asset.csp_header {
+    if let Ok(header) = Header::from_bytes("Content-Security-Policy", csp) {
+      response.add_header(header);
+    }
+  }
+  if let (Authorization::SetCookie, Some(token)) = (authorization, token) {
+    response.add_header(
+      Header::from_bytes(
+        "Set-Cookie",
+        format!(
+          "{}={}; Path=/; HttpOnly; SameSite=Strict",
+          TOKEN_NAME, token
+        ),
+      )
+      .unwrap(),
+    );
+  }
+  let _ = request.respond(response);
+}
+
+/// Starts the server on a background thread.
+pub(crate) fn start<R: Runtime>(
+  manager: WindowManager<R>,
+  config: &LocalhostConfig,
+) -> crate::Result<LocalhostServer> {
+  let (server, port) = bind(config)?;
+  let token: Option<String> = config.auth_token.then(|| {
+    rand::thread_rng()
+      .sample_iter(&Alphanumeric)
+      .take(32)
+      .map(char::from)
+      .collect()
+  });
+
+  let server_token = token.clone();
+  std::thread::spawn(move || {
+    for request in server.incoming_requests() {
+      handle(&manager, request, port, server_token.as_deref());
+    }
+  });
+
+  Ok(LocalhostServer {
+    url: format!("http://localhost:{}", port).parse().unwrap(),
+    token,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{authorize, Authorization, LocalhostServer};
+
+  #[test]
+  fn authorizes_requests() {
+    let host = Some("localhost:1440");
+    let token = Some("secret");
+
+    assert_eq!(
+      authorize(1440, token, host, "/?__tauri_token=secret", None),
+      Authorization::SetCookie
+    );
+    assert_eq!(
+      authorize(
+        1440,
+        token,
+        Some("127.0.0.1:1440"),
+        "/app.js",
+        Some("theme=dark; __tauri_token=secret")
+      ),
+      Authorization::Allowed
+    );
+    assert_eq!(
+      authorize(1440, None, host, "/", None),
+      Authorization::Allowed
+    );
+
+    // missing or wrong token
+    assert_eq!(
+      authorize(1440, token, host, "/", None),
+      Authorization::Denied
+    );
+    assert_eq!(
+      authorize(
+        1440,
+        token,
+        host,
+        "/?__tauri_token=guess",
+        Some("__tauri_token=guess")
+      ),
+      Authorization::Denied
+    );
+    // DNS rebinding
+    assert_eq!(
+      authorize(1440, None, Some("attacker.com:1440"), "/", None),
+      Authorization::Denied
+    );
+    assert_eq!(
+      authorize(1440, None, Some("localhost:1441"), "/", None),
+      Authorization::Denied
+    );
+    assert_eq!(
+      authorize(1440, None, None, "/", None),
+      Authorization::Denied
+    );
+  }
+
+  #[test]
+  fn appends_token_to_server_urls() {
+    let server = LocalhostServer {
+      url: "http://localhost:1440".parse().unwrap(),
+      token: Some("secret".into()),
+    };
+    let mut url = "http://localhost:1440/settings.html".parse().unwrap();
+    server.authorize_url(&mut url);
+    assert_eq!(
+      url.as_str(),
+      "http://localhost:1440/settings.html?__tauri_token=secret"
+    );
+
+    let mut url = "https://tauri.app".parse().unwrap();
+    server.authorize_url(&mut url);
+    assert_eq!(url.as_str(), "https://tauri.app/");
+  }
+}
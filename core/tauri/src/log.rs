@@ -0,0 +1,293 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Logging to a rotating file in the app log directory, configured on `tauri > log`.
+//!
+//! When the configuration is set, the records of the [`log`](https://docs.rs/log) macros re-exported by this module,
+//! the `log` module of the JavaScript API and, if `webviewConsole` is enabled, the `console` messages of the webviews,
+//! are written to the same sink.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! tauri::Builder::default()
+//!   .setup(|app| {
+//!     tauri::log::info!("started {}", app.package_info().version);
+//!     Ok(())
+//!   });
+//! ```
+
+use std::{
+  fs::{File, OpenOptions},
+  io::Write,
+  path::{Path, PathBuf},
+  sync::Mutex,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+pub use ::log::{debug, error, info, log, trace, warn, Level, LevelFilter};
+
+use crate::{
+  api::path::app_log_dir,
+  utils::config::{Config, LogLevel},
+  PackageInfo,
+};
+
+/// The `log` level of the configured level.
+pub(crate) fn level(level: LogLevel) -> Level {
+  match level {
+    LogLevel::Trace => Level::Trace,
+    LogLevel::Debug => Level::Debug,
+    LogLevel::Info => Level::Info,
+    LogLevel::Warn => Level::Warn,
+    LogLevel::Error => Level::Error,
+  }
+}
+
+/// A log file moved to `<name>.1.log`, `<name>.2.log` and so on when it exceeds its maximum size.
+struct RotatingFile {
+  path: PathBuf,
+  max_size: u64,
+  max_files: usize,
+  file: Option<File>,
+  size: u64,
+}
+
+impl RotatingFile {
+  fn open(path: PathBuf, max_size: u64, max_files: usize) -> std::io::Result<Self> {
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let size = file.metadata()?.len();
+    Ok(Self {
+      path,
+      max_size,
+      max_files,
+      file: Some(file),
+      size,
+    })
+  }
+
+  fn write(&mut self, line: &str) -> std::io::Result<()> {
+    if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+      self.rotate()?;
+    }
+    if let Some(file) = &mut self.file {
+      file.write_all(line.as_bytes())?;
+      self.size += line.len() as u64;
+    }
+    Ok(())
+  }
+
+  fn rotate(&mut self) -> std::io::Result<()> {
+    // the file must be closed before it is renamed on Windows
+    self.file.take();
+    if self.max_files == 0 {
+      std::fs::remove_file(&self.path)?;
+    } else {
+      let _ = std::fs::remove_file(rotated_path(&self.path, self.max_files));
+      for index in (1..self.max_files).rev() {
+        let from = rotated_path(&self.path, index);
+        if from.exists() {
+          std::fs::rename(from, rotated_path(&self.path, index + 1))?;
+        }
+      }
+      std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+    }
+    self.file.replace(
+      OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&self.path)?,
+    );
+    self.size = 0;
+    Ok(())
+  }
+}
+
+/// The path of the `index`th rotated file, e.g. `app.2.log` for `app.log`.
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+  let stem = path
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  match path.extension() {
+    Some(extension) => path.with_file_name(format!(
+      "{}.{}.{}",
+      stem,
+      index,
+      extension.to_string_lossy()
+    )),
+    None => path.with_file_name(format!("{}.{}", stem, index)),
+  }
+}
+
+/// Formats the time as an RFC 3339 UTC timestamp with millisecond precision.
+fn timestamp(time: SystemTime) -> String {
+  let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+  let seconds = duration.as_secs();
+  let (days, seconds_of_day) = ((seconds / 86_400) as i64, seconds % 86_400);
+
+  // converts the days since the epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let day_of_era = z.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  };
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+    year,
+    month,
+    day,
+    seconds_of_day / 3600,
+    seconds_of_day % 3600 / 60,
+    seconds_of_day % 60,
+    duration.subsec_millis()
+  )
+}
+
+fn format_record(time: SystemTime, level: Level, target: &str, message: &str) -> String {
+  format!("[{}][{}][{}] {}\n", timestamp(time), level, target, message)
+}
+
+struct Logger {
+  level: LevelFilter,
+  stdout: bool,
+  file: Option<Mutex<RotatingFile>>,
+}
+
+impl ::log::Log for Logger {
+  fn enabled(&self, metadata: &::log::Metadata<'_>) -> bool {
+    metadata.level() <= self.level
+  }
+
+  fn log(&self, record: &::log::Record<'_>) {
+    if !self.enabled(record.metadata()) {
+      return;
+    }
+    let line = format_record(
+      SystemTime::now(),
+      record.level(),
+      record.target(),
+      &record.args().to_string(),
+    );
+    if self.stdout {
+      print!("{}", line);
+    }
+    if let Some(file) = &self.file {
+      if let Err(e) = file.lock().unwrap().write(&line) {
+        eprintln!("failed to write to the log file: {}", e);
+      }
+    }
+  }
+
+  fn flush(&self) {
+    if let Some(file) = &self.file {
+      if let Some(file) = &mut file.lock().unwrap().file {
+        let _ = file.flush();
+      }
+    }
+  }
+}
+
+/// Installs the logger configured on `tauri > log`, if any.
+pub(crate) fn init(config: &Config, package_info: &PackageInfo) -> crate::Result<()> {
+  let log_config = match &config.tauri.log {
+    Some(log_config) => log_config,
+    None => return Ok(()),
+  };
+
+  let file = match app_log_dir(config).filter(|_| log_config.file) {
+    Some(dir) => Some(Mutex::new(RotatingFile::open(
+      dir.join(format!("{}.log", package_info.name)),
+      log_config.max_file_size,
+      log_config.max_files,
+    )?)),
+    None => None,
+  };
+  let level = level(log_config.level).to_level_filter();
+  let logger = Logger {
+    level,
+    stdout: log_config.stdout,
+    file,
+  };
+
+  if ::log::set_boxed_logger(Box::new(logger)).is_ok() {
+    ::log::set_max_level(level);
+  } else {
+    eprintln!("a logger is already installed, ignoring the `tauri > log` configuration");
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{format_record, rotated_path, timestamp, Level, RotatingFile};
+  use std::{
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+  };
+
+  #[test]
+  fn formats_timestamps() {
+    assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    assert_eq!(
+      timestamp(UNIX_EPOCH + Duration::from_millis(951_782_400_123)),
+      "2000-02-29T00:00:00.123Z"
+    );
+    assert_eq!(
+      format_record(
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        Level::Warn,
+        "webview:main",
+        "low disk space"
+      ),
+      "[2023-11-14T22:13:20.000Z][WARN][webview:main] low disk space\n"
+    );
+  }
+
+  #[test]
+  fn names_rotated_files() {
+    assert_eq!(
+      rotated_path(Path::new("/logs/app.log"), 2),
+      Path::new("/logs/app.2.log")
+    );
+    assert_eq!(
+      rotated_path(Path::new("/logs/app"), 1),
+      Path::new("/logs/app.1")
+    );
+  }
+
+  #[test]
+  fn rotates_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let mut file = RotatingFile::open(path.clone(), 10, 2).unwrap();
+    for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+      file.write(line).unwrap();
+    }
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "fourth\n");
+    assert_eq!(
+      std::fs::read_to_string(dir.path().join("app.1.log")).unwrap(),
+      "third\n"
+    );
+    assert_eq!(
+      std::fs::read_to_string(dir.path().join("app.2.log")).unwrap(),
+      "second\n"
+    );
+    assert!(!dir.path().join("app.3.log").exists());
+  }
+}
@@ -7,7 +7,7 @@ use std::{
   collections::{HashMap, HashSet},
   fmt,
   fs::create_dir_all,
-  sync::{Arc, Mutex, MutexGuard},
+  sync::{Arc, Condvar, Mutex, MutexGuard},
 };
 
 use serde::Serialize;
@@ -17,30 +17,36 @@ use url::Url;
 
 use tauri_macros::default_runtime;
 use tauri_utils::debug_eprintln;
-#[cfg(feature = "isolation")]
-use tauri_utils::pattern::isolation::RawIsolationPayload;
 use tauri_utils::{
   assets::{AssetKey, CspHash},
   config::{Csp, CspDirectiveSources},
   html::{SCRIPT_NONCE_TOKEN, STYLE_NONCE_TOKEN},
 };
+#[cfg(feature = "isolation")]
+use tauri_utils::{config::PatternKind, pattern::isolation::RawIsolationPayload};
 
 use crate::hooks::IpcJavascript;
 #[cfg(feature = "isolation")]
 use crate::hooks::IsolationJavascript;
+#[cfg(all(feature = "localhost", not(dev)))]
+use crate::localhost::LocalhostServer;
 use crate::pattern::{format_real_schema, PatternJavascript};
 use crate::{
   app::{AppHandle, GlobalWindowEvent, GlobalWindowEventListener},
   event::{assert_event_name_is_valid, Event, EventHandler, Listeners},
-  hooks::{InvokeHandler, InvokePayload, InvokeResponder, OnPageLoad, PageLoadPayload},
+  hooks::{
+    AssetFallback, InvokeArgs, InvokeHandler, InvokeMetrics, InvokePayload, InvokeResponder,
+    InvokeSerializer, IpcOriginValidator, IsolationValidator, OnAssetResponse, OnInvokeMetrics,
+    OnPageLoad, PageLoadPayload,
+  },
   plugin::PluginStore,
   runtime::{
     http::{
       MimeType, Request as HttpRequest, Response as HttpResponse,
       ResponseBuilder as HttpResponseBuilder,
     },
-    webview::{WebviewIpcHandler, WindowBuilder},
-    window::{dpi::PhysicalSize, DetachedWindow, FileDropEvent, PendingWindow},
+    webview::{WebviewAttributes, WebviewIpcHandler, WindowBuilder},
+    window::{dpi::PhysicalSize, DetachedWindow, DropEvent, FileDropEvent, PendingWindow},
   },
   utils::{
     assets::Assets,
@@ -71,8 +77,14 @@ const WINDOW_THEME_CHANGED: &str = "tauri://theme-changed";
 const WINDOW_FILE_DROP_EVENT: &str = "tauri://file-drop";
 const WINDOW_FILE_DROP_HOVER_EVENT: &str = "tauri://file-drop-hover";
 const WINDOW_FILE_DROP_CANCELLED_EVENT: &str = "tauri://file-drop-cancelled";
+const WINDOW_DROP_EVENT: &str = "tauri://drop";
+const WINDOW_DROP_HOVER_EVENT: &str = "tauri://drop-hover";
 const MENU_EVENT: &str = "tauri://menu";
 
+/// How long the initial navigation of a webview waits for its window to be attached,
+/// when the window was created on another thread than the main thread.
+const WINDOW_ATTACH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 #[derive(Default)]
 /// Spaced and quoted Content-Security-Policy hash values.
 struct CspHashStrings {
@@ -199,6 +211,15 @@ fn replace_csp_nonce(
 #[default_runtime(crate::Wry, wry)]
 pub struct InnerWindowManager<R: Runtime> {
   windows: Mutex<HashMap<String, Window<R>>>,
+  /// Notified when a window is attached, see [`WindowManager::wait_for_window`].
+  window_attached: Condvar,
+  /// The group of the windows, by label.
+  window_groups: Mutex<HashMap<String, String>>,
+  /// The MIME types of the data other than files the windows accept to be dropped, by label.
+  drop_types: Mutex<HashMap<String, Vec<String>>>,
+  /// The isolation keys of the windows, when they are rotated on every load of the isolation application.
+  #[cfg(feature = "isolation")]
+  isolation_keys: Mutex<HashMap<String, tauri_utils::pattern::isolation::Keys>>,
   #[cfg(all(desktop, feature = "system-tray"))]
   pub(crate) trays: Mutex<HashMap<String, crate::SystemTrayHandle<R>>>,
   pub(crate) plugins: Mutex<PluginStore<R>>,
@@ -212,6 +233,9 @@ pub struct InnerWindowManager<R: Runtime> {
   on_page_load: Box<OnPageLoad<R>>,
 
   config: Arc<Config>,
+  /// The configuration reloaded while running `tauri dev`.
+  #[cfg(config_reload)]
+  reloaded_config: Mutex<Option<Arc<Config>>>,
   assets: Arc<dyn Assets>,
   pub(crate) default_window_icon: Option<Icon>,
   pub(crate) app_icon: Option<Vec<u8>>,
@@ -219,7 +243,7 @@ pub struct InnerWindowManager<R: Runtime> {
 
   package_info: PackageInfo,
   /// The webview protocols available to all windows.
-  uri_scheme_protocols: HashMap<String, Arc<CustomProtocol<R>>>,
+  uri_scheme_protocols: Mutex<HashMap<String, Arc<CustomProtocol<R>>>>,
   /// The menu set to all windows.
   menu: Option<Menu>,
   /// Menu event listeners to all windows.
@@ -230,8 +254,24 @@ pub struct InnerWindowManager<R: Runtime> {
   invoke_responder: Arc<InvokeResponder<R>>,
   /// The script that initializes the invoke system.
   invoke_initialization_script: String,
+  /// The serialization format of the invoke messages.
+  invoke_serializer: Option<Arc<dyn InvokeSerializer>>,
+  /// Validates the origin of the IPC messages.
+  ipc_origin_validator: Option<Box<IpcOriginValidator<R>>>,
+  /// Validates the invoke requests on the isolation application.
+  #[cfg_attr(not(feature = "isolation"), allow(dead_code))]
+  isolation_validator: Option<Box<IsolationValidator<R>>>,
+  /// Receives the metrics of the invoke requests.
+  on_invoke_metrics: Option<Box<OnInvokeMetrics<R>>>,
+  /// Modifies the responses of the `tauri` and `asset` protocols.
+  on_asset_response: Option<Box<OnAssetResponse<R>>>,
+  /// Generates the responses of the `tauri` protocol for missing assets.
+  asset_fallback: Option<Box<AssetFallback<R>>>,
   /// Application pattern.
   pattern: Pattern,
+  /// The recorders of the emitted events.
+  #[cfg(any(test, feature = "test"))]
+  event_recorders: Mutex<Vec<std::sync::Weak<crate::test::RecorderState>>>,
 }
 
 impl<R: Runtime> fmt::Debug for InnerWindowManager<R> {
@@ -260,6 +300,70 @@ pub struct Asset {
   pub csp_header: Option<String>,
 }
 
+/// The bytes of an asset, shared with the cache of the assets when they have one.
+enum AssetBytes<'a> {
+  Shared(Arc<[u8]>),
+  Read(Cow<'a, [u8]>),
+}
+
+impl AssetBytes<'_> {
+  fn read(assets: &dyn Assets, key: &AssetKey) -> Option<AssetBytes<'_>> {
+    assets
+      .get_shared(key)
+      .map(AssetBytes::Shared)
+      .or_else(|| assets.get(key).map(AssetBytes::Read))
+  }
+
+  fn as_slice(&self) -> &[u8] {
+    match self {
+      Self::Shared(bytes) => bytes,
+      Self::Read(bytes) => bytes,
+    }
+  }
+
+  /// The response bodies own their bytes, so the shared bytes are only copied here.
+  fn into_vec(self) -> Vec<u8> {
+    match self {
+      Self::Shared(bytes) => bytes.to_vec(),
+      Self::Read(bytes) => bytes.into_owned(),
+    }
+  }
+}
+
+/// Responds with the asset, with an `ETag` header answering the revalidation requests with `304 Not Modified`.
+///
+/// The HTML files have new CSP nonces on every request, so they are not revalidated.
+fn asset_response(
+  builder: HttpResponseBuilder,
+  #[allow(unused_variables)] request: &HttpRequest,
+  asset: Asset,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+  // the status code and headers cannot be set on Linux without the `linux-protocol-headers` feature
+  #[cfg(any(not(target_os = "linux"), feature = "linux-protocol-headers"))]
+  if !asset.mime_type.starts_with("text/html") {
+    let etag = asset_etag(&asset.bytes);
+    let builder = builder
+      .header("ETag", &etag)
+      .header("Cache-Control", "no-cache");
+    return if crate::protocol::is_not_modified(request, &etag) {
+      builder.status(304).body(Vec::new())
+    } else {
+      builder.mimetype(&asset.mime_type).body(asset.bytes)
+    };
+  }
+  builder.mimetype(&asset.mime_type).body(asset.bytes)
+}
+
+/// An entity tag derived from the asset bytes, which only change when the app is rebuilt.
+#[cfg(any(not(target_os = "linux"), feature = "linux-protocol-headers"))]
+fn asset_etag(bytes: &[u8]) -> String {
+  use std::hash::{Hash, Hasher};
+
+  let mut hasher = std::collections::hash_map::DefaultHasher::new();
+  bytes.hash(&mut hasher);
+  format!("\"{:x}\"", hasher.finish())
+}
+
 /// Uses a custom URI scheme handler to resolve file requests
 pub struct CustomProtocol<R: Runtime> {
   /// Handler for protocol
@@ -296,7 +400,16 @@ impl<R: Runtime> WindowManager<R> {
     state: StateManager,
     window_event_listeners: Vec<GlobalWindowEventListener<R>>,
     (menu, menu_event_listeners): (Option<Menu>, Vec<GlobalMenuEventListener<R>>),
-    (invoke_responder, invoke_initialization_script): (Arc<InvokeResponder<R>>, String),
+    (invoke_responder, invoke_initialization_script, invoke_serializer): (
+      Arc<InvokeResponder<R>>,
+      String,
+      Option<Arc<dyn InvokeSerializer>>,
+    ),
+    ipc_origin_validator: Option<Box<IpcOriginValidator<R>>>,
+    isolation_validator: Option<Box<IsolationValidator<R>>>,
+    on_invoke_metrics: Option<Box<OnInvokeMetrics<R>>>,
+    on_asset_response: Option<Box<OnAssetResponse<R>>>,
+    asset_fallback: Option<Box<AssetFallback<R>>>,
   ) -> Self {
     // generate a random isolation key at runtime
     #[cfg(feature = "isolation")]
@@ -307,6 +420,11 @@ impl<R: Runtime> WindowManager<R> {
     Self {
       inner: Arc::new(InnerWindowManager {
         windows: Mutex::default(),
+        window_attached: Condvar::new(),
+        window_groups: Mutex::default(),
+        drop_types: Mutex::default(),
+        #[cfg(feature = "isolation")]
+        isolation_keys: Mutex::default(),
         #[cfg(all(desktop, feature = "system-tray"))]
         trays: Default::default(),
         plugins: Mutex::new(plugins),
@@ -315,18 +433,28 @@ impl<R: Runtime> WindowManager<R> {
         invoke_handler,
         on_page_load,
         config: Arc::new(context.config),
+        #[cfg(config_reload)]
+        reloaded_config: Default::default(),
         assets: context.assets,
         default_window_icon: context.default_window_icon,
         app_icon: context.app_icon,
         tray_icon: context.system_tray_icon,
         package_info: context.package_info,
         pattern: context.pattern,
-        uri_scheme_protocols,
+        uri_scheme_protocols: Mutex::new(uri_scheme_protocols),
         menu,
         menu_event_listeners: Arc::new(menu_event_listeners),
         window_event_listeners: Arc::new(window_event_listeners),
         invoke_responder,
         invoke_initialization_script,
+        invoke_serializer,
+        ipc_origin_validator,
+        isolation_validator,
+        on_invoke_metrics,
+        on_asset_response,
+        asset_fallback,
+        #[cfg(any(test, feature = "test"))]
+        event_recorders: Default::default(),
       }),
     }
   }
@@ -340,6 +468,21 @@ impl<R: Runtime> WindowManager<R> {
     self.inner.windows.lock().expect("poisoned window manager")
   }
 
+  /// Gets the window with the given label, waiting up to [`WINDOW_ATTACH_TIMEOUT`] for it to be attached.
+  ///
+  /// The webview of a window created on another thread may start loading on the main thread
+  /// before the creating thread attached the window.
+  fn wait_for_window(&self, label: &str) -> Option<Window<R>> {
+    let (windows, _) = self
+      .inner
+      .window_attached
+      .wait_timeout_while(self.windows_lock(), WINDOW_ATTACH_TIMEOUT, |windows| {
+        !windows.contains_key(label)
+      })
+      .expect("poisoned window manager");
+    windows.get(label).cloned()
+  }
+
   /// State managed by the application.
   pub(crate) fn state(&self) -> Arc<StateManager> {
     self.inner.state.clone()
@@ -350,6 +493,94 @@ impl<R: Runtime> WindowManager<R> {
     self.inner.invoke_responder.clone()
   }
 
+  /// Registers a URI scheme protocol for the windows created from now on.
+  pub(crate) fn register_uri_scheme_protocol(
+    &self,
+    uri_scheme: String,
+    protocol: Arc<CustomProtocol<R>>,
+  ) {
+    self
+      .inner
+      .uri_scheme_protocols
+      .lock()
+      .unwrap()
+      .insert(uri_scheme, protocol);
+  }
+
+  /// Whether a URI scheme protocol is registered with the given scheme.
+  pub(crate) fn has_uri_scheme_protocol(&self, uri_scheme: &str) -> bool {
+    self
+      .inner
+      .uri_scheme_protocols
+      .lock()
+      .unwrap()
+      .contains_key(uri_scheme)
+  }
+
+  /// Whether the IPC messages from the given window are accepted by the origin validator.
+  pub(crate) fn is_ipc_origin_valid(&self, window: &Window<R>) -> bool {
+    match &self.inner.ipc_origin_validator {
+      Some(validator) => window
+        .url()
+        .map(|url| validator(&url, window))
+        .unwrap_or(false),
+      None => true,
+    }
+  }
+
+  /// Whether the isolation validator accepts a decrypted invoke request of the isolation pattern.
+  #[cfg(feature = "isolation")]
+  fn is_isolation_request_valid(&self, window: &Window<R>, payload: &InvokePayload) -> bool {
+    match &self.inner.isolation_validator {
+      Some(validator) if payload.cmd != "__initialized" => {
+        let command = match &payload.tauri_module {
+          Some(module) => format!("tauri:{}", module.to_lowercase()),
+          None => payload.cmd.clone(),
+        };
+        validator(
+          window,
+          &crate::hooks::IsolationRequest {
+            command: &command,
+            args: &payload.inner,
+          },
+        )
+      }
+      _ => true,
+    }
+  }
+
+  /// Whether the invoke requests must be measured.
+  pub(crate) fn measures_invokes(&self) -> bool {
+    cfg!(feature = "ipc-tracing") || self.inner.on_invoke_metrics.is_some()
+  }
+
+  pub(crate) fn run_on_invoke_metrics(&self, window: &Window<R>, metrics: &InvokeMetrics) {
+    if let Some(on_invoke_metrics) = &self.inner.on_invoke_metrics {
+      on_invoke_metrics(window, metrics);
+    }
+  }
+
+  /// Runs the asset response hook, if one is set.
+  fn run_on_asset_response(
+    &self,
+    app_handle: &AppHandle<R>,
+    request: &HttpRequest,
+    response: &mut HttpResponse,
+  ) {
+    if let Some(on_asset_response) = &self.inner.on_asset_response {
+      on_asset_response(app_handle, request, response);
+    }
+  }
+
+  /// The invoke serializer, if one is set and the application uses the brownfield pattern.
+  pub(crate) fn invoke_serializer(&self) -> Option<Arc<dyn InvokeSerializer>> {
+    match self.pattern() {
+      Pattern::Brownfield(_) => self.inner.invoke_serializer.clone(),
+      #[allow(unreachable_patterns)]
+      _ => None,
+    }
+  }
+
   /// Get the base path to serve data from.
   ///
   /// * In dev mode, this will be based on the `devPath` configuration value.
@@ -368,6 +599,10 @@ impl<R: Runtime> WindowManager<R> {
   ///
   /// In dev mode, this will be based on the `devPath` configuration value.
   fn get_url(&self) -> Cow<'_, Url> {
+    #[cfg(all(feature = "localhost", not(dev)))]
+    if let Some(server) = self.inner.state.try_get::<LocalhostServer>() {
+      return Cow::Owned(server.inner().url.clone());
+    }
     match self.base_path() {
       AppUrl::Url(WindowUrl::External(url)) => Cow::Borrowed(url),
       _ => Cow::Owned(Url::parse("tauri://localhost").unwrap()),
@@ -376,6 +611,10 @@ impl<R: Runtime> WindowManager<R> {
 
   /// Get the origin as it will be seen in the webview.
   fn get_browser_origin(&self) -> String {
+    #[cfg(all(feature = "localhost", not(dev)))]
+    if let Some(server) = self.inner.state.try_get::<LocalhostServer>() {
+      return server.inner().url.origin().ascii_serialization();
+    }
     match self.base_path() {
       AppUrl::Url(WindowUrl::External(url)) => url.origin().ascii_serialization(),
       _ => format_real_schema("tauri"),
@@ -397,6 +636,11 @@ impl<R: Runtime> WindowManager<R> {
     }
   }
 
+  /// The CSP of the webview, which overrides the app CSP.
+  fn webview_csp(&self, webview_attributes: &WebviewAttributes) -> Option<Csp> {
+    webview_attributes.csp.clone().or_else(|| self.csp())
+  }
+
   fn prepare_pending_window(
     &self,
     mut pending: PendingWindow<EventLoopMessage, R>,
@@ -434,6 +678,11 @@ impl<R: Runtime> WindowManager<R> {
     if !window_labels.contains(&l) {
       window_labels.push(l);
     }
+    if let Some(serializer) = self.invoke_serializer() {
+      webview_attributes =
+        webview_attributes.initialization_script(&serializer.initialization_script());
+    }
+
     webview_attributes = webview_attributes
       .initialization_script(&self.inner.invoke_initialization_script)
       .initialization_script(&format!(
@@ -464,11 +713,23 @@ impl<R: Runtime> WindowManager<R> {
       );
     }
 
+    if self
+      .inner
+      .config
+      .tauri
+      .log
+      .as_ref()
+      .map_or(false, |log| log.webview_console)
+    {
+      webview_attributes =
+        webview_attributes.initialization_script(include_str!("../scripts/console.js"));
+    }
+
     pending.webview_attributes = webview_attributes;
 
     let mut registered_scheme_protocols = Vec::new();
 
-    for (uri_scheme, protocol) in &self.inner.uri_scheme_protocols {
+    for (uri_scheme, protocol) in self.inner.uri_scheme_protocols.lock().unwrap().iter() {
       registered_scheme_protocols.push(uri_scheme.clone());
       let protocol = protocol.clone();
       let app_handle = Mutex::new(app_handle.clone());
@@ -497,210 +758,25 @@ impl<R: Runtime> WindowManager<R> {
     if !registered_scheme_protocols.contains(&"tauri".into()) {
       pending.register_uri_scheme_protocol(
         "tauri",
-        self.prepare_uri_scheme_protocol(&window_origin, web_resource_request_handler),
+        self.prepare_uri_scheme_protocol(
+          &window_origin,
+          app_handle.clone(),
+          self.webview_csp(&pending.webview_attributes),
+          web_resource_request_handler,
+        ),
       );
       registered_scheme_protocols.push("tauri".into());
     }
 
     #[cfg(protocol_asset)]
     if !registered_scheme_protocols.contains(&"asset".into()) {
-      use crate::api::file::SafePathBuf;
-      use tokio::io::{AsyncReadExt, AsyncSeekExt};
-      use url::Position;
       let asset_scope = self.state().get::<crate::Scopes>().asset_protocol.clone();
+      let manager = self.clone();
+      let app_handle = Mutex::new(app_handle.clone());
       pending.register_uri_scheme_protocol("asset", move |request| {
-        let parsed_path = Url::parse(request.uri())?;
-        let filtered_path = &parsed_path[..Position::AfterPath];
-        let path = filtered_path
-          .strip_prefix("asset://localhost/")
-          // the `strip_prefix` only returns None when a request is made to `https://tauri.$P` on Windows
-          // where `$P` is not `localhost/*`
-          .unwrap_or("");
-        let path = percent_encoding::percent_decode(path.as_bytes())
-          .decode_utf8_lossy()
-          .to_string();
-
-        if let Err(e) = SafePathBuf::new(path.clone().into()) {
-          debug_eprintln!("asset protocol path \"{}\" is not valid: {}", path, e);
-          return HttpResponseBuilder::new().status(403).body(Vec::new());
-        }
-
-        if !asset_scope.is_allowed(&path) {
-          debug_eprintln!("asset protocol not configured to allow the path: {}", path);
-          return HttpResponseBuilder::new().status(403).body(Vec::new());
-        }
-
-        let path_ = path.clone();
-
-        let mut response =
-          HttpResponseBuilder::new().header("Access-Control-Allow-Origin", &window_origin);
-
-        // handle 206 (partial range) http request
-        if let Some(range) = request
-          .headers()
-          .get("range")
-          .and_then(|r| r.to_str().map(|r| r.to_string()).ok())
-        {
-          #[derive(Default)]
-          struct RangeMetadata {
-            file: Option<tokio::fs::File>,
-            range: Option<crate::runtime::http::HttpRange>,
-            metadata: Option<std::fs::Metadata>,
-            headers: HashMap<&'static str, String>,
-            status_code: u16,
-            body: Vec<u8>,
-          }
-
-          let mut range_metadata = crate::async_runtime::safe_block_on(async move {
-            let mut data = RangeMetadata::default();
-            // open the file
-            let mut file = match tokio::fs::File::open(path_.clone()).await {
-              Ok(file) => file,
-              Err(e) => {
-                debug_eprintln!("Failed to open asset: {}", e);
-                data.status_code = 404;
-                return data;
-              }
-            };
-            // Get the file size
-            let file_size = match file.metadata().await {
-              Ok(metadata) => {
-                let len = metadata.len();
-                data.metadata.replace(metadata);
-                len
-              }
-              Err(e) => {
-                debug_eprintln!("Failed to read asset metadata: {}", e);
-                data.file.replace(file);
-                data.status_code = 404;
-                return data;
-              }
-            };
-            // parse the range
-            let range = match crate::runtime::http::HttpRange::parse(
-              &if range.ends_with("-*") {
-                range.chars().take(range.len() - 1).collect::<String>()
-              } else {
-                range.clone()
-              },
-              file_size,
-            ) {
-              Ok(r) => r,
-              Err(e) => {
-                debug_eprintln!("Failed to parse range {}: {:?}", range, e);
-                data.file.replace(file);
-                data.status_code = 400;
-                return data;
-              }
-            };
-
-            // FIXME: Support multiple ranges
-            // let support only 1 range for now
-            if let Some(range) = range.first() {
-              data.range.replace(*range);
-              let mut real_length = range.length;
-              // prevent max_length;
-              // specially on webview2
-              if range.length > file_size / 3 {
-                // max size sent (400ko / request)
-                // as it's local file system we can afford to read more often
-                real_length = std::cmp::min(file_size - range.start, 1024 * 400);
-              }
-
-              // last byte we are reading, the length of the range include the last byte
-              // who should be skipped on the header
-              let last_byte = range.start + real_length - 1;
-
-              data.headers.insert("Connection", "Keep-Alive".into());
-              data.headers.insert("Accept-Ranges", "bytes".into());
-              data
-                .headers
-                .insert("Content-Length", real_length.to_string());
-              data.headers.insert(
-                "Content-Range",
-                format!("bytes {}-{}/{}", range.start, last_byte, file_size),
-              );
-
-              if let Err(e) = file.seek(std::io::SeekFrom::Start(range.start)).await {
-                debug_eprintln!("Failed to seek file to {}: {}", range.start, e);
-                data.file.replace(file);
-                data.status_code = 422;
-                return data;
-              }
-
-              let mut f = file.take(real_length);
-              let r = f.read_to_end(&mut data.body).await;
-              file = f.into_inner();
-              data.file.replace(file);
-
-              if let Err(e) = r {
-                debug_eprintln!("Failed read file: {}", e);
-                data.status_code = 422;
-                return data;
-              }
-              // partial content
-              data.status_code = 206;
-            } else {
-              data.status_code = 200;
-            }
-
-            data
-          });
-
-          for (k, v) in range_metadata.headers {
-            response = response.header(k, v);
-          }
-
-          let mime_type = if let (Some(mut file), Some(metadata), Some(range)) = (
-            range_metadata.file,
-            range_metadata.metadata,
-            range_metadata.range,
-          ) {
-            // if we're already reading the beginning of the file, we do not need to re-read it
-            if range.start == 0 {
-              MimeType::parse(&range_metadata.body, &path)
-            } else {
-              let (status, bytes) = crate::async_runtime::safe_block_on(async move {
-                let mut status = None;
-                if let Err(e) = file.rewind().await {
-                  debug_eprintln!("Failed to rewind file: {}", e);
-                  status.replace(422);
-                  (status, Vec::with_capacity(0))
-                } else {
-                  // taken from https://docs.rs/infer/0.9.0/src/infer/lib.rs.html#240-251
-                  let limit = std::cmp::min(metadata.len(), 8192) as usize + 1;
-                  let mut bytes = Vec::with_capacity(limit);
-                  if let Err(e) = file.take(8192).read_to_end(&mut bytes).await {
-                    debug_eprintln!("Failed read file: {}", e);
-                    status.replace(422);
-                  }
-                  (status, bytes)
-                }
-              });
-              if let Some(s) = status {
-                range_metadata.status_code = s;
-              }
-              MimeType::parse(&bytes, &path)
-            }
-          } else {
-            MimeType::parse(&range_metadata.body, &path)
-          };
-          response
-            .mimetype(&mime_type)
-            .status(range_metadata.status_code)
-            .body(range_metadata.body)
-        } else {
-          match crate::async_runtime::safe_block_on(async move { tokio::fs::read(path_).await }) {
-            Ok(data) => {
-              let mime_type = MimeType::parse(&data, &path);
-              response.mimetype(&mime_type).body(data)
-            }
-            Err(e) => {
-              debug_eprintln!("Failed to read file: {}", e);
-              response.status(404).body(Vec::new())
-            }
-          }
-        }
+        let mut response = crate::protocol::asset::handle(request, &asset_scope, &window_origin)?;
+        manager.run_on_asset_response(&app_handle.lock().unwrap(), request, &mut response);
+        Ok(response)
       });
     }
 
@@ -716,11 +792,33 @@ impl<R: Runtime> WindowManager<R> {
       let schema_ = schema.clone();
       let url_base = format!("{}://localhost", schema_);
       let aes_gcm_key = *crypto_keys.aes_gcm().raw();
+      let rotate_key = matches!(
+        self.inner.config.tauri.pattern,
+        PatternKind::Isolation {
+          rotate_key: true,
+          ..
+        }
+      );
+      let manager = self.clone();
+      let label = label.to_string();
 
       pending.register_uri_scheme_protocol(schema, move |request| {
         match request_to_path(request, &url_base).as_str() {
           "index.html" => match assets.get(&"index.html".into()) {
             Some(asset) => {
+              let aes_gcm_key = if rotate_key {
+                let keys = tauri_utils::pattern::isolation::Keys::new()?;
+                let raw = *keys.aes_gcm().raw();
+                manager
+                  .inner
+                  .isolation_keys
+                  .lock()
+                  .unwrap()
+                  .insert(label.clone(), keys);
+                raw
+              } else {
+                aes_gcm_key
+              };
               let asset = String::from_utf8_lossy(asset.as_ref());
               let template = tauri_utils::pattern::isolation::IsolationJavascriptRuntime {
                 runtime_aes_gcm_key: &aes_gcm_key,
@@ -762,9 +860,20 @@ impl<R: Runtime> WindowManager<R> {
 
       #[cfg(feature = "isolation")]
       if let Pattern::Isolation { crypto_keys, .. } = manager.pattern() {
-        match RawIsolationPayload::try_from(request.as_str())
-          .and_then(|raw| crypto_keys.decrypt(raw))
-        {
+        let decrypted = RawIsolationPayload::try_from(request.as_str()).and_then(|raw| {
+          // the rotated key of the window, if any
+          match manager
+            .inner
+            .isolation_keys
+            .lock()
+            .unwrap()
+            .get(window.label())
+          {
+            Some(keys) => keys.decrypt(raw),
+            None => crypto_keys.decrypt(raw),
+          }
+        });
+        match decrypted {
           Ok(json) => request = json,
           Err(e) => {
             let error: crate::Error = e.into();
@@ -777,12 +886,38 @@ impl<R: Runtime> WindowManager<R> {
         }
       }
 
-      match serde_json::from_str::<InvokePayload>(&request) {
-        Ok(message) => {
-          let _ = window.on_message(message);
+      let payload = match manager.invoke_serializer() {
+        #[cfg(feature = "ipc-msgpack")]
+        Some(serializer) if serializer.is_message_pack() => {
+          crate::hooks::MessagePackArgs::decode(&request)
+            .map(|(payload, args)| (payload, InvokeArgs::MessagePack(args)))
+        }
+        Some(serializer) => serializer
+          .deserialize_request(&request)
+          .map(InvokeArgs::split),
+        None => serde_json::from_str::<InvokePayload>(&request)
+          .map(InvokeArgs::split)
+          .map_err(Into::into),
+      };
+
+      match payload {
+        Ok((message, args)) => {
+          // validated after decryption, so the webview cannot change the request once it is accepted
+          #[cfg(feature = "isolation")]
+          if matches!(manager.pattern(), Pattern::Isolation { .. })
+            && !manager.is_isolation_request_valid(&window, &message)
+          {
+            crate::hooks::InvokeResolver::new(window, message.callback, message.error).reject(
+              format!(
+                "command {} rejected by the isolation validator",
+                message.cmd
+              ),
+            );
+            return;
+          }
+          let _ = window.on_invoke(message, args);
         }
-        Err(e) => {
-          let error: crate::Error = e.into();
+        Err(error) => {
           let _ = window.eval(&format!(
             r#"console.error({})"#,
             JsonValue::String(error.to_string())
@@ -792,30 +927,29 @@ impl<R: Runtime> WindowManager<R> {
     })
   }
 
-  pub fn get_asset(&self, mut path: String) -> Result<Asset, Box<dyn std::error::Error>> {
-    let assets = &self.inner.assets;
-    if path.ends_with('/') {
-      path.pop();
-    }
-    path = percent_encoding::percent_decode(path.as_bytes())
-      .decode_utf8_lossy()
-      .to_string();
-    let path = if path.is_empty() {
-      // if the url is `tauri://localhost`, we should load `index.html`
-      "index.html".to_string()
-    } else {
-      // skip leading `/`
-      path.chars().skip(1).collect::<String>()
-    };
+  pub fn get_asset(&self, path: String) -> Result<Asset, Box<dyn std::error::Error>> {
+    self.resolve_asset(path, true, self.csp())
+  }
+
+  /// Resolves the asset on the given path, optionally falling back to `index.html` when it is not found.
+  ///
+  /// The CSP is injected on HTML files, with new nonces on every call.
+  fn resolve_asset(
+    &self,
+    path: String,
+    index_fallback: bool,
+    csp: Option<Csp>,
+  ) -> Result<Asset, Box<dyn std::error::Error>> {
+    let assets = &*self.inner.assets;
+    let path = asset_path(path);
 
     let mut asset_path = AssetKey::from(path.as_str());
 
-    let asset_response = assets
-      .get(&path.as_str().into())
+    let asset_response = AssetBytes::read(assets, &path.as_str().into())
       .or_else(|| {
         eprintln!("Asset `{}` not found; fallback to {}.html", path, path);
         let fallback = format!("{}.html", path.as_str()).into();
-        let asset = assets.get(&fallback);
+        let asset = AssetBytes::read(assets, &fallback);
         asset_path = fallback;
         asset
       })
@@ -826,19 +960,21 @@ impl<R: Runtime> WindowManager<R> {
           path
         );
         let fallback = format!("{}/index.html", path.as_str()).into();
-        let asset = assets.get(&fallback);
+        let asset = AssetBytes::read(assets, &fallback);
         asset_path = fallback;
         asset
       })
       .or_else(|| {
+        if !index_fallback {
+          return None;
+        }
         debug_eprintln!("Asset `{}` not found; fallback to index.html", path);
         let fallback = AssetKey::from("index.html");
-        let asset = assets.get(&fallback);
+        let asset = AssetBytes::read(assets, &fallback);
         asset_path = fallback;
         asset
       })
-      .ok_or_else(|| crate::Error::AssetNotFound(path.clone()))
-      .map(Cow::into_owned);
+      .ok_or_else(|| crate::Error::AssetNotFound(path.clone()));
 
     let mut csp_header = None;
     let is_html = asset_path.as_ref().ends_with(".html");
@@ -846,8 +982,8 @@ impl<R: Runtime> WindowManager<R> {
     match asset_response {
       Ok(asset) => {
         let final_data = if is_html {
-          let mut asset = String::from_utf8_lossy(&asset).into_owned();
-          if let Some(csp) = self.csp() {
+          let mut asset = String::from_utf8_lossy(asset.as_slice()).into_owned();
+          if let Some(csp) = csp {
             csp_header.replace(set_csp(
               &mut asset,
               self.inner.assets.clone(),
@@ -857,13 +993,13 @@ impl<R: Runtime> WindowManager<R> {
             ));
           }
 
-          asset.as_bytes().to_vec()
+          asset.into_bytes()
         } else {
-          asset
+          asset.into_vec()
         };
         let mime_type = MimeType::parse(&final_data, &path);
         Ok(Asset {
-          bytes: final_data.to_vec(),
+          bytes: final_data,
           mime_type,
           csp_header,
         })
@@ -875,10 +1011,39 @@ impl<R: Runtime> WindowManager<R> {
     }
   }
 
+  /// Gets the asset on the given path compressed with Brotli,
+  /// if it exists and is served without modifications.
+  #[cfg(all(
+    feature = "compression",
+    any(not(target_os = "linux"), feature = "linux-protocol-headers")
+  ))]
+  fn get_brotli_asset(&self, path: String) -> Option<Asset> {
+    use std::io::Read;
+
+    let path = asset_path(path);
+    if path.ends_with(".html") {
+      return None;
+    }
+    let bytes = self.inner.assets.get_brotli(&path.as_str().into())?;
+    // the MIME type is inferred from the first decompressed bytes
+    let mut head = Vec::new();
+    brotli::Decompressor::new(bytes.as_ref(), 4096)
+      .take(8192)
+      .read_to_end(&mut head)
+      .ok()?;
+    Some(Asset {
+      mime_type: MimeType::parse(&head, &path),
+      bytes: bytes.into_owned(),
+      csp_header: None,
+    })
+  }
+
   #[allow(clippy::type_complexity)]
   fn prepare_uri_scheme_protocol(
     &self,
     window_origin: &str,
+    app_handle: AppHandle<R>,
+    csp: Option<Csp>,
     web_resource_request_handler: Option<
       Box<dyn Fn(&HttpRequest, &mut HttpResponse) + Send + Sync>,
     >,
@@ -886,6 +1051,7 @@ impl<R: Runtime> WindowManager<R> {
   {
     let manager = self.clone();
     let window_origin = window_origin.to_string();
+    let app_handle = Mutex::new(app_handle);
     Box::new(move |request| {
       let path = request
         .uri()
@@ -898,16 +1064,55 @@ impl<R: Runtime> WindowManager<R> {
         // the `strip_prefix` only returns None when a request is made to `https://tauri.$P` on Windows
         // where `$P` is not `localhost/*`
         .unwrap_or_else(|| "".to_string());
-      let asset = manager.get_asset(path)?;
-      let mut builder = HttpResponseBuilder::new()
-        .header("Access-Control-Allow-Origin", &window_origin)
-        .mimetype(&asset.mime_type);
-      if let Some(csp) = &asset.csp_header {
-        builder = builder.header("Content-Security-Policy", csp);
+
+      // the compressed asset is only served if the response headers are applied by the webview
+      #[cfg(all(
+        feature = "compression",
+        any(not(target_os = "linux"), feature = "linux-protocol-headers")
+      ))]
+      if web_resource_request_handler.is_none()
+        && manager.inner.on_asset_response.is_none()
+        && accepts_brotli(request)
+      {
+        if let Some(asset) = manager.get_brotli_asset(path.clone()) {
+          let builder = HttpResponseBuilder::new()
+            .header("Access-Control-Allow-Origin", &window_origin)
+            .header("Content-Encoding", "br");
+          return asset_response(builder, request, asset);
+        }
+      }
+
+      // the asset fallback is consulted before falling back to `index.html`
+      let mut asset = None;
+      let mut fallback_response = None;
+      if let Some(asset_fallback) = &manager.inner.asset_fallback {
+        match manager.resolve_asset(path.clone(), false, csp.clone()) {
+          Ok(a) => asset = Some(a),
+          Err(_) => fallback_response = asset_fallback(&app_handle.lock().unwrap(), request),
+        }
       }
-      let mut response = builder.body(asset.bytes)?;
-      if let Some(handler) = &web_resource_request_handler {
-        handler(request, &mut response);
+
+      let (mut response, csp_header) = match fallback_response {
+        Some(response) => (response, None),
+        None => {
+          let asset = match asset {
+            Some(asset) => asset,
+            None => manager.resolve_asset(path, true, csp.clone())?,
+          };
+          let mut builder =
+            HttpResponseBuilder::new().header("Access-Control-Allow-Origin", &window_origin);
+          let csp_header = asset.csp_header.clone();
+          if let Some(csp) = &csp_header {
+            builder = builder.header("Content-Security-Policy", csp);
+          }
+          (asset_response(builder, request, asset)?, csp_header)
+        }
+      };
+      if web_resource_request_handler.is_some() || manager.inner.on_asset_response.is_some() {
+        manager.run_on_asset_response(&app_handle.lock().unwrap(), request, &mut response);
+        if let Some(handler) = &web_resource_request_handler {
+          handler(request, &mut response);
+        }
 
         // if it's an HTML file, we need to set the CSP meta tag on Linux
         #[cfg(target_os = "linux")]
@@ -919,7 +1124,7 @@ impl<R: Runtime> WindowManager<R> {
       } else {
         #[cfg(target_os = "linux")]
         {
-          if let Some(csp) = &asset.csp_header {
+          if let Some(csp) = &csp_header {
             let body = set_html_csp(&String::from_utf8_lossy(response.body()), csp);
             *response.body_mut() = body.as_bytes().to_vec();
           }
@@ -1052,14 +1257,15 @@ impl<R: Runtime> WindowManager<R> {
 
 #[cfg(test)]
 mod test {
-  use crate::{generate_context, plugin::PluginStore, StateManager, Wry};
+  use std::collections::HashMap;
 
-  use super::WindowManager;
+  use crate::{generate_context, plugin::PluginStore, utils::config::Csp, StateManager, Wry};
 
-  #[test]
-  fn check_get_url() {
+  use super::{replace_csp_nonce, WindowManager, SCRIPT_NONCE_TOKEN};
+
+  fn manager() -> WindowManager<Wry> {
     let context = generate_context!("test/fixture/src-tauri/tauri.conf.json", crate);
-    let manager: WindowManager<Wry> = WindowManager::with_handlers(
+    WindowManager::with_handlers(
       context,
       PluginStore::default(),
       Box::new(|_| ()),
@@ -1068,8 +1274,45 @@ mod test {
       StateManager::new(),
       Default::default(),
       Default::default(),
-      (std::sync::Arc::new(|_, _, _, _| ()), "".into()),
-    );
+      (std::sync::Arc::new(|_, _, _, _| ()), "".into(), None),
+      None,
+      None,
+      None,
+      None,
+      None,
+    )
+  }
+
+  #[test]
+  fn register_uri_scheme_protocol() {
+    use crate::http::{Request, ResponseBuilder};
+
+    let app = crate::test::mock_app();
+    app.register_uri_scheme_protocol("plugin-assets", |_, _| {
+      ResponseBuilder::new().body(b"first".to_vec())
+    });
+    // replaces the protocol registered with the same scheme
+    app.register_uri_scheme_protocol("plugin-assets", |_, _| {
+      ResponseBuilder::new().body(b"second".to_vec())
+    });
+
+    let handle = app.handle();
+    let protocol = handle
+      .manager
+      .inner
+      .uri_scheme_protocols
+      .lock()
+      .unwrap()
+      .get("plugin-assets")
+      .cloned()
+      .unwrap();
+    let response = (protocol.protocol)(&handle, &Request::new(Vec::new())).unwrap();
+    assert_eq!(response.body(), b"second");
+  }
+
+  #[test]
+  fn check_get_url() {
+    let manager = manager();
 
     #[cfg(custom_protocol)]
     assert_eq!(manager.get_url().to_string(), "tauri://localhost");
@@ -1077,6 +1320,55 @@ mod test {
     #[cfg(dev)]
     assert_eq!(manager.get_url().to_string(), "http://localhost:4000/");
   }
+
+  #[cfg(custom_protocol)]
+  #[test]
+  fn resolve_asset_index_fallback() {
+    let manager = manager();
+    assert!(manager.get_asset("/missing".into()).is_ok());
+    assert!(manager
+      .resolve_asset("/missing".into(), false, None)
+      .is_err());
+    assert!(manager
+      .resolve_asset("/index.html".into(), false, None)
+      .is_ok());
+  }
+
+  #[cfg(custom_protocol)]
+  #[test]
+  fn resolve_asset_window_csp() {
+    let manager = manager();
+    let asset = manager
+      .resolve_asset(
+        "/index.html".into(),
+        false,
+        Some(Csp::Policy("default-src 'none'".into())),
+      )
+      .unwrap();
+    assert!(asset.csp_header.unwrap().contains("default-src 'none'"));
+  }
+
+  #[test]
+  fn csp_nonces_change_on_every_load() {
+    let html = format!("<script nonce=\"{}\"></script>", SCRIPT_NONCE_TOKEN);
+    let load = || {
+      let mut asset = html.clone();
+      let mut csp = HashMap::new();
+      replace_csp_nonce(
+        &mut asset,
+        SCRIPT_NONCE_TOKEN,
+        &mut csp,
+        "script-src",
+        Vec::new(),
+      );
+      (asset, Csp::DirectiveMap(csp).to_string())
+    };
+    let (first_asset, first_csp) = load();
+    let (second_asset, second_csp) = load();
+    assert_ne!(first_asset, second_asset);
+    assert_ne!(first_csp, second_csp);
+    assert!(first_csp.starts_with("script-src 'self' 'nonce-"));
+  }
 }
 
 impl<R: Runtime> WindowManager<R> {
@@ -1086,6 +1378,8 @@ impl<R: Runtime> WindowManager<R> {
 
   pub fn run_on_page_load(&self, window: Window<R>, payload: PageLoadPayload) {
     (self.inner.on_page_load)(window.clone(), payload.clone());
+    #[cfg(desktop)]
+    crate::splashscreen::on_page_load(&window);
     self
       .inner
       .plugins
@@ -1094,6 +1388,36 @@ impl<R: Runtime> WindowManager<R> {
       .on_page_load(window, payload);
   }
 
+  /// Runs the plugins navigation hooks, returning `false` if the navigation must be cancelled.
+  pub(crate) fn run_on_navigation(&self, window: &Window<R>, url: &Url) -> bool {
+    self
+      .inner
+      .plugins
+      .lock()
+      .expect("poisoned plugin store")
+      .on_navigation(window, url)
+  }
+
+  /// Runs the plugins data drop hooks, returning `false` if the event must be ignored.
+  pub(crate) fn run_on_data_drop(&self, window: &Window<R>, event: &DropEvent) -> bool {
+    self
+      .inner
+      .plugins
+      .lock()
+      .expect("poisoned plugin store")
+      .on_data_drop(window, event)
+  }
+
+  /// Runs the plugins file drop hooks, returning `false` if the event must be ignored.
+  pub(crate) fn run_on_file_drop(&self, window: &Window<R>, event: &FileDropEvent) -> bool {
+    self
+      .inner
+      .plugins
+      .lock()
+      .expect("poisoned plugin store")
+      .on_file_drop(window, event)
+  }
+
   pub fn extend_api(&self, invoke: Invoke<R>) {
     self
       .inner
@@ -1122,7 +1446,25 @@ impl<R: Runtime> WindowManager<R> {
     if self.windows_lock().contains_key(&pending.label) {
       return Err(crate::Error::WindowLabelAlreadyExists(pending.label));
     }
-    #[allow(unused_mut)] // mut url only for the data-url parsing
+
+    let manager = self.clone();
+    let label = pending.label.clone();
+    pending.navigation_handler = Some(Box::new(move |url| {
+      // the initial URL may be loaded before the window is attached
+      match manager.wait_for_window(&label) {
+        Some(window) => manager.run_on_navigation(&window, &url),
+        None => {
+          // the plugins must not be bypassed
+          crate::log::warn!(
+            "cancelled the navigation of window `{}` to {}: the window was not attached",
+            label,
+            url
+          );
+          false
+        }
+      }
+    }));
+    #[allow(unused_mut)] // mut url only for the data-url parsing and the dev session
     let (is_local, mut url) = match &pending.webview_attributes.url {
       WindowUrl::App(path) => {
         let url = self.get_url();
@@ -1155,7 +1497,7 @@ impl<R: Runtime> WindowManager<R> {
     }
 
     #[cfg(feature = "window-data-url")]
-    if let Some(csp) = self.csp() {
+    if let Some(csp) = self.webview_csp(&pending.webview_attributes) {
       if url.scheme() == "data" {
         if let Ok(data_url) = data_url::DataUrl::process(url.as_str()) {
           let (body, _) = data_url.decode_to_vec().unwrap();
@@ -1170,6 +1512,37 @@ impl<R: Runtime> WindowManager<R> {
       }
     }
 
+    #[cfg(all(feature = "localhost", not(dev)))]
+    if let Some(server) = self.inner.state.try_get::<LocalhostServer>() {
+      server.inner().authorize_url(&mut url);
+    }
+
+    // restore the geometry saved by `Builder::persist_window_state`
+    #[cfg(desktop)]
+    if let Some(states) = self
+      .inner
+      .state
+      .try_get::<crate::window_state::WindowStates>()
+    {
+      pending.window_builder = states
+        .inner()
+        .restore(&pending.label, pending.window_builder);
+    }
+
+    // load the window as it was before `tauri dev` restarted the app
+    #[cfg(all(dev, desktop))]
+    if let Some(session) = self
+      .inner
+      .state
+      .try_get::<Arc<crate::dev_session::DevSession>>()
+    {
+      let session = session.inner();
+      if let Some(restored_url) = session.restored_url(&pending.label, &url) {
+        url = restored_url;
+      }
+      pending.window_builder = session.restore(&pending.label, pending.window_builder);
+    }
+
     pending.url = url.to_string();
 
     if !pending.window_builder.has_icon() {
@@ -1180,6 +1553,22 @@ impl<R: Runtime> WindowManager<R> {
       }
     }
 
+    let drop_types = pending.webview_attributes.accept_drop_types.clone();
+    if drop_types.is_empty() {
+      self.inner.drop_types.lock().unwrap().remove(&pending.label);
+    } else {
+      pending
+        .webview_attributes
+        .initialization_scripts
+        .push(crate::window::drop_script(&drop_types));
+      self
+        .inner
+        .drop_types
+        .lock()
+        .unwrap()
+        .insert(pending.label.clone(), drop_types);
+    }
+
     if pending.window_builder.get_menu().is_none() {
       if let Some(menu) = &self.inner.menu {
         pending = pending.set_menu(menu.clone());
@@ -1235,6 +1624,13 @@ impl<R: Runtime> WindowManager<R> {
     let window_event_listeners = self.inner.window_event_listeners.clone();
     let manager = self.clone();
     window.on_window_event(move |event| {
+      match event {
+        WindowEvent::FileDrop(file_drop) if !manager.run_on_file_drop(&window_, file_drop) => {
+          return
+        }
+        WindowEvent::Drop(drop) if !manager.run_on_data_drop(&window_, drop) => return,
+        _ => (),
+      }
       let _ = on_window_event(&window_, &manager, event);
       for handler in window_event_listeners.iter() {
         handler(GlobalWindowEvent {
@@ -1263,6 +1659,7 @@ impl<R: Runtime> WindowManager<R> {
         .windows_lock()
         .insert(window.label().to_string(), window.clone());
     }
+    self.inner.window_attached.notify_all();
 
     // let plugins know that a new window has been added to the manager
     let manager = self.inner.clone();
@@ -1281,6 +1678,14 @@ impl<R: Runtime> WindowManager<R> {
 
   pub(crate) fn on_window_close(&self, label: &str) {
     self.windows_lock().remove(label);
+    self.inner.window_groups.lock().unwrap().remove(label);
+    self.inner.drop_types.lock().unwrap().remove(label);
+    #[cfg(feature = "isolation")]
+    self.inner.isolation_keys.lock().unwrap().remove(label);
+    if let Some(channels) = self.state().try_get::<crate::channel::Channels>() {
+      channels.close_window(label);
+    }
+    crate::endpoints::on_window_close(label);
   }
 
   pub fn emit_filter<S, F>(
@@ -1295,6 +1700,8 @@ impl<R: Runtime> WindowManager<R> {
     F: Fn(&Window<R>) -> bool,
   {
     assert_event_name_is_valid(event);
+    #[cfg(any(test, feature = "test"))]
+    self.record_event(event, source_window_label, &payload, &filter)?;
     self
       .windows_lock()
       .values()
@@ -1302,14 +1709,69 @@ impl<R: Runtime> WindowManager<R> {
       .try_for_each(|window| window.emit_internal(event, source_window_label, payload.clone()))
   }
 
+  #[cfg(any(test, feature = "test"))]
+  pub(crate) fn add_event_recorder(&self, recorder: &Arc<crate::test::RecorderState>) {
+    self
+      .inner
+      .event_recorders
+      .lock()
+      .unwrap()
+      .push(Arc::downgrade(recorder));
+  }
+
+  #[cfg(any(test, feature = "test"))]
+  fn record_event<S: Serialize, F: Fn(&Window<R>) -> bool>(
+    &self,
+    event: &str,
+    source_window_label: Option<&str>,
+    payload: &S,
+    filter: &F,
+  ) -> crate::Result<()> {
+    let mut recorders = self.inner.event_recorders.lock().unwrap();
+    recorders.retain(|r| r.strong_count() > 0);
+    if recorders.is_empty() {
+      return Ok(());
+    }
+
+    let mut windows = self
+      .windows_lock()
+      .values()
+      .filter(|&w| filter(w))
+      .map(|w| w.label().to_string())
+      .collect::<Vec<_>>();
+    windows.sort();
+    let recorded = crate::test::RecordedEvent {
+      event: event.into(),
+      source_window_label: source_window_label.map(Into::into),
+      windows,
+      payload: serde_json::to_value(payload)?,
+    };
+    for recorder in recorders.iter().filter_map(|r| r.upgrade()) {
+      recorder.record(recorded.clone());
+    }
+    Ok(())
+  }
+
   pub fn labels(&self) -> HashSet<String> {
     self.windows_lock().keys().cloned().collect()
   }
 
   pub fn config(&self) -> Arc<Config> {
+    #[cfg(config_reload)]
+    if let Some(config) = &*self.inner.reloaded_config.lock().unwrap() {
+      return config.clone();
+    }
     self.inner.config.clone()
   }
 
+  /// Replaces the configuration returned by [`Self::config`].
+  ///
+  /// Values read when the app starts, such as the CSP or the `distDir`, are not affected.
+  #[cfg(config_reload)]
+  pub(crate) fn set_config(&self, config: Arc<Config>) {
+    self.inner.reloaded_config.lock().unwrap().replace(config);
+  }
+
   pub fn package_info(&self) -> &PackageInfo {
     &self.inner.package_info
   }
@@ -1358,6 +1820,82 @@ impl<R: Runtime> WindowManager<R> {
   pub fn windows(&self) -> HashMap<String, Window<R>> {
     self.windows_lock().clone()
   }
+
+  pub(crate) fn set_window_group(&self, label: &str, group: Option<String>) {
+    let mut groups = self.inner.window_groups.lock().unwrap();
+    match group {
+      Some(group) => groups.insert(label.into(), group),
+      None => groups.remove(label),
+    };
+  }
+
+  /// Checks a drag and drop event reported by the drop script of the window against its accepted types,
+  /// sending the accepted data to the window event handlers.
+  pub(crate) fn on_drop(&self, window: &Window<R>, event: DropEvent) -> crate::Result<()> {
+    let types = self
+      .inner
+      .drop_types
+      .lock()
+      .unwrap()
+      .get(window.label())
+      .cloned()
+      .ok_or(crate::Error::DropNotAccepted)?;
+    let event = match event {
+      DropEvent::Hovered {
+        position,
+        types: dragged,
+      } => DropEvent::Hovered {
+        position,
+        types: dragged
+          .into_iter()
+          .filter(|t| crate::window::accepts_drop_type(&types, t))
+          .collect(),
+      },
+      DropEvent::Dropped(mut data) => {
+        data
+          .items
+          .retain(|t, _| crate::window::accepts_drop_type(&types, t));
+        DropEvent::Dropped(data)
+      }
+    };
+    let empty = match &event {
+      DropEvent::Hovered { types, .. } => types.is_empty(),
+      DropEvent::Dropped(data) => data.items.is_empty(),
+    };
+    if empty {
+      return Err(crate::Error::DropNotAccepted);
+    }
+    window.dispatcher().dispatch_drop(event).map_err(Into::into)
+  }
+
+  pub(crate) fn window_group(&self, label: &str) -> Option<String> {
+    self.inner.window_groups.lock().unwrap().get(label).cloned()
+  }
+
+  /// The labels of the windows in the group.
+  ///
+  /// The groups lock is released before the windows are locked, so it is never held with the windows lock.
+  pub(crate) fn window_labels_in_group(&self, group: &str) -> HashSet<String> {
+    self
+      .inner
+      .window_groups
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|(_, g)| *g == group)
+      .map(|(label, _)| label.clone())
+      .collect()
+  }
+
+  pub fn windows_in_group(&self, group: &str) -> HashMap<String, Window<R>> {
+    let labels = self.window_labels_in_group(group);
+    self
+      .windows_lock()
+      .iter()
+      .filter(|(label, _)| labels.contains(*label))
+      .map(|(label, window)| (label.clone(), window.clone()))
+      .collect()
+  }
 }
 
 /// Tray APIs
@@ -1448,6 +1986,13 @@ fn on_window_event<R: Runtime>(
       FileDropEvent::Cancelled => window.emit(WINDOW_FILE_DROP_CANCELLED_EVENT, ())?,
       _ => unimplemented!(),
     },
+    WindowEvent::Drop(event) => match event {
+      DropEvent::Hovered { position, types } => window.emit(
+        WINDOW_DROP_HOVER_EVENT,
+        serde_json::json!({ "position": position, "types": types }),
+      )?,
+      DropEvent::Dropped(data) => window.emit(WINDOW_DROP_EVENT, data)?,
+    },
     WindowEvent::ThemeChanged(theme) => window.emit(WINDOW_THEME_CHANGED, theme.to_string())?,
   }
   Ok(())
@@ -1464,37 +2009,175 @@ fn on_menu_event<R: Runtime>(window: &Window<R>, event: &MenuEvent) -> crate::Re
   window.emit(MENU_EVENT, event.menu_item_id.clone())
 }
 
-#[cfg(feature = "isolation")]
-fn request_to_path(request: &tauri_runtime::http::Request, base_url: &str) -> String {
-  let mut path = request
-    .uri()
-    .split(&['?', '#'][..])
-    // ignore query string
-    .next()
-    .unwrap()
-    .trim_start_matches(base_url)
-    .to_string();
-
+/// Normalizes a `tauri://localhost` request path to an asset path.
+fn asset_path(mut path: String) -> String {
   if path.ends_with('/') {
     path.pop();
   }
-
-  let path = percent_encoding::percent_decode(path.as_bytes())
+  path = percent_encoding::percent_decode(path.as_bytes())
     .decode_utf8_lossy()
     .to_string();
-
   if path.is_empty() {
-    // if the url has no path, we should load `index.html`
+    // if the url is `tauri://localhost`, we should load `index.html`
     "index.html".to_string()
   } else {
     // skip leading `/`
-    path.chars().skip(1).collect()
+    path.chars().skip(1).collect::<String>()
   }
 }
 
+/// Whether the request `Accept-Encoding` header allows Brotli encoded responses.
+#[cfg(all(
+  feature = "compression",
+  any(not(target_os = "linux"), feature = "linux-protocol-headers")
+))]
+fn accepts_brotli(request: &HttpRequest) -> bool {
+  request
+    .headers()
+    .get_all("Accept-Encoding")
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(|value| value.split(','))
+    .any(|encoding| {
+      let mut params = encoding.split(';').map(str::trim);
+      params.next() == Some("br")
+        && params.all(|param| {
+          param
+            .strip_prefix("q=")
+            .and_then(|q| q.parse::<f32>().ok())
+            .map(|q| q > 0.0)
+            .unwrap_or(true)
+        })
+    })
+}
+
+#[cfg(feature = "isolation")]
+fn request_to_path(request: &tauri_runtime::http::Request, base_url: &str) -> String {
+  asset_path(
+    request
+      .uri()
+      .split(&['?', '#'][..])
+      // ignore query string
+      .next()
+      .unwrap()
+      .trim_start_matches(base_url)
+      .to_string(),
+  )
+}
+
 #[cfg(test)]
 mod tests {
   use super::replace_with_callback;
+  #[cfg(any(not(target_os = "linux"), feature = "linux-protocol-headers"))]
+  use crate::runtime::http::{
+    header::{HeaderMap, HeaderValue},
+    method::Method,
+    Request as HttpRequest, RequestParts as HttpRequestParts,
+  };
+
+  #[cfg(all(
+    feature = "compression",
+    any(not(target_os = "linux"), feature = "linux-protocol-headers")
+  ))]
+  #[test]
+  fn accept_encoding() {
+    use super::accepts_brotli;
+
+    let request = |accept_encoding: &'static str| {
+      let mut headers = HeaderMap::new();
+      headers.insert("Accept-Encoding", HeaderValue::from_static(accept_encoding));
+      HttpRequest::from_parts(
+        HttpRequestParts {
+          method: Method::GET,
+          uri: "tauri://localhost/app.js".into(),
+          headers,
+        },
+        Vec::new(),
+      )
+    };
+    assert!(accepts_brotli(&request("gzip, deflate, br")));
+    assert!(accepts_brotli(&request("br;q=0.5")));
+    assert!(!accepts_brotli(&request("gzip, deflate")));
+    assert!(!accepts_brotli(&request("br;q=0")));
+  }
+
+  #[cfg(any(not(target_os = "linux"), feature = "linux-protocol-headers"))]
+  #[test]
+  fn asset_etag() {
+    use super::{asset_response, Asset, HttpResponseBuilder};
+
+    let asset = || Asset {
+      bytes: b"console.log('hello')".to_vec(),
+      mime_type: "text/javascript".into(),
+      csp_header: None,
+    };
+    let response = asset_response(
+      HttpResponseBuilder::new(),
+      &HttpRequest::new(Vec::new()),
+      asset(),
+    )
+    .unwrap();
+    assert_eq!(response.status(), 200);
+    let etag = response.headers()["ETag"].clone();
+
+    let mut headers = HeaderMap::new();
+    headers.insert("If-None-Match", etag);
+    let request = HttpRequest::from_parts(
+      HttpRequestParts {
+        method: Method::GET,
+        uri: "tauri://localhost/app.js".into(),
+        headers,
+      },
+      Vec::new(),
+    );
+    let response = asset_response(HttpResponseBuilder::new(), &request, asset()).unwrap();
+    assert_eq!(response.status(), 304);
+    assert!(response.body().is_empty());
+  }
+
+  #[test]
+  fn on_asset_response() {
+    use crate::utils::assets::{AssetKey, Assets, CspHash};
+    use std::borrow::Cow;
+
+    struct TestAssets;
+
+    impl Assets for TestAssets {
+      fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+        (key.as_ref() == "/app.js").then(|| Cow::Borrowed(&b"console.log('hello')"[..]))
+      }
+
+      fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+        Box::new(std::iter::empty())
+      }
+    }
+
+    let app = crate::test::mock_builder()
+      .on_asset_response(|_app, request, response| {
+        assert_eq!(request.uri(), "tauri://localhost/app.js");
+        response
+          .headers_mut()
+          .insert("X-Asset-Hook", "applied".parse().unwrap());
+      })
+      .build(crate::test::mock_context(TestAssets))
+      .unwrap();
+    let handle = app.handle();
+    let protocol =
+      handle
+        .manager
+        .prepare_uri_scheme_protocol("tauri://localhost", handle.clone(), None, None);
+    let request = crate::runtime::http::Request::from_parts(
+      crate::runtime::http::RequestParts {
+        method: crate::runtime::http::method::Method::GET,
+        uri: "tauri://localhost/app.js".into(),
+        headers: Default::default(),
+      },
+      Vec::new(),
+    );
+    let response = protocol(&request).unwrap();
+    assert_eq!(response.headers()["X-Asset-Hook"], "applied");
+    assert_eq!(response.body(), b"console.log('hello')");
+  }
 
   #[test]
   fn string_replace_with_callback() {
@@ -0,0 +1,201 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Resource usage of the application and its webview processes.
+
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Mutex,
+  time::Duration,
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+
+use crate::{manager::WindowManager, AppHandle, EventLoopMessage, Runtime};
+
+/// The event emitted to the webviews with the [`Metrics`] when [`Builder::metrics_interval`](crate::Builder#method.metrics_interval) is set
+/// and the `process > metrics` allowlist flag is enabled.
+pub const METRICS_EVENT: &str = "tauri://metrics";
+
+/// The minimum interval of the periodic measures, so the CPU usage is meaningful.
+pub(crate) const MIN_INTERVAL: Duration = Duration::from_millis(500);
+
+// the CPU usage is computed from the difference with the previous refresh
+static SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new()));
+
+/// The resource usage of a process.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ProcessMetrics {
+  /// The process identifier.
+  pub pid: u32,
+  /// The process name.
+  pub name: String,
+  /// The CPU usage since the previous measure, in percent of a single core.
+  pub cpu_usage: f32,
+  /// The resident memory in bytes.
+  pub memory: u64,
+}
+
+/// The resource usage of the application, returned by `App::metrics`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Metrics {
+  /// The CPU usage of all the processes since the previous measure, in percent of a single core.
+  ///
+  /// The first measure is always `0`.
+  pub cpu_usage: f32,
+  /// The resident memory of all the processes in bytes.
+  pub memory: u64,
+  /// The application process followed by its child processes,
+  /// such as the WebView2 browser and renderer processes on Windows and the WebKitGTK processes on Linux.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The WebKit processes are not children of the application, so they are not included.
+  pub processes: Vec<ProcessMetrics>,
+  /// The number of windows.
+  pub windows: usize,
+  /// The number of visible windows.
+  pub visible_windows: usize,
+}
+
+/// The process and its descendants, the process first.
+fn process_tree(pid: Pid, parents: &HashMap<Pid, Option<Pid>>) -> Vec<Pid> {
+  let mut children = HashMap::<Pid, Vec<Pid>>::new();
+  for (child, parent) in parents {
+    if let Some(parent) = parent {
+      children.entry(*parent).or_default().push(*child);
+    }
+  }
+
+  let mut tree = vec![pid];
+  let mut visited: HashSet<Pid> = tree.iter().copied().collect();
+  let mut index = 0;
+  while index < tree.len() {
+    if let Some(children) = children.get_mut(&tree[index]) {
+      children.sort();
+      for child in children.iter() {
+        // a reused pid can make a cycle
+        if visited.insert(*child) {
+          tree.push(*child);
+        }
+      }
+    }
+    index += 1;
+  }
+  tree
+}
+
+pub(crate) fn collect<R: Runtime>(manager: &WindowManager<R>) -> crate::Result<Metrics> {
+  let windows = manager.windows();
+  let visible_windows = windows
+    .values()
+    .filter(|window| window.is_visible().unwrap_or_default())
+    .count();
+
+  let pid = sysinfo::get_current_pid().map_err(|e| crate::Error::Metrics(e.into()))?;
+  let mut system = SYSTEM.lock().unwrap();
+  system.refresh_cpu();
+  // only lists the processes to find the tree of the app, the usage of the tree is refreshed below
+  system.refresh_processes_specifics(ProcessRefreshKind::new());
+  let parents = system
+    .processes()
+    .iter()
+    .map(|(pid, process)| (*pid, process.parent()))
+    .collect();
+  let tree = process_tree(pid, &parents);
+  for pid in &tree {
+    system.refresh_process(*pid);
+  }
+  let processes: Vec<ProcessMetrics> = tree
+    .into_iter()
+    .filter_map(|pid| system.process(pid))
+    .map(|process| ProcessMetrics {
+      pid: process.pid().as_u32(),
+      name: process.name().into(),
+      cpu_usage: process.cpu_usage(),
+      memory: process.memory(),
+    })
+    .collect();
+
+  Ok(Metrics {
+    cpu_usage: processes.iter().map(|p| p.cpu_usage).sum(),
+    memory: processes.iter().map(|p| p.memory).sum(),
+    processes,
+    windows: windows.len(),
+    visible_windows,
+  })
+}
+
+/// Spawns a thread measuring the metrics periodically, at least every [`MIN_INTERVAL`],
+/// emitting [`RunEvent::Metrics`](crate::RunEvent#variant.Metrics) and, when allowlisted, the [`METRICS_EVENT`] to the webviews.
+pub(crate) fn spawn<R: Runtime>(app_handle: AppHandle<R>, interval: Duration) {
+  let interval = interval.max(MIN_INTERVAL);
+  std::thread::spawn(move || {
+    // the first measure primes the CPU usage
+    let _ = collect(&app_handle.manager);
+    let mut failing = false;
+    loop {
+      std::thread::sleep(interval);
+      match collect(&app_handle.manager) {
+        Ok(metrics) => {
+          failing = false;
+          #[cfg(process_metrics)]
+          {
+            use crate::Manager;
+            let _ = app_handle.emit_all(METRICS_EVENT, &metrics);
+          }
+          if app_handle
+            .create_proxy()
+            .send_event(EventLoopMessage::Metrics(metrics))
+            .is_err()
+          {
+            break;
+          }
+        }
+        // only logs the first failure in a row
+        Err(e) if !failing => {
+          failing = true;
+          crate::log::warn!("failed to measure the metrics: {}", e);
+        }
+        Err(_) => {}
+      }
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::process_tree;
+  use std::collections::HashMap;
+  use sysinfo::{Pid, PidExt};
+
+  #[test]
+  fn finds_child_processes() {
+    let pid = |pid: u32| Pid::from_u32(pid);
+    let parents: HashMap<Pid, Option<Pid>> = [
+      (pid(1), None),
+      (pid(100), Some(pid(1))),
+      // the webview browser process and its renderers
+      (pid(101), Some(pid(100))),
+      (pid(103), Some(pid(101))),
+      (pid(102), Some(pid(101))),
+      // an unrelated process
+      (pid(200), Some(pid(1))),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+      process_tree(pid(100), &parents),
+      vec![pid(100), pid(101), pid(102), pid(103)]
+    );
+    assert_eq!(process_tree(pid(300), &parents), vec![pid(300)]);
+  }
+}
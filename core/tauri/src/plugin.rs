@@ -5,14 +5,18 @@
 //! The Tauri plugin extension to expand Tauri functionality.
 
 use crate::{
-  utils::config::PluginConfig, AppHandle, Invoke, InvokeHandler, PageLoadPayload, RunEvent,
-  Runtime, Window,
+  manager::CustomProtocol,
+  runtime::http::{Request as HttpRequest, Response as HttpResponse},
+  utils::config::PluginConfig,
+  AppHandle, Invoke, InvokeHandler, Manager, PageLoadPayload, RunEvent, Runtime, Scopes, Window,
 };
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
 use tauri_macros::default_runtime;
+use tauri_runtime::window::{DropEvent, FileDropEvent};
+use url::Url;
 
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 /// The result type of Tauri plugin module.
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -22,6 +26,13 @@ pub trait Plugin<R: Runtime>: Send {
   /// The plugin name. Used as key on the plugin config object.
   fn name(&self) -> &'static str;
 
+  /// The JSON Schema of the plugin configuration on `tauri.conf.json > plugins > yourPluginName`.
+  ///
+  /// With the `plugin-config-schema` feature, the configuration is validated against it before the plugin is initialized.
+  fn config_schema(&self) -> Option<JsonValue> {
+    None
+  }
+
   /// Initializes the plugin.
   #[allow(unused_variables)]
   fn initialize(&mut self, app: &AppHandle<R>, config: JsonValue) -> Result<()> {
@@ -45,9 +56,39 @@ pub trait Plugin<R: Runtime>: Send {
   fn created(&mut self, window: Window<R>) {}
 
   /// Callback invoked when the webview performs a navigation to a page.
+  ///
+  /// It runs for the main frame and for each child frame that loads the initialization scripts,
+  /// see [`PageLoadPayload::is_main_frame`].
   #[allow(unused_variables)]
   fn on_page_load(&mut self, window: Window<R>, payload: PageLoadPayload) {}
 
+  /// Callback invoked when the webview is about to navigate to the given URL, including its initial URL.
+  ///
+  /// Returning `false` cancels the navigation.
+  #[allow(unused_variables)]
+  fn on_navigation(&mut self, window: &Window<R>, url: &Url) -> bool {
+    true
+  }
+
+  /// Callback invoked when files are hovered, dropped or the hover is cancelled on the window.
+  ///
+  /// Returning `false` prevents the event from being emitted to the webview
+  /// and the dropped paths from being added to the filesystem scopes.
+  #[allow(unused_variables)]
+  fn on_file_drop(&mut self, window: &Window<R>, event: &FileDropEvent) -> bool {
+    true
+  }
+
+  /// Callback invoked when data other than files is hovered or dropped on a window accepting it,
+  /// see [`crate::window::WindowBuilder::accept_drop_types`].
+  ///
+  /// The data comes from the page, so it must be validated like any other input of the webview.
+  /// Returning `false` prevents the event from being emitted to the webview and the window event listeners.
+  #[allow(unused_variables)]
+  fn on_data_drop(&mut self, window: &Window<R>, event: &DropEvent) -> bool {
+    true
+  }
+
   /// Callback invoked when the event loop receives a new event.
   #[allow(unused_variables)]
   fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {}
@@ -55,6 +96,65 @@ pub trait Plugin<R: Runtime>: Send {
   /// Extend commands to [`crate::Builder::invoke_handler`].
   #[allow(unused_variables)]
   fn extend_api(&mut self, invoke: Invoke<R>) {}
+
+  /// The permissions that must be granted to a window to invoke the plugin commands.
+  ///
+  /// See [`Permission`] for more information.
+  fn permissions(&self) -> Vec<Permission> {
+    Vec::new()
+  }
+}
+
+/// A named permission that grants access to a set of plugin commands.
+///
+/// Once a plugin declares permissions, each of its commands can only be invoked by the windows
+/// granted a permission that includes it on the [`tauri > security > pluginPermissions`](crate::utils::config::SecurityConfig#structfield.plugin_permissions) configuration,
+/// where the permission is identified as `<plugin-name>:<permission-name>`.
+/// Commands not included in any permission are rejected.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::{plugin::{Builder, Permission, TauriPlugin}, Runtime};
+///
+/// fn init<R: Runtime>() -> TauriPlugin<R> {
+///   Builder::new("store")
+///     .permission(Permission::new("read").command("get").command("keys"))
+///     .permission(Permission::new("write").command("set").command("delete"))
+///     .build()
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Permission {
+  name: String,
+  commands: Vec<String>,
+}
+
+impl Permission {
+  /// Creates a permission with the given name and no commands.
+  pub fn new(name: impl Into<String>) -> Self {
+    Self {
+      name: name.into(),
+      commands: Vec::new(),
+    }
+  }
+
+  /// Includes the given command on the permission.
+  #[must_use]
+  pub fn command(mut self, command: impl Into<String>) -> Self {
+    self.commands.push(command.into());
+    self
+  }
+
+  /// The permission name.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The commands included on the permission.
+  pub fn commands(&self) -> &[String] {
+    &self.commands
+  }
 }
 
 type SetupHook<R> = dyn FnOnce(&AppHandle<R>) -> Result<()> + Send;
@@ -62,6 +162,9 @@ type SetupWithConfigHook<R, T> = dyn FnOnce(&AppHandle<R>, T) -> Result<()> + Se
 type OnWebviewReady<R> = dyn FnMut(Window<R>) + Send;
 type OnEvent<R> = dyn FnMut(&AppHandle<R>, &RunEvent) + Send;
 type OnPageLoad<R> = dyn FnMut(Window<R>, PageLoadPayload) + Send;
+type OnNavigation<R> = dyn FnMut(&Window<R>, &Url) -> bool + Send;
+type OnFileDrop<R> = dyn FnMut(&Window<R>, &FileDropEvent) -> bool + Send;
+type OnDataDrop<R> = dyn FnMut(&Window<R>, &DropEvent) -> bool + Send;
 type OnDrop<R> = dyn FnOnce(AppHandle<R>) + Send;
 
 /// Builds a [`TauriPlugin`].
@@ -143,9 +246,15 @@ pub struct Builder<R: Runtime, C: DeserializeOwned = ()> {
   setup_with_config: Option<Box<SetupWithConfigHook<R, C>>>,
   js_init_script: Option<String>,
   on_page_load: Box<OnPageLoad<R>>,
+  on_navigation: Box<OnNavigation<R>>,
+  on_file_drop: Box<OnFileDrop<R>>,
+  on_data_drop: Box<OnDataDrop<R>>,
   on_webview_ready: Box<OnWebviewReady<R>>,
   on_event: Box<OnEvent<R>>,
   on_drop: Option<Box<OnDrop<R>>>,
+  permissions: Vec<Permission>,
+  uri_scheme_protocols: HashMap<String, Arc<CustomProtocol<R>>>,
+  config_schema: Option<JsonValue>,
 }
 
 impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
@@ -158,9 +267,15 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
       js_init_script: None,
       invoke_handler: Box::new(|_| ()),
       on_page_load: Box::new(|_, _| ()),
+      on_navigation: Box::new(|_, _| true),
+      on_file_drop: Box::new(|_, _| true),
+      on_data_drop: Box::new(|_, _| true),
       on_webview_ready: Box::new(|_| ()),
       on_event: Box::new(|_, _| ()),
       on_drop: None,
+      permissions: Vec::new(),
+      uri_scheme_protocols: Default::default(),
+      config_schema: None,
     }
   }
 
@@ -330,6 +445,92 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self
   }
 
+  /// Callback invoked when the webview is about to navigate to a URL, including its initial URL. Returning `false` cancels the navigation.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .on_navigation(|window, url| {
+  ///       // only allow navigating to the app's own pages
+  ///       url.scheme() == "tauri" || url.domain() == Some("tauri.localhost")
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn on_navigation<F>(mut self, on_navigation: F) -> Self
+  where
+    F: FnMut(&Window<R>, &Url) -> bool + Send + 'static,
+  {
+    self.on_navigation = Box::new(on_navigation);
+    self
+  }
+
+  /// Callback invoked when files are hovered, dropped or the hover is cancelled on a window.
+  /// Returning `false` prevents the event from reaching the app.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, FileDropEvent, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .on_file_drop(|window, event| {
+  ///       // ignore executables dropped on the window
+  ///       match event {
+  ///         FileDropEvent::Dropped(paths) => !paths.iter().any(|p| p.extension().map_or(false, |e| e == "exe")),
+  ///         _ => true,
+  ///       }
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn on_file_drop<F>(mut self, on_file_drop: F) -> Self
+  where
+    F: FnMut(&Window<R>, &FileDropEvent) -> bool + Send + 'static,
+  {
+    self.on_file_drop = Box::new(on_file_drop);
+    self
+  }
+
+  /// Callback invoked when data other than files is hovered or dropped on a window accepting it,
+  /// see [`crate::window::WindowBuilder::accept_drop_types`].
+  /// Returning `false` prevents the event from reaching the app.
+  ///
+  /// The data comes from the page, so it must be validated like any other input of the webview.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, DropEvent, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .on_data_drop(|window, event| {
+  ///       // only accept drops on the main window
+  ///       match event {
+  ///         DropEvent::Dropped(_) => window.label() == "main",
+  ///         _ => true,
+  ///       }
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn on_data_drop<F>(mut self, on_data_drop: F) -> Self
+  where
+    F: FnMut(&Window<R>, &DropEvent) -> bool + Send + 'static,
+  {
+    self.on_data_drop = Box::new(on_data_drop);
+    self
+  }
+
   /// Callback invoked when the webview is created.
   ///
   /// # Examples
@@ -411,6 +612,121 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self
   }
 
+  /// Declares a permission required to invoke some of the plugin commands.
+  ///
+  /// See [`Permission`] for more information.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, Permission, TauriPlugin}, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .permission(Permission::new("read").command("read_file"))
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn permission(mut self, permission: Permission) -> Self {
+    self.permissions.push(permission);
+    self
+  }
+
+  /// Sets the JSON Schema of the plugin configuration on `tauri.conf.json > plugins > yourPluginName`.
+  ///
+  /// With the `plugin-config-schema` feature, the configuration is validated against it before the plugin is initialized,
+  /// reporting the path of each invalid value instead of a deserialization error.
+  /// The Tauri CLI also validates the configuration when the plugin crate declares the schema file
+  /// on its `Cargo.toml` with `package.metadata.tauri-plugin.name` and `package.metadata.tauri-plugin.config-schema`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// #[derive(serde::Deserialize)]
+  /// struct Config {
+  ///   api_url: String,
+  /// }
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R, Config> {
+  ///   Builder::<R, Config>::new("example")
+  ///     .config_schema(serde_json::json!({
+  ///       "type": "object",
+  ///       "properties": { "api_url": { "type": "string" } },
+  ///       "required": ["api_url"]
+  ///     }))
+  ///     .setup_with_config(|_app, config: Config| {
+  ///       println!("{}", config.api_url);
+  ///       Ok(())
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn config_schema(mut self, schema: JsonValue) -> Self {
+    self.config_schema.replace(schema);
+    self
+  }
+
+  /// Registers a URI scheme protocol available to the webviews created after the plugin is initialized.
+  ///
+  /// The scheme must be the plugin name or start with `<plugin-name>-`,
+  /// and a scheme already registered by the app is not replaced.
+  /// See [`crate::Builder::register_uri_scheme_protocol`] for the arguments.
+  ///
+  /// # Panics
+  ///
+  /// * Panics if the scheme is not scoped to the plugin name.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{http::ResponseBuilder, plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("media")
+  ///     .register_uri_scheme_protocol("media", |_app, request| {
+  ///       ResponseBuilder::new()
+  ///         .mimetype("text/plain")
+  ///         .body(request.uri().as_bytes().to_vec())
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn register_uri_scheme_protocol<
+    N: Into<String>,
+    H: Fn(
+        &AppHandle<R>,
+        &HttpRequest,
+      ) -> std::result::Result<HttpResponse, Box<dyn std::error::Error>>
+      + Send
+      + Sync
+      + 'static,
+  >(
+    mut self,
+    uri_scheme: N,
+    protocol: H,
+  ) -> Self {
+    let uri_scheme = uri_scheme.into();
+    assert!(
+      is_plugin_scheme(self.name, &uri_scheme),
+      "URI scheme '{}' must be '{}' or start with '{}-'",
+      uri_scheme,
+      self.name,
+      self.name
+    );
+    self.uri_scheme_protocols.insert(
+      uri_scheme,
+      Arc::new(CustomProtocol {
+        protocol: Box::new(protocol),
+      }),
+    );
+    self
+  }
+
   /// Builds the [TauriPlugin].
   pub fn build(self) -> TauriPlugin<R, C> {
     TauriPlugin {
@@ -421,9 +737,15 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
       setup_with_config: self.setup_with_config,
       js_init_script: self.js_init_script,
       on_page_load: self.on_page_load,
+      on_navigation: self.on_navigation,
+      on_file_drop: self.on_file_drop,
+      on_data_drop: self.on_data_drop,
       on_webview_ready: self.on_webview_ready,
       on_event: self.on_event,
       on_drop: self.on_drop,
+      permissions: self.permissions,
+      uri_scheme_protocols: self.uri_scheme_protocols,
+      config_schema: self.config_schema,
     }
   }
 }
@@ -437,9 +759,15 @@ pub struct TauriPlugin<R: Runtime, C: DeserializeOwned = ()> {
   setup_with_config: Option<Box<SetupWithConfigHook<R, C>>>,
   js_init_script: Option<String>,
   on_page_load: Box<OnPageLoad<R>>,
+  on_navigation: Box<OnNavigation<R>>,
+  on_file_drop: Box<OnFileDrop<R>>,
+  on_data_drop: Box<OnDataDrop<R>>,
   on_webview_ready: Box<OnWebviewReady<R>>,
   on_event: Box<OnEvent<R>>,
   on_drop: Option<Box<OnDrop<R>>>,
+  permissions: Vec<Permission>,
+  uri_scheme_protocols: HashMap<String, Arc<CustomProtocol<R>>>,
+  config_schema: Option<JsonValue>,
 }
 
 impl<R: Runtime, C: DeserializeOwned> Drop for TauriPlugin<R, C> {
@@ -455,8 +783,19 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
     self.name
   }
 
+  fn config_schema(&self) -> Option<JsonValue> {
+    self.config_schema.clone()
+  }
+
   fn initialize(&mut self, app: &AppHandle<R>, config: JsonValue) -> Result<()> {
     self.app.replace(app.clone());
+    for (uri_scheme, protocol) in self.uri_scheme_protocols.drain() {
+      if !app.manager.has_uri_scheme_protocol(&uri_scheme) {
+        app
+          .manager
+          .register_uri_scheme_protocol(uri_scheme, protocol);
+      }
+    }
     if let Some(s) = self.setup.take() {
       (s)(app)?;
     }
@@ -478,6 +817,18 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
     (self.on_page_load)(window, payload)
   }
 
+  fn on_navigation(&mut self, window: &Window<R>, url: &Url) -> bool {
+    (self.on_navigation)(window, url)
+  }
+
+  fn on_file_drop(&mut self, window: &Window<R>, event: &FileDropEvent) -> bool {
+    (self.on_file_drop)(window, event)
+  }
+
+  fn on_data_drop(&mut self, window: &Window<R>, event: &DropEvent) -> bool {
+    (self.on_data_drop)(window, event)
+  }
+
   fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
     (self.on_event)(app, event)
   }
@@ -485,12 +836,50 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
   fn extend_api(&mut self, invoke: Invoke<R>) {
     (self.invoke_handler)(invoke)
   }
+
+  fn permissions(&self) -> Vec<Permission> {
+    self.permissions.clone()
+  }
+}
+
+/// Validates the configuration of a plugin against its JSON Schema.
+#[cfg(feature = "plugin-config-schema")]
+fn validate_config(plugin: &str, schema: &JsonValue, config: &JsonValue) -> crate::Result<()> {
+  let schema = jsonschema::JSONSchema::compile(schema).map_err(|e| {
+    crate::Error::PluginInitialization(plugin.into(), format!("invalid config schema: {}", e))
+  })?;
+  let result = schema.validate(config);
+  if let Err(errors) = result {
+    let errors = errors
+      .map(|error| {
+        let path = std::iter::once(format!("plugins > {}", plugin))
+          .chain(error.instance_path.clone().into_vec())
+          .collect::<Vec<_>>()
+          .join(" > ");
+        format!("`{}`: {}", path, error)
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+    return Err(crate::Error::PluginConfig(plugin.into(), errors));
+  }
+  Ok(())
+}
+
+/// Whether the URI scheme is scoped to the plugin with the given name.
+fn is_plugin_scheme(plugin: &str, uri_scheme: &str) -> bool {
+  uri_scheme == plugin
+    || uri_scheme
+      .strip_prefix(plugin)
+      .and_then(|suffix| suffix.strip_prefix('-'))
+      .map_or(false, |suffix| !suffix.is_empty())
 }
 
 /// Plugin collection type.
 #[default_runtime(crate::Wry, wry)]
 pub(crate) struct PluginStore<R: Runtime> {
   store: HashMap<&'static str, Box<dyn Plugin<R>>>,
+  /// The permissions declared by each plugin.
+  permissions: HashMap<&'static str, Vec<Permission>>,
 }
 
 impl<R: Runtime> fmt::Debug for PluginStore<R> {
@@ -505,6 +894,7 @@ impl<R: Runtime> Default for PluginStore<R> {
   fn default() -> Self {
     Self {
       store: HashMap::new(),
+      permissions: HashMap::new(),
     }
   }
 }
@@ -514,11 +904,13 @@ impl<R: Runtime> PluginStore<R> {
   ///
   /// Returns `true` if a plugin with the same name is already in the store.
   pub fn register<P: Plugin<R> + 'static>(&mut self, plugin: P) -> bool {
+    self.permissions.insert(plugin.name(), plugin.permissions());
     self.store.insert(plugin.name(), Box::new(plugin)).is_some()
   }
 
   /// Removes the plugin with the given name from the store.
   pub fn unregister(&mut self, plugin: &'static str) -> bool {
+    self.permissions.remove(plugin);
     self.store.remove(plugin).is_some()
   }
 
@@ -529,11 +921,13 @@ impl<R: Runtime> PluginStore<R> {
     config: &PluginConfig,
   ) -> crate::Result<()> {
     self.store.values_mut().try_for_each(|plugin| {
+      let plugin_config = config.0.get(plugin.name()).cloned();
+      #[cfg(feature = "plugin-config-schema")]
+      if let (Some(schema), Some(plugin_config)) = (plugin.config_schema(), &plugin_config) {
+        validate_config(plugin.name(), &schema, plugin_config)?;
+      }
       plugin
-        .initialize(
-          app,
-          config.0.get(plugin.name()).cloned().unwrap_or_default(),
-        )
+        .initialize(app, plugin_config.unwrap_or_default())
         .map_err(|e| crate::Error::PluginInitialization(plugin.name().to_string(), e.to_string()))
     })
   }
@@ -565,6 +959,33 @@ impl<R: Runtime> PluginStore<R> {
       .for_each(|plugin| plugin.on_page_load(window.clone(), payload.clone()))
   }
 
+  /// Runs the on_navigation hook for all plugins in the store.
+  ///
+  /// Returns `false` if any plugin cancelled the navigation.
+  pub(crate) fn on_navigation(&mut self, window: &Window<R>, url: &Url) -> bool {
+    self.store.values_mut().fold(true, |allowed, plugin| {
+      plugin.on_navigation(window, url) && allowed
+    })
+  }
+
+  /// Runs the on_file_drop hook for all plugins in the store.
+  ///
+  /// Returns `false` if any plugin vetoed the event.
+  pub(crate) fn on_file_drop(&mut self, window: &Window<R>, event: &FileDropEvent) -> bool {
+    self.store.values_mut().fold(true, |allowed, plugin| {
+      plugin.on_file_drop(window, event) && allowed
+    })
+  }
+
+  /// Runs the on_data_drop hook for all plugins in the store.
+  ///
+  /// Returns `false` if any plugin vetoed the event.
+  pub(crate) fn on_data_drop(&mut self, window: &Window<R>, event: &DropEvent) -> bool {
+    self.store.values_mut().fold(true, |allowed, plugin| {
+      plugin.on_data_drop(window, event) && allowed
+    })
+  }
+
   /// Runs the on_event hook for all plugins in the store.
   pub(crate) fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
     self
@@ -584,6 +1005,27 @@ impl<R: Runtime> PluginStore<R> {
         .next()
         .map(|c| c.to_string())
         .unwrap_or_else(String::new);
+
+      let permissions = self
+        .permissions
+        .get(target)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+      let window = invoke.message.window_ref();
+      if !window.state::<Scopes>().plugin_permissions.allows_command(
+        window.label(),
+        target,
+        permissions,
+        &invoke.message.command,
+      ) {
+        let message = format!(
+          "plugin:{}|{} not allowed: missing permission",
+          target, invoke.message.command
+        );
+        invoke.resolver.reject(message);
+        return;
+      }
+
       plugin.extend_api(invoke);
     } else {
       invoke
@@ -592,3 +1034,62 @@ impl<R: Runtime> PluginStore<R> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::is_plugin_scheme;
+
+  #[test]
+  fn plugin_scheme() {
+    assert!(is_plugin_scheme("media", "media"));
+    assert!(is_plugin_scheme("media", "media-thumbnails"));
+    assert!(!is_plugin_scheme("media", "media-"));
+    assert!(!is_plugin_scheme("media", "mediaplayer"));
+    assert!(!is_plugin_scheme("media", "tauri"));
+  }
+
+  #[test]
+  fn navigation_and_file_drop_hooks() {
+    use super::Builder;
+    use crate::{
+      test::{mock_builder, mock_context, noop_assets},
+      FileDropEvent, Manager,
+    };
+    use std::path::PathBuf;
+
+    let app = mock_builder()
+      .plugin(Builder::<_, ()>::new("allow").build())
+      .plugin(
+        Builder::<_, ()>::new("guard")
+          .on_navigation(|_window, url| url.scheme() == "tauri")
+          .on_file_drop(|_window, event| !matches!(event, FileDropEvent::Dropped(_)))
+          .build(),
+      )
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+    let manager = &app.handle().manager;
+
+    assert!(manager.run_on_navigation(&window, &"tauri://localhost/about".parse().unwrap()));
+    assert!(!manager.run_on_navigation(&window, &"https://tauri.app".parse().unwrap()));
+    assert!(manager.run_on_file_drop(&window, &FileDropEvent::Cancelled));
+    assert!(!manager.run_on_file_drop(
+      &window,
+      &FileDropEvent::Dropped(vec![PathBuf::from("file.txt")])
+    ));
+  }
+
+  #[cfg(feature = "plugin-config-schema")]
+  #[test]
+  fn validate_config() {
+    let schema = serde_json::json!({
+      "type": "object",
+      "properties": { "ttl": { "type": "integer", "minimum": 0 } }
+    });
+    assert!(super::validate_config("store", &schema, &serde_json::json!({ "ttl": 10 })).is_ok());
+    let error = super::validate_config("store", &schema, &serde_json::json!({ "ttl": -1 }))
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("`plugins > store > ttl`"));
+  }
+}
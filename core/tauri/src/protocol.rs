@@ -0,0 +1,366 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Types for the URI scheme protocols registered with [`Builder::register_asynchronous_uri_scheme_protocol`](crate::Builder#method.register_asynchronous_uri_scheme_protocol).
+
+use std::{fmt, future::Future, pin::Pin};
+
+use http::StatusCode;
+use http_range::HttpRange;
+use tokio::{
+  io::{AsyncRead, AsyncReadExt},
+  sync::mpsc,
+};
+
+use crate::runtime::http::{
+  header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE},
+  Request as HttpRequest, RequestParts as HttpRequestParts, Response as HttpResponse,
+};
+
+#[cfg(protocol_asset)]
+pub(crate) mod asset;
+
+pub use http::response::Builder as AsyncResponseBuilder;
+
+/// The maximum number of bytes of a [`ResponseBody::Reader`] or [`ResponseBody::Chunks`] body
+/// sent for each range requested with the `Range` header.
+const MAX_RANGE_LENGTH: u64 = 1024 * 1024;
+
+/// The response of an asynchronous URI scheme protocol.
+///
+/// Use [`AsyncResponseBuilder`] to create it.
+pub type AsyncResponse = http::Response<ResponseBody>;
+
+/// The error of an asynchronous URI scheme protocol.
+pub type AsyncProtocolError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The future returned by an asynchronous URI scheme protocol.
+pub(crate) type AsyncProtocolFuture =
+  Pin<Box<dyn Future<Output = Result<AsyncResponse, AsyncProtocolError>> + Send>>;
+
+/// The body of an [`AsyncResponse`].
+pub enum ResponseBody {
+  /// A body that is already in memory.
+  Bytes(Vec<u8>),
+  /// A body read until the end of the reader.
+  Reader(Pin<Box<dyn AsyncRead + Send>>),
+  /// A body made of the chunks received until every sender is dropped.
+  Chunks(mpsc::Receiver<Vec<u8>>),
+}
+
+impl fmt::Debug for ResponseBody {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Bytes(bytes) => f.debug_tuple("Bytes").field(&bytes.len()).finish(),
+      Self::Reader(_) => f.debug_tuple("Reader").finish(),
+      Self::Chunks(_) => f.debug_tuple("Chunks").finish(),
+    }
+  }
+}
+
+impl ResponseBody {
+  /// A body read from the given reader, such as a [`tokio::fs::File`].
+  pub fn reader<T: AsyncRead + Send + 'static>(reader: T) -> Self {
+    Self::Reader(Box::pin(reader))
+  }
+
+  /// A body made of the chunks sent on the returned sender.
+  ///
+  /// The body ends when every sender is dropped.
+  /// `buffer` is the number of chunks that can be sent before the sender waits for them to be read.
+  pub fn channel(buffer: usize) -> (mpsc::Sender<Vec<u8>>, Self) {
+    let (tx, rx) = mpsc::channel(buffer.max(1));
+    (tx, Self::Chunks(rx))
+  }
+
+  /// Reads the whole body.
+  pub(crate) async fn collect(self) -> std::io::Result<Vec<u8>> {
+    match self {
+      Self::Bytes(bytes) => Ok(bytes),
+      Self::Reader(mut reader) => {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(bytes)
+      }
+      Self::Chunks(mut rx) => {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+          bytes.extend(chunk);
+        }
+        Ok(bytes)
+      }
+    }
+  }
+
+  /// Reads at most `length` bytes of the body from `start`, stopping at its end.
+  ///
+  /// The rest of the body is not read.
+  async fn read_range(self, start: u64, length: u64) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    match self {
+      Self::Bytes(body) => {
+        let start = start.min(body.len() as u64) as usize;
+        let end = (start as u64).saturating_add(length).min(body.len() as u64) as usize;
+        bytes.extend_from_slice(&body[start..end]);
+      }
+      Self::Reader(mut reader) => {
+        tokio::io::copy(&mut (&mut reader).take(start), &mut tokio::io::sink()).await?;
+        reader.take(length).read_to_end(&mut bytes).await?;
+      }
+      Self::Chunks(mut rx) => {
+        let end = start.saturating_add(length);
+        let mut offset = 0;
+        while offset < end {
+          let chunk = match rx.recv().await {
+            Some(chunk) => chunk,
+            None => break,
+          };
+          let chunk_end = offset + chunk.len() as u64;
+          if chunk_end > start {
+            let from = start.saturating_sub(offset) as usize;
+            let to = (end.min(chunk_end) - offset) as usize;
+            bytes.extend_from_slice(&chunk[from..to]);
+          }
+          offset = chunk_end;
+        }
+      }
+    }
+    Ok(bytes)
+  }
+}
+
+impl From<Vec<u8>> for ResponseBody {
+  fn from(bytes: Vec<u8>) -> Self {
+    Self::Bytes(bytes)
+  }
+}
+
+impl From<&'static [u8]> for ResponseBody {
+  fn from(bytes: &'static [u8]) -> Self {
+    Self::Bytes(bytes.to_vec())
+  }
+}
+
+impl From<String> for ResponseBody {
+  fn from(string: String) -> Self {
+    Self::Bytes(string.into_bytes())
+  }
+}
+
+impl From<&'static str> for ResponseBody {
+  fn from(string: &'static str) -> Self {
+    Self::Bytes(string.as_bytes().to_vec())
+  }
+}
+
+/// Clones the request so it can be moved to the asynchronous handler.
+pub(crate) fn owned_request(request: &HttpRequest) -> HttpRequest {
+  HttpRequest::from_parts(
+    HttpRequestParts {
+      method: request.method().clone(),
+      uri: request.uri().to_string(),
+      headers: request.headers().clone(),
+    },
+    request.body().clone(),
+  )
+}
+
+/// Runs the asynchronous handler and converts its response to the runtime response.
+///
+/// The webview protocols only take complete bodies, so the event loop waits for them.
+/// To keep that wait short, the reader and chunks bodies are read up to the range requested
+/// with the `Range` header, at most [`MAX_RANGE_LENGTH`] bytes, and answered with a `206 Partial Content` response.
+/// Media elements request their source that way, so they load it piece by piece instead of as a whole.
+pub(crate) fn block_on_response(
+  response: AsyncProtocolFuture,
+  range: Option<String>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+  crate::async_runtime::safe_block_on(async move {
+    let (mut parts, body) = response.await?.into_parts();
+    if parts.status != StatusCode::OK || matches!(body, ResponseBody::Bytes(_)) {
+      let body = body.collect().await?;
+      return Ok(into_runtime_response(parts, body));
+    }
+
+    parts
+      .headers
+      .insert(ACCEPT_RANGES, http::HeaderValue::from_static("bytes"));
+    let size = parts
+      .headers
+      .get(CONTENT_LENGTH)
+      .and_then(|size| size.to_str().ok())
+      .and_then(|size| size.parse::<u64>().ok());
+    let body = match range.and_then(|range| requested_range(&range, size)) {
+      Some((start, length)) => {
+        let body = body.read_range(start, length).await?;
+        if body.is_empty() {
+          parts.status = StatusCode::RANGE_NOT_SATISFIABLE;
+        } else {
+          parts.status = StatusCode::PARTIAL_CONTENT;
+          let end = start + body.len() as u64 - 1;
+          let size = size
+            .map(|size| size.to_string())
+            .unwrap_or_else(|| "*".into());
+          parts.headers.insert(
+            CONTENT_RANGE,
+            format!("bytes {}-{}/{}", start, end, size).parse()?,
+          );
+        }
+        parts.headers.insert(CONTENT_LENGTH, body.len().into());
+        body
+      }
+      None => body.collect().await?,
+    };
+    Ok::<_, AsyncProtocolError>(into_runtime_response(parts, body))
+  })
+  .map_err(|e| e as Box<dyn std::error::Error>)
+}
+
+/// The start and length of the single range requested with a `Range` header, capped to [`MAX_RANGE_LENGTH`].
+///
+/// Suffix ranges need the size of the body, given by its `Content-Length` header.
+fn requested_range(range: &str, size: Option<u64>) -> Option<(u64, u64)> {
+  if size.is_none() && range.trim_start_matches("bytes=").starts_with('-') {
+    return None;
+  }
+  match HttpRange::parse(range, size.unwrap_or(u64::MAX))
+    .ok()?
+    .as_slice()
+  {
+    [range] => Some((range.start, range.length.min(MAX_RANGE_LENGTH))),
+    _ => None,
+  }
+}
+
+/// Whether the `If-None-Match` header value matches the entity tag, using the weak comparison.
+#[cfg_attr(
+  all(target_os = "linux", not(feature = "linux-protocol-headers")),
+  allow(dead_code)
+)]
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+  if_none_match.trim() == "*"
+    || if_none_match
+      .split(',')
+      .map(|tag| tag.trim().trim_start_matches("W/"))
+      .any(|tag| tag == etag.trim_start_matches("W/"))
+}
+
+/// Whether the request revalidates a response with the entity tag, which can be answered with `304 Not Modified`.
+#[cfg(any(not(target_os = "linux"), feature = "linux-protocol-headers"))]
+pub(crate) fn is_not_modified(request: &HttpRequest, etag: &str) -> bool {
+  request
+    .headers()
+    .get("if-none-match")
+    .and_then(|value| value.to_str().ok())
+    .map(|value| etag_matches(value, etag))
+    .unwrap_or(false)
+}
+
+fn into_runtime_response(parts: http::response::Parts, body: Vec<u8>) -> HttpResponse {
+  let mut response = HttpResponse::new(body);
+  response.set_status(parts.status);
+  let mut headers = parts.headers;
+  if let Some(content_type) = headers.remove(CONTENT_TYPE) {
+    response.set_mimetype(content_type.to_str().ok().map(Into::into));
+  }
+  response.headers_mut().extend(headers);
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    block_on_response, etag_matches, into_runtime_response, requested_range, AsyncResponseBuilder,
+    ResponseBody, MAX_RANGE_LENGTH,
+  };
+
+  #[test]
+  fn if_none_match() {
+    assert!(etag_matches("\"64-1\"", "\"64-1\""));
+    assert!(etag_matches("\"a\", W/\"64-1\"", "\"64-1\""));
+    assert!(etag_matches("*", "\"64-1\""));
+    assert!(!etag_matches("\"64-2\"", "\"64-1\""));
+  }
+
+  #[test]
+  fn collect_body() {
+    crate::async_runtime::block_on(async {
+      assert_eq!(
+        ResponseBody::from("bytes").collect().await.unwrap(),
+        b"bytes"
+      );
+
+      let reader = ResponseBody::reader(&b"reader"[..]);
+      assert_eq!(reader.collect().await.unwrap(), b"reader");
+
+      let (tx, body) = ResponseBody::channel(1);
+      crate::async_runtime::spawn(async move {
+        for chunk in ["ch", "un", "ks"] {
+          tx.send(chunk.as_bytes().to_vec()).await.unwrap();
+        }
+      });
+      assert_eq!(body.collect().await.unwrap(), b"chunks");
+    });
+  }
+
+  #[test]
+  fn runtime_response() {
+    let (parts, _) = AsyncResponseBuilder::new()
+      .status(206)
+      .header("Content-Type", "video/mp4")
+      .header("Content-Range", "bytes 0-1/10")
+      .body(())
+      .unwrap()
+      .into_parts();
+    let response = into_runtime_response(parts, vec![0, 1]);
+    assert_eq!(response.status(), 206);
+    assert_eq!(response.mimetype().map(|m| m.as_str()), Some("video/mp4"));
+    assert_eq!(response.headers()["Content-Range"], "bytes 0-1/10");
+    assert!(response.headers().get("Content-Type").is_none());
+    assert_eq!(response.body(), &vec![0, 1]);
+  }
+
+  #[test]
+  fn streamed_range() {
+    let respond = |body: ResponseBody, range: Option<&str>| {
+      let response = AsyncResponseBuilder::new()
+        .header("Content-Length", "10")
+        .body(body)
+        .unwrap();
+      block_on_response(Box::pin(async move { Ok(response) }), range.map(Into::into)).unwrap()
+    };
+
+    let response = respond(ResponseBody::reader(&b"0123456789"[..]), Some("bytes=2-5"));
+    assert_eq!(response.status(), 206);
+    assert_eq!(response.headers()["Content-Range"], "bytes 2-5/10");
+    assert_eq!(response.body(), b"2345");
+
+    let (tx, body) = ResponseBody::channel(4);
+    crate::async_runtime::spawn(async move {
+      for chunk in ["012", "345", "678", "9"] {
+        let _ = tx.send(chunk.as_bytes().to_vec()).await;
+      }
+    });
+    let response = respond(body, Some("bytes=4-"));
+    assert_eq!(response.headers()["Content-Range"], "bytes 4-9/10");
+    assert_eq!(response.body(), b"456789");
+
+    let response = respond(ResponseBody::reader(&b"0123456789"[..]), None);
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["Accept-Ranges"], "bytes");
+    assert_eq!(response.body(), b"0123456789");
+  }
+
+  #[test]
+  fn requested_ranges() {
+    assert_eq!(requested_range("bytes=0-99", Some(1000)), Some((0, 100)));
+    assert_eq!(requested_range("bytes=-100", Some(1000)), Some((900, 100)));
+    assert_eq!(requested_range("bytes=-100", None), None);
+    assert_eq!(
+      requested_range("bytes=0-", None),
+      Some((0, MAX_RANGE_LENGTH))
+    );
+    assert_eq!(requested_range("bytes=0-9,20-29", Some(1000)), None);
+  }
+}
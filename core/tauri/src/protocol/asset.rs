@@ -0,0 +1,266 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The `asset://` protocol, serving files from the asset protocol scope.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use http_range::{HttpRange, HttpRangeParseError};
+use tauri_utils::debug_eprintln;
+use url::{Position, Url};
+
+use crate::{
+  api::file::SafePathBuf,
+  runtime::http::{
+    MimeType, Request as HttpRequest, Response as HttpResponse,
+    ResponseBuilder as HttpResponseBuilder,
+  },
+  scope::FsScope,
+};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// The maximum number of bytes sent for each requested range.
+///
+/// Media elements usually request `bytes=0-`, so the response is capped
+/// and the webview requests the next chunk when it needs it.
+const MAX_RANGE_LENGTH: u64 = 1024 * 1024;
+
+/// The number of bytes read to detect the MIME type of the file.
+const MIME_SNIFF_LENGTH: u64 = 8192;
+
+/// Handles an `asset://localhost/<path>` request.
+pub(crate) fn handle(
+  request: &HttpRequest,
+  scope: &FsScope,
+  window_origin: &str,
+) -> Result<HttpResponse> {
+  let parsed_path = Url::parse(request.uri())?;
+  let filtered_path = &parsed_path[..Position::AfterPath];
+  let path = filtered_path
+    .strip_prefix("asset://localhost/")
+    // the `strip_prefix` only returns None when a request is made to `https://tauri.$P` on Windows
+    // where `$P` is not `localhost/*`
+    .unwrap_or("");
+  let path = percent_encoding::percent_decode(path.as_bytes())
+    .decode_utf8_lossy()
+    .to_string();
+
+  if let Err(e) = SafePathBuf::new(path.clone().into()) {
+    debug_eprintln!("asset protocol path \"{}\" is not valid: {}", path, e);
+    return HttpResponseBuilder::new().status(403).body(Vec::new());
+  }
+
+  if !scope.is_allowed(&path) {
+    debug_eprintln!("asset protocol not configured to allow the path: {}", path);
+    return HttpResponseBuilder::new().status(403).body(Vec::new());
+  }
+
+  let response = HttpResponseBuilder::new().header("Access-Control-Allow-Origin", window_origin);
+
+  let (mut file, metadata) = match std::fs::File::open(&path).and_then(|file| {
+    let metadata = file.metadata()?;
+    Ok((file, metadata))
+  }) {
+    Ok(file) => file,
+    Err(e) => {
+      debug_eprintln!("Failed to open asset: {}", e);
+      return response.status(404).body(Vec::new());
+    }
+  };
+  let size = metadata.len();
+
+  let etag = etag(&metadata);
+  // the status code and headers cannot be set on Linux without the `linux-protocol-headers` feature
+  #[cfg(any(not(target_os = "linux"), feature = "linux-protocol-headers"))]
+  if super::is_not_modified(request, &etag) {
+    return response.status(304).header("ETag", etag).body(Vec::new());
+  }
+  let response = response
+    .header("ETag", etag)
+    .header("Cache-Control", "no-cache");
+
+  let range = request
+    .headers()
+    .get("range")
+    .and_then(|range| range.to_str().ok());
+
+  respond(response, &mut file, size, &path, range)
+}
+
+/// Responds with the file contents, honoring the `Range` header.
+///
+/// A single range is sent as a `206 Partial Content` response,
+/// multiple ranges as a `multipart/byteranges` body
+/// and ranges outside of the file as a `416 Range Not Satisfiable` response.
+fn respond<F: Read + Seek>(
+  response: HttpResponseBuilder,
+  file: &mut F,
+  size: u64,
+  path: &str,
+  range: Option<&str>,
+) -> Result<HttpResponse> {
+  let mut head = Vec::new();
+  file
+    .by_ref()
+    .take(MIME_SNIFF_LENGTH)
+    .read_to_end(&mut head)?;
+  let mime_type = MimeType::parse(&head, path);
+
+  let response = response.header("Accept-Ranges", "bytes");
+
+  // some webviews send `bytes=0-*`
+  let ranges = match range.map(|range| HttpRange::parse(range.trim_end_matches('*'), size)) {
+    Some(Ok(ranges)) if !ranges.is_empty() => ranges,
+    Some(Err(HttpRangeParseError::NoOverlap)) => {
+      return response
+        .status(416)
+        .header("Content-Range", format!("bytes */{}", size))
+        .body(Vec::new());
+    }
+    Some(Err(HttpRangeParseError::InvalidRange)) => {
+      debug_eprintln!("Ignoring invalid range {:?}", range);
+      return full(response, file, &mime_type);
+    }
+    _ => return full(response, file, &mime_type),
+  };
+
+  if let [range] = ranges.as_slice() {
+    let (start, end) = bounds(range);
+    let body = read_range(file, start, end)?;
+    response
+      .status(206)
+      .mimetype(&mime_type)
+      .header("Content-Range", format!("bytes {}-{}/{}", start, end, size))
+      .header("Content-Length", body.len())
+      .body(body)
+  } else {
+    let boundary = format!("{:016x}", rand::random::<u64>());
+    let mut body = Vec::new();
+    for range in &ranges {
+      let (start, end) = bounds(range);
+      write!(
+        body,
+        "--{}\r\nContent-Type: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+        boundary, mime_type, start, end, size
+      )?;
+      body.extend(read_range(file, start, end)?);
+      body.extend_from_slice(b"\r\n");
+    }
+    write!(body, "--{}--\r\n", boundary)?;
+    response
+      .status(206)
+      .mimetype(&format!("multipart/byteranges; boundary={}", boundary))
+      .header("Content-Length", body.len())
+      .body(body)
+  }
+}
+
+/// An entity tag derived from the file size and modification time.
+fn etag(metadata: &std::fs::Metadata) -> String {
+  let modified = metadata
+    .modified()
+    .ok()
+    .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+    .map(|modified| modified.as_nanos())
+    .unwrap_or_default();
+  format!("\"{:x}-{:x}\"", metadata.len(), modified)
+}
+
+/// The first and last byte of the range, capped to [`MAX_RANGE_LENGTH`].
+fn bounds(range: &HttpRange) -> (u64, u64) {
+  let length = range.length.min(MAX_RANGE_LENGTH);
+  (range.start, range.start + length - 1)
+}
+
+fn read_range<F: Read + Seek>(file: &mut F, start: u64, end: u64) -> Result<Vec<u8>> {
+  file.seek(SeekFrom::Start(start))?;
+  let mut bytes = Vec::with_capacity((end - start + 1) as usize);
+  file.take(end - start + 1).read_to_end(&mut bytes)?;
+  Ok(bytes)
+}
+
+fn full<F: Read + Seek>(
+  response: HttpResponseBuilder,
+  file: &mut F,
+  mime_type: &str,
+) -> Result<HttpResponse> {
+  file.rewind()?;
+  let mut body = Vec::new();
+  file.read_to_end(&mut body)?;
+  response.mimetype(mime_type).body(body)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{respond, HttpResponseBuilder};
+  use std::io::Cursor;
+
+  fn request(range: Option<&str>) -> crate::runtime::http::Response {
+    let data = (0..100u8).collect::<Vec<_>>();
+    respond(
+      HttpResponseBuilder::new(),
+      &mut Cursor::new(data),
+      100,
+      "video.mp4",
+      range,
+    )
+    .unwrap()
+  }
+
+  fn header(response: &crate::runtime::http::Response, name: &str) -> String {
+    response.headers()[name].to_str().unwrap().to_string()
+  }
+
+  #[test]
+  fn full_file() {
+    let response = request(None);
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body().len(), 100);
+    assert_eq!(header(&response, "Accept-Ranges"), "bytes");
+
+    let response = request(Some("invalid"));
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body().len(), 100);
+  }
+
+  #[test]
+  fn single_range() {
+    let response = request(Some("bytes=10-19"));
+    assert_eq!(response.status(), 206);
+    assert_eq!(response.body(), &(10..20u8).collect::<Vec<_>>());
+    assert_eq!(header(&response, "Content-Range"), "bytes 10-19/100");
+    assert_eq!(header(&response, "Content-Length"), "10");
+
+    let response = request(Some("bytes=90-*"));
+    assert_eq!(response.status(), 206);
+    assert_eq!(header(&response, "Content-Range"), "bytes 90-99/100");
+
+    let response = request(Some("bytes=-5"));
+    assert_eq!(response.body(), &(95..100u8).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn multiple_ranges() {
+    let response = request(Some("bytes=0-1,50-51"));
+    assert_eq!(response.status(), 206);
+    let content_type = response.mimetype().unwrap();
+    let boundary = content_type
+      .strip_prefix("multipart/byteranges; boundary=")
+      .unwrap();
+    let body = response.body();
+    assert!(body.starts_with(format!("--{}\r\n", boundary).as_bytes()));
+    assert!(body.ends_with(format!("--{}--\r\n", boundary).as_bytes()));
+    let body = String::from_utf8_lossy(body);
+    assert!(body.contains("Content-Range: bytes 0-1/100\r\n\r\n\u{0}\u{1}\r\n"));
+    assert!(body.contains("Content-Range: bytes 50-51/100\r\n\r\n\u{32}\u{33}\r\n"));
+  }
+
+  #[test]
+  fn unsatisfiable_range() {
+    let response = request(Some("bytes=200-300"));
+    assert_eq!(response.status(), 416);
+    assert_eq!(header(&response, "Content-Range"), "bytes */100");
+  }
+}
@@ -0,0 +1,163 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::{Arc, Mutex};
+
+use glob::Pattern;
+use tauri_utils::config::CommandScopeEntry;
+use url::Url;
+
+#[derive(Debug, Clone)]
+struct Entry {
+  windows: Vec<Pattern>,
+  origins: Vec<Pattern>,
+  commands: Vec<Pattern>,
+}
+
+fn patterns(list: &[String]) -> crate::Result<Vec<Pattern>> {
+  list
+    .iter()
+    .map(|p| Pattern::new(p).map_err(Into::into))
+    .collect()
+}
+
+fn matches(patterns: &[Pattern], value: &str) -> bool {
+  patterns.is_empty() || patterns.iter().any(|p| p.matches(value))
+}
+
+impl Entry {
+  fn new(entry: &CommandScopeEntry) -> crate::Result<Self> {
+    Ok(Self {
+      windows: patterns(&entry.windows)?,
+      origins: patterns(&entry.origins)?,
+      commands: patterns(&entry.commands)?,
+    })
+  }
+
+  fn applies_to(&self, window_label: &str, origin: &str) -> bool {
+    matches(&self.windows, window_label) && matches(&self.origins, origin)
+  }
+}
+
+/// The `scheme://host[:port]` of the given URL.
+///
+/// [`Url::origin`] is not used since it is opaque for custom schemes such as `tauri://`.
+fn origin(url: &Url) -> String {
+  let mut origin = format!("{}://", url.scheme());
+  if let Some(host) = url.host_str() {
+    origin.push_str(host);
+  }
+  if let Some(port) = url.port() {
+    origin.push_str(&format!(":{}", port));
+  }
+  origin
+}
+
+/// Scope for the IPC commands a window can invoke.
+///
+/// See [`SecurityConfig::command_scope`](crate::utils::config::SecurityConfig#structfield.command_scope).
+#[derive(Debug, Clone)]
+pub struct Scope {
+  entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+impl Scope {
+  /// Creates a new scope from the `tauri > security > commandScope` configuration.
+  pub(crate) fn new(entries: &[CommandScopeEntry]) -> crate::Result<Self> {
+    Ok(Self {
+      entries: Arc::new(Mutex::new(
+        entries
+          .iter()
+          .map(Entry::new)
+          .collect::<crate::Result<_>>()?,
+      )),
+    })
+  }
+
+  /// Adds an entry to the scope.
+  pub fn add_entry(&self, entry: &CommandScopeEntry) -> crate::Result<()> {
+    let entry = Entry::new(entry)?;
+    self.entries.lock().unwrap().push(entry);
+    Ok(())
+  }
+
+  /// Replaces all entries of the scope.
+  pub fn set_entries(&self, entries: &[CommandScopeEntry]) -> crate::Result<()> {
+    let entries = entries
+      .iter()
+      .map(Entry::new)
+      .collect::<crate::Result<_>>()?;
+    *self.entries.lock().unwrap() = entries;
+    Ok(())
+  }
+
+  /// Removes all entries of the scope, so every window can invoke every command.
+  pub fn clear(&self) {
+    self.entries.lock().unwrap().clear();
+  }
+
+  /// Whether the scope has no entries, meaning it does not restrict any command.
+  pub fn is_empty(&self) -> bool {
+    self.entries.lock().unwrap().is_empty()
+  }
+
+  /// Determines if the given command can be invoked by the window with the given label
+  /// while it is loading the given URL.
+  pub fn is_allowed(&self, window_label: &str, url: &Url, command: &str) -> bool {
+    let origin = origin(url);
+    let entries = self.entries.lock().unwrap();
+    let mut matching = entries
+      .iter()
+      .filter(|e| e.applies_to(window_label, &origin))
+      .peekable();
+    matching.peek().is_none() || matching.any(|e| e.commands.iter().any(|c| c.matches(command)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Scope;
+  use tauri_utils::config::CommandScopeEntry;
+
+  fn entry(windows: &[&str], origins: &[&str], commands: &[&str]) -> CommandScopeEntry {
+    CommandScopeEntry {
+      windows: windows.iter().map(|s| s.to_string()).collect(),
+      origins: origins.iter().map(|s| s.to_string()).collect(),
+      commands: commands.iter().map(|s| s.to_string()).collect(),
+    }
+  }
+
+  #[test]
+  fn is_allowed() {
+    let local = "tauri://localhost/index.html".parse().unwrap();
+    let remote = "https://embed.example.com:8080/page".parse().unwrap();
+
+    let scope = Scope::new(&[]).unwrap();
+    assert!(scope.is_allowed("main", &local, "any_command"));
+    assert!(scope.is_allowed("main", &remote, "plugin:store|get"));
+
+    let scope = Scope::new(&[entry(
+      &[],
+      &["https://*.example.com:*"],
+      &["greet", "plugin:store|*"],
+    )])
+    .unwrap();
+    assert!(scope.is_allowed("main", &local, "any_command"));
+    assert!(scope.is_allowed("main", &remote, "greet"));
+    assert!(scope.is_allowed("main", &remote, "plugin:store|get"));
+    assert!(!scope.is_allowed("main", &remote, "tauri:fs"));
+    assert!(!scope.is_allowed("main", &remote, "plugin:sql|execute"));
+
+    scope
+      .add_entry(&entry(&["embed-*"], &[], &["tauri:event"]))
+      .unwrap();
+    assert!(scope.is_allowed("embed-1", &remote, "tauri:event"));
+    assert!(scope.is_allowed("embed-1", &remote, "greet"));
+    assert!(!scope.is_allowed("main", &remote, "tauri:event"));
+    assert!(!scope.is_allowed("embed-1", &local, "any_command"));
+
+    scope.clear();
+    assert!(scope.is_allowed("embed-1", &local, "any_command"));
+  }
+}
@@ -10,13 +10,14 @@ use std::{
 };
 
 pub use glob::Pattern;
+use serde::{Deserialize, Serialize};
 use tauri_utils::{
-  config::{Config, FsAllowlistScope},
+  config::{Config, FsAllowlistScope, SymlinkPolicy},
   Env, PackageInfo,
 };
 use uuid::Uuid;
 
-use crate::api::path::parse as parse_path;
+use crate::{api::path::parse as parse_path, log::warn};
 
 /// Scope change event.
 #[derive(Debug, Clone)]
@@ -29,12 +30,61 @@ pub enum Event {
 
 type EventListener = Box<dyn Fn(&Event) + Send>;
 
+/// A path checked by [`Scope::is_allowed`], given to the hook set with [`Scope::audit`].
+#[derive(Debug, Clone)]
+pub struct AccessDecision {
+  /// The checked path.
+  pub path: PathBuf,
+  /// The path matched against the scope patterns, with its symbolic links resolved by the
+  /// [`SymlinkPolicy`], or `None` if the policy denied it.
+  pub resolved_path: Option<PathBuf>,
+  /// Whether the access was allowed.
+  pub allowed: bool,
+}
+
+type AuditHook = Arc<dyn Fn(&AccessDecision) + Send + Sync>;
+
+/// A runtime change of the scope, saved by [`Scope::persist`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Change {
+  AllowDirectory { path: PathBuf, recursive: bool },
+  AllowFile { path: PathBuf },
+  ForbidDirectory { path: PathBuf, recursive: bool },
+  ForbidFile { path: PathBuf },
+}
+
+/// The file where the runtime changes of a scope are saved.
+#[derive(Debug)]
+struct Persistence {
+  path: PathBuf,
+  changes: Vec<Change>,
+}
+
+impl Persistence {
+  /// Writes the changes to a temporary file renamed over the file, so a crash does not truncate it.
+  fn save(&self) -> crate::Result<()> {
+    let parent = match self.path.parent() {
+      Some(parent) if !parent.as_os_str().is_empty() => parent,
+      _ => Path::new("."),
+    };
+    std::fs::create_dir_all(parent)?;
+    let mut file = tempfile::NamedTempFile::new_in(parent)?;
+    std::io::Write::write_all(&mut file, &serde_json::to_vec(&self.changes)?)?;
+    file.persist(&self.path).map_err(|e| e.error)?;
+    Ok(())
+  }
+}
+
 /// Scope for filesystem access.
 #[derive(Clone)]
 pub struct Scope {
   allowed_patterns: Arc<Mutex<HashSet<Pattern>>>,
   forbidden_patterns: Arc<Mutex<HashSet<Pattern>>>,
   event_listeners: Arc<Mutex<HashMap<Uuid, EventListener>>>,
+  persistence: Arc<Mutex<Option<Persistence>>>,
+  symlink_policy: SymlinkPolicy,
+  audit_hook: Arc<Mutex<Option<AuditHook>>>,
 }
 
 impl fmt::Debug for Scope {
@@ -60,6 +110,7 @@ impl fmt::Debug for Scope {
           .map(|p| p.as_str())
           .collect::<Vec<&str>>(),
       )
+      .field("symlink_policy", &self.symlink_policy)
       .finish()
   }
 }
@@ -110,9 +161,36 @@ impl Scope {
       allowed_patterns: Arc::new(Mutex::new(allowed_patterns)),
       forbidden_patterns: Arc::new(Mutex::new(forbidden_patterns)),
       event_listeners: Default::default(),
+      persistence: Default::default(),
+      symlink_policy: config.tauri.security.symlink_policy,
+      audit_hook: Default::default(),
     })
   }
 
+  /// Replaces the patterns of the `previous` scope with the patterns of the `scope`,
+  /// keeping the paths allowed or forbidden at runtime.
+  #[cfg(config_reload)]
+  pub(crate) fn update(&self, previous: &Self, scope: &Self) {
+    for (list, previous, next) in [
+      (
+        &self.allowed_patterns,
+        previous.allowed_patterns(),
+        scope.allowed_patterns(),
+      ),
+      (
+        &self.forbidden_patterns,
+        previous.forbidden_patterns(),
+        scope.forbidden_patterns(),
+      ),
+    ] {
+      let mut list = list.lock().unwrap();
+      for pattern in previous {
+        list.remove(&pattern);
+      }
+      list.extend(next);
+    }
+  }
+
   /// The list of allowed patterns.
   pub fn allowed_patterns(&self) -> HashSet<Pattern> {
     self.allowed_patterns.lock().unwrap().clone()
@@ -130,6 +208,27 @@ impl Scope {
     id
   }
 
+  /// Sets a hook that receives every path checked by [`Self::is_allowed`] and whether it was allowed,
+  /// replacing the previous one.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::Manager;
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     app.fs_scope().audit(|decision| {
+  ///       if !decision.allowed {
+  ///         eprintln!("denied access to {}", decision.path.display());
+  ///       }
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn audit<F: Fn(&AccessDecision) + Send + Sync + 'static>(&self, f: F) {
+    self.audit_hook.lock().unwrap().replace(Arc::new(f));
+  }
+
   fn trigger(&self, event: Event) {
     let listeners = self.event_listeners.lock().unwrap();
     let handlers = listeners.values();
@@ -138,22 +237,109 @@ impl Scope {
     }
   }
 
+  /// Saves the paths allowed and forbidden at runtime to the given file,
+  /// after restoring the ones it contains.
+  ///
+  /// This is opt-in, so the paths the user granted access to, e.g. with a dialog,
+  /// are remembered across restarts. Only the changes made after this call are saved.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::Manager;
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let path = app.path_resolver().app_data_dir().unwrap().join("fs-scope.json");
+  ///     app.fs_scope().persist(path)?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn persist<P: Into<PathBuf>>(&self, path: P) -> crate::Result<()> {
+    let path = path.into();
+    // whether the file must be rewritten without the changes that could not be read or restored
+    let mut reset = false;
+    let changes: Vec<Change> = match std::fs::read(&path) {
+      Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+        warn!(
+          "resetting the corrupted scope file {}: {}",
+          path.display(),
+          e
+        );
+        reset = true;
+        Vec::new()
+      }),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+      Err(e) => return Err(e.into()),
+    };
+    // stop saving to the previous file, if any, while restoring
+    self.persistence.lock().unwrap().take();
+    let mut restored = Vec::new();
+    for change in changes {
+      let result = match &change {
+        Change::AllowDirectory { path, recursive } => self.allow_directory(path, *recursive),
+        Change::AllowFile { path } => self.allow_file(path),
+        Change::ForbidDirectory { path, recursive } => self.forbid_directory(path, *recursive),
+        Change::ForbidFile { path } => self.forbid_file(path),
+      };
+      match result {
+        Ok(()) => restored.push(change),
+        Err(e) => {
+          warn!("failed to restore the scope change {:?}: {}", change, e);
+          reset = true;
+        }
+      }
+    }
+    let persistence = Persistence {
+      path,
+      changes: restored,
+    };
+    if reset {
+      if let Err(e) = persistence.save() {
+        warn!(
+          "failed to save the scope file {}: {}",
+          persistence.path.display(),
+          e
+        );
+      }
+    }
+    self.persistence.lock().unwrap().replace(persistence);
+    Ok(())
+  }
+
+  /// Saves the change, if the scope is persisted.
+  ///
+  /// Called before the patterns are applied, so the scope is left unchanged when the save fails.
+  fn record(&self, change: Change) -> crate::Result<()> {
+    if let Some(persistence) = &mut *self.persistence.lock().unwrap() {
+      if !persistence.changes.contains(&change) {
+        persistence.changes.push(change);
+        if let Err(e) = persistence.save() {
+          persistence.changes.pop();
+          return Err(e);
+        }
+      }
+    }
+    Ok(())
+  }
+
   /// Extend the allowed patterns with the given directory.
   ///
   /// After this function has been called, the frontend will be able to use the Tauri API to read
   /// the directory and all of its files and subdirectories.
   pub fn allow_directory<P: AsRef<Path>>(&self, path: P, recursive: bool) -> crate::Result<()> {
     let path = path.as_ref();
-    {
-      let mut list = self.allowed_patterns.lock().unwrap();
-
-      // allow the directory to be read
-      push_pattern(&mut list, path, escaped_pattern)?;
-      // allow its files and subdirectories to be read
-      push_pattern(&mut list, path, |p| {
-        escaped_pattern_with(p, if recursive { "**" } else { "*" })
-      })?;
-    }
+    let mut patterns = HashSet::new();
+    // allow the directory to be read
+    push_pattern(&mut patterns, path, escaped_pattern)?;
+    // allow its files and subdirectories to be read
+    push_pattern(&mut patterns, path, |p| {
+      escaped_pattern_with(p, if recursive { "**" } else { "*" })
+    })?;
+    self.record(Change::AllowDirectory {
+      path: path.to_path_buf(),
+      recursive,
+    })?;
+    self.allowed_patterns.lock().unwrap().extend(patterns);
     self.trigger(Event::PathAllowed(path.to_path_buf()));
     Ok(())
   }
@@ -163,11 +349,12 @@ impl Scope {
   /// After this function has been called, the frontend will be able to use the Tauri API to read the contents of this file.
   pub fn allow_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
     let path = path.as_ref();
-    push_pattern(
-      &mut self.allowed_patterns.lock().unwrap(),
-      path,
-      escaped_pattern,
-    )?;
+    let mut patterns = HashSet::new();
+    push_pattern(&mut patterns, path, escaped_pattern)?;
+    self.record(Change::AllowFile {
+      path: path.to_path_buf(),
+    })?;
+    self.allowed_patterns.lock().unwrap().extend(patterns);
     self.trigger(Event::PathAllowed(path.to_path_buf()));
     Ok(())
   }
@@ -177,16 +364,18 @@ impl Scope {
   /// **Note:** this takes precedence over allowed paths, so its access gets denied **always**.
   pub fn forbid_directory<P: AsRef<Path>>(&self, path: P, recursive: bool) -> crate::Result<()> {
     let path = path.as_ref();
-    {
-      let mut list = self.forbidden_patterns.lock().unwrap();
-
-      // allow the directory to be read
-      push_pattern(&mut list, path, escaped_pattern)?;
-      // allow its files and subdirectories to be read
-      push_pattern(&mut list, path, |p| {
-        escaped_pattern_with(p, if recursive { "**" } else { "*" })
-      })?;
-    }
+    let mut patterns = HashSet::new();
+    // allow the directory to be read
+    push_pattern(&mut patterns, path, escaped_pattern)?;
+    // allow its files and subdirectories to be read
+    push_pattern(&mut patterns, path, |p| {
+      escaped_pattern_with(p, if recursive { "**" } else { "*" })
+    })?;
+    self.record(Change::ForbidDirectory {
+      path: path.to_path_buf(),
+      recursive,
+    })?;
+    self.forbidden_patterns.lock().unwrap().extend(patterns);
     self.trigger(Event::PathForbidden(path.to_path_buf()));
     Ok(())
   }
@@ -196,33 +385,30 @@ impl Scope {
   /// **Note:** this takes precedence over allowed paths, so its access gets denied **always**.
   pub fn forbid_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
     let path = path.as_ref();
-    push_pattern(
-      &mut self.forbidden_patterns.lock().unwrap(),
-      path,
-      escaped_pattern,
-    )?;
+    let mut patterns = HashSet::new();
+    push_pattern(&mut patterns, path, escaped_pattern)?;
+    self.record(Change::ForbidFile {
+      path: path.to_path_buf(),
+    })?;
+    self.forbidden_patterns.lock().unwrap().extend(patterns);
     self.trigger(Event::PathForbidden(path.to_path_buf()));
     Ok(())
   }
 
   /// Determines if the given path is allowed on this scope.
+  ///
+  /// The symbolic links of the path are handled according to the `tauri > security > symlinkPolicy` configuration.
   pub fn is_allowed<P: AsRef<Path>>(&self, path: P) -> bool {
     let path = path.as_ref();
-    let path = if !path.exists() {
-      crate::Result::Ok(path.to_path_buf())
-    } else {
-      std::fs::canonicalize(path).map_err(Into::into)
-    };
-
-    if let Ok(path) = path {
-      let path: PathBuf = path.components().collect();
+    let resolved_path = resolve_path(path, self.symlink_policy);
 
+    let allowed = if let Some(resolved_path) = &resolved_path {
       let forbidden = self
         .forbidden_patterns
         .lock()
         .unwrap()
         .iter()
-        .any(|p| p.matches_path(&path));
+        .any(|p| p.matches_path(resolved_path));
 
       if forbidden {
         false
@@ -232,13 +418,67 @@ impl Scope {
           .lock()
           .unwrap()
           .iter()
-          .any(|p| p.matches_path(&path));
+          .any(|p| p.matches_path(resolved_path));
         allowed
       }
     } else {
       false
+    };
+
+    // the hook is called without holding the lock, so it can use the scope
+    let audit_hook = self.audit_hook.lock().unwrap().clone();
+    if let Some(audit_hook) = audit_hook {
+      audit_hook(&AccessDecision {
+        path: path.to_path_buf(),
+        resolved_path,
+        allowed,
+      });
+    }
+
+    allowed
+  }
+}
+
+/// Resolves the path matched against the patterns of a scope, or `None` if the policy denies it.
+fn resolve_path(path: &Path, policy: SymlinkPolicy) -> Option<PathBuf> {
+  let resolved = match policy {
+    SymlinkPolicy::Follow => {
+      if path.exists() {
+        std::fs::canonicalize(path).ok()?
+      } else {
+        path.to_path_buf()
+      }
+    }
+    SymlinkPolicy::DenyEscape => resolve_existing_ancestor(path)?,
+    SymlinkPolicy::Deny => {
+      let is_link = |path: &Path| {
+        std::fs::symlink_metadata(path)
+          .map(|metadata| metadata.file_type().is_symlink())
+          .unwrap_or(false)
+      };
+      if path.ancestors().any(is_link) {
+        return None;
+      }
+      resolve_existing_ancestor(path)?
     }
+  };
+  Some(resolved.components().collect())
+}
+
+/// Canonicalizes the deepest ancestor of the path that exists, appending the components that do not exist yet.
+///
+/// Returns `None` if the path has a parent directory component that does not exist, or a dangling link.
+fn resolve_existing_ancestor(path: &Path) -> Option<PathBuf> {
+  let mut existing = path;
+  let mut missing = Vec::new();
+  // links are not followed, so dangling links fail to canonicalize
+  while std::fs::symlink_metadata(existing).is_err() {
+    missing.push(existing.file_name()?);
+    existing = existing.parent()?;
   }
+  let mut resolved = std::fs::canonicalize(existing).ok()?;
+  resolved.extend(missing.into_iter().rev());
+  Some(resolved)
 }
 
 fn escaped_pattern(p: &str) -> Result<Pattern, glob::PatternError> {
@@ -257,12 +497,19 @@ fn escaped_pattern_with(p: &str, append: &str) -> Result<Pattern, glob::PatternE
 #[cfg(test)]
 mod tests {
   use super::Scope;
+  use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+  };
 
   fn new_scope() -> Scope {
     Scope {
       allowed_patterns: Default::default(),
       forbidden_patterns: Default::default(),
       event_listeners: Default::default(),
+      persistence: Default::default(),
+      symlink_policy: Default::default(),
+      audit_hook: Default::default(),
     }
   }
 
@@ -297,4 +544,154 @@ mod tests {
     assert!(scope.is_allowed("/home/tauri/**/inner/file"));
     assert!(scope.is_allowed("/home/tauri/anyfile"));
   }
+
+  #[test]
+  fn persisted_changes_are_restored() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("scope.json");
+
+    let scope = new_scope();
+    scope.allow_file("/home/tauri/before.txt").unwrap();
+    scope.persist(&file).unwrap();
+    scope.allow_directory("/home/tauri/projects", true).unwrap();
+    scope.forbid_file("/home/tauri/projects/secret").unwrap();
+
+    let restored = new_scope();
+    restored.persist(&file).unwrap();
+    assert!(!restored.is_allowed("/home/tauri/before.txt"));
+    assert!(restored.is_allowed("/home/tauri/projects/app/file"));
+    assert!(!restored.is_allowed("/home/tauri/projects/secret"));
+
+    // restoring does not duplicate the saved changes
+    restored
+      .allow_directory("/home/tauri/projects", true)
+      .unwrap();
+    let saved: Vec<super::Change> = serde_json::from_slice(&std::fs::read(&file).unwrap()).unwrap();
+    assert_eq!(saved.len(), 2);
+  }
+
+  #[test]
+  fn failed_saves_are_not_applied() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("scope").join("scope.json");
+
+    let scope = new_scope();
+    scope.persist(&file).unwrap();
+    // the directory of the file can no longer be created
+    std::fs::write(dir.path().join("scope"), "").unwrap();
+
+    assert!(scope.allow_file("/home/tauri/file.txt").is_err());
+    assert!(!scope.is_allowed("/home/tauri/file.txt"));
+    assert!(scope.allow_directory("/home/tauri/projects", true).is_err());
+    assert!(!scope.is_allowed("/home/tauri/projects/file"));
+  }
+
+  #[test]
+  fn corrupted_files_are_reset() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("scope.json");
+    std::fs::write(
+      &file,
+      r#"[{"type":"allowFile","path":"/home/tauri/file.txt"}"#,
+    )
+    .unwrap();
+
+    let scope = new_scope();
+    scope.persist(&file).unwrap();
+    assert!(!scope.is_allowed("/home/tauri/file.txt"));
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "[]");
+
+    // the persistence is attached
+    scope.allow_file("/home/tauri/file.txt").unwrap();
+    let saved: Vec<super::Change> = serde_json::from_slice(&std::fs::read(&file).unwrap()).unwrap();
+    assert_eq!(saved.len(), 1);
+  }
+
+  #[test]
+  fn audit_hook_can_use_the_scope() {
+    let scope = new_scope();
+    scope.allow_file("/home/tauri/file.txt").unwrap();
+    let checked = Arc::new(Mutex::new(Vec::new()));
+    let checked_ = checked.clone();
+    let scope_ = scope.clone();
+    scope.audit(move |decision| {
+      // a check from the hook would deadlock if the hook was called with the lock held
+      if decision.path != PathBuf::from("/home/tauri/nested.txt") {
+        scope_.is_allowed("/home/tauri/nested.txt");
+      }
+      checked_.lock().unwrap().push(decision.path.clone());
+    });
+    assert!(scope.is_allowed("/home/tauri/file.txt"));
+    assert_eq!(checked.lock().unwrap().len(), 2);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symlink_policy() {
+    use std::os::unix::fs::symlink;
+    use tauri_utils::config::SymlinkPolicy;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = std::fs::canonicalize(dir.path()).unwrap();
+    let allowed = root.join("allowed");
+    let outside = root.join("outside");
+    std::fs::create_dir_all(&allowed).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    std::fs::write(outside.join("secret"), "").unwrap();
+    std::fs::write(allowed.join("file"), "").unwrap();
+    symlink(&outside, allowed.join("link")).unwrap();
+    symlink(allowed.join("file"), allowed.join("file-link")).unwrap();
+
+    let scope = |symlink_policy| {
+      let mut scope = new_scope();
+      scope.symlink_policy = symlink_policy;
+      scope.allow_directory(&allowed, true).unwrap();
+      scope
+    };
+    let follow = scope(SymlinkPolicy::Follow);
+    let deny_escape = scope(SymlinkPolicy::DenyEscape);
+    let deny = scope(SymlinkPolicy::Deny);
+
+    // existing files are resolved on every policy
+    for scope in [&follow, &deny_escape, &deny] {
+      assert!(!scope.is_allowed(allowed.join("link/secret")));
+      assert!(scope.is_allowed(allowed.join("new")));
+    }
+
+    // new files through a link pointing outside of the scope
+    assert!(follow.is_allowed(allowed.join("link/new")));
+    assert!(!deny_escape.is_allowed(allowed.join("link/new")));
+    assert!(!deny.is_allowed(allowed.join("link/new")));
+
+    // links pointing inside of the scope
+    assert!(deny_escape.is_allowed(allowed.join("file-link")));
+    assert!(!deny.is_allowed(allowed.join("file-link")));
+
+    // parent directory components of paths that do not exist
+    assert!(!deny_escape.is_allowed(allowed.join("missing/../../outside/new")));
+  }
+
+  #[test]
+  fn audit_hook_receives_decisions() {
+    let scope = new_scope();
+    scope.allow_file("/home/tauri/allowed.txt").unwrap();
+    let decisions = Arc::new(Mutex::new(Vec::new()));
+    let decisions_ = decisions.clone();
+    scope.audit(move |decision| {
+      decisions_
+        .lock()
+        .unwrap()
+        .push((decision.path.clone(), decision.allowed))
+    });
+
+    assert!(scope.is_allowed("/home/tauri/allowed.txt"));
+    assert!(!scope.is_allowed("/home/tauri/other.txt"));
+    assert_eq!(
+      *decisions.lock().unwrap(),
+      vec![
+        (PathBuf::from("/home/tauri/allowed.txt"), true),
+        (PathBuf::from("/home/tauri/other.txt"), false)
+      ]
+    );
+  }
 }
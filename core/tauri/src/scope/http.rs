@@ -3,12 +3,13 @@
 // SPDX-License-Identifier: MIT
 
 use glob::Pattern;
-use tauri_utils::config::HttpAllowlistScope;
+use std::sync::{Arc, Mutex};
+use tauri_utils::config::{HttpAllowlistScope, OAuthAllowlistScope, WebsocketAllowlistScope};
 
 /// Scope for filesystem access.
 #[derive(Debug, Clone)]
 pub struct Scope {
-  allowed_urls: Vec<Pattern>,
+  allowed_urls: Arc<Mutex<Vec<Pattern>>>,
 }
 
 impl Scope {
@@ -16,21 +17,45 @@ impl Scope {
   #[allow(dead_code)]
   pub(crate) fn for_http_api(scope: &HttpAllowlistScope) -> Self {
     Self {
-      allowed_urls: scope
-        .0
-        .iter()
-        .map(|url| {
-          glob::Pattern::new(url.as_str())
-            .unwrap_or_else(|_| panic!("scoped URL is not a valid glob pattern: `{}`", url))
-        })
-        .collect(),
+      allowed_urls: Arc::new(Mutex::new(
+        scope
+          .0
+          .iter()
+          .map(|url| {
+            glob::Pattern::new(url.as_str())
+              .unwrap_or_else(|_| panic!("scoped URL is not a valid glob pattern: `{}`", url))
+          })
+          .collect(),
+      )),
     }
   }
 
+  /// Creates a new scope from the allowlist's `websocket` scope configuration.
+  #[allow(dead_code)]
+  pub(crate) fn for_websocket_api(scope: &WebsocketAllowlistScope) -> Self {
+    Self::for_http_api(&HttpAllowlistScope(scope.0.clone()))
+  }
+
+  /// Creates a new scope from the allowlist's `oauth` scope configuration.
+  #[allow(dead_code)]
+  pub(crate) fn for_oauth_api(scope: &OAuthAllowlistScope) -> Self {
+    Self::for_http_api(&HttpAllowlistScope(scope.0.clone()))
+  }
+
+  /// Replaces the allowed URLs with the ones of the given scope.
+  #[cfg(config_reload)]
+  #[allow(dead_code)]
+  pub(crate) fn replace(&self, scope: Self) {
+    let allowed_urls = scope.allowed_urls.lock().unwrap().clone();
+    *self.allowed_urls.lock().unwrap() = allowed_urls;
+  }
+
   /// Determines if the given URL is allowed on this scope.
   pub fn is_allowed(&self, url: &url::Url) -> bool {
     self
       .allowed_urls
+      .lock()
+      .unwrap()
       .iter()
       .any(|allowed| allowed.matches(url.as_str()))
   }
@@ -0,0 +1,76 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use glob::Pattern;
+use std::sync::{Arc, Mutex};
+use tauri_utils::config::MdnsAllowlistScope;
+
+/// Scope for the service types the webviews can advertise and browse.
+#[derive(Debug, Clone)]
+pub struct Scope {
+  allowed_service_types: Arc<Mutex<Vec<Pattern>>>,
+}
+
+impl Scope {
+  /// Creates a new scope from the allowlist's `mdns` scope configuration.
+  #[allow(dead_code)]
+  pub(crate) fn for_mdns_api(scope: &MdnsAllowlistScope) -> Self {
+    Self {
+      allowed_service_types: Arc::new(Mutex::new(
+        scope
+          .0
+          .iter()
+          .map(|service_type| {
+            Pattern::new(service_type).unwrap_or_else(|_| {
+              panic!(
+                "scoped service type is not a valid glob pattern: `{}`",
+                service_type
+              )
+            })
+          })
+          .collect(),
+      )),
+    }
+  }
+
+  /// Replaces the allowed service types with the ones of the given scope.
+  #[cfg(config_reload)]
+  #[allow(dead_code)]
+  pub(crate) fn replace(&self, scope: Self) {
+    let allowed_service_types = scope.allowed_service_types.lock().unwrap().clone();
+    *self.allowed_service_types.lock().unwrap() = allowed_service_types;
+  }
+
+  /// Determines if the given service type, e.g. `_myapp._tcp` or `_myapp._tcp.local.`, is allowed on this scope.
+  pub fn is_allowed(&self, service_type: &str) -> bool {
+    let service_type = service_type.trim_end_matches('.');
+    let service_type = service_type.strip_suffix(".local").unwrap_or(service_type);
+    self
+      .allowed_service_types
+      .lock()
+      .unwrap()
+      .iter()
+      .any(|allowed| allowed.matches(service_type))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tauri_utils::config::MdnsAllowlistScope;
+
+  #[test]
+  fn is_allowed() {
+    let scope = super::Scope::for_mdns_api(&MdnsAllowlistScope(vec![
+      "_myapp._tcp".into(),
+      "_myapp-*._udp".into(),
+    ]));
+
+    assert!(scope.is_allowed("_myapp._tcp"));
+    assert!(scope.is_allowed("_myapp._tcp.local."));
+    assert!(scope.is_allowed("_myapp-sync._udp"));
+
+    assert!(!scope.is_allowed("_http._tcp"));
+    assert!(!scope.is_allowed("_myapp-sync._tcp"));
+  }
+}
@@ -2,13 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+mod command;
 mod fs;
 mod http;
+#[cfg(mdns_any)]
+mod mdns;
+mod permission;
+#[cfg(serial_any)]
+mod serial;
 #[cfg(shell_scope)]
 mod shell;
+#[cfg(store_any)]
+mod store;
 
 pub use self::http::Scope as HttpScope;
-pub use fs::{Event as FsScopeEvent, Pattern as GlobPattern, Scope as FsScope};
+/// Scope for the WebSocket connections, matching the URLs like the [`HttpScope`].
+pub type WebsocketScope = HttpScope;
+/// Scope for the OAuth endpoints, matching the URLs like the [`HttpScope`].
+pub type OAuthScope = HttpScope;
+pub use command::Scope as CommandScope;
+pub use fs::{
+  AccessDecision as FsScopeAccessDecision, Event as FsScopeEvent, Pattern as GlobPattern,
+  Scope as FsScope,
+};
+#[cfg(mdns_any)]
+pub use mdns::Scope as MdnsScope;
+pub(crate) use permission::Scope as PluginPermissionScope;
+#[cfg(serial_any)]
+pub use serial::Scope as SerialScope;
 #[cfg(shell_scope)]
 pub use shell::{
   ExecuteArgs, Scope as ShellScope, ScopeAllowedArg as ShellScopeAllowedArg,
@@ -16,13 +37,27 @@ pub use shell::{
   ScopeError as ShellScopeError,
 };
 use std::path::Path;
+#[cfg(store_any)]
+pub use store::Scope as StoreScope;
 
 pub(crate) struct Scopes {
+  pub command: CommandScope,
+  pub plugin_permissions: PluginPermissionScope,
   pub fs: FsScope,
   #[cfg(protocol_asset)]
   pub asset_protocol: FsScope,
   #[cfg(http_request)]
   pub http: HttpScope,
+  #[cfg(websocket_connect)]
+  pub websocket: WebsocketScope,
+  #[cfg(oauth_authenticate)]
+  pub oauth: OAuthScope,
+  #[cfg(serial_any)]
+  pub serial: SerialScope,
+  #[cfg(mdns_any)]
+  pub mdns: MdnsScope,
+  #[cfg(store_any)]
+  pub store: StoreScope,
   #[cfg(shell_scope)]
   pub shell: ShellScope,
 }
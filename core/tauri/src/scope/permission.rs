@@ -0,0 +1,110 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use glob::Pattern;
+use tauri_utils::config::PluginPermissionEntry;
+
+use crate::plugin::Permission;
+
+#[derive(Debug, Clone)]
+struct Entry {
+  windows: Vec<Pattern>,
+  permissions: Vec<Pattern>,
+}
+
+fn patterns(list: &[String]) -> crate::Result<Vec<Pattern>> {
+  list
+    .iter()
+    .map(|p| Pattern::new(p).map_err(Into::into))
+    .collect()
+}
+
+/// Scope for the plugin permissions granted to each window.
+///
+/// See [`SecurityConfig::plugin_permissions`](crate::utils::config::SecurityConfig#structfield.plugin_permissions).
+#[derive(Debug, Clone)]
+pub(crate) struct Scope {
+  entries: Vec<Entry>,
+}
+
+impl Scope {
+  /// Creates a new scope from the `tauri > security > pluginPermissions` configuration.
+  pub(crate) fn new(entries: &[PluginPermissionEntry]) -> crate::Result<Self> {
+    Ok(Self {
+      entries: entries
+        .iter()
+        .map(|entry| {
+          Ok(Entry {
+            windows: patterns(&entry.windows)?,
+            permissions: patterns(&entry.permissions)?,
+          })
+        })
+        .collect::<crate::Result<_>>()?,
+    })
+  }
+
+  /// Determines if the given permission, identified as `<plugin-name>:<permission>`,
+  /// is granted to the window with the given label.
+  pub(crate) fn is_granted(&self, window_label: &str, permission: &str) -> bool {
+    self.entries.iter().any(|e| {
+      (e.windows.is_empty() || e.windows.iter().any(|w| w.matches(window_label)))
+        && e.permissions.iter().any(|p| p.matches(permission))
+    })
+  }
+
+  /// Determines if the window with the given label can invoke the command of a plugin with the given permissions.
+  ///
+  /// Plugins that do not declare permissions are not restricted.
+  pub(crate) fn allows_command(
+    &self,
+    window_label: &str,
+    plugin: &str,
+    permissions: &[Permission],
+    command: &str,
+  ) -> bool {
+    permissions.is_empty()
+      || permissions
+        .iter()
+        .filter(|p| p.commands().iter().any(|c| c == command))
+        .any(|p| self.is_granted(window_label, &format!("{}:{}", plugin, p.name())))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Scope;
+  use crate::plugin::Permission;
+  use tauri_utils::config::PluginPermissionEntry;
+
+  fn entry(windows: &[&str], permissions: &[&str]) -> PluginPermissionEntry {
+    PluginPermissionEntry {
+      windows: windows.iter().map(|s| s.to_string()).collect(),
+      permissions: permissions.iter().map(|s| s.to_string()).collect(),
+    }
+  }
+
+  #[test]
+  fn allows_command() {
+    let permissions = [
+      Permission::new("read").command("get").command("has"),
+      Permission::new("write").command("set"),
+    ];
+
+    let scope = Scope::new(&[]).unwrap();
+    assert!(scope.allows_command("main", "store", &[], "set"));
+    assert!(!scope.allows_command("main", "store", &permissions, "get"));
+
+    let scope = Scope::new(&[
+      entry(&[], &["store:read"]),
+      entry(&["settings-*"], &["store:*"]),
+    ])
+    .unwrap();
+    assert!(scope.allows_command("main", "store", &permissions, "get"));
+    assert!(scope.allows_command("main", "store", &permissions, "has"));
+    assert!(!scope.allows_command("main", "store", &permissions, "set"));
+    assert!(scope.allows_command("settings-1", "store", &permissions, "set"));
+    assert!(!scope.allows_command("settings-1", "store", &permissions, "clear"));
+    assert!(!scope.allows_command("main", "sql", &permissions, "get"));
+  }
+}
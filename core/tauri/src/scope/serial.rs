@@ -0,0 +1,134 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use glob::{MatchOptions, Pattern};
+use std::{
+  path::{Component, Path},
+  sync::{Arc, Mutex},
+};
+use tauri_utils::config::SerialAllowlistScope;
+
+use crate::api::serial::{Device, SerialPortInfo, UsbInfo};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+  case_sensitive: true,
+  // `*` must not match the path separator, so `/dev/tty*` doesn't allow `/dev/tty/../sda`
+  require_literal_separator: true,
+  require_literal_leading_dot: false,
+};
+
+/// Scope for serial port access.
+#[derive(Debug, Clone)]
+pub struct Scope {
+  allowed_ports: Arc<Mutex<Vec<Pattern>>>,
+}
+
+impl Scope {
+  /// Creates a new scope from the allowlist's `serial` scope configuration.
+  #[allow(dead_code)]
+  pub(crate) fn for_serial_api(scope: &SerialAllowlistScope) -> Self {
+    Self {
+      allowed_ports: Arc::new(Mutex::new(
+        scope
+          .0
+          .iter()
+          .map(|port| {
+            Pattern::new(port)
+              .unwrap_or_else(|_| panic!("scoped port is not a valid glob pattern: `{}`", port))
+          })
+          .collect(),
+      )),
+    }
+  }
+
+  /// Replaces the allowed ports with the ones of the given scope.
+  #[cfg(config_reload)]
+  #[allow(dead_code)]
+  pub(crate) fn replace(&self, scope: Self) {
+    let allowed_ports = scope.allowed_ports.lock().unwrap().clone();
+    *self.allowed_ports.lock().unwrap() = allowed_ports;
+  }
+
+  /// Determines if the given port is allowed on this scope,
+  /// matching its path or the `<vendor id>:<product id>` identifier of its USB device.
+  ///
+  /// Paths with a `..` component are never allowed.
+  pub fn is_allowed(&self, port: &SerialPortInfo) -> bool {
+    if Path::new(&port.path)
+      .components()
+      .any(|component| component == Component::ParentDir)
+    {
+      return false;
+    }
+    let usb_id = port.usb_id();
+    self.allowed_ports.lock().unwrap().iter().any(|allowed| {
+      allowed.matches_with(&port.path, MATCH_OPTIONS)
+        || usb_id
+          .as_ref()
+          .map_or(false, |id| allowed.matches_with(id, MATCH_OPTIONS))
+    })
+  }
+
+  /// Determines if the given USB device is allowed on this scope, matching its `<vendor id>:<product id>` identifier.
+  pub fn is_usb_allowed(&self, usb: &UsbInfo) -> bool {
+    let id = usb.id();
+    self
+      .allowed_ports
+      .lock()
+      .unwrap()
+      .iter()
+      .any(|allowed| allowed.matches_with(&id, MATCH_OPTIONS))
+  }
+
+  /// Determines if the given device is allowed on this scope, see [`Self::is_allowed`] and [`Self::is_usb_allowed`].
+  pub fn is_device_allowed(&self, device: &Device) -> bool {
+    match device {
+      Device::SerialPort(port) => self.is_allowed(port),
+      Device::Usb(usb) => self.is_usb_allowed(&usb.info),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::serial::{SerialPortInfo, UsbInfo};
+  use tauri_utils::config::SerialAllowlistScope;
+
+  fn port(path: &str, usb: Option<(u16, u16)>) -> SerialPortInfo {
+    SerialPortInfo {
+      path: path.into(),
+      usb: usb.map(|(vendor_id, product_id)| UsbInfo {
+        vendor_id,
+        product_id,
+        serial_number: None,
+        manufacturer: None,
+        product: None,
+      }),
+    }
+  }
+
+  #[test]
+  fn is_allowed() {
+    let scope = super::Scope::for_serial_api(&SerialAllowlistScope(vec![
+      "/dev/ttyUSB*".into(),
+      "2341:*".into(),
+    ]));
+
+    assert!(scope.is_allowed(&port("/dev/ttyUSB0", None)));
+    assert!(scope.is_allowed(&port("/dev/ttyACM0", Some((0x2341, 0x0043)))));
+    assert!(scope.is_allowed(&port("COM3", Some((0x2341, 0x8036)))));
+
+    assert!(!scope.is_allowed(&port("/dev/ttyS0", None)));
+    assert!(!scope.is_allowed(&port("/dev/ttyACM1", Some((0x0403, 0x6001)))));
+  }
+
+  #[test]
+  fn rejects_traversal() {
+    let scope = super::Scope::for_serial_api(&SerialAllowlistScope(vec!["/dev/tty*".into()]));
+
+    assert!(scope.is_allowed(&port("/dev/ttyUSB0", None)));
+    assert!(!scope.is_allowed(&port("/dev/tty/../sda", None)));
+    assert!(!scope.is_allowed(&port("/dev/ttyUSB0/../../etc/passwd", None)));
+  }
+}
@@ -78,6 +78,12 @@ pub struct ScopeAllowedCommand {
 
   /// If this command is a sidecar command.
   pub sidecar: bool,
+
+  /// The arguments passed before the validated arguments on the current platform.
+  pub default_args: Vec<String>,
+
+  /// The environment variables set on the current platform.
+  pub env: HashMap<String, String>,
 }
 
 /// A configured argument to a scoped shell command.
@@ -204,6 +210,14 @@ impl Scope {
       if let Ok(path) = crate::api::path::parse(config, package_info, env, &cmd.command) {
         cmd.command = path;
       }
+      for value in cmd.default_args.iter_mut().chain(cmd.env.values_mut()) {
+        // only values starting with a variable are parsed, since parsing normalizes the path
+        if value.starts_with('$') {
+          if let Ok(path) = crate::api::path::parse(config, package_info, env, value.as_str()) {
+            *value = path.to_string_lossy().into_owned();
+          }
+        }
+      }
     }
     Self(scope)
   }
@@ -289,13 +303,15 @@ impl Scope {
           .into_owned()
       })
       .unwrap_or_else(|| command.command.to_string_lossy().into_owned());
+    let default_args = &command.default_args;
+    let env = command.env.clone();
     let command = if command.sidecar {
       Command::new_sidecar(command_s).map_err(ScopeError::Sidecar)?
     } else {
       Command::new(command_s)
     };
 
-    Ok(command.args(args))
+    Ok(command.args(default_args).args(args).envs(env))
   }
 
   /// Open a path in the default (or specified) browser.
@@ -0,0 +1,69 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use glob::Pattern;
+use std::sync::{Arc, Mutex};
+use tauri_utils::config::StoreAllowlistScope;
+
+/// Scope for the stores the webviews can open.
+#[derive(Debug, Clone)]
+pub struct Scope {
+  allowed_stores: Arc<Mutex<Vec<Pattern>>>,
+}
+
+impl Scope {
+  /// Creates a new scope from the allowlist's `store` scope configuration.
+  #[allow(dead_code)]
+  pub(crate) fn for_store_api(scope: &StoreAllowlistScope) -> Self {
+    Self {
+      allowed_stores: Arc::new(Mutex::new(
+        scope
+          .0
+          .iter()
+          .map(|store| {
+            Pattern::new(store)
+              .unwrap_or_else(|_| panic!("scoped store is not a valid glob pattern: `{}`", store))
+          })
+          .collect(),
+      )),
+    }
+  }
+
+  /// Replaces the allowed stores with the ones of the given scope.
+  #[cfg(config_reload)]
+  #[allow(dead_code)]
+  pub(crate) fn replace(&self, scope: Self) {
+    let allowed_stores = scope.allowed_stores.lock().unwrap().clone();
+    *self.allowed_stores.lock().unwrap() = allowed_stores;
+  }
+
+  /// Determines if the store with the given name is allowed on this scope.
+  pub fn is_allowed(&self, name: &str) -> bool {
+    self
+      .allowed_stores
+      .lock()
+      .unwrap()
+      .iter()
+      .any(|allowed| allowed.matches(name))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tauri_utils::config::StoreAllowlistScope;
+
+  #[test]
+  fn is_allowed() {
+    let scope = super::Scope::for_store_api(&StoreAllowlistScope(vec![
+      "settings".into(),
+      "cache-*".into(),
+    ]));
+
+    assert!(scope.is_allowed("settings"));
+    assert!(scope.is_allowed("cache-images"));
+
+    assert!(!scope.is_allowed("settings-backup"));
+    assert!(!scope.is_allowed("secrets"));
+  }
+}
@@ -0,0 +1,179 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The splashscreen configured on `tauri > splashscreen`.
+
+use std::{
+  sync::atomic::{AtomicBool, Ordering},
+  time::Duration,
+};
+
+use crate::{
+  log::warn,
+  runtime::{webview::WebviewAttributes, window::PendingWindow},
+  utils::config::{SplashscreenConfig, WindowConfig},
+  EventLoopMessage, Manager, Runtime, Window,
+};
+
+/// The label of the splashscreen window, reserved so it does not conflict with the windows of the app.
+pub(crate) const SPLASHSCREEN_LABEL: &str = "__tauri_splashscreen";
+
+/// The interval between the opacity updates of the fade transitions, about one frame at 60Hz.
+const FADE_STEP: Duration = Duration::from_millis(16);
+
+/// The state of the splashscreen, managed by the app.
+pub(crate) struct Splashscreen {
+  config: SplashscreenConfig,
+  /// Whether the main window was hidden while the splashscreen is shown.
+  show_main_window: bool,
+  closed: AtomicBool,
+}
+
+impl Splashscreen {
+  pub(crate) fn new(config: SplashscreenConfig, show_main_window: bool) -> Self {
+    Self {
+      config,
+      show_main_window,
+      closed: AtomicBool::new(false),
+    }
+  }
+}
+
+/// The window showing the splashscreen, created before the other windows.
+pub(crate) fn pending_window<R: Runtime>(
+  config: &SplashscreenConfig,
+) -> crate::Result<PendingWindow<EventLoopMessage, R>> {
+  let window_config = WindowConfig {
+    label: SPLASHSCREEN_LABEL.into(),
+    url: config.url.clone(),
+    width: config.width,
+    height: config.height,
+    center: true,
+    resizable: false,
+    decorations: false,
+    transparent: config.transparent,
+    always_on_top: config.always_on_top,
+    skip_taskbar: true,
+    ..Default::default()
+  };
+  Ok(PendingWindow::with_config(
+    window_config,
+    WebviewAttributes::new(config.url.clone()),
+    SPLASHSCREEN_LABEL,
+  )?)
+}
+
+/// Hides the main window until the splashscreen is closed, returning whether it was visible.
+pub(crate) fn hide_main_window(windows: &mut [WindowConfig], main_window: &str) -> bool {
+  match windows
+    .iter_mut()
+    .find(|window| window.label == main_window && window.visible)
+  {
+    Some(window) => {
+      window.visible = false;
+      true
+    }
+    None => false,
+  }
+}
+
+/// Closes the splashscreen when the main window finished loading, unless `autoClose` is disabled.
+pub(crate) fn on_page_load<R: Runtime>(window: &Window<R>) {
+  if let Some(splashscreen) = window.try_state::<Splashscreen>() {
+    if splashscreen.config.auto_close && window.label() == splashscreen.config.main_window {
+      if let Err(e) = close(window) {
+        warn!("failed to close the splashscreen: {}", e);
+      }
+    }
+  }
+}
+
+/// Shows the main window and closes the splashscreen, with the configured fade transitions.
+pub(crate) fn close<R: Runtime, M: Manager<R>>(manager: &M) -> crate::Result<()> {
+  let splashscreen = match manager.try_state::<Splashscreen>() {
+    Some(splashscreen) => splashscreen,
+    None => return Ok(()),
+  };
+  if splashscreen.closed.swap(true, Ordering::SeqCst) {
+    return Ok(());
+  }
+  let config = &splashscreen.config;
+
+  if splashscreen.show_main_window {
+    if let Some(window) = manager.get_window(&config.main_window) {
+      if config.fade_in_duration > 0 {
+        window.set_opacity(0.0)?;
+      }
+      window.show()?;
+      window.set_focus()?;
+      if config.fade_in_duration > 0 {
+        let duration = Duration::from_millis(config.fade_in_duration);
+        std::thread::spawn(move || {
+          if let Err(e) = fade(&window, 0.0, 1.0, duration) {
+            warn!("failed to fade in the main window: {}", e);
+            let _ = window.set_opacity(1.0);
+          }
+        });
+      }
+    }
+  }
+
+  if let Some(window) = manager.get_window(SPLASHSCREEN_LABEL) {
+    if config.fade_out_duration > 0 {
+      let duration = Duration::from_millis(config.fade_out_duration);
+      std::thread::spawn(move || {
+        if let Err(e) = fade(&window, 1.0, 0.0, duration) {
+          warn!("failed to fade out the splashscreen: {}", e);
+        }
+        let _ = window.close();
+      });
+    } else {
+      window.close()?;
+    }
+  }
+  Ok(())
+}
+
+/// Animates the opacity of the window, blocking the current thread for the `duration`.
+fn fade<R: Runtime>(
+  window: &Window<R>,
+  from: f64,
+  to: f64,
+  duration: Duration,
+) -> crate::Result<()> {
+  let steps = (duration.as_millis() / FADE_STEP.as_millis()).max(1) as u32;
+  for step in 1..=steps {
+    std::thread::sleep(FADE_STEP);
+    window.set_opacity(from + (to - from) * f64::from(step) / f64::from(steps))?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::hide_main_window;
+  use crate::utils::config::WindowConfig;
+
+  fn window(label: &str, visible: bool) -> WindowConfig {
+    WindowConfig {
+      label: label.into(),
+      visible,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn hides_visible_main_window() {
+    let mut windows = vec![window("main", true), window("settings", true)];
+    assert!(hide_main_window(&mut windows, "main"));
+    assert!(!windows[0].visible);
+    assert!(windows[1].visible);
+
+    // a hidden main window is shown by the app itself
+    let mut windows = vec![window("main", false)];
+    assert!(!hide_main_window(&mut windows, "main"));
+
+    assert!(!hide_main_window(&mut [], "main"));
+  }
+}
@@ -0,0 +1,219 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  collections::HashMap,
+  sync::{Arc, Condvar, Mutex},
+  time::{Duration, Instant},
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+use crate::{sealed::ManagerBase, Manager, Runtime};
+
+/// An event emitted with [`Manager::emit_all`], [`Manager::emit_to`] or [`crate::Window::emit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent {
+  /// The event name.
+  pub event: String,
+  /// The label of the window that emitted the event, if any.
+  pub source_window_label: Option<String>,
+  /// The labels of the windows the event was sent to.
+  pub windows: Vec<String>,
+  /// The event payload.
+  pub payload: JsonValue,
+}
+
+impl RecordedEvent {
+  /// Deserializes the event payload.
+  pub fn parse_payload<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+    serde_json::from_value(self.payload.clone())
+  }
+}
+
+#[derive(Default)]
+struct Recording {
+  events: Vec<RecordedEvent>,
+  /// The number of events matched by [`EventRecorder::expect`], per event name.
+  matched: HashMap<String, usize>,
+}
+
+#[derive(Default)]
+pub(crate) struct RecorderState {
+  recording: Mutex<Recording>,
+  condvar: Condvar,
+}
+
+impl RecorderState {
+  pub(crate) fn record(&self, event: RecordedEvent) {
+    self.recording.lock().unwrap().events.push(event);
+    self.condvar.notify_all();
+  }
+}
+
+/// Records the events emitted by the application, so tests can assert on them.
+///
+/// Events are recorded from the moment the recorder is created until it is dropped.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+/// use tauri::Manager;
+///
+/// let app = tauri::test::mock_app();
+/// let recorder = tauri::test::EventRecorder::new(&app);
+///
+/// let handle = app.handle();
+/// std::thread::spawn(move || handle.emit_all("progress", 100).unwrap());
+///
+/// let progress: u32 = recorder.expect_payload("progress", Duration::from_secs(1));
+/// assert_eq!(progress, 100);
+/// ```
+#[derive(Clone)]
+pub struct EventRecorder {
+  state: Arc<RecorderState>,
+}
+
+impl EventRecorder {
+  /// Starts recording the events emitted by the application.
+  pub fn new<R: Runtime, M: Manager<R>>(manager: &M) -> Self {
+    let state = Arc::new(RecorderState::default());
+    manager.manager().add_event_recorder(&state);
+    Self { state }
+  }
+
+  /// The events recorded so far, in emission order.
+  pub fn events(&self) -> Vec<RecordedEvent> {
+    self.state.recording.lock().unwrap().events.clone()
+  }
+
+  /// The deserialized payloads of the recorded events with the given name.
+  pub fn payloads<T: DeserializeOwned>(&self, event: &str) -> serde_json::Result<Vec<T>> {
+    self
+      .state
+      .recording
+      .lock()
+      .unwrap()
+      .events
+      .iter()
+      .filter(|e| e.event == event)
+      .map(RecordedEvent::parse_payload)
+      .collect()
+  }
+
+  /// Removes the recorded events.
+  pub fn clear(&self) {
+    let mut recording = self.state.recording.lock().unwrap();
+    recording.events.clear();
+    recording.matched.clear();
+  }
+
+  /// Waits until the event is emitted, returning it.
+  ///
+  /// Each call matches the next emission of the event, so calling it twice waits for the event to be emitted twice.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the event is not emitted before the timeout.
+  pub fn expect(&self, event: &str, timeout: Duration) -> RecordedEvent {
+    let deadline = Instant::now() + timeout;
+    let mut recording = self.state.recording.lock().unwrap();
+    loop {
+      let matched = recording.matched.get(event).copied().unwrap_or_default();
+      let found = recording
+        .events
+        .iter()
+        .filter(|e| e.event == event)
+        .nth(matched)
+        .cloned();
+      if let Some(found) = found {
+        recording.matched.insert(event.into(), matched + 1);
+        return found;
+      }
+
+      let now = Instant::now();
+      if now >= deadline {
+        panic!(
+          "event `{}` was not emitted within {:?}; recorded events: {:?}",
+          event,
+          timeout,
+          recording
+            .events
+            .iter()
+            .map(|e| &e.event)
+            .collect::<Vec<_>>()
+        );
+      }
+      recording = self
+        .state
+        .condvar
+        .wait_timeout(recording, deadline - now)
+        .unwrap()
+        .0;
+    }
+  }
+
+  /// Waits until the event is emitted, returning its deserialized payload.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the event is not emitted before the timeout or its payload can't be deserialized.
+  pub fn expect_payload<T: DeserializeOwned>(&self, event: &str, timeout: Duration) -> T {
+    self
+      .expect(event, timeout)
+      .parse_payload()
+      .unwrap_or_else(|e| panic!("failed to deserialize the `{}` event payload: {}", event, e))
+  }
+
+  /// Asynchronous version of [`Self::expect`], waiting on a blocking thread of the async runtime.
+  pub async fn wait_for(&self, event: &str, timeout: Duration) -> RecordedEvent {
+    let recorder = self.clone();
+    let event = event.to_string();
+    crate::async_runtime::spawn_blocking(move || recorder.expect(&event, timeout))
+      .await
+      .unwrap_or_else(|e| panic!("{}", e))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::EventRecorder;
+  use crate::Manager;
+
+  #[test]
+  fn expect() {
+    let app = crate::test::mock_app();
+    let recorder = EventRecorder::new(&app);
+
+    app.emit_to("main", "progress", 50).unwrap();
+    let handle = app.handle();
+    std::thread::spawn(move || handle.emit_all("progress", 100).unwrap());
+
+    let event = recorder.expect("progress", Duration::from_secs(5));
+    assert_eq!(event.windows, vec!["main".to_string()]);
+    assert_eq!(event.parse_payload::<u32>().unwrap(), 50);
+    assert_eq!(
+      recorder.expect_payload::<u32>("progress", Duration::from_secs(5)),
+      100
+    );
+    assert_eq!(recorder.payloads::<u32>("progress").unwrap(), vec![50, 100]);
+
+    recorder.clear();
+    drop(recorder);
+    app.emit_all("progress", 0).unwrap();
+  }
+
+  #[test]
+  #[should_panic(expected = "event `done` was not emitted")]
+  fn expect_timeout() {
+    let app = crate::test::mock_app();
+    let recorder = EventRecorder::new(&app);
+    app.emit_all("progress", 0).unwrap();
+    recorder.expect("done", Duration::from_millis(10));
+  }
+}
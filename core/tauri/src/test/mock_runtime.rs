@@ -7,13 +7,13 @@
 use tauri_runtime::{
   menu::{Menu, MenuUpdate},
   monitor::Monitor,
-  webview::{WindowBuilder, WindowBuilderBase},
+  webview::{WebviewIpcHandler, WindowBuilder, WindowBuilderBase},
   window::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
-    CursorIcon, DetachedWindow, MenuEvent, PendingWindow, WindowEvent,
+    CursorIcon, DetachedWindow, DropEvent, MenuEvent, PendingWindow, WindowEvent,
   },
-  Dispatch, EventLoopProxy, Icon, Result, RunEvent, Runtime, RuntimeHandle, UserAttentionType,
-  UserEvent,
+  Dispatch, Error, EventLoopProxy, ExitRequestedEventAction, Icon, Result, RunEvent, Runtime,
+  RuntimeHandle, UserAttentionType, UserEvent,
 };
 #[cfg(all(desktop, feature = "system-tray"))]
 use tauri_runtime::{
@@ -29,15 +29,55 @@ use uuid::Uuid;
 use windows::Win32::Foundation::HWND;
 
 use std::{
+  any::Any,
   collections::HashMap,
   fmt,
-  sync::{Arc, Mutex},
+  sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex,
+  },
 };
 
 type ShortcutMap = HashMap<String, Box<dyn Fn() + Send + 'static>>;
+type WindowEventHandler = Box<dyn Fn(&WindowEvent) + Send + 'static>;
+type WindowId = usize;
+
+struct MockWindow {
+  label: String,
+  listeners: Arc<Mutex<HashMap<Uuid, WindowEventHandler>>>,
+  emitted_events: Vec<EmittedEvent>,
+  /// The [`WebviewIpcHandler`] of the window, whose event type is erased.
+  ipc_handler: Option<Arc<dyn Any + Send + Sync>>,
+  evaluated_scripts: Vec<String>,
+}
+
+/// An event emitted to the JavaScript listeners of a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmittedEvent {
+  /// The event name.
+  pub event: String,
+  /// The label of the window that emitted the event, if any.
+  pub source_window_label: Option<String>,
+  /// The event payload.
+  pub payload: serde_json::Value,
+}
 
+enum Message {
+  Task(Box<dyn FnOnce() + Send + 'static>),
+  UserEvent(Box<dyn Any + Send + 'static>),
+  WindowEvent(WindowId, WindowEvent),
+  CloseRequested(WindowId),
+  CloseWindow(WindowId),
+}
+
+/// The state shared by the mock runtime, its handles and its window dispatchers.
 #[derive(Clone)]
 pub struct RuntimeContext {
+  is_running: Arc<AtomicBool>,
+  windows: Arc<Mutex<HashMap<WindowId, MockWindow>>>,
+  next_window_id: Arc<AtomicUsize>,
+  tx: Arc<Mutex<Sender<Message>>>,
   shortcuts: Arc<Mutex<ShortcutMap>>,
   clipboard: Arc<Mutex<Option<String>>>,
 }
@@ -45,11 +85,138 @@ pub struct RuntimeContext {
 impl fmt::Debug for RuntimeContext {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     f.debug_struct("RuntimeContext")
+      .field("is_running", &self.is_running)
       .field("clipboard", &self.clipboard)
       .finish()
   }
 }
 
+impl RuntimeContext {
+  fn send_message(&self, message: Message) -> Result<()> {
+    self
+      .tx
+      .lock()
+      .unwrap()
+      .send(message)
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  /// Runs the task right away if the event loop is not running yet, otherwise on the next loop iteration.
+  fn run_on_main_thread(&self, f: Box<dyn FnOnce() + Send + 'static>) -> Result<()> {
+    if self.is_running.load(Ordering::Relaxed) {
+      self.send_message(Message::Task(f))
+    } else {
+      f();
+      Ok(())
+    }
+  }
+
+  fn create_window<T: UserEvent>(
+    &self,
+    pending: PendingWindow<T, MockRuntime>,
+  ) -> Result<DetachedWindow<T, MockRuntime>> {
+    let id = self.next_window_id.fetch_add(1, Ordering::Relaxed);
+    self.windows.lock().unwrap().insert(
+      id,
+      MockWindow {
+        label: pending.label.clone(),
+        listeners: Default::default(),
+        emitted_events: Default::default(),
+        ipc_handler: pending
+          .ipc_handler
+          .map(|handler| Arc::new(Mutex::new(handler)) as Arc<dyn Any + Send + Sync>),
+        evaluated_scripts: Default::default(),
+      },
+    );
+    Ok(DetachedWindow {
+      label: pending.label,
+      dispatcher: MockDispatcher {
+        id,
+        context: self.clone(),
+        url: pending.url,
+      },
+      menu_ids: Default::default(),
+      js_event_listeners: Default::default(),
+    })
+  }
+
+  /// Triggers the window event listeners and the event loop callback.
+  fn on_window_event<T: UserEvent, F: FnMut(RunEvent<T>)>(
+    &self,
+    id: WindowId,
+    event: WindowEvent,
+    callback: &mut F,
+  ) {
+    let (label, listeners) = match self.windows.lock().unwrap().get(&id) {
+      Some(window) => (window.label.clone(), window.listeners.clone()),
+      None => return,
+    };
+    for listener in listeners.lock().unwrap().values() {
+      listener(&event);
+    }
+    callback(RunEvent::WindowEvent { label, event });
+  }
+
+  /// Destroys the window, returning `true` if it was the last one and the exit was not prevented.
+  fn close_window<T: UserEvent, F: FnMut(RunEvent<T>)>(
+    &self,
+    id: WindowId,
+    callback: &mut F,
+  ) -> bool {
+    self.on_window_event(id, WindowEvent::Destroyed, callback);
+    let is_empty = {
+      let mut windows = self.windows.lock().unwrap();
+      if windows.remove(&id).is_none() {
+        return false;
+      }
+      windows.is_empty()
+    };
+
+    if is_empty {
+      let (tx, rx) = channel();
+      callback(RunEvent::ExitRequested { tx });
+      !matches!(rx.try_recv(), Ok(ExitRequestedEventAction::Prevent))
+    } else {
+      false
+    }
+  }
+
+  /// Handles a message sent to the event loop, returning `true` if the event loop must exit.
+  fn handle_message<T: UserEvent, F: FnMut(RunEvent<T>)>(
+    &self,
+    message: Message,
+    callback: &mut F,
+  ) -> bool {
+    match message {
+      Message::Task(task) => {
+        task();
+        false
+      }
+      Message::UserEvent(event) => {
+        if let Ok(event) = event.downcast::<T>() {
+          callback(RunEvent::UserEvent(*event));
+        }
+        false
+      }
+      Message::WindowEvent(id, event) => {
+        self.on_window_event(id, event, callback);
+        false
+      }
+      Message::CloseRequested(id) => {
+        let (tx, rx) = channel();
+        self.on_window_event(id, WindowEvent::CloseRequested { signal_tx: tx }, callback);
+        if rx.try_iter().any(|prevent| prevent) {
+          false
+        } else {
+          self.close_window(id, callback)
+        }
+      }
+      Message::CloseWindow(id) => self.close_window(id, callback),
+    }
+  }
+}
+
+/// A handle to the mock runtime.
 #[derive(Debug, Clone)]
 pub struct MockRuntimeHandle {
   context: RuntimeContext,
@@ -59,7 +226,9 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   type Runtime = MockRuntime;
 
   fn create_proxy(&self) -> EventProxy {
-    unimplemented!()
+    EventProxy {
+      context: self.context.clone(),
+    }
   }
 
   /// Create a new webview window.
@@ -67,19 +236,12 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     &self,
     pending: PendingWindow<T, Self::Runtime>,
   ) -> Result<DetachedWindow<T, Self::Runtime>> {
-    Ok(DetachedWindow {
-      label: pending.label,
-      dispatcher: MockDispatcher {
-        context: self.context.clone(),
-      },
-      menu_ids: Default::default(),
-      js_event_listeners: Default::default(),
-    })
+    self.context.create_window(pending)
   }
 
   /// Run a task on the main thread.
   fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<()> {
-    unimplemented!()
+    self.context.run_on_main_thread(Box::new(f))
   }
 
   #[cfg(all(desktop, feature = "system-tray"))]
@@ -88,11 +250,14 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     &self,
     system_tray: SystemTray,
   ) -> Result<<Self::Runtime as Runtime<T>>::TrayHandler> {
-    unimplemented!()
+    Ok(MockTrayHandler {
+      context: self.context.clone(),
+    })
   }
 
   fn raw_display_handle(&self) -> raw_window_handle::RawDisplayHandle {
-    unimplemented!()
+    // the web handles carry no pointers, so the code expecting a native display ignores them
+    raw_window_handle::RawDisplayHandle::Web(raw_window_handle::WebDisplayHandle::empty())
   }
 
   /// Shows the application, but does not automatically focus it.
@@ -108,9 +273,76 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   }
 }
 
+/// The window dispatcher of the mock runtime.
 #[derive(Debug, Clone)]
 pub struct MockDispatcher {
+  id: WindowId,
   context: RuntimeContext,
+  url: String,
+}
+
+impl MockDispatcher {
+  /// Sends a window event, delivered on the next event loop iteration.
+  ///
+  /// A [`WindowEvent::CloseRequested`] closes the window unless a listener prevents it.
+  pub(crate) fn send_window_event(&self, event: WindowEvent) -> Result<()> {
+    match event {
+      WindowEvent::CloseRequested { .. } => {
+        self.context.send_message(Message::CloseRequested(self.id))
+      }
+      event => self
+        .context
+        .send_message(Message::WindowEvent(self.id, event)),
+    }
+  }
+
+  /// Calls the IPC handler of the webview with the message, returning `false` if the window has no handler.
+  pub(crate) fn post_ipc_message<T: UserEvent>(
+    &self,
+    window: DetachedWindow<T, MockRuntime>,
+    message: String,
+  ) -> bool {
+    // the handler is called without holding the windows lock, since it evaluates the response script
+    let handler = self
+      .context
+      .windows
+      .lock()
+      .unwrap()
+      .get(&self.id)
+      .and_then(|window| window.ipc_handler.clone());
+    match handler
+      .as_ref()
+      .and_then(|handler| handler.downcast_ref::<Mutex<WebviewIpcHandler<T, MockRuntime>>>())
+    {
+      Some(handler) => {
+        (handler.lock().unwrap())(window, message);
+        true
+      }
+      None => false,
+    }
+  }
+
+  pub(crate) fn evaluated_scripts(&self) -> Vec<String> {
+    self
+      .context
+      .windows
+      .lock()
+      .unwrap()
+      .get(&self.id)
+      .map(|window| window.evaluated_scripts.clone())
+      .unwrap_or_default()
+  }
+
+  pub(crate) fn emitted_events(&self) -> Vec<EmittedEvent> {
+    self
+      .context
+      .windows
+      .lock()
+      .unwrap()
+      .get(&self.id)
+      .map(|window| window.emitted_events.clone())
+      .unwrap_or_default()
+  }
 }
 
 #[cfg(all(desktop, feature = "global-shortcut"))]
@@ -250,6 +482,10 @@ impl WindowBuilder for MockWindowBuilder {
     self
   }
 
+  fn kiosk(self, kiosk: bool) -> Self {
+    self
+  }
+
   fn icon(self, icon: Icon) -> Result<Self> {
     Ok(self)
   }
@@ -307,11 +543,32 @@ impl<T: UserEvent> Dispatch<T> for MockDispatcher {
   type WindowBuilder = MockWindowBuilder;
 
   fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<()> {
-    Ok(())
+    self.context.run_on_main_thread(Box::new(f))
   }
 
   fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> Uuid {
-    Uuid::new_v4()
+    let id = Uuid::new_v4();
+    if let Some(window) = self.context.windows.lock().unwrap().get(&self.id) {
+      window.listeners.lock().unwrap().insert(id, Box::new(f));
+    }
+    id
+  }
+
+  fn dispatch_drop(&self, event: DropEvent) -> Result<()> {
+    let listeners = self
+      .context
+      .windows
+      .lock()
+      .unwrap()
+      .get(&self.id)
+      .map(|window| window.listeners.clone());
+    if let Some(listeners) = listeners {
+      let event = WindowEvent::Drop(event);
+      for listener in listeners.lock().unwrap().values() {
+        listener(&event);
+      }
+    }
+    Ok(())
   }
 
   fn on_menu_event<F: Fn(&MenuEvent) + Send + 'static>(&self, f: F) -> Uuid {
@@ -329,6 +586,13 @@ impl<T: UserEvent> Dispatch<T> for MockDispatcher {
     Ok(false)
   }
 
+  fn url(&self) -> Result<url::Url> {
+    self
+      .url
+      .parse()
+      .map_err(|_| tauri_runtime::Error::FailedToReceiveMessage)
+  }
+
   fn scale_factor(&self) -> Result<f64> {
     Ok(1.0)
   }
@@ -403,11 +667,14 @@ impl<T: UserEvent> Dispatch<T> for MockDispatcher {
     target_os = "openbsd"
   ))]
   fn gtk_window(&self) -> Result<gtk::ApplicationWindow> {
-    unimplemented!()
+    // the mock windows have no native window
+    Err(Error::CreateWindow)
   }
 
   fn raw_window_handle(&self) -> Result<raw_window_handle::RawWindowHandle> {
-    unimplemented!()
+    Ok(raw_window_handle::RawWindowHandle::Web(
+      raw_window_handle::WebWindowHandle::empty(),
+    ))
   }
 
   fn center(&self) -> Result<()> {
@@ -426,7 +693,7 @@ impl<T: UserEvent> Dispatch<T> for MockDispatcher {
     &mut self,
     pending: PendingWindow<T, Self::Runtime>,
   ) -> Result<DetachedWindow<T, Self::Runtime>> {
-    unimplemented!()
+    self.context.create_window(pending)
   }
 
   fn set_resizable(&self, resizable: bool) -> Result<()> {
@@ -470,7 +737,7 @@ impl<T: UserEvent> Dispatch<T> for MockDispatcher {
   }
 
   fn close(&self) -> Result<()> {
-    Ok(())
+    self.context.send_message(Message::CloseWindow(self.id))
   }
 
   fn set_decorations(&self, decorations: bool) -> Result<()> {
@@ -533,14 +800,36 @@ impl<T: UserEvent> Dispatch<T> for MockDispatcher {
     Ok(())
   }
 
+  fn set_opacity(&self, opacity: f64) -> Result<()> {
+    Ok(())
+  }
+
   fn start_dragging(&self) -> Result<()> {
     Ok(())
   }
 
   fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
+    if let Some(window) = self.context.windows.lock().unwrap().get_mut(&self.id) {
+      window.evaluated_scripts.push(script.into());
+    }
     Ok(())
   }
 
+  fn on_event_emitted(
+    &self,
+    event: &str,
+    source_window_label: Option<&str>,
+    payload: &serde_json::Value,
+  ) {
+    if let Some(window) = self.context.windows.lock().unwrap().get_mut(&self.id) {
+      window.emitted_events.push(EmittedEvent {
+        event: event.into(),
+        source_window_label: source_window_label.map(Into::into),
+        payload: payload.clone(),
+      });
+    }
+  }
+
   fn update_menu_item(&self, id: u16, update: MenuUpdate) -> Result<()> {
     Ok(())
   }
@@ -578,18 +867,30 @@ impl TrayHandle for MockTrayHandler {
   }
 }
 
+/// Sends user events to the mock runtime event loop.
 #[derive(Debug, Clone)]
-pub struct EventProxy {}
+pub struct EventProxy {
+  context: RuntimeContext,
+}
 
 impl<T: UserEvent> EventLoopProxy<T> for EventProxy {
   fn send_event(&self, event: T) -> Result<()> {
-    Ok(())
+    self
+      .context
+      .send_message(Message::UserEvent(Box::new(event)))
   }
 }
 
+/// A runtime that does not create native windows or webviews, so it runs without a display server.
+///
+/// Its event loop delivers the user events, the tasks sent to the main thread and the window events,
+/// and exits when the last window is closed, like the default runtime.
+/// It can be used to test the IPC, state and window management logic of an application on a headless CI environment.
 #[derive(Debug)]
 pub struct MockRuntime {
+  /// The state shared with the runtime handles and window dispatchers.
   pub context: RuntimeContext,
+  rx: Receiver<Message>,
   #[cfg(all(desktop, feature = "global-shortcut"))]
   global_shortcut_manager: MockGlobalShortcutManager,
   #[cfg(feature = "clipboard")]
@@ -600,7 +901,12 @@ pub struct MockRuntime {
 
 impl MockRuntime {
   fn init() -> Self {
+    let (tx, rx) = channel();
     let context = RuntimeContext {
+      is_running: Default::default(),
+      windows: Default::default(),
+      next_window_id: Default::default(),
+      tx: Arc::new(Mutex::new(tx)),
       shortcuts: Default::default(),
       clipboard: Default::default(),
     };
@@ -618,6 +924,7 @@ impl MockRuntime {
         context: context.clone(),
       },
       context,
+      rx,
     }
   }
 }
@@ -643,7 +950,9 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
   }
 
   fn create_proxy(&self) -> EventProxy {
-    unimplemented!()
+    EventProxy {
+      context: self.context.clone(),
+    }
   }
 
   fn handle(&self) -> Self::Handle {
@@ -663,14 +972,7 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
   }
 
   fn create_window(&self, pending: PendingWindow<T, Self>) -> Result<DetachedWindow<T, Self>> {
-    Ok(DetachedWindow {
-      label: pending.label,
-      dispatcher: MockDispatcher {
-        context: self.context.clone(),
-      },
-      menu_ids: Default::default(),
-      js_event_listeners: Default::default(),
-    })
+    self.context.create_window(pending)
   }
 
   #[cfg(all(desktop, feature = "system-tray"))]
@@ -708,12 +1010,27 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
     &mut self,
     callback: F,
   ) -> tauri_runtime::RunIteration {
-    Default::default()
+    let mut callback = |event: RunEvent<T>| callback(event);
+    self.context.is_running.store(true, Ordering::Relaxed);
+    while let Ok(message) = self.rx.try_recv() {
+      self.context.handle_message(message, &mut callback);
+    }
+    callback(RunEvent::MainEventsCleared);
+    tauri_runtime::RunIteration {
+      window_count: self.context.windows.lock().unwrap().len(),
+    }
   }
 
-  fn run<F: FnMut(RunEvent<T>) + 'static>(self, callback: F) {
-    loop {
-      std::thread::sleep(std::time::Duration::from_secs(1));
+  fn run<F: FnMut(RunEvent<T>) + 'static>(self, mut callback: F) {
+    self.context.is_running.store(true, Ordering::Relaxed);
+    callback(RunEvent::Ready);
+    while let Ok(message) = self.rx.recv() {
+      if self.context.handle_message(message, &mut callback) {
+        break;
+      }
+      callback(RunEvent::MainEventsCleared);
     }
+    self.context.is_running.store(false, Ordering::Relaxed);
+    callback(RunEvent::Exit);
   }
 }
@@ -4,21 +4,54 @@
 
 #![allow(unused_variables)]
 
+mod event_recorder;
 mod mock_runtime;
+pub(crate) use event_recorder::RecorderState;
+pub use event_recorder::{EventRecorder, RecordedEvent};
 pub use mock_runtime::*;
 
-#[cfg(shell_scope)]
-use std::collections::HashMap;
-use std::{borrow::Cow, sync::Arc};
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  fmt::Debug,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{sync_channel, RecvTimeoutError, SyncSender},
+    Arc, Mutex,
+  },
+  time::Duration,
+};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
 
 #[cfg(shell_scope)]
 use crate::ShellScopeConfig;
-use crate::{Manager, Pattern};
+use crate::{
+  api::ipc::CallbackFn,
+  runtime::window::{DropEvent, FileDropEvent, WindowEvent},
+  InvokePayload, Manager, Pattern, PhysicalPosition, PhysicalSize, Theme, Window,
+};
 use tauri_utils::{
   assets::{AssetKey, Assets, CspHash},
   config::{CliConfig, Config, PatternKind, TauriConfig},
 };
 
+type IpcResponse = Result<JsonValue, JsonValue>;
+
+#[derive(PartialEq, Eq, Hash)]
+struct IpcKey {
+  callback: usize,
+  error: usize,
+}
+
+/// How long [`invoke`] waits for the command response.
+const INVOKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The pending [`invoke`] calls.
+struct Ipc(Mutex<HashMap<IpcKey, SyncSender<IpcResponse>>>);
+
+/// An empty [`Assets`] implementation.
 pub struct NoopAsset {
   csp_hashes: Vec<CspHash<'static>>,
 }
@@ -33,12 +66,14 @@ impl Assets for NoopAsset {
   }
 }
 
+/// Creates a new empty [`Assets`] implementation.
 pub fn noop_assets() -> NoopAsset {
   NoopAsset {
     csp_hashes: Default::default(),
   }
 }
 
+/// Creates a new [`crate::Context`] for testing, with a single `main` window and the given assets.
 pub fn mock_context<A: Assets>(assets: A) -> crate::Context<A> {
   crate::Context {
     config: Config {
@@ -64,6 +99,8 @@ pub fn mock_context<A: Assets>(assets: A) -> crate::Context<A> {
       },
       build: Default::default(),
       plugins: Default::default(),
+      profiles: Default::default(),
+      extends: None,
     },
     assets: Arc::new(assets),
     default_window_icon: None,
@@ -82,15 +119,228 @@ pub fn mock_context<A: Assets>(assets: A) -> crate::Context<A> {
       open: None,
       scopes: HashMap::new(),
     },
+    permissions_manifest: None,
+    inspect_manifest: None,
   }
 }
 
-pub fn mock_app() -> crate::App<MockRuntime> {
+/// Creates a new [`crate::Builder`] using the [`MockRuntime`].
+///
+/// The builder captures the command responses, so it must be used to create the app given to [`invoke`].
+pub fn mock_builder() -> crate::Builder<MockRuntime> {
   crate::Builder::<MockRuntime>::new()
-    .build(mock_context(noop_assets()))
+    .manage(Ipc(Default::default()))
+    .invoke_system(String::new(), |window, response, callback, error| {
+      let tx = window.state::<Ipc>().0.lock().unwrap().remove(&IpcKey {
+        callback: callback.0,
+        error: error.0,
+      });
+      if let Some(tx) = tx {
+        let _ = tx.send(response.into_result());
+      }
+    })
+}
+
+/// Creates a new [`crate::App`] using the [`MockRuntime`] and the [`mock_context`].
+pub fn mock_app() -> crate::App<MockRuntime> {
+  mock_builder().build(mock_context(noop_assets())).unwrap()
+}
+
+/// A window event sent with [`send_window_event`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum MockWindowEvent {
+  /// The window has been resized.
+  Resized(PhysicalSize<u32>),
+  /// The window has been moved.
+  Moved(PhysicalPosition<i32>),
+  /// The user requested the window to be closed. The window is closed unless a listener prevents it.
+  CloseRequested,
+  /// The window gained or lost focus.
+  Focused(bool),
+  /// The window scale factor has changed.
+  ScaleFactorChanged {
+    /// The new scale factor.
+    scale_factor: f64,
+    /// The window inner size.
+    new_inner_size: PhysicalSize<u32>,
+  },
+  /// Files were dragged or dropped on the window.
+  FileDrop(FileDropEvent),
+  /// Data other than files was dragged or dropped on the webview.
+  Drop(DropEvent),
+  /// The system theme has changed.
+  ThemeChanged(Theme),
+}
+
+/// Sends an event to the window, as if it was triggered by the user or the system.
+///
+/// The window event listeners are called on the next event loop iteration,
+/// so either call [`crate::App::run_iteration`] or run the app after sending the event.
+pub fn send_window_event(
+  window: &Window<MockRuntime>,
+  event: MockWindowEvent,
+) -> crate::Result<()> {
+  let event = match event {
+    MockWindowEvent::Resized(size) => WindowEvent::Resized(size),
+    MockWindowEvent::Moved(position) => WindowEvent::Moved(position),
+    MockWindowEvent::CloseRequested => WindowEvent::CloseRequested {
+      signal_tx: std::sync::mpsc::channel().0,
+    },
+    MockWindowEvent::Focused(focused) => WindowEvent::Focused(focused),
+    MockWindowEvent::ScaleFactorChanged {
+      scale_factor,
+      new_inner_size,
+    } => WindowEvent::ScaleFactorChanged {
+      scale_factor,
+      new_inner_size,
+    },
+    MockWindowEvent::FileDrop(event) => WindowEvent::FileDrop(event),
+    MockWindowEvent::Drop(event) => WindowEvent::Drop(event),
+    MockWindowEvent::ThemeChanged(theme) => WindowEvent::ThemeChanged(theme),
+  };
+  window
+    .window
+    .dispatcher
+    .send_window_event(event)
+    .map_err(Into::into)
+}
+
+/// Simulates a frontend `invoke` call to the command `cmd` with the given arguments from the window,
+/// and waits for the command response.
+///
+/// The app must be created with [`mock_builder`].
+///
+/// # Panics
+///
+/// Panics if the command is dropped without a response or does not respond within 10 seconds,
+/// e.g. when it waits on the event loop, which is not running.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// #[tauri::command]
+/// fn greet(name: String) -> String {
+///   format!("Hello, {}!", name)
+/// }
+///
+/// let app = tauri::test::mock_builder()
+///   .invoke_handler(tauri::generate_handler![greet])
+///   .build(tauri::test::mock_context(tauri::test::noop_assets()))
+///   .unwrap();
+/// let window = app.get_window("main").unwrap();
+/// assert_eq!(
+///   tauri::test::invoke(&window, "greet", serde_json::json!({ "name": "Tauri" })),
+///   Ok(serde_json::json!("Hello, Tauri!"))
+/// );
+/// ```
+pub fn invoke(window: &Window<MockRuntime>, cmd: &str, args: JsonValue) -> IpcResponse {
+  static NEXT_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+  let callback = NEXT_CALLBACK.fetch_add(2, Ordering::Relaxed);
+  let error = callback + 1;
+
+  let (tx, rx) = sync_channel(1);
+  window
+    .try_state::<Ipc>()
+    .expect("the app must be created with `tauri::test::mock_builder`")
+    .0
+    .lock()
     .unwrap()
+    .insert(IpcKey { callback, error }, tx);
+
+  window
+    .clone()
+    .on_message(InvokePayload {
+      cmd: cmd.into(),
+      tauri_module: None,
+      callback: CallbackFn(callback),
+      error: CallbackFn(error),
+      inner: args,
+    })
+    .map_err(|e| JsonValue::String(e.to_string()))?;
+
+  match rx.recv_timeout(INVOKE_TIMEOUT) {
+    Ok(response) => response,
+    Err(RecvTimeoutError::Timeout) => panic!(
+      "the command `{}` did not respond within {} seconds",
+      cmd,
+      INVOKE_TIMEOUT.as_secs()
+    ),
+    Err(RecvTimeoutError::Disconnected) => panic!("the command was dropped without a response"),
+  }
+}
+
+/// Asserts that invoking the command `cmd` from the window resolves or rejects with the expected value.
+pub fn assert_ipc_response<T: Serialize + Debug>(
+  window: &Window<MockRuntime>,
+  cmd: &str,
+  args: JsonValue,
+  expected: Result<T, T>,
+) {
+  let expected = match expected {
+    Ok(value) => Ok(serde_json::to_value(value).unwrap()),
+    Err(value) => Err(serde_json::to_value(value).unwrap()),
+  };
+  assert_eq!(invoke(window, cmd, args), expected);
 }
 
+/// Posts a raw message to the IPC handler of the window's webview, like the frontend `invoke` function does.
+///
+/// Unlike [`invoke`], the message goes through the deserialization of the IPC handler and the response is
+/// delivered by the invoke responder of the app, so the response callback is evaluated on the webview,
+/// see [`evaluated_scripts`].
+///
+/// # Panics
+///
+/// Panics if the window has no IPC handler, which is the case for remote URLs.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let app = tauri::Builder::<tauri::test::MockRuntime>::new()
+///   .invoke_handler(tauri::generate_handler![greet])
+///   .build(tauri::test::mock_context(tauri::test::noop_assets()))
+///   .unwrap();
+/// let window = app.get_window("main").unwrap();
+/// tauri::test::post_ipc_message(
+///   &window,
+///   r#"{ "cmd": "greet", "callback": 1, "error": 2, "name": "Tauri" }"#,
+/// );
+/// assert!(tauri::test::evaluated_scripts(&window)
+///   .iter()
+///   .any(|script| script.contains("Hello, Tauri!")));
+/// ```
+pub fn post_ipc_message(window: &Window<MockRuntime>, message: &str) {
+  let posted = window
+    .window
+    .dispatcher
+    .post_ipc_message(window.window.clone(), message.into());
+  assert!(posted, "the window `{}` has no IPC handler", window.label());
+}
+
+/// The scripts evaluated on the webview of the window in evaluation order,
+/// such as the command response callbacks and the event listeners.
+pub fn evaluated_scripts(window: &Window<MockRuntime>) -> Vec<String> {
+  window.window.dispatcher.evaluated_scripts()
+}
+
+/// Registers a JavaScript listener for the event on the window, like the frontend `listen` function,
+/// so the event is delivered to the window and recorded in [`emitted_events`].
+pub fn listen_js(window: &Window<MockRuntime>, event: &str) {
+  static NEXT_LISTENER: AtomicUsize = AtomicUsize::new(0);
+  window.register_js_listener(
+    None,
+    event.into(),
+    NEXT_LISTENER.fetch_add(1, Ordering::Relaxed) as u64,
+  );
+}
+
+/// The events delivered to the JavaScript listeners of the window, in emission order.
+pub fn emitted_events(window: &Window<MockRuntime>) -> Vec<EmittedEvent> {
+  window.window.dispatcher.emitted_events()
+}
+
+#[cfg(test)]
 pub(crate) fn mock_invoke_context() -> crate::endpoints::InvokeContext<MockRuntime> {
   let app = mock_app();
   crate::endpoints::InvokeContext {
@@ -99,3 +349,158 @@ pub(crate) fn mock_invoke_context() -> crate::endpoints::InvokeContext<MockRunti
     package_info: app.package_info().clone(),
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use serde_json::json;
+
+  use super::{EmittedEvent, MockWindowEvent};
+  use crate::{Manager, PhysicalSize, RunEvent, WindowEvent};
+
+  #[test]
+  fn invoke() {
+    let app = super::mock_builder()
+      .invoke_handler(|invoke| match invoke.message.command() {
+        "ping" => invoke.resolver.resolve("pong"),
+        command => invoke
+          .resolver
+          .reject(format!("unknown command {}", command)),
+      })
+      .build(super::mock_context(super::noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+
+    super::assert_ipc_response(&window, "ping", json!({}), Ok("pong"));
+    super::assert_ipc_response(&window, "pong", json!({}), Err("unknown command pong"));
+  }
+
+  #[test]
+  fn ipc_message() {
+    use crate::api::ipc::{format_callback_result, CallbackFn};
+
+    let app = crate::Builder::<super::MockRuntime>::new()
+      .invoke_handler(|invoke| match invoke.message.command() {
+        "ping" => invoke.resolver.resolve(invoke.message.payload().clone()),
+        command => invoke
+          .resolver
+          .reject(format!("unknown command {}", command)),
+      })
+      .build(super::mock_context(super::noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+
+    super::post_ipc_message(
+      &window,
+      r#"{ "cmd": "ping", "callback": 1, "error": 2, "value": [1, "two"] }"#,
+    );
+    super::post_ipc_message(&window, r#"{ "cmd": "pong", "callback": 3, "error": 4 }"#);
+    // not an invoke payload
+    super::post_ipc_message(&window, r#"{ "cmd": "ping" }"#);
+
+    let scripts = super::evaluated_scripts(&window);
+    assert_eq!(scripts.len(), 3);
+    assert_eq!(
+      scripts[0],
+      format_callback_result::<_, ()>(
+        Ok(json!({ "value": [1, "two"] })),
+        CallbackFn(1),
+        CallbackFn(2)
+      )
+      .unwrap()
+    );
+    assert_eq!(
+      scripts[1],
+      format_callback_result::<(), _>(Err("unknown command pong"), CallbackFn(3), CallbackFn(4))
+        .unwrap()
+    );
+    assert!(scripts[2].starts_with("console.error("));
+  }
+
+  #[test]
+  fn window_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_ = events.clone();
+    let mut app = super::mock_builder()
+      .on_window_event(move |event| {
+        events_.lock().unwrap().push(match event.event() {
+          WindowEvent::Resized(size) => format!("resized {}x{}", size.width, size.height),
+          WindowEvent::CloseRequested { .. } => "close-requested".into(),
+          WindowEvent::Destroyed => "destroyed".into(),
+          _ => "other".into(),
+        })
+      })
+      .build(super::mock_context(super::noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+
+    super::send_window_event(
+      &window,
+      MockWindowEvent::Resized(PhysicalSize {
+        width: 800,
+        height: 600,
+      }),
+    )
+    .unwrap();
+    super::send_window_event(&window, MockWindowEvent::CloseRequested).unwrap();
+    assert!(events.lock().unwrap().is_empty());
+
+    app.run_iteration();
+    assert_eq!(
+      *events.lock().unwrap(),
+      vec!["resized 800x600", "close-requested", "destroyed"]
+    );
+    assert!(app.get_window("main").is_none());
+  }
+
+  #[test]
+  fn emitted_events() {
+    let app = super::mock_app();
+    let window = app.get_window("main").unwrap();
+
+    // events are only delivered to windows listening to them
+    window.emit("progress", 10).unwrap();
+    assert!(super::emitted_events(&window).is_empty());
+
+    super::listen_js(&window, "progress");
+    app.emit_all("progress", 50).unwrap();
+    assert_eq!(
+      super::emitted_events(&window),
+      vec![EmittedEvent {
+        event: "progress".into(),
+        source_window_label: None,
+        payload: json!(50),
+      }]
+    );
+  }
+
+  #[test]
+  fn run_exits_after_last_window_closes() {
+    let app = super::mock_app();
+    let window = app.get_window("main").unwrap();
+    window.close().unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_ = events.clone();
+    app.run(move |_app, event| {
+      let event = match event {
+        RunEvent::Ready => "ready",
+        RunEvent::WindowEvent {
+          label,
+          event: WindowEvent::Destroyed,
+          ..
+        } if label == "main" => "destroyed",
+        RunEvent::ExitRequested { .. } => "exit-requested",
+        RunEvent::Exit => "exit",
+        _ => return,
+      };
+      events_.lock().unwrap().push(event);
+    });
+
+    assert_eq!(
+      *events.lock().unwrap(),
+      vec!["ready", "destroyed", "exit-requested", "exit"]
+    );
+  }
+}
@@ -550,6 +550,24 @@ fn send_status_update<R: Runtime>(handle: &AppHandle<R>, message: UpdaterEvent)
     .send_event(EventLoopMessage::Updater(message));
 }
 
+/// Translates the built-in dialog strings with the translations configured on `tauri > i18n`.
+#[allow(unused_variables)]
+fn localized<R: Runtime>(
+  app: &AppHandle<R>,
+  key: &str,
+  args: &[(&str, serde_json::Value)],
+  default: impl FnOnce() -> String,
+) -> String {
+  #[cfg(feature = "i18n")]
+  if let Some(message) = app
+    .try_state::<crate::I18n>()
+    .and_then(|i18n| i18n.try_translate(key, args))
+  {
+    return message;
+  }
+  default()
+}
+
 // Prompt a dialog asking if the user want to install the new version
 // Maybe we should add an option to customize it in future versions.
 async fn prompt_for_install<R: Runtime>(
@@ -565,15 +583,32 @@ async fn prompt_for_install<R: Runtime>(
   // something more conventional.
   let should_install = ask(
     parent_window,
-    format!(r#"A new version of {} is available! "#, app_name),
-    format!(
-      r#"{} {} is now available -- you have {}.
+    localized(
+      &update.app,
+      "tauri-updater-title",
+      &[("appName", app_name.into())],
+      || format!(r#"A new version of {} is available! "#, app_name),
+    ),
+    localized(
+      &update.app,
+      "tauri-updater-body",
+      &[
+        ("appName", app_name.into()),
+        ("version", update.version.clone().into()),
+        ("currentVersion", update.current_version.to_string().into()),
+        ("notes", body.into()),
+      ],
+      || {
+        format!(
+          r#"{} {} is now available -- you have {}.
 
 Would you like to install it now?
 
 Release Notes:
 {}"#,
-      app_name, update.version, update.current_version, body,
+          app_name, update.version, update.current_version, body,
+        )
+      },
     ),
   );
 
@@ -589,8 +624,12 @@ Release Notes:
     // Ask user if we need to restart the application
     let should_exit = ask(
       parent_window,
-      "Ready to Restart",
-      "The installation was successful, do you want to restart the application now?",
+      localized(&update.app, "tauri-updater-restart-title", &[], || {
+        "Ready to Restart".into()
+      }),
+      localized(&update.app, "tauri-updater-restart-body", &[], || {
+        "The installation was successful, do you want to restart the application now?".into()
+      }),
     );
     if should_exit {
       update.app.restart();
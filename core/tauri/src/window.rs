@@ -5,8 +5,12 @@
 //! The Tauri window types and functions.
 
 pub(crate) mod menu;
+#[cfg(desktop)]
+mod thumbnail;
 
 pub use menu::{MenuEvent, MenuHandle};
+#[cfg(desktop)]
+pub use thumbnail::RgbaImage;
 
 #[cfg(target_os = "macos")]
 use crate::TitleBarStyle;
@@ -14,7 +18,9 @@ use crate::{
   app::AppHandle,
   command::{CommandArg, CommandItem},
   event::{Event, EventHandler},
-  hooks::{InvokePayload, InvokeResponder},
+  hooks::{
+    InvokeArgs, InvokeMeasurement, InvokeMetrics, InvokePayload, InvokeResponder, InvokeSerializer,
+  },
   manager::WindowManager,
   runtime::{
     http::{Request as HttpRequest, Response as HttpResponse},
@@ -23,18 +29,19 @@ use crate::{
     webview::{WebviewAttributes, WindowBuilder as _},
     window::{
       dpi::{PhysicalPosition, PhysicalSize, Position, Size},
-      DetachedWindow, JsEventListenerKey, PendingWindow,
+      DetachedWindow, DropEvent, JsEventListenerKey, PendingWindow,
     },
     Dispatch, RuntimeHandle, UserAttentionType,
   },
   sealed::ManagerBase,
   sealed::RuntimeOrDispatch,
-  utils::config::WindowUrl,
+  utils::config::{Csp, WindowUrl},
   CursorIcon, EventLoopMessage, Icon, Invoke, InvokeError, InvokeMessage, InvokeResolver, Manager,
   PageLoadPayload, Runtime, Theme, WindowEvent,
 };
 
 use serde::Serialize;
+use url::Url;
 #[cfg(windows)]
 use windows::Win32::Foundation::HWND;
 
@@ -108,6 +115,7 @@ pub struct WindowBuilder<'a, R: Runtime> {
   pub(crate) window_builder: <R::Dispatcher as Dispatch<EventLoopMessage>>::WindowBuilder,
   pub(crate) webview_attributes: WebviewAttributes,
   web_resource_request_handler: Option<Box<WebResourceRequestHandler>>,
+  group: Option<String>,
 }
 
 impl<'a, R: Runtime> fmt::Debug for WindowBuilder<'a, R> {
@@ -118,6 +126,7 @@ impl<'a, R: Runtime> fmt::Debug for WindowBuilder<'a, R> {
       .field("label", &self.label)
       .field("window_builder", &self.window_builder)
       .field("webview_attributes", &self.webview_attributes)
+      .field("group", &self.group)
       .finish()
   }
 }
@@ -125,10 +134,13 @@ impl<'a, R: Runtime> fmt::Debug for WindowBuilder<'a, R> {
 impl<'a, R: Runtime> WindowBuilder<'a, R> {
   /// Initializes a webview window builder with the given window label and URL to load on the webview.
   ///
-  /// # Known issues
+  /// Windows can be created from any thread. When called from another thread, the window is created on the event loop
+  /// and the returned [`Window`] can be used right away.
   ///
-  /// On Windows, this function deadlocks when used in a synchronous command, see [the Webview2 issue].
-  /// You should use `async` commands when creating windows.
+  /// When called in a synchronous command, the window is created once the command returns,
+  /// since WebView2 deadlocks when a webview is created while handling an IPC message (see [the Webview2 issue]).
+  /// Until then the methods that query the window fail with [`crate::Error::Runtime`],
+  /// while the other methods are applied after the window is created.
   ///
   /// # Examples
   ///
@@ -162,7 +174,7 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
   ///
   /// ```
   /// #[tauri::command]
-  /// async fn create_window(app: tauri::AppHandle) {
+  /// fn create_window(app: tauri::AppHandle) {
   ///   let window = tauri::WindowBuilder::new(&app, "label", tauri::WindowUrl::External("https://tauri.app/".parse().unwrap()))
   ///     .build()
   ///     .unwrap();
@@ -181,6 +193,7 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
       window_builder: <R::Dispatcher as Dispatch<EventLoopMessage>>::WindowBuilder::new(),
       webview_attributes: WebviewAttributes::new(url),
       web_resource_request_handler: None,
+      group: None,
     }
   }
 
@@ -229,6 +242,13 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
     self
   }
 
+  /// Adds the window to a group, see [`Window::set_group`].
+  #[must_use]
+  pub fn group(mut self, group: impl Into<String>) -> Self {
+    self.group.replace(group.into());
+    self
+  }
+
   /// Creates a new webview window.
   pub fn build(mut self) -> crate::Result<Window<R>> {
     let web_resource_request_handler = self.web_resource_request_handler.take();
@@ -251,6 +271,10 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
     }
     .map(|window| self.manager.attach_window(self.app_handle.clone(), window))?;
 
+    if let Some(group) = self.group.take() {
+      self.manager.set_window_group(window.label(), Some(group));
+    }
+
     self.manager.emit_filter(
       "tauri://window-created",
       None,
@@ -398,6 +422,30 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
     self
   }
 
+  /// Runs the window in kiosk mode, for point-of-sale and signage deployments.
+  ///
+  /// The window is fullscreen, always on top and without decorations, the context menu of the webview is disabled,
+  /// and the system shortcuts switching or closing the app are blocked while the window is open.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Uses exclusive fullscreen, blocking Alt+Tab, Alt+F4, Alt+Esc, Ctrl+Esc and the Windows keys
+  /// while the window is focused. Ctrl+Alt+Del cannot be blocked.
+  /// - **macOS:** The window covers the screen instead of using a fullscreen space,
+  /// hiding the Dock and the menu bar and disabling the process switching, Force Quit and the Apple menu.
+  /// - **Linux:** The system shortcuts are not blocked.
+  #[must_use]
+  pub fn kiosk(mut self, kiosk: bool) -> Self {
+    self.window_builder = self.window_builder.kiosk(kiosk);
+    if kiosk {
+      self
+        .webview_attributes
+        .initialization_scripts
+        .push(KIOSK_SCRIPT.into());
+    }
+    self
+  }
+
   /// Sets the window icon.
   pub fn icon(mut self, icon: Icon) -> crate::Result<Self> {
     self.window_builder = self.window_builder.icon(icon.try_into()?)?;
@@ -543,6 +591,29 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
     self
   }
 
+  /// Accepts drops of data other than files on the webview, such as text and URLs dragged from browsers and other apps.
+  ///
+  /// The drags carrying any of the given MIME types are accepted while hovering the webview, unless the page handles them.
+  /// They are emitted as [`WindowEvent::Drop`] and the `tauri://drop-hover` event when they enter the webview,
+  /// and their data as the `tauri://drop` event when they are dropped.
+  /// Plugins can refuse them with [`crate::plugin::Builder::on_data_drop`].
+  /// A type can match a group of types, e.g. `text/*`, or any type with `*`.
+  ///
+  /// The drops are reported by a script on the page, so their data must be treated like any other input from the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The file drop handler must be disabled with [`Self::disable_file_drop_handler`].
+  #[must_use]
+  pub fn accept_drop_types<I, S>(mut self, types: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.webview_attributes.accept_drop_types = types.into_iter().map(Into::into).collect();
+    self
+  }
+
   /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
   ///
   /// **macOS** doesn't provide such method and is always enabled by default,
@@ -559,6 +630,93 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
     self.webview_attributes.accept_first_mouse = accept;
     self
   }
+
+  /// Sets the Content Security Policy of the HTML files served to this window,
+  /// overriding the `tauri > security > csp` and `tauri > security > devCsp` configuration values.
+  ///
+  /// The nonces of the inline scripts and styles are injected on every load, as with the app policy.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{utils::config::{Csp, WindowUrl}, WindowBuilder};
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     WindowBuilder::new(app, "preview", WindowUrl::App("preview.html".into()))
+  ///       .csp(Csp::Policy("default-src 'self'; img-src 'self' https:".into()))
+  ///       .build()?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn csp(mut self, csp: Csp) -> Self {
+    self.webview_attributes.csp.replace(csp);
+    self
+  }
+
+  /// Sets whether the webview renders with the GPU, which renders black windows with some drivers.
+  ///
+  /// The `TAURI_WEBVIEW_HARDWARE_ACCELERATION` environment variable set to `true` or `false`
+  /// overrides this value on all windows, so users can work around their drivers without a new build.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The windows sharing a data directory must use the same GPU settings.
+  /// - **macOS:** Unsupported.
+  #[must_use]
+  pub fn hardware_acceleration(mut self, enabled: bool) -> Self {
+    self.webview_attributes.hardware_acceleration = enabled;
+    self
+  }
+
+  /// Sets whether the webview composites its layers with the GPU.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The windows sharing a data directory must use the same GPU settings.
+  /// - **Linux:** Disabling it also disables the hardware acceleration.
+  /// - **macOS:** Unsupported.
+  #[must_use]
+  pub fn gpu_compositing(mut self, enabled: bool) -> Self {
+    self.webview_attributes.gpu_compositing = enabled;
+    self
+  }
+
+  /// Sets whether WebGL is available to the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The windows sharing a data directory must use the same GPU settings.
+  /// - **macOS:** Unsupported.
+  #[must_use]
+  pub fn webgl(mut self, enabled: bool) -> Self {
+    self.webview_attributes.webgl = enabled;
+    self
+  }
+}
+
+/// The script disabling the context menu of the webview on kiosk windows.
+pub(crate) const KIOSK_SCRIPT: &str =
+  "window.addEventListener('contextmenu', function (e) { e.preventDefault() }, true)";
+
+/// The script accepting the drops of the given MIME types on the webview.
+pub(crate) fn drop_script(types: &[String]) -> String {
+  format!(
+    "{}({})",
+    include_str!("../scripts/drop.js").trim_end(),
+    serde_json::to_string(types).unwrap()
+  )
+}
+
+/// Whether the MIME type matches one of the accepted types, the same way the drop script does.
+pub(crate) fn accepts_drop_type(accepted: &[String], mime_type: &str) -> bool {
+  accepted.iter().any(|accepted| {
+    accepted == "*"
+      || accepted == mime_type
+      || accepted.strip_suffix('*').map_or(false, |prefix| {
+        prefix.ends_with('/') && mime_type.starts_with(prefix)
+      })
+  })
 }
 
 // TODO: expand these docs since this is a pretty important type
@@ -570,7 +728,7 @@ impl<'a, R: Runtime> WindowBuilder<'a, R> {
 #[derive(Debug)]
 pub struct Window<R: Runtime> {
   /// The webview window created by the runtime.
-  window: DetachedWindow<EventLoopMessage, R>,
+  pub(crate) window: DetachedWindow<EventLoopMessage, R>,
   /// The manager to associate this webview window with.
   manager: WindowManager<R>,
   pub(crate) app_handle: AppHandle<R>,
@@ -624,6 +782,20 @@ impl<R: Runtime> Manager<R> for Window<R> {
       .manager()
       .emit_filter(event, Some(self.label()), payload, |_| true)
   }
+
+  fn emit_to_group<S: Serialize + Clone>(
+    &self,
+    group: &str,
+    event: &str,
+    payload: S,
+  ) -> crate::Result<()> {
+    let labels = self.manager().window_labels_in_group(group);
+    self
+      .manager()
+      .emit_filter(event, Some(self.label()), payload, |w| {
+        labels.contains(w.label())
+      })
+  }
 }
 impl<R: Runtime> ManagerBase<R> for Window<R> {
   fn manager(&self) -> &WindowManager<R> {
@@ -802,6 +974,14 @@ impl<R: Runtime> Window<R> {
     self.manager.invoke_responder()
   }
 
+  pub(crate) fn invoke_serializer(&self) -> Option<Arc<dyn InvokeSerializer>> {
+    self.manager.invoke_serializer()
+  }
+
+  pub(crate) fn run_on_invoke_metrics(&self, metrics: &InvokeMetrics) {
+    self.manager.run_on_invoke_metrics(self, metrics)
+  }
+
   /// The current window's dispatcher.
   pub(crate) fn dispatcher(&self) -> R::Dispatcher {
     self.window.dispatcher.clone()
@@ -821,6 +1001,45 @@ impl<R: Runtime> Window<R> {
     &self.window.label
   }
 
+  /// Adds the window to a group, removing it from its previous group.
+  ///
+  /// Groups address a subset of the windows, such as one window per document,
+  /// with [`Manager::emit_to_group`] and [`Manager::windows_in_group`].
+  /// The window leaves its group when it is closed.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::Manager;
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let window = app.get_window("main").unwrap();
+  ///     window.set_group("editors");
+  ///     assert_eq!(app.windows_in_group("editors").len(), 1);
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn set_group(&self, group: impl Into<String>) {
+    self
+      .manager
+      .set_window_group(self.label(), Some(group.into()))
+  }
+
+  /// Removes the window from its group.
+  pub fn remove_group(&self) {
+    self.manager.set_window_group(self.label(), None)
+  }
+
+  /// The group of the window.
+  pub fn group(&self) -> Option<String> {
+    self.manager.window_group(self.label())
+  }
+
+  /// Handles a drag and drop event reported by the drop script of the window.
+  pub(crate) fn handle_drop(&self, event: DropEvent) -> crate::Result<()> {
+    self.manager.on_drop(self, event)
+  }
+
   /// Registers a window event listener.
   pub fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) {
     self
@@ -854,6 +1073,11 @@ impl<R: Runtime> Window<R> {
     }
   }
 
+  /// Returns the URL currently loaded on the webview.
+  pub fn url(&self) -> crate::Result<Url> {
+    self.window.dispatcher.url().map_err(Into::into)
+  }
+
   /// Returns the scale factor that can be used to map logical pixels to physical pixels, and vice versa.
   pub fn scale_factor(&self) -> crate::Result<f64> {
     self.window.dispatcher.scale_factor().map_err(Into::into)
@@ -990,6 +1214,30 @@ impl<R: Runtime> Window<R> {
     self.window.dispatcher.gtk_window().map_err(Into::into)
   }
 
+  /// Captures the window contents, scaled down to fit `max_size` keeping the aspect ratio,
+  /// e.g. to show the windows of the app on a custom window switcher.
+  ///
+  /// The window is captured at its physical resolution, so the thumbnail can be zoomed
+  /// with [`RgbaImage::resize`] up to that size without capturing it again.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Captured with `PrintWindow`, rendering the DWM composition of the window. Minimized windows cannot be captured.
+  /// - **macOS:** Captured with `CGWindowListCreateImage`.
+  /// - **Linux:** Captured from the GDK window, which must be visible.
+  #[cfg(desktop)]
+  pub fn thumbnail(&self, max_size: PhysicalSize<u32>) -> crate::Result<RgbaImage> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let window = self.clone();
+    self.run_on_main_thread(move || {
+      let _ = tx.send(thumbnail::capture(&window));
+    })?;
+    let image = rx
+      .recv()
+      .map_err(|_| crate::runtime::Error::FailedToReceiveMessage)??;
+    Ok(image.resize(max_size))
+  }
+
   /// Returns the current window theme.
   ///
   /// ## Platform-specific
@@ -1187,6 +1435,15 @@ impl<R: Runtime> Window<R> {
       .map_err(Into::into)
   }
 
+  /// Updates the opacity of the window, used by the splashscreen transitions.
+  pub(crate) fn set_opacity(&self, opacity: f64) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .set_opacity(opacity)
+      .map_err(Into::into)
+  }
+
   /// Grabs the cursor, preventing it from leaving the window.
   ///
   /// There's no guarantee that the cursor will be hidden. You should
@@ -1258,20 +1515,62 @@ impl<R: Runtime> Window<R> {
 impl<R: Runtime> Window<R> {
   /// Handles this window receiving an [`InvokeMessage`].
   pub fn on_message(self, payload: InvokePayload) -> crate::Result<()> {
+    let (payload, args) = InvokeArgs::split(payload);
+    self.on_invoke(payload, args)
+  }
+
+  /// Handles this window receiving an invoke request with the given arguments.
+  pub(crate) fn on_invoke(self, payload: InvokePayload, args: InvokeArgs) -> crate::Result<()> {
     let manager = self.manager.clone();
+
+    if !manager.is_ipc_origin_valid(&self) {
+      if payload.cmd != "__initialized" {
+        InvokeResolver::new(self, payload.callback, payload.error)
+          .reject("IPC messages from this origin are not allowed");
+      }
+      return Ok(());
+    }
+
     match payload.cmd.as_str() {
       "__initialized" => {
-        let payload: PageLoadPayload = serde_json::from_value(payload.inner)?;
+        let payload: PageLoadPayload = serde_json::from_value(args.into_json())?;
         manager.run_on_page_load(self, payload);
       }
       _ => {
-        let message = InvokeMessage::new(
-          self.clone(),
-          manager.state(),
-          payload.cmd.to_string(),
-          payload.inner,
-        );
-        let resolver = InvokeResolver::new(self, payload.callback, payload.error);
+        let command = match &payload.tauri_module {
+          Some(module) => format!("tauri:{}", module.to_lowercase()),
+          None => payload.cmd.clone(),
+        };
+        let scope = self.command_scope();
+        if !scope.is_empty() {
+          let allowed = self
+            .url()
+            .map(|url| scope.is_allowed(self.label(), &url, &command))
+            .unwrap_or(false);
+          if !allowed {
+            let resolver = InvokeResolver::new(self, payload.callback, payload.error);
+            resolver.reject(format!(
+              "command {} not allowed by the command scope",
+              command
+            ));
+            return Ok(());
+          }
+        }
+
+        let measurement = if manager.measures_invokes() {
+          Some(InvokeMeasurement::new(command, self.label(), args.size()))
+        } else {
+          None
+        };
+
+        let message =
+          InvokeMessage::new(self.clone(), manager.state(), payload.cmd.to_string(), args);
+        let mut resolver = InvokeResolver::new(self, payload.callback, payload.error);
+        if let Some(measurement) = measurement {
+          resolver = resolver.measure(measurement);
+        }
+        #[cfg(feature = "ipc-tracing")]
+        let _span = resolver.span().cloned().map(|span| span.entered());
 
         let invoke = Invoke { message, resolver };
         if let Some(module) = &payload.tauri_module {
@@ -1455,13 +1754,18 @@ impl<R: Runtime> Window<R> {
     source_window_label: Option<&str>,
     payload: S,
   ) -> crate::Result<()> {
+    let payload = serde_json::to_value(payload)?;
     self.eval(&format!(
       "window['{}']({{event: {}, windowLabel: {}, payload: {}}})",
       self.manager.event_emit_function_name(),
       serde_json::to_string(event)?,
       serde_json::to_string(&source_window_label)?,
-      serde_json::to_value(payload)?,
+      payload,
     ))?;
+    self
+      .window
+      .dispatcher
+      .on_event_emitted(event, source_window_label, &payload);
     Ok(())
   }
 
@@ -1520,4 +1824,163 @@ mod tests {
     crate::test_utils::assert_send::<super::Window>();
     crate::test_utils::assert_sync::<super::Window>();
   }
+
+  #[test]
+  fn ipc_origin_validator() {
+    use crate::{
+      test::{assert_ipc_response, mock_builder, mock_context, noop_assets},
+      Manager,
+    };
+    use serde_json::json;
+
+    let app = mock_builder()
+      .invoke_handler(|invoke| invoke.resolver.resolve("pong"))
+      .ipc_origin_validator(|url, window| url == &window.url())
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+    assert_ipc_response(&window, "ping", json!({}), Ok("pong"));
+
+    let app = mock_builder()
+      .invoke_handler(|invoke| invoke.resolver.resolve("pong"))
+      .ipc_origin_validator(|_, _| false)
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+    assert_ipc_response(
+      &window,
+      "ping",
+      json!({}),
+      Err("IPC messages from this origin are not allowed"),
+    );
+  }
+
+  #[test]
+  fn window_groups() {
+    use crate::{test::EventRecorder, Manager};
+
+    let app = crate::test::mock_app();
+    let recorder = EventRecorder::new(&app);
+    let window = app.get_window("main").unwrap();
+
+    window.set_group("editors");
+    assert_eq!(window.group().as_deref(), Some("editors"));
+    assert!(app.windows_in_group("editors").contains_key("main"));
+    app.emit_to_group("editors", "saved", ()).unwrap();
+    app.emit_to_group("viewers", "saved", ()).unwrap();
+
+    window.remove_group();
+    assert_eq!(window.group(), None);
+    assert!(app.windows_in_group("editors").is_empty());
+
+    let windows: Vec<Vec<String>> = recorder.events().into_iter().map(|e| e.windows).collect();
+    assert_eq!(windows, vec![vec!["main".to_string()], vec![]]);
+  }
+
+  #[test]
+  fn kiosk_script() {
+    let app = crate::test::mock_app();
+    let builder = crate::WindowBuilder::new(&app, "kiosk", Default::default());
+    assert!(!builder
+      .webview_attributes
+      .initialization_scripts
+      .iter()
+      .any(|script| script == super::KIOSK_SCRIPT));
+    let builder = builder.kiosk(true);
+    assert!(builder
+      .webview_attributes
+      .initialization_scripts
+      .iter()
+      .any(|script| script == super::KIOSK_SCRIPT));
+  }
+
+  #[test]
+  fn drop_data() {
+    use crate::{test::EventRecorder, DropData, DropEvent, Manager, WindowEvent};
+    use std::sync::{Arc, Mutex};
+
+    let app = crate::test::mock_app();
+    let recorder = EventRecorder::new(&app);
+    let window = crate::WindowBuilder::new(&app, "drop", Default::default())
+      .accept_drop_types(vec!["text/*".into()])
+      .build()
+      .unwrap();
+    let dropped = Arc::new(Mutex::new(None));
+    let dropped_ = dropped.clone();
+    window.on_window_event(move |event| {
+      if let WindowEvent::Drop(DropEvent::Dropped(data)) = event {
+        dropped_.lock().unwrap().replace(data.clone());
+      }
+    });
+
+    let mut data = DropData::default();
+    data.items.insert(
+      "text/uri-list".into(),
+      "# dragged links\r\nhttps://tauri.app\r\nhttps://github.com/tauri-apps\r\n".into(),
+    );
+    data
+      .items
+      .insert("application/x-secret".into(), "secret".into());
+    window
+      .handle_drop(DropEvent::Dropped(data.clone()))
+      .unwrap();
+
+    let dropped = dropped.lock().unwrap().take().unwrap();
+    assert_eq!(
+      dropped.urls(),
+      vec!["https://tauri.app", "https://github.com/tauri-apps"]
+    );
+    assert_eq!(dropped.text(), None);
+    // the types the window does not accept are removed
+    assert_eq!(dropped.get("application/x-secret"), None);
+    assert!(recorder
+      .events()
+      .iter()
+      .any(|e| e.event == "tauri://drop" && e.payload["items"]["text/uri-list"].is_string()));
+
+    // windows that do not accept drops reject them
+    let main = app.get_window("main").unwrap();
+    assert!(matches!(
+      main.handle_drop(DropEvent::Dropped(data)),
+      Err(crate::Error::DropNotAccepted)
+    ));
+    // and so do windows accepting none of the dragged types
+    assert!(matches!(
+      window.handle_drop(DropEvent::Hovered {
+        position: Default::default(),
+        types: vec!["application/x-secret".into()],
+      }),
+      Err(crate::Error::DropNotAccepted)
+    ));
+  }
+
+  #[test]
+  fn accepts_drop_type() {
+    use super::accepts_drop_type;
+
+    let accepted = ["text/*".to_string(), "application/json".to_string()];
+    assert!(accepts_drop_type(&accepted, "text/plain"));
+    assert!(accepts_drop_type(&accepted, "application/json"));
+    assert!(!accepts_drop_type(&accepted, "application/jsonp"));
+    assert!(!accepts_drop_type(&accepted, "texts/plain"));
+    assert!(accepts_drop_type(&["*".to_string()], "image/png"));
+  }
+
+  #[test]
+  fn raw_handles() {
+    use crate::Manager;
+    use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
+
+    let app = crate::test::mock_app();
+    let window = app.get_window("main").unwrap();
+    // the mock runtime has no native handles, but must not panic
+    let _ = window.raw_window_handle();
+    let _ = app.raw_display_handle();
+  }
+
+  #[test]
+  fn drop_script() {
+    let script = super::drop_script(&["text/*".into()]);
+    assert!(script.ends_with(r#"})(["text/*"])"#));
+  }
 }
@@ -0,0 +1,390 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The snapshots of the window contents used by [`crate::Window::thumbnail`].
+//!
+//! The windows must be captured on the main thread.
+
+use crate::runtime::window::dpi::PhysicalSize;
+
+/// An image with 8-bit RGBA pixels, stored row by row from the top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+  width: u32,
+  height: u32,
+  rgba: Vec<u8>,
+}
+
+impl RgbaImage {
+  /// Creates an image from its pixels, returning `None` if the buffer length is not `width * height * 4`.
+  pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Option<Self> {
+    (rgba.len() == width as usize * height as usize * 4).then(|| Self {
+      width,
+      height,
+      rgba,
+    })
+  }
+
+  /// The width in pixels.
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  /// The height in pixels.
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+
+  /// The size in pixels.
+  pub fn size(&self) -> PhysicalSize<u32> {
+    PhysicalSize::new(self.width, self.height)
+  }
+
+  /// The RGBA pixels.
+  pub fn rgba(&self) -> &[u8] {
+    &self.rgba
+  }
+
+  /// Consumes the image, returning its RGBA pixels.
+  pub fn into_rgba(self) -> Vec<u8> {
+    self.rgba
+  }
+
+  /// Scales the image down to fit `max_size`, keeping its aspect ratio.
+  ///
+  /// The image is never enlarged, so a thumbnail can be zoomed up to the resolution it was captured with.
+  pub fn resize(&self, max_size: PhysicalSize<u32>) -> Self {
+    let (width, height) = fit(self.size(), max_size);
+    if (width, height) == (self.width, self.height) {
+      return self.clone();
+    }
+
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+      let (top, bottom) = span(y, height, self.height);
+      for x in 0..width {
+        let (left, right) = span(x, width, self.width);
+        // the colors are weighted by their alpha, so transparent pixels do not darken the edges
+        let mut sum = [0u64; 4];
+        for sy in top..bottom {
+          let row = sy as usize * self.width as usize * 4;
+          for sx in left..right {
+            let pixel = &self.rgba[row + sx as usize * 4..][..4];
+            let alpha = pixel[3] as u64;
+            sum[0] += pixel[0] as u64 * alpha;
+            sum[1] += pixel[1] as u64 * alpha;
+            sum[2] += pixel[2] as u64 * alpha;
+            sum[3] += alpha;
+          }
+        }
+        let count = ((right - left) * (bottom - top)) as u64;
+        if sum[3] == 0 {
+          rgba.extend_from_slice(&[0, 0, 0, 0]);
+        } else {
+          rgba.extend_from_slice(&[
+            (sum[0] / sum[3]) as u8,
+            (sum[1] / sum[3]) as u8,
+            (sum[2] / sum[3]) as u8,
+            (sum[3] / count) as u8,
+          ]);
+        }
+      }
+    }
+
+    Self {
+      width,
+      height,
+      rgba,
+    }
+  }
+}
+
+/// The size of an image of `size` scaled down to fit `max_size`.
+fn fit(size: PhysicalSize<u32>, max_size: PhysicalSize<u32>) -> (u32, u32) {
+  let scale = (max_size.width as f64 / size.width as f64)
+    .min(max_size.height as f64 / size.height as f64)
+    .min(1.0);
+  (
+    ((size.width as f64 * scale).round() as u32).max(1),
+    ((size.height as f64 * scale).round() as u32).max(1),
+  )
+}
+
+/// The source pixels, from `start` to `end`, covered by the pixel `index` of the scaled image.
+fn span(index: u32, scaled: u32, source: u32) -> (u32, u32) {
+  let start = (index as u64 * source as u64 / scaled as u64) as u32;
+  let end = ((index as u64 + 1) * source as u64 / scaled as u64) as u32;
+  (start, end.max(start + 1).min(source))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub(crate) use linux::capture;
+#[cfg(target_os = "macos")]
+pub(crate) use macos::capture;
+#[cfg(windows)]
+pub(crate) use win32::capture;
+
+fn capture_error(message: &str) -> crate::Error {
+  crate::Error::WindowCapture(message.into())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod linux {
+  use super::{capture_error, RgbaImage};
+  use crate::{Runtime, Window};
+  use gtk::prelude::*;
+
+  pub fn capture<R: Runtime>(window: &Window<R>) -> crate::Result<RgbaImage> {
+    let gdk_window = window
+      .gtk_window()?
+      .window()
+      .ok_or_else(|| capture_error("the window is not realized"))?;
+    // the pixbuf is scaled to the scale factor of the window
+    let pixbuf =
+      gtk::gdk::pixbuf_get_from_window(&gdk_window, 0, 0, gdk_window.width(), gdk_window.height())
+        .ok_or_else(|| capture_error("the window contents are not available"))?;
+
+    let (width, height) = (pixbuf.width() as usize, pixbuf.height() as usize);
+    let channels = pixbuf.n_channels() as usize;
+    let rowstride = pixbuf.rowstride() as usize;
+    let has_alpha = pixbuf.has_alpha();
+    // SAFETY: the pixbuf is not shared, so its pixels are not modified while they are read
+    let pixels = unsafe { pixbuf.pixels() };
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+      for x in 0..width {
+        let pixel = &pixels[y * rowstride + x * channels..][..channels];
+        rgba.extend_from_slice(&pixel[..3]);
+        rgba.push(if has_alpha { pixel[3] } else { u8::MAX });
+      }
+    }
+    Ok(RgbaImage {
+      width: width as u32,
+      height: height as u32,
+      rgba,
+    })
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use super::{capture_error, RgbaImage};
+  use crate::{Runtime, Window};
+  use cocoa::{
+    base::id,
+    foundation::{NSPoint, NSRect, NSSize},
+  };
+  use objc::{msg_send, sel, sel_impl};
+  use std::ffi::c_void;
+
+  const WINDOW_LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
+  const WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING: u32 = 1 << 0;
+  const WINDOW_IMAGE_BEST_RESOLUTION: u32 = 1 << 3;
+  const IMAGE_ALPHA_PREMULTIPLIED_LAST: u32 = 1;
+
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGWindowListCreateImage(
+      bounds: NSRect,
+      list_option: u32,
+      window_id: u32,
+      image_option: u32,
+    ) -> *mut c_void;
+    fn CGImageGetWidth(image: *mut c_void) -> usize;
+    fn CGImageGetHeight(image: *mut c_void) -> usize;
+    fn CGImageRelease(image: *mut c_void);
+    fn CGColorSpaceCreateDeviceRGB() -> *mut c_void;
+    fn CGColorSpaceRelease(space: *mut c_void);
+    fn CGBitmapContextCreate(
+      data: *mut c_void,
+      width: usize,
+      height: usize,
+      bits_per_component: usize,
+      bytes_per_row: usize,
+      space: *mut c_void,
+      bitmap_info: u32,
+    ) -> *mut c_void;
+    fn CGContextDrawImage(context: *mut c_void, rect: NSRect, image: *mut c_void);
+    fn CGContextRelease(context: *mut c_void);
+  }
+
+  pub fn capture<R: Runtime>(window: &Window<R>) -> crate::Result<RgbaImage> {
+    let ns_window = window.ns_window()? as id;
+    unsafe {
+      let window_number: isize = msg_send![ns_window, windowNumber];
+      // the null rect captures the bounds of the window, at the resolution of its screen
+      let null_rect = NSRect::new(
+        NSPoint::new(f64::INFINITY, f64::INFINITY),
+        NSSize::new(0., 0.),
+      );
+      let image = CGWindowListCreateImage(
+        null_rect,
+        WINDOW_LIST_OPTION_INCLUDING_WINDOW,
+        window_number as u32,
+        WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING | WINDOW_IMAGE_BEST_RESOLUTION,
+      );
+      if image.is_null() {
+        return Err(capture_error("the window contents are not available"));
+      }
+
+      let (width, height) = (CGImageGetWidth(image), CGImageGetHeight(image));
+      let mut rgba = vec![0u8; width * height * 4];
+      let color_space = CGColorSpaceCreateDeviceRGB();
+      let context = CGBitmapContextCreate(
+        rgba.as_mut_ptr() as *mut c_void,
+        width,
+        height,
+        8,
+        width * 4,
+        color_space,
+        IMAGE_ALPHA_PREMULTIPLIED_LAST,
+      );
+      CGColorSpaceRelease(color_space);
+      if context.is_null() {
+        CGImageRelease(image);
+        return Err(capture_error("failed to create the bitmap context"));
+      }
+      CGContextDrawImage(
+        context,
+        NSRect::new(
+          NSPoint::new(0., 0.),
+          NSSize::new(width as f64, height as f64),
+        ),
+        image,
+      );
+      CGContextRelease(context);
+      CGImageRelease(image);
+
+      for pixel in rgba.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        if alpha > 0 && alpha < 255 {
+          for component in &mut pixel[..3] {
+            *component = (*component as u32 * 255 / alpha).min(255) as u8;
+          }
+        }
+      }
+      Ok(RgbaImage {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+      })
+    }
+  }
+}
+
+#[cfg(windows)]
+mod win32 {
+  use super::{capture_error, RgbaImage};
+  use crate::{Runtime, Window};
+  use windows::Win32::{
+    Foundation::RECT,
+    Graphics::Gdi::{
+      CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+      ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS,
+    },
+    Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS, PW_CLIENTONLY},
+    UI::WindowsAndMessaging::GetClientRect,
+  };
+
+  /// Renders the DWM composition of the window, which includes the WebView2 content.
+  const PW_RENDERFULLCONTENT: u32 = 2;
+
+  pub fn capture<R: Runtime>(window: &Window<R>) -> crate::Result<RgbaImage> {
+    let hwnd = window.hwnd()?;
+    let mut rect = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut rect) };
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 {
+      return Err(capture_error("the window is minimized"));
+    }
+
+    let mut rgba = vec![0u8; width as usize * height as usize * 4];
+    let mut info = BITMAPINFO {
+      bmiHeader: BITMAPINFOHEADER {
+        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: width,
+        // a negative height stores the rows from the top
+        biHeight: -height,
+        biPlanes: 1,
+        biBitCount: 32,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let (printed, rows) = unsafe {
+      let window_dc = GetDC(hwnd);
+      let dc = CreateCompatibleDC(window_dc);
+      let bitmap = CreateCompatibleBitmap(window_dc, width, height);
+      let previous = SelectObject(dc, bitmap);
+      let printed = PrintWindow(
+        hwnd,
+        dc,
+        PRINT_WINDOW_FLAGS(PW_CLIENTONLY.0 | PW_RENDERFULLCONTENT),
+      );
+      SelectObject(dc, previous);
+      let rows = GetDIBits(
+        dc,
+        bitmap,
+        0,
+        height as u32,
+        rgba.as_mut_ptr() as _,
+        &mut info,
+        DIB_RGB_COLORS,
+      );
+      DeleteObject(bitmap);
+      DeleteDC(dc);
+      ReleaseDC(hwnd, window_dc);
+      (printed, rows)
+    };
+    if !printed.as_bool() || rows == 0 {
+      return Err(capture_error("failed to print the window"));
+    }
+
+    // GDI stores BGRA pixels, without alpha
+    for pixel in rgba.chunks_exact_mut(4) {
+      pixel.swap(0, 2);
+      pixel[3] = u8::MAX;
+    }
+    Ok(RgbaImage {
+      width: width as u32,
+      height: height as u32,
+      rgba,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{fit, PhysicalSize, RgbaImage};
+
+  #[test]
+  fn fits_max_size() {
+    let size = PhysicalSize::new(2560, 1600);
+    assert_eq!(fit(size, PhysicalSize::new(320, 320)), (320, 200));
+    assert_eq!(fit(size, PhysicalSize::new(1000, 100)), (160, 100));
+    // never enlarged
+    assert_eq!(fit(size, PhysicalSize::new(4000, 4000)), (2560, 1600));
+    assert_eq!(
+      fit(PhysicalSize::new(10000, 1), PhysicalSize::new(100, 100)),
+      (100, 1)
+    );
+  }
+
+  #[test]
+  fn resizes() {
+    let red = [255, 0, 0, 255];
+    let transparent = [0, 0, 0, 0];
+    let blue = [0, 0, 255, 255];
+    let rgba = [red, transparent, blue, blue, red, red, blue, blue].concat();
+    let image = RgbaImage::new(4, 2, rgba).unwrap();
+
+    let thumbnail = image.resize(PhysicalSize::new(2, 2));
+    assert_eq!(thumbnail.size(), PhysicalSize::new(2, 1));
+    // the transparent pixel keeps the color of the red ones
+    assert_eq!(thumbnail.rgba(), &[255, 0, 0, 191, 0, 0, 255, 255]);
+
+    assert_eq!(image.resize(PhysicalSize::new(8, 8)), image);
+    assert!(RgbaImage::new(2, 2, vec![0; 15]).is_none());
+  }
+}
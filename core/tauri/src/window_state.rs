@@ -0,0 +1,426 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Saves the position, size and state of the windows, restoring them when they are created again.
+//!
+//! Enabled with [`Builder::persist_window_state`](crate::Builder#method.persist_window_state).
+
+use std::{
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+  sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::{
+  api::path::app_config_dir,
+  log::warn,
+  plugin::{Plugin, Result as PluginResult},
+  runtime::webview::WindowBuilder,
+  AppHandle, Manager, Monitor, PhysicalPosition, RunEvent, Runtime, Window, WindowEvent,
+};
+
+/// The minimum width and height, in physical pixels, of the part of a window that must be
+/// on a monitor to restore its position, so it can still be grabbed and moved.
+const MIN_VISIBLE_SIZE: i64 = 64;
+
+/// The options of [`Builder::persist_window_state`](crate::Builder#method.persist_window_state).
+///
+/// Everything is saved by default.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::WindowStateOptions;
+/// tauri::Builder::default()
+///   .persist_window_state(
+///     WindowStateOptions::new()
+///       .fullscreen(false)
+///       .skip("splashscreen"),
+///   );
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowStateOptions {
+  file_name: String,
+  position: bool,
+  size: bool,
+  maximized: bool,
+  fullscreen: bool,
+  skipped: HashSet<String>,
+}
+
+impl Default for WindowStateOptions {
+  fn default() -> Self {
+    Self {
+      file_name: ".window-state.json".into(),
+      position: true,
+      size: true,
+      maximized: true,
+      fullscreen: true,
+      skipped: Default::default(),
+    }
+  }
+}
+
+impl WindowStateOptions {
+  /// Creates the default options.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the name of the file in the [app config directory](crate::api::path::app_config_dir)
+  /// where the state is saved. Defaults to `.window-state.json`.
+  #[must_use]
+  pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+    self.file_name = file_name.into();
+    self
+  }
+
+  /// Whether the window position is restored.
+  ///
+  /// The window is moved back on-screen when it would be off-screen,
+  /// e.g. when the monitor it was on is disconnected.
+  /// Windows configured to be centered are still centered.
+  #[must_use]
+  pub fn position(mut self, position: bool) -> Self {
+    self.position = position;
+    self
+  }
+
+  /// Whether the window size is restored.
+  #[must_use]
+  pub fn size(mut self, size: bool) -> Self {
+    self.size = size;
+    self
+  }
+
+  /// Whether the window is maximized again if it was maximized.
+  #[must_use]
+  pub fn maximized(mut self, maximized: bool) -> Self {
+    self.maximized = maximized;
+    self
+  }
+
+  /// Whether the window is made fullscreen again if it was fullscreen.
+  #[must_use]
+  pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+    self.fullscreen = fullscreen;
+    self
+  }
+
+  /// Neither saves nor restores the state of the window with the given label.
+  #[must_use]
+  pub fn skip(mut self, label: impl Into<String>) -> Self {
+    self.skipped.insert(label.into());
+    self
+  }
+}
+
+fn default_scale_factor() -> f64 {
+  1.0
+}
+
+/// The saved state of a window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WindowState {
+  /// The outer position, in physical pixels.
+  x: i32,
+  y: i32,
+  /// The inner size, in physical pixels.
+  width: u32,
+  height: u32,
+  /// The scale factor of the window, converting the geometry to the logical pixels of the window builder.
+  #[serde(default = "default_scale_factor")]
+  scale_factor: f64,
+  maximized: bool,
+  fullscreen: bool,
+  /// The name of the monitor the window was on.
+  monitor: Option<String>,
+}
+
+impl Default for WindowState {
+  fn default() -> Self {
+    Self {
+      x: 0,
+      y: 0,
+      width: 0,
+      height: 0,
+      scale_factor: default_scale_factor(),
+      maximized: false,
+      fullscreen: false,
+      monitor: None,
+    }
+  }
+}
+
+impl WindowState {
+  /// Whether the geometry of the window was saved.
+  fn has_geometry(&self) -> bool {
+    self.width > 0 && self.height > 0
+  }
+
+  /// Applies the state to the builder, so the window is created with it.
+  pub(crate) fn apply<W: WindowBuilder>(&self, mut builder: W, options: &WindowStateOptions) -> W {
+    let logical = |pixels: f64| pixels / self.scale_factor;
+    if self.has_geometry() {
+      if options.size {
+        builder = builder.inner_size(
+          logical(f64::from(self.width)),
+          logical(f64::from(self.height)),
+        );
+      }
+      if options.position {
+        builder = builder.position(logical(f64::from(self.x)), logical(f64::from(self.y)));
+      }
+    }
+    if options.maximized && self.maximized {
+      builder = builder.maximized(true);
+    }
+    if options.fullscreen && self.fullscreen {
+      builder = builder.fullscreen(true);
+    }
+    builder
+  }
+
+  /// Moves the created window back on-screen if its restored position is off-screen.
+  pub(crate) fn keep_on_screen<R: Runtime>(
+    &self,
+    window: &Window<R>,
+    options: &WindowStateOptions,
+  ) -> crate::Result<()> {
+    if !options.position || !self.has_geometry() {
+      return Ok(());
+    }
+    let monitors = window.available_monitors()?;
+    if is_on_screen(self, &monitors) {
+      return Ok(());
+    }
+    match monitors
+      .iter()
+      .find(|m| m.name.is_some() && m.name == self.monitor)
+    {
+      // the monitor is still connected but its layout changed
+      Some(monitor) => window.set_position(center_on(self, monitor)),
+      // the monitor was disconnected
+      None => window.center(),
+    }
+  }
+
+  /// Reads the current state of the window.
+  pub(crate) fn update<R: Runtime>(&mut self, window: &Window<R>) -> crate::Result<()> {
+    self.maximized = window.is_maximized()?;
+    self.fullscreen = window.is_fullscreen()?;
+    // keep the geometry of the window before it was maximized, so it is restored when unmaximized
+    if !self.maximized && !self.fullscreen {
+      let position = window.outer_position()?;
+      let size = window.inner_size()?;
+      // minimized windows report an empty size
+      if size.width > 0 && size.height > 0 {
+        self.x = position.x;
+        self.y = position.y;
+        self.width = size.width;
+        self.height = size.height;
+        self.scale_factor = window.scale_factor()?;
+      }
+    }
+    if let Some(monitor) = window.current_monitor()? {
+      self.monitor = monitor.name;
+    }
+    Ok(())
+  }
+}
+
+/// Whether enough of a window is on one of the monitors to be grabbed.
+fn is_on_screen(state: &WindowState, monitors: &[Monitor]) -> bool {
+  monitors.iter().any(|monitor| {
+    let left = i64::from(state.x).max(i64::from(monitor.position.x));
+    let top = i64::from(state.y).max(i64::from(monitor.position.y));
+    let right = (i64::from(state.x) + i64::from(state.width))
+      .min(i64::from(monitor.position.x) + i64::from(monitor.size.width));
+    let bottom = (i64::from(state.y) + i64::from(state.height))
+      .min(i64::from(monitor.position.y) + i64::from(monitor.size.height));
+    right - left >= MIN_VISIBLE_SIZE && bottom - top >= MIN_VISIBLE_SIZE
+  })
+}
+
+/// Centers the window on the monitor, keeping it on-screen.
+fn center_on(state: &WindowState, monitor: &Monitor) -> PhysicalPosition<i32> {
+  let offset =
+    |monitor_size: u32, size: u32| (i64::from(monitor_size) - i64::from(size)).max(0) as i32 / 2;
+  PhysicalPosition::new(
+    monitor.position.x + offset(monitor.size.width, state.width),
+    monitor.position.y + offset(monitor.size.height, state.height),
+  )
+}
+
+/// The saved states of the windows, managed as a state so they are applied before the windows are created.
+pub(crate) struct WindowStates {
+  options: WindowStateOptions,
+  path: Option<PathBuf>,
+  states: Mutex<HashMap<String, WindowState>>,
+}
+
+impl WindowStates {
+  /// Applies the saved state of the window to its builder.
+  pub(crate) fn restore<W: WindowBuilder>(&self, label: &str, builder: W) -> W {
+    match self.state(label) {
+      Some(state) => state.apply(builder, &self.options),
+      None => builder,
+    }
+  }
+
+  /// The saved state of the window, read without holding the lock while the window is changed or queried.
+  fn state(&self, label: &str) -> Option<WindowState> {
+    if self.options.skipped.contains(label) {
+      return None;
+    }
+    self.states.lock().unwrap().get(label).cloned()
+  }
+
+  fn update<R: Runtime>(&self, window: &Window<R>) -> crate::Result<()> {
+    let mut state = self.state(window.label()).unwrap_or_default();
+    state.update(window)?;
+    self
+      .states
+      .lock()
+      .unwrap()
+      .insert(window.label().into(), state);
+    Ok(())
+  }
+
+  fn save(&self) -> crate::Result<()> {
+    if let Some(path) = &self.path {
+      if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+      }
+      std::fs::write(path, serde_json::to_vec(&*self.states.lock().unwrap())?)?;
+    }
+    Ok(())
+  }
+}
+
+pub(crate) struct WindowStatePlugin {
+  options: WindowStateOptions,
+}
+
+impl WindowStatePlugin {
+  pub(crate) fn new(options: WindowStateOptions) -> Self {
+    Self { options }
+  }
+}
+
+impl<R: Runtime> Plugin<R> for WindowStatePlugin {
+  fn name(&self) -> &'static str {
+    "tauri-window-state"
+  }
+
+  fn initialize(&mut self, app: &AppHandle<R>, _config: JsonValue) -> PluginResult<()> {
+    let path = app_config_dir(&app.config()).map(|dir| dir.join(&self.options.file_name));
+    // a missing or corrupted file is replaced on the next save
+    let states = path
+      .as_ref()
+      .and_then(|path| std::fs::read(path).ok())
+      .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+      .unwrap_or_default();
+    app.manage(WindowStates {
+      options: self.options.clone(),
+      path,
+      states: Mutex::new(states),
+    });
+    Ok(())
+  }
+
+  fn created(&mut self, window: Window<R>) {
+    if let Some(state) = window.state::<WindowStates>().state(window.label()) {
+      if let Err(e) = state.keep_on_screen(&window, &self.options) {
+        warn!(
+          "failed to restore the state of window `{}`: {}",
+          window.label(),
+          e
+        );
+      }
+    }
+  }
+
+  fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
+    let states = app.state::<WindowStates>();
+    let result = match event {
+      RunEvent::WindowEvent { label, event, .. } if !self.options.skipped.contains(label) => {
+        match (event, app.get_window(label)) {
+          (WindowEvent::Moved(_) | WindowEvent::Resized(_), Some(window)) => states.update(&window),
+          (WindowEvent::CloseRequested { .. }, Some(window)) => {
+            states.update(&window).and_then(|_| states.save())
+          }
+          _ => Ok(()),
+        }
+      }
+      RunEvent::Exit => states.save(),
+      _ => Ok(()),
+    };
+    if let Err(e) = result {
+      warn!("failed to save the window state: {}", e);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{center_on, is_on_screen, WindowState};
+  use crate::{Monitor, PhysicalPosition, PhysicalSize};
+
+  fn monitor(x: i32, y: i32, width: u32, height: u32) -> Monitor {
+    Monitor {
+      name: Some(format!("{}x{}", width, height)),
+      size: PhysicalSize::new(width, height),
+      position: PhysicalPosition::new(x, y),
+      scale_factor: 1.0,
+    }
+  }
+
+  fn window(x: i32, y: i32) -> WindowState {
+    WindowState {
+      x,
+      y,
+      width: 800,
+      height: 600,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn off_screen_windows_are_detected() {
+    // a 4K monitor on the left of the primary monitor
+    let monitors = [monitor(0, 0, 1920, 1080), monitor(-3840, 0, 3840, 2160)];
+
+    assert!(is_on_screen(&window(100, 100), &monitors));
+    assert!(is_on_screen(&window(-2000, 1500), &monitors));
+    // partially on the primary monitor
+    assert!(is_on_screen(&window(1800, 1000), &monitors));
+
+    // below the primary monitor
+    assert!(!is_on_screen(&window(100, 1100), &monitors));
+    // only a sliver is visible
+    assert!(!is_on_screen(&window(1900, 100), &monitors));
+    // the secondary monitor was disconnected
+    assert!(!is_on_screen(&window(-2000, 100), &monitors[..1]));
+  }
+
+  #[test]
+  fn centers_on_monitor() {
+    let monitor = monitor(-1920, 0, 1920, 1080);
+    assert_eq!(
+      center_on(&window(0, 0), &monitor),
+      PhysicalPosition::new(-1360, 240)
+    );
+    let large = WindowState {
+      width: 2560,
+      height: 1440,
+      ..Default::default()
+    };
+    assert_eq!(center_on(&large, &monitor), PhysicalPosition::new(-1920, 0));
+  }
+}
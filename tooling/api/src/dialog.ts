@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: MIT
 
 /**
- * Native system dialogs for opening and saving files.
+ * Native system dialogs for opening and saving files, showing messages and picking colors and fonts.
  *
  * This package is also accessible with `window.__TAURI__.dialog` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
  *
@@ -15,7 +15,9 @@
  *       "dialog": {
  *         "all": true, // enable all dialog APIs
  *         "open": true, // enable file open API
- *         "save": true // enable file save API
+ *         "save": true, // enable file save API
+ *         "colorPicker": true, // enable color picker API
+ *         "fontPicker": true // enable font picker API
  *       }
  *     }
  *   }
@@ -96,6 +98,51 @@ interface MessageDialogOptions {
   type?: 'info' | 'warning' | 'error'
 }
 
+/**
+ * A color picked with {@link colorPicker}.
+ *
+ * @since 1.3.0
+ */
+interface Color {
+  red: number
+  green: number
+  blue: number
+  /** The alpha component, where `255` is fully opaque. Defaults to `255`. */
+  alpha?: number
+}
+
+/**
+ * A font picked with {@link fontPicker}.
+ *
+ * @since 1.3.0
+ */
+interface Font {
+  /** The font family name. */
+  family: string
+  /** The size in points. */
+  size: number
+  /** The weight, from `100` (thin) to `900` (black). Defaults to `400`. */
+  weight?: number
+  /** Whether the font is italic. Defaults to `false`. */
+  italic?: boolean
+}
+
+/**
+ * @since 1.3.0
+ */
+interface ColorPickerOptions {
+  /** The initially selected color. */
+  defaultColor?: Color
+}
+
+/**
+ * @since 1.3.0
+ */
+interface FontPickerOptions {
+  /** The initially selected font. */
+  defaultFont?: Font
+}
+
 /**
  * Open a file/directory selection dialog.
  *
@@ -299,11 +346,82 @@ async function confirm(
   })
 }
 
+/**
+ * Shows the native color picker.
+ *
+ * On macOS the color panel has no cancel button, so closing it picks the current color.
+ * On Windows the alpha component cannot be picked and is always `255`.
+ * @example
+ * ```typescript
+ * import { colorPicker } from '@tauri-apps/api/dialog';
+ * const color = await colorPicker({ defaultColor: { red: 36, green: 200, blue: 219 } });
+ * if (color !== null) {
+ *   document.body.style.background = `rgba(${color.red}, ${color.green}, ${color.blue}, ${color.alpha / 255})`;
+ * }
+ * ```
+ *
+ * @returns A promise resolving to the picked color, or `null` if the user cancelled the picker.
+ *
+ * @since 1.3.0
+ */
+async function colorPicker(
+  options: ColorPickerOptions = {}
+): Promise<Required<Color> | null> {
+  if (typeof options === 'object') {
+    Object.freeze(options)
+  }
+
+  return invokeTauriCommand({
+    __tauriModule: 'Dialog',
+    message: {
+      cmd: 'colorPickerDialog',
+      options
+    }
+  })
+}
+
+/**
+ * Shows the native font picker.
+ *
+ * On macOS the font panel has no cancel button, so closing it picks the current font.
+ * @example
+ * ```typescript
+ * import { fontPicker } from '@tauri-apps/api/dialog';
+ * const font = await fontPicker({ defaultFont: { family: 'Helvetica', size: 14 } });
+ * if (font !== null) {
+ *   document.body.style.font = `${font.italic ? 'italic ' : ''}${font.weight} ${font.size}pt "${font.family}"`;
+ * }
+ * ```
+ *
+ * @returns A promise resolving to the picked font, or `null` if the user cancelled the picker.
+ *
+ * @since 1.3.0
+ */
+async function fontPicker(
+  options: FontPickerOptions = {}
+): Promise<Required<Font> | null> {
+  if (typeof options === 'object') {
+    Object.freeze(options)
+  }
+
+  return invokeTauriCommand({
+    __tauriModule: 'Dialog',
+    message: {
+      cmd: 'fontPickerDialog',
+      options
+    }
+  })
+}
+
 export type {
   DialogFilter,
   OpenDialogOptions,
   SaveDialogOptions,
-  MessageDialogOptions
+  MessageDialogOptions,
+  Color,
+  Font,
+  ColorPickerOptions,
+  FontPickerOptions
 }
 
-export { open, save, message, ask, confirm }
+export { open, save, message, ask, confirm, colorPicker, fontPicker }
@@ -30,6 +30,9 @@ export enum TauriEvent {
   WINDOW_FILE_DROP = 'tauri://file-drop',
   WINDOW_FILE_DROP_HOVER = 'tauri://file-drop-hover',
   WINDOW_FILE_DROP_CANCELLED = 'tauri://file-drop-cancelled',
+  WINDOW_DROP = 'tauri://drop',
+  WINDOW_DROP_HOVER = 'tauri://drop-hover',
+  NETWORK_STATUS = 'tauri://network-status',
   MENU = 'tauri://menu',
   CHECK_UPDATE = 'tauri://update',
   UPDATE_AVAILABLE = 'tauri://update-available',
@@ -113,6 +116,27 @@ async function emit(event: string, payload?: unknown): Promise<void> {
   return eventApi.emit(event, undefined, payload)
 }
 
+/**
+ * Emits an event to the windows of a group, added with `Window::set_group` on the Rust side.
+ * @example
+ * ```typescript
+ * import { emitToGroup } from '@tauri-apps/api/event';
+ * await emitToGroup('settings-changed', 'editors', { theme: 'dark' });
+ * ```
+ *
+ * @param event Event name. Must include only alphanumeric characters, `-`, `/`, `:` and `_`.
+ * @param group The group of the windows.
+ *
+ * @since 1.3.0
+ */
+async function emitToGroup(
+  event: string,
+  group: string,
+  payload?: unknown
+): Promise<void> {
+  return eventApi.emitToGroup(event, group, payload)
+}
+
 export type { Event, EventCallback, UnlistenFn }
 
-export { listen, once, emit }
+export { listen, once, emit, emitToGroup }
@@ -22,7 +22,9 @@
  *         "removeDir": true,
  *         "removeFile": true,
  *         "renameFile": true,
- *         "exists": true
+ *         "exists": true,
+ *         "trash": true,
+ *         "watch": true
  *       }
  *     }
  *   }
@@ -73,6 +75,8 @@
  */
 
 import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
+import type { UnlistenFn } from './helpers/event'
 
 /**
  * @since 1.0.0
@@ -382,6 +386,118 @@ async function writeBinaryFile(
   })
 }
 
+/**
+ * Reads a file as a stream of byte chunks, so large files are not loaded in memory at once.
+ * The file is closed when the stream is read to the end or cancelled.
+ * @example
+ * ```typescript
+ * import { readFileStream, BaseDirectory } from '@tauri-apps/api/fs';
+ * // Read the `$APPDATA/video.mp4` file in chunks
+ * const stream = await readFileStream('video.mp4', { dir: BaseDirectory.AppData });
+ * const reader = stream.getReader();
+ * for (let chunk = await reader.read(); !chunk.done; chunk = await reader.read()) {
+ *   console.log(chunk.value.length);
+ * }
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function readFileStream(
+  filePath: string,
+  options: FsOptions = {}
+): Promise<ReadableStream<Uint8Array>> {
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'openReadStream',
+      path: filePath,
+      options
+    }
+  })
+
+  return new ReadableStream<Uint8Array>({
+    async pull(controller) {
+      // the chunks are encoded as base64
+      const chunk = await invokeTauriCommand<string>({
+        __tauriModule: 'Fs',
+        message: {
+          cmd: 'readStreamChunk',
+          id
+        }
+      })
+      if (chunk.length === 0) {
+        controller.close()
+      } else {
+        controller.enqueue(Uint8Array.from(atob(chunk), (c) => c.charCodeAt(0)))
+      }
+    },
+    async cancel() {
+      await invokeTauriCommand({
+        __tauriModule: 'Fs',
+        message: {
+          cmd: 'closeReadStream',
+          id
+        }
+      })
+    }
+  })
+}
+
+/**
+ * Writes a file from a stream of byte chunks, so large contents are not sent to the core at once.
+ * The file is flushed and closed when the stream is closed or aborted.
+ * @example
+ * ```typescript
+ * import { writeFileStream, BaseDirectory } from '@tauri-apps/api/fs';
+ * // Write the `$APPDATA/recording.webm` file in chunks
+ * const stream = await writeFileStream('recording.webm', { dir: BaseDirectory.AppData });
+ * const writer = stream.getWriter();
+ * await writer.write(new Uint8Array([1, 2, 3]));
+ * await writer.close();
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function writeFileStream(
+  filePath: string,
+  options: FsOptions = {}
+): Promise<WritableStream<BinaryFileContents>> {
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'openWriteStream',
+      path: filePath,
+      options
+    }
+  })
+
+  const close = async (): Promise<void> =>
+    invokeTauriCommand({
+      __tauriModule: 'Fs',
+      message: {
+        cmd: 'closeWriteStream',
+        id
+      }
+    })
+
+  return new WritableStream<BinaryFileContents>({
+    async write(contents) {
+      await invokeTauriCommand({
+        __tauriModule: 'Fs',
+        message: {
+          cmd: 'writeStreamChunk',
+          id,
+          contents: Array.from(
+            contents instanceof ArrayBuffer ? new Uint8Array(contents) : contents
+          )
+        }
+      })
+    },
+    close,
+    abort: close
+  })
+}
+
 /**
  * List directory files.
  * @example
@@ -529,6 +645,31 @@ async function removeFile(
   })
 }
 
+/**
+ * Moves a file or directory to the trash of the system instead of deleting it permanently,
+ * so the user can restore it.
+ * @example
+ * ```typescript
+ * import { trash, BaseDirectory } from '@tauri-apps/api/fs';
+ * // Move the `$APPDATA/drafts/old.md` file to the trash
+ * await trash('drafts/old.md', { dir: BaseDirectory.AppData });
+ * ```
+ *
+ * @returns A promise indicating the success or failure of the operation.
+ *
+ * @since 1.3.0
+ */
+async function trash(path: string, options: FsOptions = {}): Promise<void> {
+  return invokeTauriCommand({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'trash',
+      path,
+      options
+    }
+  })
+}
+
 /**
  * Renames a file.
  * @example
@@ -580,13 +721,89 @@ async function exists(path: string, options: FsOptions = {}): Promise<boolean> {
   })
 }
 
+/**
+ * Options object used to watch paths.
+ *
+ * @since 1.3.0
+ */
+interface WatchOptions {
+  /** The base directory of the watched paths. */
+  dir?: BaseDirectory
+  /** Whether the directories are watched recursively. Defaults to `true`. */
+  recursive?: boolean
+  /** How long the changes are collected before they are delivered, in milliseconds. Defaults to 500. */
+  delayMs?: number
+}
+
+/**
+ * A change of a watched path.
+ *
+ * @since 1.3.0
+ */
+interface WatchEvent {
+  kind: 'create' | 'modify' | 'remove'
+  /** The absolute path that changed. */
+  path: string
+}
+
+/**
+ * Watches files and directories for changes.
+ * The changes are debounced and only reported for the paths allowed by the scope.
+ * @example
+ * ```typescript
+ * import { watch, BaseDirectory } from '@tauri-apps/api/fs';
+ * // Watch the `$APPDATA/notes` directory
+ * const unwatch = await watch('notes', (events) => {
+ *   for (const event of events) {
+ *     console.log(event.kind, event.path);
+ *   }
+ * }, { dir: BaseDirectory.AppData });
+ * // stop watching
+ * await unwatch();
+ * ```
+ * @param paths The file or directory paths to watch.
+ * @param cb The function called with each batch of changes.
+ * @returns A promise resolving to a function that stops watching.
+ *
+ * @since 1.3.0
+ */
+async function watch(
+  paths: string | string[],
+  cb: (events: WatchEvent[]) => void,
+  options: WatchOptions = {}
+): Promise<UnlistenFn> {
+  const handler = transformCallback(cb)
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'watch',
+      paths: typeof paths === 'string' ? [paths] : paths,
+      options,
+      handler
+    }
+  })
+
+  return async () => {
+    await invokeTauriCommand({
+      __tauriModule: 'Fs',
+      message: {
+        cmd: 'unwatch',
+        id
+      }
+    })
+    Reflect.deleteProperty(window, `_${handler}`)
+  }
+}
+
 export type {
   FsOptions,
   FsDirOptions,
   FsTextFileOption,
   BinaryFileContents,
   FsBinaryFileOption,
-  FileEntry
+  FileEntry,
+  WatchOptions,
+  WatchEvent
 }
 
 export {
@@ -596,11 +813,15 @@ export {
   writeTextFile,
   writeTextFile as writeFile,
   writeBinaryFile,
+  readFileStream,
+  writeFileStream,
   readDir,
   createDir,
   removeDir,
   copyFile,
   removeFile,
+  trash,
   renameFile,
-  exists
+  exists,
+  watch
 }
@@ -65,6 +65,30 @@ async function emit(
   })
 }
 
+/**
+ * Emits an event to the windows of a group.
+ *
+ * @param event Event name. Must include only alphanumeric characters, `-`, `/`, `:` and `_`.
+ * @param group The group of the windows to which the event is sent
+ * @param [payload] Event payload
+ * @returns
+ */
+async function emitToGroup(
+  event: string,
+  group: string,
+  payload?: unknown
+): Promise<void> {
+  await invokeTauriCommand({
+    __tauriModule: 'Event',
+    message: {
+      cmd: 'emit',
+      event,
+      group,
+      payload
+    }
+  })
+}
+
 /**
  * Listen to an event from the backend.
  *
@@ -108,4 +132,4 @@ async function once<T>(
   })
 }
 
-export { emit, listen, once }
+export { emit, emitToGroup, listen, once }
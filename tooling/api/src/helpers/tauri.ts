@@ -19,9 +19,17 @@ type TauriModule =
   | 'Cli'
   | 'Notification'
   | 'Http'
+  | 'Websocket'
+  | 'Serial'
+  | 'Mdns'
   | 'GlobalShortcut'
   | 'Process'
   | 'Clipboard'
+  | 'Channel'
+  | 'Log'
+  | 'I18n'
+  | 'OAuth'
+  | 'Store'
 
 interface TauriCommand {
   __tauriModule: TauriModule
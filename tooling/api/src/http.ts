@@ -44,6 +44,7 @@
  */
 
 import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
 
 /**
  * @since 1.0.0
@@ -63,6 +64,13 @@ interface ClientOptions {
    * If set to 0, no redirects will be followed.
    */
   connectTimeout?: number | Duration
+  /**
+   * Whether the client stores the cookies set by the servers and sends them back on the following requests.
+   * The cookies are shared by all the clients with this option enabled and persisted in the app data directory.
+   *
+   * @since 1.3.0
+   */
+  cookies?: boolean
 }
 
 /**
@@ -254,6 +262,59 @@ interface HttpOptions {
   body?: Body
   timeout?: number | Duration
   responseType?: ResponseType
+  /**
+   * Called with the progress of the body upload, at most once per percent.
+   * Multipart form files given by path are streamed from disk.
+   *
+   * @since 1.3.0
+   */
+  onUploadProgress?: (progress: Progress) => void
+  /**
+   * Called with each chunk of the response body as it is received, instead of buffering it in the response `data`, which is then `null`.
+   *
+   * @since 1.3.0
+   */
+  onChunk?: (chunk: Uint8Array) => void
+  /**
+   * Writes the response body to the given file path as it is received, instead of buffering it in the response `data`, which is then `null`.
+   * The path must be allowed on the `fs` scope.
+   *
+   * @since 1.3.0
+   */
+  saveTo?: string
+}
+
+/**
+ * The progress of a transfer.
+ *
+ * @since 1.3.0
+ */
+interface Progress {
+  /** The number of bytes transferred so far. */
+  transferred: number
+  /** The total number of bytes, if known. */
+  total: number | null
+}
+
+/**
+ * A cookie set by a server.
+ *
+ * @since 1.3.0
+ */
+interface Cookie {
+  name: string
+  value: string
+  /** The domain the cookie is sent to. */
+  domain: string
+  /** Whether the cookie is only sent to `domain` and not to its subdomains. */
+  hostOnly: boolean
+  /** The path prefix of the URLs the cookie is sent to. */
+  path: string
+  /** Whether the cookie is only sent over HTTPS. */
+  secure: boolean
+  httpOnly: boolean
+  /** The expiration time in seconds since the UNIX epoch, or `null` for session cookies. */
+  expires: number | null
 }
 
 /** Request options. */
@@ -341,41 +402,67 @@ class Client {
    * });
    * ```
    */
-  async request<T>(options: HttpOptions): Promise<Response<T>> {
+  async request<T>({
+    onUploadProgress,
+    onChunk,
+    saveTo,
+    ...options
+  }: HttpOptions): Promise<Response<T>> {
+    const streamed = onChunk !== undefined || saveTo !== undefined
     const jsonResponse =
-      !options.responseType || options.responseType === ResponseType.JSON
+      !streamed &&
+      (!options.responseType || options.responseType === ResponseType.JSON)
     if (jsonResponse) {
       options.responseType = ResponseType.Text
     }
+    const uploadProgressHandler = onUploadProgress
+      ? transformCallback(onUploadProgress)
+      : undefined
+    const chunkHandler = onChunk
+      ? transformCallback((chunk: string) =>
+          onChunk(Uint8Array.from(atob(chunk), (c) => c.charCodeAt(0)))
+        )
+      : undefined
     return invokeTauriCommand<IResponse<T>>({
       __tauriModule: 'Http',
       message: {
         cmd: 'httpRequest',
         client: this.id,
-        options
+        options,
+        uploadProgressHandler,
+        chunkHandler,
+        saveTo
       }
-    }).then((res) => {
-      const response = new Response(res)
-      if (jsonResponse) {
-        /* eslint-disable */
-        try {
-          response.data = JSON.parse(response.data as string)
-        } catch (e) {
-          if (response.ok && (response.data as unknown as string) === '') {
-            // @ts-expect-error
-            response.data = {}
-          } else if (response.ok) {
-            throw Error(
-              `Failed to parse response \`${response.data}\` as JSON: ${e};
+    })
+      .finally(() => {
+        for (const handler of [uploadProgressHandler, chunkHandler]) {
+          if (handler !== undefined) {
+            Reflect.deleteProperty(window, `_${handler}`)
+          }
+        }
+      })
+      .then((res) => {
+        const response = new Response(res)
+        if (jsonResponse) {
+          /* eslint-disable */
+          try {
+            response.data = JSON.parse(response.data as string)
+          } catch (e) {
+            if (response.ok && (response.data as unknown as string) === '') {
+              // @ts-expect-error
+              response.data = {}
+            } else if (response.ok) {
+              throw Error(
+                `Failed to parse response \`${response.data}\` as JSON: ${e};
               try setting the \`responseType\` option to \`ResponseType.Text\` or \`ResponseType.Binary\` if the API does not return a JSON response.`
-            )
+              )
+            }
           }
+          /* eslint-enable */
+          return response
         }
-        /* eslint-enable */
         return response
-      }
-      return response
-    })
+      })
   }
 
   /**
@@ -510,15 +597,62 @@ class Client {
  * @since 1.0.0
  */
 async function getClient(options?: ClientOptions): Promise<Client> {
+  const { cookies, ...clientOptions } = options ?? {}
   return invokeTauriCommand<number>({
     __tauriModule: 'Http',
     message: {
       cmd: 'createClient',
-      options
+      options: clientOptions,
+      cookies
     }
   }).then((id) => new Client(id))
 }
 
+/**
+ * Gets the stored cookies sent on requests to the given URL by the clients created with the `cookies` option.
+ * The `HttpOnly` cookies are not returned.
+ * @example
+ * ```typescript
+ * import { getCookies } from '@tauri-apps/api/http';
+ * const cookies = await getCookies('https://api.tauri.app/v1');
+ * ```
+ *
+ * @param url The request URL. It must be allowed on the HTTP scope.
+ *
+ * @since 1.3.0
+ */
+async function getCookies(url: string): Promise<Cookie[]> {
+  return invokeTauriCommand<Cookie[]>({
+    __tauriModule: 'Http',
+    message: {
+      cmd: 'getCookies',
+      url
+    }
+  })
+}
+
+/**
+ * Removes the stored cookies sent on requests to the given URL, e.g. to log out of a session.
+ * @example
+ * ```typescript
+ * import { clearCookies } from '@tauri-apps/api/http';
+ * await clearCookies('https://api.tauri.app');
+ * ```
+ *
+ * @param url The request URL. It must be allowed on the HTTP scope.
+ *
+ * @since 1.3.0
+ */
+async function clearCookies(url: string): Promise<void> {
+  return invokeTauriCommand({
+    __tauriModule: 'Http',
+    message: {
+      cmd: 'clearCookies',
+      url
+    }
+  })
+}
+
 /** @internal */
 let defaultClient: Client | null = null
 
@@ -554,7 +688,19 @@ export type {
   HttpVerb,
   HttpOptions,
   RequestOptions,
-  FetchOptions
+  FetchOptions,
+  Progress,
+  Cookie
 }
 
-export { getClient, fetch, Body, Client, Response, ResponseType, FilePart }
+export {
+  getClient,
+  getCookies,
+  clearCookies,
+  fetch,
+  Body,
+  Client,
+  Response,
+  ResponseType,
+  FilePart
+}
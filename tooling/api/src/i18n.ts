@@ -0,0 +1,109 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Translate messages with the localization files of the application, the same translations as `App::i18n` on the Rust side.
+ *
+ * The translations are the `<locale>.ftl` or `<locale>.json` files of the directory configured on [`tauri.i18n`](https://tauri.app/v1/api/config/#tauriconfig.i18n) in `tauri.conf.json`.
+ *
+ * This package is also accessible with `window.__TAURI__.i18n` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+
+/**
+ * The arguments of a message, Fluent variables or `{name}` placeholders on JSON files.
+ *
+ * @since 1.3.0
+ */
+type TranslationArgs = Record<string, string | number>
+
+/**
+ * Translates a message on the selected locale, falling back to the default locale and then to the key itself.
+ * @example
+ * ```typescript
+ * import { translate } from '@tauri-apps/api/i18n';
+ * const greeting = await translate('greeting', { name: 'Ana' });
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function translate(
+  key: string,
+  args: TranslationArgs = {}
+): Promise<string> {
+  return invokeTauriCommand({
+    __tauriModule: 'I18n',
+    message: {
+      cmd: 'translate',
+      key,
+      args
+    }
+  })
+}
+
+/**
+ * Gets the selected locale, the system locale unless it was changed with {@link setLocale}.
+ * @example
+ * ```typescript
+ * import { locale } from '@tauri-apps/api/i18n';
+ * const current = await locale();
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function locale(): Promise<string> {
+  return invokeTauriCommand({
+    __tauriModule: 'I18n',
+    message: {
+      cmd: 'locale'
+    }
+  })
+}
+
+/**
+ * Selects the locale of the application, overriding the system locale.
+ *
+ * The change applies to the whole application, including the Rust side.
+ * @example
+ * ```typescript
+ * import { setLocale } from '@tauri-apps/api/i18n';
+ * await setLocale('fr');
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function setLocale(locale: string): Promise<void> {
+  return invokeTauriCommand({
+    __tauriModule: 'I18n',
+    message: {
+      cmd: 'setLocale',
+      locale
+    }
+  })
+}
+
+/**
+ * Lists the locales with translations.
+ * @example
+ * ```typescript
+ * import { availableLocales } from '@tauri-apps/api/i18n';
+ * const locales = await availableLocales();
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function availableLocales(): Promise<string[]> {
+  return invokeTauriCommand({
+    __tauriModule: 'I18n',
+    message: {
+      cmd: 'availableLocales'
+    }
+  })
+}
+
+export type { TranslationArgs }
+
+export { translate, locale, setLocale, availableLocales }
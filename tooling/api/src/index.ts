@@ -21,12 +21,19 @@ import * as event from './event'
 import * as fs from './fs'
 import * as globalShortcut from './globalShortcut'
 import * as http from './http'
+import * as i18n from './i18n'
+import * as log from './log'
+import * as mdns from './mdns'
 import * as notification from './notification'
+import * as oauth from './oauth'
 import * as path from './path'
 import * as process from './process'
+import * as serial from './serial'
 import * as shell from './shell'
+import * as store from './store'
 import * as tauri from './tauri'
 import * as updater from './updater'
+import * as websocket from './websocket'
 import * as window from './window'
 import * as os from './os'
 
@@ -43,12 +50,19 @@ export {
   fs,
   globalShortcut,
   http,
+  i18n,
+  log,
+  mdns,
   notification,
+  oauth,
   path,
   process,
+  serial,
   shell,
+  store,
   tauri,
   updater,
+  websocket,
   window,
   os
 }
@@ -0,0 +1,114 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Write records to the application log, the same sink as the `tauri::log` macros of the Rust side.
+ *
+ * The records are written to the file and standard output configured on [`tauri.log`](https://tauri.app/v1/api/config/#tauriconfig.log) in `tauri.conf.json`.
+ * Set `tauri.log.webviewConsole` to also forward the `console` messages.
+ * The records are written with the `webview` target, prefixed with the label of the window, and their line breaks are escaped.
+ *
+ * This package is also accessible with `window.__TAURI__.log` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "log": {
+ *         "all": true, // enable all log APIs
+ *         "write": true // enable writing records and forwarding the console messages
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * It is recommended to allowlist only the APIs you use for optimal bundle size and security.
+ *
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+
+/**
+ * The level of a log record.
+ *
+ * @since 1.3.0
+ */
+type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error'
+
+/**
+ * Writes a record to the application log.
+ * @example
+ * ```typescript
+ * import { log } from '@tauri-apps/api/log';
+ * await log('warn', 'the cache is full');
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function log(level: LogLevel, message: string): Promise<void> {
+  return invokeTauriCommand({
+    __tauriModule: 'Log',
+    message: {
+      cmd: 'log',
+      level,
+      message
+    }
+  })
+}
+
+/**
+ * Writes a `trace` record to the application log.
+ *
+ * @since 1.3.0
+ */
+async function trace(message: string): Promise<void> {
+  return log('trace', message)
+}
+
+/**
+ * Writes a `debug` record to the application log.
+ *
+ * @since 1.3.0
+ */
+async function debug(message: string): Promise<void> {
+  return log('debug', message)
+}
+
+/**
+ * Writes an `info` record to the application log.
+ * @example
+ * ```typescript
+ * import { info } from '@tauri-apps/api/log';
+ * await info('user signed in');
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function info(message: string): Promise<void> {
+  return log('info', message)
+}
+
+/**
+ * Writes a `warn` record to the application log.
+ *
+ * @since 1.3.0
+ */
+async function warn(message: string): Promise<void> {
+  return log('warn', message)
+}
+
+/**
+ * Writes an `error` record to the application log.
+ *
+ * @since 1.3.0
+ */
+async function error(message: string): Promise<void> {
+  return log('error', message)
+}
+
+export type { LogLevel }
+
+export { log, trace, debug, info, warn, error }
@@ -0,0 +1,182 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Advertise the services of the app and browse the services of other devices on the local network with multicast DNS (Bonjour).
+ *
+ * This package is also accessible with `window.__TAURI__.mdns` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "mdns": {
+ *         "all": true, // enable all mDNS APIs
+ *         "advertise": true, // enable advertising services
+ *         "browse": true // enable browsing services
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * It is recommended to allowlist only the APIs you use for optimal bundle size and security.
+ *
+ * ## Security
+ *
+ * This API has a scope configuration that forces you to restrict the service types that can be advertised and browsed using glob patterns.
+ *
+ * For instance, this scope configuration only allows the services of the app:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "mdns": {
+ *         "scope": ["_myapp._tcp"]
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * Trying to advertise or browse any service type not configured on the scope results in a promise rejection due to denied access.
+ *
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
+import type { UnlistenFn } from './helpers/event'
+
+/**
+ * A service discovered on the local network.
+ *
+ * @since 1.3.0
+ */
+interface Service {
+  /** The instance name, e.g. `Living Room`. */
+  instanceName: string
+  /** The fully qualified service type, e.g. `_http._tcp.local.`. */
+  serviceType: string
+  /** The host name of the service, e.g. `living-room.local.`. */
+  hostName: string
+  port: number
+  /** The addresses of the host. */
+  addresses: string[]
+  /** The TXT record properties. */
+  properties: Record<string, string>
+}
+
+/**
+ * A service was found or removed.
+ *
+ * @since 1.3.0
+ */
+type BrowseEvent =
+  | { type: 'resolved'; service: Service }
+  | { type: 'removed'; serviceType: string; instanceName: string }
+
+/**
+ * The options of an advertised service.
+ *
+ * @since 1.3.0
+ */
+interface AdvertiseOptions {
+  /** The instance name, unique on the local network. Defaults to the name of the app. */
+  instanceName?: string
+  /** The TXT record properties, e.g. the version of the protocol. */
+  properties?: Record<string, string>
+}
+
+/**
+ * Advertises a service of the app on the local network.
+ * @example
+ * ```typescript
+ * import { advertise } from '@tauri-apps/api/mdns';
+ * const stop = await advertise('_myapp._tcp', 8080, { properties: { version: '1' } });
+ * // stop advertising
+ * await stop();
+ * ```
+ *
+ * @param serviceType The service type, e.g. `_myapp._tcp`.
+ * @param port The port of the service.
+ *
+ * @returns A promise resolving to a function to stop advertising the service.
+ *
+ * @since 1.3.0
+ */
+async function advertise(
+  serviceType: string,
+  port: number,
+  options?: AdvertiseOptions
+): Promise<() => Promise<void>> {
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Mdns',
+    message: {
+      cmd: 'advertise',
+      serviceType,
+      port,
+      ...options
+    }
+  })
+
+  return async () => {
+    await invokeTauriCommand({
+      __tauriModule: 'Mdns',
+      message: {
+        cmd: 'unadvertise',
+        id
+      }
+    })
+  }
+}
+
+/**
+ * Browses the services of the given type on the local network.
+ * @example
+ * ```typescript
+ * import { browse } from '@tauri-apps/api/mdns';
+ * const unlisten = await browse('_myapp._tcp', (event) => {
+ *   if (event.type === 'resolved') {
+ *     console.log(`found ${event.service.instanceName} on ${event.service.addresses}`);
+ *   }
+ * });
+ * // stop browsing
+ * unlisten();
+ * ```
+ *
+ * @param serviceType The service type, e.g. `_myapp._tcp`.
+ *
+ * @returns A promise resolving to a function to stop browsing.
+ *
+ * @since 1.3.0
+ */
+async function browse(
+  serviceType: string,
+  cb: (event: BrowseEvent) => void
+): Promise<UnlistenFn> {
+  const handler = transformCallback(cb)
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Mdns',
+    message: {
+      cmd: 'browse',
+      serviceType,
+      handler
+    }
+  })
+
+  return async () => {
+    await invokeTauriCommand({
+      __tauriModule: 'Mdns',
+      message: {
+        cmd: 'stopBrowse',
+        id
+      }
+    })
+    Reflect.deleteProperty(window, `_${handler}`)
+  }
+}
+
+export type { Service, BrowseEvent, AdvertiseOptions }
+
+export { advertise, browse }
@@ -0,0 +1,122 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Run OAuth 2.0 authorization code flows with PKCE.
+ *
+ * The authorization page is opened on the default browser, which redirects to a listener on `http://127.0.0.1:<port>/callback`,
+ * so that redirect URI must be registered on the provider.
+ *
+ * This package is also accessible with `window.__TAURI__.oauth` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "oauth": {
+ *         "all": true, // enable all OAuth APIs
+ *         "authenticate": true // enable the authorization code flow
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * It is recommended to allowlist only the APIs you use for optimal bundle size and security.
+ *
+ * ## Security
+ *
+ * This API has a scope configuration that forces you to restrict the authorization and token endpoints that can be used with glob patterns.
+ * The endpoints must use HTTPS, or HTTP on the loopback interface.
+ *
+ * For instance, this scope configuration only allows the GitHub endpoints:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "oauth": {
+ *         "scope": ["https://github.com/login/oauth/*"]
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * Trying to use any endpoint not configured on the scope results in a promise rejection due to denied access.
+ *
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+
+/**
+ * The authorization request.
+ *
+ * @since 1.3.0
+ */
+interface AuthRequest {
+  /** The authorization endpoint of the provider, e.g. `https://github.com/login/oauth/authorize`. */
+  authorizationUrl: string
+  /** The token endpoint of the provider, e.g. `https://github.com/login/oauth/access_token`. */
+  tokenUrl: string
+  /** The client identifier of the app. */
+  clientId: string
+  /** The client secret, if the provider requires one for native apps. */
+  clientSecret?: string
+  /** The requested scopes. */
+  scopes?: string[]
+  /** Additional parameters of the authorization request, e.g. `prompt` or `audience`. */
+  params?: Record<string, string>
+  /** The port of the loopback listener. Defaults to a random port. */
+  port?: number
+  /** The time to wait for the user to authorize the app, in seconds. Defaults to 5 minutes. */
+  timeout?: number
+}
+
+/**
+ * The tokens issued by the provider.
+ *
+ * @since 1.3.0
+ */
+interface TokenResponse {
+  accessToken: string
+  tokenType: string
+  /** The lifetime of the access token in seconds. */
+  expiresIn?: number
+  refreshToken?: string
+  /** The granted scopes, if they differ from the requested ones. */
+  scope?: string
+  /** The OpenID Connect ID token. */
+  idToken?: string
+  /** The other fields of the response. */
+  [key: string]: unknown
+}
+
+/**
+ * Opens the authorization page and exchanges the authorization code for the tokens.
+ * @example
+ * ```typescript
+ * import { authenticate } from '@tauri-apps/api/oauth';
+ * const tokens = await authenticate({
+ *   authorizationUrl: 'https://github.com/login/oauth/authorize',
+ *   tokenUrl: 'https://github.com/login/oauth/access_token',
+ *   clientId: 'client-id',
+ *   scopes: ['read:user']
+ * });
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function authenticate(request: AuthRequest): Promise<TokenResponse> {
+  return invokeTauriCommand({
+    __tauriModule: 'OAuth',
+    message: {
+      cmd: 'authenticate',
+      request
+    }
+  })
+}
+
+export type { AuthRequest, TokenResponse }
+
+export { authenticate }
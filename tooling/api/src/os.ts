@@ -14,6 +14,7 @@
  *     "allowlist": {
  *       "os": {
  *         "all": true, // enable all Os APIs
+ *         "networkStatus": true // enable reading and watching the network status, not enabled by `all`
  *       }
  *     }
  *   }
@@ -25,6 +26,8 @@
 
 import { isWindows } from './helpers/os-check'
 import { invokeTauriCommand } from './helpers/tauri'
+import { listen, TauriEvent } from './event'
+import type { EventCallback, UnlistenFn } from './event'
 
 type Platform =
   | 'linux'
@@ -53,6 +56,27 @@ type Arch =
   | 's390x'
   | 'sparc64'
 
+/**
+ * The type of the connection used to reach the internet, `'none'` when offline.
+ *
+ * @since 1.3.0
+ */
+type ConnectionType = 'none' | 'wifi' | 'ethernet' | 'cellular' | 'other'
+
+/**
+ * The network status of the system.
+ *
+ * @since 1.3.0
+ */
+interface NetworkStatus {
+  /** Whether the internet is reachable. */
+  online: boolean
+  /** The type of the connection used to reach the internet. */
+  connectionType: ConnectionType
+  /** Whether the data usage of the connection is limited or charged, as on cellular connections and hotspots. */
+  metered: boolean
+}
+
 /**
  * The operating system-specific end-of-line marker.
  * - `\n` on POSIX
@@ -160,5 +184,58 @@ async function tempdir(): Promise<string> {
   })
 }
 
-export { EOL, platform, version, type, arch, tempdir }
-export type { Platform, OsType, Arch }
+/**
+ * Returns the network status of the system, read from the native APIs instead of `navigator.onLine`.
+ * Requires the `os > networkStatus` allowlist flag.
+ * @example
+ * ```typescript
+ * import { networkStatus } from '@tauri-apps/api/os';
+ * const { online, metered } = await networkStatus();
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function networkStatus(): Promise<NetworkStatus> {
+  return invokeTauriCommand<NetworkStatus>({
+    __tauriModule: 'Os',
+    message: {
+      cmd: 'networkStatus'
+    }
+  })
+}
+
+/**
+ * Listens to the changes of the network status of the system.
+ * Requires the `os > networkStatus` allowlist flag.
+ * @example
+ * ```typescript
+ * import { onNetworkStatusChange } from '@tauri-apps/api/os';
+ * const unlisten = await onNetworkStatusChange(({ payload }) => {
+ *   console.log(`online: ${payload.online}, metered: ${payload.metered}`);
+ * });
+ *
+ * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+ * unlisten();
+ * ```
+ *
+ * @returns A promise resolving to a function to unlisten to the event.
+ *
+ * @since 1.3.0
+ */
+async function onNetworkStatusChange(
+  handler: EventCallback<NetworkStatus>
+): Promise<UnlistenFn> {
+  return listen<NetworkStatus>(TauriEvent.NETWORK_STATUS, handler)
+}
+
+export {
+  EOL,
+  platform,
+  version,
+  type,
+  arch,
+  tempdir,
+  networkStatus,
+  onNetworkStatusChange
+}
+export type { Platform, OsType, Arch, ConnectionType, NetworkStatus }
@@ -14,6 +14,7 @@
  *     "allowlist": {
  *       "path": {
  *         "all": true, // enable all Path APIs
+ *         "volumes": true // enable listing and watching the mounted volumes, not enabled by `all`
  *       }
  *     }
  *   }
@@ -26,6 +27,8 @@
 import { invokeTauriCommand } from './helpers/tauri'
 import { BaseDirectory } from './fs'
 import { isWindows } from './helpers/os-check'
+import { transformCallback } from './tauri'
+import type { UnlistenFn } from './helpers/event'
 
 /**
  * Returns the path to the suggested directory for your app config files.
@@ -810,6 +813,99 @@ async function isAbsolute(path: string): Promise<boolean> {
   })
 }
 
+/**
+ * A mounted drive or volume.
+ *
+ * @since 1.3.0
+ */
+interface Volume {
+  /** The name of the volume, or of its device when it has no label. */
+  name: string
+  /** The path where the volume is mounted. */
+  mountPoint: string
+  /** The capacity in bytes. */
+  totalSpace: number
+  /** The space available to the current user in bytes. */
+  availableSpace: number
+  /** Whether the volume is on a removable drive, such as a USB stick or an SD card. */
+  removable: boolean
+}
+
+/**
+ * A volume was mounted or unmounted.
+ *
+ * @since 1.3.0
+ */
+interface VolumeEvent {
+  type: 'mounted' | 'unmounted'
+  volume: Volume
+}
+
+/**
+ * Returns the mounted drives and volumes.
+ * Requires the `path > volumes` allowlist flag, which is not enabled by `path > all`.
+ * @example
+ * ```typescript
+ * import { volumes } from '@tauri-apps/api/path';
+ * for (const volume of await volumes()) {
+ *   console.log(`${volume.name} mounted on ${volume.mountPoint}`);
+ * }
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function volumes(): Promise<Volume[]> {
+  return invokeTauriCommand<Volume[]>({
+    __tauriModule: 'Path',
+    message: {
+      cmd: 'volumes'
+    }
+  })
+}
+
+/**
+ * Listens to the drives and volumes being mounted or unmounted.
+ * Requires the `path > volumes` allowlist flag, which is not enabled by `path > all`.
+ * @example
+ * ```typescript
+ * import { onVolumeChange } from '@tauri-apps/api/path';
+ * const unlisten = await onVolumeChange((event) => {
+ *   console.log(`${event.volume.name} was ${event.type}`);
+ * });
+ * // stop listening
+ * unlisten();
+ * ```
+ *
+ * @returns A promise resolving to a function to stop listening.
+ *
+ * @since 1.3.0
+ */
+async function onVolumeChange(
+  cb: (event: VolumeEvent) => void
+): Promise<UnlistenFn> {
+  const handler = transformCallback(cb)
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Path',
+    message: {
+      cmd: 'watchVolumes',
+      handler
+    }
+  })
+
+  return async () => {
+    await invokeTauriCommand({
+      __tauriModule: 'Path',
+      message: {
+        cmd: 'unwatchVolumes',
+        id
+      }
+    })
+    Reflect.deleteProperty(window, `_${handler}`)
+  }
+}
+
+export type { Volume, VolumeEvent }
+
 export {
   appDir,
   appConfigDir,
@@ -845,5 +941,7 @@ export {
   dirname,
   extname,
   basename,
-  isAbsolute
+  isAbsolute,
+  volumes,
+  onVolumeChange
 }
@@ -0,0 +1,308 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * List serial ports and USB devices, watch them being connected and disconnected, and communicate with serial ports.
+ *
+ * This package is also accessible with `window.__TAURI__.serial` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "serial": {
+ *         "all": true, // enable all serial APIs
+ *         "list": true, // enable listing and watching the serial ports and USB devices
+ *         "open": true // enable opening serial ports
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * It is recommended to allowlist only the APIs you use for optimal bundle size and security.
+ *
+ * ## Security
+ *
+ * This API has a scope configuration that forces you to restrict the serial ports that can be opened using glob patterns,
+ * matched against the path of the port or the `<vendor id>:<product id>` identifier of its USB device, as lowercase hexadecimal numbers.
+ *
+ * For instance, this scope configuration only allows opening the USB serial ports on Linux and the ports of Arduino boards:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "serial": {
+ *         "scope": ["/dev/ttyUSB*", "2341:*"]
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * Trying to open any port not configured on the scope results in a promise rejection due to denied access,
+ * and the ports and USB devices not configured on the scope are not listed.
+ *
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
+import type { UnlistenFn } from './helpers/event'
+
+/**
+ * The information of a USB device.
+ *
+ * @since 1.3.0
+ */
+interface UsbInfo {
+  vendorId: number
+  productId: number
+  /** The serial number, if the device reports one. */
+  serialNumber: string | null
+  /** The manufacturer name, if the device reports one. */
+  manufacturer: string | null
+  /** The product name, if the device reports one. */
+  product: string | null
+}
+
+/**
+ * A serial port.
+ *
+ * @since 1.3.0
+ */
+interface SerialPortInfo {
+  /** The path of the port, e.g. `/dev/ttyUSB0` or `COM3`. */
+  path: string
+  /** The USB device of the port, when it is a USB serial adapter. */
+  usb: UsbInfo | null
+}
+
+/**
+ * A USB device.
+ *
+ * The devices are not opened, so their strings are only read on Linux.
+ *
+ * @since 1.3.0
+ */
+interface UsbDevice extends UsbInfo {
+  /** The number of the bus the device is connected to. */
+  busNumber: number
+  /** The address of the device on its bus. */
+  address: number
+}
+
+/**
+ * A serial port or a USB device.
+ *
+ * @since 1.3.0
+ */
+type Device =
+  | ({ kind: 'serialPort' } & SerialPortInfo)
+  | ({ kind: 'usb' } & UsbDevice)
+
+/**
+ * A device was connected or disconnected.
+ *
+ * @since 1.3.0
+ */
+interface DeviceEvent {
+  type: 'connected' | 'disconnected'
+  device: Device
+}
+
+/**
+ * The options of a serial port.
+ *
+ * @since 1.3.0
+ */
+interface PortOptions {
+  /** The baud rate. Defaults to 9600. */
+  baudRate?: number
+  /** The number of data bits. Defaults to 8. */
+  dataBits?: 'five' | 'six' | 'seven' | 'eight'
+  /** The parity checking mode. Defaults to no parity. */
+  parity?: 'none' | 'odd' | 'even'
+  /** The number of stop bits. Defaults to 1. */
+  stopBits?: 'one' | 'two'
+  /** The flow control mode. Defaults to no flow control. */
+  flowControl?: 'none' | 'software' | 'hardware'
+  /** The timeout of the read and write operations in milliseconds. Defaults to 1 second. */
+  timeout?: number
+}
+
+/**
+ * The data received on a serial port.
+ *
+ * @since 1.3.0
+ */
+type SerialEvent = { type: 'Data'; data: number[] } | { type: 'Closed' }
+
+/**
+ * Returns the serial ports allowed on the `serial` scope.
+ * @example
+ * ```typescript
+ * import { ports } from '@tauri-apps/api/serial';
+ * for (const port of await ports()) {
+ *   console.log(port.path, port.usb?.product);
+ * }
+ * ```
+ *
+ * @since 1.3.0
+ */
+async function ports(): Promise<SerialPortInfo[]> {
+  return invokeTauriCommand<SerialPortInfo[]>({
+    __tauriModule: 'Serial',
+    message: {
+      cmd: 'ports'
+    }
+  })
+}
+
+/**
+ * Returns the USB devices allowed on the `serial` scope.
+ *
+ * @since 1.3.0
+ */
+async function usbDevices(): Promise<UsbDevice[]> {
+  return invokeTauriCommand<UsbDevice[]>({
+    __tauriModule: 'Serial',
+    message: {
+      cmd: 'usbDevices'
+    }
+  })
+}
+
+/**
+ * Listens to the serial ports and USB devices allowed on the `serial` scope being connected or disconnected.
+ * The devices are checked on the USB hotplug events, or every second on Windows.
+ * @example
+ * ```typescript
+ * import { onDeviceChange } from '@tauri-apps/api/serial';
+ * const unlisten = await onDeviceChange((event) => {
+ *   console.log(`${event.device.kind} ${event.type}`);
+ * });
+ * // stop listening
+ * unlisten();
+ * ```
+ *
+ * @returns A promise resolving to a function to stop listening.
+ *
+ * @since 1.3.0
+ */
+async function onDeviceChange(
+  cb: (event: DeviceEvent) => void
+): Promise<UnlistenFn> {
+  const handler = transformCallback(cb)
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Serial',
+    message: {
+      cmd: 'watchDevices',
+      handler
+    }
+  })
+
+  return async () => {
+    await invokeTauriCommand({
+      __tauriModule: 'Serial',
+      message: {
+        cmd: 'unwatchDevices',
+        id
+      }
+    })
+    Reflect.deleteProperty(window, `_${handler}`)
+  }
+}
+
+/**
+ * An open serial port.
+ *
+ * @example
+ * ```typescript
+ * import { SerialPort } from '@tauri-apps/api/serial';
+ * const port = await SerialPort.open('/dev/ttyUSB0', (event) => {
+ *   if (event.type === 'Data') {
+ *     console.log(new TextDecoder().decode(new Uint8Array(event.data)));
+ *   }
+ * }, { baudRate: 115200 });
+ * await port.write('ping\n');
+ * await port.close();
+ * ```
+ *
+ * @since 1.3.0
+ */
+class SerialPort {
+  /** The port identifier. */
+  id: number
+
+  /** @ignore */
+  private constructor(id: number) {
+    this.id = id
+  }
+
+  /**
+   * Opens the serial port.
+   *
+   * @param path The path of the port, which must be allowed on the `serial` scope.
+   * @param onData The handler of the received data. A `Closed` event is the last one.
+   * @param options The port options.
+   */
+  static async open(
+    path: string,
+    onData: (event: SerialEvent) => void,
+    options?: PortOptions
+  ): Promise<SerialPort> {
+    return invokeTauriCommand<number>({
+      __tauriModule: 'Serial',
+      message: {
+        cmd: 'open',
+        path,
+        options,
+        callback: transformCallback(onData)
+      }
+    }).then((id) => new SerialPort(id))
+  }
+
+  /**
+   * Writes to the port. Strings are encoded as UTF-8.
+   */
+  async write(data: string | number[] | Uint8Array): Promise<void> {
+    const bytes =
+      typeof data === 'string'
+        ? Array.from(new TextEncoder().encode(data))
+        : Array.from(data)
+    return invokeTauriCommand({
+      __tauriModule: 'Serial',
+      message: {
+        cmd: 'write',
+        id: this.id,
+        data: bytes
+      }
+    })
+  }
+
+  /**
+   * Closes the port.
+   */
+  async close(): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Serial',
+      message: {
+        cmd: 'close',
+        id: this.id
+      }
+    })
+  }
+}
+
+export type {
+  UsbInfo,
+  SerialPortInfo,
+  UsbDevice,
+  Device,
+  DeviceEvent,
+  PortOptions,
+  SerialEvent
+}
+
+export { ports, usbDevices, onDeviceChange, SerialPort }
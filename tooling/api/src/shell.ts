@@ -92,6 +92,21 @@ interface SpawnOptions {
    * @since 1.1.0
    *  */
   encoding?: string
+  /**
+   * Runs the command in a pseudo terminal of the given size, so interactive programs behave like they do in a terminal emulator.
+   * Stderr is merged into stdout, and the `data` events of `stdout` are emitted as the output is written instead of line by line.
+   * Requires the `process-pty` feature of the `tauri` crate.
+   *
+   * @since 1.3.0
+   */
+  pty?: { rows: number; cols: number }
+  /**
+   * Tracks the processes spawned by the child, to kill them with {@link Child.killTree} and when the app exits,
+   * even if the child exited before them.
+   *
+   * @since 1.3.0
+   */
+  killTreeOnExit?: boolean
 }
 
 /** @ignore */
@@ -351,6 +366,52 @@ class Child {
     })
   }
 
+  /**
+   * Closes the `stdin`, so the process reads an end of file.
+   * In PTY mode, write the `EOF` control character (`'\x04'`) instead.
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 1.3.0
+   */
+  async closeStdin(): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Shell',
+      message: {
+        cmd: 'stdinClose',
+        pid: this.pid
+      }
+    })
+  }
+
+  /**
+   * Resizes the pseudo terminal of a process spawned with the `pty` option.
+   * @example
+   * ```typescript
+   * import { Command } from '@tauri-apps/api/shell';
+   * const command = new Command('bash', [], { pty: { rows: 24, cols: 80 } });
+   * const child = await command.spawn();
+   * await child.resize(48, 120);
+   * ```
+   *
+   * @param rows The number of rows.
+   * @param cols The number of columns.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 1.3.0
+   */
+  async resize(rows: number, cols: number): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Shell',
+      message: {
+        cmd: 'resizePty',
+        pid: this.pid,
+        rows,
+        cols
+      }
+    })
+  }
+
   /**
    * Kills the child process.
    *
@@ -365,6 +426,25 @@ class Child {
       }
     })
   }
+
+  /**
+   * Kills the child process and all its descendants.
+   * The descendants are only tracked when the child was spawned with the `killTreeOnExit` option, otherwise only the child is killed.
+   * On Linux and macOS the processes that moved to another process group (e.g. daemons) are not killed.
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 1.3.0
+   */
+  async killTree(): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Shell',
+      message: {
+        cmd: 'killChildTree',
+        pid: this.pid
+      }
+    })
+  }
 }
 
 /**
@@ -0,0 +1,216 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Durable storage on SQLite databases in the app data directory.
+ *
+ * A store is both a key-value store of JSON values and a SQL database with versioned migrations.
+ *
+ * This package is also accessible with `window.__TAURI__.store` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "store": {
+ *         "all": true, // enable all store APIs
+ *         "keyValue": true, // enable the get, set, delete and keys APIs
+ *         "sql": true // enable the migrate, execute and select APIs
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * It is recommended to allowlist only the APIs you use for optimal bundle size and security.
+ *
+ * ## Security
+ *
+ * This API has a scope configuration that forces you to restrict the stores that can be opened using glob patterns matched against their names.
+ *
+ * For instance, this scope configuration only allows the `settings` store and the stores whose name starts with `cache-`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "store": {
+ *         "scope": ["settings", "cache-*"]
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * Trying to open any store not configured on the scope results in a promise rejection due to denied access.
+ * The statements cannot attach other database files.
+ *
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+
+/**
+ * A versioned change of the database schema.
+ *
+ * @since 1.3.0
+ */
+interface Migration {
+  /** The version of the schema after the migration, starting at `1`. */
+  version: number
+  /** The description of the migration. */
+  description: string
+  /** The SQL statements of the migration. */
+  sql: string
+}
+
+/**
+ * The result of a statement that does not return rows.
+ *
+ * @since 1.3.0
+ */
+interface ExecuteResult {
+  /** The number of rows changed. */
+  rowsAffected: number
+  /** The rowid of the last inserted row. */
+  lastInsertId: number
+}
+
+/**
+ * A SQLite database in the app data directory, stored on the `<name>.db` file.
+ *
+ * @example
+ * ```typescript
+ * import { Store } from '@tauri-apps/api/store';
+ * const store = new Store('notes');
+ * await store.migrate([
+ *   { version: 1, description: 'create the notes table', sql: 'CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL)' }
+ * ]);
+ * await store.execute('INSERT INTO notes (body) VALUES (?)', ['hello']);
+ * const notes = await store.select<{ id: number; body: string }>('SELECT * FROM notes');
+ * await store.set('theme', 'dark');
+ * ```
+ *
+ * @since 1.3.0
+ */
+class Store {
+  /** The store name. */
+  name: string
+
+  /**
+   * @param name The store name, a file name without extension.
+   */
+  constructor(name: string) {
+    this.name = name
+  }
+
+  /**
+   * Applies the migrations newer than the schema version of the database.
+   *
+   * @returns The schema version after the migrations.
+   */
+  async migrate(migrations: Migration[]): Promise<number> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'migrate',
+        store: this.name,
+        migrations
+      }
+    })
+  }
+
+  /**
+   * Executes a statement, binding the `?` parameters.
+   */
+  async execute(sql: string, params: unknown[] = []): Promise<ExecuteResult> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'execute',
+        store: this.name,
+        sql,
+        params
+      }
+    })
+  }
+
+  /**
+   * Runs a query, binding the `?` parameters, and returns the rows keyed by column name.
+   */
+  async select<T = Record<string, unknown>>(
+    sql: string,
+    params: unknown[] = []
+  ): Promise<T[]> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'select',
+        store: this.name,
+        sql,
+        params
+      }
+    })
+  }
+
+  /**
+   * Reads the value of the key, or `null` if it is not set.
+   */
+  async get<T>(key: string): Promise<T | null> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'get',
+        store: this.name,
+        key
+      }
+    })
+  }
+
+  /**
+   * Sets the value of the key.
+   */
+  async set(key: string, value: unknown): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'set',
+        store: this.name,
+        key,
+        value
+      }
+    })
+  }
+
+  /**
+   * Deletes the key.
+   *
+   * @returns Whether the key was set.
+   */
+  async delete(key: string): Promise<boolean> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'delete',
+        store: this.name,
+        key
+      }
+    })
+  }
+
+  /**
+   * The keys of the key-value store, sorted.
+   */
+  async keys(): Promise<string[]> {
+    return invokeTauriCommand({
+      __tauriModule: 'Store',
+      message: {
+        cmd: 'keys',
+        store: this.name
+      }
+    })
+  }
+}
+
+export type { Migration, ExecuteResult }
+
+export { Store }
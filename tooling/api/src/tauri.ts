@@ -98,6 +98,46 @@ async function invoke<T>(cmd: string, args: InvokeArgs = {}): Promise<T> {
   })
 }
 
+/**
+ * The rejection value of a command that returned a `tauri::command::CommandError`.
+ *
+ * @since 1.3.0
+ */
+interface CommandError<T = unknown> {
+  /** The machine-readable error code. */
+  code: string
+  /** The human-readable error message. */
+  message: string
+  /** The data attached to the error, or `null`. */
+  data: T | null
+}
+
+/**
+ * Checks whether an `invoke` rejection value is a {@link CommandError}.
+ * @example
+ * ```typescript
+ * import { invoke, isCommandError } from '@tauri-apps/api/tauri';
+ * try {
+ *   await invoke('read_config', { path: 'app.toml' });
+ * } catch (e) {
+ *   if (isCommandError(e) && e.code === 'CONFIG_UNREADABLE') {
+ *     // fallback to the default configuration
+ *   }
+ * }
+ * ```
+ *
+ * @since 1.3.0
+ */
+function isCommandError<T = unknown>(error: unknown): error is CommandError<T> {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as CommandError).code === 'string' &&
+    typeof (error as CommandError).message === 'string' &&
+    'data' in error
+  )
+}
+
 /**
  * Convert a device file path to an URL that can be loaded by the webview.
  * Note that `asset:` and `https://asset.localhost` must be added to [`tauri.security.csp`](https://tauri.app/v1/api/config/#securityconfig.csp) in `tauri.conf.json`.
@@ -135,6 +175,173 @@ function convertFileSrc(filePath: string, protocol = 'asset'): string {
     : `${protocol}://localhost/${path}`
 }
 
-export type { InvokeArgs }
+/**
+ * The serialized form of a {@link Channel}.
+ *
+ * @since 1.3.0
+ */
+interface ChannelReference {
+  __TAURI_CHANNEL_ID__: number
+  capacity: number
+}
+
+/**
+ * A bidirectional channel to stream data to and from the Rust side, with bounded buffering on both directions.
+ *
+ * A channel can be created on the webview and passed as a command argument, received by the command as a `tauri::channel::Channel`,
+ * or created on the Rust side and returned by a command, in which case it is converted with {@link Channel.from}.
+ *
+ * Messages sent by Rust are buffered until consumed with {@link Channel.recv} or the {@link Channel.onmessage} handler,
+ * and Rust waits when `capacity` messages are waiting to be consumed.
+ * The promise returned by {@link Channel.send} resolves when the Rust side has room for the message.
+ *
+ * @example
+ * ```typescript
+ * import { invoke, Channel } from '@tauri-apps/api/tauri';
+ * const channel = new Channel<number[]>();
+ * await invoke('stream_samples', { channel });
+ * let samples;
+ * while ((samples = await channel.recv()) !== undefined) {
+ *   console.log(samples);
+ * }
+ * ```
+ *
+ * @since 1.3.0
+ */
+class Channel<T = unknown> {
+  /** The channel identifier. */
+  readonly id: number
+  /** The maximum number of messages buffered on each direction. */
+  readonly capacity: number
+  private readonly queue: T[] = []
+  private readonly waiting: Array<(message: T | undefined) => void> = []
+  private handler?: (message: T) => void
+  private closed = false
+  private unacknowledged = 0
+
+  constructor(capacity = 16, id = uid()) {
+    this.id = id
+    this.capacity = Math.min(Math.max(1, capacity), 1024)
+    // @ts-expect-error defined on the initialization script
+    window.__TAURI_CHANNEL_REGISTER__(this.id, {
+      onmessage: (message: T) => this.push(message),
+      onclose: () => this.finish()
+    })
+  }
+
+  /**
+   * Converts a channel returned by a command to a {@link Channel}.
+   */
+  static from<T = unknown>(reference: ChannelReference): Channel<T> {
+    return new Channel<T>(reference.capacity, reference.__TAURI_CHANNEL_ID__)
+  }
+
+  /**
+   * Sets a handler that consumes every message sent by the Rust side, including the ones already buffered.
+   */
+  set onmessage(handler: (message: T) => void) {
+    this.handler = handler
+    for (const message of this.queue.splice(0)) {
+      this.consume(message)
+    }
+  }
+
+  /**
+   * Receives the next message sent by the Rust side.
+   *
+   * @returns The message, or `undefined` if the channel was closed.
+   */
+  async recv(): Promise<T | undefined> {
+    if (this.queue.length > 0) {
+      const message = this.queue.shift() as T
+      this.acknowledge()
+      return message
+    }
+    if (this.closed) {
+      return undefined
+    }
+    return new Promise((resolve) => this.waiting.push(resolve))
+  }
+
+  /**
+   * Sends a message to the Rust side, resolving when it has room for it.
+   *
+   * The channel must have been passed to a command or returned by one before,
+   * otherwise the promise is rejected.
+   */
+  async send(message: T): Promise<void> {
+    return invoke('tauri', {
+      __tauriModule: 'Channel',
+      message: {
+        cmd: 'send',
+        id: this.id,
+        message
+      }
+    })
+  }
+
+  /**
+   * Closes the channel on both sides.
+   */
+  async close(): Promise<void> {
+    this.finish()
+    // @ts-expect-error defined on the initialization script
+    window.__TAURI_CHANNEL_REGISTER__(this.id, null)
+    return invoke('tauri', {
+      __tauriModule: 'Channel',
+      message: {
+        cmd: 'close',
+        id: this.id
+      }
+    })
+  }
+
+  toJSON(): ChannelReference {
+    return { __TAURI_CHANNEL_ID__: this.id, capacity: this.capacity }
+  }
+
+  private push(message: T): void {
+    const waiting = this.waiting.shift()
+    if (waiting) {
+      waiting(message)
+      this.acknowledge()
+    } else if (this.handler) {
+      this.consume(message)
+    } else {
+      this.queue.push(message)
+    }
+  }
+
+  private consume(message: T): void {
+    this.handler?.(message)
+    this.acknowledge()
+  }
+
+  private finish(): void {
+    this.closed = true
+    for (const resolve of this.waiting.splice(0)) {
+      resolve(undefined)
+    }
+  }
+
+  private acknowledge(): void {
+    this.unacknowledged += 1
+    // acknowledge in batches to reduce the IPC traffic
+    if (this.unacknowledged >= Math.max(1, Math.floor(this.capacity / 2))) {
+      const count = this.unacknowledged
+      this.unacknowledged = 0
+      void invoke('tauri', {
+        __tauriModule: 'Channel',
+        message: {
+          cmd: 'ack',
+          id: this.id,
+          count
+        }
+      })
+    }
+  }
+}
+
+export type { InvokeArgs, ChannelReference, CommandError }
 
-export { transformCallback, invoke, convertFileSrc }
+export { transformCallback, invoke, isCommandError, convertFileSrc, Channel }
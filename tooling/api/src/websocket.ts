@@ -0,0 +1,212 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Connect to WebSocket servers through the Rust side, bypassing the CORS and mixed content rules of the webview.
+ *
+ * This package is also accessible with `window.__TAURI__.websocket` when [`build.withGlobalTauri`](https://tauri.app/v1/api/config/#buildconfig.withglobaltauri) in `tauri.conf.json` is set to `true`.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "websocket": {
+ *         "all": true, // enable all WebSocket APIs
+ *         "connect": true // enable the connect API
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * It is recommended to allowlist only the APIs you use for optimal bundle size and security.
+ *
+ * ## Security
+ *
+ * This API has a scope configuration that forces you to restrict the URLs that can be connected to using glob patterns.
+ *
+ * For instance, this scope configuration only allows connecting to secure servers on `example.com` subdomains:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "websocket": {
+ *         "scope": ["wss://*.example.com/**"]
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * Trying to connect to any URL not configured on the scope results in a promise rejection due to denied access.
+ *
+ * @module
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
+
+/**
+ * The options of a WebSocket connection.
+ *
+ * @since 1.3.0
+ */
+interface ConnectionConfig {
+  /** The maximum size of a message. Defaults to 64 MiB. */
+  maxMessageSize?: number
+  /** The maximum size of a single message frame. Defaults to 16 MiB. */
+  maxFrameSize?: number
+  /** Additional headers of the handshake request, e.g. `Authorization`. */
+  headers?: Record<string, string>
+}
+
+/**
+ * The close frame of a connection.
+ *
+ * @since 1.3.0
+ */
+interface CloseFrame {
+  /** The status code, see https://www.rfc-editor.org/rfc/rfc6455#section-7.4. */
+  code: number
+  reason: string
+}
+
+/**
+ * A WebSocket message.
+ *
+ * @since 1.3.0
+ */
+type Message =
+  | { type: 'Text'; data: string }
+  | { type: 'Binary'; data: Uint8Array }
+  | { type: 'Ping'; data: Uint8Array }
+  | { type: 'Pong'; data: Uint8Array }
+  | { type: 'Close'; data: CloseFrame | null }
+
+/** A message as sent on the IPC, with its binary data encoded as base64. */
+type RawMessage =
+  | { type: 'Text'; data: string }
+  | { type: 'Binary' | 'Ping' | 'Pong'; data: string }
+  | { type: 'Close'; data: CloseFrame | null }
+
+function fromRawMessage(message: RawMessage): Message {
+  switch (message.type) {
+    case 'Binary':
+    case 'Ping':
+    case 'Pong':
+      return {
+        type: message.type,
+        data: Uint8Array.from(atob(message.data), (c) => c.charCodeAt(0))
+      }
+    default:
+      return message
+  }
+}
+
+function toRawMessage(message: Message): RawMessage {
+  switch (message.type) {
+    case 'Binary':
+    case 'Ping':
+    case 'Pong':
+      return {
+        type: message.type,
+        // spreading large arrays into `String.fromCharCode` overflows the stack
+        data: btoa(
+          message.data.reduce((s, byte) => s + String.fromCharCode(byte), '')
+        )
+      }
+    default:
+      return message
+  }
+}
+
+/**
+ * A WebSocket connection made from the Rust side.
+ *
+ * @example
+ * ```typescript
+ * import { WebSocket } from '@tauri-apps/api/websocket';
+ * const socket = await WebSocket.connect('wss://echo.websocket.events', (message) => {
+ *   console.log(message);
+ * });
+ * await socket.send('hello');
+ * await socket.disconnect();
+ * ```
+ *
+ * @since 1.3.0
+ */
+class WebSocket {
+  /** The connection identifier. */
+  id: number
+
+  /** @ignore */
+  private constructor(id: number) {
+    this.id = id
+  }
+
+  /**
+   * Connects to the WebSocket server.
+   *
+   * @param url The server URL, which must be allowed on the `websocket` scope.
+   * @param onMessage The handler of the received messages. A `Close` message is the last one.
+   * @param config The connection options.
+   */
+  static async connect(
+    url: string,
+    onMessage: (message: Message) => void,
+    config?: ConnectionConfig
+  ): Promise<WebSocket> {
+    return invokeTauriCommand<number>({
+      __tauriModule: 'Websocket',
+      message: {
+        cmd: 'connect',
+        url,
+        callback: transformCallback((message: RawMessage) =>
+          onMessage(fromRawMessage(message))
+        ),
+        config
+      }
+    }).then((id) => new WebSocket(id))
+  }
+
+  /**
+   * Sends a message. Strings are sent as text messages and arrays of bytes as binary messages.
+   */
+  async send(message: Message | string | number[] | Uint8Array): Promise<void> {
+    let m: Message
+    if (typeof message === 'string') {
+      m = { type: 'Text', data: message }
+    } else if (Array.isArray(message)) {
+      m = { type: 'Binary', data: Uint8Array.from(message) }
+    } else if (message instanceof Uint8Array) {
+      m = { type: 'Binary', data: message }
+    } else {
+      m = message
+    }
+    return invokeTauriCommand({
+      __tauriModule: 'Websocket',
+      message: {
+        cmd: 'send',
+        id: this.id,
+        message: toRawMessage(m)
+      }
+    })
+  }
+
+  /**
+   * Closes the connection.
+   */
+  async disconnect(): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Websocket',
+      message: {
+        cmd: 'disconnect',
+        id: this.id
+      }
+    })
+  }
+}
+
+export type { ConnectionConfig, CloseFrame, Message }
+
+export { WebSocket }
@@ -103,6 +103,31 @@ type FileDropEvent =
   | { type: 'drop'; paths: string[] }
   | { type: 'cancel' }
 
+/**
+ * The data other than files dropped on the webview.
+ *
+ * @since 1.3.0
+ */
+interface DropData {
+  /** The position of the cursor, relative to the webview. */
+  position: { x: number; y: number }
+  /** The dropped data by MIME type, e.g. `text/plain`, `text/uri-list` or a custom type. */
+  items: Record<string, string>
+}
+
+/**
+ * A drag carrying data other than files entered the webview.
+ * The data itself is only available when it is dropped.
+ *
+ * @since 1.3.0
+ */
+interface DropHover {
+  /** The position of the cursor, relative to the webview. */
+  position: { x: number; y: number }
+  /** The accepted MIME types of the dragged data. */
+  types: string[]
+}
+
 /**
  * A size represented in logical pixels.
  *
@@ -709,6 +734,33 @@ class WindowManager extends WebviewWindowHandle {
     })
   }
 
+  /**
+   * Gets the group of the window, added with `Window::set_group` on the Rust side.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/api/window';
+   * const group = await appWindow.group();
+   * ```
+   *
+   * @returns The window group, or `null` if the window is not in a group.
+   *
+   * @since 1.3.0
+   * */
+  async group(): Promise<string | null> {
+    return invokeTauriCommand({
+      __tauriModule: 'Window',
+      message: {
+        cmd: 'manage',
+        data: {
+          label: this.label,
+          cmd: {
+            type: 'group'
+          }
+        }
+      }
+    })
+  }
+
   // Setters
 
   /**
@@ -1804,6 +1856,54 @@ class WindowManager extends WebviewWindowHandle {
     }
   }
 
+  /**
+   * Listen to the drops of data other than files on the webview, such as text and URLs dragged from other apps.
+   * Only the MIME types accepted with the `acceptDropTypes` window option are dropped.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/api/window";
+   * const unlisten = await appWindow.onDrop((event) => {
+   *   console.log('User dropped', event.payload.items['text/uri-list']);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 1.3.0
+   */
+  async onDrop(handler: EventCallback<DropData>): Promise<UnlistenFn> {
+    return this.listen<DropData>(TauriEvent.WINDOW_DROP, handler)
+  }
+
+  /**
+   * Listen to the drags of data other than files entering the webview.
+   * Only the drags carrying MIME types accepted with the `acceptDropTypes` window option are reported.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/api/window";
+   * const unlisten = await appWindow.onDropHover((event) => {
+   *   console.log('User is dragging', event.payload.types);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 1.3.0
+   */
+  async onDropHover(handler: EventCallback<DropHover>): Promise<UnlistenFn> {
+    return this.listen<DropHover>(TauriEvent.WINDOW_DROP_HOVER, handler)
+  }
+
   /**
    * Listen to the system theme change.
    *
@@ -2060,6 +2160,32 @@ interface WindowOptions {
    * The user agent for the webview.
    */
   userAgent?: string
+  /**
+   * Whether the webview renders with the GPU. Not supported on macOS. Defaults to `true`.
+   *
+   * @since 1.3.0
+   */
+  hardwareAcceleration?: boolean
+  /**
+   * Whether the webview composites its layers with the GPU. Not supported on macOS. Defaults to `true`.
+   *
+   * @since 1.3.0
+   */
+  gpuCompositing?: boolean
+  /**
+   * Whether WebGL is available to the webview. Not supported on macOS. Defaults to `true`.
+   *
+   * @since 1.3.0
+   */
+  webgl?: boolean
+  /**
+   * The MIME types of the data other than files that can be dropped on the webview, such as `text/uri-list` or `text/*`.
+   * The drags and drops are emitted as the `tauri://drop-hover` and `tauri://drop` events, see {@link WindowManager.onDropHover} and {@link WindowManager.onDrop}.
+   * On Windows, the file drop handler must be disabled with `fileDropEnabled`.
+   *
+   * @since 1.3.0
+   */
+  acceptDropTypes?: string[]
 }
 
 function mapMonitor(m: Monitor | null): Monitor | null {
@@ -2171,5 +2297,7 @@ export type {
   Monitor,
   ScaleFactorChanged,
   FileDropEvent,
+  DropData,
+  DropHover,
   WindowOptions
 }
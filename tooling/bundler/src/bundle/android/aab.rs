@@ -0,0 +1,44 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{generate_project, gradle, variant};
+use crate::{bundle::common, Settings};
+
+use log::{info, warn};
+
+use std::path::PathBuf;
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the .aab was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let project_dir = generate_project(settings)?;
+  let variant = variant(settings);
+
+  let aab_name = format!(
+    "{}_{}.aab",
+    settings.product_name(),
+    settings.version_string()
+  );
+  let aab_path = settings
+    .project_out_directory()
+    .join("bundle/aab")
+    .join(&aab_name);
+
+  info!(action = "Bundling"; "{} ({})", aab_name, aab_path.display());
+
+  gradle(&project_dir, "bundle", variant)?;
+
+  if !settings.android().debug && settings.android().signing.is_none() {
+    warn!("The AAB is not signed. The Play Store requires it to be signed with your upload key, set in `tauri.conf.json > tauri > bundle > android > signing`.");
+  }
+  common::copy_file(
+    project_dir
+      .join("app/build/outputs/bundle")
+      .join(variant)
+      .join(format!("app-{}.aab", variant)),
+    &aab_path,
+  )?;
+
+  Ok(vec![aab_path])
+}
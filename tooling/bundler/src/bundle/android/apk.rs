@@ -0,0 +1,47 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{abi, generate_project, gradle, variant};
+use crate::{bundle::common, Settings};
+
+use log::{info, warn};
+
+use std::path::PathBuf;
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the .apk was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let project_dir = generate_project(settings)?;
+  let variant = variant(settings);
+
+  let apk_name = format!(
+    "{}_{}_{}.apk",
+    settings.product_name(),
+    settings.version_string(),
+    abi(settings.target())?
+  );
+  let apk_path = settings
+    .project_out_directory()
+    .join("bundle/apk")
+    .join(&apk_name);
+
+  info!(action = "Bundling"; "{} ({})", apk_name, apk_path.display());
+
+  gradle(&project_dir, "assemble", variant)?;
+
+  let output_dir = project_dir.join("app/build/outputs/apk").join(variant);
+  let signed = output_dir.join(format!("app-{}.apk", variant));
+  if signed.exists() {
+    common::copy_file(signed, &apk_path)?;
+  } else {
+    // Gradle only signs the release APK with the configured key
+    warn!("The APK is not signed and can't be installed. Set `tauri.conf.json > tauri > bundle > android > signing` to sign it.");
+    common::copy_file(
+      output_dir.join(format!("app-{}-unsigned.apk", variant)),
+      &apk_path,
+    )?;
+  }
+
+  Ok(vec![apk_path])
+}
@@ -0,0 +1,407 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// The Android bundles are built by Gradle from a project generated like:
+//
+// bundle/android
+//     settings.gradle           # The Gradle project
+//     local.properties          # The path to the Android SDK
+//     gradlew                   # The Gradle wrapper, pinning the Gradle version
+//     app
+//         build.gradle          # The application id, versions and signing key
+//         src/main
+//             AndroidManifest.xml
+//             java/...          # The activity, IPC bridge and webview client bound to the Rust library
+//             assets            # The frontend
+//             jniLibs/<abi>     # The Rust library
+//             res               # The launcher icons
+//
+// The Kotlin classes call the functions exported by `wry::android_binding!` in the Rust library,
+// so the app must invoke it with the domain and name of its package, e.g. `android_binding!(com_example, app, main)`
+// for the `com.example.app` identifier. The binding is not part of the wry release used by `tauri-runtime-wry` yet,
+// so the bundle is refused when the library doesn't export it instead of producing an app crashing on launch.
+//
+// The project is kept between builds so Gradle can reuse its caches.
+
+pub mod aab;
+pub mod apk;
+
+use super::common::{self, CommandExt};
+use crate::Settings;
+
+use anyhow::Context;
+use handlebars::Handlebars;
+use log::warn;
+use serde::Serialize;
+
+use std::{
+  env::var_os,
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// The Gradle version of the wrapper, supported by the Android Gradle plugin of `build.gradle`.
+const GRADLE_VERSION: &str = "7.5.1";
+
+#[derive(Serialize)]
+struct ProjectData<'a> {
+  package: String,
+  product_name: &'a str,
+  lib_name: &'a str,
+  version: &'a str,
+  version_code: u32,
+  min_sdk_version: u32,
+  target_sdk_version: u32,
+  keystore: Option<String>,
+  key_alias: Option<&'a str>,
+  icon: bool,
+}
+
+/// Generates the Gradle project in `bundle/android`, returning its path.
+fn generate_project(settings: &Settings) -> crate::Result<PathBuf> {
+  warn!("Android bundle support is still experimental.");
+
+  let android = settings.android();
+  let project_dir = settings.project_out_directory().join("bundle/android");
+  let main_dir = project_dir.join("app/src/main");
+
+  let sdk_dir = var_os("ANDROID_HOME")
+    .or_else(|| var_os("ANDROID_SDK_ROOT"))
+    .context("ANDROID_HOME must be set to the path of the Android SDK")?;
+  fs::create_dir_all(&project_dir)?;
+  fs::write(
+    project_dir.join("local.properties"),
+    format!(
+      "sdk.dir={}\n",
+      escape_property(&PathBuf::from(sdk_dir).display().to_string())
+    ),
+  )?;
+
+  let package = package_name(settings.bundle_identifier());
+  let lib_path = settings
+    .project_out_directory()
+    .join(format!("lib{}.so", android.lib_name));
+  let lib =
+    fs::read(&lib_path).with_context(|| format!("failed to read {}", lib_path.display()))?;
+  let entry_point = jni_symbol(&package, "TauriActivity", "create");
+  if !contains(&lib, entry_point.as_bytes()) {
+    return Err(anyhow::anyhow!(
+      "{} doesn't export `{}`, the Android runtime support of the app must be provided with `wry::android_binding!`",
+      lib_path.display(),
+      entry_point
+    )
+    .into());
+  }
+  let jni_libs_dir = main_dir.join("jniLibs");
+  clear_dir(&jni_libs_dir)?;
+  common::copy_file(
+    &lib_path,
+    jni_libs_dir
+      .join(abi(settings.target())?)
+      .join(lib_path.file_name().unwrap()),
+  )?;
+
+  let assets_dir = main_dir.join("assets");
+  clear_dir(&assets_dir)?;
+  for asset in &android.assets {
+    if asset.is_dir() {
+      copy_dir_contents(asset, &assets_dir)?;
+    } else {
+      common::copy_file(asset, assets_dir.join(asset.file_name().unwrap()))?;
+    }
+  }
+
+  let res_dir = main_dir.join("res");
+  clear_dir(&res_dir)?;
+  let icon = match android.icons_dir.as_ref().filter(|dir| dir.is_dir()) {
+    Some(icons_dir) => {
+      copy_dir_contents(icons_dir, &res_dir)?;
+      true
+    }
+    // without the icons generated by `tauri icon`, the largest PNG icon is used for every density
+    None => match settings
+      .icon_files()
+      .filter_map(Result::ok)
+      .filter(|path| path.extension().map_or(false, |ext| ext == "png"))
+      .max_by_key(|path| image::image_dimensions(path).map_or(0, |(width, _)| width))
+    {
+      Some(icon) => {
+        common::copy_file(icon, res_dir.join("mipmap/ic_launcher.png"))?;
+        true
+      }
+      None => false,
+    },
+  };
+
+  let data = ProjectData {
+    package,
+    product_name: settings.product_name(),
+    lib_name: &android.lib_name,
+    version: settings.version_string(),
+    version_code: android
+      .version_code
+      .unwrap_or_else(|| version_code(settings.version_string())),
+    min_sdk_version: android.min_sdk_version,
+    target_sdk_version: android.target_sdk_version,
+    keystore: android
+      .signing
+      .as_ref()
+      .map(|signing| {
+        // Gradle resolves relative paths from the `app` directory
+        signing
+          .keystore
+          .canonicalize()
+          .with_context(|| format!("failed to find {}", signing.keystore.display()))
+          // backslashes are escape characters in Groovy strings
+          .map(|path| path.display().to_string().replace('\\', "/"))
+      })
+      .transpose()?,
+    key_alias: android
+      .signing
+      .as_ref()
+      .map(|signing| signing.key_alias.as_str()),
+    icon,
+  };
+
+  render_templates(&project_dir, &data)?;
+
+  if !project_dir.join(gradlew()).exists() {
+    Command::new(if cfg!(windows) {
+      "gradle.bat"
+    } else {
+      "gradle"
+    })
+    .args(["wrapper", "--gradle-version", GRADLE_VERSION])
+    .current_dir(&project_dir)
+    .output_ok()
+    .context("error running `gradle wrapper`, make sure Gradle is installed and in your PATH to generate the Gradle wrapper")?;
+  }
+
+  Ok(project_dir)
+}
+
+/// Renders the Gradle project files and the Kotlin classes of the package.
+fn render_templates(project_dir: &Path, data: &ProjectData<'_>) -> crate::Result<()> {
+  let handlebars = Handlebars::new();
+  let render = |path: &str, template: &str| -> crate::Result<()> {
+    let path = project_dir.join(path);
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(path, handlebars.render_template(template, &data)?)?;
+    Ok(())
+  };
+  for (path, template) in [
+    ("settings.gradle", include_str!("templates/settings.gradle")),
+    ("build.gradle", include_str!("templates/build.gradle")),
+    (
+      "gradle.properties",
+      include_str!("templates/gradle.properties"),
+    ),
+    ("app/build.gradle", include_str!("templates/app.gradle")),
+    (
+      "app/src/main/AndroidManifest.xml",
+      include_str!("templates/AndroidManifest.xml"),
+    ),
+  ] {
+    render(path, template)?;
+  }
+  let kotlin_dir = format!("app/src/main/java/{}", data.package.replace('.', "/"));
+  for (name, template) in [
+    ("MainActivity.kt", include_str!("templates/MainActivity.kt")),
+    (
+      "TauriActivity.kt",
+      include_str!("templates/TauriActivity.kt"),
+    ),
+    ("Ipc.kt", include_str!("templates/Ipc.kt")),
+    (
+      "RustWebViewClient.kt",
+      include_str!("templates/RustWebViewClient.kt"),
+    ),
+  ] {
+    render(&format!("{}/{}", kotlin_dir, name), template)?;
+  }
+  Ok(())
+}
+
+/// The Gradle wrapper script.
+fn gradlew() -> &'static str {
+  if cfg!(windows) {
+    "gradlew.bat"
+  } else {
+    "gradlew"
+  }
+}
+
+/// The Gradle build variant of the bundles.
+fn variant(settings: &Settings) -> &'static str {
+  if settings.android().debug {
+    "debug"
+  } else {
+    "release"
+  }
+}
+
+/// Runs a Gradle task for the variant with the Gradle wrapper, e.g. `assembleRelease`.
+fn gradle(project_dir: &Path, task: &str, variant: &str) -> crate::Result<()> {
+  let task = format!("{}{}{}", task, variant[..1].to_uppercase(), &variant[1..]);
+  Command::new(project_dir.join(gradlew()))
+    .arg(&task)
+    .current_dir(project_dir)
+    .output_ok()
+    .with_context(|| format!("error running `gradlew {}`", task))?;
+  Ok(())
+}
+
+/// The Android ABI of a Rust target triple.
+fn abi(target: &str) -> crate::Result<&'static str> {
+  match target.split('-').next() {
+    Some("aarch64") => Ok("arm64-v8a"),
+    Some("armv7") | Some("thumbv7neon") => Ok("armeabi-v7a"),
+    Some("i686") => Ok("x86"),
+    Some("x86_64") => Ok("x86_64"),
+    _ => Err(crate::Error::ArchError(format!(
+      "Unsupported Android target {}",
+      target
+    ))),
+  }
+}
+
+/// Java package names can't contain hyphens, which are allowed in the bundle identifier.
+fn package_name(identifier: &str) -> String {
+  identifier.replace('-', "_")
+}
+
+/// Derives the version code from the version, so every release gets a greater version code.
+fn version_code(version: &str) -> u32 {
+  let mut parts = version
+    .split(['-', '+'])
+    .next()
+    .unwrap_or_default()
+    .split('.')
+    .map(|part| part.parse::<u32>().unwrap_or_default());
+  let major = parts.next().unwrap_or_default();
+  let minor = parts.next().unwrap_or_default();
+  let patch = parts.next().unwrap_or_default();
+  major * 1_000_000 + minor * 1_000 + patch
+}
+
+/// The name of the JNI function implementing a native method of a class of the package.
+fn jni_symbol(package: &str, class: &str, method: &str) -> String {
+  let mangle = |name: &str| name.replace('_', "_1").replace('.', "_");
+  format!(
+    "Java_{}_{}_{}",
+    mangle(package),
+    mangle(class),
+    mangle(method)
+  )
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+  haystack
+    .windows(needle.len())
+    .any(|window| window == needle)
+}
+
+fn escape_property(value: &str) -> String {
+  value.replace('\\', "\\\\").replace(':', "\\:")
+}
+
+fn clear_dir(dir: &Path) -> crate::Result<()> {
+  if dir.exists() {
+    fs::remove_dir_all(dir)?;
+  }
+  fs::create_dir_all(dir)?;
+  Ok(())
+}
+
+fn copy_dir_contents(from: &Path, to: &Path) -> crate::Result<()> {
+  for entry in walkdir::WalkDir::new(from) {
+    let entry = entry?;
+    if entry.file_type().is_file() {
+      common::copy_file(entry.path(), to.join(entry.path().strip_prefix(from)?))?;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{abi, jni_symbol, package_name, render_templates, version_code, ProjectData};
+
+  #[test]
+  fn maps_targets_to_abis() {
+    assert_eq!(abi("aarch64-linux-android").unwrap(), "arm64-v8a");
+    assert_eq!(abi("armv7-linux-androideabi").unwrap(), "armeabi-v7a");
+    assert_eq!(abi("i686-linux-android").unwrap(), "x86");
+    assert_eq!(abi("x86_64-linux-android").unwrap(), "x86_64");
+    assert!(abi("mips-unknown-linux-gnu").is_err());
+  }
+
+  #[test]
+  fn derives_version_code() {
+    assert_eq!(version_code("1.2.3"), 1_002_003);
+    assert_eq!(version_code("0.10.0-beta.2"), 10_000);
+    assert_eq!(version_code("2.0.1+build.5"), 2_000_001);
+  }
+
+  #[test]
+  fn sanitizes_package_name() {
+    assert_eq!(package_name("com.my-app.desktop"), "com.my_app.desktop");
+  }
+
+  #[test]
+  fn mangles_jni_symbols() {
+    assert_eq!(
+      jni_symbol("com.example.app", "TauriActivity", "create"),
+      "Java_com_example_app_TauriActivity_create"
+    );
+    assert_eq!(
+      jni_symbol("com.my_app.desktop", "TauriActivity", "create"),
+      "Java_com_my_1app_desktop_TauriActivity_create"
+    );
+  }
+
+  #[test]
+  fn renders_templates() {
+    let project_dir = tempfile::tempdir().unwrap();
+    let data = ProjectData {
+      package: "com.example.app".into(),
+      product_name: "Example",
+      lib_name: "example_lib",
+      version: "1.2.3",
+      version_code: 1_002_003,
+      min_sdk_version: 24,
+      target_sdk_version: 33,
+      keystore: None,
+      key_alias: None,
+      icon: false,
+    };
+    render_templates(project_dir.path(), &data).unwrap();
+
+    for path in [
+      "settings.gradle",
+      "build.gradle",
+      "gradle.properties",
+      "app/build.gradle",
+      "app/src/main/AndroidManifest.xml",
+    ] {
+      assert!(
+        project_dir.path().join(path).is_file(),
+        "{} is missing",
+        path
+      );
+    }
+    let kotlin_dir = project_dir.path().join("app/src/main/java/com/example/app");
+    let activity = std::fs::read_to_string(kotlin_dir.join("TauriActivity.kt")).unwrap();
+    assert!(activity.starts_with("package com.example.app\n"));
+    assert!(activity.contains("System.loadLibrary(\"example_lib\")"));
+    for class in ["MainActivity.kt", "Ipc.kt", "RustWebViewClient.kt"] {
+      assert!(kotlin_dir.join(class).is_file(), "{} is missing", class);
+    }
+    let gradle = std::fs::read_to_string(project_dir.path().join("app/build.gradle")).unwrap();
+    assert!(gradle.contains("com.example.app"));
+    assert!(gradle.contains("1.2.3"));
+    assert!(!gradle.contains("signingConfig"));
+  }
+}
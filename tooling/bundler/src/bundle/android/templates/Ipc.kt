@@ -0,0 +1,13 @@
+package {{package}}
+
+import android.webkit.JavascriptInterface
+
+// added to the webview as `window.ipc`, the bridge of the Tauri commands
+class Ipc {
+  @JavascriptInterface
+  fun postMessage(message: String) {
+    ipc(message)
+  }
+
+  private external fun ipc(message: String)
+}
@@ -0,0 +1,3 @@
+package {{package}}
+
+class MainActivity : TauriActivity()
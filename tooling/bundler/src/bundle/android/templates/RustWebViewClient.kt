@@ -0,0 +1,18 @@
+package {{package}}
+
+import android.webkit.WebResourceRequest
+import android.webkit.WebResourceResponse
+import android.webkit.WebView
+import android.webkit.WebViewClient
+
+// serves the custom protocols, including the frontend assets, from the Rust library
+class RustWebViewClient : WebViewClient() {
+  override fun shouldInterceptRequest(
+    view: WebView,
+    request: WebResourceRequest
+  ): WebResourceResponse? {
+    return handleRequest(request)
+  }
+
+  private external fun handleRequest(request: WebResourceRequest): WebResourceResponse?
+}
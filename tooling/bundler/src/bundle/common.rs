@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use super::settings::ToolchainSettings;
 use log::debug;
+use sha2::Digest;
 
 use std::{
   ffi::OsStr,
@@ -135,6 +137,45 @@ pub fn copy_dir(from: &Path, to: &Path) -> crate::Result<()> {
   Ok(())
 }
 
+/// Whether the SHA-256 checksum of the data is the hex encoded `hash`.
+pub fn sha256_matches(data: &[u8], hash: &str) -> crate::Result<bool> {
+  let mut hasher = sha2::Sha256::new();
+  hasher.update(data);
+  Ok(hasher.finalize().to_vec() == hex::decode(hash)?)
+}
+
+/// Reads a tool from the local toolchain directory, verifying its SHA-256 checksum.
+///
+/// The checksum configured for the file name takes precedence over `pinned_hash`,
+/// the checksum of the tool known by the bundler.
+#[allow(dead_code)]
+pub fn read_toolchain_file(
+  toolchain: &ToolchainSettings,
+  name: &str,
+  pinned_hash: Option<&str>,
+) -> crate::Result<Vec<u8>> {
+  let hash = toolchain
+    .sha256
+    .get(name)
+    .map(String::as_str)
+    .or(pinned_hash)
+    .ok_or_else(|| {
+      crate::Error::ToolchainError(format!("missing the SHA-256 checksum of `{}`", name))
+    })?;
+  let path = toolchain.dir.join(name);
+  let data = fs::read(&path).map_err(|e| {
+    crate::Error::ToolchainError(format!("failed to read {}: {}", path.display(), e))
+  })?;
+  if sha256_matches(&data, hash)? {
+    Ok(data)
+  } else {
+    Err(crate::Error::ToolchainError(format!(
+      "checksum mismatch of {}",
+      path.display()
+    )))
+  }
+}
+
 pub trait CommandExt {
   fn output_ok(&mut self) -> crate::Result<Output>;
 }
@@ -205,8 +246,9 @@ impl CommandExt for Command {
 
 #[cfg(test)]
 mod tests {
-  use super::{create_file, is_retina};
-  use std::{io::Write, path::PathBuf};
+  use super::{create_file, is_retina, read_toolchain_file};
+  use crate::bundle::settings::ToolchainSettings;
+  use std::{collections::HashMap, io::Write, path::PathBuf};
   use tauri_utils::resources::resource_relpath;
 
   #[test]
@@ -273,6 +315,40 @@ mod tests {
     );
   }
 
+  #[test]
+  fn verifies_toolchain_files() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    std::fs::write(tmp.path().join("AppRun-x86_64"), b"hello").expect("unable to write file");
+    let hello_sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    let mut toolchain = ToolchainSettings {
+      dir: tmp.path().to_path_buf(),
+      sha256: HashMap::new(),
+    };
+    // no checksum
+    assert!(read_toolchain_file(&toolchain, "AppRun-x86_64", None).is_err());
+    assert_eq!(
+      read_toolchain_file(&toolchain, "AppRun-x86_64", Some(hello_sha256)).unwrap(),
+      b"hello"
+    );
+
+    toolchain
+      .sha256
+      .insert("AppRun-x86_64".into(), hello_sha256.to_uppercase());
+    assert_eq!(
+      read_toolchain_file(&toolchain, "AppRun-x86_64", None).unwrap(),
+      b"hello"
+    );
+    // the configured checksum takes precedence
+    toolchain.sha256.insert(
+      "AppRun-x86_64".into(),
+      "0000000000000000000000000000000000000000000000000000000000000000".into(),
+    );
+    assert!(read_toolchain_file(&toolchain, "AppRun-x86_64", Some(hello_sha256)).is_err());
+    // missing file
+    assert!(read_toolchain_file(&toolchain, "AppRun-i686", Some(hello_sha256)).is_err());
+  }
+
   #[test]
   fn retina_icon_paths() {
     assert!(!is_retina("data/icons/512x512.png"));
@@ -4,7 +4,10 @@
 // SPDX-License-Identifier: MIT
 
 use super::{
-  super::{common::CommandExt, path_utils},
+  super::{
+    common::{read_toolchain_file, CommandExt},
+    path_utils,
+  },
   debian,
 };
 use crate::Settings;
@@ -56,13 +59,37 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   sh_map.insert("app_name", settings.main_binary_name());
   sh_map.insert("app_name_uppercase", &upcase_app_name);
   sh_map.insert("appimage_filename", &appimage_filename);
-  let tauri_tools_path = dirs_next::cache_dir().map_or_else(
-    || output_path.to_path_buf(),
-    |mut p| {
-      p.push("tauri");
-      p
-    },
-  );
+  let tauri_tools_path = if let Some(toolchain) = settings.toolchain() {
+    // copied on every build since the script patches linuxdeploy
+    let tools_path = output_path.join("toolchain");
+    std::fs::create_dir_all(&tools_path)?;
+    let arch = sh_map["arch"];
+    let linuxdeploy_arch = if arch == "i686" { "i386" } else { arch };
+    let mut tools = vec![
+      format!("AppRun-{}", arch),
+      format!("linuxdeploy-{}.AppImage", linuxdeploy_arch),
+      "linuxdeploy-plugin-gtk.sh".into(),
+    ];
+    if matches!(std::env::var("APPIMAGE_BUNDLE_GSTREAMER"), Ok(v) if v != "0") {
+      tools.push("linuxdeploy-plugin-gstreamer.sh".into());
+    }
+    for tool in tools {
+      write(
+        tools_path.join(&tool),
+        read_toolchain_file(toolchain, &tool, None)?,
+      )?;
+    }
+    sh_map.insert("offline", "true");
+    tools_path
+  } else {
+    dirs_next::cache_dir().map_or_else(
+      || output_path.to_path_buf(),
+      |mut p| {
+        p.push("tauri");
+        p
+      },
+    )
+  };
   std::fs::create_dir_all(&tauri_tools_path)?;
   let tauri_tools_path_str = tauri_tools_path.to_string_lossy();
   sh_map.insert("tauri_tools_path", &tauri_tools_path_str);
@@ -1,4 +1,3 @@
-// Copyright 2016-2019 Cargo-Bundle developers <https://github.com/burtonageo/cargo-bundle>
 // Copyright 2019-2022 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
@@ -12,6 +11,10 @@
 //
 // See https://developer.apple.com/go/?id=bundle-structure for a full
 // explanation.
+//
+// No Xcode project is generated: the executable is the app binary itself, which must start the UIKit
+// application and host the webview through the runtime. Storyboards, asset catalogs and app extensions
+// are not supported.
 
 use crate::{bundle::common, Settings};
 
@@ -30,7 +33,7 @@ use std::{
 /// Bundles the project.
 /// Returns a vector of PathBuf that shows where the .app was created.
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
-  warn!("iOS bundle support is still experimental.");
+  warn!("iOS bundle support is still experimental, the app binary is packaged as is without an Xcode project hosting the webview.");
 
   let app_product_name = format!("{}.app", settings.product_name());
 
@@ -196,6 +199,28 @@ fn generate_info_plist(
     writeln!(file, "  </array>")?;
   }
   writeln!(file, "  <key>LSRequiresIPhoneOS</key>\n  <true/>")?;
+  writeln!(
+    file,
+    "  <key>CFBundlePackageType</key>\n  <string>APPL</string>"
+  )?;
+  writeln!(
+    file,
+    "  <key>CFBundleSupportedPlatforms</key>\n  <array>\n    <string>iPhoneOS</string>\n  </array>"
+  )?;
+  if let Some(version) = &settings.ios().minimum_system_version {
+    writeln!(
+      file,
+      "  <key>MinimumOSVersion</key>\n  <string>{}</string>",
+      version
+    )?;
+  }
+  // iPhone and iPad
+  writeln!(
+    file,
+    "  <key>UIDeviceFamily</key>\n  <array>\n    <integer>1</integer>\n    <integer>2</integer>\n  </array>"
+  )?;
+  // without a launch screen the app is shown letterboxed
+  writeln!(file, "  <key>UILaunchScreen</key>\n  <dict/>")?;
   writeln!(file, "</dict>\n</plist>")?;
   file.flush()?;
   Ok(())
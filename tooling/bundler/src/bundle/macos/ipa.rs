@@ -0,0 +1,112 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// An IPA is a zip archive laid out like:
+//
+// Payload
+//     Foobar.app                      # The signed iOS app bundle
+//         embedded.mobileprovision    # The provisioning profile
+//
+// The archive only installs the app on the devices of an ad hoc or development provisioning profile.
+// The App Store requires an archive built by Xcode, with compiled asset catalogs and the SDK metadata, which is not generated.
+
+use super::{ios, sign::sign_ios};
+use crate::{
+  bundle::{
+    common::{self, CommandExt},
+    Bundle,
+  },
+  PackageType::IosBundle,
+  Settings,
+};
+
+use anyhow::Context;
+use log::{info, warn};
+
+use std::{
+  fs,
+  io::Cursor,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the IPA was created.
+pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
+  // generate the .app bundle if needed
+  let app_bundle_path = match bundles
+    .iter()
+    .find(|bundle| bundle.package_type == IosBundle)
+  {
+    Some(bundle) => bundle.bundle_paths[0].clone(),
+    None => ios::bundle_project(settings)?.remove(0),
+  };
+
+  let output_path = settings.project_out_directory().join("bundle/ipa");
+  let ipa_name = format!(
+    "{}_{}.ipa",
+    settings.product_name(),
+    settings.version_string()
+  );
+  let ipa_path = output_path.join(&ipa_name);
+
+  if output_path.exists() {
+    fs::remove_dir_all(&output_path)
+      .with_context(|| format!("Failed to remove old {}", ipa_name))?;
+  }
+  fs::create_dir_all(&output_path)?;
+
+  info!(action = "Bundling"; "{} ({})", ipa_name, ipa_path.display());
+
+  let ios_settings = settings.ios();
+  if let Some(profile) = &ios_settings.provisioning_profile {
+    common::copy_file(profile, app_bundle_path.join("embedded.mobileprovision"))
+      .with_context(|| format!("Failed to copy provisioning profile {:?}", profile))?;
+  }
+
+  match &ios_settings.signing_identity {
+    Some(identity) => {
+      let entitlements = match &ios_settings.provisioning_profile {
+        Some(profile) => Some(
+          write_entitlements(profile, &output_path.join("entitlements.plist"))
+            .with_context(|| "Failed to read the entitlements of the provisioning profile")?,
+        ),
+        None => None,
+      };
+      sign_ios(&app_bundle_path, identity, entitlements.as_deref())?;
+    }
+    None => warn!("The IPA is not signed and can't be installed on devices. Set `tauri.conf.json > tauri > bundle > iOS > signingIdentity` to sign it."),
+  }
+
+  let payload_path = output_path.join("Payload");
+  common::copy_dir(
+    &app_bundle_path,
+    &payload_path.join(app_bundle_path.file_name().unwrap()),
+  )?;
+  Command::new("ditto")
+    .args(["-c", "-k", "--keepParent"])
+    .arg(&payload_path)
+    .arg(&ipa_path)
+    .output_ok()
+    .context("failed to zip the IPA")?;
+  fs::remove_dir_all(&payload_path)?;
+
+  Ok(vec![ipa_path])
+}
+
+/// Writes the entitlements granted by the provisioning profile, which must be set when signing the app.
+fn write_entitlements(profile: &Path, path: &Path) -> crate::Result<PathBuf> {
+  // the profile is a plist wrapped in a signed CMS message
+  let output = Command::new("security")
+    .args(["cms", "-D", "-i"])
+    .arg(profile)
+    .output_ok()?;
+  let profile = plist::Value::from_reader(Cursor::new(output.stdout))?;
+  let entitlements = profile
+    .as_dictionary()
+    .and_then(|profile| profile.get("Entitlements"))
+    .context("the provisioning profile has no entitlements")?;
+  entitlements.to_file_xml(path)?;
+  Ok(path.to_path_buf())
+}
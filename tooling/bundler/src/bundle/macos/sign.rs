@@ -4,7 +4,12 @@
 // SPDX-License-Identifier: MIT
 
 use std::ffi::OsString;
-use std::{fs::File, io::prelude::*, path::PathBuf, process::Command};
+use std::{
+  fs::File,
+  io::prelude::*,
+  path::{Path, PathBuf},
+  process::Command,
+};
 
 use crate::{bundle::common::CommandExt, Settings};
 use anyhow::Context;
@@ -146,6 +151,44 @@ pub fn sign(
 ) -> crate::Result<()> {
   info!(action = "Signing"; "{} with identity \"{}\"", path_to_sign.display(), identity);
 
+  with_keychain(|tauri_keychain| {
+    try_sign(
+      path_to_sign,
+      identity,
+      settings,
+      is_an_executable,
+      tauri_keychain,
+    )
+  })
+}
+
+/// Signs an iOS app bundle, with the entitlements granted by its provisioning profile.
+pub fn sign_ios(
+  app_bundle_path: &Path,
+  identity: &str,
+  entitlements: Option<&Path>,
+) -> crate::Result<()> {
+  info!(action = "Signing"; "{} with identity \"{}\"", app_bundle_path.display(), identity);
+
+  with_keychain(|tauri_keychain| {
+    let mut cmd = Command::new("codesign");
+    cmd.args(["--force", "-s", identity]);
+    if tauri_keychain {
+      cmd.args(["--keychain", KEYCHAIN_ID]);
+    }
+    if let Some(entitlements) = entitlements {
+      cmd.arg("--entitlements").arg(entitlements);
+    }
+    cmd
+      .arg(app_bundle_path)
+      .output_ok()
+      .context("failed to sign app")?;
+    Ok(())
+  })
+}
+
+/// Runs `f` with the certificate from the environment variables imported in the tauri keychain if set.
+fn with_keychain<F: FnOnce(bool) -> crate::Result<()>>(f: F) -> crate::Result<()> {
   let setup_keychain = if let (Some(certificate_encoded), Some(certificate_password)) = (
     std::env::var_os("APPLE_CERTIFICATE"),
     std::env::var_os("APPLE_CERTIFICATE_PASSWORD"),
@@ -158,13 +201,7 @@ pub fn sign(
     false
   };
 
-  let res = try_sign(
-    path_to_sign,
-    identity,
-    settings,
-    is_an_executable,
-    setup_keychain,
-  );
+  let res = f(setup_keychain);
 
   if setup_keychain {
     // delete the keychain again after signing
@@ -5,7 +5,7 @@
 
 use super::category::AppCategory;
 use crate::bundle::{common, platform::target_triple};
-pub use tauri_utils::config::WebviewInstallMode;
+pub use tauri_utils::config::{NsisInstallMode, WebviewInstallMode};
 use tauri_utils::{
   config::BundleType,
   resources::{external_binaries, ResourcePaths},
@@ -26,6 +26,8 @@ pub enum PackageType {
   IosBundle,
   /// The Windows bundle (.msi).
   WindowsMsi,
+  /// The NSIS installer (.exe).
+  Nsis,
   /// The Linux Debian package bundle (.deb).
   Deb,
   /// The Linux RPM bundle (.rpm).
@@ -36,6 +38,12 @@ pub enum PackageType {
   Dmg,
   /// The Updater bundle.
   Updater,
+  /// The Android application package (.apk).
+  Apk,
+  /// The Android App Bundle (.aab).
+  Aab,
+  /// The iOS app archive (.ipa), installable on the devices of its provisioning profile. It is not built by Xcode, so it can't be submitted to the App Store.
+  Ipa,
 }
 
 impl From<BundleType> for PackageType {
@@ -44,27 +52,34 @@ impl From<BundleType> for PackageType {
       BundleType::Deb => Self::Deb,
       BundleType::AppImage => Self::AppImage,
       BundleType::Msi => Self::WindowsMsi,
+      BundleType::Nsis => Self::Nsis,
       BundleType::App => Self::MacOsBundle,
       BundleType::Dmg => Self::Dmg,
       BundleType::Updater => Self::Updater,
+      BundleType::Apk => Self::Apk,
+      BundleType::Aab => Self::Aab,
+      BundleType::Ipa => Self::Ipa,
     }
   }
 }
 
 impl PackageType {
   /// Maps a short name to a PackageType.
-  /// Possible values are "deb", "ios", "msi", "app", "rpm", "appimage", "dmg", "updater".
+  /// Possible values are "deb", "ios", "msi", "nsis", "app", "rpm", "appimage", "dmg", "updater", "apk", "aab", "ipa".
   pub fn from_short_name(name: &str) -> Option<PackageType> {
-    // Other types we may eventually want to support: apk.
     match name {
       "deb" => Some(PackageType::Deb),
       "ios" => Some(PackageType::IosBundle),
       "msi" => Some(PackageType::WindowsMsi),
+      "nsis" => Some(PackageType::Nsis),
       "app" => Some(PackageType::MacOsBundle),
       "rpm" => Some(PackageType::Rpm),
       "appimage" => Some(PackageType::AppImage),
       "dmg" => Some(PackageType::Dmg),
       "updater" => Some(PackageType::Updater),
+      "apk" => Some(PackageType::Apk),
+      "aab" => Some(PackageType::Aab),
+      "ipa" => Some(PackageType::Ipa),
       _ => None,
     }
   }
@@ -76,11 +91,15 @@ impl PackageType {
       PackageType::Deb => "deb",
       PackageType::IosBundle => "ios",
       PackageType::WindowsMsi => "msi",
+      PackageType::Nsis => "nsis",
       PackageType::MacOsBundle => "app",
       PackageType::Rpm => "rpm",
       PackageType::AppImage => "appimage",
       PackageType::Dmg => "dmg",
       PackageType::Updater => "updater",
+      PackageType::Apk => "apk",
+      PackageType::Aab => "aab",
+      PackageType::Ipa => "ipa",
     }
   }
 
@@ -97,6 +116,8 @@ const ALL_PACKAGE_TYPES: &[PackageType] = &[
   PackageType::IosBundle,
   #[cfg(target_os = "windows")]
   PackageType::WindowsMsi,
+  #[cfg(target_os = "windows")]
+  PackageType::Nsis,
   #[cfg(target_os = "macos")]
   PackageType::MacOsBundle,
   #[cfg(target_os = "linux")]
@@ -106,6 +127,10 @@ const ALL_PACKAGE_TYPES: &[PackageType] = &[
   #[cfg(target_os = "linux")]
   PackageType::AppImage,
   PackageType::Updater,
+  PackageType::Apk,
+  PackageType::Aab,
+  #[cfg(target_os = "macos")]
+  PackageType::Ipa,
 ];
 
 /// The package settings.
@@ -185,6 +210,19 @@ pub struct MacOsSettings {
   pub info_plist_path: Option<PathBuf>,
 }
 
+/// The iOS bundle settings.
+#[derive(Clone, Debug, Default)]
+pub struct IosSettings {
+  /// The minimum iOS version that the bundled app supports (e.g. `"13.0"`).
+  pub minimum_system_version: Option<String>,
+  /// Code signing identity.
+  pub signing_identity: Option<String>,
+  /// Path to the provisioning profile embedded as `embedded.mobileprovision`.
+  ///
+  /// The app is signed with the entitlements of the profile.
+  pub provisioning_profile: Option<PathBuf>,
+}
+
 /// Configuration for a target language for the WiX build.
 #[derive(Debug, Clone, Default)]
 pub struct WixLanguageConfig {
@@ -240,6 +278,21 @@ pub struct WixSettings {
   pub dialog_image_path: Option<PathBuf>,
   /// Enables FIPS compliant algorithms.
   pub fips_compliant: bool,
+  /// The UpgradeCode GUID of the installer. Defaults to a GUID derived from the main binary name.
+  pub upgrade_code: Option<String>,
+}
+
+/// Settings specific to the NSIS implementation.
+#[derive(Clone, Debug, Default)]
+pub struct NsisSettings {
+  /// A custom .nsi template to use.
+  pub template: Option<PathBuf>,
+  /// Who the app is installed for.
+  pub install_mode: NsisInstallMode,
+  /// The paths of the installation directory kept by the uninstaller, relative to it.
+  pub preserved_paths: Vec<PathBuf>,
+  /// The subkeys of the app registry key kept by the uninstaller.
+  pub preserved_registry_keys: Vec<String>,
 }
 
 /// The Windows bundle settings.
@@ -256,6 +309,8 @@ pub struct WindowsSettings {
   pub tsp: bool,
   /// WiX configuration.
   pub wix: Option<WixSettings>,
+  /// NSIS configuration.
+  pub nsis: Option<NsisSettings>,
   /// The path to the application icon. Defaults to `./icons/icon.ico`.
   pub icon_path: PathBuf,
   /// The installation mode for the Webview2 runtime.
@@ -272,6 +327,12 @@ pub struct WindowsSettings {
   ///
   /// /// The default value of this flag is `true`.
   pub allow_downgrades: bool,
+  /// Whether installing the same version again reinstalls the app instead of blocking the installer.
+  ///
+  /// Only used when [`Self::allow_downgrades`] is `false`. The default value of this flag is `true`.
+  pub allow_same_version_upgrades: bool,
+  /// A custom message shown when blocking a downgrade.
+  pub downgrade_error_message: Option<String>,
 }
 
 impl Default for WindowsSettings {
@@ -282,14 +343,75 @@ impl Default for WindowsSettings {
       timestamp_url: None,
       tsp: false,
       wix: None,
+      nsis: None,
       icon_path: PathBuf::from("icons/icon.ico"),
       webview_install_mode: Default::default(),
       webview_fixed_runtime_path: None,
       allow_downgrades: true,
+      allow_same_version_upgrades: true,
+      downgrade_error_message: None,
     }
   }
 }
 
+/// The signing key of the Android bundles.
+#[derive(Clone, Debug)]
+pub struct AndroidSigningSettings {
+  /// Path to the Java keystore containing the key.
+  pub keystore: PathBuf,
+  /// The alias of the key in the keystore.
+  pub key_alias: String,
+}
+
+/// The Android bundle settings.
+#[derive(Clone, Debug)]
+pub struct AndroidSettings {
+  /// The name of the Rust library loaded by the application, without the `lib` prefix and `.so` extension.
+  pub lib_name: String,
+  /// The minimum API level required to run the application.
+  pub min_sdk_version: u32,
+  /// The API level the application targets.
+  pub target_sdk_version: u32,
+  /// The internal version number. Derived from the package version if not set.
+  pub version_code: Option<u32>,
+  /// The frontend files and directories copied to the `assets` directory of the package.
+  ///
+  /// The content of a directory is copied, not the directory itself.
+  pub assets: Vec<PathBuf>,
+  /// The directory of the launcher icon resources, laid out like the `res` directory of an Android project.
+  pub icons_dir: Option<PathBuf>,
+  /// The key used to sign the release bundles.
+  ///
+  /// The passwords are read by Gradle from the `TAURI_ANDROID_KEYSTORE_PASSWORD` and `TAURI_ANDROID_KEY_PASSWORD` environment variables.
+  pub signing: Option<AndroidSigningSettings>,
+  /// Builds the debug variant, signed with the debug key of the Android SDK.
+  pub debug: bool,
+}
+
+impl Default for AndroidSettings {
+  fn default() -> Self {
+    Self {
+      lib_name: String::new(),
+      min_sdk_version: 24,
+      target_sdk_version: 33,
+      version_code: None,
+      assets: Vec::new(),
+      icons_dir: None,
+      signing: None,
+      debug: false,
+    }
+  }
+}
+
+/// The local toolchain used instead of downloading the tools of the bundler.
+#[derive(Clone, Debug)]
+pub struct ToolchainSettings {
+  /// The directory containing the tools.
+  pub dir: PathBuf,
+  /// The SHA-256 checksums of the tools, as hex strings keyed by file name.
+  pub sha256: HashMap<String, String>,
+}
+
 /// The bundle settings of the BuildArtifact we're bundling.
 #[derive(Clone, Debug, Default)]
 pub struct BundleSettings {
@@ -336,10 +458,16 @@ pub struct BundleSettings {
   pub deb: DebianSettings,
   /// MacOS-specific settings.
   pub macos: MacOsSettings,
+  /// iOS-specific settings.
+  pub ios: IosSettings,
   /// Updater configuration.
   pub updater: Option<UpdaterSettings>,
   /// Windows-specific settings.
   pub windows: WindowsSettings,
+  /// Android-specific settings.
+  pub android: AndroidSettings,
+  /// The local toolchain, used instead of downloading the tools at bundle time.
+  pub toolchain: Option<ToolchainSettings>,
 }
 
 /// A binary to bundle.
@@ -566,12 +694,20 @@ impl Settings {
   ///
   /// Fails if the host/target's native package type is not supported.
   pub fn package_types(&self) -> crate::Result<Vec<PackageType>> {
-    let target_os = std::env::consts::OS;
+    // Android packages are cross compiled from any host, iOS packages from macOS
+    let target_os = if self.target.contains("android") {
+      "android"
+    } else if self.target.contains("apple-ios") {
+      "ios"
+    } else {
+      std::env::consts::OS
+    };
     let mut platform_types = match target_os {
       "macos" => vec![PackageType::MacOsBundle, PackageType::Dmg],
-      "ios" => vec![PackageType::IosBundle],
+      "ios" => vec![PackageType::IosBundle, PackageType::Ipa],
       "linux" => vec![PackageType::Deb, PackageType::AppImage],
-      "windows" => vec![PackageType::WindowsMsi],
+      "windows" => vec![PackageType::WindowsMsi, PackageType::Nsis],
+      "android" => vec![PackageType::Apk, PackageType::Aab],
       os => {
         return Err(crate::Error::GenericError(format!(
           "Native {} bundles not yet supported.",
@@ -581,7 +717,7 @@ impl Settings {
     };
 
     // add updater if needed
-    if self.is_update_enabled() {
+    if self.is_update_enabled() && !matches!(target_os, "android" | "ios") {
       platform_types.push(PackageType::Updater)
     }
 
@@ -599,6 +735,8 @@ impl Settings {
       }
       Ok(types)
     } else {
+      // the NSIS installer requires `makensis`, so it is only built when requested
+      platform_types.retain(|t| *t != PackageType::Nsis);
       Ok(platform_types)
     }
   }
@@ -732,11 +870,26 @@ impl Settings {
     &self.bundle_settings.macos
   }
 
+  /// Returns the iOS settings.
+  pub fn ios(&self) -> &IosSettings {
+    &self.bundle_settings.ios
+  }
+
   /// Returns the Windows settings.
   pub fn windows(&self) -> &WindowsSettings {
     &self.bundle_settings.windows
   }
 
+  /// Returns the Android settings.
+  pub fn android(&self) -> &AndroidSettings {
+    &self.bundle_settings.android
+  }
+
+  /// Returns the local toolchain settings.
+  pub fn toolchain(&self) -> Option<&ToolchainSettings> {
+    self.bundle_settings.toolchain.as_ref()
+  }
+
   /// Returns the Updater settings.
   pub fn updater(&self) -> Option<&UpdaterSettings> {
     self.bundle_settings.updater.as_ref()
@@ -4,4 +4,5 @@
 // SPDX-License-Identifier: MIT
 
 pub mod msi;
+pub mod nsis;
 pub mod sign;
@@ -5,7 +5,10 @@
 
 mod wix;
 
-pub use wix::{MSI_FOLDER_NAME, MSI_UPDATER_FOLDER_NAME};
+pub use wix::{
+  download, MSI_FOLDER_NAME, MSI_UPDATER_FOLDER_NAME, WEBVIEW2_BOOTSTRAPPER_FILE_NAME,
+  WEBVIEW2_BOOTSTRAPPER_URL,
+};
 
 use crate::Settings;
 use log::warn;
@@ -28,6 +31,16 @@ const WIX_REQUIRED_FILES: &[&str] = &[
 /// Runs all of the commands to build the MSI installer.
 /// Returns a vector of PathBuf that shows where the MSI was created.
 pub fn bundle_project(settings: &Settings, updater: bool) -> crate::Result<Vec<PathBuf>> {
+  if let Some(toolchain) = settings.toolchain() {
+    // extracted on every build, so the checksum is always verified
+    let wix_path = settings.project_out_directory().join("WixTools");
+    if wix_path.exists() {
+      std::fs::remove_dir_all(&wix_path)?;
+    }
+    wix::extract_toolchain_wix(toolchain, &wix_path)?;
+    return wix::build_wix_app_installer(settings, &wix_path, updater);
+  }
+
   let mut wix_path = dirs_next::cache_dir().unwrap();
   wix_path.push("tauri/WixTools");
 
@@ -3,18 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::super::sign::{sign, SignParams};
+use super::super::sign::try_sign;
 use crate::bundle::{
-  common::CommandExt,
+  common::{read_toolchain_file, sha256_matches, CommandExt},
   path_utils::{copy_file, FileOpts},
-  settings::Settings,
+  settings::{Settings, ToolchainSettings},
 };
 use anyhow::{bail, Context};
 use handlebars::{to_json, Handlebars};
 use log::info;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use sha2::Digest;
 use std::{
   collections::{BTreeMap, HashMap},
   fs::{create_dir_all, read_to_string, remove_dir_all, rename, write, File},
@@ -30,9 +29,12 @@ use zip::ZipArchive;
 pub const WIX_URL: &str =
   "https://github.com/wixtoolset/wix3/releases/download/wix3112rtm/wix311-binaries.zip";
 pub const WIX_SHA256: &str = "2c1888d5d1dba377fc7fa14444cf556963747ff9a0a289a3599cf09da03b9e2e";
+/// The file name of the WiX binaries on the local toolchain.
+const WIX_FILE_NAME: &str = "wix311-binaries.zip";
 pub const MSI_FOLDER_NAME: &str = "msi";
 pub const MSI_UPDATER_FOLDER_NAME: &str = "msi-updater";
-const WEBVIEW2_BOOTSTRAPPER_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+pub const WEBVIEW2_BOOTSTRAPPER_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+pub const WEBVIEW2_BOOTSTRAPPER_FILE_NAME: &str = "MicrosoftEdgeWebview2Setup.exe";
 const WEBVIEW2_X86_INSTALLER_GUID: &str = "a17bde80-b5ab-47b5-8bbb-1cbe93fc6ec9";
 const WEBVIEW2_X64_INSTALLER_GUID: &str = "aa5fd9b3-dc11-4cbc-8343-a50f57b311e1";
 
@@ -174,7 +176,7 @@ fn copy_icon(settings: &Settings, filename: &str, path: &Path) -> crate::Result<
   Ok(icon_target_path)
 }
 
-fn download(url: &str) -> crate::Result<Vec<u8>> {
+pub fn download(url: &str) -> crate::Result<Vec<u8>> {
   info!(action = "Downloading"; "{}", url);
   let response = attohttpc::get(url).send()?;
   response.bytes().map_err(Into::into)
@@ -185,13 +187,7 @@ fn download_and_verify(url: &str, hash: &str) -> crate::Result<Vec<u8>> {
   let data = download(url)?;
   info!("validating hash");
 
-  let mut hasher = sha2::Sha256::new();
-  hasher.update(&data);
-
-  let url_hash = hasher.finalize().to_vec();
-  let expected_hash = hex::decode(hash)?;
-
-  if expected_hash == url_hash {
+  if sha256_matches(&data, hash)? {
     Ok(data)
   } else {
     Err(crate::Error::HashError)
@@ -281,6 +277,17 @@ pub fn get_and_extract_wix(path: &Path) -> crate::Result<()> {
   extract_zip(&data, path)
 }
 
+/// Extracts Wix from the local toolchain, verifying it with the configured or pinned checksum.
+pub fn extract_toolchain_wix(toolchain: &ToolchainSettings, path: &Path) -> crate::Result<()> {
+  info!("Verifying wix package from {}", toolchain.dir.display());
+
+  let data = read_toolchain_file(toolchain, WIX_FILE_NAME, Some(WIX_SHA256))?;
+
+  info!("extracting WIX");
+
+  extract_zip(&data, path)
+}
+
 fn clear_env_for_wix(cmd: &mut Command) {
   cmd.env_clear();
   let required_vars: Vec<std::ffi::OsString> =
@@ -293,6 +300,33 @@ fn clear_env_for_wix(cmd: &mut Command) {
   }
 }
 
+/// Validates the folder of the WebView2 fixed version runtime, which is bundled as a resource.
+fn validate_fixed_runtime(path: &Path, arch: &str) -> anyhow::Result<()> {
+  if !path.join("msedgewebview2.exe").exists() {
+    bail!(
+      "the WebView2 fixed runtime folder `{}` does not contain msedgewebview2.exe, the fixed version `.cab` file must be extracted to it",
+      path.display()
+    );
+  }
+  // the extracted folder is named `Microsoft.WebView2.FixedVersionRuntime.<version>.<arch>`
+  let folder_arch = path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .and_then(|name| name.rsplit('.').next())
+    .filter(|folder_arch| ["x86", "x64", "arm64"].contains(folder_arch));
+  if let Some(folder_arch) = folder_arch {
+    if folder_arch != arch {
+      bail!(
+        "the WebView2 fixed runtime `{}` is for {} but the app is built for {}",
+        path.display(),
+        folder_arch,
+        arch
+      );
+    }
+  }
+  Ok(())
+}
+
 /// Runs the Candle.exe executable for Wix. Candle parses the wxs file and generates the code for building the installer.
 fn run_candle(
   settings: &Settings,
@@ -414,6 +448,41 @@ fn validate_version(version: &str) -> anyhow::Result<()> {
   Ok(())
 }
 
+/// The UpgradeCode GUID of the installer: the configured one, or one derived from the main binary name.
+fn upgrade_code(configured: Option<&str>, main_binary_name: &str) -> crate::Result<String> {
+  let upgrade_code = match configured {
+    Some(upgrade_code) => Uuid::parse_str(upgrade_code)
+      .with_context(|| format!("invalid WiX upgrade code `{}`", upgrade_code))?,
+    None => Uuid::new_v5(
+      &Uuid::NAMESPACE_DNS,
+      format!("{}.app.x64", main_binary_name).as_bytes(),
+    ),
+  };
+  Ok(upgrade_code.to_string())
+}
+
+/// Adds the default `locale_strings` that the `locale_contents` do not define.
+fn merge_locale_strings(locale_contents: &str, locale_strings: &str) -> String {
+  let mut unset_locale_strings = String::new();
+  let prefix_len = "<String ".len();
+  for locale_string in locale_strings.split('\n').filter(|s| !s.is_empty()) {
+    // strip `<String ` prefix and `>{value}</String` suffix.
+    let id = locale_string
+      .chars()
+      .skip(prefix_len)
+      .take(locale_string.find('>').unwrap() - prefix_len)
+      .collect::<String>();
+    if !locale_contents.contains(&id) {
+      unset_locale_strings.push_str(locale_string);
+    }
+  }
+
+  locale_contents.replace(
+    "</WixLocalization>",
+    &format!("{}</WixLocalization>", unset_locale_strings),
+  )
+}
+
 // Entry point for bundling and creating the MSI installer. For now the only supported platform is Windows x64.
 pub fn build_wix_app_installer(
   settings: &Settings,
@@ -442,33 +511,7 @@ pub fn build_wix_app_installer(
     .find(|bin| bin.main())
     .ok_or_else(|| anyhow::anyhow!("Failed to get main binary"))?;
   let app_exe_source = settings.binary_path(main_binary);
-  let try_sign = |file_path: &PathBuf| -> crate::Result<()> {
-    if let Some(certificate_thumbprint) = &settings.windows().certificate_thumbprint {
-      info!(action = "Signing"; "{}", file_path.display());
-      sign(
-        &file_path,
-        &SignParams {
-          product_name: settings.product_name().into(),
-          digest_algorithm: settings
-            .windows()
-            .digest_algorithm
-            .as_ref()
-            .map(|algorithm| algorithm.to_string())
-            .unwrap_or_else(|| "sha256".to_string()),
-          certificate_thumbprint: certificate_thumbprint.to_string(),
-          timestamp_url: settings
-            .windows()
-            .timestamp_url
-            .as_ref()
-            .map(|url| url.to_string()),
-          tsp: settings.windows().tsp,
-        },
-      )?;
-    }
-    Ok(())
-  };
-
-  try_sign(&app_exe_source)?;
+  try_sign(&app_exe_source, settings)?;
 
   let output_path = settings.project_out_directory().join("wix").join(arch);
 
@@ -489,17 +532,23 @@ pub fn build_wix_app_installer(
     true
   };
 
-  let webview_install_mode = if updater {
+  let webview_install_mode = if let Some(fixed_runtime_path) =
+    settings.windows().webview_fixed_runtime_path.clone()
+  {
+    WebviewInstallMode::FixedRuntime {
+      path: fixed_runtime_path,
+    }
+  } else if let WebviewInstallMode::FixedRuntime { path } = &settings.windows().webview_install_mode
+  {
+    // the updater must not install the Evergreen runtime either
+    WebviewInstallMode::FixedRuntime { path: path.clone() }
+  } else if updater {
     WebviewInstallMode::DownloadBootstrapper {
       silent: silent_webview_install,
     }
   } else {
     let mut webview_install_mode = settings.windows().webview_install_mode.clone();
-    if let Some(fixed_runtime_path) = settings.windows().webview_fixed_runtime_path.clone() {
-      webview_install_mode = WebviewInstallMode::FixedRuntime {
-        path: fixed_runtime_path,
-      };
-    } else if let Some(wix) = &settings.windows().wix {
+    if let Some(wix) = &settings.windows().wix {
       if wix.skip_webview_install {
         webview_install_mode = WebviewInstallMode::Skip;
       }
@@ -518,7 +567,11 @@ pub fn build_wix_app_installer(
   );
 
   match webview_install_mode {
-    WebviewInstallMode::Skip | WebviewInstallMode::FixedRuntime { .. } => {
+    WebviewInstallMode::Skip => {
+      data.insert("install_webview", to_json(false));
+    }
+    WebviewInstallMode::FixedRuntime { path } => {
+      validate_fixed_runtime(&path, arch)?;
       data.insert("install_webview", to_json(false));
     }
     WebviewInstallMode::DownloadBootstrapper { silent: _ } => {
@@ -533,11 +586,12 @@ pub fn build_wix_app_installer(
       );
     }
     WebviewInstallMode::EmbedBootstrapper { silent: _ } => {
-      let webview2_bootstrapper_path = output_path.join("MicrosoftEdgeWebview2Setup.exe");
-      std::fs::write(
-        &webview2_bootstrapper_path,
-        download(WEBVIEW2_BOOTSTRAPPER_URL)?,
-      )?;
+      let webview2_bootstrapper_path = output_path.join(WEBVIEW2_BOOTSTRAPPER_FILE_NAME);
+      let webview2_bootstrapper = match settings.toolchain() {
+        Some(toolchain) => read_toolchain_file(toolchain, WEBVIEW2_BOOTSTRAPPER_FILE_NAME, None)?,
+        None => download(WEBVIEW2_BOOTSTRAPPER_URL)?,
+      };
+      std::fs::write(&webview2_bootstrapper_path, webview2_bootstrapper)?;
       data.insert(
         "webview2_bootstrapper_path",
         to_json(webview2_bootstrapper_path),
@@ -549,25 +603,38 @@ pub fn build_wix_app_installer(
       } else {
         WEBVIEW2_X86_INSTALLER_GUID
       };
-      let mut offline_installer_path = dirs_next::cache_dir().unwrap();
-      offline_installer_path.push("tauri");
-      offline_installer_path.push(guid);
-      offline_installer_path.push(arch);
-      create_dir_all(&offline_installer_path)?;
-      let webview2_installer_path =
-        offline_installer_path.join("MicrosoftEdgeWebView2RuntimeInstaller.exe");
-      if !webview2_installer_path.exists() {
+      if let Some(toolchain) = settings.toolchain() {
+        let file_name = format!(
+          "MicrosoftEdgeWebView2RuntimeInstaller{}.exe",
+          arch.to_uppercase()
+        );
+        let webview2_installer_path = output_path.join(&file_name);
         std::fs::write(
           &webview2_installer_path,
-          download(
-            &format!("https://msedge.sf.dl.delivery.mp.microsoft.com/filestreamingservice/files/{}/MicrosoftEdgeWebView2RuntimeInstaller{}.exe",
-              guid,
-              arch.to_uppercase(),
-            ),
-          )?,
+          read_toolchain_file(toolchain, &file_name, None)?,
         )?;
+        data.insert("webview2_installer_path", to_json(webview2_installer_path));
+      } else {
+        let mut offline_installer_path = dirs_next::cache_dir().unwrap();
+        offline_installer_path.push("tauri");
+        offline_installer_path.push(guid);
+        offline_installer_path.push(arch);
+        create_dir_all(&offline_installer_path)?;
+        let webview2_installer_path =
+          offline_installer_path.join("MicrosoftEdgeWebView2RuntimeInstaller.exe");
+        if !webview2_installer_path.exists() {
+          std::fs::write(
+            &webview2_installer_path,
+            download(
+              &format!("https://msedge.sf.dl.delivery.mp.microsoft.com/filestreamingservice/files/{}/MicrosoftEdgeWebView2RuntimeInstaller{}.exe",
+                guid,
+                arch.to_uppercase(),
+              ),
+            )?,
+          )?;
+        }
+        data.insert("webview2_installer_path", to_json(webview2_installer_path));
       }
-      data.insert("webview2_installer_path", to_json(webview2_installer_path));
     }
   }
 
@@ -613,17 +680,27 @@ pub fn build_wix_app_installer(
     .unwrap_or_else(|| bundle_id.split('.').nth(1).unwrap_or(bundle_id));
   data.insert("bundle_id", to_json(bundle_id));
   data.insert("manufacturer", to_json(manufacturer));
-  let upgrade_code = Uuid::new_v5(
-    &Uuid::NAMESPACE_DNS,
-    format!("{}.app.x64", &settings.main_binary_name()).as_bytes(),
-  )
-  .to_string();
+  let upgrade_code = upgrade_code(
+    settings
+      .windows()
+      .wix
+      .as_ref()
+      .and_then(|wix| wix.upgrade_code.as_deref()),
+    settings.main_binary_name(),
+  )?;
 
   data.insert("upgrade_code", to_json(&upgrade_code.as_str()));
   data.insert(
     "allow_downgrades",
     to_json(settings.windows().allow_downgrades),
   );
+  data.insert(
+    "allow_same_version_upgrades",
+    to_json(settings.windows().allow_same_version_upgrades),
+  );
+  if let Some(message) = &settings.windows().downgrade_error_message {
+    data.insert("downgrade_error_message", to_json(message));
+  }
 
   let path_guid = generate_package_guid(settings).to_string();
   data.insert("path_component_guid", to_json(&path_guid.as_str()));
@@ -817,24 +894,7 @@ pub fn build_wix_app_installer(
       .replace("__codepage__", &language_metadata.ascii_code.to_string())
       .replace("__productName__", settings.product_name());
 
-    let mut unset_locale_strings = String::new();
-    let prefix_len = "<String ".len();
-    for locale_string in locale_strings.split('\n').filter(|s| !s.is_empty()) {
-      // strip `<String ` prefix and `>{value}</String` suffix.
-      let id = locale_string
-        .chars()
-        .skip(prefix_len)
-        .take(locale_string.find('>').unwrap() - prefix_len)
-        .collect::<String>();
-      if !locale_contents.contains(&id) {
-        unset_locale_strings.push_str(locale_string);
-      }
-    }
-
-    let locale_contents = locale_contents.replace(
-      "</WixLocalization>",
-      &format!("{}</WixLocalization>", unset_locale_strings),
-    );
+    let locale_contents = merge_locale_strings(&locale_contents, &locale_strings);
     let locale_path = output_path.join("locale.wxl");
     {
       let mut fileout = File::create(&locale_path).expect("Failed to create locale file");
@@ -868,7 +928,7 @@ pub fn build_wix_app_installer(
       &msi_output_path,
     )?;
     rename(&msi_output_path, &msi_path)?;
-    try_sign(&msi_path)?;
+    try_sign(&msi_path, settings)?;
     output_paths.push(msi_path);
   }
 
@@ -1081,3 +1141,80 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
 
   Ok(resources)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{merge_locale_strings, upgrade_code};
+  use handlebars::Handlebars;
+  use serde_json::json;
+
+  #[test]
+  fn upgrade_codes() {
+    // the default code must stay stable so the installed versions keep being upgraded
+    assert_eq!(
+      upgrade_code(None, "app").unwrap(),
+      "90eda035-17e2-5840-8927-6a118fb1ea71"
+    );
+    assert_ne!(
+      upgrade_code(None, "app").unwrap(),
+      upgrade_code(None, "renamed-app").unwrap()
+    );
+    assert_eq!(
+      upgrade_code(Some("7DA0B5B8-1C8A-4F4B-9C9D-3E0B0F6C5D21"), "app").unwrap(),
+      "7da0b5b8-1c8a-4f4b-9c9d-3e0b0f6c5d21"
+    );
+    assert!(upgrade_code(Some("not a guid"), "app").is_err());
+  }
+
+  #[test]
+  fn locale_fallback() {
+    let locale_strings = include_str!("./default-locale-strings.xml")
+      .replace("__language__", "1033")
+      .replace("__codepage__", "1252")
+      .replace("__productName__", "App");
+    let locale = merge_locale_strings(
+      r#"<WixLocalization Culture="en-us"><String Id="DowngradeErrorMessage">Custom</String></WixLocalization>"#,
+      &locale_strings,
+    );
+    assert_eq!(locale.matches(r#"Id="DowngradeErrorMessage""#).count(), 1);
+    assert!(locale.contains(
+      r#"<String Id="SameVersionErrorMessage">This version of App is already installed.</String>"#
+    ));
+    assert!(locale.ends_with("</WixLocalization>"));
+  }
+
+  #[test]
+  fn major_upgrade() {
+    let mut handlebars = Handlebars::new();
+    handlebars
+      .register_template_string("main.wxs", include_str!("../templates/main.wxs"))
+      .unwrap();
+    let render = |data| handlebars.render("main.wxs", &data).unwrap();
+
+    let wxs = render(json!({ "allow_downgrades": true, "allow_same_version_upgrades": true }));
+    assert!(wxs.contains(r#"<MajorUpgrade AllowDowngrades="yes""#));
+
+    let wxs = render(json!({
+      "allow_downgrades": false,
+      "allow_same_version_upgrades": true,
+      "downgrade_error_message": "Uninstall the newer version first",
+    }));
+    assert!(wxs.contains(
+      r#"<MajorUpgrade DowngradeErrorMessage="Uninstall the newer version first" AllowSameVersionUpgrades="yes" />"#
+    ));
+    assert!(!wxs.contains("SAMEVERSIONDETECTED"));
+
+    let wxs = render(json!({
+      "allow_downgrades": false,
+      "allow_same_version_upgrades": false,
+      "upgrade_code": "7da0b5b8-1c8a-4f4b-9c9d-3e0b0f6c5d21",
+      "version": "1.2.0",
+    }));
+    assert!(wxs.contains(
+      r#"DowngradeErrorMessage="!(loc.DowngradeErrorMessage)" AllowSameVersionUpgrades="no""#
+    ));
+    assert!(wxs.contains(r#"<Upgrade Id="7da0b5b8-1c8a-4f4b-9c9d-3e0b0f6c5d21">"#));
+    assert!(wxs.contains(r#"Minimum="1.2.0" Maximum="1.2.0""#));
+    assert!(wxs.contains(r#"<Condition Message="!(loc.SameVersionErrorMessage)">"#));
+  }
+}
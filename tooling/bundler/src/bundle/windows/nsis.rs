@@ -0,0 +1,373 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{
+  msi::{download, WEBVIEW2_BOOTSTRAPPER_FILE_NAME, WEBVIEW2_BOOTSTRAPPER_URL},
+  sign::try_sign,
+};
+use crate::bundle::{
+  common::{read_toolchain_file, CommandExt},
+  settings::{NsisInstallMode, Settings, WebviewInstallMode},
+};
+
+use anyhow::Context;
+use handlebars::Handlebars;
+use log::info;
+use serde::Serialize;
+use tauri_utils::resources::resource_relpath;
+
+use std::{
+  collections::BTreeSet,
+  env::var_os,
+  fs::{create_dir_all, read_to_string, remove_dir_all, write},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+pub const NSIS_FOLDER_NAME: &str = "nsis";
+
+/// A file installed in the installation directory.
+#[derive(Serialize)]
+struct InstalledFile {
+  /// The path of the file on the build machine.
+  source: String,
+  /// The path of the file relative to the installation directory.
+  path: String,
+}
+
+/// A path kept by the uninstaller.
+#[derive(Serialize)]
+struct PreservedPath {
+  /// The path relative to the installation directory.
+  path: String,
+  /// The parent directory of the path, empty for the files of the installation directory.
+  parent: String,
+}
+
+#[derive(Serialize)]
+struct InstallerData<'a> {
+  product_name: &'a str,
+  version: &'a str,
+  manufacturer: &'a str,
+  homepage: Option<&'a str>,
+  x64: bool,
+  per_machine: bool,
+  both: bool,
+  app_exe_name: &'a str,
+  app_exe_source: String,
+  icon_path: String,
+  binaries: Vec<InstalledFile>,
+  resource_directories: BTreeSet<String>,
+  resources: Vec<InstalledFile>,
+  install_webview: bool,
+  webview2_bootstrapper_path: Option<String>,
+  webview_installer_args: &'static str,
+  preserved_paths: Vec<PreservedPath>,
+  preserved_registry_keys: &'a [String],
+  out_file: String,
+}
+
+/// Runs all of the commands to build the NSIS installer.
+/// Returns a vector of PathBuf that shows where the installer was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let arch = match settings.binary_arch() {
+    "x86_64" => "x64",
+    "x86" => "x86",
+    target => {
+      return Err(crate::Error::ArchError(format!(
+        "unsupported target: {}",
+        target
+      )))
+    }
+  };
+
+  let main_binary = settings
+    .binaries()
+    .iter()
+    .find(|bin| bin.main())
+    .ok_or_else(|| anyhow::anyhow!("Failed to get main binary"))?;
+  let app_exe_source = settings.binary_path(main_binary);
+  try_sign(&app_exe_source, settings)?;
+
+  let output_path = settings.project_out_directory().join("nsis").join(arch);
+  if output_path.exists() {
+    remove_dir_all(&output_path)?;
+  }
+  create_dir_all(&output_path)?;
+
+  let nsis = settings.windows().nsis.clone().unwrap_or_default();
+  for key in &nsis.preserved_registry_keys {
+    if key.is_empty() || key.contains('\\') {
+      return Err(
+        anyhow::anyhow!(
+          "invalid preserved registry key `{}`, only the direct subkeys of the app key can be preserved",
+          key
+        )
+        .into(),
+      );
+    }
+  }
+
+  let webview_install_mode = match &settings.windows().webview_fixed_runtime_path {
+    // the fixed runtime is bundled as a resource
+    Some(_) => WebviewInstallMode::Skip,
+    None => settings.windows().webview_install_mode.clone(),
+  };
+  let (install_webview, silent, webview2_bootstrapper_path) = match webview_install_mode {
+    WebviewInstallMode::Skip | WebviewInstallMode::FixedRuntime { .. } => (false, true, None),
+    WebviewInstallMode::DownloadBootstrapper { silent } => (true, silent, None),
+    WebviewInstallMode::EmbedBootstrapper { silent } => {
+      let path = output_path.join(WEBVIEW2_BOOTSTRAPPER_FILE_NAME);
+      let bootstrapper = match settings.toolchain() {
+        Some(toolchain) => read_toolchain_file(toolchain, WEBVIEW2_BOOTSTRAPPER_FILE_NAME, None)?,
+        None => download(WEBVIEW2_BOOTSTRAPPER_URL)?,
+      };
+      write(&path, bootstrapper)?;
+      (true, silent, Some(path.display().to_string()))
+    }
+    WebviewInstallMode::OfflineInstaller { .. } => {
+      return Err(anyhow::anyhow!(
+        "the `offlineInstaller` WebView2 install mode is not supported by the NSIS installer, use the `embedBootstrapper` mode or the MSI installer"
+      ).into())
+    }
+  };
+
+  let installer_path = settings.project_out_directory().join(format!(
+    "bundle/{}/{}_{}_{}-setup.exe",
+    NSIS_FOLDER_NAME,
+    settings.main_binary_name().replace(".exe", ""),
+    settings.version_string(),
+    arch,
+  ));
+  create_dir_all(installer_path.parent().unwrap())?;
+
+  let bundle_id = settings.bundle_identifier();
+  let data = InstallerData {
+    product_name: settings.product_name(),
+    version: settings.version_string(),
+    manufacturer: settings
+      .publisher()
+      .unwrap_or_else(|| bundle_id.split('.').nth(1).unwrap_or(bundle_id)),
+    homepage: Some(settings.homepage_url()).filter(|url| !url.is_empty()),
+    x64: arch == "x64",
+    per_machine: nsis.install_mode == NsisInstallMode::PerMachine,
+    both: nsis.install_mode == NsisInstallMode::Both,
+    app_exe_name: settings.main_binary_name(),
+    app_exe_source: app_exe_source.display().to_string(),
+    icon_path: std::env::current_dir()?
+      .join(&settings.windows().icon_path)
+      .display()
+      .to_string(),
+    binaries: generate_binaries_data(settings)?,
+    resource_directories: BTreeSet::new(),
+    resources: Vec::new(),
+    install_webview,
+    webview2_bootstrapper_path,
+    webview_installer_args: if silent { "/silent" } else { "" },
+    preserved_paths: nsis
+      .preserved_paths
+      .iter()
+      .map(|path| PreservedPath {
+        path: windows_path(path),
+        parent: path.parent().map(windows_path).unwrap_or_default(),
+      })
+      .collect(),
+    preserved_registry_keys: &nsis.preserved_registry_keys,
+    out_file: installer_path.display().to_string(),
+  };
+  let data = generate_resource_data(settings, data)?;
+
+  let template = match &nsis.template {
+    Some(template) => read_to_string(template)
+      .with_context(|| format!("failed to read the NSIS template {}", template.display()))?,
+    None => include_str!("templates/installer.nsi").to_string(),
+  };
+  let installer_nsi_path = output_path.join("installer.nsi");
+  write(&installer_nsi_path, render_installer(&template, &data)?)?;
+
+  info!(action = "Running"; "makensis to produce {}", installer_path.display());
+  Command::new(makensis())
+    .args(["-V2", "-INPUTCHARSET", "UTF8"])
+    .arg(&installer_nsi_path)
+    .current_dir(&output_path)
+    .output_ok()
+    .context("error running makensis.exe, make sure NSIS 3 is installed and in your PATH")?;
+
+  try_sign(&installer_path, settings)?;
+
+  Ok(vec![installer_path])
+}
+
+/// Renders the NSIS script, escaping the values for NSIS strings.
+fn render_installer(template: &str, data: &InstallerData<'_>) -> crate::Result<String> {
+  let mut handlebars = Handlebars::new();
+  handlebars.register_escape_fn(|value| value.replace('$', "$$").replace('"', "$\\\""));
+  handlebars
+    .render_template(template, data)
+    .map_err(Into::into)
+}
+
+/// The `makensis.exe` of the default NSIS installation directory, or the one in the PATH.
+fn makensis() -> PathBuf {
+  var_os("ProgramFiles(x86)")
+    .map(|dir| PathBuf::from(dir).join("NSIS").join("makensis.exe"))
+    .filter(|path| path.exists())
+    .unwrap_or_else(|| PathBuf::from("makensis.exe"))
+}
+
+fn windows_path(path: &Path) -> String {
+  path.display().to_string().replace('/', "\\")
+}
+
+/// Generates the data of the external binaries and extra binaries, installed next to the main binary.
+fn generate_binaries_data(settings: &Settings) -> crate::Result<Vec<InstalledFile>> {
+  let mut binaries = Vec::new();
+  let cwd = std::env::current_dir()?;
+  for src in settings.external_binaries() {
+    let src = src?;
+    let file_name = src
+      .file_name()
+      .expect("failed to extract external binary filename")
+      .to_string_lossy()
+      .replace(&format!("-{}", settings.target()), "");
+    binaries.push(InstalledFile {
+      source: cwd.join(&src).display().to_string(),
+      path: file_name,
+    });
+  }
+
+  for bin in settings.binaries() {
+    if !bin.main() {
+      binaries.push(InstalledFile {
+        source: settings.binary_path(bin).display().to_string(),
+        path: bin.name().to_string(),
+      });
+    }
+  }
+
+  Ok(binaries)
+}
+
+/// Adds the resources and the directories to create for them.
+fn generate_resource_data<'a>(
+  settings: &Settings,
+  mut data: InstallerData<'a>,
+) -> crate::Result<InstallerData<'a>> {
+  let cwd = std::env::current_dir()?;
+  let mut added_resources = BTreeSet::new();
+  for src in settings.resource_files() {
+    let src = src?;
+    // glob resource paths like `assets/**/*` can list a file twice
+    if !added_resources.insert(src.clone()) {
+      continue;
+    }
+    let path = resource_relpath(&src);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+      data.resource_directories.insert(windows_path(parent));
+    }
+    data.resources.push(InstalledFile {
+      source: cwd.join(&src).display().to_string(),
+      path: windows_path(&path),
+    });
+  }
+  Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{render_installer, InstalledFile, InstallerData, PreservedPath};
+
+  fn data(preserved_registry_keys: &[String]) -> InstallerData<'_> {
+    InstallerData {
+      product_name: "My $App",
+      version: "1.0.0",
+      manufacturer: "Tauri \"Apps\"",
+      homepage: None,
+      x64: true,
+      per_machine: false,
+      both: false,
+      app_exe_name: "app.exe",
+      app_exe_source: r"C:\app\target\release\app.exe".into(),
+      icon_path: r"C:\app\icons\icon.ico".into(),
+      binaries: Vec::new(),
+      resource_directories: [r"assets\images".to_string()].into_iter().collect(),
+      resources: vec![InstalledFile {
+        source: r"C:\app\assets\images\logo.png".into(),
+        path: r"assets\images\logo.png".into(),
+      }],
+      install_webview: false,
+      webview2_bootstrapper_path: None,
+      webview_installer_args: "/silent",
+      preserved_paths: Vec::new(),
+      preserved_registry_keys,
+      out_file: r"C:\app\target\release\bundle\nsis\app_1.0.0_x64-setup.exe".into(),
+    }
+  }
+
+  fn render(data: &InstallerData<'_>) -> String {
+    render_installer(include_str!("templates/installer.nsi"), data).unwrap()
+  }
+
+  #[test]
+  fn escapes_values() {
+    let script = render(&data(&[]));
+    assert!(script.contains(r#"!define PRODUCT_NAME "My $$App""#));
+    assert!(script.contains(r#"!define MANUFACTURER "Tauri $\"Apps$\"""#));
+    assert!(script.contains(r#"CreateDirectory "$INSTDIR\assets\images""#));
+    assert!(
+      script.contains(r#"File "/oname=assets\images\logo.png" "C:\app\assets\images\logo.png""#)
+    );
+  }
+
+  #[test]
+  fn renders_install_modes() {
+    let mut data = data(&[]);
+    let script = render(&data);
+    assert!(script.contains("RequestExecutionLevel user"));
+    assert!(script.contains("StrCpy $AllUsers 0"));
+    assert!(!script.contains("Page custom InstallModePage"));
+
+    data.per_machine = true;
+    let script = render(&data);
+    assert!(script.contains("RequestExecutionLevel admin"));
+    assert!(script.contains("StrCpy $AllUsers 1"));
+    assert!(!script.contains("Page custom InstallModePage"));
+
+    data.per_machine = false;
+    data.both = true;
+    let script = render(&data);
+    assert!(script.contains("RequestExecutionLevel user"));
+    assert!(script.contains("Page custom InstallModePage InstallModePageLeave"));
+    assert!(script.contains(r#"ExecShell "runas" "$EXEPATH" "/ALLUSERS""#));
+  }
+
+  #[test]
+  fn preserves_paths_and_registry_keys() {
+    let script = render(&data(&[]));
+    assert!(!script.contains("PRESERVED_DIR}\\"));
+    assert!(script.contains(r#"DeleteRegKey SHCTX "${APP_KEY}""#));
+    assert!(!script.contains("DeleteRegKey /ifempty"));
+
+    let keys = ["Settings".to_string()];
+    let mut data = data(&keys);
+    data.preserved_paths = vec![
+      PreservedPath {
+        path: "LICENSE.txt".into(),
+        parent: String::new(),
+      },
+      PreservedPath {
+        path: r"data\db.sqlite".into(),
+        parent: "data".into(),
+      },
+    ];
+    let script = render(&data);
+    assert!(script.contains(r#"Rename "$INSTDIR\LICENSE.txt" "${PRESERVED_DIR}\LICENSE.txt""#));
+    assert!(script.contains(r#"CreateDirectory "${PRESERVED_DIR}\data""#));
+    assert!(
+      script.contains(r#"Rename "$INSTDIR\data\db.sqlite" "${PRESERVED_DIR}\data\db.sqlite""#)
+    );
+    assert!(script.contains(r#"${If} $1 == "Settings""#));
+    assert!(script.contains(r#"DeleteRegKey /ifempty SHCTX "${APP_KEY}""#));
+  }
+}
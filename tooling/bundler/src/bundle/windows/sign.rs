@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::bundle::common::CommandExt;
+use crate::{bundle::common::CommandExt, Settings};
 use bitness::{self, Bitness};
 use log::{debug, info};
 use std::{
@@ -126,3 +126,30 @@ pub fn sign<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Result<()> {
 
   Ok(())
 }
+
+/// Signs the file with the certificate of the Windows settings, if one is configured.
+pub fn try_sign(file_path: &Path, settings: &Settings) -> crate::Result<()> {
+  if let Some(certificate_thumbprint) = &settings.windows().certificate_thumbprint {
+    info!(action = "Signing"; "{}", file_path.display());
+    sign(
+      file_path,
+      &SignParams {
+        product_name: settings.product_name().into(),
+        digest_algorithm: settings
+          .windows()
+          .digest_algorithm
+          .as_ref()
+          .map(|algorithm| algorithm.to_string())
+          .unwrap_or_else(|| "sha256".to_string()),
+        certificate_thumbprint: certificate_thumbprint.to_string(),
+        timestamp_url: settings
+          .windows()
+          .timestamp_url
+          .as_ref()
+          .map(|url| url.to_string()),
+        tsp: settings.windows().tsp,
+      },
+    )?;
+  }
+  Ok(())
+}
@@ -39,7 +39,6 @@ pub enum Error {
   #[error("`{0}`")]
   ZipError(#[from] zip::result::ZipError),
   /// Hex error.
-  #[cfg(target_os = "windows")]
   #[error("`{0}`")]
   HexError(#[from] hex::FromHexError),
   /// Handlebars template error.
@@ -67,6 +66,9 @@ pub enum Error {
   /// Failed to validate downloaded file hash.
   #[error("hash mismatch of downloaded file")]
   HashError,
+  /// Invalid tool on the local toolchain.
+  #[error("toolchain error: {0}")]
+  ToolchainError(String),
   /// Unsupported architecture.
   #[error("Architecture Error: `{0}`")]
   ArchError(String),
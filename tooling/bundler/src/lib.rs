@@ -11,10 +11,13 @@
 //! # Platform support
 //! - macOS
 //!   - DMG and App bundles
+//!   - iOS App bundles and IPA (experimental)
 //! - Linux
 //!   - Appimage and Debian packages
 //! - Windows
 //!   - MSI using WiX
+//! - Android (experimental)
+//!   - APK and AAB built with Gradle
 
 /// The bundle API.
 pub mod bundle;
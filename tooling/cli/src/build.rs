@@ -7,20 +7,14 @@ use crate::{
     app_paths::{app_dir, tauri_dir},
     command_env,
     config::{get as get_config, AppUrl, HookCommand, WindowUrl, MERGE_CONFIG_EXTENSION_NAME},
-    updater_signature::{read_key_from_file, secret_key as updater_secret_key, sign_file},
   },
   interface::{AppInterface, AppSettings, Interface},
   CommandExt, Result,
 };
 use anyhow::{bail, Context};
 use clap::{ArgAction, Parser};
-use log::{debug, error, info, warn};
-use std::{
-  env::{set_current_dir, var_os},
-  path::{Path, PathBuf},
-  process::Command,
-};
-use tauri_bundler::bundle::{bundle_project, Bundle, PackageType};
+use log::{debug, error, info};
+use std::{env::set_current_dir, process::Command};
 
 #[derive(Debug, Clone, Parser)]
 #[clap(about = "Tauri build")]
@@ -36,6 +30,9 @@ pub struct Options {
   /// It must be one of the values outputted by `$rustc --print target-list` or `universal-apple-darwin` for an universal macOS application.
   ///
   /// Note that compiling an universal macOS application requires both `aarch64-apple-darwin` and `x86_64-apple-darwin` targets to be installed.
+  ///
+  /// Android targets such as `aarch64-linux-android` build the app library with the NDK set in `ANDROID_NDK_HOME` and bundle it with Gradle.
+  /// iOS targets such as `aarch64-apple-ios` can only be bundled on macOS.
   #[clap(short, long)]
   pub target: Option<String>,
   /// Space or comma separated list of features to activate
@@ -43,7 +40,7 @@ pub struct Options {
   pub features: Option<Vec<String>>,
   /// Space or comma separated list of bundles to package.
   ///
-  /// Each bundle must be one of `deb`, `appimage`, `msi`, `app` or `dmg` on MacOS and `updater` on all platforms.
+  /// Each bundle must be one of `deb`, `appimage`, `msi`, `nsis`, `app` or `dmg` on MacOS, `apk` or `aab` for Android targets, `ios` or `ipa` for iOS targets and `updater` on all platforms.
   /// If `none` is specified, the bundler will be skipped.
   ///
   /// Note that the `updater` bundle is not automatically added so you must specify it if the updater is enabled.
@@ -52,6 +49,9 @@ pub struct Options {
   /// JSON string or path to JSON file to merge with tauri.conf.json
   #[clap(short, long)]
   pub config: Option<String>,
+  /// Name of the configuration profile to merge with tauri.conf.json, defaults to `release` or `dev` with `--debug`
+  #[clap(long)]
+  pub profile: Option<String>,
   /// Command line arguments passed to the runner
   pub args: Vec<String>,
 }
@@ -76,6 +76,15 @@ pub fn command(mut options: Options) -> Result<()> {
   let tauri_path = tauri_dir();
   set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
 
+  // lets `tauri-build` know the hooks are run by the CLI
+  std::env::set_var("TAURI_CLI", "true");
+  std::env::set_var(
+    "TAURI_PROFILE",
+    options
+      .profile
+      .as_deref()
+      .unwrap_or(if options.debug { "dev" } else { "release" }),
+  );
   let config = get_config(options.config.as_deref())?;
 
   let config_guard = config.lock().unwrap();
@@ -123,6 +132,15 @@ pub fn command(mut options: Options) -> Result<()> {
     )?;
   }
 
+  if let AppUrl::Files(web_asset_paths) = &config_.build.dist_dir {
+    if let Some(web_asset_path) = web_asset_paths.iter().find(|p| !p.exists()) {
+      return Err(anyhow::anyhow!(
+          "Unable to find your web assets, did you forget to build your web app? Your distDir includes \"{:?}\".",
+          web_asset_path
+        ));
+    }
+  }
+
   if let AppUrl::Url(WindowUrl::App(web_asset_path)) = &config_.build.dist_dir {
     if !web_asset_path.exists() {
       return Err(anyhow::anyhow!(
@@ -167,158 +185,19 @@ pub fn command(mut options: Options) -> Result<()> {
 
   interface.build(interface_options)?;
 
-  let app_settings = interface.app_settings();
-
   if config_.tauri.bundle.active {
-    let package_types = if let Some(names) = &options.bundles {
-      let mut types = vec![];
-      for name in names
-        .iter()
-        .flat_map(|n| n.split(',').map(|s| s.to_string()).collect::<Vec<String>>())
-      {
-        if name == "none" {
-          break;
-        }
-        match PackageType::from_short_name(&name) {
-          Some(package_type) => {
-            types.push(package_type);
-          }
-          None => {
-            return Err(anyhow::anyhow!(format!(
-              "Unsupported bundle format: {}",
-              name
-            )));
-          }
-        }
-      }
-      Some(types)
-    } else {
-      let targets = config_.tauri.bundle.targets.to_vec();
-      if targets.is_empty() {
-        None
-      } else {
-        Some(targets.into_iter().map(Into::into).collect())
-      }
-    };
-
-    if let Some(types) = &package_types {
-      if config_.tauri.updater.active && !types.contains(&PackageType::Updater) {
-        warn!("The updater is enabled but the bundle target list does not contain `updater`, so the updater artifacts won't be generated.");
-      }
-    }
-
-    // if we have a package to bundle, let's run the `before_bundle_command`.
-    if package_types.as_ref().map_or(true, |p| !p.is_empty()) {
-      if let Some(before_bundle) = config_.build.before_bundle_command.clone() {
-        run_hook(
-          "beforeBundleCommand",
-          before_bundle,
-          &interface,
-          options.debug,
-        )?;
-      }
-    }
-
-    let settings = app_settings
-      .get_bundler_settings(&options.into(), config_, out_dir, package_types)
-      .with_context(|| "failed to build bundler settings")?;
-
-    // set env vars used by the bundler
-    #[cfg(target_os = "linux")]
-    {
-      use crate::helpers::config::ShellAllowlistOpen;
-      if matches!(
-        config_.tauri.allowlist.shell.open,
-        ShellAllowlistOpen::Flag(true) | ShellAllowlistOpen::Validate(_)
-      ) {
-        std::env::set_var("APPIMAGE_BUNDLE_XDG_OPEN", "1");
-      }
-      if config_.tauri.system_tray.is_some() {
-        if let Ok(tray) = std::env::var("TAURI_TRAY") {
-          std::env::set_var(
-            "TRAY_LIBRARY_PATH",
-            if tray == "ayatana" {
-              format!(
-                "{}/libayatana-appindicator3.so.1",
-                pkgconfig_utils::get_library_path("ayatana-appindicator3-0.1")
-                  .expect("failed to get ayatana-appindicator library path using pkg-config.")
-              )
-            } else {
-              format!(
-                "{}/libappindicator3.so.1",
-                pkgconfig_utils::get_library_path("appindicator3-0.1")
-                  .expect("failed to get libappindicator-gtk library path using pkg-config.")
-              )
-            },
-          );
-        } else {
-          std::env::set_var(
-            "TRAY_LIBRARY_PATH",
-            pkgconfig_utils::get_appindicator_library_path(),
-          );
-        }
-      }
-      if config_.tauri.bundle.appimage.bundle_media_framework {
-        std::env::set_var("APPIMAGE_BUNDLE_GSTREAMER", "1");
-      }
-    }
-
-    let bundles = bundle_project(settings).with_context(|| "failed to bundle project")?;
-
-    let updater_bundles: Vec<&Bundle> = bundles
-      .iter()
-      .filter(|bundle| bundle.package_type == PackageType::Updater)
-      .collect();
-    // If updater is active and we bundled it
-    if config_.tauri.updater.active && !updater_bundles.is_empty() {
-      // if no password provided we use an empty string
-      let password = var_os("TAURI_KEY_PASSWORD").map(|v| v.to_str().unwrap().to_string());
-      // get the private key
-      let secret_key = if let Some(mut private_key) =
-        var_os("TAURI_PRIVATE_KEY").map(|v| v.to_str().unwrap().to_string())
-      {
-        // check if env var points to a file..
-        let pk_dir = Path::new(&private_key);
-        // Check if user provided a path or a key
-        // We validate if the path exist or not.
-        if pk_dir.exists() {
-          // read file content and use it as private key
-          private_key = read_key_from_file(pk_dir)?;
-        }
-        updater_secret_key(private_key, password)
-      } else {
-        Err(anyhow::anyhow!("A public key has been found, but no private key. Make sure to set `TAURI_PRIVATE_KEY` environment variable."))
-      }?;
-
-      let pubkey = base64::decode(&config_.tauri.updater.pubkey)?;
-      let pub_key_decoded = String::from_utf8_lossy(&pubkey);
-      let public_key = minisign::PublicKeyBox::from_string(&pub_key_decoded)?.into_public_key()?;
-
-      // make sure we have our package built
-      let mut signed_paths = Vec::new();
-      for elem in updater_bundles {
-        // we expect to have only one path in the vec but we iter if we add
-        // another type of updater package who require multiple file signature
-        for path in elem.bundle_paths.iter() {
-          // sign our path from environment variables
-          let (signature_path, signature) = sign_file(&secret_key, path)?;
-          if signature.keynum() != public_key.keynum() {
-            return Err(anyhow::anyhow!(
-              "The updater secret key from `TAURI_PRIVATE_KEY` does not match the public key defined in `tauri.conf.json > tauri > updater > pubkey`."
-            ));
-          }
-          signed_paths.append(&mut vec![signature_path]);
-        }
-      }
-
-      print_signed_updater_archive(&signed_paths)?;
-    }
+    crate::bundle::bundle(&options.into(), config_, &interface, out_dir)?;
   }
 
   Ok(())
 }
 
-fn run_hook(name: &str, hook: HookCommand, interface: &AppInterface, debug: bool) -> Result<()> {
+pub fn run_hook(
+  name: &str,
+  hook: HookCommand,
+  interface: &AppInterface,
+  debug: bool,
+) -> Result<()> {
   let (script, script_cwd) = match hook {
     HookCommand::Script(s) if s.is_empty() => (None, None),
     HookCommand::Script(s) => (Some(s), None),
@@ -363,51 +242,3 @@ fn run_hook(name: &str, hook: HookCommand, interface: &AppInterface, debug: bool
 
   Ok(())
 }
-
-fn print_signed_updater_archive(output_paths: &[PathBuf]) -> crate::Result<()> {
-  let pluralised = if output_paths.len() == 1 {
-    "updater archive"
-  } else {
-    "updater archives"
-  };
-  let msg = format!("{} {} at:", output_paths.len(), pluralised);
-  info!("{}", msg);
-  for path in output_paths {
-    info!("        {}", path.display());
-  }
-  Ok(())
-}
-
-#[cfg(target_os = "linux")]
-mod pkgconfig_utils {
-  use std::{path::PathBuf, process::Command};
-
-  pub fn get_appindicator_library_path() -> PathBuf {
-    match get_library_path("ayatana-appindicator3-0.1") {
-      Some(p) => format!("{}/libayatana-appindicator3.so.1", p).into(),
-      None => match get_library_path("appindicator3-0.1") {
-        Some(p) => format!("{}/libappindicator3.so.1", p).into(),
-        None => panic!("Can't detect any appindicator library"),
-      },
-    }
-  }
-
-  /// Gets the folder in which a library is located using `pkg-config`.
-  pub fn get_library_path(name: &str) -> Option<String> {
-    let mut cmd = Command::new("pkg-config");
-    cmd.env("PKG_CONFIG_ALLOW_SYSTEM_LIBS", "1");
-    cmd.arg("--libs-only-L");
-    cmd.arg(name);
-    if let Ok(output) = cmd.output() {
-      if !output.stdout.is_empty() {
-        // output would be "-L/path/to/library\n"
-        let word = output.stdout[2..].to_vec();
-        return Some(String::from_utf8_lossy(&word).trim().to_string());
-      } else {
-        None
-      }
-    } else {
-      None
-    }
-  }
-}
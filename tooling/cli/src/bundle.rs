@@ -0,0 +1,356 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  build::run_hook,
+  helpers::{
+    app_paths::tauri_dir,
+    config::{get as get_config, Config},
+    updater_signature::{
+      public_key as updater_public_key, read_key_from_file, secret_key as updater_secret_key,
+      sign_file,
+    },
+  },
+  interface::{AppInterface, AppSettings, Interface},
+  Result,
+};
+use anyhow::{bail, Context};
+use clap::{ArgAction, Parser};
+use log::{info, warn};
+use std::{
+  env::{set_current_dir, var_os},
+  path::{Path, PathBuf},
+};
+use tauri_bundler::bundle::{bundle_project, Bundle, PackageType};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Tauri bundler, packages an application that was already compiled")]
+pub struct Options {
+  /// Bundles the binary built with the debug flag
+  #[clap(short, long)]
+  pub debug: bool,
+  /// Target triple the binary was built for.
+  ///
+  /// It must be one of the values outputted by `$rustc --print target-list` or `universal-apple-darwin` for an universal macOS application.
+  #[clap(short, long)]
+  pub target: Option<String>,
+  /// Space or comma separated list of features the binary was built with
+  #[clap(short, long, action = ArgAction::Append, num_args(0..))]
+  pub features: Option<Vec<String>>,
+  /// Space or comma separated list of bundles to package.
+  ///
+  /// Each bundle must be one of `deb`, `appimage`, `msi`, `nsis`, `app` or `dmg` on MacOS, `apk` or `aab` for Android targets, `ios` or `ipa` for iOS targets and `updater` on all platforms.
+  /// Defaults to `tauri.conf.json > tauri > bundle > targets`.
+  ///
+  /// Note that the `updater` bundle is not automatically added so you must specify it if the updater is enabled.
+  #[clap(short, long, action = ArgAction::Append, num_args(0..))]
+  pub bundles: Option<Vec<String>>,
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<String>,
+  /// Name of the configuration profile to merge with tauri.conf.json, defaults to `release` or `dev` with `--debug`
+  #[clap(long)]
+  pub profile: Option<String>,
+  /// Command line arguments the binary was built with, such as `--no-default-features`
+  pub args: Vec<String>,
+}
+
+impl From<crate::build::Options> for Options {
+  fn from(options: crate::build::Options) -> Self {
+    Self {
+      debug: options.debug,
+      target: options.target,
+      features: options.features,
+      bundles: options.bundles,
+      config: options.config,
+      profile: options.profile,
+      args: options.args,
+    }
+  }
+}
+
+pub fn command(mut options: Options) -> Result<()> {
+  if let Some(config) = &options.config {
+    if !config.starts_with('{') {
+      options.config = Some(
+        std::fs::read_to_string(config).with_context(|| "failed to read custom configuration")?,
+      );
+    }
+  }
+
+  let tauri_path = tauri_dir();
+  set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
+
+  // lets the hooks know they are run by the CLI
+  std::env::set_var("TAURI_CLI", "true");
+  std::env::set_var(
+    "TAURI_PROFILE",
+    options
+      .profile
+      .as_deref()
+      .unwrap_or(if options.debug { "dev" } else { "release" }),
+  );
+  let config = get_config(options.config.as_deref())?;
+
+  let config_guard = config.lock().unwrap();
+  let config_ = config_guard.as_ref().unwrap();
+
+  if !config_.tauri.bundle.active {
+    warn!("The bundler is disabled in `tauri.conf.json > tauri > bundle > active`, skipping.");
+    return Ok(());
+  }
+
+  let interface = AppInterface::new(config_, options.target.clone())?;
+
+  options
+    .features
+    .get_or_insert(Vec::new())
+    .extend(config_.build.features.clone().unwrap_or_default());
+
+  let bin_path = interface
+    .app_settings()
+    .app_binary_path(&options.clone().into())?;
+  if !bin_path.exists() {
+    bail!(
+      "The application binary was not found at {}, make sure it was built with the same `--target` and `--debug` flags, e.g. with `tauri build --bundles none`.",
+      bin_path.display()
+    );
+  }
+  let out_dir = bin_path.parent().unwrap();
+
+  bundle(&options, config_, &interface, out_dir)
+}
+
+/// Packages the binary found in `out_dir` and signs the updater archives.
+pub fn bundle(
+  options: &Options,
+  config: &Config,
+  interface: &AppInterface,
+  out_dir: &Path,
+) -> Result<()> {
+  let package_types = if let Some(names) = &options.bundles {
+    Some(parse_package_types(names)?)
+  } else {
+    let targets = config.tauri.bundle.targets.to_vec();
+    if targets.is_empty() {
+      None
+    } else {
+      Some(targets.into_iter().map(Into::into).collect())
+    }
+  };
+
+  if let Some(types) = &package_types {
+    if config.tauri.updater.active && !types.contains(&PackageType::Updater) {
+      warn!("The updater is enabled but the bundle target list does not contain `updater`, so the updater artifacts won't be generated.");
+    }
+  }
+
+  // if we have a package to bundle, let's run the `before_bundle_command`.
+  if package_types.as_ref().map_or(true, |p| !p.is_empty()) {
+    if let Some(before_bundle) = config.build.before_bundle_command.clone() {
+      run_hook(
+        "beforeBundleCommand",
+        before_bundle,
+        interface,
+        options.debug,
+      )?;
+    }
+  }
+
+  let settings = interface
+    .app_settings()
+    .get_bundler_settings(&options.clone().into(), config, out_dir, package_types)
+    .with_context(|| "failed to build bundler settings")?;
+
+  // set env vars used by the bundler
+  #[cfg(target_os = "linux")]
+  {
+    use crate::helpers::config::ShellAllowlistOpen;
+    if matches!(
+      config.tauri.allowlist.shell.open,
+      ShellAllowlistOpen::Flag(true) | ShellAllowlistOpen::Validate(_)
+    ) {
+      std::env::set_var("APPIMAGE_BUNDLE_XDG_OPEN", "1");
+    }
+    if config.tauri.system_tray.is_some() {
+      if let Ok(tray) = std::env::var("TAURI_TRAY") {
+        std::env::set_var(
+          "TRAY_LIBRARY_PATH",
+          if tray == "ayatana" {
+            format!(
+              "{}/libayatana-appindicator3.so.1",
+              pkgconfig_utils::get_library_path("ayatana-appindicator3-0.1")
+                .expect("failed to get ayatana-appindicator library path using pkg-config.")
+            )
+          } else {
+            format!(
+              "{}/libappindicator3.so.1",
+              pkgconfig_utils::get_library_path("appindicator3-0.1")
+                .expect("failed to get libappindicator-gtk library path using pkg-config.")
+            )
+          },
+        );
+      } else {
+        std::env::set_var(
+          "TRAY_LIBRARY_PATH",
+          pkgconfig_utils::get_appindicator_library_path(),
+        );
+      }
+    }
+    if config.tauri.bundle.appimage.bundle_media_framework {
+      std::env::set_var("APPIMAGE_BUNDLE_GSTREAMER", "1");
+    }
+  }
+
+  let bundles = bundle_project(settings).with_context(|| "failed to bundle project")?;
+
+  let updater_bundles: Vec<&Bundle> = bundles
+    .iter()
+    .filter(|bundle| bundle.package_type == PackageType::Updater)
+    .collect();
+  // If updater is active and we bundled it
+  if config.tauri.updater.active && !updater_bundles.is_empty() {
+    // if no password provided we use an empty string
+    let password = var_os("TAURI_KEY_PASSWORD").map(|v| v.to_str().unwrap().to_string());
+    // get the private key
+    let secret_key = if let Some(mut private_key) =
+      var_os("TAURI_PRIVATE_KEY").map(|v| v.to_str().unwrap().to_string())
+    {
+      // check if env var points to a file..
+      let pk_dir = Path::new(&private_key);
+      // Check if user provided a path or a key
+      // We validate if the path exist or not.
+      if pk_dir.exists() {
+        // read file content and use it as private key
+        private_key = read_key_from_file(pk_dir)?;
+      }
+      updater_secret_key(private_key, password)
+    } else {
+      Err(anyhow::anyhow!("A public key has been found, but no private key. Make sure to set `TAURI_PRIVATE_KEY` environment variable."))
+    }?;
+
+    let public_key = updater_public_key(&config.tauri.updater.pubkey)?;
+
+    // make sure we have our package built
+    let mut signed_paths = Vec::new();
+    for elem in updater_bundles {
+      // we expect to have only one path in the vec but we iter if we add
+      // another type of updater package who require multiple file signature
+      for path in elem.bundle_paths.iter() {
+        // sign our path from environment variables
+        let (signature_path, signature) = sign_file(&secret_key, path)?;
+        if signature.keynum() != public_key.keynum() {
+          return Err(anyhow::anyhow!(
+            "The updater secret key from `TAURI_PRIVATE_KEY` does not match the public key defined in `tauri.conf.json > tauri > updater > pubkey`."
+          ));
+        }
+        signed_paths.append(&mut vec![signature_path]);
+      }
+    }
+
+    print_signed_updater_archive(&signed_paths)?;
+  }
+
+  Ok(())
+}
+
+/// Parses the space or comma separated list of bundles, where `none` ends the list.
+fn parse_package_types(names: &[String]) -> Result<Vec<PackageType>> {
+  let mut types = vec![];
+  for name in names
+    .iter()
+    .flat_map(|n| n.split(',').map(|s| s.to_string()).collect::<Vec<String>>())
+  {
+    if name == "none" {
+      break;
+    }
+    match PackageType::from_short_name(&name) {
+      Some(package_type) => {
+        types.push(package_type);
+      }
+      None => {
+        return Err(anyhow::anyhow!(format!(
+          "Unsupported bundle format: {}",
+          name
+        )));
+      }
+    }
+  }
+  Ok(types)
+}
+
+fn print_signed_updater_archive(output_paths: &[PathBuf]) -> crate::Result<()> {
+  let pluralised = if output_paths.len() == 1 {
+    "updater archive"
+  } else {
+    "updater archives"
+  };
+  let msg = format!("{} {} at:", output_paths.len(), pluralised);
+  info!("{}", msg);
+  for path in output_paths {
+    info!("        {}", path.display());
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod pkgconfig_utils {
+  use std::{path::PathBuf, process::Command};
+
+  pub fn get_appindicator_library_path() -> PathBuf {
+    match get_library_path("ayatana-appindicator3-0.1") {
+      Some(p) => format!("{}/libayatana-appindicator3.so.1", p).into(),
+      None => match get_library_path("appindicator3-0.1") {
+        Some(p) => format!("{}/libappindicator3.so.1", p).into(),
+        None => panic!("Can't detect any appindicator library"),
+      },
+    }
+  }
+
+  /// Gets the folder in which a library is located using `pkg-config`.
+  pub fn get_library_path(name: &str) -> Option<String> {
+    let mut cmd = Command::new("pkg-config");
+    cmd.env("PKG_CONFIG_ALLOW_SYSTEM_LIBS", "1");
+    cmd.arg("--libs-only-L");
+    cmd.arg(name);
+    if let Ok(output) = cmd.output() {
+      if !output.stdout.is_empty() {
+        // output would be "-L/path/to/library\n"
+        let word = output.stdout[2..].to_vec();
+        return Some(String::from_utf8_lossy(&word).trim().to_string());
+      } else {
+        None
+      }
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::parse_package_types;
+  use tauri_bundler::bundle::PackageType;
+
+  #[test]
+  fn package_types() {
+    let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+      parse_package_types(&names(&["deb,appimage", "updater"])).unwrap(),
+      vec![
+        PackageType::Deb,
+        PackageType::AppImage,
+        PackageType::Updater
+      ]
+    );
+    assert!(parse_package_types(&names(&["none"])).unwrap().is_empty());
+    assert_eq!(
+      parse_package_types(&names(&["foo"]))
+        .unwrap_err()
+        .to_string(),
+      "Unsupported bundle format: foo"
+    );
+  }
+}
@@ -6,7 +6,10 @@ use crate::{
   helpers::{
     app_paths::{app_dir, tauri_dir},
     command_env,
-    config::{get as get_config, reload as reload_config, AppUrl, BeforeDevCommand, WindowUrl},
+    config::{
+      dev_session_path, get as get_config, reload as reload_config, AppUrl, BeforeDevCommand,
+      WindowUrl,
+    },
   },
   interface::{AppInterface, ExitReason, Interface},
   CommandExt, Result,
@@ -53,6 +56,9 @@ pub struct Options {
   /// JSON string or path to JSON file to merge with tauri.conf.json
   #[clap(short, long)]
   pub config: Option<String>,
+  /// Name of the configuration profile to merge with tauri.conf.json, defaults to `dev` or `release` with `--release`
+  #[clap(long)]
+  pub profile: Option<String>,
   /// Run the code in release mode
   #[clap(long = "release")]
   pub release_mode: bool,
@@ -64,6 +70,9 @@ pub struct Options {
   /// Disable the dev server for static files.
   #[clap(long)]
   pub no_dev_server: bool,
+  /// Serve the dev server over HTTPS with a locally-trusted certificate, for APIs that require a secure context.
+  #[clap(long)]
+  pub https: bool,
 }
 
 pub fn command(options: Options) -> Result<()> {
@@ -90,6 +99,19 @@ fn command_internal(mut options: Options) -> Result<()> {
 
   set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
 
+  // lets `tauri-build` know the hooks are run by the CLI
+  std::env::set_var("TAURI_CLI", "true");
+  std::env::set_var(
+    "TAURI_PROFILE",
+    options
+      .profile
+      .as_deref()
+      .unwrap_or(if options.release_mode {
+        "release"
+      } else {
+        "dev"
+      }),
+  );
   let config = get_config(options.config.as_deref())?;
 
   let mut interface = AppInterface::new(
@@ -181,6 +203,7 @@ fn command_internal(mut options: Options) -> Result<()> {
 
         let _ = ctrlc::set_handler(move || {
           kill_before_dev_process();
+          let _ = std::fs::remove_file(dev_session_path());
           #[cfg(not(debug_assertions))]
           let _ = check_for_updates();
           exit(130);
@@ -222,27 +245,22 @@ fn command_internal(mut options: Options) -> Result<()> {
     .dev_path
     .clone();
   if !options.no_dev_server {
-    if let AppUrl::Url(WindowUrl::App(path)) = &dev_path {
+    let serve_dirs = match &dev_path {
+      AppUrl::Url(WindowUrl::App(path)) => vec![path.clone()],
+      AppUrl::Files(paths) if paths.iter().all(|p| p.is_dir()) => paths.clone(),
+      _ => Vec::new(),
+    };
+    if !serve_dirs.is_empty() {
       use crate::helpers::web_dev_server::{start_dev_server, SERVER_URL};
-      if path.exists() {
-        let path = path.canonicalize()?;
-        start_dev_server(path);
+      if serve_dirs.iter().all(|p| p.exists()) {
+        let serve_dirs = serve_dirs
+          .iter()
+          .map(|p| p.canonicalize())
+          .collect::<std::io::Result<Vec<_>>>()?;
+        start_dev_server(serve_dirs);
         dev_path = AppUrl::Url(WindowUrl::External(SERVER_URL.parse().unwrap()));
 
-        // TODO: in v2, use an env var to pass the url to the app context
-        // or better separate the config passed from the cli internally and
-        // config passed by the user in `--config` into to separate env vars
-        // and the context merges, the user first, then the internal cli config
-        if let Some(c) = options.config {
-          let mut c: tauri_utils::config::Config = serde_json::from_str(&c)?;
-          c.build.dev_path = dev_path.clone();
-          options.config = Some(serde_json::to_string(&c).unwrap());
-        } else {
-          options.config = Some(format!(
-            r#"{{ "build": {{ "devPath": "{}" }} }}"#,
-            SERVER_URL
-          ))
-        }
+        set_dev_path(&mut options.config, &dev_path)?;
       }
     }
 
@@ -250,7 +268,7 @@ fn command_internal(mut options: Options) -> Result<()> {
   }
 
   if std::env::var_os("TAURI_SKIP_DEVSERVER_CHECK") != Some("true".into()) {
-    if let AppUrl::Url(WindowUrl::External(dev_server_url)) = dev_path {
+    if let AppUrl::Url(WindowUrl::External(dev_server_url)) = &dev_path {
       let host = dev_server_url
         .host()
         .unwrap_or_else(|| panic!("No host name in the URL"));
@@ -300,6 +318,25 @@ fn command_internal(mut options: Options) -> Result<()> {
     }
   }
 
+  if options.https {
+    match &dev_path {
+      AppUrl::Url(WindowUrl::External(dev_server_url)) if dev_server_url.scheme() == "http" => {
+        let proxy_url = crate::helpers::https_proxy::start_https_proxy(dev_server_url.clone())?;
+        info!("Serving {} over HTTPS on {}", dev_server_url, proxy_url);
+        dev_path = AppUrl::Url(WindowUrl::External(proxy_url));
+        set_dev_path(&mut options.config, &dev_path)?;
+        reload_config(options.config.as_deref())?;
+      }
+      AppUrl::Url(WindowUrl::External(_)) => {
+        warn!("The dev server is not served over HTTP, ignoring `--https`.")
+      }
+      _ => warn!("`--https` requires a dev server, ignoring it."),
+    }
+  }
+
+  // the windows of a previous `tauri dev` process with the same id are not restored
+  let _ = std::fs::remove_file(dev_session_path());
+
   let exit_on_panic = options.exit_on_panic;
   let no_watch = options.no_watch;
   interface.dev(options.into(), move |status, reason| {
@@ -307,12 +344,29 @@ fn command_internal(mut options: Options) -> Result<()> {
   })
 }
 
+/// Overrides the `devPath` of the configuration passed to the app.
+fn set_dev_path(config: &mut Option<String>, dev_path: &AppUrl) -> Result<()> {
+  // TODO: in v2, use an env var to pass the url to the app context
+  // or better separate the config passed from the cli internally and
+  // config passed by the user in `--config` into to separate env vars
+  // and the context merges, the user first, then the internal cli config
+  if let Some(c) = config {
+    let mut c: tauri_utils::config::Config = serde_json::from_str(c)?;
+    c.build.dev_path = dev_path.clone();
+    *config = Some(serde_json::to_string(&c).unwrap());
+  } else {
+    *config = Some(format!(r#"{{ "build": {{ "devPath": "{}" }} }}"#, dev_path))
+  }
+  Ok(())
+}
+
 fn on_dev_exit(status: ExitStatus, reason: ExitReason, exit_on_panic: bool, no_watch: bool) {
   if no_watch
     || (!matches!(reason, ExitReason::TriggeredKill)
       && (exit_on_panic || matches!(reason, ExitReason::NormalExit)))
   {
     kill_before_dev_process();
+    let _ = std::fs::remove_file(dev_session_path());
     #[cfg(not(debug_assertions))]
     let _ = check_for_updates();
     exit(status.code().unwrap_or(0));
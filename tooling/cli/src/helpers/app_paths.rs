@@ -59,17 +59,26 @@ fn lookup<F: Fn(&PathBuf) -> bool>(dir: &Path, checker: F) -> Option<PathBuf> {
   None
 }
 
-fn get_tauri_dir() -> PathBuf {
-  let cwd = current_dir().expect("failed to read cwd");
-
-  if cwd.join("src-tauri/tauri.conf.json").exists()
-    || cwd.join("src-tauri/tauri.conf.json5").exists()
-  {
-    return cwd.join("src-tauri/");
+fn find_tauri_dir(dir: &Path) -> Option<PathBuf> {
+  let src_tauri = dir.join("src-tauri/");
+  if folder_has_configuration_file(&src_tauri) {
+    return Some(src_tauri);
   }
 
-  lookup(&cwd, |path| folder_has_configuration_file(path) || is_configuration_file(path))
-  .map(|p| if p.is_dir() { p } else {  p.parent().unwrap().to_path_buf() })
+  lookup(dir, |path| {
+    folder_has_configuration_file(path) || is_configuration_file(path)
+  })
+  .map(|p| {
+    if p.is_dir() {
+      p
+    } else {
+      p.parent().unwrap().to_path_buf()
+    }
+  })
+}
+
+fn get_tauri_dir() -> PathBuf {
+  find_tauri_dir(&current_dir().expect("failed to read cwd"))
   .unwrap_or_else(||
     panic!("Couldn't recognize the current folder as a Tauri project. It must contain a `{}`, `{}` or `{}` file in any subfolder.",
       ConfigFormat::Json.into_file_name(),
@@ -99,3 +108,20 @@ pub fn app_dir() -> &'static PathBuf {
 pub fn tauri_dir() -> PathBuf {
   get_tauri_dir()
 }
+
+#[cfg(test)]
+mod tests {
+  use super::find_tauri_dir;
+
+  #[test]
+  fn src_tauri_toml_config() {
+    let dir = std::env::temp_dir().join(format!("tauri-cli-app-paths-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src-tauri")).unwrap();
+    std::fs::write(dir.join("src-tauri/Tauri.toml"), "").unwrap();
+
+    assert_eq!(find_tauri_dir(&dir), Some(dir.join("src-tauri/")));
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
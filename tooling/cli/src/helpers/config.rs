@@ -4,8 +4,9 @@
 
 use anyhow::Context;
 use json_patch::merge;
-use log::error;
+use log::{error, warn};
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use serde_json::Value as JsonValue;
 
 pub use tauri_utils::config::*;
@@ -14,11 +15,17 @@ use std::{
   collections::HashMap,
   env::{set_var, var_os},
   ffi::OsStr,
-  process::exit,
+  path::{Path, PathBuf},
+  process::{exit, Command},
   sync::{Arc, Mutex},
+  time::SystemTime,
 };
 
 pub const MERGE_CONFIG_EXTENSION_NAME: &str = "--config";
+/// The environment variable that tells the app where to read the reloaded configuration from in `tauri dev`.
+pub const DEV_CONFIG_PATH_ENV: &str = "TAURI_DEV_CONFIG_PATH";
+/// The environment variable that tells the app where to keep its windows across the restarts of `tauri dev`.
+pub const DEV_SESSION_PATH_ENV: &str = "TAURI_DEV_SESSION_PATH";
 
 pub struct ConfigMetadata {
   /// The actual configuration, merged with any extension.
@@ -94,6 +101,16 @@ pub fn wix_settings(config: WixConfig) -> tauri_bundler::WixSettings {
     banner_path: config.banner_path,
     dialog_image_path: config.dialog_image_path,
     fips_compliant: var_os("TAURI_FIPS_COMPLIANT").map_or(false, |v| v == "true"),
+    upgrade_code: config.upgrade_code,
+  }
+}
+
+pub fn nsis_settings(config: NsisConfig) -> tauri_bundler::NsisSettings {
+  tauri_bundler::NsisSettings {
+    template: config.template,
+    install_mode: config.install_mode,
+    preserved_paths: config.preserved_paths,
+    preserved_registry_keys: config.preserved_registry_keys,
   }
 }
 
@@ -115,7 +132,7 @@ fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<Confi
   let mut extensions = HashMap::new();
 
   if let Some((platform_config, config_path)) =
-    tauri_utils::config::parse::read_platform(tauri_dir)?
+    tauri_utils::config::parse::read_platform(tauri_dir.clone())?
   {
     merge(&mut config, &platform_config);
     extensions.insert(
@@ -124,6 +141,9 @@ fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<Confi
     );
   }
 
+  let profile = var_os("TAURI_PROFILE").and_then(|p| p.into_string().ok());
+  tauri_utils::config::parse::apply_profile(&mut config, profile.as_deref())?;
+
   if let Some(merge_config) = merge_config {
     set_var("TAURI_CONFIG", merge_config);
     let merge_config: JsonValue =
@@ -132,12 +152,15 @@ fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<Confi
     extensions.insert(MERGE_CONFIG_EXTENSION_NAME.into(), merge_config);
   };
 
+  tauri_utils::config::parse::interpolate_env(&mut config)?;
+
   if config_path.extension() == Some(OsStr::new("json"))
     || config_path.extension() == Some(OsStr::new("json5"))
   {
     let schema: JsonValue = serde_json::from_str(include_str!("../../schema.json"))?;
     let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
     let result = schema.validate(&config);
+    let mut valid = true;
     if let Err(errors) = result {
       for error in errors {
         let path = error.instance_path.clone().into_vec().join(" > ");
@@ -147,10 +170,44 @@ fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<Confi
           error!("`{}` error on `{}`: {}", config_file_name, path, error);
         }
       }
-      if !reload {
-        exit(1);
+      valid = false;
+    }
+
+    if let Some(plugins) = config
+      .get("plugins")
+      .and_then(|p| p.as_object())
+      .filter(|p| !p.is_empty())
+    {
+      for (plugin, schema) in plugin_config_schemas(&tauri_dir) {
+        let plugin_config = match plugins.get(&plugin) {
+          Some(c) => c,
+          None => continue,
+        };
+        let schema = match jsonschema::JSONSchema::compile(&schema) {
+          Ok(s) => s,
+          Err(e) => {
+            error!("invalid config schema for plugin `{}`: {}", plugin, e);
+            continue;
+          }
+        };
+        let result = schema.validate(plugin_config);
+        if let Err(errors) = result {
+          for error in errors {
+            let path = ["plugins".to_string(), plugin.clone()]
+              .into_iter()
+              .chain(error.instance_path.clone().into_vec())
+              .collect::<Vec<_>>()
+              .join(" > ");
+            error!("`{}` error on `{}`: {}", config_file_name, path, error);
+          }
+          valid = false;
+        }
       }
     }
+
+    if !valid && !reload {
+      exit(1);
+    }
   }
 
   let config: Config = serde_json::from_value(config)?;
@@ -163,10 +220,109 @@ fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<Confi
   Ok(config_handle().clone())
 }
 
+/// Reads the JSON Schema of the plugin configurations, declared by the plugin crates on their `Cargo.toml`
+/// with `package.metadata.tauri-plugin.name` and `package.metadata.tauri-plugin.config-schema`.
+///
+/// The schemas are cached until the `Cargo.toml` or `Cargo.lock` of the app changes.
+fn plugin_config_schemas(tauri_dir: &Path) -> HashMap<String, JsonValue> {
+  type Cache = Option<(PathBuf, Vec<Option<SystemTime>>, HashMap<String, JsonValue>)>;
+  static CACHE: Lazy<Mutex<Cache>> = Lazy::new(Default::default);
+
+  let modified = ["Cargo.toml", "Cargo.lock"]
+    .iter()
+    .map(|file| {
+      std::fs::metadata(tauri_dir.join(file))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+    })
+    .collect::<Vec<_>>();
+
+  let mut cache = CACHE.lock().unwrap();
+  if let Some((dir, cached_modified, schemas)) = &*cache {
+    if dir == tauri_dir && cached_modified == &modified {
+      return schemas.clone();
+    }
+  }
+  let schemas = read_plugin_config_schemas(tauri_dir);
+  cache.replace((tauri_dir.to_path_buf(), modified, schemas.clone()));
+  schemas
+}
+
+/// Reads the plugin schemas of the packages resolved by `cargo metadata`, including the registry dependencies.
+///
+/// Returns an empty map, warning about it, if the metadata cannot be resolved.
+fn read_plugin_config_schemas(tauri_dir: &Path) -> HashMap<String, JsonValue> {
+  #[derive(Deserialize)]
+  struct CargoMetadata {
+    packages: Vec<Package>,
+  }
+
+  #[derive(Deserialize)]
+  struct Package {
+    manifest_path: PathBuf,
+    #[serde(default)]
+    metadata: JsonValue,
+  }
+
+  let metadata = Command::new("cargo")
+    .args(["metadata", "--format-version", "1"])
+    .current_dir(tauri_dir)
+    .output()
+    .map_err(|e| e.to_string())
+    .and_then(|output| {
+      if output.status.success() {
+        serde_json::from_slice::<CargoMetadata>(&output.stdout).map_err(|e| e.to_string())
+      } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+      }
+    });
+  let packages = match metadata {
+    Ok(metadata) => metadata.packages,
+    Err(e) => {
+      warn!(
+        "failed to resolve the Cargo metadata, the plugin configurations are not validated: {}",
+        e
+      );
+      return HashMap::new();
+    }
+  };
+
+  packages
+    .into_iter()
+    .filter_map(|package| {
+      let plugin = package.metadata.get("tauri-plugin")?;
+      let name = plugin.get("name")?.as_str()?.to_string();
+      let schema_path = package
+        .manifest_path
+        .parent()?
+        .join(plugin.get("config-schema")?.as_str()?);
+      let schema = std::fs::read_to_string(schema_path).ok()?;
+      Some((name, serde_json::from_str(&schema).ok()?))
+    })
+    .collect()
+}
+
 pub fn get(merge_config: Option<&str>) -> crate::Result<ConfigHandle> {
   get_internal(merge_config, false)
 }
 
+/// The file where the configuration is written when it changes while running `tauri dev`.
+pub fn dev_config_path() -> PathBuf {
+  std::env::temp_dir().join(format!("tauri-dev-config-{}.json", std::process::id()))
+}
+
+/// The file where the app saves its windows, so they are restored when it is rebuilt while running `tauri dev`.
+pub fn dev_session_path() -> PathBuf {
+  std::env::temp_dir().join(format!("tauri-dev-session-{}.json", std::process::id()))
+}
+
+/// Writes the configuration to [`dev_config_path`] so the running app can reload it.
+pub fn write_dev_config(config: &Config) -> crate::Result<()> {
+  let path = dev_config_path();
+  std::fs::write(&path, serde_json::to_vec(config)?)
+    .with_context(|| format!("failed to write {}", path.display()))
+}
+
 pub fn reload(merge_config: Option<&str>) -> crate::Result<ConfigHandle> {
   get_internal(merge_config, true)
 }
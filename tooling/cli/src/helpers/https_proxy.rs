@@ -0,0 +1,357 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use anyhow::Context;
+use axum::{
+  body::Body,
+  http::{
+    header::{HeaderValue, HOST, UPGRADE},
+    Request, StatusCode,
+  },
+  response::{IntoResponse, Response},
+  routing::any,
+  Router,
+};
+use axum_server::tls_rustls::RustlsConfig;
+use hyper::{client::HttpConnector, Client};
+use log::{error, warn};
+use rcgen::{
+  BasicConstraints, Certificate, CertificateParams, CidrSubnet, DnType, GeneralSubtree, IsCa,
+  KeyPair, KeyUsagePurpose, NameConstraints, SanType,
+};
+use std::{
+  fs,
+  io::Write,
+  net::{Ipv4Addr, TcpListener},
+  path::Path,
+};
+use url::Url;
+
+/// The port of the proxy, fixed so the origin and its storage persist between runs.
+const PROXY_PORT: u16 = 1431;
+
+/// Serves the given HTTP dev server over HTTPS, returning the URL to load in the webview.
+///
+/// The certificate is signed by a local certificate authority, created on the first run
+/// and added to the trusted roots if the user agrees. It can only sign certificates for `localhost`.
+pub fn start_https_proxy(upstream: Url) -> crate::Result<Url> {
+  let ca = certificate_authority()?;
+  let (cert, key) = server_certificate(&ca)?;
+
+  let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, PROXY_PORT))
+    .with_context(|| format!("failed to bind the HTTPS proxy to port {}", PROXY_PORT))?;
+  let (url, authority) = proxy_url(upstream)?;
+
+  let runtime = tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .context("failed to start the HTTPS proxy runtime")?;
+  let config = runtime
+    .block_on(RustlsConfig::from_pem(cert.into_bytes(), key.into_bytes()))
+    .context("failed to load the HTTPS proxy certificate")?;
+
+  std::thread::spawn(move || {
+    runtime.block_on(async move {
+      let client = Client::new();
+      let router = Router::new().fallback(any(move |req| {
+        proxy(req, client.clone(), authority.clone())
+      }));
+      if let Err(e) = axum_server::from_tcp_rustls(listener, config)
+        .serve(router.into_make_service())
+        .await
+      {
+        error!("The HTTPS proxy stopped: {}", e);
+      }
+    })
+  });
+
+  Ok(url)
+}
+
+/// Returns the URL of the proxy for the given dev server URL, and the authority of the dev server.
+fn proxy_url(upstream: Url) -> crate::Result<(Url, String)> {
+  let authority = format!(
+    "{}:{}",
+    upstream.host_str().context("No host name in the URL")?,
+    upstream
+      .port_or_known_default()
+      .context("No port number in the URL")?
+  );
+
+  let mut url = upstream;
+  url
+    .set_scheme("https")
+    .map_err(|_| anyhow::anyhow!("failed to set the scheme of {}", url))?;
+  url.set_host(Some("localhost"))?;
+  url
+    .set_port(Some(PROXY_PORT))
+    .map_err(|_| anyhow::anyhow!("failed to set the port of {}", url))?;
+
+  Ok((url, authority))
+}
+
+async fn proxy(
+  mut req: Request<Body>,
+  client: Client<HttpConnector>,
+  authority: String,
+) -> Response {
+  let path = req
+    .uri()
+    .path_and_query()
+    .map(|p| p.as_str())
+    .unwrap_or("/");
+  *req.uri_mut() = match format!("http://{}{}", authority, path).parse() {
+    Ok(uri) => uri,
+    Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+  };
+  match HeaderValue::from_str(&authority) {
+    Ok(host) => req.headers_mut().insert(HOST, host),
+    Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+  };
+
+  // websockets, e.g. the hot module replacement of the dev server, are piped once both sides upgraded
+  let client_upgrade = req
+    .headers()
+    .contains_key(UPGRADE)
+    .then(|| hyper::upgrade::on(&mut req));
+
+  match client.request(req).await {
+    Ok(mut res) => {
+      if let Some(client_upgrade) = client_upgrade {
+        if res.status() == StatusCode::SWITCHING_PROTOCOLS {
+          let upstream_upgrade = hyper::upgrade::on(&mut res);
+          tokio::spawn(async move {
+            if let (Ok(mut client), Ok(mut upstream)) =
+              tokio::join!(client_upgrade, upstream_upgrade)
+            {
+              let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+            }
+          });
+        }
+      }
+      res.into_response()
+    }
+    Err(e) => (StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+  }
+}
+
+/// Loads the local certificate authority, creating it if needed.
+fn certificate_authority() -> crate::Result<Certificate> {
+  let dir = dirs_next::data_local_dir()
+    .context("failed to resolve the local data directory")?
+    .join("tauri")
+    .join("dev-certs");
+  let cert_path = dir.join("ca.pem");
+  let key_path = dir.join("ca-key.pem");
+
+  if cert_path.exists() && key_path.exists() {
+    let key = KeyPair::from_pem(&fs::read_to_string(&key_path)?)?;
+    let params = CertificateParams::from_ca_cert_pem(&fs::read_to_string(&cert_path)?, key)?;
+    return Ok(Certificate::from_params(params)?);
+  }
+
+  let mut params = CertificateParams::default();
+  params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+  params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+  // a trusted root can sign certificates for any host, so the leaked key could be used to impersonate any website
+  params.name_constraints = Some(NameConstraints {
+    permitted_subtrees: vec![
+      GeneralSubtree::DnsName("localhost".into()),
+      GeneralSubtree::IpAddress(CidrSubnet::V4(
+        Ipv4Addr::LOCALHOST.octets(),
+        [255, 255, 255, 255],
+      )),
+    ],
+    excluded_subtrees: Vec::new(),
+  });
+  params
+    .distinguished_name
+    .push(DnType::CommonName, "Tauri Development CA");
+  let ca = Certificate::from_params(params)?;
+
+  create_private_dir(&dir)?;
+  write_private_file(&key_path, ca.serialize_private_key_pem().as_bytes())?;
+  fs::write(&cert_path, ca.serialize_pem()?)?;
+
+  let trusted = confirm_trust(&cert_path)? && trust(&cert_path);
+  if !trusted {
+    warn!(
+      "The development certificate authority is not trusted. Add {} to the trusted roots to load the app over HTTPS.",
+      cert_path.display()
+    );
+  }
+
+  Ok(ca)
+}
+
+/// Asks the user to add the certificate authority to the trusted roots, which is skipped on CI.
+fn confirm_trust(cert: &Path) -> crate::Result<bool> {
+  if std::env::var_os("CI").is_some() {
+    return Ok(false);
+  }
+  let theme = dialoguer::theme::ColorfulTheme::default();
+  dialoguer::Confirm::with_theme(&theme)
+    .with_prompt(format!(
+      "Add the development certificate authority {} to the trusted roots? It can only sign certificates for localhost",
+      cert.display()
+    ))
+    .default(false)
+    .interact()
+    .map_err(Into::into)
+}
+
+/// Creates the directory of the certificate authority, only accessible by the current user.
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+  let mut builder = fs::DirBuilder::new();
+  builder.recursive(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::DirBuilderExt;
+    builder.mode(0o700);
+  }
+  builder.create(dir)
+}
+
+/// Writes the private key, readable only by the current user from its creation.
+/// On Windows the local data directory is already restricted to the user.
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+  let mut options = fs::OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    options.mode(0o600);
+    // the mode is only applied to new files
+    if path.exists() {
+      fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+  }
+  options.open(path)?.write_all(contents)
+}
+
+/// Signs a certificate for `localhost`, valid for a year.
+fn server_certificate(ca: &Certificate) -> crate::Result<(String, String)> {
+  let mut params = CertificateParams::new(vec!["localhost".to_string()]);
+  params
+    .subject_alt_names
+    .push(SanType::IpAddress(Ipv4Addr::LOCALHOST.into()));
+  params
+    .distinguished_name
+    .push(DnType::CommonName, "localhost");
+  // some platforms reject long lived server certificates
+  let now = time::OffsetDateTime::now_utc();
+  params.not_before = now - time::Duration::days(1);
+  params.not_after = now + time::Duration::days(365);
+
+  let cert = Certificate::from_params(params)?;
+  Ok((
+    cert.serialize_pem_with_signer(ca)?,
+    cert.serialize_private_key_pem(),
+  ))
+}
+
+#[cfg(target_os = "macos")]
+fn trust(cert: &Path) -> bool {
+  let keychain = match dirs_next::home_dir() {
+    Some(home) => home.join("Library/Keychains/login.keychain-db"),
+    None => return false,
+  };
+  std::process::Command::new("security")
+    .args(["add-trusted-cert", "-r", "trustRoot", "-k"])
+    .arg(keychain)
+    .arg(cert)
+    .status()
+    .map(|s| s.success())
+    .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn trust(cert: &Path) -> bool {
+  std::process::Command::new("certutil")
+    .args(["-user", "-addstore", "Root"])
+    .arg(cert)
+    .status()
+    .map(|s| s.success())
+    .unwrap_or(false)
+}
+
+// WebKitGTK uses the system trust store, which can only be changed by root
+#[cfg(not(any(target_os = "macos", windows)))]
+fn trust(cert: &Path) -> bool {
+  let sudo = |args: &[&std::ffi::OsStr]| {
+    std::process::Command::new("sudo")
+      .args(args)
+      .status()
+      .map(|s| s.success())
+      .unwrap_or(false)
+  };
+  let install = |dir: &str, file_name: &str, update: &str| {
+    let target = Path::new(dir).join(file_name);
+    sudo(&["cp".as_ref(), cert.as_os_str(), target.as_os_str()]) && sudo(&[update.as_ref()])
+  };
+
+  // Debian, Ubuntu
+  if Path::new("/usr/local/share/ca-certificates").is_dir() {
+    install(
+      "/usr/local/share/ca-certificates",
+      "tauri-dev-ca.crt",
+      "update-ca-certificates",
+    )
+  }
+  // Fedora, RHEL
+  else if Path::new("/etc/pki/ca-trust/source/anchors").is_dir() {
+    install(
+      "/etc/pki/ca-trust/source/anchors",
+      "tauri-dev-ca.pem",
+      "update-ca-trust",
+    )
+  }
+  // Arch and the other distributions using p11-kit
+  else {
+    sudo(&[
+      "trust".as_ref(),
+      "anchor".as_ref(),
+      "--store".as_ref(),
+      cert.as_os_str(),
+    ])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::proxy_url;
+
+  #[test]
+  fn url() {
+    let (url, authority) = proxy_url("http://127.0.0.1:5173/app?page=1".parse().unwrap()).unwrap();
+    assert_eq!(url.as_str(), "https://localhost:1431/app?page=1");
+    assert_eq!(authority, "127.0.0.1:5173");
+
+    let (url, authority) = proxy_url("http://localhost/".parse().unwrap()).unwrap();
+    assert_eq!(url.as_str(), "https://localhost:1431/");
+    assert_eq!(authority, "localhost:80");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn private_key_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("tauri-cli-dev-certs-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    super::create_private_dir(&dir).unwrap();
+    let key = dir.join("ca-key.pem");
+    std::fs::write(&key, "").unwrap();
+    super::write_private_file(&key, b"key").unwrap();
+
+    let mode =
+      |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(&dir), 0o700);
+    // an existing file is restricted too
+    assert_eq!(mode(&key), 0o600);
+    assert_eq!(std::fs::read(&key).unwrap(), b"key");
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
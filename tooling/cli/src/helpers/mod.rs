@@ -5,6 +5,7 @@
 pub mod app_paths;
 pub mod config;
 pub mod framework;
+pub mod https_proxy;
 pub mod template;
 pub mod updater_signature;
 pub mod web_dev_server;
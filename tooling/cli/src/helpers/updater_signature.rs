@@ -4,7 +4,9 @@
 
 use anyhow::Context;
 use base64::{decode, encode};
-use minisign::{sign, KeyPair as KP, SecretKey, SecretKeyBox, SignatureBox};
+use minisign::{
+  sign, verify, KeyPair as KP, PublicKey, PublicKeyBox, SecretKey, SecretKeyBox, SignatureBox,
+};
 use std::{
   fs::{self, File, OpenOptions},
   io::{BufReader, BufWriter, Write},
@@ -105,10 +107,7 @@ where
   P: AsRef<Path>,
 {
   let bin_path = bin_path.as_ref();
-  // We need to append .sig at the end it's where the signature will be stored
-  let mut extension = bin_path.extension().unwrap().to_os_string();
-  extension.push(".sig");
-  let signature_path = bin_path.with_extension(extension);
+  let signature_path = signature_path(bin_path);
 
   let mut signature_box_writer = create_file(&signature_path)?;
 
@@ -134,6 +133,44 @@ where
   Ok((fs::canonicalize(&signature_path)?, signature_box))
 }
 
+/// Verifies the signature of a file, returning its trusted comment.
+pub fn verify_file<P, S>(
+  public_key: &PublicKey,
+  bin_path: P,
+  signature_path: S,
+) -> crate::Result<String>
+where
+  P: AsRef<Path>,
+  S: AsRef<Path>,
+{
+  let signature_path = signature_path.as_ref();
+  let encoded_signature = fs::read_to_string(signature_path)
+    .with_context(|| format!("failed to read signature {}", signature_path.display()))?;
+  let signature_box = SignatureBox::from_string(&decode_key(encoded_signature.trim().into())?)
+    .with_context(|| "failed to load signature")?;
+
+  let data_reader = open_data_file(bin_path)?;
+  verify(public_key, &signature_box, data_reader, true, false, false)
+    .with_context(|| "signature verification failed")?;
+
+  signature_box.trusted_comment().map_err(Into::into)
+}
+
+/// The path where the signature of a file is stored: its path with `.sig` appended.
+pub fn signature_path<P: AsRef<Path>>(bin_path: P) -> PathBuf {
+  let mut path = bin_path.as_ref().as_os_str().to_os_string();
+  path.push(".sig");
+  path.into()
+}
+
+/// Gets the updater public key from its base64 encoded form, as defined in `tauri.conf.json > tauri > updater > pubkey`.
+pub fn public_key(public_key: &str) -> crate::Result<PublicKey> {
+  let decoded_public = decode_key(public_key.trim().into())?;
+  let pk_box =
+    PublicKeyBox::from_string(&decoded_public).with_context(|| "failed to load public key")?;
+  pk_box.into_public_key().map_err(Into::into)
+}
+
 /// Gets the updater secret key from the given private key and password.
 pub fn secret_key(private_key: String, password: Option<String>) -> crate::Result<SecretKey> {
   let decoded_secret = decode_key(private_key)?;
@@ -164,3 +201,31 @@ where
     .map_err(|e| minisign::PError::new(minisign::ErrorKind::Io, e))?;
   Ok(BufReader::new(file))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{generate_key, public_key, secret_key, sign_file, signature_path, verify_file};
+
+  #[test]
+  fn sign_and_verify() {
+    let dir = std::env::temp_dir().join(format!("tauri-cli-signer-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("app.tar.gz");
+    std::fs::write(&file, "signed contents").unwrap();
+
+    let key = generate_key(Some("password".into())).unwrap();
+    let secret_key = secret_key(key.sk, Some("password".into())).unwrap();
+    let public_key = public_key(&key.pk).unwrap();
+
+    let (signature, _) = sign_file(&secret_key, &file).unwrap();
+    assert_eq!(signature, signature_path(&file).canonicalize().unwrap());
+    let trusted_comment = verify_file(&public_key, &file, &signature).unwrap();
+    assert!(trusted_comment.ends_with("\tfile:app.tar.gz"));
+
+    std::fs::write(&file, "tampered contents").unwrap();
+    assert!(verify_file(&public_key, &file, &signature).is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -1,7 +1,10 @@
 use axum::{
   extract::{ws::WebSocket, WebSocketUpgrade},
-  http::{header::CONTENT_TYPE, Request, StatusCode},
-  response::IntoResponse,
+  http::{
+    header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    Request, StatusCode,
+  },
+  response::{IntoResponse, Response},
   routing::get,
   Router, Server,
 };
@@ -10,8 +13,10 @@ use kuchiki::{traits::TendrilSink, NodeRef};
 use notify::RecursiveMode;
 use notify_debouncer_mini::new_debouncer;
 use std::{
+  collections::hash_map::DefaultHasher,
+  hash::{Hash, Hasher},
   net::SocketAddr,
-  path::{Path, PathBuf},
+  path::PathBuf,
   str::FromStr,
   sync::{mpsc::sync_channel, Arc},
   thread,
@@ -24,13 +29,12 @@ const AUTO_RELOAD_SCRIPT: &str = include_str!("./auto-reload.js");
 pub const SERVER_URL: &str = "http://127.0.0.1:1430";
 
 struct State {
-  serve_dir: PathBuf,
+  /// The directories to serve, where later directories override the files of earlier ones.
+  serve_dirs: Vec<PathBuf>,
   tx: Sender<()>,
 }
 
-pub fn start_dev_server<P: AsRef<Path>>(path: P) {
-  let serve_dir = path.as_ref().to_path_buf();
-
+pub fn start_dev_server(serve_dirs: Vec<PathBuf>) {
   std::thread::spawn(move || {
     tokio::runtime::Builder::new_current_thread()
       .enable_io()
@@ -40,7 +44,7 @@ pub fn start_dev_server<P: AsRef<Path>>(path: P) {
         let (tx, _) = channel(1);
 
         let tokio_tx = tx.clone();
-        let serve_dir_ = serve_dir.clone();
+        let serve_dirs_ = serve_dirs.clone();
         thread::spawn(move || {
           let (tx, rx) = sync_channel(1);
           let mut watcher = new_debouncer(Duration::from_secs(1), None, move |r| {
@@ -50,10 +54,12 @@ pub fn start_dev_server<P: AsRef<Path>>(path: P) {
           })
           .unwrap();
 
-          watcher
-            .watcher()
-            .watch(&serve_dir_, RecursiveMode::Recursive)
-            .unwrap();
+          for serve_dir in &serve_dirs_ {
+            watcher
+              .watcher()
+              .watch(serve_dir, RecursiveMode::Recursive)
+              .unwrap();
+          }
 
           loop {
             if rx.recv().is_ok() {
@@ -62,7 +68,7 @@ pub fn start_dev_server<P: AsRef<Path>>(path: P) {
           }
         });
 
-        let state = Arc::new(State { serve_dir, tx });
+        let state = Arc::new(State { serve_dirs, tx });
         let router = Router::new()
           .fallback(
             Router::new().nest(
@@ -88,7 +94,7 @@ pub fn start_dev_server<P: AsRef<Path>>(path: P) {
   });
 }
 
-async fn handler<T>(req: Request<T>, state: Arc<State>) -> impl IntoResponse {
+async fn handler<T>(req: Request<T>, state: Arc<State>) -> Response {
   let uri = req.uri().to_string();
   let uri = if uri == "/" {
     &uri
@@ -96,10 +102,10 @@ async fn handler<T>(req: Request<T>, state: Arc<State>) -> impl IntoResponse {
     uri.strip_prefix('/').unwrap_or(&uri)
   };
 
-  let file = std::fs::read(state.serve_dir.join(uri))
-    .or_else(|_| std::fs::read(state.serve_dir.join(format!("{}.html", &uri))))
-    .or_else(|_| std::fs::read(state.serve_dir.join(format!("{}/index.html", &uri))))
-    .or_else(|_| std::fs::read(state.serve_dir.join("index.html")));
+  let file = read_file(&state.serve_dirs, uri)
+    .or_else(|_| read_file(&state.serve_dirs, &format!("{}.html", &uri)))
+    .or_else(|_| read_file(&state.serve_dirs, &format!("{}/index.html", &uri)))
+    .or_else(|_| read_file(&state.serve_dirs, "index.html"));
 
   file
     .map(|mut f| {
@@ -129,17 +135,57 @@ async fn handler<T>(req: Request<T>, state: Arc<State>) -> impl IntoResponse {
         f = document.to_string().as_bytes().to_vec();
       }
 
-      (StatusCode::OK, [(CONTENT_TYPE, mime_type)], f)
+      let mut hasher = DefaultHasher::new();
+      f.hash(&mut hasher);
+      let etag = format!("\"{:x}\"", hasher.finish());
+
+      let not_modified = req
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+          value
+            .split(',')
+            .any(|tag| tag.trim().trim_start_matches("W/") == etag)
+        })
+        .unwrap_or(false);
+      if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+      }
+
+      (
+        StatusCode::OK,
+        [
+          (CONTENT_TYPE, mime_type),
+          (ETAG, etag),
+          (CACHE_CONTROL, "no-cache".into()),
+        ],
+        f,
+      )
+        .into_response()
     })
     .unwrap_or_else(|_| {
       (
         StatusCode::NOT_FOUND,
-        [(CONTENT_TYPE, "text/plain".into())],
+        [(CONTENT_TYPE, "text/plain".to_string())],
         vec![],
       )
+        .into_response()
     })
 }
 
+/// Reads the file from the last directory that contains it.
+fn read_file(serve_dirs: &[PathBuf], path: &str) -> std::io::Result<Vec<u8>> {
+  let mut result = Err(std::io::ErrorKind::NotFound.into());
+  for serve_dir in serve_dirs.iter().rev() {
+    result = std::fs::read(serve_dir.join(path));
+    if result.is_ok() {
+      break;
+    }
+  }
+  result
+}
+
 async fn ws_handler(mut ws: WebSocket, state: Arc<State>) {
   let mut rx = state.tx.subscribe();
   while tokio::select! {
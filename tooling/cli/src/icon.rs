@@ -2,63 +2,79 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{helpers::app_paths::tauri_dir, Result};
+use crate::{
+  helpers::{app_paths::tauri_dir, config::get as get_config},
+  Result,
+};
 
 use std::{
-  collections::HashMap,
-  fs::{create_dir_all, File},
-  io::{BufWriter, Write},
+  fs::{create_dir_all, read_to_string, write},
   path::{Path, PathBuf},
 };
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use clap::Parser;
-use icns::{IconFamily, IconType};
-use image::{
-  codecs::{
-    ico::{IcoEncoder, IcoFrame},
-    png::{CompressionType, FilterType as PngFilterType, PngEncoder},
-  },
-  imageops::FilterType,
-  open, ColorType, DynamicImage, ImageEncoder,
+use tauri_utils::config::parse::{parse_json5_value, ConfigFormat};
+use tauri_utils::icons::{
+  padded, resize_and_save_png, save_png, silhouette, write_icns, write_ico, DynamicImage,
+  ICO_SIZES, PNG_ICONS,
 };
-use serde::Deserialize;
-
-#[derive(Debug, Deserialize)]
-struct IcnsEntry {
-  size: u32,
-  ostype: String,
-}
+use toml_edit::{value, Document};
 
 #[derive(Debug, Parser)]
 #[clap(about = "Generates various icons for all major platforms")]
 pub struct Options {
   // TODO: Confirm 1240px
-  /// Path to the source icon (png, 1240x1240px with transparency).
+  /// Path to the source icon (png, jpeg, bmp or ico, 1240x1240px with transparency).
   #[clap(default_value = "./app-icon.png")]
   input: PathBuf,
   /// Output directory.
   /// Default: 'icons' directory next to the tauri.conf.json file.
   #[clap(short, long)]
   output: Option<PathBuf>,
+  /// Background color of the Android adaptive icon, as `#RRGGBB` or `#AARRGGBB`.
+  #[clap(long, default_value = "#FFFFFF")]
+  android_background: String,
 }
 
+/// The Android densities and their scale factor.
+const ANDROID_DENSITIES: [(&str, f32); 5] = [
+  ("mdpi", 1.0),
+  ("hdpi", 1.5),
+  ("xhdpi", 2.0),
+  ("xxhdpi", 3.0),
+  ("xxxhdpi", 4.0),
+];
+
+/// The macOS tray icon, relative to the Tauri directory.
+const TRAY_TEMPLATE_ICON: &str = "icons/tray/iconTemplate.png";
+
+const ANDROID_ADAPTIVE_ICON: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<adaptive-icon xmlns:android="http://schemas.android.com/apk/res/android">
+  <background android:drawable="@color/ic_launcher_background"/>
+  <foreground android:drawable="@mipmap/ic_launcher_foreground"/>
+  <monochrome android:drawable="@mipmap/ic_launcher_monochrome"/>
+</adaptive-icon>
+"#;
+
 pub fn command(options: Options) -> Result<()> {
   let input = options.input;
+  let update_config = options.output.is_none();
   let out_dir = options.output.unwrap_or_else(|| tauri_dir().join("icons"));
+  let android_background = options.android_background;
+  let is_hex = android_background
+    .strip_prefix('#')
+    .map(|c| (c.len() == 6 || c.len() == 8) && c.chars().all(|c| c.is_ascii_hexdigit()))
+    .unwrap_or(false);
+  if !is_hex {
+    bail!(
+      "Invalid Android background color `{}`, expected `#RRGGBB` or `#AARRGGBB`",
+      android_background
+    );
+  }
   create_dir_all(&out_dir).context("Can't create output directory")?;
 
-  // Try to read the image as a DynamicImage, convert it to rgba8 and turn it into a DynamicImage again.
-  // Both things should be catched by the explicit conversions to rgba8 anyway.
-  let source = open(input)
-    .context("Can't read and decode source image")?
-    .into_rgba8();
-
-  let source = DynamicImage::ImageRgba8(source);
-
-  if source.height() != source.width() {
-    panic!("Source image must be square");
-  }
+  let source = tauri_utils::icons::open(&input).context("Can't read and decode source image")?;
 
   appx(&source, &out_dir).context("Failed to generate appx icons")?;
 
@@ -68,6 +84,14 @@ pub fn command(options: Options) -> Result<()> {
 
   png(&source, &out_dir).context("Failed to generate png icons")?;
 
+  tray(&source, &out_dir).context("Failed to generate tray icons")?;
+
+  android(&source, &out_dir, &android_background).context("Failed to generate Android icons")?;
+
+  if update_config {
+    update_tray_config().context("Failed to reference the macOS tray icon")?;
+  }
+
   Ok(())
 }
 
@@ -88,101 +112,205 @@ fn appx(source: &DynamicImage, out_dir: &Path) -> Result<()> {
 // Main target: macOS
 fn icns(source: &DynamicImage, out_dir: &Path) -> Result<()> {
   log::info!(action = "ICNS"; "Creating icon.icns");
-  let entries: HashMap<String, IcnsEntry> =
-    serde_json::from_slice(include_bytes!("helpers/icns.json")).unwrap();
-
-  let mut family = IconFamily::new();
+  Ok(write_icns(source, &out_dir.join("icon.icns"))?)
+}
 
-  for (name, entry) in entries {
-    let size = entry.size;
-    let mut buf = Vec::new();
+// Generate .ico file with layers for the most common sizes.
+// Main target: Windows
+fn ico(source: &DynamicImage, out_dir: &Path) -> Result<()> {
+  log::info!(action = "ICO"; "Creating icon.ico");
+  Ok(write_ico(source, ICO_SIZES, &out_dir.join("icon.ico"))?)
+}
 
-    let image = source.resize_exact(size, size, FilterType::Lanczos3);
+// Generate the monochrome tray icon.
+// Main target: macOS template images, the Windows tray uses the app icon
+fn tray(source: &DynamicImage, out_dir: &Path) -> Result<()> {
+  let out_dir = out_dir.join("tray");
+  create_dir_all(&out_dir)?;
 
-    write_png(image.as_bytes(), &mut buf, size)?;
+  // the menu bar draws the icon at 22pt, so use twice that for retina displays
+  log::info!(action = "Tray"; "Creating iconTemplate.png");
+  resize_and_save_png(
+    &silhouette(source, 0),
+    44,
+    &out_dir.join("iconTemplate.png"),
+  )?;
 
-    let image = icns::Image::read_png(&buf[..])?;
+  Ok(())
+}
 
-    family
-      .add_icon_with_type(
-        &image,
-        IconType::from_ostype(entry.ostype.parse().unwrap()).unwrap(),
-      )
-      .with_context(|| format!("Can't add {} to Icns Family", name))?;
+// Generate the legacy launcher icons and the adaptive icon layers in the Android resources layout.
+// Main target: Android
+fn android(source: &DynamicImage, out_dir: &Path, background: &str) -> Result<()> {
+  let res_dir = out_dir.join("android");
+  let monochrome = silhouette(source, 255);
+
+  for (density, scale) in ANDROID_DENSITIES {
+    let mipmap_dir = res_dir.join(format!("mipmap-{}", density));
+    create_dir_all(&mipmap_dir)?;
+    log::info!(action = "Android"; "Creating mipmap-{} icons", density);
+
+    resize_and_save_png(
+      source,
+      (48.0 * scale) as u32,
+      &mipmap_dir.join("ic_launcher.png"),
+    )?;
+    // the layers are 108dp, but only the 66dp in the middle are always visible
+    let layer_size = (108.0 * scale) as u32;
+    let content_size = (66.0 * scale) as u32;
+    save_png(
+      &padded(source, layer_size, content_size),
+      &mipmap_dir.join("ic_launcher_foreground.png"),
+    )?;
+    save_png(
+      &padded(&monochrome, layer_size, content_size),
+      &mipmap_dir.join("ic_launcher_monochrome.png"),
+    )?;
   }
 
-  let mut out_file = BufWriter::new(File::create(out_dir.join("icon.icns"))?);
-  family.write(&mut out_file)?;
-  out_file.flush()?;
+  log::info!(action = "Android"; "Creating the adaptive icon");
+  let anydpi_dir = res_dir.join("mipmap-anydpi-v26");
+  create_dir_all(&anydpi_dir)?;
+  write(anydpi_dir.join("ic_launcher.xml"), ANDROID_ADAPTIVE_ICON)?;
+  let values_dir = res_dir.join("values");
+  create_dir_all(&values_dir)?;
+  write(
+    values_dir.join("ic_launcher_background.xml"),
+    format!(
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+  <color name="ic_launcher_background">{}</color>
+</resources>
+"#,
+      background
+    ),
+  )?;
 
   Ok(())
 }
 
-// Generate .ico file with layers for the most common sizes.
-// Main target: Windows
-fn ico(source: &DynamicImage, out_dir: &Path) -> Result<()> {
-  log::info!(action = "ICO"; "Creating icon.ico");
-  let mut frames = Vec::new();
-
-  for size in [32, 16, 24, 48, 64, 256] {
-    let image = source.resize_exact(size, size, FilterType::Lanczos3);
-
-    // Only the 256px layer can be compressed according to the ico specs.
-    if size == 256 {
-      let mut buf = Vec::new();
-
-      write_png(image.as_bytes(), &mut buf, size)?;
+// Reference the template icon in the macOS configuration, when the app has a system tray.
+// The macOS configuration is written in the format of the app configuration.
+fn update_tray_config() -> Result<()> {
+  let config = get_config(None)?;
+  if config
+    .lock()
+    .unwrap()
+    .as_ref()
+    .unwrap()
+    .tauri
+    .system_tray
+    .is_none()
+  {
+    return Ok(());
+  }
 
-      frames.push(IcoFrame::with_encoded(buf, size, size, ColorType::Rgba8)?)
+  let tauri_dir = tauri_dir();
+  if tauri_dir.join(ConfigFormat::Toml.into_file_name()).exists() {
+    let config_path = tauri_dir.join("Tauri.macos.toml");
+    // edit the document to keep its comments and formatting
+    let mut macos_config = if config_path.exists() {
+      read_to_string(&config_path)?
+        .parse::<Document>()
+        .context("failed to parse Tauri.macos.toml")?
+    } else {
+      Document::new()
+    };
+    macos_config["tauri"]["systemTray"]["iconPath"] = value(TRAY_TEMPLATE_ICON);
+    macos_config["tauri"]["systemTray"]["iconAsTemplate"] = value(true);
+
+    log::info!(action = "Config"; "Updating Tauri.macos.toml");
+    write(&config_path, macos_config.to_string())?;
+  } else {
+    let is_json5 = tauri_dir
+      .join(ConfigFormat::Json5.into_file_name())
+      .exists();
+    let file_name = if is_json5 {
+      "tauri.macos.conf.json5"
+    } else {
+      "tauri.macos.conf.json"
+    };
+    let config_path = tauri_dir.join(file_name);
+    let mut macos_config: serde_json::Value = if config_path.exists() {
+      let raw = read_to_string(&config_path)?;
+      if is_json5 {
+        parse_json5_value(&raw, &config_path)?
+      } else {
+        serde_json::from_str(&raw)?
+      }
     } else {
-      frames.push(IcoFrame::as_png(
-        image.as_bytes(),
-        size,
-        size,
-        ColorType::Rgba8,
-      )?);
-    }
+      serde_json::json!({})
+    };
+    let system_tray = macos_config
+      .as_object_mut()
+      .with_context(|| format!("{} must be an object", file_name))?
+      .entry("tauri")
+      .or_insert_with(|| serde_json::json!({}))
+      .as_object_mut()
+      .context("`tauri` must be an object")?
+      .entry("systemTray")
+      .or_insert_with(|| serde_json::json!({}))
+      .as_object_mut()
+      .context("`tauri > systemTray` must be an object")?;
+    system_tray.insert("iconPath".into(), TRAY_TEMPLATE_ICON.into());
+    system_tray.insert("iconAsTemplate".into(), true.into());
+
+    // JSON is valid JSON5, but the comments of a JSON5 file are not kept
+    log::info!(action = "Config"; "Updating {}", file_name);
+    write(&config_path, serde_json::to_string_pretty(&macos_config)?)?;
   }
 
-  let mut out_file = BufWriter::new(File::create(out_dir.join("icon.ico"))?);
-  let encoder = IcoEncoder::new(&mut out_file);
-  encoder.encode_images(&frames)?;
-  out_file.flush()?;
-
   Ok(())
 }
 
 // Generate .png files in 32x32, 128x128, 256x256, 512x512 (icon.png)
 // Main target: Linux
 fn png(source: &DynamicImage, out_dir: &Path) -> Result<()> {
-  for size in [32, 128, 256, 512] {
-    let file_name = match size {
-      256 => "128x128@2x.png".to_string(),
-      512 => "icon.png".to_string(),
-      _ => format!("{}x{}.png", size, size),
-    };
-
+  for (size, file_name) in PNG_ICONS {
     log::info!(action = "PNG"; "Creating {}", file_name);
-    resize_and_save_png(source, size, &out_dir.join(&file_name))?;
+    resize_and_save_png(source, *size, &out_dir.join(file_name))?;
   }
 
   Ok(())
 }
 
-// Resize image and save it to disk.
-fn resize_and_save_png(source: &DynamicImage, size: u32, file_path: &Path) -> Result<()> {
-  let image = source.resize_exact(size, size, FilterType::Lanczos3);
+#[cfg(test)]
+mod tests {
+  use tauri_utils::icons::{open, DynamicImage};
 
-  let mut out_file = BufWriter::new(File::create(file_path)?);
+  #[test]
+  fn tray_and_android_icons() {
+    let dir = std::env::temp_dir().join(format!("tauri-cli-icon-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = DynamicImage::new_rgba8(512, 512);
 
-  write_png(image.as_bytes(), &mut out_file, size)?;
+    super::tray(&source, &dir).unwrap();
+    super::android(&source, &dir, "#123456").unwrap();
 
-  Ok(out_file.flush()?)
-}
-
-// Encode image data as png with compression.
-fn write_png<W: Write>(image_data: &[u8], w: W, size: u32) -> Result<()> {
-  let encoder = PngEncoder::new_with_quality(w, CompressionType::Best, PngFilterType::Adaptive);
-  encoder.write_image(image_data, size, size, ColorType::Rgba8)?;
-  Ok(())
+    let size = |path: &str| {
+      let image = open(&dir.join(path)).unwrap();
+      (image.width(), image.height())
+    };
+    assert_eq!(size("tray/iconTemplate.png"), (44, 44));
+    assert_eq!(size("android/mipmap-mdpi/ic_launcher.png"), (48, 48));
+    assert_eq!(
+      size("android/mipmap-xxxhdpi/ic_launcher_foreground.png"),
+      (432, 432)
+    );
+    assert_eq!(
+      size("android/mipmap-hdpi/ic_launcher_monochrome.png"),
+      (162, 162)
+    );
+    assert!(dir
+      .join("android/mipmap-anydpi-v26/ic_launcher.xml")
+      .exists());
+    assert!(
+      std::fs::read_to_string(dir.join("android/values/ic_launcher_background.xml"))
+        .unwrap()
+        .contains(">#123456</color>")
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
 }
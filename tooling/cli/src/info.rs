@@ -9,7 +9,7 @@ use crate::{
 };
 use clap::Parser;
 use colored::Colorize;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use std::{
   collections::HashMap,
@@ -87,7 +87,75 @@ enum PackageManager {
 
 #[derive(Debug, Parser)]
 #[clap(about = "Shows information about Tauri dependencies and project configuration")]
-pub struct Options;
+pub struct Options {
+  /// Print the information as JSON instead of the human readable format
+  #[clap(long)]
+  pub json: bool,
+}
+
+/// The information gathered by the `info` command.
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  environment: Environment,
+  packages: Vec<PackageInfo>,
+  app: Option<AppInfo>,
+  app_directory_structure: Option<Vec<String>>,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Environment {
+  os: String,
+  webview: Option<WebviewInfo>,
+  msvc: Option<Vec<String>>,
+  node: Option<String>,
+  #[serde(skip)]
+  node_target: String,
+  npm: Option<String>,
+  pnpm: Option<String>,
+  yarn: Option<String>,
+  rustup: Option<String>,
+  rustc: Option<String>,
+  cargo: Option<String>,
+  rust_toolchain: Option<String>,
+  rust_targets: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct WebviewInfo {
+  name: &'static str,
+  version: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum PackageKind {
+  Npm,
+  Rust,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageInfo {
+  name: String,
+  #[serde(rename = "type")]
+  kind: PackageKind,
+  version: Option<String>,
+  latest: Option<String>,
+  outdated: bool,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppInfo {
+  build_type: Option<String>,
+  csp: Option<String>,
+  dist_dir: Option<String>,
+  dev_path: Option<String>,
+  framework: Option<String>,
+  bundler: Option<String>,
+}
 
 fn version_metadata() -> Result<VersionMetadata> {
   serde_json::from_str::<VersionMetadata>(include_str!("../metadata.json")).map_err(Into::into)
@@ -387,12 +455,74 @@ fn active_rust_toolchain() -> crate::Result<Option<String>> {
   Ok(toolchain)
 }
 
+fn rust_tool_version(command: &str) -> Option<String> {
+  get_version(command, &[])
+    .unwrap_or_default()
+    .and_then(|v| v.split(' ').nth(1).map(Into::into))
+}
+
+fn installed_rust_targets() -> crate::Result<Vec<String>> {
+  let output = cross_command("rustup")
+    .args(["target", "list", "--installed"])
+    .output()?;
+  let targets = if output.status.success() {
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .map(|l| l.trim().to_string())
+      .filter(|l| !l.is_empty())
+      .collect()
+  } else {
+    Vec::new()
+  };
+  Ok(targets)
+}
+
+#[cfg(windows)]
+fn webview_info() -> WebviewInfo {
+  WebviewInfo {
+    name: "Webview2",
+    version: webview2_version().unwrap_or_default(),
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn webview_info() -> WebviewInfo {
+  let version = Command::new("defaults")
+    .args([
+      "read",
+      "/System/Library/Frameworks/WebKit.framework/Resources/Info.plist",
+      "CFBundleVersion",
+    ])
+    .output()
+    .ok()
+    .filter(|o| o.status.success())
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+  WebviewInfo {
+    name: "WebKit",
+    version,
+  }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn webview_info() -> WebviewInfo {
+  let version = Command::new("pkg-config")
+    .args(["--modversion", "webkit2gtk-4.0"])
+    .output()
+    .ok()
+    .filter(|o| o.status.success())
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+  WebviewInfo {
+    name: "webkit2gtk",
+    version,
+  }
+}
+
 fn crate_version(
   tauri_dir: &Path,
   manifest: Option<&CargoManifest>,
   lock: Option<&CargoLock>,
   name: &str,
-) -> (String, Option<String>) {
+) -> PackageInfo {
   let crate_lock_packages: Vec<CargoLockPackage> = lock
     .as_ref()
     .map(|lock| {
@@ -501,18 +631,37 @@ fn crate_version(
     .into_iter()
     .map(|v| semver::Version::parse(&v).unwrap())
     .max();
-  let suffix = match (crate_version, crate_latest_version(name)) {
+  let latest = crate_latest_version(name);
+  let outdated = match (crate_version, &latest) {
     (Some(version), Some(target_version)) => {
-      let target_version = semver::Version::parse(&target_version).unwrap();
-      if version < target_version {
-        Some(format!(" (outdated, latest: {})", target_version))
-      } else {
-        None
-      }
+      version < semver::Version::parse(target_version).unwrap()
     }
-    _ => None,
+    _ => false,
   };
-  (crate_version_string, suffix)
+  PackageInfo {
+    name: name.into(),
+    kind: PackageKind::Rust,
+    version: Some(crate_version_string),
+    latest,
+    outdated,
+  }
+}
+
+fn npm_package(name: &str, version: Option<String>, latest: Option<String>) -> PackageInfo {
+  let outdated = match (&version, &latest) {
+    (Some(version), Some(latest)) => matches!(
+      (semver::Version::parse(version), semver::Version::parse(latest)),
+      (Ok(version), Ok(latest)) if version < latest
+    ),
+    _ => false,
+  };
+  PackageInfo {
+    name: name.into(),
+    kind: PackageKind::Npm,
+    version,
+    latest,
+    outdated,
+  }
 }
 
 fn indent(spaces: usize) {
@@ -602,44 +751,50 @@ impl InfoBlock {
   }
 }
 
-pub fn command(_options: Options) -> Result<()> {
-  Section("Environment").display();
+pub fn command(options: Options) -> Result<()> {
+  let report = gather()?;
+  if options.json {
+    println!("{}", serde_json::to_string_pretty(&report)?);
+  } else {
+    report.display();
+  }
+  Ok(())
+}
+
+fn gather() -> Result<Report> {
+  let mut report = Report::default();
 
   let os_info = os_info::get();
-  VersionBlock::new(
-    "OS",
-    format!(
+  let metadata = version_metadata()?;
+  report.environment = Environment {
+    os: format!(
       "{} {} {:?}",
       os_info.os_type(),
       os_info.version(),
       os_info.bitness()
     ),
-  )
-  .display();
-
-  #[cfg(windows)]
-  VersionBlock::new(
-    "Webview2",
-    webview2_version().unwrap_or_default().unwrap_or_default(),
-  )
-  .display();
-
-  #[cfg(windows)]
-  {
-    let build_tools = build_tools_version()
+    webview: Some(webview_info()),
+    #[cfg(windows)]
+    msvc: Some(
+      build_tools_version()
+        .unwrap_or_default()
+        .unwrap_or_default(),
+    ),
+    #[cfg(not(windows))]
+    msvc: None,
+    node: get_version("node", &[])
       .unwrap_or_default()
-      .unwrap_or_default();
-
-    if build_tools.is_empty() {
-      InfoBlock::new("MSVC", "").display();
-    } else {
-      InfoBlock::new("MSVC", "").display();
-      for i in build_tools {
-        indent(6);
-        println!("{}", format!("{} {}", "-".cyan(), i));
-      }
-    }
-  }
+      .map(|v| v.chars().skip(1).collect()),
+    node_target: metadata.js_cli.node.replace(">= ", ""),
+    npm: get_version("npm", &[]).unwrap_or_default(),
+    pnpm: get_version("pnpm", &[]).unwrap_or_default(),
+    yarn: get_version("yarn", &[]).unwrap_or_default(),
+    rustup: rust_tool_version("rustup"),
+    rustc: rust_tool_version("rustc"),
+    cargo: rust_tool_version("cargo"),
+    rust_toolchain: active_rust_toolchain().unwrap_or_default(),
+    rust_targets: installed_rust_targets().unwrap_or_default(),
+  };
 
   let hook = panic::take_hook();
   panic::set_hook(Box::new(|_info| {
@@ -650,84 +805,6 @@ pub fn command(_options: Options) -> Result<()> {
     .unwrap_or_default();
   panic::set_hook(hook);
 
-  let yarn_version = get_version("yarn", &[])
-    .unwrap_or_default()
-    .unwrap_or_default();
-
-  let metadata = version_metadata()?;
-  VersionBlock::new(
-    "Node.js",
-    get_version("node", &[])
-      .unwrap_or_default()
-      .unwrap_or_default()
-      .chars()
-      .skip(1)
-      .collect::<String>(),
-  )
-  .target_version(metadata.js_cli.node.replace(">= ", ""))
-  .display();
-
-  VersionBlock::new(
-    "npm",
-    get_version("npm", &[])
-      .unwrap_or_default()
-      .unwrap_or_default(),
-  )
-  .display();
-  VersionBlock::new(
-    "pnpm",
-    get_version("pnpm", &[])
-      .unwrap_or_default()
-      .unwrap_or_default(),
-  )
-  .display();
-  VersionBlock::new("yarn", &yarn_version).display();
-  VersionBlock::new(
-    "rustup",
-    get_version("rustup", &[])
-      .unwrap_or_default()
-      .map(|v| {
-        let mut s = v.split(' ');
-        s.next();
-        s.next().unwrap().to_string()
-      })
-      .unwrap_or_default(),
-  )
-  .display();
-  VersionBlock::new(
-    "rustc",
-    get_version("rustc", &[])
-      .unwrap_or_default()
-      .map(|v| {
-        let mut s = v.split(' ');
-        s.next();
-        s.next().unwrap().to_string()
-      })
-      .unwrap_or_default(),
-  )
-  .display();
-  VersionBlock::new(
-    "cargo",
-    get_version("cargo", &[])
-      .unwrap_or_default()
-      .map(|v| {
-        let mut s = v.split(' ');
-        s.next();
-        s.next().unwrap().to_string()
-      })
-      .unwrap_or_default(),
-  )
-  .display();
-  InfoBlock::new(
-    "Rust toolchain",
-    active_rust_toolchain()
-      .unwrap_or_default()
-      .unwrap_or_default(),
-  )
-  .display();
-
-  Section("Packages").display();
-
   let mut package_manager = PackageManager::Npm;
   if let Some(app_dir) = &app_dir {
     let app_dir_entries = read_dir(app_dir)
@@ -738,38 +815,28 @@ pub fn command(_options: Options) -> Result<()> {
   }
 
   if package_manager == PackageManager::Yarn
-    && yarn_version
-      .chars()
-      .next()
+    && report
+      .environment
+      .yarn
+      .as_ref()
+      .and_then(|v| v.chars().next())
       .map(|c| c > '1')
       .unwrap_or_default()
   {
     package_manager = PackageManager::Berry;
   }
 
-  VersionBlock::new(
-    format!("{} {}", "@tauri-apps/cli", "[NPM]".dimmed()),
-    metadata.js_cli.version,
-  )
-  .target_version(
-    npm_latest_version(&package_manager, "@tauri-apps/cli")
-      .unwrap_or_default()
-      .unwrap_or_default(),
-  )
-  .display();
+  report.packages.push(npm_package(
+    "@tauri-apps/cli",
+    Some(metadata.js_cli.version),
+    npm_latest_version(&package_manager, "@tauri-apps/cli").unwrap_or_default(),
+  ));
   if let Some(app_dir) = &app_dir {
-    VersionBlock::new(
-      format!("{} {}", "@tauri-apps/api", "[NPM]".dimmed()),
-      npm_package_version(&package_manager, "@tauri-apps/api", app_dir)
-        .unwrap_or_default()
-        .unwrap_or_default(),
-    )
-    .target_version(
-      npm_latest_version(&package_manager, "@tauri-apps/api")
-        .unwrap_or_default()
-        .unwrap_or_default(),
-    )
-    .display();
+    report.packages.push(npm_package(
+      "@tauri-apps/api",
+      npm_package_version(&package_manager, "@tauri-apps/api", app_dir).unwrap_or_default(),
+      npm_latest_version(&package_manager, "@tauri-apps/api").unwrap_or_default(),
+    ));
   }
 
   let hook = panic::take_hook();
@@ -781,111 +848,162 @@ pub fn command(_options: Options) -> Result<()> {
     .unwrap_or_default();
   panic::set_hook(hook);
 
-  if tauri_dir.is_some() || app_dir.is_some() {
-    if let Some(tauri_dir) = tauri_dir.clone() {
-      let manifest: Option<CargoManifest> =
-        if let Ok(manifest_contents) = read_to_string(tauri_dir.join("Cargo.toml")) {
-          toml::from_str(&manifest_contents).ok()
-        } else {
-          None
-        };
-      let lock: Option<CargoLock> = get_workspace_dir()
-        .ok()
-        .and_then(|p| read_to_string(p.join("Cargo.lock")).ok())
-        .and_then(|s| toml::from_str(&s).ok());
-
-      for (dep, label) in [
-        ("tauri", format!("{} {}", "tauri", "[RUST]".dimmed())),
-        (
-          "tauri-build",
-          format!("{} {}", "tauri-build", "[RUST]".dimmed()),
-        ),
-        ("tao", format!("{} {}", "tao", "[RUST]".dimmed())),
-        ("wry", format!("{} {}", "wry", "[RUST]".dimmed())),
-      ] {
-        let (version_string, version_suffix) =
-          crate_version(&tauri_dir, manifest.as_ref(), lock.as_ref(), dep);
-        VersionBlock::new(
-          label,
-          format!(
-            "{},{}",
-            version_string,
-            version_suffix.unwrap_or_else(|| "".into())
-          ),
-        )
-        .display();
-      }
+  if let Some(tauri_dir) = &tauri_dir {
+    let manifest: Option<CargoManifest> =
+      if let Ok(manifest_contents) = read_to_string(tauri_dir.join("Cargo.toml")) {
+        toml::from_str(&manifest_contents).ok()
+      } else {
+        None
+      };
+    let lock: Option<CargoLock> = get_workspace_dir()
+      .ok()
+      .and_then(|p| read_to_string(p.join("Cargo.lock")).ok())
+      .and_then(|s| toml::from_str(&s).ok());
+
+    for dep in ["tauri", "tauri-build", "tao", "wry"] {
+      report.packages.push(crate_version(
+        tauri_dir,
+        manifest.as_ref(),
+        lock.as_ref(),
+        dep,
+      ));
     }
   }
 
   if tauri_dir.is_some() || app_dir.is_some() {
-    Section("App").display();
+    let mut app = AppInfo::default();
     if tauri_dir.is_some() {
       if let Ok(config) = get_config(None) {
         let config_guard = config.lock().unwrap();
         let config = config_guard.as_ref().unwrap();
-        InfoBlock::new(
-          "build-type",
-          if config.tauri.bundle.active {
-            "bundle".to_string()
-          } else {
-            "build".to_string()
-          },
-        )
-        .display();
-        InfoBlock::new(
-          "CSP",
-          config
-            .tauri
-            .security
-            .csp
-            .clone()
-            .map(|c| c.to_string())
-            .unwrap_or_else(|| "unset".to_string()),
-        )
-        .display();
-        InfoBlock::new("distDir", config.build.dist_dir.to_string()).display();
-        InfoBlock::new("devPath", config.build.dev_path.to_string()).display();
+        app.build_type = Some(if config.tauri.bundle.active {
+          "bundle".to_string()
+        } else {
+          "build".to_string()
+        });
+        app.csp = config.tauri.security.csp.clone().map(|c| c.to_string());
+        app.dist_dir = Some(config.build.dist_dir.to_string());
+        app.dev_path = Some(config.build.dev_path.to_string());
       }
     }
 
-    if let Some(app_dir) = app_dir {
+    if let Some(app_dir) = &app_dir {
       if let Ok(package_json) = read_to_string(app_dir.join("package.json")) {
         let (framework, bundler) = infer_framework(&package_json);
-        if let Some(framework) = framework {
-          InfoBlock::new("framework", framework.to_string()).display();
-        }
-        if let Some(bundler) = bundler {
-          InfoBlock::new("bundler", bundler.to_string()).display();
-        }
+        app.framework = framework.map(|f| f.to_string());
+        app.bundler = bundler.map(|b| b.to_string());
       } else {
-        println!("package.json not found");
+        eprintln!("package.json not found");
       }
     }
+    report.app = Some(app);
   }
 
   if let Some(app_dir) = app_dir {
-    Section("App directory structure").display();
-    let dirs = read_dir(app_dir)?
-      .filter(|p| p.is_ok() && p.as_ref().unwrap().path().is_dir())
-      .collect::<Vec<Result<std::fs::DirEntry, _>>>();
-    let dirs_len = dirs.len();
-    for (i, entry) in dirs.into_iter().enumerate() {
-      let entry = entry?;
-      let prefix = if i + 1 == dirs_len {
-        "└─".cyan()
-      } else {
-        "├─".cyan()
-      };
-      println!(
-        "  {} {}",
-        prefix,
-        entry.path().file_name().unwrap().to_string_lossy()
-      );
+    let mut dirs = Vec::new();
+    for entry in read_dir(app_dir)? {
+      let path = entry?.path();
+      if path.is_dir() {
+        dirs.push(path.file_name().unwrap().to_string_lossy().into_owned());
+      }
     }
+    report.app_directory_structure = Some(dirs);
   }
 
-  Ok(())
+  Ok(report)
+}
+
+impl Report {
+  fn display(&self) {
+    let environment = &self.environment;
+    Section("Environment").display();
+    VersionBlock::new("OS", &environment.os).display();
+    if let Some(webview) = &environment.webview {
+      VersionBlock::new(webview.name, webview.version.clone().unwrap_or_default()).display();
+    }
+    if let Some(build_tools) = &environment.msvc {
+      InfoBlock::new("MSVC", "").display();
+      for i in build_tools {
+        indent(6);
+        println!("{}", format!("{} {}", "-".cyan(), i));
+      }
+    }
+    VersionBlock::new("Node.js", environment.node.clone().unwrap_or_default())
+      .target_version(&environment.node_target)
+      .display();
+    for (name, version) in [
+      ("npm", &environment.npm),
+      ("pnpm", &environment.pnpm),
+      ("yarn", &environment.yarn),
+      ("rustup", &environment.rustup),
+      ("rustc", &environment.rustc),
+      ("cargo", &environment.cargo),
+    ] {
+      VersionBlock::new(name, version.clone().unwrap_or_default()).display();
+    }
+    InfoBlock::new(
+      "Rust toolchain",
+      environment.rust_toolchain.clone().unwrap_or_default(),
+    )
+    .display();
+    InfoBlock::new("Rust targets", environment.rust_targets.join(", ")).display();
+
+    Section("Packages").display();
+    for package in &self.packages {
+      match package.kind {
+        PackageKind::Npm => VersionBlock::new(
+          format!("{} {}", package.name, "[NPM]".dimmed()),
+          package.version.clone().unwrap_or_default(),
+        )
+        .target_version(package.latest.clone().unwrap_or_default())
+        .display(),
+        PackageKind::Rust => VersionBlock::new(
+          format!("{} {}", package.name, "[RUST]".dimmed()),
+          format!(
+            "{},{}",
+            package.version.clone().unwrap_or_default(),
+            match (&package.latest, package.outdated) {
+              (Some(latest), true) => format!(" (outdated, latest: {})", latest),
+              _ => "".into(),
+            }
+          ),
+        )
+        .display(),
+      }
+    }
+
+    if let Some(app) = &self.app {
+      Section("App").display();
+      if let Some(build_type) = &app.build_type {
+        InfoBlock::new("build-type", build_type).display();
+        InfoBlock::new("CSP", app.csp.as_deref().unwrap_or("unset")).display();
+      }
+      if let Some(dist_dir) = &app.dist_dir {
+        InfoBlock::new("distDir", dist_dir).display();
+      }
+      if let Some(dev_path) = &app.dev_path {
+        InfoBlock::new("devPath", dev_path).display();
+      }
+      if let Some(framework) = &app.framework {
+        InfoBlock::new("framework", framework).display();
+      }
+      if let Some(bundler) = &app.bundler {
+        InfoBlock::new("bundler", bundler).display();
+      }
+    }
+
+    if let Some(dirs) = &self.app_directory_structure {
+      Section("App directory structure").display();
+      for (i, dir) in dirs.iter().enumerate() {
+        let prefix = if i + 1 == dirs.len() {
+          "└─".cyan()
+        } else {
+          "├─".cyan()
+        };
+        println!("  {} {}", prefix, dir);
+      }
+    }
+  }
 }
 
 fn get_package_manager<T: AsRef<str>>(app_dir_entries: &[T]) -> crate::Result<PackageManager> {
@@ -904,7 +1022,7 @@ fn get_package_manager<T: AsRef<str>>(app_dir_entries: &[T]) -> crate::Result<Pa
   }
 
   if !use_npm && !use_pnpm && !use_yarn {
-    println!("WARNING: no lock files found, defaulting to npm");
+    eprintln!("WARNING: no lock files found, defaulting to npm");
     return Ok(PackageManager::Npm);
   }
 
@@ -935,3 +1053,38 @@ fn get_package_manager<T: AsRef<str>>(app_dir_entries: &[T]) -> crate::Result<Pa
     Ok(PackageManager::Yarn)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{npm_package, Report};
+  use serde_json::json;
+
+  #[test]
+  fn json_report() {
+    let report = Report {
+      packages: vec![
+        npm_package(
+          "@tauri-apps/cli",
+          Some("1.1.0".into()),
+          Some("1.2.0".into()),
+        ),
+        npm_package("@tauri-apps/api", Some("1.2.0".into()), None),
+      ],
+      app_directory_structure: Some(vec!["src-tauri".into()]),
+      ..Default::default()
+    };
+    let value = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(
+      value["packages"],
+      json!([
+        { "name": "@tauri-apps/cli", "type": "npm", "version": "1.1.0", "latest": "1.2.0", "outdated": true },
+        { "name": "@tauri-apps/api", "type": "npm", "version": "1.2.0", "latest": null, "outdated": false },
+      ])
+    );
+    assert_eq!(value["appDirectoryStructure"], json!(["src-tauri"]));
+    assert!(value["environment"].get("rustTargets").is_some());
+    // the Node.js target is only used to display the report
+    assert!(value["environment"].get("nodeTarget").is_none());
+  }
+}
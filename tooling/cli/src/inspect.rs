@@ -0,0 +1,160 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::Result;
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use colored::Colorize;
+use serde_json::Value;
+use tauri_utils::inspect::{find, AssetInfo};
+
+use std::{
+  fs::{read, read_dir},
+  path::{Path, PathBuf},
+};
+
+#[derive(Debug, Parser)]
+#[clap(about = "Shows the configuration, scopes and assets embedded in a built application")]
+pub struct Options {
+  /// Path to the application binary, or to an unpacked bundle such as a macOS `.app`.
+  path: PathBuf,
+  /// Print the embedded manifest as JSON instead of the human readable format
+  #[clap(long)]
+  json: bool,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  let manifest = if options.path.is_dir() {
+    find_in_dir(&options.path)?
+  } else {
+    find(
+      &read(&options.path).with_context(|| format!("failed to read {}", options.path.display()))?,
+    )
+  };
+  let manifest = match manifest {
+    Some(manifest) => manifest,
+    // compressed bundles such as the AppImage, deb and msi packages are not unpacked
+    None => bail!(
+      "No Tauri manifest found in {}. Make sure it is an application built with tauri 1.3 or later, or an unpacked bundle.",
+      options.path.display()
+    ),
+  };
+
+  if options.json {
+    println!("{}", serde_json::to_string_pretty(&manifest)?);
+    return Ok(());
+  }
+
+  let config = &manifest["config"];
+  section("Application");
+  item("identifier", &config["tauri"]["bundle"]["identifier"]);
+  item("productName", &config["package"]["productName"]);
+  item("version", &config["package"]["version"]);
+
+  let permissions = &manifest["permissions"];
+  section("Allowlist");
+  item("apis", &permissions["apis"]);
+  if let Some(scopes) = permissions["scopes"].as_object() {
+    for (name, scope) in scopes.iter().filter(|(_, scope)| !scope.is_null()) {
+      item(&format!("{} scope", name), scope);
+    }
+  }
+  item("remoteDomains", &permissions["remoteDomains"]);
+  item("csp", &permissions["csp"]);
+
+  section("Updater");
+  let updater = &config["tauri"]["updater"];
+  if updater["active"].as_bool().unwrap_or_default() {
+    item("pubkey", &updater["pubkey"]);
+    item("endpoints", &updater["endpoints"]);
+  } else {
+    println!("  {} {}", "›".cyan(), "not active".dimmed());
+  }
+
+  let assets: Vec<AssetInfo> = serde_json::from_value(manifest["assets"].clone())?;
+  section(&format!("Assets ({})", assets.len()));
+  let path_width = assets
+    .iter()
+    .map(|a| a.path.len())
+    .max()
+    .unwrap_or_default();
+  for asset in &assets {
+    println!(
+      "  {} {:width$}  {:>10}  {:>10}",
+      "›".cyan(),
+      asset.path,
+      format_size(asset.size),
+      format!("({})", format_size(asset.compressed_size)).dimmed(),
+      width = path_width
+    );
+  }
+  println!(
+    "  {} {}  {:>10}  {:>10}",
+    "›".cyan(),
+    format!("{:width$}", "total", width = path_width).bold(),
+    format_size(assets.iter().map(|a| a.size).sum()),
+    format!(
+      "({})",
+      format_size(assets.iter().map(|a| a.compressed_size).sum())
+    )
+    .dimmed(),
+  );
+
+  section("Config");
+  println!("{}", serde_json::to_string_pretty(config)?);
+
+  Ok(())
+}
+
+/// Looks for the manifest in the files of an unpacked bundle, e.g. `Contents/MacOS` of an `.app`.
+fn find_in_dir(dir: &Path) -> Result<Option<Value>> {
+  for entry in read_dir(dir)? {
+    let path = entry?.path();
+    let manifest = if path.is_dir() {
+      find_in_dir(&path)?
+    } else {
+      read(&path).ok().and_then(|contents| find(&contents))
+    };
+    if manifest.is_some() {
+      return Ok(manifest);
+    }
+  }
+  Ok(None)
+}
+
+fn section(name: &str) {
+  println!();
+  println!("{}", name.yellow().bold());
+}
+
+fn item(key: &str, value: &Value) {
+  let value = match value {
+    Value::Null => "unset".dimmed().to_string(),
+    Value::String(s) => s.clone(),
+    Value::Array(a) if a.is_empty() => "none".dimmed().to_string(),
+    Value::Array(a) if a.iter().all(Value::is_string) => a
+      .iter()
+      .filter_map(Value::as_str)
+      .collect::<Vec<_>>()
+      .join(", "),
+    value => value.to_string(),
+  };
+  println!("  {} {}: {}", "›".cyan(), key.bold(), value);
+}
+
+fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+  let mut size = bytes as f64;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{} {}", bytes, UNITS[0])
+  } else {
+    format!("{:.1} {}", size, UNITS[unit])
+  }
+}
@@ -48,9 +48,12 @@ pub trait AppSettings {
       tauri_utils::platform::target_triple()?
     };
 
+    let mut bundle_settings = self.get_bundle_settings(config, &enabled_features)?;
+    bundle_settings.android.debug = options.debug;
+
     let mut settings_builder = SettingsBuilder::new()
       .package_settings(self.get_package_settings())
-      .bundle_settings(self.get_bundle_settings(config, &enabled_features)?)
+      .bundle_settings(bundle_settings)
       .binaries(self.get_binaries(config, &target)?)
       .project_out_directory(out_dir)
       .target(target);
@@ -28,17 +28,22 @@ use notify_debouncer_mini::new_debouncer;
 use serde::Deserialize;
 use shared_child::SharedChild;
 use tauri_bundler::{
-  AppCategory, BundleBinary, BundleSettings, DebianSettings, MacOsSettings, PackageSettings,
-  UpdaterSettings, WindowsSettings,
+  AndroidSettings, AndroidSigningSettings, AppCategory, BundleBinary, BundleSettings,
+  DebianSettings, IosSettings, MacOsSettings, PackageSettings, ToolchainSettings, UpdaterSettings,
+  WindowsSettings,
 };
 use tauri_utils::config::parse::is_configuration_file;
 
 use super::{AppSettings, ExitReason, Interface};
 use crate::helpers::{
   app_paths::{app_dir, tauri_dir},
-  config::{reload as reload_config, wix_settings, Config},
+  config::{
+    dev_config_path, nsis_settings, reload as reload_config, wix_settings, write_dev_config,
+    AppUrl, Config, WindowUrl,
+  },
 };
 
+mod android;
 mod cargo_config;
 mod desktop;
 mod manifest;
@@ -70,6 +75,20 @@ impl From<crate::build::Options> for Options {
   }
 }
 
+impl From<crate::bundle::Options> for Options {
+  fn from(options: crate::bundle::Options) -> Self {
+    Self {
+      runner: None,
+      debug: options.debug,
+      target: options.target,
+      features: options.features,
+      args: options.args,
+      config: options.config,
+      no_watch: true,
+    }
+  }
+}
+
 impl From<crate::dev::Options> for Options {
   fn from(options: crate::dev::Options) -> Self {
     Self {
@@ -121,6 +140,7 @@ pub struct Rust {
   config_features: Vec<String>,
   product_name: Option<String>,
   available_targets: Option<Vec<Target>>,
+  android_min_sdk_version: u32,
 }
 
 impl Interface for Rust {
@@ -155,12 +175,17 @@ impl Interface for Rust {
     if let Some(minimum_system_version) = &config.tauri.bundle.macos.minimum_system_version {
       std::env::set_var("MACOSX_DEPLOYMENT_TARGET", minimum_system_version);
     }
+    std::env::set_var(
+      "IPHONEOS_DEPLOYMENT_TARGET",
+      &config.tauri.bundle.ios.minimum_system_version,
+    );
 
     Ok(Self {
       app_settings: RustAppSettings::new(config, manifest, target)?,
       config_features: config.build.features.clone().unwrap_or_default(),
       product_name: config.package.product_name.clone(),
       available_targets: None,
+      android_min_sdk_version: config.tauri.bundle.android.min_sdk_version,
     })
   }
 
@@ -173,13 +198,23 @@ impl Interface for Rust {
       .features
       .get_or_insert(Vec::new())
       .push("custom-protocol".into());
-    desktop::build(
-      options,
-      &self.app_settings,
-      self.product_name.clone(),
-      &mut self.available_targets,
-      self.config_features.clone(),
-    )?;
+    if self.app_settings.is_android() {
+      android::build(
+        options,
+        &self.app_settings,
+        &mut self.available_targets,
+        self.config_features.clone(),
+        self.android_min_sdk_version,
+      )?;
+    } else {
+      desktop::build(
+        options,
+        &self.app_settings,
+        self.product_name.clone(),
+        &mut self.available_targets,
+        self.config_features.clone(),
+      )?;
+    }
     Ok(())
   }
 
@@ -378,6 +413,11 @@ impl Rust {
     }
 
     options.args = args;
+    // watches the configuration written by the CLI
+    options
+      .features
+      .get_or_insert(Vec::new())
+      .push("tauri/dev-config-reload".into());
 
     desktop::run_dev(
       options,
@@ -449,6 +489,8 @@ impl Rust {
       }
     }
 
+    let _ = std::fs::remove_file(dev_config_path());
+
     loop {
       if let Ok(events) = rx.recv() {
         for event in events {
@@ -460,8 +502,11 @@ impl Rust {
               match reload_config(options.config.as_deref()) {
                 Ok(config) => {
                   info!("Tauri configuration changed. Rewriting manifest...");
-                  self.app_settings.manifest =
-                    rewrite_manifest(config.lock().unwrap().as_ref().unwrap())?
+                  let config = config.lock().unwrap();
+                  let config = config.as_ref().unwrap();
+                  self.app_settings.manifest = rewrite_manifest(config)?;
+                  // the running app reloads the runtime-safe configuration values from this file
+                  write_dev_config(config)?;
                 }
                 Err(err) => {
                   let p = process.lock().unwrap();
@@ -515,6 +560,14 @@ struct BinarySettings {
   path: Option<String>,
 }
 
+/// The library target configuration.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct LibrarySettings {
+  name: Option<String>,
+  crate_type: Option<Vec<String>>,
+}
+
 /// The package settings.
 #[derive(Debug, Clone, Deserialize)]
 pub struct CargoPackageSettings {
@@ -545,6 +598,8 @@ struct CargoSettings {
   workspace: Option<WorkspaceSettings>,
   /// the binary targets configuration.
   bin: Option<Vec<BinarySettings>>,
+  /// the library target configuration.
+  lib: Option<LibrarySettings>,
 }
 
 impl CargoSettings {
@@ -581,16 +636,36 @@ impl AppSettings for RustAppSettings {
     config: &Config,
     features: &[String],
   ) -> crate::Result<BundleSettings> {
-    tauri_config_to_bundle_settings(
+    let mut settings = tauri_config_to_bundle_settings(
       &self.manifest,
       features,
       config.tauri.bundle.clone(),
       config.tauri.system_tray.clone(),
       config.tauri.updater.clone(),
-    )
+    )?;
+    if let Some(i18n) = &config.tauri.i18n {
+      settings
+        .resources
+        .get_or_insert_with(Vec::new)
+        .push(i18n.resource_pattern());
+    }
+    settings.android.lib_name = self.lib_name();
+    settings.android.assets = match &config.build.dist_dir {
+      AppUrl::Url(WindowUrl::App(path)) => vec![path.clone()],
+      AppUrl::Files(files) => files.clone(),
+      _ => Vec::new(),
+    };
+    Ok(settings)
   }
 
   fn app_binary_path(&self, options: &Options) -> crate::Result<PathBuf> {
+    if self.is_android() {
+      let out_dir = self
+        .out_dir(options.target.clone(), options.debug)
+        .with_context(|| "failed to get project out directory")?;
+      return Ok(out_dir.join(format!("lib{}.so", self.lib_name())));
+    }
+
     let bin_name = self
       .cargo_package_settings()
       .name
@@ -785,6 +860,28 @@ impl RustAppSettings {
     &self.cargo_package_settings
   }
 
+  /// Whether the app is built for Android, as a library loaded by the Android application.
+  pub fn is_android(&self) -> bool {
+    self.target_triple.contains("android")
+  }
+
+  /// The name of the library target, which defaults to the package name with underscores.
+  pub fn lib_name(&self) -> String {
+    self
+      .cargo_settings
+      .lib
+      .as_ref()
+      .and_then(|lib| lib.name.clone())
+      .unwrap_or_else(|| {
+        self
+          .cargo_package_settings
+          .name
+          .clone()
+          .expect("Cargo manifest must have the `package.name` field")
+          .replace('-', "_")
+      })
+  }
+
   pub fn out_dir(&self, target: Option<String>, debug: bool) -> crate::Result<PathBuf> {
     get_target_dir(
       target
@@ -887,12 +984,8 @@ fn tauri_config_to_bundle_settings(
 
   #[cfg(windows)]
   {
-    if let Some(webview_fixed_runtime_path) = &config.windows.webview_fixed_runtime_path {
+    if let Some(webview_fixed_runtime_path) = config.windows.fixed_runtime_path() {
       resources.push(webview_fixed_runtime_path.display().to_string());
-    } else if let crate::helpers::config::WebviewInstallMode::FixedRuntime { path } =
-      &config.windows.webview_install_mode
-    {
-      resources.push(path.display().to_string());
     }
   }
 
@@ -961,6 +1054,14 @@ fn tauri_config_to_bundle_settings(
         }
       },
     },
+    ios: IosSettings {
+      minimum_system_version: Some(config.ios.minimum_system_version),
+      signing_identity: config.ios.signing_identity,
+      provisioning_profile: config
+        .ios
+        .provisioning_profile
+        .map(|profile| tauri_dir().join(profile)),
+    },
     windows: WindowsSettings {
       timestamp_url: config.windows.timestamp_url,
       tsp: config.windows.tsp,
@@ -971,10 +1072,28 @@ fn tauri_config_to_bundle_settings(
         wix.license = wix.license.map(|l| tauri_dir().join(l));
         wix
       }),
+      nsis: config.windows.nsis.map(|n| {
+        let mut nsis = nsis_settings(n);
+        nsis.template = nsis.template.map(|t| tauri_dir().join(t));
+        nsis
+      }),
       icon_path: windows_icon_path,
       webview_install_mode: config.windows.webview_install_mode,
       webview_fixed_runtime_path: config.windows.webview_fixed_runtime_path,
       allow_downgrades: config.windows.allow_downgrades,
+      allow_same_version_upgrades: config.windows.allow_same_version_upgrades,
+      downgrade_error_message: config.windows.downgrade_error_message,
+    },
+    android: AndroidSettings {
+      min_sdk_version: config.android.min_sdk_version,
+      target_sdk_version: config.android.target_sdk_version,
+      version_code: config.android.version_code,
+      icons_dir: Some(tauri_dir().join("icons/android")),
+      signing: config.android.signing.map(|s| AndroidSigningSettings {
+        keystore: tauri_dir().join(s.keystore),
+        key_alias: s.key_alias,
+      }),
+      ..Default::default()
     },
     updater: Some(UpdaterSettings {
       active: updater_config.active,
@@ -987,6 +1106,10 @@ fn tauri_config_to_bundle_settings(
         .map(|endpoints| endpoints.iter().map(|e| e.to_string()).collect()),
       msiexec_args: Some(updater_config.windows.install_mode.msiexec_args()),
     }),
+    toolchain: config.toolchain.map(|toolchain| ToolchainSettings {
+      dir: tauri_dir().join(toolchain.dir),
+      sha256: toolchain.sha256,
+    }),
     ..Default::default()
   })
 }
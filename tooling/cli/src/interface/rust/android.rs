@@ -0,0 +1,91 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{desktop, Options, RustAppSettings, Target};
+
+use anyhow::{bail, Context};
+use std::{
+  env::{set_var, var_os},
+  path::PathBuf,
+};
+
+/// Builds the app library, linked with the toolchain of the Android NDK.
+pub fn build(
+  mut options: Options,
+  app_settings: &RustAppSettings,
+  available_targets: &mut Option<Vec<Target>>,
+  config_features: Vec<String>,
+  min_sdk_version: u32,
+) -> crate::Result<()> {
+  let is_cdylib = app_settings
+    .cargo_settings
+    .lib
+    .as_ref()
+    .and_then(|lib| lib.crate_type.as_ref())
+    .map_or(false, |types| types.iter().any(|t| t == "cdylib"));
+  if !is_cdylib {
+    bail!("The Android application loads the app as a shared library. Add `crate-type = [\"cdylib\"]` to the `[lib]` section of your Cargo.toml.");
+  }
+
+  let target = &app_settings.target_triple;
+  let toolchain = ndk_toolchain()?;
+  // the compiler wrappers are named after the target and API level, with `armv7a` as the ARM architecture
+  let clang_target = match target.split('-').next() {
+    Some("armv7") | Some("thumbv7neon") => "armv7a-linux-androideabi",
+    _ => target.as_str(),
+  };
+  let script_extension = if cfg!(windows) { ".cmd" } else { "" };
+  let clang = toolchain.join(format!(
+    "{}{}-clang{}",
+    clang_target, min_sdk_version, script_extension
+  ));
+  let clangxx = toolchain.join(format!(
+    "{}{}-clang++{}",
+    clang_target, min_sdk_version, script_extension
+  ));
+  let ar = toolchain.join(format!("llvm-ar{}", std::env::consts::EXE_SUFFIX));
+  if !clang.exists() {
+    bail!(
+      "{} not found. Make sure the NDK supports the API level {} set in `tauri.conf.json > tauri > bundle > android > minSdkVersion`.",
+      clang.display(),
+      min_sdk_version
+    );
+  }
+
+  // read by cargo and by the `cc` crate used to compile C dependencies
+  let env_target = target.replace('-', "_");
+  set_var(
+    format!("CARGO_TARGET_{}_LINKER", env_target.to_uppercase()),
+    &clang,
+  );
+  set_var(format!("CC_{}", env_target), &clang);
+  set_var(format!("CXX_{}", env_target), &clangxx);
+  set_var(format!("AR_{}", env_target), &ar);
+
+  options.args.insert(0, "--lib".into());
+  desktop::build_production_app(options, available_targets, config_features)
+    .with_context(|| "failed to build app")
+}
+
+/// The directory of the LLVM toolchain of the Android NDK.
+fn ndk_toolchain() -> crate::Result<PathBuf> {
+  let ndk_home = var_os("ANDROID_NDK_HOME")
+    .or_else(|| var_os("NDK_HOME"))
+    .map(PathBuf::from)
+    .context("ANDROID_NDK_HOME must be set to the path of the Android NDK")?;
+  // the NDK only ships x86_64 host tools, which also run on Apple Silicon
+  let host_tag = if cfg!(target_os = "macos") {
+    "darwin-x86_64"
+  } else if cfg!(windows) {
+    "windows-x86_64"
+  } else {
+    "linux-x86_64"
+  };
+  Ok(
+    ndk_home
+      .join("toolchains/llvm/prebuilt")
+      .join(host_tag)
+      .join("bin"),
+  )
+}
@@ -1,5 +1,8 @@
 use super::{AppSettings, DevChild, ExitReason, Options, RustAppSettings, Target};
-use crate::CommandExt;
+use crate::{
+  helpers::config::{dev_config_path, dev_session_path, DEV_CONFIG_PATH_ENV, DEV_SESSION_PATH_ENV},
+  CommandExt,
+};
 
 use anyhow::Context;
 #[cfg(target_os = "linux")]
@@ -44,6 +47,8 @@ pub fn run_dev<F: Fn(ExitStatus, ExitReason) + Send + Sync + 'static>(
         app.stdout(os_pipe::dup_stdout().unwrap());
         app.stderr(os_pipe::dup_stderr().unwrap());
         app.args(run_args);
+        app.env(DEV_CONFIG_PATH_ENV, dev_config_path());
+        app.env(DEV_SESSION_PATH_ENV, dev_session_path());
         let app_child = Arc::new(SharedChild::spawn(&mut app).unwrap());
         let app_child_t = app_child.clone();
         std::thread::spawn(move || {
@@ -228,7 +233,7 @@ fn build_dev_app<F: FnOnce(ExitStatus, ExitReason) + Send + 'static>(
   Ok(build_child)
 }
 
-fn build_production_app(
+pub(super) fn build_production_app(
   options: Options,
   available_targets: &mut Option<Vec<Target>>,
   config_features: Vec<String>,
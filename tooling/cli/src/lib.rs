@@ -5,11 +5,13 @@
 pub use anyhow::Result;
 
 mod build;
+mod bundle;
 mod dev;
 mod helpers;
 mod icon;
 mod info;
 mod init;
+mod inspect;
 mod interface;
 mod plugin;
 mod signer;
@@ -62,10 +64,12 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
   Build(build::Options),
+  Bundle(bundle::Options),
   Dev(dev::Options),
   Icon(icon::Options),
   Info(info::Options),
   Init(init::Options),
+  Inspect(inspect::Options),
   Plugin(plugin::Cli),
   Signer(signer::Cli),
 }
@@ -161,10 +165,12 @@ where
 
   match cli.command {
     Commands::Build(options) => build::command(options)?,
+    Commands::Bundle(options) => bundle::command(options)?,
     Commands::Dev(options) => dev::command(options)?,
     Commands::Icon(options) => icon::command(options)?,
     Commands::Info(options) => info::command(options)?,
     Commands::Init(options) => init::command(options)?,
+    Commands::Inspect(options) => inspect::command(options)?,
     Commands::Plugin(cli) => plugin::command(cli)?,
     Commands::Signer(cli) => signer::command(cli)?,
   }
@@ -7,6 +7,7 @@ use clap::{Parser, Subcommand};
 
 mod generate;
 mod sign;
+mod verify;
 
 #[derive(Parser)]
 #[clap(
@@ -24,12 +25,14 @@ pub struct Cli {
 #[derive(Subcommand)]
 enum Commands {
   Sign(sign::Options),
+  Verify(verify::Options),
   Generate(generate::Options),
 }
 
 pub fn command(cli: Cli) -> Result<()> {
   match cli.command {
     Commands::Sign(options) => sign::command(options)?,
+    Commands::Verify(options) => verify::command(options)?,
     Commands::Generate(options) => generate::command(options)?,
   }
   Ok(())
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::path::{Path, PathBuf};
+use std::{
+  env::var,
+  path::{Path, PathBuf},
+};
 
 use crate::{
   helpers::updater_signature::{read_key_from_file, secret_key, sign_file},
@@ -12,26 +15,37 @@ use anyhow::Context;
 use clap::Parser;
 
 #[derive(Debug, Parser)]
-#[clap(about = "Sign a file")]
+#[clap(about = "Sign files")]
 pub struct Options {
-  /// Load the private key from a file
-  #[clap(short = 'k', long, conflicts_with("private-key-path"))]
+  /// Load the private key from a string, defaults to the `TAURI_PRIVATE_KEY` environment variable
+  #[clap(short = 'k', long, conflicts_with("private_key_path"))]
   private_key: Option<String>,
-  /// Load the private key from a string
-  #[clap(short = 'f', long, conflicts_with("private-key"))]
+  /// Load the private key from a file
+  #[clap(short = 'f', long, conflicts_with("private_key"))]
   private_key_path: Option<PathBuf>,
-  /// Set private key password when signing
+  /// Set private key password when signing, defaults to the `TAURI_KEY_PASSWORD` environment variable
   #[clap(short, long)]
   password: Option<String>,
-  /// Sign the specified file
-  file: PathBuf,
+  /// Sign the specified files
+  #[clap(required = true)]
+  files: Vec<PathBuf>,
 }
 
 pub fn command(mut options: Options) -> Result<()> {
   options.private_key = if let Some(private_key) = options.private_key_path {
     Some(read_key_from_file(Path::new(&private_key)).expect("Unable to extract private key"))
+  } else if let Some(private_key) = options.private_key {
+    Some(private_key)
+  } else if let Ok(private_key) = var("TAURI_PRIVATE_KEY") {
+    // the environment variable can also point to the key file
+    let path = Path::new(&private_key);
+    Some(if path.exists() {
+      read_key_from_file(path)?
+    } else {
+      private_key
+    })
   } else {
-    options.private_key
+    None
   };
   let private_key = if let Some(pk) = options.private_key {
     pk
@@ -40,20 +54,23 @@ pub fn command(mut options: Options) -> Result<()> {
       "Key generation aborted: Unable to find the private key".to_string(),
     ));
   };
+  let password = options.password.or_else(|| var("TAURI_KEY_PASSWORD").ok());
 
-  if options.password.is_none() {
+  if password.is_none() {
     println!("Signing without password.");
   }
 
-  let (manifest_dir, signature) =
-    sign_file(&secret_key(private_key, options.password)?, options.file)
-      .with_context(|| "failed to sign file")?;
+  let secret_key = secret_key(private_key, password)?;
+  for file in options.files {
+    let (manifest_dir, signature) = sign_file(&secret_key, &file)
+      .with_context(|| format!("failed to sign file {}", file.display()))?;
 
-  println!(
-           "\nYour file was signed successfully, You can find the signature here:\n{}\n\nPublic signature:\n{}\n\nMake sure to include this into the signature field of your update server.",
-           manifest_dir.display(),
-           base64::encode(signature.to_string())
-         );
+    println!(
+      "\nYour file was signed successfully, You can find the signature here:\n{}\n\nPublic signature:\n{}\n\nMake sure to include this into the signature field of your update server.",
+      manifest_dir.display(),
+      base64::encode(signature.to_string())
+    );
+  }
 
   Ok(())
 }
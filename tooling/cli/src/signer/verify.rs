@@ -0,0 +1,54 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::path::PathBuf;
+
+use crate::{
+  helpers::updater_signature::{public_key, read_key_from_file, signature_path, verify_file},
+  Result,
+};
+use anyhow::Context;
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+#[clap(about = "Verify the signature of a file")]
+pub struct Options {
+  /// Load the public key from a string, as defined in `tauri.conf.json > tauri > updater > pubkey`
+  #[clap(short = 'k', long, conflicts_with("public_key_path"))]
+  public_key: Option<String>,
+  /// Load the public key from a file
+  #[clap(short = 'f', long, conflicts_with("public_key"))]
+  public_key_path: Option<PathBuf>,
+  /// Path of the signature, defaults to the file path with `.sig` appended
+  #[clap(short, long)]
+  signature: Option<PathBuf>,
+  /// Verify the specified file
+  file: PathBuf,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  let key = if let Some(path) = options.public_key_path {
+    read_key_from_file(&path)
+      .with_context(|| format!("failed to read public key {}", path.display()))?
+  } else if let Some(key) = options.public_key {
+    key
+  } else {
+    return Err(anyhow::anyhow!(
+      "Verification aborted: Unable to find the public key".to_string(),
+    ));
+  };
+
+  let signature = options
+    .signature
+    .unwrap_or_else(|| signature_path(&options.file));
+  let trusted_comment = verify_file(&public_key(&key)?, &options.file, &signature)?;
+
+  println!(
+    "\nThe signature of {} is valid.\nTrusted comment: {}",
+    options.file.display(),
+    trusted_comment
+  );
+
+  Ok(())
+}
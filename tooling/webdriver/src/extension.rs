@@ -0,0 +1,381 @@
+// Copyright 2019-2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! WebDriver [extension commands] to target windows by their Tauri label and script the Tauri APIs.
+//!
+//! They are prefixed with `/session/{session id}/tauri` and implemented with the standard commands of the native driver.
+//!
+//! [extension commands]: https://www.w3.org/TR/webdriver/#dfn-extension-commands
+
+use crate::cli::Args;
+use anyhow::Error;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::http::uri::Authority;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::server::HttpClient;
+
+const PREFIX: &str = "tauri";
+
+/// Reads the label of the current window.
+const LABEL_SCRIPT: &str = "return window.__TAURI_METADATA__.__currentWindow.label";
+
+/// Runs a command with the arguments passed to the script.
+const INVOKE_SCRIPT: &str = r#"
+const [cmd, args, done] = arguments;
+window.__TAURI_INVOKE__(cmd, args).then(
+  (value) => done({ ok: true, value }),
+  (value) => done({ ok: false, value })
+);
+"#;
+
+/// Records the payloads of an event emitted to the current window.
+const LISTEN_SCRIPT: &str = r#"
+const [event, done] = arguments;
+const events = (window.__TAURI_DRIVER_EVENTS__ = window.__TAURI_DRIVER_EVENTS__ || {});
+if (events[event]) {
+  done({ ok: true, value: null });
+  return;
+}
+events[event] = [];
+window.__TAURI_INVOKE__('tauri', {
+  __tauriModule: 'Event',
+  message: {
+    cmd: 'listen',
+    event,
+    windowLabel: null,
+    handler: window.__TAURI__.transformCallback((e) => events[event].push(e.payload))
+  }
+}).then(
+  () => done({ ok: true, value: null }),
+  (value) => {
+    delete events[event];
+    done({ ok: false, value });
+  }
+);
+"#;
+
+/// Returns and clears the payloads recorded for an event.
+const EVENTS_SCRIPT: &str = r#"
+const events = window.__TAURI_DRIVER_EVENTS__ || {};
+const payloads = events[arguments[0]];
+if (!payloads) {
+  return null;
+}
+return payloads.splice(0, payloads.length);
+"#;
+
+/// The Tauri extension commands.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+  /// `GET /session/{session id}/tauri/windows`: maps the window labels to their WebDriver handles.
+  Windows,
+  /// `POST /session/{session id}/tauri/window`: switches to the window with the given label.
+  SwitchToWindow,
+  /// `POST /session/{session id}/tauri/invoke`: runs a command on the current window.
+  Invoke,
+  /// `POST /session/{session id}/tauri/listen`: starts recording an event on the current window.
+  Listen,
+  /// `POST /session/{session id}/tauri/events`: returns the payloads recorded since the last call.
+  Events,
+}
+
+impl Command {
+  /// Matches the request against the extension commands, returning the session id and the command.
+  pub fn parse(method: &Method, path: &str) -> Option<(String, Self)> {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let (session, command) = match segments.as_slice() {
+      ["session", session, PREFIX, command] => (session, *command),
+      _ => return None,
+    };
+    let command = match (method, command) {
+      (&Method::GET, "windows") => Self::Windows,
+      (&Method::POST, "window") => Self::SwitchToWindow,
+      (&Method::POST, "invoke") => Self::Invoke,
+      (&Method::POST, "listen") => Self::Listen,
+      (&Method::POST, "events") => Self::Events,
+      _ => return None,
+    };
+    Some((session.to_string(), command))
+  }
+}
+
+/// A WebDriver error, serialized as the `value` of the response.
+struct Failure {
+  status: StatusCode,
+  value: Value,
+}
+
+impl Failure {
+  fn new(status: StatusCode, error: &str, message: impl ToString) -> Self {
+    Self {
+      status,
+      value: json!({ "error": error, "message": message.to_string(), "stacktrace": "" }),
+    }
+  }
+
+  fn invalid_argument(message: impl ToString) -> Self {
+    Self::new(StatusCode::BAD_REQUEST, "invalid argument", message)
+  }
+}
+
+impl From<Error> for Failure {
+  fn from(error: Error) -> Self {
+    Self::new(StatusCode::INTERNAL_SERVER_ERROR, "unknown error", error)
+  }
+}
+
+/// A session of the native WebDriver server.
+struct Session {
+  client: HttpClient,
+  url: String,
+}
+
+impl Session {
+  async fn request(
+    &self,
+    method: Method,
+    path: &str,
+    body: Option<Value>,
+  ) -> Result<Value, Failure> {
+    let bytes = body.map(|body| body.to_string()).unwrap_or_default();
+    let request = Request::builder()
+      .method(method)
+      .uri(format!("{}/{}", self.url, path))
+      .header(CONTENT_TYPE, "application/json; charset=utf-8")
+      .header(CONTENT_LENGTH, bytes.len())
+      .body(Body::from(bytes))
+      .map_err(Error::from)?;
+
+    let response = self.client.request(request).await.map_err(Error::from)?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+      .await
+      .map_err(Error::from)?;
+    let mut body: Value = serde_json::from_slice(&body).map_err(Error::from)?;
+    let value = body.get_mut("value").map(Value::take).unwrap_or_default();
+    if status.is_success() {
+      Ok(value)
+    } else {
+      Err(Failure { status, value })
+    }
+  }
+
+  async fn execute(
+    &self,
+    asynchronous: bool,
+    script: &str,
+    args: Vec<Value>,
+  ) -> Result<Value, Failure> {
+    let path = if asynchronous {
+      "execute/async"
+    } else {
+      "execute/sync"
+    };
+    self
+      .request(
+        Method::POST,
+        path,
+        Some(json!({ "script": script, "args": args })),
+      )
+      .await
+  }
+
+  /// Runs a script that calls `done({ ok, value })`, failing with a `javascript error` when `ok` is false.
+  async fn execute_fallible(
+    &self,
+    script: &str,
+    args: Vec<Value>,
+    what: &str,
+  ) -> Result<Value, Failure> {
+    let mut result = self.execute(true, script, args).await?;
+    let value = result.get_mut("value").map(Value::take).unwrap_or_default();
+    if result.get("ok") == Some(&Value::Bool(true)) {
+      Ok(value)
+    } else {
+      let mut failure = Failure::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "javascript error",
+        format!("{} failed: {}", what, value),
+      );
+      failure.value["data"] = value;
+      Err(failure)
+    }
+  }
+
+  /// Maps the window labels to their WebDriver handles, keeping the current window focused.
+  async fn windows(&self) -> Result<Map<String, Value>, Failure> {
+    let current = self.request(Method::GET, "window", None).await.ok();
+    let handles = self.request(Method::GET, "window/handles", None).await?;
+
+    let mut windows = Map::new();
+    for handle in handles.as_array().into_iter().flatten() {
+      self.switch_to(handle.clone()).await?;
+      if let Value::String(label) = self.execute(false, LABEL_SCRIPT, Vec::new()).await? {
+        windows.insert(label, handle.clone());
+      }
+    }
+
+    if let Some(current) = current {
+      // the current window might have been closed
+      let _ = self.switch_to(current).await;
+    }
+
+    Ok(windows)
+  }
+
+  async fn switch_to(&self, handle: Value) -> Result<Value, Failure> {
+    self
+      .request(Method::POST, "window", Some(json!({ "handle": handle })))
+      .await
+  }
+}
+
+#[derive(Deserialize)]
+struct SwitchToWindowArgs {
+  label: String,
+}
+
+#[derive(Deserialize)]
+struct InvokeArgs {
+  cmd: String,
+  #[serde(default)]
+  args: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct EventArgs {
+  event: String,
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T, Failure> {
+  serde_json::from_slice(body).map_err(Failure::invalid_argument)
+}
+
+/// Runs an extension command against the native WebDriver server.
+pub async fn handle(
+  client: HttpClient,
+  req: Request<Body>,
+  args: Args,
+  session: String,
+  command: Command,
+) -> Result<Response<Body>, Error> {
+  let host: Authority = req
+    .headers()
+    .get("host")
+    .expect("hyper request has host")
+    .to_str()?
+    .parse()?;
+  let session = Session {
+    client,
+    url: format!(
+      "http://{}:{}/session/{}",
+      host.host(),
+      args.native_port,
+      session
+    ),
+  };
+  let body = hyper::body::to_bytes(req.into_body()).await?;
+
+  let (status, value) = match run(&session, command, &body).await {
+    Ok(value) => (StatusCode::OK, value),
+    Err(failure) => (failure.status, failure.value),
+  };
+  let bytes = serde_json::to_vec(&json!({ "value": value }))?;
+  Ok(
+    Response::builder()
+      .status(status)
+      .header(CONTENT_TYPE, "application/json; charset=utf-8")
+      .header(CONTENT_LENGTH, bytes.len())
+      .body(bytes.into())?,
+  )
+}
+
+async fn run(session: &Session, command: Command, body: &[u8]) -> Result<Value, Failure> {
+  match command {
+    Command::Windows => session.windows().await.map(Value::Object),
+    Command::SwitchToWindow => {
+      let SwitchToWindowArgs { label } = parse_body(body)?;
+      match session.windows().await?.remove(&label) {
+        Some(handle) => session.switch_to(handle).await,
+        None => Err(Failure::new(
+          StatusCode::NOT_FOUND,
+          "no such window",
+          format!("window with label `{}` not found", label),
+        )),
+      }
+    }
+    Command::Invoke => {
+      let InvokeArgs { cmd, args } = parse_body(body)?;
+      let what = format!("command `{}`", cmd);
+      session
+        .execute_fallible(INVOKE_SCRIPT, vec![cmd.into(), args.into()], &what)
+        .await
+    }
+    Command::Listen => {
+      let EventArgs { event } = parse_body(body)?;
+      let what = format!("listening to `{}`", event);
+      session
+        .execute_fallible(LISTEN_SCRIPT, vec![event.into()], &what)
+        .await
+    }
+    Command::Events => {
+      let EventArgs { event } = parse_body(body)?;
+      match session
+        .execute(false, EVENTS_SCRIPT, vec![event.clone().into()])
+        .await?
+      {
+        Value::Null => Err(Failure::invalid_argument(format!(
+          "the `{}` event is not recorded, call `tauri/listen` first",
+          event
+        ))),
+        payloads => Ok(payloads),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_body, Command, InvokeArgs};
+  use hyper::{Method, StatusCode};
+
+  #[test]
+  fn parse_command() {
+    assert_eq!(
+      Command::parse(&Method::GET, "/session/abc/tauri/windows"),
+      Some(("abc".into(), Command::Windows))
+    );
+    assert_eq!(
+      Command::parse(&Method::POST, "/session/abc/tauri/invoke/"),
+      Some(("abc".into(), Command::Invoke))
+    );
+    // the method must match the command
+    assert_eq!(
+      Command::parse(&Method::GET, "/session/abc/tauri/invoke"),
+      None
+    );
+    // the standard commands are proxied to the native driver
+    assert_eq!(Command::parse(&Method::GET, "/session/abc/window"), None);
+    assert_eq!(
+      Command::parse(&Method::POST, "/session/abc/tauri/unknown"),
+      None
+    );
+  }
+
+  #[test]
+  fn parse_invoke_body() {
+    let args: InvokeArgs = parse_body(br#"{ "cmd": "greet" }"#).ok().unwrap();
+    assert_eq!(args.cmd, "greet");
+    assert!(args.args.is_empty());
+
+    let failure = parse_body::<InvokeArgs>(br#"{ "args": {} }"#)
+      .err()
+      .unwrap();
+    assert_eq!(failure.status, StatusCode::BAD_REQUEST);
+    assert_eq!(failure.value["error"], "invalid argument");
+  }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: MIT
 
 mod cli;
+mod extension;
 mod server;
 mod webdriver;
 
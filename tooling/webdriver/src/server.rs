@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: MIT
 
 use crate::cli::Args;
+use crate::extension::Command;
 use anyhow::Error;
 use futures_util::TryFutureExt;
 use hyper::header::CONTENT_LENGTH;
@@ -15,7 +16,7 @@ use std::convert::Infallible;
 use std::path::PathBuf;
 use std::process::Child;
 
-type HttpClient = Client<hyper::client::HttpConnector>;
+pub type HttpClient = Client<hyper::client::HttpConnector>;
 
 const TAURI_OPTIONS: &str = "tauri:options";
 
@@ -55,6 +56,11 @@ async fn handle(
   mut req: Request<Body>,
   args: Args,
 ) -> Result<Response<Body>, Error> {
+  // the Tauri extension commands are implemented on top of the native driver
+  if let Some((session, command)) = Command::parse(req.method(), req.uri().path()) {
+    return crate::extension::handle(client, req, args, session, command).await;
+  }
+
   // manipulate a new session to convert options to the native driver format
   if let (&Method::POST, "/session") = (req.method(), req.uri().path()) {
     let (mut parts, body) = req.into_parts();